@@ -0,0 +1,196 @@
+//! The `#[advent]` attribute, which turns a unit struct and two free
+//! functions `part1` and `part2` into a solution binary.  Re-exported as
+//! `rust_advent::advent`; see there for usage.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse::Parser;
+use syn::{DeriveInput, Error, LitInt, LitStr, Path, Type, parse_macro_input};
+
+/// What the attribute's arguments asked for.
+struct Args {
+    year: u32,
+    day: u32,
+    variant: String,
+    input: Option<Type>,
+    parse: Option<Path>,
+    description: Option<String>,
+    max_positions: Option<usize>,
+    max_grid_width: Option<usize>,
+    input_size: Option<Path>,
+}
+
+impl Args {
+    fn parse(attr: TokenStream) -> syn::Result<Self> {
+        let (mut year, mut day, mut variant) = (None, None, None);
+        let (mut input, mut parse, mut description) = (None, None, None);
+        let (mut max_positions, mut max_grid_width, mut input_size) = (None, None, None);
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("year") {
+                year = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("day") {
+                let lit: LitInt = meta.value()?.parse()?;
+                let value: u32 = lit.base10_parse()?;
+                if !(1..=25).contains(&value) {
+                    return Err(Error::new(lit.span(), "day must be between 1 and 25"));
+                }
+                day = Some(value);
+            } else if meta.path.is_ident("variant") {
+                variant = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("input") {
+                input = Some(meta.value()?.parse::<Type>()?);
+            } else if meta.path.is_ident("parse") {
+                parse = Some(meta.value()?.parse::<Path>()?);
+            } else if meta.path.is_ident("description") {
+                description = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("max_positions") {
+                max_positions = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("max_grid_width") {
+                max_grid_width = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("input_size") {
+                input_size = Some(meta.value()?.parse::<Path>()?);
+            } else {
+                return Err(meta.error(
+                    "expected year, day, variant, input, parse, description, \
+                     max_positions, max_grid_width or input_size",
+                ));
+            }
+            Ok(())
+        });
+        parser.parse(attr)?;
+
+        let missing = |name: &str| Error::new(Span::call_site(), format!("missing `{}`", name));
+        if input.is_some() != parse.is_some() {
+            return Err(Error::new(
+                Span::call_site(),
+                "`input` and `parse` go together",
+            ));
+        }
+        if (max_positions.is_some() || max_grid_width.is_some()) && input_size.is_none() {
+            return Err(Error::new(
+                Span::call_site(),
+                "limits need `input_size` to measure the input against",
+            ));
+        }
+        Ok(Args {
+            year: year.ok_or_else(|| missing("year"))?,
+            day: day.ok_or_else(|| missing("day"))?,
+            variant: variant.ok_or_else(|| missing("variant"))?,
+            input,
+            parse,
+            description,
+            max_positions,
+            max_grid_width,
+            input_size,
+        })
+    }
+}
+
+/// See `rust_advent::advent`.
+#[proc_macro_attribute]
+pub fn advent(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = match Args::parse(attr) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let item = parse_macro_input!(item as DeriveInput);
+    let name = &item.ident;
+    let Args {
+        year,
+        day,
+        variant,
+        input,
+        parse,
+        description,
+        max_positions,
+        max_grid_width,
+        input_size,
+    } = args;
+
+    let day_name = format!("{:02}", day);
+    let (input, parse) = match (input, parse) {
+        (Some(input), Some(parse)) => (
+            quote!(#input),
+            quote!(#parse(input).map_err(::std::convert::Into::into)),
+        ),
+        _ => (
+            quote!(::std::vec::Vec<::std::string::String>),
+            quote!(Ok(input
+                .lines()
+                .map(::std::string::ToString::to_string)
+                .collect())),
+        ),
+    };
+    let description = description.map(|text| {
+        quote! {
+            fn description(&self) -> &'static str {
+                #text
+            }
+        }
+    });
+    let limits = input_size.map(|input_size| {
+        let max_positions = max_positions.map(|max| quote!(.with_max_positions(#max)));
+        let max_grid_width = max_grid_width.map(|max| quote!(.with_max_grid_width(#max)));
+        quote! {
+            fn limits(&self) -> ::rust_advent::solution::Limits {
+                ::rust_advent::solution::Limits::new() #max_positions #max_grid_width
+            }
+
+            fn input_size(&self, input: &Self::Input) -> ::rust_advent::solution::InputSize {
+                #input_size(input)
+            }
+        }
+    });
+
+    quote! {
+        #item
+
+        const _: () = ::rust_advent::solution::check_registration(
+            ::std::option_env!("CARGO_BIN_NAME"),
+            #year,
+            #variant,
+            #day,
+        );
+
+        impl ::rust_advent::solution::Solution for #name {
+            const DAY: &'static str = #day_name;
+            type Input = #input;
+
+            fn parse(
+                &self,
+                input: &str,
+            ) -> ::std::result::Result<Self::Input, ::std::boxed::Box<dyn ::std::error::Error>> {
+                #parse
+            }
+
+            fn part1(
+                &self,
+                input: &Self::Input,
+                ctx: &::rust_advent::solution::Context,
+            ) -> ::std::result::Result<::rust_advent::Answer, ::std::boxed::Box<dyn ::std::error::Error>> {
+                part1(input, ctx)
+                    .map(::std::convert::Into::into)
+                    .map_err(::std::convert::Into::into)
+            }
+
+            fn part2(
+                &self,
+                input: &Self::Input,
+                ctx: &::rust_advent::solution::Context,
+            ) -> ::std::result::Result<::rust_advent::Answer, ::std::boxed::Box<dyn ::std::error::Error>> {
+                part2(input, ctx)
+                    .map(::std::convert::Into::into)
+                    .map_err(::std::convert::Into::into)
+            }
+
+            #description
+            #limits
+        }
+
+        fn main() -> ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> {
+            ::rust_advent::solution::run(&#name)
+        }
+    }
+    .into()
+}
@@ -18,7 +18,8 @@ mod cursor;
 mod gemini_cli;
 
 fn benchmark_part1(c: &mut Criterion) {
-    let inputs = rust_advent::read_number_grid("03").expect("Failed to read input");
+    let inputs = rust_advent::read_number_grid("03", rust_advent::ParseMode::Strict)
+        .expect("Failed to read input");
 
     let mut group = c.benchmark_group("Day 3 Part 1");
 
@@ -31,7 +32,8 @@ fn benchmark_part1(c: &mut Criterion) {
 }
 
 fn benchmark_part2(c: &mut Criterion) {
-    let inputs = rust_advent::read_number_grid("03").expect("Failed to read input");
+    let inputs = rust_advent::read_number_grid("03", rust_advent::ParseMode::Strict)
+        .expect("Failed to read input");
 
     let mut group = c.benchmark_group("Day 3 Part 2");
 
@@ -0,0 +1,27 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+mod support;
+
+// Include the binary files as modules
+// Their tests are compiled out here, leaving their imports unused
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/claude_day07.rs"]
+mod claude;
+
+fn benchmark_part2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Day 7 Part 2");
+
+    // The puzzle input is 141 columns with a splitter in about one in eight
+    // cells of every other row; the column DP should scale with the area.
+    for size in [71, 141, 281, 561] {
+        let grid = support::splitter_grid(size, size, 8);
+        group.bench_with_input(BenchmarkId::new("claude", size), &grid, |b, grid| {
+            b.iter(|| claude::part2::<u64>(grid))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_part2);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+mod support;
+
+// Include the binary files as modules
+#[allow(dead_code)]
+#[path = "../src/bin/claude_day08.rs"]
+mod claude;
+
+fn benchmark_closest_pairs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Day 8 Closest Pairs");
+    group.sample_size(10);
+
+    for count in [1_000, 4_000, 16_000] {
+        let points = support::scattered_points(count);
+        group.bench_with_input(BenchmarkId::new("claude", count), &points, |b, points| {
+            b.iter(|| claude::find_n_closest_pairs(points, 1_000))
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_part2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Day 8 Part 2");
+    group.sample_size(10);
+
+    for count in [1_000, 4_000] {
+        let points = support::scattered_points(count);
+        group.bench_with_input(BenchmarkId::new("prim", count), &points, |b, points| {
+            b.iter(|| claude::part2(points))
+        });
+        group.bench_with_input(BenchmarkId::new("kruskal", count), &points, |b, points| {
+            b.iter(|| claude::part2_kruskal(points))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_closest_pairs, benchmark_part2);
+criterion_main!(benches);
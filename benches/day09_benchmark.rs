@@ -0,0 +1,25 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+mod support;
+
+// Include the binary files as modules
+// Their tests are compiled out here, leaving their imports unused
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/claude_day09.rs"]
+mod claude;
+
+fn benchmark_convex_hull(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Day 9 Convex Hull");
+
+    for count in [500, 5_000, 50_000] {
+        let points = support::scattered_points2d(count);
+        group.bench_with_input(BenchmarkId::new("claude", count), &points, |b, points| {
+            b.iter(|| rust_advent::convex_hull(points))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_convex_hull);
+criterion_main!(benches);
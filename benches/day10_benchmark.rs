@@ -0,0 +1,56 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rust_advent::day10::{self, Machine, Strategy};
+use rust_advent::solution::Context;
+
+mod support;
+
+// Include the binary files as modules
+// Their tests are compiled out here, leaving their imports unused
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/codex_day10.rs"]
+mod codex;
+
+fn benchmark_part2(c: &mut Criterion) {
+    let worst_case = Machine::parse(support::WORST_CASE).unwrap();
+    let hard_case = Machine::parse(support::HARD_CASE).unwrap();
+    let ctx = Context::new();
+
+    let mut group = c.benchmark_group("Day 10 Part 2");
+    group.sample_size(10);
+
+    for (name, machine, strategies) in [
+        ("worst case", &worst_case, &[Strategy::Bfs][..]),
+        ("hard case", &hard_case, &[Strategy::Parity][..]),
+    ] {
+        for &strategy in strategies {
+            group.bench_function(format!("{:?} {}", strategy, name), |b| {
+                b.iter(|| day10::min_presses_with(machine, strategy, &ctx))
+            });
+        }
+        group.bench_function(format!("auto {}", name), |b| {
+            b.iter(|| day10::min_presses(machine, &ctx))
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_gf2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Day 10 GF(2) Solve");
+
+    // Twice as many buttons as counters leaves a large kernel to enumerate.
+    for counters in [8, 16, 32] {
+        let buttons = support::button_masks(counters, 2 * counters);
+        let target = buttons[0] ^ buttons[counters];
+        group.bench_with_input(
+            BenchmarkId::new("codex", counters),
+            &buttons,
+            |b, buttons| b.iter(|| codex::solve_gf2(buttons, target, counters)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_gf2, benchmark_part2);
+criterion_main!(benches);
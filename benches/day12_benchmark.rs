@@ -0,0 +1,86 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts heap allocations so the benchmark can report them per solve.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Heap allocations made by one call of `f`.
+fn count_allocations<R>(f: impl FnOnce() -> R) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    std::hint::black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+mod support;
+
+// Include the binary files as modules
+// Their tests are compiled out here, leaving their imports unused
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/claude_day12.rs"]
+mod claude;
+
+fn benchmark_part1(c: &mut Criterion) {
+    let fits = support::example_with_region("12x5: 1 0 1 0 2 2");
+    let does_not_fit = support::example_with_region("12x5: 1 0 1 0 3 2");
+
+    for (name, input) in [("fits", &fits), ("does not fit", &does_not_fit)] {
+        eprintln!(
+            "claude 12x5 {}: {} allocations per solve",
+            name,
+            count_allocations(|| claude::part1(input))
+        );
+    }
+
+    let mut group = c.benchmark_group("Day 12 Part 1");
+
+    group.bench_function("claude 12x5 fits", |b| b.iter(|| claude::part1(&fits)));
+    group.bench_function("claude 12x5 does not fit", |b| {
+        b.iter(|| claude::part1(&does_not_fit))
+    });
+
+    group.finish();
+}
+
+fn benchmark_backtracking(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Day 12 Backtracking");
+    group.sample_size(10);
+
+    // Presents covering most of the region keep the search from finding a
+    // packing on its first try.
+    for (width, height) in [(8, 8), (10, 10), (12, 10)] {
+        let input =
+            support::example_with_region(&support::packing_region(width, height, 6, 7, 0.8));
+        group.bench_with_input(
+            BenchmarkId::new("claude", format!("{}x{}", width, height)),
+            &input,
+            |b, input| b.iter(|| claude::part1(input)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_part1, benchmark_backtracking);
+criterion_main!(benches);
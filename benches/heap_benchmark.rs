@@ -0,0 +1,90 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rust_advent::IndexedBinaryHeap;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+mod support;
+
+/// The cells next to `idx` in a `side` by `side` grid.
+fn neighbours(idx: usize, side: usize) -> impl Iterator<Item = usize> {
+    let (row, col) = (idx / side, idx % side);
+    [
+        (row > 0).then(|| idx - side),
+        (row + 1 < side).then(|| idx + side),
+        (col > 0).then(|| idx - 1),
+        (col + 1 < side).then(|| idx + 1),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// Cheapest path cost from the top left to the bottom right corner, pushing
+/// a new entry for every improvement and skipping stale ones.
+fn lazy_deletion(costs: &[u8], side: usize) -> u32 {
+    let target = costs.len() - 1;
+    let mut dist = vec![u32::MAX; costs.len()];
+    let mut heap = BinaryHeap::new();
+    dist[0] = 0;
+    heap.push(Reverse((0, 0)));
+    while let Some(Reverse((d, idx))) = heap.pop() {
+        if idx == target {
+            return d;
+        }
+        if d > dist[idx] {
+            continue;
+        }
+        for next in neighbours(idx, side) {
+            let nd = d + u32::from(costs[next]);
+            if nd < dist[next] {
+                dist[next] = nd;
+                heap.push(Reverse((nd, next)));
+            }
+        }
+    }
+    u32::MAX
+}
+
+/// The same search, lowering queued entries in place.
+fn decrease_key(costs: &[u8], side: usize) -> u32 {
+    let target = costs.len() - 1;
+    let mut done = vec![false; costs.len()];
+    let mut heap = IndexedBinaryHeap::new();
+    heap.push(0, 0);
+    while let Some((idx, d)) = heap.pop() {
+        if idx == target {
+            return d;
+        }
+        done[idx] = true;
+        for next in neighbours(idx, side) {
+            if !done[next] {
+                heap.decrease(next, d + u32::from(costs[next]));
+            }
+        }
+    }
+    u32::MAX
+}
+
+fn benchmark_grid_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Dijkstra on Cost Grids");
+    group.sample_size(10);
+
+    for side in [100, 300, 700] {
+        let costs: Vec<u8> = support::cost_grid(side).concat();
+        assert_eq!(lazy_deletion(&costs, side), decrease_key(&costs, side));
+        group.bench_with_input(
+            BenchmarkId::new("lazy_deletion", side),
+            &costs,
+            |b, costs| b.iter(|| lazy_deletion(costs, side)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("indexed_heap", side),
+            &costs,
+            |b, costs| b.iter(|| decrease_key(costs, side)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_grid_dijkstra);
+criterion_main!(benches);
@@ -0,0 +1,116 @@
+//! Fixed-seed inputs from `rust_advent::generators`, so benchmarks give the
+//! same numbers on every run.
+
+// Each benchmark uses only some of the generators.
+#![allow(dead_code)]
+
+use rust_advent::generators::{
+    Lcg, random_digit_grid, random_points, random_points2d, random_region, random_splitter_grid,
+};
+use rust_advent::{Point, Point2d};
+
+/// `count` points with coordinates in `0..100_000`.
+pub fn scattered_points(count: usize) -> Vec<Point> {
+    random_points(&mut Lcg::new(0x2545_f491_4f6c_dd1d), count, 100_000)
+}
+
+/// `count` points with coordinates in `0..100_000`.
+pub fn scattered_points2d(count: usize) -> Vec<Point2d> {
+    random_points2d(&mut Lcg::new(0x9e37_79b9_7f4a_7c15), count, 100_000)
+}
+
+/// A `side` by `side` grid of step costs 1 to 9, for shortest path searches.
+pub fn cost_grid(side: usize) -> Vec<Vec<u8>> {
+    random_digit_grid(&mut Lcg::new(0x0dd1_0dd1), side, side)
+}
+
+/// A day 7 manifold; see [`random_splitter_grid`].
+pub fn splitter_grid(width: usize, rows: usize, spacing: u64) -> Vec<String> {
+    random_splitter_grid(&mut Lcg::new(0x0007_0007), width, rows, spacing)
+}
+
+/// A day 10 machine with small targets, but thirty buttons on ten counters
+/// leave twenty free parity choices at every level; parity decomposition
+/// takes seconds here.
+pub const WORST_CASE: &str = "[..........] \
+(0) (1) (2) (3) (4) (5) (6) (7) (8) (9) \
+(0,1) (1,2) (2,3) (3,4) (4,5) (5,6) (6,7) (7,8) (8,9) (0,9) \
+(0,2) (1,3) (2,4) (3,5) (4,6) (5,7) (6,8) (7,9) (0,5) (1,6) \
+{2,2,2,2,2,2,2,2,2,2}";
+
+/// A day 10 machine with large targets; far too many counter states for a
+/// search.
+pub const HARD_CASE: &str = "[#..##.###.] \
+(0,1,2,3,5,6,7,8) (0,1,2,4,6,7,8,9) (5,8,9) (3,4,6,7) (3,5,6) (1,4,8,9) \
+(2,3,7,8,9) (0,1,2,6,7,8) (0,6,9) (0,5,7,8,9) (0,2,3,4,6,7,8,9) (1,4,6,9) (1,2,5,6) \
+{225,56,230,208,204,28,256,231,235,246}";
+
+/// `buttons` day 10 buttons over `counters` counters, each wiring one to
+/// three counters.
+pub fn button_masks(counters: usize, buttons: usize) -> Vec<u32> {
+    let mut rng = Lcg::new(0x0010_0010);
+    (0..buttons)
+        .map(|_| (0..1 + rng.below(3)).fold(0u32, |mask, _| mask | 1 << rng.below(counters as u64)))
+        .collect()
+}
+
+/// A day 12 region line asking for presents of `shapes` kinds, each of
+/// `shape_cells` cells, that together cover about `fill` of the region.
+pub fn packing_region(
+    width: usize,
+    height: usize,
+    shapes: usize,
+    shape_cells: usize,
+    fill: f64,
+) -> String {
+    let presents = (width * height) as f64 * fill / shape_cells as f64;
+    random_region(
+        &mut Lcg::new((width * height) as u64),
+        width,
+        height,
+        shapes,
+        presents as usize,
+    )
+}
+
+/// The shapes from the day 12 example.
+pub const EXAMPLE_SHAPES: &str = "\
+0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+";
+
+/// The day 12 example's shapes followed by a single region line.
+pub fn example_with_region(region: &str) -> Vec<String> {
+    EXAMPLE_SHAPES
+        .lines()
+        .chain(["", region])
+        .map(str::to_string)
+        .collect()
+}
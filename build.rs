@@ -0,0 +1,19 @@
+//! Regenerates the C header for the `ffi` module when that feature is on.
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("set by cargo");
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+            .expect("cbindgen.toml is valid");
+        cbindgen::Builder::new()
+            // Only the ffi module, so other public constants stay out of C
+            .with_src(format!("{}/src/ffi.rs", crate_dir))
+            .with_config(config)
+            .generate()
+            .expect("the ffi module can be described in C")
+            .write_to_file(format!("{}/include/rust_advent.h", crate_dir));
+    }
+}
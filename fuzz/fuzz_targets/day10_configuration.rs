@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Include the binary file as a module
+#[allow(dead_code)]
+#[path = "../../src/bin/claude_day10.rs"]
+mod claude;
+
+fuzz_target!(|input: &str| {
+    for line in input.lines() {
+        let _ = rust_advent::day10::Machine::parse(line);
+        let _ = claude::parse_configuration(line);
+    }
+});
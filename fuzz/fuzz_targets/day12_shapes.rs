@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Include the binary file as a module
+#[allow(dead_code)]
+#[path = "../../src/bin/claude_day12.rs"]
+mod claude;
+
+fuzz_target!(|input: &str| {
+    let lines: Vec<String> = input.lines().map(str::to_string).collect();
+    let _ = claude::parse_input(&lines);
+});
@@ -0,0 +1,44 @@
+#ifndef RUST_ADVENT_H
+#define RUST_ADVENT_H
+
+/* Generated from src/ffi.rs by cbindgen; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The answer was written to `out`.
+#define ADVENT_OK 0
+
+// The input could not be solved; `out` holds the reason.
+#define ADVENT_ERROR 1
+
+// A pointer was null or the input was not UTF-8; `out` is untouched if it
+// was the null one.
+#define ADVENT_INVALID_ARGUMENT 2
+
+// Solves `part` of `day` for the `len` bytes of UTF-8 at `input`, and
+// points `*out` at a NUL-terminated string with the answer, or with the
+// error for any status other than `ADVENT_OK`.  The string belongs to the
+// caller, who must release it with `advent_free_string`.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes, and `out` to writable space
+// for a pointer.
+int32_t advent_solve(uint32_t year,
+                     uint32_t day,
+                     uint32_t part,
+                     const uint8_t *input,
+                     uintptr_t len,
+                     char **out);
+
+// Releases a string returned by `advent_solve`.  Null is ignored.
+//
+// # Safety
+//
+// `text` must be null or a string from `advent_solve` not yet released.
+void advent_free_string(char *text);
+
+#endif  /* RUST_ADVENT_H */
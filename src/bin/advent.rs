@@ -0,0 +1,1994 @@
+//! Command line runner for the solutions in this crate.
+//!
+//! Each solution lives in its own binary named `<variant>_day<DD>`; the runner
+//! builds those binaries through cargo so it always sees the latest source,
+//! then runs them as child processes.
+
+use rust_advent::history::{self, HISTORY_FILE, Record, Status};
+use rust_advent::meta;
+use rust_advent::solution::parse_duration;
+use rust_advent::stats::{self, Leaderboard, Member};
+use std::ffi::OsString;
+use std::io::Read;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+const YEAR: u32 = 2025;
+const LAST_DAY: u32 = meta::DAYS.len() as u32;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CHILD_POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Extra time a solution gets to notice its deadline before it is killed.
+const KILL_GRACE: Duration = Duration::from_secs(1);
+const STDERR_TAIL_LINES: usize = 10;
+/// Most recent runs shown per solution by `advent history`.
+const HISTORY_ROWS: usize = 10;
+/// Read by `tests/heavy.rs` as the time each variant gets per input.
+const HEAVY_TIMEOUT_VAR: &str = "ADVENT_HEAVY_TIMEOUT";
+/// Port `advent serve` listens on without `--port`.
+const DEFAULT_PORT: u16 = 8080;
+/// Where `advent stats` caches leaderboards, relative to the crate root.
+const STATS_CACHE_DIR: &str = ".advent-cache";
+/// Leaderboard members listed by `advent stats`.
+const LEADERBOARD_ROWS: usize = 10;
+
+const USAGE: &str = "\
+usage: advent <command> [args] [options]
+
+commands:
+  run <year> <day> --variant <name>     run a single solution
+  compare <year> <day> [--repeat <n>]   run every variant of a day side by side, or each
+                                        n times to spot answers that change between runs
+  run-all <year>                        run every variant of every day
+  report <year> [--out <file>]          run every variant and write a Markdown table of
+                                        answers, times and notes on each approach
+  watch <year> <day> --variant <name>   re-run a solution whenever its source or input changes
+  history <year> <day>                  show timings and answers of past runs
+  inspect <year> <day>                  parse a day's input and summarize its size and
+                                        shape, before choosing how to solve it
+  test [--heavy]                        run the test suite, or with --heavy the slow
+                                        checks of what each variant can solve
+  serve [--port <n>]                    answer POST /solve/<year>/<day>/<part> with the
+                                        input as the body, for the days the library
+                                        solves (default port 8080)
+  stats <year> --leaderboard <id>       show a private leaderboard and solve times next
+                                        to the fastest local runs (needs AOC_SESSION)
+
+options:
+  --variant <name>    solution variant, e.g. claude or gemini_cli (optional for history)
+  --timeout <dur>     stop solutions after this long, e.g. 500ms, 30s, 2m
+                      (per variant and input for test --heavy, per request
+                      for serve)
+  --jobs <n>          run up to n solutions at once (compare, run-all and
+                      report)
+  --algo <name>       run one of the solution's algorithms instead of its
+                      default (run only; see the solution's --describe)
+  --visualize         step through the frames a solution draws (run only;
+                      solutions that cannot draw ignore it)
+  --dump-svg <file>   write the puzzle's geometry to an SVG file (run only;
+                      claude day 9)
+  --dump-dot <file>   write the puzzle's graph to a Graphviz DOT file (run
+                      only; claude day 11)
+  --out <file>        where report writes its Markdown (default: stdout)
+  --member <id>       whose solve times stats shows (default: the
+                      leaderboard's owner)";
+
+/// [`USAGE`] followed by the days of the year.
+fn usage() -> String {
+    let mut text = format!("{}\n\ndays:\n", USAGE);
+    for info in &meta::DAYS {
+        let parts = if info.has_part2 { "" } else { " (one part)" };
+        text.push_str(&format!(
+            "  {:>2}  {}{}: {}\n",
+            info.day, info.title, parts, info.input
+        ));
+    }
+    text.pop();
+    text
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = parse_args(&args).and_then(|invocation| {
+        let Invocation {
+            subcommand,
+            options,
+        } = invocation;
+        match subcommand {
+            Subcommand::Watch { day } => watch(day, &options),
+            Subcommand::Run {
+                day,
+                visualize,
+                dumps,
+            } => run(day, visualize, &dumps, &options),
+            Subcommand::Compare { day, repeat } => compare(Some(day), repeat, &options),
+            Subcommand::RunAll => compare(None, None, &options),
+            Subcommand::Report { out } => report(out.as_deref(), &options),
+            Subcommand::History { day } => show_history(day, &options),
+            Subcommand::Inspect { day } => inspect(day),
+            Subcommand::Test { heavy } => test(heavy, &options),
+            Subcommand::Serve { port } => serve(port, &options),
+            Subcommand::Stats {
+                leaderboard,
+                member,
+            } => show_stats(leaderboard, member),
+        }
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Subcommand {
+    Watch {
+        day: u32,
+    },
+    Run {
+        day: u32,
+        visualize: bool,
+        dumps: Vec<Dump>,
+    },
+    Compare {
+        day: u32,
+        repeat: Option<usize>,
+    },
+    RunAll,
+    Report {
+        out: Option<PathBuf>,
+    },
+    History {
+        day: u32,
+    },
+    Inspect {
+        day: u32,
+    },
+    Test {
+        heavy: bool,
+    },
+    Serve {
+        port: u16,
+    },
+    Stats {
+        leaderboard: u64,
+        member: Option<u64>,
+    },
+}
+
+/// Options asking a solution to draw its puzzle into a file.
+const DUMP_OPTIONS: [&str; 2] = ["dump-svg", "dump-dot"];
+
+/// A file a solution is asked to draw its puzzle into, and the option that
+/// asks for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Dump {
+    option: &'static str,
+    path: PathBuf,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Options {
+    variant: Option<String>,
+    timeout: Option<Duration>,
+    jobs: Option<usize>,
+    /// The solution's algorithm to run instead of its default.
+    algo: Option<String>,
+}
+
+impl Options {
+    fn variant(&self) -> Result<&str, String> {
+        self.variant
+            .as_deref()
+            .ok_or_else(|| "this command requires --variant <name>".to_string())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Invocation {
+    subcommand: Subcommand,
+    options: Options,
+}
+
+/// Returns the value of `--name value` or `--name=value` if `arg` is that option.
+fn option_value<'a, I: Iterator<Item = &'a String>>(
+    arg: &str,
+    name: &str,
+    rest: &mut I,
+) -> Option<Result<String, String>> {
+    let flag = arg.strip_prefix("--")?;
+    if let Some(value) = flag.strip_prefix(name)?.strip_prefix('=') {
+        return Some(Ok(value.to_string()));
+    }
+    if flag != name {
+        return None;
+    }
+    Some(
+        rest.next()
+            .cloned()
+            .ok_or_else(|| format!("--{} requires a value", name)),
+    )
+}
+
+fn parse_args(args: &[String]) -> Result<Invocation, String> {
+    let (name, rest) = args.split_first().ok_or_else(usage)?;
+    let mut positional = Vec::new();
+    let mut options = Options::default();
+    let mut heavy = false;
+    let mut visualize = false;
+    let mut dumps = Vec::new();
+    let mut port = None;
+    let mut out = None;
+    let mut repeat = None;
+    let mut leaderboard = None;
+    let mut member = None;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(value) = option_value(arg, "variant", &mut iter) {
+            options.variant = Some(value?);
+        } else if let Some(value) = option_value(arg, "timeout", &mut iter) {
+            options.timeout = Some(parse_duration(&value?)?);
+        } else if let Some(value) = option_value(arg, "algo", &mut iter) {
+            options.algo = Some(value?);
+        } else if let Some(value) = option_value(arg, "jobs", &mut iter) {
+            let value = value?;
+            let jobs = value.parse().ok().filter(|&n: &usize| n > 0);
+            options.jobs = Some(jobs.ok_or_else(|| format!("invalid job count '{}'", value))?);
+        } else if let Some(value) = option_value(arg, "port", &mut iter) {
+            let value = value?;
+            port = Some(
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid port '{}'", value))?,
+            );
+        } else if let Some(value) = option_value(arg, "leaderboard", &mut iter) {
+            leaderboard = Some(parse_member_id(&value?)?);
+        } else if let Some(value) = option_value(arg, "member", &mut iter) {
+            member = Some(parse_member_id(&value?)?);
+        } else if let Some(value) = option_value(arg, "repeat", &mut iter) {
+            let value = value?;
+            let runs = value.parse().ok().filter(|&n: &usize| n > 0);
+            repeat = Some(runs.ok_or_else(|| format!("invalid repeat count '{}'", value))?);
+        } else if let Some(value) = option_value(arg, "out", &mut iter) {
+            out = Some(PathBuf::from(value?));
+        } else if arg == "--heavy" {
+            heavy = true;
+        } else if arg == "--visualize" {
+            visualize = true;
+        } else if let Some((option, value)) = DUMP_OPTIONS
+            .iter()
+            .find_map(|&option| Some((option, option_value(arg, option, &mut iter)?)))
+        {
+            dumps.push(Dump {
+                option,
+                path: PathBuf::from(value?),
+            });
+        } else if arg.starts_with("--") {
+            return Err(format!("unknown option {}\n\n{}", arg, usage()));
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+
+    let subcommand = match name.as_str() {
+        "watch" => Subcommand::Watch {
+            day: parse_year_and_day(&positional)?,
+        },
+        "run" => Subcommand::Run {
+            day: parse_year_and_day(&positional)?,
+            visualize,
+            dumps: dumps.clone(),
+        },
+        "compare" => Subcommand::Compare {
+            day: parse_year_and_day(&positional)?,
+            repeat,
+        },
+        "run-all" => {
+            let [year] = positional[..] else {
+                return Err(format!("expected <year>\n\n{}", usage()));
+            };
+            parse_year(year)?;
+            Subcommand::RunAll
+        }
+        "report" => {
+            let [year] = positional[..] else {
+                return Err(format!("expected <year>\n\n{}", usage()));
+            };
+            parse_year(year)?;
+            Subcommand::Report { out: out.clone() }
+        }
+        "history" => Subcommand::History {
+            day: parse_year_and_day(&positional)?,
+        },
+        "inspect" => Subcommand::Inspect {
+            day: parse_year_and_day(&positional)?,
+        },
+        "test" => {
+            if !positional.is_empty() {
+                return Err(format!("test takes no arguments\n\n{}", usage()));
+            }
+            Subcommand::Test { heavy }
+        }
+        "serve" => {
+            if !positional.is_empty() {
+                return Err(format!("serve takes no arguments\n\n{}", usage()));
+            }
+            Subcommand::Serve {
+                port: port.unwrap_or(DEFAULT_PORT),
+            }
+        }
+        "stats" => {
+            let [year] = positional[..] else {
+                return Err(format!("expected <year>\n\n{}", usage()));
+            };
+            parse_year(year)?;
+            Subcommand::Stats {
+                leaderboard: leaderboard
+                    .ok_or_else(|| "stats requires --leaderboard <id>".to_string())?,
+                member,
+            }
+        }
+        _ => return Err(format!("unknown command {}\n\n{}", name, usage())),
+    };
+    if matches!(
+        subcommand,
+        Subcommand::Watch { .. } | Subcommand::Run { .. }
+    ) {
+        options.variant()?;
+    }
+    if heavy && !matches!(subcommand, Subcommand::Test { .. }) {
+        return Err("--heavy only applies to test".to_string());
+    }
+    if out.is_some() && !matches!(subcommand, Subcommand::Report { .. }) {
+        return Err("--out only applies to report".to_string());
+    }
+    if repeat.is_some() && !matches!(subcommand, Subcommand::Compare { .. }) {
+        return Err("--repeat only applies to compare".to_string());
+    }
+    if port.is_some() && !matches!(subcommand, Subcommand::Serve { .. }) {
+        return Err("--port only applies to serve".to_string());
+    }
+    if (leaderboard.is_some() || member.is_some())
+        && !matches!(subcommand, Subcommand::Stats { .. })
+    {
+        return Err("--leaderboard and --member only apply to stats".to_string());
+    }
+    if visualize && !matches!(subcommand, Subcommand::Run { .. }) {
+        return Err("--visualize only applies to run".to_string());
+    }
+    if options.algo.is_some() && !matches!(subcommand, Subcommand::Run { .. }) {
+        return Err("--algo only applies to run".to_string());
+    }
+    if let Some(dump) = dumps.first()
+        && !matches!(subcommand, Subcommand::Run { .. })
+    {
+        return Err(format!("--{} only applies to run", dump.option));
+    }
+    Ok(Invocation {
+        subcommand,
+        options,
+    })
+}
+
+/// Parses a leaderboard or member id, the number in the leaderboard's URL.
+fn parse_member_id(id: &str) -> Result<u64, String> {
+    id.parse().map_err(|_| format!("invalid id '{}'", id))
+}
+
+fn parse_year(year: &str) -> Result<(), String> {
+    let year: u32 = year
+        .parse()
+        .map_err(|_| format!("invalid year '{}'", year))?;
+    if year != YEAR {
+        return Err(format!("only {} solutions live in this repository", YEAR));
+    }
+    Ok(())
+}
+
+/// Validates the `<year> <day>` positional arguments and returns the day.
+fn parse_year_and_day(positional: &[&str]) -> Result<u32, String> {
+    let [year, day] = positional else {
+        return Err(format!("expected <year> <day>\n\n{}", usage()));
+    };
+    parse_year(year)?;
+    let day: u32 = day.parse().map_err(|_| format!("invalid day '{}'", day))?;
+    if !(1..=LAST_DAY).contains(&day) {
+        return Err(format!("day must be between 1 and {}", LAST_DAY));
+    }
+    Ok(day)
+}
+
+fn bin_name(variant: &str, day: u32) -> String {
+    format!("{}_day{:02}", variant, day)
+}
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn bin_source_path(bin: &str) -> PathBuf {
+    manifest_dir().join("src/bin").join(format!("{}.rs", bin))
+}
+
+/// Splits a binary name such as `gemini_cli_day07` into its variant and day.
+fn split_bin_name(bin: &str) -> Option<(&str, u32)> {
+    let (variant, day) = bin.rsplit_once("_day")?;
+    if day.len() != 2 {
+        return None;
+    }
+    Some((variant, day.parse().ok()?))
+}
+
+/// Lists the solution binaries, optionally restricted to one day.  The
+/// `base` variant holds unimplemented templates and is skipped.
+fn solution_bins(day: Option<u32>) -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(manifest_dir().join("src/bin"))
+        .map_err(|err| format!("failed to list solutions: {}", err))?;
+    let mut bins: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let bin = path.file_stem()?.to_str()?.to_string();
+            let (variant, bin_day) = split_bin_name(&bin)?;
+            let wanted = variant != "base" && day.is_none_or(|d| d == bin_day);
+            wanted.then_some(bin)
+        })
+        .collect();
+    bins.sort_by_key(|bin| split_bin_name(bin).map(|(variant, day)| (day, variant.to_string())));
+    Ok(bins)
+}
+
+/// The source and input files whose modification should trigger a re-run.
+fn watched_files(source: &Path, day: u32) -> Vec<PathBuf> {
+    let mut files = vec![source.to_path_buf(), manifest_dir().join("src/lib.rs")];
+    // Picks up the puzzle input as well as any example files saved next to it.
+    let prefix = format!("{:02}", day);
+    if let Ok(entries) = std::fs::read_dir(rust_advent::get_input_dir()) {
+        let mut inputs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix))
+            })
+            .collect();
+        inputs.sort();
+        files.extend(inputs);
+    }
+    files
+}
+
+fn snapshot(files: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    files
+        .iter()
+        .map(|path| {
+            let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+            (path.clone(), modified)
+        })
+        .collect()
+}
+
+fn tail(text: &str, count: usize) -> Vec<&str> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(count)..].to_vec()
+}
+
+/// Builds the given binaries in release mode.  On failure, returns the tail
+/// of the compiler output.
+fn build(bins: &[String]) -> Result<(), String> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = Command::new(cargo);
+    command.args(["build", "--release", "--quiet"]);
+    for bin in bins {
+        command.args(["--bin", bin]);
+    }
+    let output = command
+        .current_dir(manifest_dir())
+        .output()
+        .map_err(|err| format!("failed to launch cargo: {}", err))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(tail(&stderr, STDERR_TAIL_LINES).join("\n"))
+    }
+}
+
+fn release_dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| manifest_dir().join("target"))
+        .join("release")
+}
+
+/// Output of a single run of a solution binary.
+struct RunOutput {
+    success: bool,
+    timed_out: bool,
+    stdout: String,
+    stderr: String,
+    elapsed: Duration,
+}
+
+fn read_in_background<R: Read + Send + 'static>(reader: Option<R>) -> JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut reader) = reader {
+            let _ = reader.read_to_end(&mut buffer);
+        }
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+/// Runs an already built solution binary.  With a timeout the solution is
+/// asked to stop cooperatively via `--timeout`, and killed if it overruns.
+/// `threads` caps the solution's own rayon pool, and `args` are passed on
+/// after the runner's own options.
+fn execute(
+    bin: &str,
+    timeout: Option<Duration>,
+    threads: Option<usize>,
+    args: &[OsString],
+) -> Result<RunOutput, String> {
+    let mut command = Command::new(release_dir().join(bin));
+    command
+        .current_dir(manifest_dir())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Output is captured, so there is nowhere to render progress.
+    command.arg("--progress=none");
+    if let Some(timeout) = timeout {
+        command.arg(format!("--timeout={}ms", timeout.as_millis()));
+    }
+    if let Some(threads) = threads {
+        // Solutions not yet on the `Solution` trait ignore `--threads`, but
+        // their global rayon pool still honors the environment variable.
+        command
+            .arg(format!("--threads={}", threads))
+            .env("RAYON_NUM_THREADS", threads.to_string());
+    }
+    command.args(args);
+
+    let start = Instant::now();
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("failed to launch {}: {}", bin, err))?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let wait_error = |err| format!("failed to wait for {}: {}", bin, err);
+
+    let mut killed = false;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(wait_error)? {
+            break status;
+        }
+        if timeout.is_some_and(|t| start.elapsed() > t + KILL_GRACE) {
+            let _ = child.kill();
+            killed = true;
+            break child.wait().map_err(wait_error)?;
+        }
+        std::thread::sleep(CHILD_POLL_INTERVAL);
+    };
+    let elapsed = start.elapsed();
+    let success = status.success();
+    Ok(RunOutput {
+        success,
+        // Solutions that honor the deadline exit on their own with an error.
+        timed_out: killed || (!success && timeout.is_some_and(|t| elapsed >= t)),
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        elapsed,
+    })
+}
+
+fn print_result(bin: &str, output: &RunOutput) {
+    let summary = if output.timed_out {
+        "TIMED OUT".to_string()
+    } else {
+        let answers = parse_answers(&output.stdout)
+            .iter()
+            .map(|a| format!("{}: {}", a.label, a.value))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        if output.success {
+            answers
+        } else {
+            let reason = tail(&output.stderr, 1).join("");
+            format!("FAILED {} {}", answers, reason)
+                .trim_end()
+                .to_string()
+        }
+    };
+    println!("{:<22} {:>10.2?}  {}", bin, output.elapsed, summary);
+}
+
+/// A labelled answer line such as `Part 1: 42`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AnswerLine {
+    label: String,
+    value: String,
+}
+
+fn parse_answers(stdout: &str) -> Vec<AnswerLine> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (label, value) = line.split_once(':')?;
+            let label = label.trim();
+            label.starts_with("Part").then(|| AnswerLine {
+                label: label.to_string(),
+                value: value.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Describes how each answer changed relative to the previous run.
+fn format_diff(previous: Option<&[AnswerLine]>, current: &[AnswerLine]) -> Vec<String> {
+    let find = |answers: &[AnswerLine], label: &str| {
+        answers
+            .iter()
+            .find(|a| a.label == label)
+            .map(|a| a.value.clone())
+    };
+
+    let mut lines: Vec<String> = current
+        .iter()
+        .map(|answer| {
+            let note = match previous.map(|p| find(p, &answer.label)) {
+                None => String::new(),
+                Some(None) => " (new)".to_string(),
+                Some(Some(old)) if old == answer.value => " (unchanged)".to_string(),
+                Some(Some(old)) => format!(" (was {})", old),
+            };
+            format!("  {}: {}{}", answer.label, answer.value, note)
+        })
+        .collect();
+    if let Some(previous) = previous {
+        lines.extend(
+            previous
+                .iter()
+                .filter(|old| find(current, &old.label).is_none())
+                .map(|old| format!("  {}: missing (was {})", old.label, old.value)),
+        );
+    }
+    lines
+}
+
+fn history_path() -> PathBuf {
+    manifest_dir().join(HISTORY_FILE)
+}
+
+/// The checked out commit, marked `-dirty` when tracked files have changed.
+fn git_commit() -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(manifest_dir())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let commit = git(&["rev-parse", "--short", "HEAD"])?;
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"])?;
+    Some(if dirty.is_empty() {
+        commit
+    } else {
+        format!("{}-dirty", commit)
+    })
+}
+
+/// Appends a run to the history file.  Failing to record is not fatal.
+fn record_run(bin: &str, output: &RunOutput, commit: Option<&str>) {
+    let status = if output.timed_out {
+        Status::TimedOut
+    } else if output.success {
+        Status::Ok
+    } else {
+        Status::Failed
+    };
+    let record = Record {
+        timestamp: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        commit: commit.map(str::to_string),
+        bin: bin.to_string(),
+        elapsed: output.elapsed,
+        status,
+        answers: parse_answers(&output.stdout)
+            .into_iter()
+            .map(|a| (a.label, a.value))
+            .collect(),
+    };
+    if let Err(err) = history::append(&history_path(), &record) {
+        eprintln!("warning: failed to record run history: {}", err);
+    }
+}
+
+fn run(day: u32, visualize: bool, dumps: &[Dump], options: &Options) -> Result<(), String> {
+    let bin = bin_name(options.variant()?, day);
+    if !bin_source_path(&bin).exists() {
+        return Err(format!("no solution named {}", bin));
+    }
+    // Solutions run from the repository root, not from here
+    let dumps = dumps
+        .iter()
+        .map(|dump| {
+            let path = std::path::absolute(&dump.path)
+                .map_err(|err| format!("invalid --{} path: {}", dump.option, err))?;
+            Ok(Dump { path, ..*dump })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let mut args = Vec::new();
+    if let Some(algo) = &options.algo {
+        args.extend([OsString::from("--algo"), OsString::from(algo)]);
+    }
+    for dump in &dumps {
+        args.extend([
+            OsString::from(format!("--{}", dump.option)),
+            dump.path.clone().into_os_string(),
+        ]);
+    }
+    build(std::slice::from_ref(&bin))?;
+    clear_dumps(&dumps)?;
+    if visualize {
+        return run_visualized(&bin, &args);
+    }
+    let output = execute(&bin, options.timeout, None, &args)?;
+    record_run(&bin, &output, git_commit().as_deref());
+    print_result(&bin, &output);
+    if !output.success {
+        return Err(format!("{} did not complete", bin));
+    }
+    for dump in &dumps {
+        if !dump.path.exists() {
+            return Err(format!("{} does not support --{}", bin, dump.option));
+        }
+        println!("Wrote {}", dump.path.display());
+    }
+    Ok(())
+}
+
+/// Removes files left at the dump paths by earlier runs, so a solution that
+/// ignores a dump option is not taken to have written one.
+fn clear_dumps(dumps: &[Dump]) -> Result<(), String> {
+    for dump in dumps {
+        match std::fs::remove_file(&dump.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!(
+                    "failed to remove old {}: {}",
+                    dump.path.display(),
+                    err
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Runs `bin` with `--visualize` and `args` attached to the terminal, so its
+/// frames can be stepped through.  Interactive runs are not timed or recorded.
+fn run_visualized(bin: &str, args: &[OsString]) -> Result<(), String> {
+    let status = Command::new(release_dir().join(bin))
+        .current_dir(manifest_dir())
+        .arg("--visualize")
+        .args(args)
+        .status()
+        .map_err(|err| format!("failed to launch {}: {}", bin, err))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} did not complete", bin))
+    }
+}
+
+/// Runs every variant of one day, or of every day when `day` is `None`.
+/// With `repeat`, runs each that many times and summarizes the runs.
+fn compare(day: Option<u32>, repeat: Option<usize>, options: &Options) -> Result<(), String> {
+    let bins = solution_bins(day)?;
+    if bins.is_empty() {
+        return Err("no solutions found".to_string());
+    }
+    build(&bins)?;
+    let commit = git_commit();
+    if let Some(repeat) = repeat {
+        return compare_repeated(&bins, repeat, options, commit.as_deref());
+    }
+    let report = |bin: &str, output: &RunOutput| {
+        record_run(bin, output, commit.as_deref());
+        print_result(bin, output);
+    };
+
+    let jobs = options.jobs.unwrap_or(1).min(bins.len());
+    if jobs == 1 {
+        for bin in &bins {
+            report(bin, &execute(bin, options.timeout, None, &[])?);
+        }
+        return Ok(());
+    }
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let threads = solver_threads(jobs, available);
+    // Results are reported in order once all runs finish, so rows stay
+    // sorted by day regardless of which solution completes first.
+    let outputs = run_jobs(&bins, jobs, |bin| {
+        execute(bin, options.timeout, Some(threads), &[])
+    })?;
+    for (bin, output) in bins.iter().zip(outputs) {
+        report(bin, &output?);
+    }
+    Ok(())
+}
+
+/// Runs each of `bins` `repeat` times and prints how its answers and times
+/// spread, flagging solutions whose answers change between runs, as a
+/// parallel solver breaking ties by whichever thread finishes first would.
+fn compare_repeated(
+    bins: &[String],
+    repeat: usize,
+    options: &Options,
+    commit: Option<&str>,
+) -> Result<(), String> {
+    let runs: Vec<&String> = bins
+        .iter()
+        .flat_map(|bin| std::iter::repeat_n(bin, repeat))
+        .collect();
+    let jobs = options.jobs.unwrap_or(1).min(runs.len());
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let threads = (jobs > 1).then(|| solver_threads(jobs, available));
+    let outputs = run_jobs(&runs, jobs, |bin| {
+        execute(bin, options.timeout, threads, &[])
+    })?
+    .into_iter()
+    .collect::<Result<Vec<_>, String>>()?;
+
+    let mut flaky = 0;
+    for (bin, outputs) in bins.iter().zip(outputs.chunks(repeat)) {
+        for output in outputs {
+            record_run(bin, output, commit);
+        }
+        let summary = RepeatSummary::new(outputs);
+        if summary.is_flaky() {
+            flaky += 1;
+        }
+        println!("{}", format_repeats(bin, &summary));
+    }
+    if flaky > 0 {
+        println!(
+            "{} of {} solutions gave different answers across {} runs",
+            flaky,
+            bins.len(),
+            repeat
+        );
+    }
+    Ok(())
+}
+
+/// How one solution's answers and times spread over repeated runs.
+#[derive(Debug, PartialEq)]
+struct RepeatSummary {
+    runs: usize,
+    mean: Duration,
+    stddev: Duration,
+    /// Each answer label with the values it took and how many runs gave
+    /// each, most common first.  A run that stopped before giving the
+    /// answer counts as `failed` or `timed out`.
+    answers: Vec<(String, Vec<(String, usize)>)>,
+}
+
+impl RepeatSummary {
+    fn new(outputs: &[RunOutput]) -> Self {
+        let runs = outputs.len();
+        let seconds: Vec<f64> = outputs.iter().map(|o| o.elapsed.as_secs_f64()).collect();
+        let mean = seconds.iter().sum::<f64>() / runs.max(1) as f64;
+        let variance = seconds.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / runs.max(1) as f64;
+
+        let parsed: Vec<Vec<AnswerLine>> =
+            outputs.iter().map(|o| parse_answers(&o.stdout)).collect();
+        let mut answers: Vec<(String, Vec<(String, usize)>)> = Vec::new();
+        for answer in parsed.iter().flatten() {
+            if !answers.iter().any(|(label, _)| *label == answer.label) {
+                answers.push((answer.label.clone(), Vec::new()));
+            }
+        }
+        for (label, values) in &mut answers {
+            for (output, parsed) in outputs.iter().zip(&parsed) {
+                let value = match parsed.iter().find(|a| a.label == *label) {
+                    Some(answer) => answer.value.clone(),
+                    None if output.timed_out => "timed out".to_string(),
+                    None => "failed".to_string(),
+                };
+                match values.iter_mut().find(|(other, _)| *other == value) {
+                    Some((_, count)) => *count += 1,
+                    None => values.push((value, 1)),
+                }
+            }
+            values.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        }
+        RepeatSummary {
+            runs,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+            answers,
+        }
+    }
+
+    fn is_flaky(&self) -> bool {
+        self.answers.iter().any(|(_, values)| values.len() > 1)
+    }
+}
+
+/// One line per solution: its mean time and spread, then each answer, with
+/// how many runs gave each value when they disagree.
+fn format_repeats(bin: &str, summary: &RepeatSummary) -> String {
+    let answers = if summary.answers.is_empty() {
+        "no answers".to_string()
+    } else {
+        summary
+            .answers
+            .iter()
+            .map(|(label, values)| match &values[..] {
+                [(value, _)] => format!("{}: {}", label, value),
+                _ => {
+                    let spread: Vec<String> = values
+                        .iter()
+                        .map(|(value, count)| format!("{} ({} of {})", value, count, summary.runs))
+                        .collect();
+                    format!("{}: {}", label, spread.join(", "))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+    let flag = if summary.is_flaky() { "FLAKY " } else { "" };
+    format!(
+        "{:<22} {:>10.2?} ± {:<10.2?} {}{}",
+        bin, summary.mean, summary.stddev, flag, answers
+    )
+}
+
+/// Whether `bin` is on the `Solution` trait, directly or through
+/// `#[advent]`, and so answers `--describe` without solving.  Other
+/// solutions ignore their arguments and would run.
+fn describes_itself(bin: &str) -> bool {
+    std::fs::read_to_string(bin_source_path(bin))
+        .is_ok_and(|source| source.contains("impl Solution for") || source.contains("#[advent("))
+}
+
+/// The notes `bin` gives on its approach, if it has any.
+fn describe(bin: &str) -> Option<String> {
+    if !describes_itself(bin) {
+        return None;
+    }
+    let output = Command::new(release_dir().join(bin))
+        .current_dir(manifest_dir())
+        .arg("--describe")
+        .output()
+        .ok()?;
+    let notes = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !notes.is_empty()).then_some(notes)
+}
+
+/// One solution's row in `advent report`.
+struct ReportRow<'a> {
+    bin: &'a str,
+    output: &'a RunOutput,
+    notes: Option<String>,
+}
+
+/// Makes `text` safe to put in a Markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Renders the report: a heading, then one table row per solution.
+fn format_report(rows: &[ReportRow], commit: Option<&str>) -> String {
+    let mut report = format!("# Advent of Code {} results\n\n", YEAR);
+    report += &format!(
+        "Generated by `advent report {}` at commit {}.\n\n",
+        YEAR,
+        commit.unwrap_or("unknown")
+    );
+    report += "| Day | Variant | Part 1 | Part 2 | Time | Notes |\n";
+    report += "|---:|---|---|---|---:|---|\n";
+    for row in rows {
+        let Some((variant, day)) = split_bin_name(row.bin) else {
+            continue;
+        };
+        let answers = parse_answers(&row.output.stdout);
+        let part = |label: &str| match answers.iter().find(|a| a.label == label) {
+            Some(answer) => format!("`{}`", answer.value),
+            None if row.output.timed_out => "timed out".to_string(),
+            None if !row.output.success => "failed".to_string(),
+            None => "-".to_string(),
+        };
+        report += &format!(
+            "| {} | {} | {} | {} | {:.2?} | {} |\n",
+            day,
+            variant,
+            table_cell(&part("Part 1")),
+            table_cell(&part("Part 2")),
+            row.output.elapsed,
+            table_cell(row.notes.as_deref().unwrap_or(""))
+        );
+    }
+    report
+}
+
+/// Runs every variant of every day and writes the results as Markdown to
+/// `out`, or to stdout.
+fn report(out: Option<&Path>, options: &Options) -> Result<(), String> {
+    let bins = solution_bins(None)?;
+    if bins.is_empty() {
+        return Err("no solutions found".to_string());
+    }
+    build(&bins)?;
+    let commit = git_commit();
+    let jobs = options.jobs.unwrap_or(1).min(bins.len());
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let threads = (jobs > 1).then(|| solver_threads(jobs, available));
+    let outputs = run_jobs(&bins, jobs, |bin| {
+        execute(bin, options.timeout, threads, &[])
+    })?
+    .into_iter()
+    .collect::<Result<Vec<_>, String>>()?;
+
+    let mut rows = Vec::new();
+    for (bin, output) in bins.iter().zip(&outputs) {
+        record_run(bin, output, commit.as_deref());
+        print_result(bin, output);
+        rows.push(ReportRow {
+            bin,
+            output,
+            notes: describe(bin),
+        });
+    }
+    let report = format_report(&rows, commit.as_deref());
+    match out {
+        Some(path) => {
+            std::fs::write(path, report)
+                .map_err(|err| format!("failed to write {}: {}", path.display(), err))?;
+            println!("Wrote {}", path.display());
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+/// Runs `job` on every item with at most `jobs` running at once, returning
+/// the results in item order.  Each job has a thread of its own, outside
+/// any rayon pool, so parallel work inside a job goes to the global pool
+/// and never picks up another job while it waits.
+fn run_jobs<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    job: impl Fn(&T) -> R + Sync,
+) -> Result<Vec<R>, String> {
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut done = Vec::new();
+        loop {
+            let idx = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(idx) else {
+                return done;
+            };
+            done.push((idx, job(item)));
+        }
+    };
+    std::thread::scope(|scope| {
+        let workers = (0..jobs.max(1))
+            .map(|_| std::thread::Builder::new().spawn_scoped(scope, work))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("failed to start {} jobs: {}", jobs, err))?;
+        let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+        for worker in workers {
+            let done = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (idx, result) in done {
+                results[idx] = Some(result);
+            }
+        }
+        Ok(results.into_iter().map(Option::unwrap).collect())
+    })
+}
+
+/// Splits the machine's threads evenly between `jobs` concurrent solutions.
+fn solver_threads(jobs: usize, available: usize) -> usize {
+    (available / jobs.max(1)).max(1)
+}
+
+fn watch(day: u32, options: &Options) -> Result<(), String> {
+    let bin = bin_name(options.variant()?, day);
+    let source = bin_source_path(&bin);
+    if !source.exists() {
+        return Err(format!("no solution named {} ({})", bin, source.display()));
+    }
+    println!(
+        "Watching {} and day {:02} inputs; Ctrl-C to stop.",
+        bin, day
+    );
+
+    let mut last_snapshot = None;
+    let mut previous: Option<Vec<AnswerLine>> = None;
+    let mut run_count = 0;
+    loop {
+        let current_snapshot = snapshot(&watched_files(&source, day));
+        if last_snapshot.as_ref() != Some(&current_snapshot) {
+            last_snapshot = Some(current_snapshot);
+            run_count += 1;
+            if let Err(compiler_output) = build(std::slice::from_ref(&bin)) {
+                println!("#{} {} BUILD FAILED", run_count, bin);
+                for line in compiler_output.lines() {
+                    println!("  {}", line);
+                }
+            } else {
+                let output = execute(&bin, options.timeout, None, &[])?;
+                record_run(&bin, &output, git_commit().as_deref());
+                let status = if output.timed_out {
+                    " TIMED OUT"
+                } else if output.success {
+                    ""
+                } else {
+                    " FAILED"
+                };
+                println!("#{} {} ({:.2?}){}", run_count, bin, output.elapsed, status);
+                if output.success {
+                    let answers = parse_answers(&output.stdout);
+                    for line in format_diff(previous.as_deref(), &answers) {
+                        println!("{}", line);
+                    }
+                    previous = Some(answers);
+                } else {
+                    for line in tail(&output.stderr, STDERR_TAIL_LINES) {
+                        println!("  {}", line);
+                    }
+                }
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn format_history_row(record: &Record) -> String {
+    let summary = match record.status {
+        Status::Ok => record
+            .answers
+            .iter()
+            .map(|(label, value)| format!("{}: {}", label, value))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Status::Failed => "FAILED".to_string(),
+        Status::TimedOut => "TIMED OUT".to_string(),
+    };
+    format!(
+        "  {}  {:<13} {:>10.2?}  {}",
+        history::format_timestamp(record.timestamp),
+        record.commit.as_deref().unwrap_or("-"),
+        record.elapsed,
+        summary
+    )
+}
+
+/// Summarizes the successful runs in `records` (oldest first): the latest
+/// time, its change relative to the previous run, and the best time.
+fn format_trend(records: &[&Record]) -> Option<String> {
+    let times: Vec<Duration> = records
+        .iter()
+        .filter(|r| r.status == Status::Ok)
+        .map(|r| r.elapsed)
+        .collect();
+    let (&latest, earlier) = times.split_last()?;
+    let best = times.iter().min()?;
+    let change = earlier
+        .last()
+        .map(|previous| {
+            let percent =
+                (latest.as_secs_f64() / previous.as_secs_f64().max(f64::EPSILON) - 1.0) * 100.0;
+            format!(" ({:+.1}% vs previous)", percent)
+        })
+        .unwrap_or_default();
+    Some(format!(
+        "  latest {:.2?}{}, best {:.2?} over {} successful runs",
+        latest,
+        change,
+        best,
+        times.len()
+    ))
+}
+
+/// Summarizes the day's input, as found by the solutions themselves.
+fn inspect(day: u32) -> Result<(), String> {
+    let path = rust_advent::get_input_path(&format!("{:02}", day));
+    let input = std::fs::read_to_string(&path)
+        .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    match rust_advent::inspect::inspect(day, &input) {
+        Ok(report) => {
+            print!("{}", report);
+            Ok(())
+        }
+        Err(err) => {
+            eprintln!("{}", err.render(&input));
+            Err(format!("{} is not a day {} input", path.display(), day))
+        }
+    }
+}
+
+fn show_history(day: u32, options: &Options) -> Result<(), String> {
+    let path = history_path();
+    let records = history::load(&path)
+        .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    let mut bins: Vec<&str> = Vec::new();
+    for record in &records {
+        let wanted = split_bin_name(&record.bin).is_some_and(|(variant, bin_day)| {
+            bin_day == day && options.variant.as_deref().is_none_or(|v| v == variant)
+        });
+        if wanted && !bins.contains(&record.bin.as_str()) {
+            bins.push(&record.bin);
+        }
+    }
+    if bins.is_empty() {
+        return Err(format!("no recorded runs for day {:02}", day));
+    }
+    bins.sort_by_key(|bin| split_bin_name(bin));
+
+    for (idx, bin) in bins.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        let runs: Vec<&Record> = records.iter().filter(|r| r.bin == *bin).collect();
+        println!("{} ({} runs)", bin, runs.len());
+        for record in &runs[runs.len().saturating_sub(HISTORY_ROWS)..] {
+            println!("{}", format_history_row(record));
+        }
+        if let Some(trend) = format_trend(&runs) {
+            println!("{}", trend);
+        }
+    }
+    Ok(())
+}
+
+/// Cargo arguments for `advent test`: the whole suite, or with `heavy` the
+/// slow checks in `tests/heavy.rs`, built with optimisations.
+fn test_args(heavy: bool) -> Vec<&'static str> {
+    if heavy {
+        vec![
+            "test",
+            "--release",
+            "--features",
+            "heavy-tests",
+            "--test",
+            "heavy",
+        ]
+    } else {
+        vec!["test", "--workspace"]
+    }
+}
+
+fn test(heavy: bool, options: &Options) -> Result<(), String> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = Command::new(cargo);
+    command.args(test_args(heavy)).current_dir(manifest_dir());
+    if let Some(timeout) = options.timeout {
+        command.env(HEAVY_TIMEOUT_VAR, format!("{}ms", timeout.as_millis()));
+    }
+    let status = command
+        .status()
+        .map_err(|err| format!("failed to launch cargo: {}", err))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("tests failed".to_string())
+    }
+}
+
+/// Answers solve requests over HTTP on localhost until interrupted.  Only
+/// the days the library solves from a string are served; the others read
+/// their input from disk in their binaries.
+fn serve(port: u16, options: &Options) -> Result<(), String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|err| format!("failed to listen on port {}: {}", port, err))?;
+    println!("Listening on http://{}:{}", Ipv4Addr::LOCALHOST, port);
+    rust_advent::serve::serve(listener, options.timeout);
+    Ok(())
+}
+
+/// The fastest successful recorded run of any variant of `day`.
+fn fastest_run(records: &[Record], day: u32) -> Option<&Record> {
+    records
+        .iter()
+        .filter(|r| r.status == Status::Ok && split_bin_name(&r.bin).is_some_and(|(_, d)| d == day))
+        .min_by_key(|r| r.elapsed)
+}
+
+/// The leaderboard's top members, then `member`'s solve time for each part
+/// of each day next to the fastest local run from `records`.
+fn format_stats(leaderboard: &Leaderboard, member: &Member, records: &[Record]) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} private leaderboard, {} members",
+        leaderboard.year,
+        leaderboard.members.len()
+    )];
+    for (rank, entry) in leaderboard
+        .members
+        .iter()
+        .take(LEADERBOARD_ROWS)
+        .enumerate()
+    {
+        lines.push(format!(
+            "{:>4}  {:<30} {:>3}*  {:>5}",
+            rank + 1,
+            entry.name,
+            entry.stars,
+            entry.local_score
+        ));
+    }
+    lines.push(String::new());
+    lines.push(format!("Solve times for {}", member.name));
+    lines.push(format!(
+        "{:>3}  {:>10}  {:>10}  fastest local run",
+        "day", "part 1", "part 2"
+    ));
+    for day in 1..=LAST_DAY {
+        let time = |part| {
+            member
+                .solve_time(leaderboard.year, day, part)
+                .map_or("-".to_string(), stats::format_solve_time)
+        };
+        let local = fastest_run(records, day)
+            .map_or("-".to_string(), |r| format!("{:.2?} {}", r.elapsed, r.bin));
+        lines.push(format!(
+            "{:>3}  {:>10}  {:>10}  {}",
+            day,
+            time(1),
+            time(2),
+            local
+        ));
+    }
+    lines
+}
+
+fn show_stats(leaderboard: u64, member: Option<u64>) -> Result<(), String> {
+    let text = stats::fetch_leaderboard(YEAR, leaderboard, &manifest_dir().join(STATS_CACHE_DIR))?;
+    let leaderboard = Leaderboard::parse(&text)?;
+    let id = member.unwrap_or(leaderboard.owner_id);
+    let member = leaderboard
+        .member(id)
+        .ok_or_else(|| format!("no member {} on this leaderboard", id))?;
+    let path = history_path();
+    let records = history::load(&path)
+        .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    for line in format_stats(&leaderboard, member, &records) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn answer(label: &str, value: &str) -> AnswerLine {
+        AnswerLine {
+            label: label.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        let expected = Invocation {
+            subcommand: Subcommand::Watch { day: 12 },
+            options: Options {
+                variant: Some("claude".to_string()),
+                timeout: None,
+                jobs: None,
+                algo: None,
+            },
+        };
+        assert_eq!(
+            parse_args(&args(&["watch", "2025", "12", "--variant", "claude"])).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_args(&args(&["watch", "--variant=claude", "2025", "12"])).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_parse_watch_errors() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["watch", "2025", "12"])).is_err());
+        assert!(parse_args(&args(&["watch", "2024", "12", "--variant", "claude"])).is_err());
+        assert!(parse_args(&args(&["watch", "2025", "13", "--variant", "claude"])).is_err());
+        assert!(parse_args(&args(&["watch", "2025", "--variant", "claude"])).is_err());
+        assert!(parse_args(&args(&["watch", "2025", "1", "--variant"])).is_err());
+        assert!(parse_args(&args(&["watch", "2025", "1", "--fast"])).is_err());
+        assert!(parse_args(&args(&["launch", "2025", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_usage_lists_days() {
+        let err = parse_args(&args(&[])).unwrap_err();
+        assert!(err.starts_with(USAGE), "{}", err);
+        assert!(
+            err.contains("\n   1  Secret Entrance: one rotation per line\n"),
+            "{}",
+            err
+        );
+        assert!(
+            err.ends_with("\n  12  Christmas Tree Farm (one part): shapes followed by regions"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        let invocation = parse_args(&args(&["compare", "2025", "10", "--timeout", "30s"])).unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Compare {
+                day: 10,
+                repeat: None
+            }
+        );
+        assert_eq!(invocation.options.timeout, Some(Duration::from_secs(30)));
+
+        let invocation = parse_args(&args(&["run-all", "2025", "--timeout=500ms"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::RunAll);
+        assert_eq!(invocation.options.timeout, Some(Duration::from_millis(500)));
+
+        assert!(parse_args(&args(&["run-all", "2025", "--timeout", "soon"])).is_err());
+        assert!(parse_args(&args(&["run-all", "2025", "10"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_jobs() {
+        let invocation = parse_args(&args(&["run-all", "2025", "--jobs", "4"])).unwrap();
+        assert_eq!(invocation.options.jobs, Some(4));
+        let invocation = parse_args(&args(&["compare", "2025", "8", "--jobs=2"])).unwrap();
+        assert_eq!(invocation.options.jobs, Some(2));
+        assert!(parse_args(&args(&["run-all", "2025", "--jobs", "0"])).is_err());
+        assert!(parse_args(&args(&["run-all", "2025", "--jobs", "-1"])).is_err());
+        assert!(parse_args(&args(&["run-all", "2025", "--jobs"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_algo() {
+        let invocation = parse_args(&args(&[
+            "run",
+            "2025",
+            "8",
+            "--variant",
+            "claude",
+            "--algo",
+            "kruskal",
+        ]))
+        .unwrap();
+        assert_eq!(invocation.options.algo.as_deref(), Some("kruskal"));
+        let err = parse_args(&args(&["run-all", "2025", "--algo=bfs"])).unwrap_err();
+        assert_eq!(err, "--algo only applies to run");
+    }
+
+    #[test]
+    fn test_parse_repeat() {
+        let invocation = parse_args(&args(&["compare", "2025", "8", "--repeat", "5"])).unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Compare {
+                day: 8,
+                repeat: Some(5)
+            }
+        );
+        assert!(parse_args(&args(&["compare", "2025", "8", "--repeat=0"])).is_err());
+        assert!(parse_args(&args(&["compare", "2025", "8", "--repeat", "x"])).is_err());
+        let err = parse_args(&args(&["run-all", "2025", "--repeat", "3"])).unwrap_err();
+        assert_eq!(err, "--repeat only applies to compare");
+    }
+
+    #[test]
+    fn test_solver_threads() {
+        assert_eq!(solver_threads(1, 8), 8);
+        assert_eq!(solver_threads(4, 8), 2);
+        assert_eq!(solver_threads(3, 8), 2);
+        assert_eq!(solver_threads(16, 8), 1);
+        assert_eq!(solver_threads(2, 1), 1);
+    }
+
+    #[test]
+    fn test_run_jobs_keeps_order_and_limit() {
+        use rayon::prelude::*;
+
+        let items: Vec<u64> = (0..48).collect();
+        for jobs in [1, 3, 8, 64] {
+            let running = AtomicUsize::new(0);
+            let peak = AtomicUsize::new(0);
+            let results = run_jobs(&items, jobs, |&item| {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // Later items finish first
+                std::thread::sleep(Duration::from_micros((48 - item) * 50));
+                // Solvers run their own parallel work inside a job
+                let sum: u64 = (0..=item).into_par_iter().sum();
+                running.fetch_sub(1, Ordering::SeqCst);
+                sum
+            })
+            .unwrap();
+            let expected: Vec<u64> = items.iter().map(|&item| item * (item + 1) / 2).collect();
+            assert_eq!(results, expected, "jobs={}", jobs);
+            assert!(peak.load(Ordering::SeqCst) <= jobs, "jobs={}", jobs);
+        }
+    }
+
+    #[test]
+    fn test_parse_run_requires_variant() {
+        assert!(parse_args(&args(&["run", "2025", "10"])).is_err());
+        let invocation = parse_args(&args(&["run", "2025", "10", "--variant", "codex"])).unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Run {
+                day: 10,
+                visualize: false,
+                dumps: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_clear_dumps() {
+        let path = std::env::temp_dir().join(format!("advent-{}-old.svg", std::process::id()));
+        std::fs::write(&path, "<svg/>").unwrap();
+        let dumps = [Dump {
+            option: "dump-svg",
+            path: path.clone(),
+        }];
+        assert_eq!(clear_dumps(&dumps), Ok(()));
+        assert!(!path.exists());
+        // Nothing to remove is fine too
+        assert_eq!(clear_dumps(&dumps), Ok(()));
+    }
+
+    #[test]
+    fn test_parse_dumps() {
+        let invocation = parse_args(&args(&[
+            "run",
+            "2025",
+            "9",
+            "--variant=claude",
+            "--dump-svg",
+            "out.svg",
+        ]))
+        .unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Run {
+                day: 9,
+                visualize: false,
+                dumps: vec![Dump {
+                    option: "dump-svg",
+                    path: PathBuf::from("out.svg"),
+                }],
+            }
+        );
+        assert!(
+            parse_args(&args(&[
+                "run",
+                "2025",
+                "9",
+                "--variant=claude",
+                "--dump-svg"
+            ]))
+            .is_err()
+        );
+        assert!(parse_args(&args(&["compare", "2025", "9", "--dump-svg=out.svg"])).is_err());
+
+        let invocation = parse_args(&args(&[
+            "run",
+            "2025",
+            "11",
+            "--variant=claude",
+            "--dump-dot=graph.dot",
+        ]))
+        .unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Run {
+                day: 11,
+                visualize: false,
+                dumps: vec![Dump {
+                    option: "dump-dot",
+                    path: PathBuf::from("graph.dot"),
+                }],
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["history", "2025", "11", "--dump-dot", "g.dot"])),
+            Err("--dump-dot only applies to run".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_visualize() {
+        let invocation = parse_args(&args(&[
+            "run",
+            "2025",
+            "7",
+            "--visualize",
+            "--variant=claude",
+        ]))
+        .unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Run {
+                day: 7,
+                visualize: true,
+                dumps: Vec::new(),
+            }
+        );
+        assert!(parse_args(&args(&["compare", "2025", "7", "--visualize"])).is_err());
+        assert!(
+            parse_args(&args(&[
+                "watch",
+                "2025",
+                "7",
+                "--variant=claude",
+                "--visualize"
+            ]))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_inspect() {
+        let invocation = parse_args(&args(&["inspect", "2025", "10"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::Inspect { day: 10 });
+        assert!(parse_args(&args(&["inspect", "2025"])).is_err());
+        assert!(parse_args(&args(&["inspect", "2025", "13"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_history() {
+        let invocation = parse_args(&args(&["history", "2025", "10"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::History { day: 10 });
+        assert_eq!(invocation.options.variant, None);
+
+        let invocation = parse_args(&args(&["history", "2025", "10", "--variant=claude"])).unwrap();
+        assert_eq!(invocation.options.variant.as_deref(), Some("claude"));
+
+        assert!(parse_args(&args(&["history", "2025"])).is_err());
+        assert!(parse_args(&args(&["history", "2025", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_test() {
+        let invocation = parse_args(&args(&["test"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::Test { heavy: false });
+        let invocation = parse_args(&args(&["test", "--heavy", "--timeout", "30s"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::Test { heavy: true });
+        assert_eq!(invocation.options.timeout, Some(Duration::from_secs(30)));
+
+        assert!(parse_args(&args(&["test", "2025"])).is_err());
+        assert!(parse_args(&args(&["compare", "2025", "10", "--heavy"])).is_err());
+        assert_eq!(test_args(false), ["test", "--workspace"]);
+        assert!(test_args(true).ends_with(&["heavy-tests", "--test", "heavy"]));
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        let invocation = parse_args(&args(&["stats", "2025", "--leaderboard", "123"])).unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Stats {
+                leaderboard: 123,
+                member: None
+            }
+        );
+        let invocation =
+            parse_args(&args(&["stats", "2025", "--leaderboard=1", "--member=2"])).unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Stats {
+                leaderboard: 1,
+                member: Some(2)
+            }
+        );
+
+        assert!(parse_args(&args(&["stats", "2025"])).is_err());
+        assert!(parse_args(&args(&["stats", "2025", "--leaderboard", "me"])).is_err());
+        assert!(parse_args(&args(&["stats", "--leaderboard", "1"])).is_err());
+        assert!(parse_args(&args(&["history", "2025", "1", "--member", "2"])).is_err());
+    }
+
+    #[test]
+    fn test_format_stats() {
+        let member = Member {
+            id: 7,
+            name: "owner".to_string(),
+            stars: 1,
+            local_score: 10,
+            // Five minutes after day 1 unlocked
+            stars_at: vec![(1, 1, stats::unlock_time(2025, 1) + 300)],
+        };
+        let leaderboard = Leaderboard {
+            year: 2025,
+            owner_id: 7,
+            members: vec![member.clone()],
+        };
+        let slow = record(Status::Ok, 20);
+        let fast = Record {
+            bin: "codex_day01".to_string(),
+            ..record(Status::Ok, 5)
+        };
+        let failed = Record {
+            bin: "codex_day01".to_string(),
+            ..record(Status::Failed, 1)
+        };
+        let records = [slow, fast, failed];
+        let lines = format_stats(&leaderboard, &member, &records);
+        assert_eq!(lines[0], "2025 private leaderboard, 1 members");
+        assert!(
+            lines[1].contains("owner") && lines[1].ends_with("10"),
+            "{}",
+            lines[1]
+        );
+        assert_eq!(lines[5], "  1     0:05:00           -  5.00ms codex_day01");
+        assert_eq!(
+            lines.last().unwrap(),
+            &format!(" 12  {:>10}  {:>10}  -", "-", "-")
+        );
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let invocation = parse_args(&args(&["report", "2025", "--out", "report.md"])).unwrap();
+        assert_eq!(
+            invocation.subcommand,
+            Subcommand::Report {
+                out: Some(PathBuf::from("report.md"))
+            }
+        );
+        let invocation = parse_args(&args(&["report", "2025", "--jobs=2"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::Report { out: None });
+        assert_eq!(invocation.options.jobs, Some(2));
+
+        assert!(parse_args(&args(&["report"])).is_err());
+        assert!(parse_args(&args(&["run-all", "2025", "--out", "x.md"])).is_err());
+    }
+
+    fn run_output(success: bool, timed_out: bool, stdout: &str) -> RunOutput {
+        RunOutput {
+            success,
+            timed_out,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            elapsed: Duration::from_millis(12),
+        }
+    }
+
+    #[test]
+    fn test_format_report() {
+        let solved = run_output(true, false, "Part 1: 7\nPart 2: 33\n");
+        let slow = run_output(false, true, "Part 1: 7\n");
+        let rows = [
+            ReportRow {
+                bin: "claude_day10",
+                output: &solved,
+                notes: Some("BFS | A*".to_string()),
+            },
+            ReportRow {
+                bin: "codex_day10",
+                output: &slow,
+                notes: None,
+            },
+        ];
+        let report = format_report(&rows, Some("abc1234"));
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "# Advent of Code 2025 results");
+        assert_eq!(
+            lines[2],
+            "Generated by `advent report 2025` at commit abc1234."
+        );
+        assert_eq!(
+            lines[6],
+            "| 10 | claude | `7` | `33` | 12.00ms | BFS \\| A* |"
+        );
+        assert_eq!(lines[7], "| 10 | codex | `7` | timed out | 12.00ms |  |");
+    }
+
+    #[test]
+    fn test_repeat_summary() {
+        let timed = |millis, stdout| RunOutput {
+            elapsed: Duration::from_millis(millis),
+            ..run_output(true, false, stdout)
+        };
+        let steady = [
+            timed(10, "Part 1: 7\nPart 2: 33\n"),
+            timed(14, "Part 1: 7\nPart 2: 33\n"),
+        ];
+        let summary = RepeatSummary::new(&steady);
+        assert!(!summary.is_flaky());
+        assert_eq!(summary.mean, Duration::from_millis(12));
+        assert_eq!(summary.stddev, Duration::from_millis(2));
+        assert_eq!(
+            format_repeats("claude_day08", &summary),
+            format!(
+                "{:<22} {:>10} ± {:<10} Part 1: 7 | Part 2: 33",
+                "claude_day08", "12.00ms", "2.00ms"
+            )
+        );
+
+        let flaky = [
+            timed(12, "Part 1: 7\nPart 2: 34\n"),
+            timed(12, "Part 1: 7\nPart 2: 33\n"),
+            timed(12, "Part 1: 7\nPart 2: 33\n"),
+            run_output(false, true, "Part 1: 7\n"),
+        ];
+        let summary = RepeatSummary::new(&flaky);
+        assert!(summary.is_flaky());
+        assert_eq!(summary.stddev, Duration::ZERO);
+        assert_eq!(
+            summary.answers[1],
+            (
+                "Part 2".to_string(),
+                vec![
+                    ("33".to_string(), 2),
+                    ("34".to_string(), 1),
+                    ("timed out".to_string(), 1)
+                ]
+            )
+        );
+        assert!(
+            format_repeats("gemini_cli_day08", &summary).ends_with(
+                "FLAKY Part 1: 7 | Part 2: 33 (2 of 4), 34 (1 of 4), timed out (1 of 4)"
+            )
+        );
+
+        let failed = [run_output(false, false, "")];
+        assert!(
+            format_repeats("codex_day08", &RepeatSummary::new(&failed)).ends_with("no answers")
+        );
+    }
+
+    #[test]
+    fn test_describes_itself() {
+        assert!(describes_itself("claude_day10"));
+        assert!(!describes_itself("claude_day01"));
+    }
+
+    #[test]
+    fn test_parse_serve() {
+        let invocation = parse_args(&args(&["serve"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::Serve { port: 8080 });
+        let invocation = parse_args(&args(&["serve", "--port=9000", "--timeout", "2s"])).unwrap();
+        assert_eq!(invocation.subcommand, Subcommand::Serve { port: 9000 });
+        assert_eq!(invocation.options.timeout, Some(Duration::from_secs(2)));
+
+        assert!(parse_args(&args(&["serve", "--port", "http"])).is_err());
+        assert!(parse_args(&args(&["serve", "--port", "70000"])).is_err());
+        assert!(parse_args(&args(&["serve", "2025"])).is_err());
+        assert!(parse_args(&args(&["compare", "2025", "10", "--port", "80"])).is_err());
+    }
+
+    fn record(status: Status, elapsed_ms: u64) -> Record {
+        Record {
+            timestamp: 1_765_000_000,
+            commit: Some("496ae92".to_string()),
+            bin: "claude_day10".to_string(),
+            elapsed: Duration::from_millis(elapsed_ms),
+            status,
+            answers: vec![
+                ("Part 1".to_string(), "7".to_string()),
+                ("Part 2".to_string(), "33".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_format_history_row() {
+        let row = format_history_row(&record(Status::Ok, 12));
+        assert!(row.starts_with("  2025-12-06 05:46  496ae92"), "{}", row);
+        assert!(row.ends_with("12.00ms  Part 1: 7 | Part 2: 33"), "{}", row);
+
+        let row = format_history_row(&Record {
+            commit: None,
+            ..record(Status::TimedOut, 2000)
+        });
+        assert!(row.contains("  -  "), "{}", row);
+        assert!(row.ends_with("TIMED OUT"), "{}", row);
+    }
+
+    #[test]
+    fn test_format_trend() {
+        assert_eq!(format_trend(&[]), None);
+        let failed = record(Status::Failed, 5);
+        assert_eq!(format_trend(&[&failed]), None);
+
+        let first = record(Status::Ok, 10);
+        assert_eq!(
+            format_trend(&[&first, &failed]).unwrap(),
+            "  latest 10.00ms, best 10.00ms over 1 successful runs"
+        );
+
+        let slower = record(Status::Ok, 15);
+        assert_eq!(
+            format_trend(&[&first, &failed, &slower]).unwrap(),
+            "  latest 15.00ms (+50.0% vs previous), best 10.00ms over 2 successful runs"
+        );
+    }
+
+    #[test]
+    fn test_bin_name_pads_day() {
+        assert_eq!(bin_name("gemini_cli", 7), "gemini_cli_day07");
+        assert_eq!(bin_name("claude", 12), "claude_day12");
+    }
+
+    #[test]
+    fn test_split_bin_name() {
+        assert_eq!(split_bin_name("gemini_cli_day07"), Some(("gemini_cli", 7)));
+        assert_eq!(split_bin_name("claude_day12"), Some(("claude", 12)));
+        assert_eq!(split_bin_name("advent"), None);
+        assert_eq!(split_bin_name("claude_day1"), None);
+    }
+
+    #[test]
+    fn test_solution_bins() {
+        let day10 = solution_bins(Some(10)).unwrap();
+        assert!(day10.contains(&"claude_day10".to_string()));
+        assert!(day10.contains(&"codex_day10".to_string()));
+        assert!(!day10.contains(&"base_day10".to_string()));
+        assert!(day10.iter().all(|bin| bin.ends_with("_day10")));
+
+        let all = solution_bins(None).unwrap();
+        assert!(!all.contains(&"advent".to_string()));
+        assert!(all.len() > day10.len());
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let stdout = "Part 1: 42\nsome debug output\nPart 2: abc: def\n";
+        assert_eq!(
+            parse_answers(stdout),
+            vec![answer("Part 1", "42"), answer("Part 2", "abc: def")]
+        );
+    }
+
+    #[test]
+    fn test_format_diff_first_run() {
+        let current = vec![answer("Part 1", "42")];
+        assert_eq!(format_diff(None, &current), vec!["  Part 1: 42"]);
+    }
+
+    #[test]
+    fn test_format_diff_changes() {
+        let previous = vec![answer("Part 1", "42"), answer("Part 2", "7")];
+        let current = vec![answer("Part 1", "42"), answer("Part 2", "8")];
+        assert_eq!(
+            format_diff(Some(&previous), &current),
+            vec!["  Part 1: 42 (unchanged)", "  Part 2: 8 (was 7)"]
+        );
+    }
+
+    #[test]
+    fn test_format_diff_added_and_missing() {
+        let previous = vec![answer("Part 1", "42")];
+        let current = vec![answer("Part 2", "8")];
+        assert_eq!(
+            format_diff(Some(&previous), &current),
+            vec!["  Part 2: 8 (new)", "  Part 1: missing (was 42)"]
+        );
+    }
+}
@@ -14,17 +14,17 @@ fn main() -> std::io::Result<()> {
 ///
 /// For example 1-22,30-50 contains the values 11, 22, 33, and 44
 /// which sum to 110.
-fn part1(ranges: &str) -> u64 {
+pub fn part1(ranges: &str) -> u64 {
     let mut total_invalid_sum: u64 = 0;
 
     for range_str in ranges.split(',') {
         if let Some((start_str, end_str)) = range_str.trim().split_once('-') {
             let start: u64 = start_str
                 .parse()
-                .expect(&format!("Could not parse {}", start_str));
+                .unwrap_or_else(|_| panic!("Could not parse {}", start_str));
             let end: u64 = end_str
                 .parse()
-                .expect(&format!("Could not parse {}", end_str));
+                .unwrap_or_else(|_| panic!("Could not parse {}", end_str));
             total_invalid_sum += sum_invalid_ids_in_range(start, end);
         }
     }
@@ -33,17 +33,17 @@ fn part1(ranges: &str) -> u64 {
 }
 
 /// Function for part 2.
-fn part2(ranges: &str) -> u64 {
+pub fn part2(ranges: &str) -> u64 {
     let mut total: u128 = 0;
 
     for range_str in ranges.split(',') {
         if let Some((start_str, end_str)) = range_str.trim().split_once('-') {
             let start: u64 = start_str
                 .parse()
-                .expect(&format!("Could not parse {}", start_str));
+                .unwrap_or_else(|_| panic!("Could not parse {}", start_str));
             let end: u64 = end_str
                 .parse()
-                .expect(&format!("Could not parse {}", end_str));
+                .unwrap_or_else(|_| panic!("Could not parse {}", end_str));
             total += sum_invalid_ids_in_range_part2(start, end) as u128;
         }
     }
@@ -101,9 +101,9 @@ fn sum_invalid_ids_in_range_part2(start: u64, end: u64) -> u64 {
         for i in 1..subset_count {
             let mut product = 1u32;
             let mut set_bits = 0;
-            for bit in 0..num_primes {
+            for (bit, &prime) in primes.iter().enumerate() {
                 if (i >> bit) & 1 == 1 {
-                    product *= primes[bit];
+                    product *= prime;
                     set_bits += 1;
                 }
             }
@@ -141,7 +141,7 @@ fn sum_with_period(d: u32, l: u32, start: u128, end: u128) -> u128 {
 
     // Y constraints from range:
     // Y * R >= start  =>  Y >= (start + R - 1) / R
-    let min_y_range = (start + r - 1) / r;
+    let min_y_range = start.div_ceil(r);
     // Y * R <= end    =>  Y <= end / R
     let max_y_range = end / r;
 
@@ -162,9 +162,9 @@ fn get_prime_factors(n: usize) -> Vec<u32> {
     let mut d = 2;
     let mut temp = n;
     while d * d <= temp {
-        if temp % d == 0 {
+        if temp.is_multiple_of(d) {
             factors.push(d as u32);
-            while temp % d == 0 {
+            while temp.is_multiple_of(d) {
                 temp /= d;
             }
         }
@@ -220,7 +220,7 @@ fn sum_invalid_upto(limit: u64) -> u64 {
         let y_min = p10_prev;
 
         // y_upper_bound from limit
-        let y_limit = (limit as u64) / multiplier;
+        let y_limit = limit / multiplier;
 
         // y_max is min(10^k - 1, y_limit)
         let y_max_possible = p10_curr - 1;
@@ -1,6 +1,6 @@
 /// Day 3.
 fn main() -> std::io::Result<()> {
-    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03")?;
+    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03", rust_advent::ParseMode::Strict)?;
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
         match args[1].as_str() {
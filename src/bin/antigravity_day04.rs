@@ -11,22 +11,18 @@ fn main() -> std::io::Result<()> {
 /// an empty space, count the number of objects that have fewer than 4 adjacent
 /// objects, not including itself, and where grid positions are adjacent
 /// horizontally, vertically, and diagonally.
-fn part1(inputs: &[String]) -> usize {
+pub fn part1(inputs: &[String]) -> usize {
     if inputs.is_empty() {
         return 0;
     }
 
     let grid: Vec<&[u8]> = inputs.iter().map(|s| s.as_bytes()).collect();
-    let rows = grid.len();
     let mut total_count = 0;
 
-    for r in 0..rows {
-        let current_row = grid[r];
-        for c in 0..current_row.len() {
-            if current_row[c] == b'@' {
-                if has_fewer_than_n_neighbors(&grid, r, c, 4) {
-                    total_count += 1;
-                }
+    for (r, current_row) in grid.iter().enumerate() {
+        for (c, &cell) in current_row.iter().enumerate() {
+            if cell == b'@' && has_fewer_than_n_neighbors(&grid, r, c, 4) {
+                total_count += 1;
             }
         }
     }
@@ -42,7 +38,7 @@ fn part1(inputs: &[String]) -> usize {
 /// it possible to remove additional objects -- which should also be removed.
 ///
 /// The return value should be the number removed.
-fn part2(inputs: &[String]) -> usize {
+pub fn part2(inputs: &[String]) -> usize {
     if inputs.is_empty() {
         return 0;
     }
@@ -66,10 +62,13 @@ fn part2(inputs: &[String]) -> usize {
                         }
                         let nr = r as isize + dr;
                         let nc = c as isize + dc;
-                        if nr >= 0 && nr < rows as isize && nc >= 0 && nc < cols as isize {
-                            if grid[nr as usize][nc as usize] == b'@' {
-                                count += 1;
-                            }
+                        if nr >= 0
+                            && nr < rows as isize
+                            && nc >= 0
+                            && nc < cols as isize
+                            && grid[nr as usize][nc as usize] == b'@'
+                        {
+                            count += 1;
                         }
                     }
                 }
@@ -137,12 +136,11 @@ fn has_fewer_than_n_neighbors(grid: &[&[u8]], r: usize, c: usize, n: usize) -> b
                     .try_into()
                     .ok()
                     .filter(|&idx: &usize| idx < neighbor_row.len())
+                    && neighbor_row[col_idx] == b'@'
                 {
-                    if neighbor_row[col_idx] == b'@' {
-                        count += 1;
-                        if count >= n {
-                            return false;
-                        }
+                    count += 1;
+                    if count >= n {
+                        return false;
                     }
                 }
             }
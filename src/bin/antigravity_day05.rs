@@ -33,7 +33,7 @@ fn merge_ranges(ranges: &[(isize, isize)]) -> Vec<(isize, isize)> {
 
 /// Part 1: Count the number of values that are present in any range.
 /// Ranges may overlap, but each value is counted once per occurrence in input.values.
-fn part1(input: &rust_advent::RangeData) -> usize {
+pub fn part1(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() || input.values.is_empty() {
         return 0;
     }
@@ -46,13 +46,13 @@ fn part1(input: &rust_advent::RangeData) -> usize {
         .iter()
         .filter(|&&v| {
             let idx = merged.partition_point(|r| r.1 < v);
-            merged.get(idx).map_or(false, |r| v >= r.0)
+            merged.get(idx).is_some_and(|r| v >= r.0)
         })
         .count()
 }
 
 /// Part 2: Sum the lengths of all intervals after merging overlapping ranges.
-fn part2(input: &rust_advent::RangeData) -> usize {
+pub fn part2(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() {
         return 0;
     }
@@ -6,7 +6,7 @@ fn main() -> std::io::Result<()> {
 }
 
 /// Part 1: Beam splitter
-fn part1(input: &[String]) -> u64 {
+pub fn part1(input: &[String]) -> u64 {
     if input.is_empty() {
         return 0;
     }
@@ -57,7 +57,7 @@ fn part1(input: &[String]) -> u64 {
     total_splits
 }
 
-fn part2(input: &[String]) -> u64 {
+pub fn part2(input: &[String]) -> u64 {
     if input.is_empty() {
         return 0;
     }
@@ -112,6 +112,7 @@ fn part2(input: &[String]) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_advent::example_input;
 
     #[test]
     fn test_example_1() {
@@ -137,24 +138,7 @@ mod tests {
 
     #[test]
     fn test_example_3() {
-        let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
-        ];
+        let input = example_input!("07/example1");
         assert_eq!(part1(&input), 21);
     }
 
@@ -213,24 +197,7 @@ mod tests {
 
     #[test]
     fn test_part2_example_3() {
-        let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
-        ];
+        let input = example_input!("07/example1");
         assert_eq!(part2(&input), 40);
     }
 
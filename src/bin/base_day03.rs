@@ -1,6 +1,6 @@
 /// Day 3
 fn main() -> std::io::Result<()> {
-    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03")?;
+    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03", rust_advent::ParseMode::Strict)?;
     println!("Part 1: {}", part1(&inputs));
     println!("Part 2: {}", part2(&inputs));
     Ok(())
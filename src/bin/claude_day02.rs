@@ -14,7 +14,7 @@ fn main() -> std::io::Result<()> {
 ///
 /// For example 1-22,30-50 contains the values 11, 22, 33, and 44
 /// which sum to 110.
-fn part1(ranges: &str) -> u64 {
+pub fn part1(ranges: &str) -> u64 {
     let mut total = 0u64;
 
     for range_str in ranges.split(',') {
@@ -91,15 +91,16 @@ fn sum_invalid_ids_in_range_part2(start: u64, end: u64) -> u64 {
             let max_pattern = 10u64.pow(pattern_length as u32) - 1;
 
             // Find patterns such that pattern * repeater is in [start, end]
-            let pattern_start = min_pattern.max((start + repeater - 1) / repeater);
+            let pattern_start = min_pattern.max(start.div_ceil(repeater));
             let pattern_end = max_pattern.min(end / repeater);
 
             if pattern_start <= pattern_end {
                 for pattern in pattern_start..=pattern_end {
-                    if let Some(number) = pattern.checked_mul(repeater) {
-                        if number >= start && number <= end {
-                            invalid_ids.insert(number);
-                        }
+                    if let Some(number) = pattern.checked_mul(repeater)
+                        && number >= start
+                        && number <= end
+                    {
+                        invalid_ids.insert(number);
                     }
                 }
             }
@@ -115,7 +116,7 @@ fn sum_invalid_ids_in_range_part2(start: u64, end: u64) -> u64 {
 /// that consist of a pattern repeated at least twice.
 ///
 /// For example: 111 = "1" repeated 3 times, 12341234 = "1234" repeated 2 times
-fn part2(ranges: &str) -> u64 {
+pub fn part2(ranges: &str) -> u64 {
     let mut total = 0u64;
 
     for range_str in ranges.split(',') {
@@ -160,7 +161,7 @@ fn sum_invalid_ids_in_range(start: u64, end: u64) -> u64 {
         let max_x = 10u64.pow(n) - 1;
 
         // Find X values such that X * repeater is in [start, end]
-        let x_start = min_x.max((start + repeater - 1) / repeater);
+        let x_start = min_x.max(start.div_ceil(repeater));
         let x_end = max_x.min(end / repeater);
 
         if x_start <= x_end {
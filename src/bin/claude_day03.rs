@@ -2,7 +2,7 @@ use rayon::prelude::*;
 
 /// Day 3.
 fn main() -> std::io::Result<()> {
-    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03")?;
+    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03", rust_advent::ParseMode::Strict)?;
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
         match args[1].as_str() {
@@ -27,7 +27,7 @@ fn main() -> std::io::Result<()> {
 /// For example, in the row [1, 2, 5, 2, 1] the largest number is 52.
 /// This function returns the sum of the largest numbers for each row
 /// over all provided rows.
-pub fn part1(grid: &Vec<Vec<u8>>) -> u64 {
+pub fn part1(grid: &[Vec<u8>]) -> u64 {
     grid.iter().map(|row| find_max_two_digit(row)).sum()
 }
 
@@ -35,7 +35,7 @@ pub fn part1(grid: &Vec<Vec<u8>>) -> u64 {
 ///
 /// For large input files with many rows, this version processes rows in parallel
 /// across multiple CPU cores for better performance.
-pub fn part1_parallel(grid: &Vec<Vec<u8>>) -> u64 {
+pub fn part1_parallel(grid: &[Vec<u8>]) -> u64 {
     grid.par_iter().map(|row| find_max_two_digit(row)).sum()
 }
 
@@ -44,7 +44,7 @@ pub fn part1_parallel(grid: &Vec<Vec<u8>>) -> u64 {
 /// Given a grid of numbers, for each row find the largest 12-digit number that
 /// can be formed by selecting 12 numbers from the row in order.
 /// Returns the sum of these numbers across all rows.
-pub fn part2(grid: &Vec<Vec<u8>>) -> u64 {
+pub fn part2(grid: &[Vec<u8>]) -> u64 {
     grid.iter().map(|row| find_max_n_digit(row, 12)).sum()
 }
 
@@ -52,7 +52,7 @@ pub fn part2(grid: &Vec<Vec<u8>>) -> u64 {
 ///
 /// For large input files with many rows, this version processes rows in parallel
 /// across multiple CPU cores for better performance.
-pub fn part2_parallel(grid: &Vec<Vec<u8>>) -> u64 {
+pub fn part2_parallel(grid: &[Vec<u8>]) -> u64 {
     grid.par_iter().map(|row| find_max_n_digit(row, 12)).sum()
 }
 
@@ -111,10 +111,10 @@ fn find_max_n_digit(row: &[u8], n: usize) -> u64 {
         let mut max_val = 0u8;
         let mut max_idx = start;
 
-        for i in start..end {
-            if row[i] > max_val {
-                max_val = row[i];
-                max_idx = i;
+        for (offset, &digit) in row[start..end].iter().enumerate() {
+            if digit > max_val {
+                max_val = digit;
+                max_idx = start + offset;
             }
         }
 
@@ -11,7 +11,7 @@ fn main() -> std::io::Result<()> {
 /// an empty space, count the number of objects that have fewer than 4 adjacent
 /// objects, not including itself, and where grid positions are adjacent
 /// horizontally, vertically, and diagonally.
-fn part1(inputs: &[String]) -> usize {
+pub fn part1(inputs: &[String]) -> usize {
     if inputs.is_empty() {
         return 0;
     }
@@ -98,7 +98,7 @@ fn has_fewer_than_n_neighbors(
 /// it possible to remove additional objects -- which should also be removed.
 ///
 /// The return value should be the number removed.
-fn part2(inputs: &[String]) -> usize {
+pub fn part2(inputs: &[String]) -> usize {
     if inputs.is_empty() {
         return 0;
     }
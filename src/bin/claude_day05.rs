@@ -11,7 +11,7 @@ fn main() -> std::io::Result<()> {
 /// one input.range, where each range is an inclusive interval [start, end].
 /// Ranges may overlap, but a value that is in multiple ranges should only
 /// count once.
-fn part1(input: &rust_advent::RangeData) -> usize {
+pub fn part1(input: &rust_advent::RangeData) -> usize {
     // Merge overlapping ranges for efficiency
     let merged_ranges = merge_ranges(&input.ranges);
 
@@ -67,7 +67,7 @@ fn merge_ranges(ranges: &[(isize, isize)]) -> Vec<(isize, isize)> {
 ///
 /// This optimized version merges and sums in a single pass without allocating
 /// a vector for the merged ranges, saving O(m) space and eliminating a second iteration.
-fn part2(input: &rust_advent::RangeData) -> usize {
+pub fn part2(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() {
         return 0;
     }
@@ -98,8 +98,36 @@ fn part2(input: &rust_advent::RangeData) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use rust_advent::RangeData;
 
+    fn covers(ranges: &[(isize, isize)], value: isize) -> bool {
+        ranges
+            .iter()
+            .any(|&(start, end)| start <= value && value <= end)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_merge_ranges_preserves_coverage(
+            ranges in prop::collection::vec((-100isize..100, 0isize..20), 0..30)
+                .prop_map(|ranges| {
+                    ranges.into_iter().map(|(start, len)| (start, start + len)).collect::<Vec<_>>()
+                })
+        ) {
+            let merged = merge_ranges(&ranges);
+            for value in -101..121 {
+                prop_assert_eq!(covers(&ranges, value), covers(&merged, value), "value {}", value);
+            }
+            // Sorted, and separated by at least one uncovered value
+            for pair in merged.windows(2) {
+                prop_assert!(pair[0].1 + 1 < pair[1].0, "{:?} not separated", pair);
+            }
+            let total: isize = merged.iter().map(|&(start, end)| end - start + 1).sum();
+            prop_assert_eq!(total as usize, part2(&RangeData { ranges, values: vec![] }));
+        }
+    }
+
     #[test]
     fn test_part1_example() {
         // Example from problem statement:
@@ -1,179 +1,29 @@
+use rust_advent::Error;
+use rust_advent::PuzzleInput;
+use rust_advent::homework::{grand_total, parse_columns, parse_rows};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let inputs = rust_advent::read_file_as_lines("06")?;
-    println!("Part 1: {}", part1(&inputs)?);
-    println!("Part 2: {}", part2(&inputs)?);
+    let input = PuzzleInput::read("06")?;
+    let lines: Vec<&str> = input.lines().collect();
+    println!("Part 1: {}", part1(&lines)?);
+    println!("Part 2: {}", part2(&lines)?);
     Ok(())
 }
 
 /// Part 1: Homework
 ///
 /// Converts lines into homework problems, then performs the problems.
-fn part1(input: &[String]) -> Result<i64, String> {
-    // Need at least 3 lines (2 data rows + 1 operator row)
-    if input.len() < 3 {
-        return Err(format!(
-            "Not enough lines: need at least 3, got {}",
-            input.len()
-        ));
-    }
-
-    // Split into data lines and operator line
-    let data_lines = &input[..input.len() - 1];
-    let operator_line = &input[input.len() - 1];
-
-    // Parse data lines into numbers
-    let mut data: Vec<Vec<i32>> = Vec::new();
-    for line in data_lines {
-        let numbers: Result<Vec<i32>, _> =
-            line.split_whitespace().map(|s| s.parse::<i32>()).collect();
-        data.push(numbers.map_err(|e| format!("Invalid number: {}", e))?);
-    }
-
-    // Check that all lines have the same number of elements (M)
-    let m = data[0].len();
-    for row in &data {
-        if row.len() != m {
-            return Err(format!(
-                "Inconsistent number of columns: expected {}, got {}",
-                m,
-                row.len()
-            ));
-        }
-    }
-
-    // Parse operators
-    let mut operators: Vec<char> = Vec::new();
-    for s in operator_line.split_whitespace() {
-        let ch = s
-            .chars()
-            .next()
-            .ok_or_else(|| "Empty operator".to_string())?;
-        operators.push(ch);
-    }
-
-    if operators.len() != m {
-        return Err(format!(
-            "Number of operators ({}) doesn't match number of columns ({})",
-            operators.len(),
-            m
-        ));
-    }
-
-    // Process each column (problem)
-    let mut total = 0i64;
-    for col_idx in 0..m {
-        let operator = operators[col_idx];
-        let mut result = data[0][col_idx] as i64;
-
-        // Apply the operator to all values in this column
-        for row in data.iter().skip(1) {
-            let value = row[col_idx] as i64;
-            match operator {
-                '+' => result += value,
-                '*' => result *= value,
-                _ => return Err(format!("Invalid operator: {}", operator)),
-            }
-        }
-
-        total += result;
-    }
-
-    Ok(total)
+pub fn part1<S: AsRef<str>>(input: &[S]) -> Result<i64, String> {
+    Ok(grand_total(&parse_rows(input)?).map_err(Error::from)?)
 }
 
 /// Part 2: Vertical Homework
 ///
 /// Numbers are formed by reading character columns vertically (top-to-bottom).
-/// Problems are identified by operator positions in the operator row.
-/// All input lines are padded to equal length.
-/// Column positions are processed right-to-left within each problem's range.
-fn part2(input: &[String]) -> Result<i64, String> {
-    // Validate input - need at least 3 lines (2 data rows + 1 operator row)
-    if input.len() < 3 {
-        return Err(format!(
-            "Not enough lines: need at least 3, got {}",
-            input.len()
-        ));
-    }
-
-    // Split into data lines and operator line
-    let data_lines = &input[..input.len() - 1];
-    let operator_line = &input[input.len() - 1];
-
-    // Find maximum line length and pad all lines to that length
-    let max_len = input.iter().map(|line| line.len()).max().unwrap();
-    let padded_data: Vec<String> = data_lines
-        .iter()
-        .map(|line| format!("{:width$}", line, width = max_len))
-        .collect();
-    let padded_operator = format!("{:width$}", operator_line, width = max_len);
-
-    // Find operator positions to identify problems
-    let operator_positions: Vec<(usize, char)> = padded_operator
-        .chars()
-        .enumerate()
-        .filter(|(_, ch)| !ch.is_whitespace())
-        .collect();
-
-    if operator_positions.is_empty() {
-        return Err("No operators found in operator row".to_string());
-    }
-
-    // Validate operators
-    for (_, op) in &operator_positions {
-        if *op != '+' && *op != '*' {
-            return Err(format!("Invalid operator: {}", op));
-        }
-    }
-
-    // Process each problem
-    let mut total = 0i64;
-
-    for (problem_idx, &(operator_pos, operator)) in operator_positions.iter().enumerate() {
-        // Determine column range for this problem
-        // The operator position marks the START of the problem
-        let start_col = operator_pos;
-        let end_col = if problem_idx + 1 < operator_positions.len() {
-            operator_positions[problem_idx + 1].0 - 1
-        } else {
-            max_len - 1
-        };
-
-        // Collect numbers by reading columns right-to-left
-        let mut numbers: Vec<i64> = Vec::new();
-
-        for col_idx in (start_col..=end_col).rev() {
-            // Read this column top-to-bottom across all data rows
-            let mut digits = String::new();
-            for row in &padded_data {
-                if let Some(ch) = row.chars().nth(col_idx)
-                    && ch.is_ascii_digit()
-                {
-                    digits.push(ch);
-                }
-            }
-
-            // If we found any digits, parse as a number
-            if !digits.is_empty() {
-                let num = digits
-                    .parse::<i64>()
-                    .map_err(|e| format!("Failed to parse number '{}': {}", digits, e))?;
-                numbers.push(num);
-            }
-        }
-
-        // Apply operator to all numbers in this problem
-        if !numbers.is_empty() {
-            let result: i64 = match operator {
-                '+' => numbers.iter().copied().sum(),
-                '*' => numbers.iter().copied().product(),
-                _ => return Err(format!("Invalid operator: {}", operator)),
-            };
-            total += result;
-        }
-    }
-
-    Ok(total)
+/// Problems are identified by operator positions in the operator row, and
+/// their columns are read right-to-left.
+pub fn part2<S: AsRef<str>>(input: &[S]) -> Result<i64, String> {
+    Ok(grand_total(&parse_columns(input)?).map_err(Error::from)?)
 }
 
 #[cfg(test)]
@@ -292,7 +142,7 @@ mod tests {
         let input = vec!["1 2".to_string(), "+ *".to_string()];
         let result = part1(&input);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Not enough lines"));
+        assert!(result.unwrap_err().contains("need at least two rows"));
     }
 
     #[test]
@@ -307,7 +157,7 @@ mod tests {
         assert!(
             result
                 .unwrap_err()
-                .contains("Inconsistent number of columns")
+                .contains("row 2 has length 2, expected 3")
         );
     }
 
@@ -320,7 +170,11 @@ mod tests {
         ];
         let result = part1(&input);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Number of operators"));
+        assert!(
+            result
+                .unwrap_err()
+                .contains("expected 3 operators, found 2")
+        );
     }
 
     #[test]
@@ -332,7 +186,7 @@ mod tests {
         ];
         let result = part1(&input);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Invalid number"));
+        assert!(result.unwrap_err().contains("invalid number 'abc'"));
     }
 
     #[test]
@@ -344,7 +198,7 @@ mod tests {
         ];
         let result = part1(&input);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Invalid operator"));
+        assert!(result.unwrap_err().contains("invalid operator"));
     }
 
     // Part 2 Tests
@@ -584,7 +438,7 @@ mod tests {
         let input = vec!["123".to_string(), "+".to_string()];
         let result = part2(&input);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Not enough lines"));
+        assert!(result.unwrap_err().contains("need at least two rows"));
     }
 
     #[test]
@@ -596,7 +450,7 @@ mod tests {
         ];
         let result = part2(&input);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("No operators found"));
+        assert!(result.unwrap_err().contains("no operators found"));
     }
 
     #[test]
@@ -608,6 +462,48 @@ mod tests {
         ];
         let result = part2(&input);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Invalid operator"));
+        assert!(result.unwrap_err().contains("invalid operator"));
+    }
+
+    #[test]
+    fn test_part1_overflow() {
+        let input = vec![
+            "2147483647 1".to_string(),
+            "2147483647 1".to_string(),
+            "2147483647 1".to_string(),
+            "* +".to_string(),
+        ];
+        assert_eq!(
+            part1(&input),
+            Err("unsupported: Problem 1 overflows i64".to_string())
+        );
+
+        // Each problem fits, but not their sum
+        let input = vec![
+            "2147483647 2147483647".to_string(),
+            "2147483647 2147483647".to_string(),
+            "2 2".to_string(),
+            "* *".to_string(),
+        ];
+        assert_eq!(
+            part1(&input),
+            Err("unsupported: Total overflows i64".to_string())
+        );
+    }
+
+    #[test]
+    fn test_part2_overflow() {
+        // Seven columns of 999 multiply past i64::MAX
+        let input = vec![
+            "9999999".to_string(),
+            "9999999".to_string(),
+            "9999999".to_string(),
+            "*      ".to_string(),
+        ];
+        assert_eq!(
+            part2(&input),
+            Err("unsupported: Problem 1 overflows i64".to_string())
+        );
+        assert_eq!(part2(&input[1..]), Ok(99i64.pow(7)));
     }
 }
@@ -1,9 +1,35 @@
-use std::collections::HashMap;
-
-fn main() -> std::io::Result<()> {
+use rust_advent::paths::{Overflow, PathCount, checked_sum};
+use rust_advent::sim::{Columns, row_automaton, row_distribution};
+use rust_advent::viz::{Color, Highlight, Visualizer, render_highlighted};
+use std::collections::BTreeSet;
+
+/// Accumulator for part 2: unbounded with the `bigint` feature.
+#[cfg(feature = "bigint")]
+type Count = num_bigint::BigUint;
+#[cfg(not(feature = "bigint"))]
+type Count = u128;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cross_check = std::env::args().any(|arg| arg == "--cross-check");
     let inputs = rust_advent::read_file_as_lines("07")?;
+    let mut viz = Visualizer::from_args()?;
+    if viz.is_enabled() {
+        visualize_beams(&inputs, &mut viz);
+    }
     println!("Part 1: {}", part1(&inputs));
-    println!("Part 2: {}", part2(&inputs));
+    let paths = part2::<Count>(&inputs)?;
+    println!("Part 2: {}", paths);
+    if cross_check {
+        let enumerated = part2_states::<Count>(&inputs)?;
+        if enumerated != paths {
+            return Err(format!(
+                "Part 2 mismatch: column DP gives {}, state enumeration gives {}",
+                paths, enumerated
+            )
+            .into());
+        }
+        eprintln!("Part 2 cross-check: state enumeration agrees");
+    }
     Ok(())
 }
 
@@ -13,7 +39,7 @@ fn main() -> std::io::Result<()> {
 /// When a beam hits a '^' splitter, it splits into two beams that continue
 /// downward from positions left and right of the splitter.
 /// Returns the total number of splits that occur.
-fn part1(input: &[String]) -> u64 {
+pub fn part1(input: &[String]) -> u64 {
     if input.is_empty() {
         return 0;
     }
@@ -25,78 +51,21 @@ fn part1(input: &[String]) -> u64 {
     };
 
     let width = input[0].len();
-
-    // Use bitmask for efficient beam tracking (works for grids up to 64 columns)
-    if width <= 64 {
-        part1_bitmask(input, start_col, width)
-    } else {
-        // Fallback for very wide grids
-        part1_vec(input, start_col, width)
-    }
-}
-
-/// Efficient implementation using bitmask for beam positions
-fn part1_bitmask(input: &[String], start_col: usize, width: usize) -> u64 {
-    let mut active_beams = 1u64 << start_col;
+    let rows = input.iter().skip(1).map(|row| row.as_bytes());
     let mut split_count = 0u64;
-
-    for row in input.iter().skip(1) {
-        let row_chars: Vec<char> = row.chars().collect();
-        let mut next_beams = 0u64;
-
-        for col in 0..width.min(row_chars.len()) {
-            if (active_beams & (1u64 << col)) != 0 {
-                // Beam at this column
-                if row_chars[col] == '^' {
-                    split_count += 1;
-                    if col > 0 {
-                        next_beams |= 1u64 << (col - 1);
-                    }
-                    if col + 1 < width {
-                        next_beams |= 1u64 << (col + 1);
-                    }
-                } else {
-                    next_beams |= 1u64 << col;
-                }
-            }
-        }
-
-        active_beams = next_beams;
-    }
-
-    split_count
-}
-
-/// Fallback implementation using Vec for wide grids
-fn part1_vec(input: &[String], start_col: usize, width: usize) -> u64 {
-    let mut active_beams = vec![start_col];
-    let mut next_beams = Vec::new();
-    let mut split_count = 0u64;
-
-    for row in input.iter().skip(1) {
-        let row_chars: Vec<char> = row.chars().collect();
-        next_beams.clear();
-
-        for &col in &active_beams {
-            if col < row_chars.len() {
-                if row_chars[col] == '^' {
-                    split_count += 1;
-                    if col > 0 {
-                        next_beams.push(col - 1);
-                    }
-                    if col + 1 < width {
-                        next_beams.push(col + 1);
-                    }
-                } else {
-                    next_beams.push(col);
-                }
-            }
-        }
-
-        next_beams.sort_unstable();
-        next_beams.dedup();
-        std::mem::swap(&mut active_beams, &mut next_beams);
-    }
+    row_automaton(Columns::single(width, start_col), rows, |beams, row| {
+        // Beams past the end of a short row leave the grid
+        let cols = 0..row.len().min(width);
+        let beams = beams & &Columns::from_ones(width, cols.clone());
+        let splitters = Columns::from_ones(width, cols.filter(|&col| row[col] == b'^'));
+        let split = &beams & &splitters;
+        split_count += split.count_ones() as u64;
+        // Split beams move one column either way, dropping off the edges
+        let mut next_beams = &beams ^ &split;
+        next_beams |= &(&split >> 1);
+        next_beams |= &(&split << 1);
+        next_beams
+    });
 
     split_count
 }
@@ -105,181 +74,179 @@ fn part1_vec(input: &[String], start_col: usize, width: usize) -> u64 {
 ///
 /// When a beam hits a '^' splitter, it takes EITHER the left path OR the right path
 /// (not both). We need to count all possible distinct paths the beam might take.
-fn part2(input: &[String]) -> u64 {
+///
+/// A path is a single beam, so it is enough to count the paths reaching each
+/// column row by row: O(rows × width) regardless of how many choices there are.
+pub fn part2<T: PathCount>(input: &[String]) -> Result<T, Overflow> {
     if input.is_empty() {
-        return 0;
+        return Ok(T::zero());
     }
 
     // Find the starting position 'S' in the first row
     let start_col = match input[0].chars().position(|c| c == 'S') {
         Some(col) => col,
-        None => return 0,
+        None => return Ok(T::zero()),
     };
 
     let width = input[0].len();
-
-    // Use bitmask for efficient state representation (works for grids up to 64 columns)
-    if width <= 64 {
-        part2_bitmask(input, start_col, width)
-    } else {
-        // Fallback for very wide grids
-        part2_vec(input, start_col, width)
-    }
-}
-
-/// Efficient implementation using bitmask to represent beam configurations
-fn part2_bitmask(input: &[String], start_col: usize, width: usize) -> u64 {
-    // State: bitmask where bit i = 1 means beam at column i
-    // Map from bitmask to count of paths reaching that configuration
-    let mut current_states: HashMap<u64, u64> = HashMap::new();
-    current_states.insert(1u64 << start_col, 1);
+    let zero = T::zero();
+    let mut paths = vec![zero.clone(); width];
+    let mut next_paths = vec![zero.clone(); width];
+    // Paths whose beam left the grid still count as distinct paths.
+    let mut exited = T::zero();
+    paths[start_col] = T::one();
 
     for row in input.iter().skip(1) {
-        let row_chars: Vec<char> = row.chars().collect();
-        let mut next_states: HashMap<u64, u64> = HashMap::new();
+        let row = row.as_bytes();
+        next_paths.fill(zero.clone());
 
-        for (&beams_mask, &path_count) in &current_states {
-            generate_next_bitmask(beams_mask, &row_chars, path_count, width, &mut next_states);
-        }
-
-        current_states = next_states;
-    }
-
-    current_states.values().sum()
-}
-
-/// Generate all possible next beam configurations using bitmask representation
-fn generate_next_bitmask(
-    beams_mask: u64,
-    row_chars: &[char],
-    path_count: u64,
-    width: usize,
-    next_states: &mut HashMap<u64, u64>,
-) {
-    // Identify splitters and their choices
-    let mut splitter_choices = Vec::new();
-    let mut base_next_mask = 0u64;
-
-    for col in 0..width.min(row_chars.len()) {
-        if (beams_mask & (1u64 << col)) != 0 {
-            // Beam at this column
-            if row_chars[col] == '^' {
-                let can_left = col > 0;
-                let can_right = col + 1 < width;
-
-                if can_left && can_right {
-                    // This is a choice point
-                    splitter_choices.push((col, true, true));
-                } else if can_left {
-                    base_next_mask |= 1u64 << (col - 1);
-                } else if can_right {
-                    base_next_mask |= 1u64 << (col + 1);
-                }
-            } else {
-                // Beam continues straight
-                base_next_mask |= 1u64 << col;
+        for (col, count) in paths.iter().enumerate() {
+            if *count == zero {
+                continue;
             }
-        }
-    }
-
-    let num_choices = splitter_choices.len();
-
-    // Generate all 2^num_choices possible configurations
-    for choice_mask in 0..(1 << num_choices) {
-        let mut next_mask = base_next_mask;
-
-        for (i, &(col, _, _)) in splitter_choices.iter().enumerate() {
-            let go_left = (choice_mask & (1 << i)) == 0;
-            if go_left {
-                next_mask |= 1u64 << (col - 1);
-            } else {
-                next_mask |= 1u64 << (col + 1);
+            match row.get(col) {
+                None => exited.add_paths(count)?,
+                Some(b'^') => {
+                    let can_left = col > 0;
+                    let can_right = col + 1 < width;
+                    if can_left {
+                        next_paths[col - 1].add_paths(count)?;
+                    }
+                    if can_right {
+                        next_paths[col + 1].add_paths(count)?;
+                    }
+                    if !can_left && !can_right {
+                        exited.add_paths(count)?;
+                    }
+                }
+                Some(_) => next_paths[col].add_paths(count)?,
             }
         }
 
-        *next_states.entry(next_mask).or_insert(0) += path_count;
-    }
-}
-
-/// Fallback implementation using Vec for wide grids
-fn part2_vec(input: &[String], start_col: usize, width: usize) -> u64 {
-    let mut current_states: HashMap<Vec<usize>, u64> = HashMap::new();
-    current_states.insert(vec![start_col], 1);
-
-    for row in input.iter().skip(1) {
-        let row_chars: Vec<char> = row.chars().collect();
-        let mut next_states: HashMap<Vec<usize>, u64> = HashMap::new();
-
-        for (beams, path_count) in current_states {
-            generate_next_vec(&beams, &row_chars, path_count, width, &mut next_states);
-        }
-
-        current_states = next_states;
+        std::mem::swap(&mut paths, &mut next_paths);
     }
 
-    current_states.values().sum()
+    let mut total = checked_sum(&paths)?;
+    total.add_paths(&exited)?;
+    Ok(total)
 }
 
-/// Generate next configurations for Vec-based representation
-fn generate_next_vec(
-    beams: &[usize],
-    row_chars: &[char],
-    path_count: u64,
-    width: usize,
-    next_states: &mut HashMap<Vec<usize>, u64>,
-) {
-    let mut splitter_info = Vec::new();
-    let mut non_splitter_next = Vec::new();
-
-    for &col in beams {
-        if col < row_chars.len() {
-            if row_chars[col] == '^' {
-                let can_go_left = col > 0;
-                let can_go_right = col + 1 < width;
-                splitter_info.push((col, can_go_left, can_go_right));
-            } else {
-                non_splitter_next.push(col);
-            }
-        }
+/// Part 2 by enumerating beam configurations, kept to cross-check `part2`.
+/// Exponential in the number of splitters hit on a row.
+fn part2_states<T: PathCount>(input: &[String]) -> Result<T, Overflow> {
+    if input.is_empty() {
+        return Ok(T::zero());
     }
 
-    let choice_splitters: Vec<_> = splitter_info
-        .iter()
-        .filter(|(_, left, right)| *left && *right)
-        .collect();
-
-    let num_choices = choice_splitters.len();
+    // Find the starting position 'S' in the first row
+    let start_col = match input[0].chars().position(|c| c == 'S') {
+        Some(col) => col,
+        None => return Ok(T::zero()),
+    };
 
-    for choice_mask in 0..(1 << num_choices) {
-        let mut next_beams = non_splitter_next.clone();
+    let width = input[0].len();
+    let rows = input.iter().skip(1).map(|row| row.as_bytes());
+    let states = row_distribution::<T, _, _>(
+        Columns::single(width, start_col),
+        rows,
+        |beams, row, next| {
+            // Beams that carry on regardless, and the splitters where a beam
+            // chooses between left and right
+            let mut forced = Columns::new(width);
+            let mut choices = Vec::new();
+            for col in beams.iter_ones() {
+                match row.get(col) {
+                    Some(b'^') => match (col > 0, col + 1 < width) {
+                        (true, true) => choices.push(col),
+                        (true, false) => forced.insert(col - 1),
+                        (false, true) => forced.insert(col + 1),
+                        (false, false) => {}
+                    },
+                    Some(_) => forced.insert(col),
+                    None => {}
+                }
+            }
+            for choice_mask in 0..1u64 << choices.len() {
+                let mut next_beams = forced.clone();
+                for (idx, &col) in choices.iter().enumerate() {
+                    let go_left = choice_mask & (1 << idx) == 0;
+                    next_beams.insert(if go_left { col - 1 } else { col + 1 });
+                }
+                next.push(next_beams);
+            }
+        },
+    );
 
-        let mut choice_idx = 0;
-        for &(col, can_go_left, can_go_right) in &splitter_info {
-            if can_go_left && can_go_right {
-                let go_left = (choice_mask & (1 << choice_idx)) == 0;
-                choice_idx += 1;
+    checked_sum(states?.values())
+}
 
-                if go_left {
-                    next_beams.push(col - 1);
-                } else {
-                    next_beams.push(col + 1);
+/// Draws the manifold one row at a time for `--visualize`: beams so far as
+/// `|`, and the splitters they hit in another color.  Returns the number of
+/// splits, which matches `part1`.
+fn visualize_beams(input: &[String], viz: &mut Visualizer) -> usize {
+    let Some(start) = input.first().and_then(|row| row.find('S')) else {
+        return 0;
+    };
+    let mut beams = BTreeSet::from([start]);
+    let mut trail = Vec::new();
+    let mut hits = Vec::new();
+    for (row_idx, row) in input.iter().enumerate().skip(1) {
+        let mut next = BTreeSet::new();
+        for &col in &beams {
+            match row.as_bytes().get(col) {
+                Some(b'^') => {
+                    hits.push((row_idx, col));
+                    if col > 0 {
+                        next.insert(col - 1);
+                    }
+                    if col + 1 < row.len() {
+                        next.insert(col + 1);
+                    }
+                }
+                Some(_) => {
+                    next.insert(col);
                 }
-            } else if can_go_left {
-                next_beams.push(col - 1);
-            } else if can_go_right {
-                next_beams.push(col + 1);
+                None => {}
             }
         }
-
-        next_beams.sort_unstable();
-        next_beams.dedup();
-        *next_states.entry(next_beams).or_insert(0) += path_count;
-    }
+        trail.extend(next.iter().map(|&col| (row_idx, col)));
+        beams = next;
+
+        let title = format!(
+            "row {} of {}: {} beams, {} splits",
+            row_idx,
+            input.len() - 1,
+            beams.len(),
+            hits.len()
+        );
+        viz.frame(&title, |ansi| {
+            let highlights = [
+                Highlight::new(Color::Cyan, trail.iter().copied()).with_glyph(b'|'),
+                Highlight::new(Color::Yellow, hits.iter().copied()),
+            ];
+            render_highlighted(input, &highlights, ansi)
+        });
+    }
+    hits.len()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_advent::generators::{Lcg, random_splitter_grid};
+    use rust_advent::paths::Modular;
+    use rust_advent::{FastMap, example_input};
+
+    /// Runs both part 2 implementations and checks that they agree.
+    fn part2_checked(input: &[String]) -> u64 {
+        let paths = part2::<u64>(input).unwrap();
+        assert_eq!(
+            paths,
+            part2_states::<u64>(input).unwrap(),
+            "implementations disagree"
+        );
+        paths
+    }
 
     #[test]
     fn test_example1() {
@@ -316,25 +283,27 @@ mod tests {
 
     #[test]
     fn test_large_example() {
+        let input = example_input!("07/example1");
+        assert_eq!(part1(&input), 21);
+    }
+
+    #[test]
+    fn test_visualized_splits_match_part1() {
+        let input = example_input!("07/example1");
+        assert_eq!(
+            visualize_beams(&input, &mut Visualizer::default()) as u64,
+            part1(&input)
+        );
         let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
+            "..S..".to_string(),
+            "..^..".to_string(),
+            ".^.^.".to_string(),
         ];
-        assert_eq!(part1(&input), 21);
+        assert_eq!(
+            visualize_beams(&input, &mut Visualizer::default()) as u64,
+            part1(&input)
+        );
+        assert_eq!(visualize_beams(&[], &mut Visualizer::default()), 0);
     }
 
     #[test]
@@ -423,7 +392,7 @@ mod tests {
             ".....".to_string(),
         ];
         // 1 splitter hit -> 2 choices (left or right)
-        assert_eq!(part2(&input), 2);
+        assert_eq!(part2_checked(&input), 2);
     }
 
     #[test]
@@ -438,30 +407,13 @@ mod tests {
         // - Left path hits another splitter: 2 choices
         // - Right path doesn't hit splitter: 1 choice
         // Total: 2 + 1 = 3
-        assert_eq!(part2(&input), 3);
+        assert_eq!(part2_checked(&input), 3);
     }
 
     #[test]
     fn test_part2_large_example() {
-        let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
-        ];
-        assert_eq!(part2(&input), 40);
+        let input = example_input!("07/example1");
+        assert_eq!(part2_checked(&input), 40);
     }
 
     #[test]
@@ -472,7 +424,7 @@ mod tests {
             ".....".to_string(),
         ];
         // No splitters -> only 1 path
-        assert_eq!(part2(&input), 1);
+        assert_eq!(part2_checked(&input), 1);
     }
 
     #[test]
@@ -487,7 +439,7 @@ mod tests {
         //   - If left (col 3): hits splitter, 2 choices
         //   - If right (col 5): hits splitter, 2 choices
         // Total: 2 + 2 = 4
-        assert_eq!(part2(&input), 4);
+        assert_eq!(part2_checked(&input), 4);
     }
 
     #[test]
@@ -504,7 +456,7 @@ mod tests {
         //   Right splitter: beam doesn't hit (beam is at col 3, splitters at 2 and 4)
         // Actually, beam at col 3 doesn't hit either splitter on row 2
         // So only 1 path, then hits splitter on row 3: 2 paths
-        assert_eq!(part2(&input), 2);
+        assert_eq!(part2_checked(&input), 2);
     }
 
     #[test]
@@ -515,7 +467,7 @@ mod tests {
             ".....".to_string(),
         ];
         // Splitter at col 0 can only go right (boundary)
-        assert_eq!(part2(&input), 1);
+        assert_eq!(part2_checked(&input), 1);
     }
 
     #[test]
@@ -530,7 +482,7 @@ mod tests {
         //   - Path with beam at col 1: hits splitter at col 1 -> 2 subpaths
         //   - Path with beam at col 3: hits splitter at col 3 -> 2 subpaths
         // Total: 2 + 2 = 4
-        assert_eq!(part2(&input), 4);
+        assert_eq!(part2_checked(&input), 4);
     }
 
     #[test]
@@ -546,19 +498,133 @@ mod tests {
         // Row 1: 1 beam -> 2 paths
         // Row 2: each path can split into 2 -> 4 paths (some may merge)
         // Row 3: further splitting
-        let result = part2(&input);
+        let result = part2_checked(&input);
         assert!(result > 4); // Should have significant path count
     }
 
     #[test]
     fn test_part2_single_row() {
         let input = vec!["..S..".to_string()];
-        assert_eq!(part2(&input), 1);
+        assert_eq!(part2_checked(&input), 1);
     }
 
     #[test]
     fn test_part2_empty() {
         let input: Vec<String> = vec![];
-        assert_eq!(part2(&input), 0);
+        assert_eq!(part2_checked(&input), 0);
+    }
+
+    #[test]
+    fn test_part2_edge_splitters() {
+        // A splitter on a one-column grid sends the beam out of the grid.
+        let input = vec!["S".to_string(), "^".to_string(), ".".to_string()];
+        assert_eq!(part2_checked(&input), 1);
+
+        // A beam past the end of a short row leaves the grid but still counts;
+        // the other beam goes on to split again.
+        let input = vec![
+            "..S..".to_string(),
+            "..^..".to_string(),
+            "..".to_string(),
+            ".^.^.".to_string(),
+        ];
+        assert_eq!(part2_checked(&input), 3);
+    }
+
+    /// A 101 column grid with `rows` rows of splitters in a checkerboard
+    /// under a centred start, roughly doubling the paths every row.
+    fn checkerboard(rows: usize) -> Vec<String> {
+        let mut input = vec![format!("{}S{}", ".".repeat(50), ".".repeat(50))];
+        for row in 0..rows {
+            input.push(
+                (0..101)
+                    .map(|col| if (col + row) % 2 == 0 { '^' } else { '.' })
+                    .collect(),
+            );
+        }
+        input
+    }
+
+    #[test]
+    fn test_part1_wide_grids() {
+        let mut rng = Lcg::new(7);
+        for width in [63, 64, 65, 129, 300, 700] {
+            let input = random_splitter_grid(&mut rng, width, 40, 3);
+            assert_eq!(
+                part1(&input),
+                visualize_beams(&input, &mut Visualizer::default()) as u64,
+                "width {}",
+                width
+            );
+        }
+        // A beam on each edge splits off the grid on one side only
+        let input = ["S".to_string() + &".".repeat(299), "^".repeat(300)];
+        assert_eq!(part1(&input), 1);
+        let input = [".".repeat(299) + "S", "^".repeat(300), ".".repeat(300)];
+        assert_eq!(part1(&input), 1);
+    }
+
+    #[test]
+    fn test_part2_overflow() {
+        let input = checkerboard(100);
+        assert_eq!(part2::<u64>(&input), Err(Overflow { type_name: "u64" }));
+        let paths: u128 = part2(&input).unwrap();
+        assert!(paths > u128::from(u64::MAX));
+        const M: u64 = 1_000_000_007;
+        let modular: Modular<M> = part2(&input).unwrap();
+        assert_eq!(u128::from(modular.value()), paths % u128::from(M));
+
+        // The enumeration overflows the same way on a narrow grid
+        let narrow: Vec<String> = checkerboard(70)
+            .iter()
+            .map(|row| row[40..61].to_string())
+            .collect();
+        assert_eq!(
+            part2_states::<u32>(&narrow),
+            Err(Overflow { type_name: "u32" })
+        );
+        assert_eq!(
+            part2_checked(&narrow[..20]),
+            part2::<u64>(&narrow[..20]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_part2_wide_grid() {
+        // Too many simultaneous choices for the state enumeration: every row
+        // of splitters doubles the paths, plus the ones bouncing off the edge.
+        let input = checkerboard(60);
+        // Reference: naive recursion over single beams, memoized by cell.
+        fn count(
+            input: &[String],
+            row: usize,
+            col: usize,
+            memo: &mut FastMap<(usize, usize), u64>,
+        ) -> u64 {
+            if row == input.len() {
+                return 1;
+            }
+            if let Some(&known) = memo.get(&(row, col)) {
+                return known;
+            }
+            let width = input[0].len();
+            let paths = if input[row].as_bytes()[col] == b'^' {
+                let mut paths = 0;
+                if col > 0 {
+                    paths += count(input, row + 1, col - 1, memo);
+                }
+                if col + 1 < width {
+                    paths += count(input, row + 1, col + 1, memo);
+                }
+                paths
+            } else {
+                count(input, row + 1, col, memo)
+            };
+            memo.insert((row, col), paths);
+            paths
+        }
+        let paths: u64 = part2(&input).unwrap();
+        assert_eq!(paths, count(&input, 1, 50, &mut FastMap::default()));
+        assert!(paths > 1 << 40);
     }
 }
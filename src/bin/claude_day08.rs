@@ -1,15 +1,73 @@
 use rayon::prelude::*;
-use rust_advent::Point;
-use std::collections::{BinaryHeap, HashMap};
-use std::sync::Mutex;
-
-fn main() -> std::io::Result<()> {
-    let inputs = rust_advent::read_points("08")?;
-    println!("Part 1: {}", part1(1000, 3, &inputs));
-    println!("Part 2: {}", part2(&inputs));
-    Ok(())
+use rust_advent::parallel::BoundedHeap;
+use rust_advent::solution::{Context, Solution};
+use rust_advent::{Answer, Point, product_checked};
+use std::collections::HashMap;
+use std::error::Error;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    rust_advent::solution::run(&Day08)
 }
 
+/// Pairs connected for part 1.
+const PAIRS: usize = 1000;
+/// Largest circuits multiplied for part 1.
+const CIRCUITS: usize = 3;
+
+struct Day08;
+
+impl Solution for Day08 {
+    const DAY: &'static str = "08";
+    type Input = Vec<Point>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Point>, Box<dyn Error>> {
+        Ok(rust_advent::parse_points(input)?)
+    }
+
+    fn part1(&self, input: &Vec<Point>, _ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+        Ok(part1(PAIRS, CIRCUITS, input).ok_or(OVERFLOW)?.into())
+    }
+
+    fn part2(&self, input: &Vec<Point>, ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+        let last = match ctx.algorithm() {
+            Some("kruskal") => part2_kruskal(input),
+            _ => part2(input),
+        };
+        Ok(last.ok_or(OVERFLOW)?.into())
+    }
+
+    /// Builds part 1's circuits once and grows part 2's spanning tree from
+    /// them.  A chosen algorithm runs the parts separately instead.
+    fn solve_both(
+        &self,
+        input: &Vec<Point>,
+        ctx: &Context,
+    ) -> Option<Result<(Answer, Answer), Box<dyn Error>>> {
+        if ctx.algorithm().is_some() {
+            return None;
+        }
+        let (part1, part2) = both_parts(PAIRS, CIRCUITS, input);
+        Some(match (part1, part2) {
+            (Some(part1), Some(part2)) => Ok((part1.into(), part2.into())),
+            _ => Err(OVERFLOW.into()),
+        })
+    }
+
+    fn description(&self) -> &'static str {
+        "Union-find over the pairs by distance: part 1 joins the closest pairs, found with \
+         per-thread bounded heaps, and part 2 is the last edge of a dense Prim's spanning \
+         tree. Run together, Prim's starts from part 1's circuits."
+    }
+
+    /// Part 2's spanning tree, grown with `prim` by default or with
+    /// `kruskal` over every pair sorted by distance.
+    fn strategies(&self) -> &'static [&'static str] {
+        &["prim", "kruskal"]
+    }
+}
+
+const OVERFLOW: &str = "answer overflows usize";
+
 /// Union-Find data structure for tracking connected components
 struct UnionFind {
     parent: Vec<usize>,
@@ -59,64 +117,43 @@ fn squared_distance(p1: &Point, p2: &Point) -> i64 {
     dx * dx + dy * dy + dz * dz
 }
 
-/// Find the n closest pairs of points globally (parallelized with early termination)
-fn find_n_closest_pairs(points: &[Point], n: usize) -> Vec<(usize, usize)> {
+/// Find the n closest pairs of points globally.
+///
+/// Each rayon worker keeps its own bounded heap, so there is no shared lock;
+/// the per-worker heaps are merged pairwise at the end.  Ties on distance go
+/// to the lower index pair, so the result does not depend on the split.
+pub fn find_n_closest_pairs(points: &[Point], n: usize) -> Vec<(usize, usize)> {
     if n == 0 || points.len() < 2 {
         return Vec::new();
     }
 
-    // Thread-safe heap for parallel updates
-    let heap = Mutex::new(BinaryHeap::<(i64, usize, usize)>::new());
-
-    // Parallel examination of all pairs
-    (0..points.len()).into_par_iter().for_each(|i| {
-        let mut local_candidates = Vec::new();
-
-        for j in (i + 1)..points.len() {
-            // Early termination heuristic: check if this pair could possibly be close enough
-            let mut should_compute = true;
-            if let Ok(guard) = heap.lock() {
-                if guard.len() >= n {
-                    if let Some(&(max_dist, _, _)) = guard.peek() {
-                        // Quick check: if coordinate differences are too large, skip
-                        let dx = (points[i].x - points[j].x).abs() as i64;
-                        let dy = (points[i].y - points[j].y).abs() as i64;
-                        let dz = (points[i].z - points[j].z).abs() as i64;
-
-                        // If any single coordinate difference squared exceeds max_dist, skip
+    let heap = (0..points.len())
+        .into_par_iter()
+        .fold(
+            || BoundedHeap::new(n),
+            |mut heap, i| {
+                for j in (i + 1)..points.len() {
+                    // Early termination: once this worker holds n pairs, skip any
+                    // pair whose single-axis distance already exceeds its worst.
+                    if heap.is_full()
+                        && let Some(&(max_dist, _, _)) = heap.max()
+                    {
+                        let dx = (points[i].x as i64) - (points[j].x as i64);
+                        let dy = (points[i].y as i64) - (points[j].y as i64);
+                        let dz = (points[i].z as i64) - (points[j].z as i64);
                         if dx * dx > max_dist || dy * dy > max_dist || dz * dz > max_dist {
-                            should_compute = false;
+                            continue;
                         }
                     }
+                    heap.push((squared_distance(&points[i], &points[j]), i, j));
                 }
-            }
-
-            if should_compute {
-                let dist = squared_distance(&points[i], &points[j]);
-                local_candidates.push((dist, i, j));
-            }
-        }
-
-        // Update global heap with local candidates
-        if !local_candidates.is_empty() {
-            if let Ok(mut guard) = heap.lock() {
-                for candidate in local_candidates {
-                    if guard.len() < n {
-                        guard.push(candidate);
-                    } else if let Some(&(max_dist, _, _)) = guard.peek() {
-                        if candidate.0 < max_dist {
-                            guard.pop();
-                            guard.push(candidate);
-                        }
-                    }
-                }
-            }
-        }
-    });
+                heap
+            },
+        )
+        .reduce(|| BoundedHeap::new(n), BoundedHeap::merge);
 
     // Extract pairs (discard distances)
-    heap.into_inner()
-        .unwrap()
+    heap.into_sorted_vec()
         .into_iter()
         .map(|(_, i, j)| (i, j))
         .collect()
@@ -134,23 +171,31 @@ fn count_component_sizes(uf: &mut UnionFind, n: usize) -> Vec<usize> {
     component_counts.into_values().collect()
 }
 
-/// Calculate product of the m largest values in the vector
-fn product_of_largest(mut sizes: Vec<usize>, m: usize) -> usize {
+/// Calculate product of the m largest values in the vector, or `None` if it
+/// overflows
+fn product_of_largest(mut sizes: Vec<usize>, m: usize) -> Option<usize> {
     if m == 0 || sizes.is_empty() {
-        return 1;
+        return Some(1);
     }
 
     // Sort in descending order
     sizes.sort_unstable_by(|a, b| b.cmp(a));
 
     // Multiply the first min(m, sizes.len()) elements
-    sizes.iter().take(m).product()
+    product_checked(sizes.into_iter().take(m))
+}
+
+/// Product of the x coordinates of points `i` and `j`, or `None` if either is
+/// negative or the product overflows
+fn x_product(inputs: &[Point], i: usize, j: usize) -> Option<usize> {
+    let x = |idx: usize| usize::try_from(inputs[idx].x).ok();
+    product_checked([x(i)?, x(j)?])
 }
 
-fn part1(n: usize, m: usize, inputs: &[Point]) -> usize {
+pub fn part1(n: usize, m: usize, inputs: &[Point]) -> Option<usize> {
     // Handle edge cases
     if inputs.is_empty() || m == 0 {
-        return 1;
+        return Some(1);
     }
 
     // Find n closest pairs globally
@@ -169,9 +214,119 @@ fn part1(n: usize, m: usize, inputs: &[Point]) -> usize {
     product_of_largest(sizes, m)
 }
 
-fn part2(inputs: &[Point]) -> usize {
+/// Minimum remaining length at which one Prim step is split across threads.
+const PRIM_PARALLEL_MIN_LEN: usize = 4096;
+
+/// Dense Prim's algorithm: O(n²) time and O(n) memory, with no edge list and
+/// no sort.  The answer uses the longest MST edge, which is the edge Kruskal's
+/// algorithm would add last.  Edges are ordered by `(distance, i, j)` with
+/// `i < j`, so ties resolve the same way in both algorithms.
+pub fn part2(inputs: &[Point]) -> Option<usize> {
+    if inputs.len() < 2 {
+        return Some(0);
+    }
+    let longest = longest_tree_edge(inputs, &mut UnionFind::new(inputs.len()));
+    match longest {
+        Some((_, i, j)) => x_product(inputs, i, j),
+        None => Some(0),
+    }
+}
+
+/// The longest edge Prim's algorithm adds to join the circuits of `uf` into
+/// one, treating each circuit as a single node: reaching any point of a
+/// circuit brings in all of it.  Circuits already joined along spanning tree
+/// edges, such as part 1's, leave the rest of the tree unchanged.
+fn longest_tree_edge(inputs: &[Point], uf: &mut UnionFind) -> Option<(i64, usize, usize)> {
+    let circuit: Vec<usize> = (0..inputs.len()).map(|point| uf.find(point)).collect();
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); inputs.len()];
+    for (point, &root) in circuit.iter().enumerate() {
+        members[root].push(point);
+    }
+
+    // Points not yet in the tree, with their cheapest edge into the tree.
+    let mut remaining: Vec<usize> = (0..inputs.len())
+        .filter(|&point| circuit[point] != circuit[0])
+        .collect();
+    let mut closest: Vec<(i64, usize, usize)> = vec![(i64::MAX, 0, 0); remaining.len()];
+    let mut longest: Option<(i64, usize, usize)> = None;
+    let mut added = std::mem::take(&mut members[circuit[0]]);
+
+    while !remaining.is_empty() {
+        // Relax distances against the points just added and pick the nearest.
+        let (edge, position) = closest
+            .par_iter_mut()
+            .zip(remaining.par_iter())
+            .enumerate()
+            .with_min_len(PRIM_PARALLEL_MIN_LEN)
+            .map(|(position, (best, &point))| {
+                for &current in &added {
+                    let edge = (
+                        squared_distance(&inputs[current], &inputs[point]),
+                        current.min(point),
+                        current.max(point),
+                    );
+                    if edge < *best {
+                        *best = edge;
+                    }
+                }
+                (*best, position)
+            })
+            .min()
+            .expect("remaining is not empty");
+        longest = longest.max(Some(edge));
+
+        let root = circuit[remaining[position]];
+        added = std::mem::take(&mut members[root]);
+        if added.len() == 1 {
+            remaining.swap_remove(position);
+            closest.swap_remove(position);
+        } else {
+            let mut idx = 0;
+            while idx < remaining.len() {
+                if circuit[remaining[idx]] == root {
+                    remaining.swap_remove(idx);
+                    closest.swap_remove(idx);
+                } else {
+                    idx += 1;
+                }
+            }
+        }
+    }
+    longest
+}
+
+/// Both answers, sharing part 1's circuits.  The `n` closest pairs are the
+/// first `n` edges in Kruskal's order, so the circuits they form are pieces
+/// of the spanning tree; Prim's algorithm then joins the circuits rather
+/// than single points.  Nothing beyond the closest pairs and O(n) per-point
+/// state is held.
+pub fn both_parts(n: usize, m: usize, inputs: &[Point]) -> (Option<usize>, Option<usize>) {
     if inputs.len() < 2 {
-        return 0;
+        return (part1(n, m, inputs), part2(inputs));
+    }
+
+    let mut uf = UnionFind::new(inputs.len());
+    let mut longest = None;
+    for (i, j) in find_n_closest_pairs(inputs, n) {
+        if uf.find(i) != uf.find(j) {
+            uf.union(i, j);
+            longest = longest.max(Some((squared_distance(&inputs[i], &inputs[j]), i, j)));
+        }
+    }
+    let part1 = product_of_largest(count_component_sizes(&mut uf, inputs.len()), m);
+
+    let part2 = match longest.max(longest_tree_edge(inputs, &mut uf)) {
+        Some((_, i, j)) => x_product(inputs, i, j),
+        None => Some(0),
+    };
+    (part1, part2)
+}
+
+/// Kruskal's algorithm over every pair of points.  Needs O(n²) memory for the
+/// edge list; kept as a reference for [`part2`] and run with `--algo kruskal`.
+pub fn part2_kruskal(inputs: &[Point]) -> Option<usize> {
+    if inputs.len() < 2 {
+        return Some(0);
     }
 
     // Generate all edges with distances (parallelized)
@@ -184,8 +339,9 @@ fn part2(inputs: &[Point]) -> usize {
         })
         .collect();
 
-    // Sort edges by distance (Kruskal's algorithm) - parallel sort
-    edges.par_sort_unstable_by_key(|&(dist, _, _)| dist);
+    // Sort edges by distance (Kruskal's algorithm) - parallel sort.  Ties
+    // are broken by index so the last edge is deterministic.
+    edges.par_sort_unstable();
 
     // Use Union-Find to build MST
     let mut uf = UnionFind::new(inputs.len());
@@ -209,9 +365,9 @@ fn part2(inputs: &[Point]) -> usize {
 
     // Return product of x coordinates of the last edge
     if let Some((i, j)) = last_edge {
-        (inputs[i].x as usize) * (inputs[j].x as usize)
+        x_product(inputs, i, j)
     } else {
-        0
+        Some(0)
     }
 }
 
@@ -230,7 +386,7 @@ mod tests {
         // n=1: Connect the closest pair (2,2,2)-(2,3,2)
         // Components: [1], [2]
         // m=1: largest component has size 2
-        assert_eq!(part1(1, 1, &points), 2);
+        assert_eq!(part1(1, 1, &points), Some(2));
     }
 
     #[test]
@@ -239,7 +395,7 @@ mod tests {
         // n=1: Connect the closest pair (2,2,2)-(2,3,2)
         // Components: [1], [2]
         // m=2: product of two largest = 1 * 2 = 2
-        assert_eq!(part1(1, 2, &points), 2);
+        assert_eq!(part1(1, 2, &points), Some(2));
     }
 
     #[test]
@@ -268,7 +424,7 @@ mod tests {
         ];
         // n=3: Creates one component of size 3, one of size 2, and rest of size 1
         // m=1: largest component = 3
-        assert_eq!(part1(3, 1, &points), 3);
+        assert_eq!(part1(3, 1, &points), Some(3));
     }
 
     #[test]
@@ -297,27 +453,27 @@ mod tests {
         ];
         // n=3: Creates one component of size 3, one of size 2
         // m=2: product = 3 * 2 = 6
-        assert_eq!(part1(3, 2, &points), 6);
+        assert_eq!(part1(3, 2, &points), Some(6));
     }
 
     #[test]
     fn test_empty_input() {
         let points: Vec<Point> = vec![];
-        assert_eq!(part1(10, 3, &points), 1);
+        assert_eq!(part1(10, 3, &points), Some(1));
     }
 
     #[test]
     fn test_single_point() {
         let points = vec![point(5, 5, 5)];
         // Single point, one component of size 1
-        assert_eq!(part1(10, 1, &points), 1);
+        assert_eq!(part1(10, 1, &points), Some(1));
     }
 
     #[test]
     fn test_m_zero() {
         let points = vec![point(0, 0, 0), point(1, 1, 1)];
         // m=0 means empty product = 1
-        assert_eq!(part1(1, 0, &points), 1);
+        assert_eq!(part1(1, 0, &points), Some(1));
     }
 
     #[test]
@@ -325,7 +481,7 @@ mod tests {
         let points = vec![point(0, 0, 0), point(1, 1, 1), point(2, 2, 2)];
         // n=0: no connections, each point is its own component
         // m=2: multiply two largest = 1 * 1 = 1
-        assert_eq!(part1(0, 2, &points), 1);
+        assert_eq!(part1(0, 2, &points), Some(1));
     }
 
     #[test]
@@ -333,7 +489,7 @@ mod tests {
         let points = vec![point(0, 0, 0), point(1, 1, 1), point(2, 2, 2)];
         // n=10 exceeds total pairs, all points connected
         // One component of size 3
-        assert_eq!(part1(10, 1, &points), 3);
+        assert_eq!(part1(10, 1, &points), Some(3));
     }
 
     #[test]
@@ -341,7 +497,7 @@ mod tests {
         let points = vec![point(0, 0, 0), point(1, 1, 1)];
         // n=0: two components of size 1 each
         // m=5 exceeds available components, multiply all = 1 * 1 = 1
-        assert_eq!(part1(0, 5, &points), 1);
+        assert_eq!(part1(0, 5, &points), Some(1));
     }
 
     #[test]
@@ -354,7 +510,7 @@ mod tests {
         // n=1: closest pair is the two negative points
         // Components: [2], [1]
         // m=1: largest = 2
-        assert_eq!(part1(1, 1, &points), 2);
+        assert_eq!(part1(1, 1, &points), Some(2));
     }
 
     #[test]
@@ -363,7 +519,7 @@ mod tests {
         // n=1: closest pair is the two identical points (distance 0)
         // Components: [2], [1]
         // m=1: largest = 2
-        assert_eq!(part1(1, 1, &points), 2);
+        assert_eq!(part1(1, 1, &points), Some(2));
     }
 
     #[test]
@@ -376,7 +532,7 @@ mod tests {
         ];
         // n=3: connects (0,1), (1,2), (2,3) - all connected in a chain
         // One component of size 4
-        assert_eq!(part1(3, 1, &points), 4);
+        assert_eq!(part1(3, 1, &points), Some(4));
     }
 
     #[test]
@@ -390,7 +546,79 @@ mod tests {
         // n=2: connects (0,1) and (10,11)
         // Two components of size 2 each
         // m=2: product = 2 * 2 = 4
-        assert_eq!(part1(2, 2, &points), 4);
+        assert_eq!(part1(2, 2, &points), Some(4));
+    }
+
+    /// Deterministic pseudo-random points for comparing against brute force.
+    fn scattered_points(count: usize, seed: u64) -> Vec<Point> {
+        let mut state = seed;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % 1000) as i32
+        };
+        (0..count).map(|_| point(next(), next(), next())).collect()
+    }
+
+    #[test]
+    fn test_closest_pairs_match_brute_force() {
+        for (count, n) in [(2, 1), (50, 10), (300, 1000), (300, 50_000)] {
+            let points = scattered_points(count, count as u64 * 31 + n as u64);
+            let mut expected: Vec<(i64, usize, usize)> = (0..count)
+                .flat_map(|i| ((i + 1)..count).map(move |j| (i, j)))
+                .map(|(i, j)| (squared_distance(&points[i], &points[j]), i, j))
+                .collect();
+            expected.sort_unstable();
+            expected.truncate(n);
+            let expected: Vec<(usize, usize)> =
+                expected.into_iter().map(|(_, i, j)| (i, j)).collect();
+
+            let mut pairs = find_n_closest_pairs(&points, n);
+            pairs.sort_unstable_by_key(|&(i, j)| (squared_distance(&points[i], &points[j]), i, j));
+            assert_eq!(pairs, expected, "count={} n={}", count, n);
+        }
+    }
+
+    #[test]
+    fn test_closest_pairs_with_ties() {
+        // Every adjacent pair on the line is at distance 1; ties resolve by index.
+        let points: Vec<Point> = (0..20).map(|x| point(x, 0, 0)).collect();
+        let mut pairs = find_n_closest_pairs(&points, 5);
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
+    }
+
+    #[test]
+    fn test_closest_pairs_identical_on_any_pool() {
+        // Coordinates in 0..4 make most distances tie, so only the index
+        // tie-break decides the order; every pool size must give the same
+        // pairs in the same order, including when called from inside other
+        // parallel work.
+        let points: Vec<Point> = scattered_points(120, 5)
+            .into_iter()
+            .map(|p| point(p.x % 4, p.y % 4, p.z % 4))
+            .collect();
+        let sequential = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(|| find_n_closest_pairs(&points, 700));
+        for threads in [2, 3, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let nested: Vec<_> = pool.install(|| {
+                (0..4)
+                    .into_par_iter()
+                    .map(|_| find_n_closest_pairs(&points, 700))
+                    .collect()
+            });
+            for pairs in nested {
+                assert_eq!(pairs, sequential, "threads={}", threads);
+            }
+        }
     }
 
     #[test]
@@ -404,16 +632,23 @@ mod tests {
 
     #[test]
     fn test_product_of_largest_basic() {
-        assert_eq!(product_of_largest(vec![5, 3, 8, 1], 2), 8 * 5);
-        assert_eq!(product_of_largest(vec![5, 3, 8, 1], 1), 8);
-        assert_eq!(product_of_largest(vec![5, 3, 8, 1], 4), 5 * 3 * 8 * 1);
+        assert_eq!(product_of_largest(vec![5, 3, 8, 1], 2), Some(8 * 5));
+        assert_eq!(product_of_largest(vec![5, 3, 8, 1], 1), Some(8));
+        assert_eq!(product_of_largest(vec![5, 3, 8, 1], 4), Some(5 * 3 * 8 * 1));
     }
 
     #[test]
     fn test_product_of_largest_edge_cases() {
-        assert_eq!(product_of_largest(vec![], 3), 1);
-        assert_eq!(product_of_largest(vec![5], 0), 1);
-        assert_eq!(product_of_largest(vec![5, 3], 5), 5 * 3);
+        assert_eq!(product_of_largest(vec![], 3), Some(1));
+        assert_eq!(product_of_largest(vec![5], 0), Some(1));
+        assert_eq!(product_of_largest(vec![5, 3], 5), Some(5 * 3));
+    }
+
+    #[test]
+    fn test_product_of_largest_overflow() {
+        let sizes = vec![1 << 32, 1 << 32, 1];
+        assert_eq!(product_of_largest(sizes.clone(), 1), Some(1 << 32));
+        assert_eq!(product_of_largest(sizes, 2), None);
     }
 
     // Part 2 tests
@@ -424,7 +659,7 @@ mod tests {
         // First edge: (2,3,4)-(3,5,6) - distance 9
         // Second edge: (1,1,1)-(2,3,4) - distance 14 (this is the final edge)
         // Product: 1 * 2 = 2
-        assert_eq!(part2(&points), 2);
+        assert_eq!(part2(&points), Some(2));
     }
 
     #[test]
@@ -452,19 +687,19 @@ mod tests {
         ];
         // Final edge: (216,146,977)-(117,168,530)
         // Product: 216 * 117 = 25272
-        assert_eq!(part2(&points), 25272);
+        assert_eq!(part2(&points), Some(25272));
     }
 
     #[test]
     fn test_part2_empty_input() {
         let points: Vec<Point> = vec![];
-        assert_eq!(part2(&points), 0);
+        assert_eq!(part2(&points), Some(0));
     }
 
     #[test]
     fn test_part2_single_point() {
         let points = vec![point(5, 5, 5)];
-        assert_eq!(part2(&points), 0);
+        assert_eq!(part2(&points), Some(0));
     }
 
     #[test]
@@ -472,7 +707,7 @@ mod tests {
         let points = vec![point(3, 1, 1), point(7, 2, 2)];
         // Only one edge: (3,1,1)-(7,2,2)
         // Product: 3 * 7 = 21
-        assert_eq!(part2(&points), 21);
+        assert_eq!(part2(&points), Some(21));
     }
 
     #[test]
@@ -486,7 +721,7 @@ mod tests {
         // Edges in order: (1,2), (2,3), (3,10)
         // Last edge connects (3,0,0) to (10,0,0)
         // Product: 3 * 10 = 30
-        assert_eq!(part2(&points), 30);
+        assert_eq!(part2(&points), Some(30));
     }
 
     #[test]
@@ -496,7 +731,7 @@ mod tests {
         // (5,3): distance 4, (3,10): distance 49, (5,10): distance 25
         // MST: (5,3) first, then (5,10)
         // Last edge: (5,10), product: 5 * 10 = 50
-        assert_eq!(part2(&points), 50);
+        assert_eq!(part2(&points), Some(50));
     }
 
     #[test]
@@ -511,7 +746,60 @@ mod tests {
         // Edges of distance 1: (0,1), (0,2), (1,3), (2,3)
         // MST would pick 3 edges, last one depends on ordering
         let result = part2(&points);
-        assert!(result > 0);
+        assert!(result.unwrap() > 0);
+    }
+
+    #[test]
+    fn test_part2_matches_kruskal() {
+        for (count, seed) in [(2, 1), (3, 2), (40, 3), (500, 4), (5000, 5)] {
+            let points = scattered_points(count, seed);
+            assert_eq!(
+                part2(&points),
+                part2_kruskal(&points),
+                "count={} seed={}",
+                count,
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn test_both_parts_match_separate_parts() {
+        for (count, seed) in [(0, 1), (1, 1), (2, 1), (3, 2), (40, 3), (500, 4)] {
+            let points = scattered_points(count, seed);
+            for (n, m) in [(0, 3), (1, 1), (10, 3), (1000, 3), (200_000, 2)] {
+                assert_eq!(
+                    both_parts(n, m, &points),
+                    (part1(n, m, &points), part2(&points)),
+                    "count={} seed={} n={} m={}",
+                    count,
+                    seed,
+                    n,
+                    m
+                );
+            }
+        }
+
+        // A lattice ties many distances, which must break the same way
+        let lattice: Vec<Point> = (0..64)
+            .map(|idx| point(idx % 4 * 3, idx / 4 % 4 * 3, idx / 16 * 5))
+            .collect();
+        for n in [0, 5, 40, 100, 3000] {
+            assert_eq!(
+                both_parts(n, 3, &lattice),
+                (part1(n, 3, &lattice), part2(&lattice)),
+                "n={}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_part2_duplicate_points() {
+        let points = vec![point(4, 0, 0), point(4, 0, 0), point(9, 0, 0)];
+        // The duplicates join at distance 0; the last edge reaches (9,0,0).
+        assert_eq!(part2(&points), Some(36));
+        assert_eq!(part2_kruskal(&points), Some(36));
     }
 
     #[test]
@@ -525,6 +813,14 @@ mod tests {
         // Connect adjacent points
         // Last edge should be (15,0,0)-(20,0,0)
         // Product: 15 * 20 = 300
-        assert_eq!(part2(&points), 300);
+        assert_eq!(part2(&points), Some(300));
+    }
+
+    #[test]
+    fn test_part2_negative_x() {
+        // The last edge reaches (-20,0,0); its x product has no usize value
+        let points = vec![point(5, 0, 0), point(10, 0, 0), point(-20, 0, 0)];
+        assert_eq!(part2(&points), None);
+        assert_eq!(part2_kruskal(&points), None);
     }
 }
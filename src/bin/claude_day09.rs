@@ -1,96 +1,67 @@
-use rust_advent::Point2d;
+use rust_advent::geo::{GridRect, RectilinearPolygon, max_area_rect_over_pairs};
+use rust_advent::viz::svg::{Drawing, draw_points_with_hull, draw_polygon};
+use rust_advent::viz::{Color, VizOptions};
+use rust_advent::{Point2d, convex_hull, rect_area_inclusive};
 
 fn main() -> std::io::Result<()> {
+    let cross_check = std::env::args().any(|arg| arg == "--cross-check");
+    let options = VizOptions::from_args(
+        std::env::args()
+            .skip(1)
+            .filter(|arg| arg != "--cross-check"),
+    )
+    .map_err(std::io::Error::other)?;
     let inputs = rust_advent::read_points2d("09")?;
     println!("Part 1: {}", part1(&inputs));
-    println!("Part 2: {}", part2(&inputs));
-    Ok(())
-}
-
-/// Andrew's monotone chain convex hull algorithm.
-/// Returns the convex hull points in counter-clockwise order.
-/// Time complexity: O(n log n)
-fn convex_hull(points: &[Point2d]) -> Vec<Point2d> {
-    if points.len() < 3 {
-        return points.to_vec();
+    let area = part2(&inputs);
+    println!("Part 2: {}", area);
+    println!("Part 2b: {}", part2b(&inputs));
+    if cross_check {
+        let checked = largest_inside_rectangle_by_edges(&inputs)
+            .map_or(0, |(p, q)| rect_area_inclusive(p, q) as usize);
+        if checked != area {
+            return Err(std::io::Error::other(format!(
+                "Part 2 mismatch: compressed grid gives {}, edge tests give {}",
+                area, checked
+            )));
+        }
+        eprintln!("Part 2 cross-check: edge tests agree");
     }
-
-    // Sort points lexicographically (first by x, then by y)
-    let mut sorted = points.to_vec();
-    sorted.sort_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
-
-    // Remove duplicates
-    sorted.dedup();
-
-    if sorted.len() < 3 {
-        return sorted;
+    if let Some(path) = options.dump_svg {
+        draw_state(&inputs).save(&path)?;
+        eprintln!("Wrote {}", path.display());
     }
+    Ok(())
+}
 
-    // Cross product to determine turn direction
-    // Positive = counter-clockwise, Negative = clockwise, Zero = collinear
-    let cross = |o: &Point2d, a: &Point2d, b: &Point2d| -> i64 {
-        (a.x as i64 - o.x as i64) * (b.y as i64 - o.y as i64)
-            - (a.y as i64 - o.y as i64) * (b.x as i64 - o.x as i64)
-    };
-
-    // Build lower hull
-    let mut lower = Vec::new();
-    for p in &sorted {
-        while lower.len() >= 2 && cross(&lower[lower.len() - 2], &lower[lower.len() - 1], p) <= 0 {
-            lower.pop();
-        }
-        lower.push(*p);
+/// The input polygon over its convex hull, with the rectangle behind each
+/// answer: part 1's in yellow, part 2's in magenta.
+fn draw_state(inputs: &[Point2d]) -> Drawing {
+    let mut drawing =
+        draw_points_with_hull(inputs, &convex_hull(inputs)).append(draw_polygon(inputs));
+    if let Some((p, q)) = largest_hull_rectangle(inputs) {
+        drawing = drawing.rect(p, q, Color::Yellow);
     }
-
-    // Build upper hull
-    let mut upper = Vec::new();
-    for p in sorted.iter().rev() {
-        while upper.len() >= 2 && cross(&upper[upper.len() - 2], &upper[upper.len() - 1], p) <= 0 {
-            upper.pop();
-        }
-        upper.push(*p);
+    if let Some((p, q)) = largest_inside_rectangle(inputs) {
+        drawing = drawing.rect(p, q, Color::Magenta);
     }
-
-    // Remove last point of each half because it's repeated
-    lower.pop();
-    upper.pop();
-
-    // Concatenate lower and upper hull
-    lower.extend(upper);
-    lower
+    drawing
 }
 
 /// Finds the maximum area of an axis-aligned rectangle formed by any two points.
 /// Uses inclusive grid counting: area = (|x2 - x1| + 1) * (|y2 - y1| + 1)
 ///
-/// Optimization: Only checks pairs of points on the convex hull, since the
-/// optimal rectangle must have both corners on the hull.
-/// Time complexity: O(n log n + h²) where h is the hull size
-fn part1(inputs: &[Point2d]) -> usize {
-    if inputs.len() < 2 {
-        return 0;
-    }
-
-    // Compute convex hull: O(n log n)
-    let hull = convex_hull(inputs);
-
-    if hull.len() < 2 {
-        return 0;
-    }
-
-    // Check all pairs on hull: O(h²) where h << n typically
-    let mut max_area: i64 = 0;
-
-    for i in 0..hull.len() {
-        for j in (i + 1)..hull.len() {
-            let width = (hull[i].x - hull[j].x).abs() as i64 + 1;
-            let height = (hull[i].y - hull[j].y).abs() as i64 + 1;
-            let area = width * height;
-            max_area = max_area.max(area);
-        }
-    }
+/// Optimization: Only the convex hull can hold the optimal corners, and
+/// along the hull's staircases the best partner of each corner moves
+/// monotonically, see [`max_area_rect_over_pairs`].
+/// Time complexity: O(n log n)
+pub fn part1(inputs: &[Point2d]) -> usize {
+    largest_hull_rectangle(inputs).map_or(0, |(p, q)| rect_area_inclusive(p, q) as usize)
+}
 
-    max_area as usize
+/// The opposite corners of the rectangle behind [`part1`].
+fn largest_hull_rectangle(inputs: &[Point2d]) -> Option<(Point2d, Point2d)> {
+    max_area_rect_over_pairs(&convex_hull(inputs))
 }
 
 /// Checks if a point is on a line segment (for rectilinear edges only).
@@ -189,21 +160,13 @@ fn build_edges(points: &[Point2d]) -> Vec<Edge> {
 /// Checks if an entire rectangle is inside the polygon.
 /// Uses explicit edge-crossing detection to avoid blind spots from sampling.
 fn rectangle_in_polygon(p1: Point2d, p2: Point2d, polygon: &[Point2d]) -> bool {
-    let min_x = p1.x.min(p2.x);
-    let max_x = p1.x.max(p2.x);
-    let min_y = p1.y.min(p2.y);
-    let max_y = p1.y.max(p2.y);
+    let rect = GridRect::from_corners(p1, p2);
+    let (min, max) = (rect.min(), rect.max());
+    let (min_x, min_y, max_x, max_y) = (min.x, min.y, max.x, max.y);
 
     // Check all four corners
-    let corners = [
-        Point2d { x: min_x, y: min_y },
-        Point2d { x: min_x, y: max_y },
-        Point2d { x: max_x, y: min_y },
-        Point2d { x: max_x, y: max_y },
-    ];
-
-    for corner in &corners {
-        if !point_in_or_on_polygon(*corner, polygon) {
+    for corner in rect.corners() {
+        if !point_in_or_on_polygon(corner, polygon) {
             return false;
         }
     }
@@ -261,12 +224,36 @@ fn rectangle_in_polygon(p1: Point2d, p2: Point2d, polygon: &[Point2d]) -> bool {
 
 /// Finds the maximum area rectangle that fits entirely within a rectilinear polygon.
 /// The polygon is formed by connecting consecutive points with horizontal/vertical lines.
-fn part2(inputs: &[Point2d]) -> usize {
+///
+/// Time complexity: O(n²), checking each pair against prefix sums over the
+/// compressed polygon, see [`RectilinearPolygon::largest_vertex_rectangle`].
+pub fn part2(inputs: &[Point2d]) -> usize {
+    largest_inside_rectangle(inputs).map_or(0, |(p, q)| rect_area_inclusive(p, q) as usize)
+}
+
+/// The opposite corners of the rectangle behind [`part2`].
+fn largest_inside_rectangle(inputs: &[Point2d]) -> Option<(Point2d, Point2d)> {
+    RectilinearPolygon::new(inputs.to_vec()).largest_vertex_rectangle()
+}
+
+/// Like [`part2`], but the rectangle's corners may fall anywhere, not just on
+/// red tiles: the largest rectangle the polygon covers at all, for seeing
+/// how much the puzzle's corner rule gives up.
+pub fn part2b(inputs: &[Point2d]) -> usize {
+    RectilinearPolygon::new(inputs.to_vec())
+        .largest_inscribed_rectangle()
+        .map_or(0, |(p, q)| rect_area_inclusive(p, q) as usize)
+}
+
+/// [`largest_inside_rectangle`] by testing every pair against every edge,
+/// O(n³), for `--cross-check`.
+fn largest_inside_rectangle_by_edges(inputs: &[Point2d]) -> Option<(Point2d, Point2d)> {
     if inputs.len() < 3 {
-        return 0;
+        return None;
     }
 
-    let mut max_area: i64 = 0;
+    let mut best = None;
+    let mut max_area: u64 = 0;
 
     // Try all pairs of input points as opposite corners
     for i in 0..inputs.len() {
@@ -276,20 +263,64 @@ fn part2(inputs: &[Point2d]) -> usize {
 
             // Check if rectangle is entirely within polygon
             if rectangle_in_polygon(p1, p2, inputs) {
-                let width = (p1.x - p2.x).abs() as i64 + 1;
-                let height = (p1.y - p2.y).abs() as i64 + 1;
-                let area = width * height;
-                max_area = max_area.max(area);
+                let area = rect_area_inclusive(p1, p2);
+                if area > max_area {
+                    max_area = area;
+                    best = Some((p1, p2));
+                }
             }
         }
     }
 
-    max_area as usize
+    best
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use rust_advent::generators::{Lcg, random_histogram_polygon};
+
+    fn small_points() -> impl Strategy<Value = Vec<Point2d>> {
+        prop::collection::vec(
+            (-50..50, -50..50).prop_map(|(x, y)| Point2d { x, y }),
+            0..60,
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn prop_convex_hull_encloses_input(points in small_points()) {
+            let hull = convex_hull(&points);
+            prop_assert!(hull.iter().all(|p| points.contains(p)));
+
+            // The lexicographic extremes on both axes are always corners
+            let by_xy = |p: &&Point2d| (p.x, p.y);
+            let by_yx = |p: &&Point2d| (p.y, p.x);
+            for extreme in [
+                points.iter().min_by_key(by_xy),
+                points.iter().max_by_key(by_xy),
+                points.iter().min_by_key(by_yx),
+                points.iter().max_by_key(by_yx),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                prop_assert!(hull.contains(extreme));
+            }
+
+            // Counter-clockwise, so every point is left of or on every edge
+            if hull.len() >= 3 {
+                for (i, &a) in hull.iter().enumerate() {
+                    let b = hull[(i + 1) % hull.len()];
+                    for &p in &points {
+                        let cross = b.dx_i64(a) * p.dy_i64(a) - b.dy_i64(a) * p.dx_i64(a);
+                        prop_assert!(cross >= 0, "{:?} is outside edge {:?} -> {:?}", p, a, b);
+                    }
+                }
+            }
+        }
+    }
 
     #[test]
     fn test_convex_hull_triangle() {
@@ -451,6 +482,39 @@ mod tests {
         ];
         // Largest rectangle is from (2,3) to (9,5) with area 8 * 3 = 24
         assert_eq!(part2(&points), 24);
+        assert_eq!(
+            largest_inside_rectangle_by_edges(&points),
+            largest_inside_rectangle(&points)
+        );
+    }
+
+    #[test]
+    fn test_part2b_corners_off_vertices() {
+        // Two boxes joined by a corridor: the corridor's full length has no
+        // red tiles at its corners
+        let points: Vec<Point2d> = [
+            (0, 0),
+            (3, 0),
+            (3, 1),
+            (6, 1),
+            (6, 0),
+            (9, 0),
+            (9, 3),
+            (6, 3),
+            (6, 2),
+            (3, 2),
+            (3, 3),
+            (0, 3),
+        ]
+        .into_iter()
+        .map(|(x, y)| Point2d { x, y })
+        .collect();
+        assert_eq!(part2(&points), 16);
+        assert_eq!(part2b(&points), 20);
+
+        let square = [(0, 0), (10, 0), (10, 10), (0, 10)].map(|(x, y)| Point2d { x, y });
+        assert_eq!(part2b(&square), part2(&square));
+        assert_eq!(part2b(&square[..2]), 0);
     }
 
     #[test]
@@ -600,6 +664,17 @@ mod tests {
         assert!(result > 0);
     }
 
+    #[test]
+    fn test_part1_extreme_coordinates() {
+        // Opposite corners are further apart than i32 can hold
+        let points = vec![
+            Point2d { x: i32::MIN, y: 0 },
+            Point2d { x: i32::MAX, y: 1 },
+            Point2d { x: 0, y: 2 },
+        ];
+        assert_eq!(part1(&points), 1 << 33);
+    }
+
     #[test]
     fn test_part2_rectangle_corners_outside() {
         // Create a cross-shaped polygon where some rectangle corners would be outside
@@ -718,4 +793,46 @@ mod tests {
         let result = part2(&points);
         assert_eq!(result, 361);
     }
+
+    #[test]
+    fn test_part2_matches_edge_tests() {
+        // The compressed grid against --cross-check, zero-width notches included
+        let mut rng = Lcg::new(26);
+        for columns in 1..30 {
+            let points = random_histogram_polygon(&mut rng, columns);
+            let checked = largest_inside_rectangle_by_edges(&points)
+                .map_or(0, |(p, q)| rect_area_inclusive(p, q) as usize);
+            assert_eq!(part2(&points), checked, "{:?}", points);
+        }
+    }
+
+    #[test]
+    fn test_drawn_rectangles_match_answers() {
+        let points: Vec<Point2d> = [
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]
+        .into_iter()
+        .map(|(x, y)| Point2d { x, y })
+        .collect();
+        let (p, q) = largest_hull_rectangle(&points).unwrap();
+        assert_eq!(rect_area_inclusive(p, q) as usize, part1(&points));
+        let (p, q) = largest_inside_rectangle(&points).unwrap();
+        assert_eq!((p, q), (Point2d { x: 9, y: 5 }, Point2d { x: 2, y: 3 }));
+
+        let svg = draw_state(&points).to_svg();
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(
+            svg.contains(r#"<rect x="2" y="3" width="7" height="2""#),
+            "{}",
+            svg
+        );
+        assert_eq!(largest_inside_rectangle(&points[..2]), None);
+    }
 }
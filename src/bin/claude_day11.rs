@@ -1,68 +1,91 @@
-use std::collections::{HashMap, HashSet};
-
-fn main() -> std::io::Result<()> {
-    let inputs = rust_advent::read_file_as_lines("11")?;
-    println!("Part 1: {}", part1("you", "out", &inputs));
-    println!("Part 2: {}", part2("svr", "out", &["dac", "fft"], &inputs));
+use rust_advent::graph::{BitsetAdjacency, DiGraph};
+use rust_advent::paths::{CountError, Cycles, Overflow, PathCount, count_paths_memo};
+use rust_advent::pipeline;
+use rust_advent::viz::VizOptions;
+use rust_advent::{PuzzleInput, split_trimmed};
+use std::collections::HashMap;
+
+/// Accumulator for the answers: unbounded with the `bigint` feature.
+#[cfg(feature = "bigint")]
+type Count = num_bigint::BigUint;
+#[cfg(not(feature = "bigint"))]
+type Count = u128;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = VizOptions::from_args(std::env::args().skip(1))?;
+    let input = PuzzleInput::read("11")?;
+    let lines: Vec<&str> = input.lines().collect();
+    println!("Part 1: {}", part1::<Count, _>("you", "out", &lines)?);
+    println!(
+        "Part 2: {}",
+        part2::<Count, _, _>("svr", "out", &["dac", "fft"], &lines)?
+    );
+    if let Some(path) = options.dump_dot {
+        std::fs::write(&path, graph_dot(&lines, "svr", "out", &["dac", "fft"])?)?;
+        eprintln!("Wrote {}", path.display());
+    }
     Ok(())
 }
 
+/// The graph in DOT form with the required vertices filled in, and one of
+/// the paths part 2 counts drawn in red if there is one.
+fn graph_dot<S: AsRef<str>>(
+    input: &[S],
+    start: &str,
+    target: &str,
+    required: &[&str],
+) -> Result<String, String> {
+    let digraph = to_digraph(&parse_graph(input)?);
+    let required: Vec<usize> = required
+        .iter()
+        .filter_map(|vertex| digraph.id(vertex))
+        .collect();
+    let path = match (digraph.id(start), digraph.id(target)) {
+        (Some(start), Some(target)) => digraph.find_path(start, target, &required),
+        _ => None,
+    };
+    Ok(digraph.to_dot(&required, path.as_deref()))
+}
+
+/// Adjacency list keyed by vertex name, borrowing names from the input
+type Graph<'a> = HashMap<&'a str, Vec<&'a str>>;
+
 /// Parse input lines into a graph represented as an adjacency list
 /// Format: "source: targ1 targ2 targ3"
 /// Returns an error if any line is malformed
 ///
-/// Generic over S: AsRef<str> to accept &[String], &[&str], or any string-like slice
-fn parse_graph<S: AsRef<str>>(input: &[S]) -> Result<HashMap<String, Vec<String>>, String> {
+/// Generic over S: AsRef<str> to accept &[String], &[&str], or any string-like slice.
+/// Vertex names are borrowed from the input rather than copied.
+fn parse_graph<S: AsRef<str>>(input: &[S]) -> Result<Graph<'_>, String> {
     let mut graph = HashMap::new();
 
-    for (line_num, line) in input.iter().enumerate() {
-        let line = line.as_ref().trim();
-        if line.is_empty() {
-            continue;
-        }
-
-        // Split by ':' to separate source from targets (avoid collecting to Vec)
-        let mut parts = line.split(':');
-        let source = parts.next().ok_or_else(|| {
+    let lines = pipeline::lines(input.iter().map(AsRef::as_ref))
+        .trimmed()
+        .non_empty();
+    for (line_num, line) in lines.numbered() {
+        // Split by ':' to separate source from targets
+        let (source, targets_str) = split_trimmed(line, ':').ok_or_else(|| {
             format!(
                 "Line {}: Expected format 'source: target1 target2...', got '{}'",
-                line_num + 1,
-                line
-            )
-        })?;
-        let targets_str = parts.next().ok_or_else(|| {
-            format!(
-                "Line {}: Expected format 'source: target1 target2...', got '{}'",
-                line_num + 1,
-                line
+                line_num, line
             )
         })?;
 
         // Ensure no extra colons
-        if parts.next().is_some() {
+        if targets_str.contains(':') {
             return Err(format!(
                 "Line {}: Too many ':' separators in '{}'",
-                line_num + 1,
-                line
+                line_num, line
             ));
         }
 
-        let source = source.trim();
         if source.is_empty() {
-            return Err(format!(
-                "Line {}: Source vertex cannot be empty",
-                line_num + 1
-            ));
+            return Err(format!("Line {}: Source vertex cannot be empty", line_num));
         }
 
-        let targets: Vec<String> = targets_str
-            .split_whitespace()
-            .map(|s| s.to_string())
-            .collect();
-
         // Empty target list is valid - represents a vertex with no outgoing edges
         // (e.g., a dead-end that isn't the target)
-        graph.insert(source.to_string(), targets);
+        graph.insert(source, targets_str.split_whitespace().collect());
     }
 
     Ok(graph)
@@ -71,222 +94,191 @@ fn parse_graph<S: AsRef<str>>(input: &[S]) -> Result<HashMap<String, Vec<String>
 /// Count all distinct paths from start vertex to target vertex
 ///
 /// Uses DFS with memoization for O(V + E) time complexity.
-/// Handles cycles correctly by tracking vertices on the current call stack.
-fn count_paths(start: &str, target: &str, graph: &HashMap<String, Vec<String>>) -> u32 {
-    let mut memo = HashMap::new();
-    let mut visiting = HashSet::new();
-    count_paths_impl(start, target, graph, &mut memo, &mut visiting)
+/// Edges back onto the current path are skipped (see [`Cycles::Skip`]), so
+/// a cycle adds no paths of its own.
+/// Counts in `T`, failing rather than wrapping if the count does not fit.
+fn count_paths<'a, T: PathCount>(
+    start: &'a str,
+    target: &str,
+    graph: &Graph<'a>,
+) -> Result<T, Overflow> {
+    skipping_cycles(count_paths_memo(
+        start,
+        Cycles::Skip,
+        |&vertex| vertex,
+        |vertex| graph.get(vertex).into_iter().flatten().copied(),
+        |&vertex| vertex == target,
+    ))
 }
 
-/// Internal implementation of path counting with DFS and memoization
-///
-/// Why we need BOTH memo and visiting as parameters (not internal variables):
-/// - `memo`: Must persist across ALL recursive calls to cache results (shared state)
-/// - `visiting`: Must track the CURRENT call stack to detect cycles (shared state)
+/// Count paths from start to target that pass through every required
+/// vertex, in any order
 ///
-/// If these were local variables, each recursive call would get fresh empty collections,
-/// breaking both memoization and cycle detection. They're parameters to share state
-/// across the entire recursion tree while keeping them out of the public API.
-///
-/// The visiting set tracks vertices on the current call stack. When we encounter
-/// a vertex already being visited, we've found a cycle and return 0 (no valid paths
-/// through this cycle). Once we finish processing a vertex, we cache its result in
-/// memo and can safely reuse it from other paths without the cycle restriction.
-fn count_paths_impl(
-    current: &str,
+/// The search state is the vertex plus a bitmask of the required vertices
+/// passed so far (including the vertex itself).  Cycles are skipped by
+/// vertex, as in [`count_paths`], whatever the mask.
+fn count_paths_through<'a, T: PathCount, R: AsRef<str>>(
+    start: &'a str,
     target: &str,
-    graph: &HashMap<String, Vec<String>>,
-    memo: &mut HashMap<String, u32>,
-    visiting: &mut HashSet<String>,
-) -> u32 {
-    // Base case: reached the target
-    if current == target {
-        return 1;
-    }
+    required: &[R],
+    graph: &Graph<'a>,
+) -> Result<T, Overflow> {
+    // Bit index of each required vertex; a repeated one keeps only its last
+    // index, so the full mask can never be reached.
+    let required_bits: HashMap<&str, u64> = required
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.as_ref(), 1u64 << i))
+        .collect();
+    let bit = |vertex: &str| required_bits.get(vertex).copied().unwrap_or(0);
+    let all_required_mask = if required.is_empty() {
+        0u64
+    } else {
+        u64::MAX >> (64 - required.len())
+    };
 
-    // Check memo cache (already computed from a previous path)
-    if let Some(&count) = memo.get(current) {
-        return count;
-    }
+    skipping_cycles(count_paths_memo(
+        (start, bit(start)),
+        Cycles::Skip,
+        |&(vertex, _)| vertex,
+        |&(vertex, mask)| {
+            // Paths end at the target whether or not they are complete
+            let neighbors = match graph.get(vertex) {
+                Some(n) if vertex != target => n.as_slice(),
+                _ => &[],
+            };
+            neighbors.iter().map(move |&next| (next, mask | bit(next)))
+        },
+        |&(vertex, mask)| vertex == target && mask == all_required_mask,
+    ))
+}
 
-    // Detect cycle: if currently on the call stack, return 0 to break the cycle
-    if visiting.contains(current) {
-        return 0;
-    }
+/// The count from a search under [`Cycles::Skip`], which never fails on a
+/// cycle.
+fn skipping_cycles<T, K>(result: Result<T, CountError<K>>) -> Result<T, Overflow> {
+    result.map_err(|err| match err {
+        CountError::Overflow(overflow) => overflow,
+        CountError::Cycle(_) => unreachable!("cycles are skipped"),
+    })
+}
 
-    // Get neighbors, handle missing vertex or dead-end
-    let neighbors = match graph.get(current) {
-        Some(n) if !n.is_empty() => n,
-        _ => {
-            // No outgoing edges: cache and return 0
-            // Avoid allocation: use entry API
-            memo.entry(current.to_string()).or_insert(0);
-            return 0;
+/// The adjacency list as a [`DiGraph`].
+fn to_digraph(graph: &Graph<'_>) -> DiGraph {
+    let mut digraph = DiGraph::new();
+    for (source, targets) in graph {
+        digraph.intern(source);
+        for target in targets {
+            digraph.add_edge(source, target);
         }
-    };
-
-    // Mark as visiting (on the call stack) - unavoidable allocation
-    visiting.insert(current.to_string());
-
-    // Sum paths from all neighbors
-    let mut total = 0;
-    for neighbor in neighbors {
-        total += count_paths_impl(neighbor, target, graph, memo, visiting);
     }
+    digraph
+}
 
-    // Unmark as visiting (remove from call stack)
-    visiting.remove(current);
+/// Most required vertices the bitset count tracks; it keeps one count per
+/// subset of them at every vertex.
+const DENSE_MAX_REQUIRED: usize = 16;
 
-    // Cache result for future lookups - unavoidable allocation
-    memo.entry(current.to_string()).or_insert(total);
-    total
+/// Counts paths on u64 adjacency rows instead of string-keyed maps.  Only
+/// applies to graphs of at most 64 vertices with no cycle between start
+/// and target; `None` means fall back to the DFS.
+fn count_paths_dense<T: PathCount, R: AsRef<str>>(
+    graph: &Graph<'_>,
+    start: &str,
+    target: &str,
+    required: &[R],
+) -> Result<Option<T>, Overflow> {
+    if graph.len() > BitsetAdjacency::MAX_NODES || required.len() > DENSE_MAX_REQUIRED {
+        return Ok(None);
+    }
+    let digraph = to_digraph(graph);
+    let Some(adjacency) = digraph.to_bitset_adjacency() else {
+        return Ok(None);
+    };
+    let (Some(start), Some(target)) = (digraph.id(start), digraph.id(target)) else {
+        return Ok(None);
+    };
+
+    let mut required_mask = 0u64;
+    for vertex in required {
+        match digraph.id(vertex.as_ref()) {
+            Some(id) => required_mask |= 1 << id,
+            None => return Ok(Some(T::zero())),
+        }
+    }
+    // A repeated required vertex can never be satisfied by the DFS's
+    // per-index mask; leave that case to it.
+    if required_mask.count_ones() as usize != required.len() {
+        return Ok(None);
+    }
+    adjacency.count_paths_through(start, target, required_mask)
 }
 
 /// Part 1: Count distinct paths from start_vertex to target_vertex
-fn part1<S: AsRef<str>>(start_vertex: &str, target_vertex: &str, input: &[S]) -> u32 {
+pub fn part1<T: PathCount, S: AsRef<str>>(
+    start_vertex: &str,
+    target_vertex: &str,
+    input: &[S],
+) -> Result<T, Overflow> {
     let graph = match parse_graph(input) {
         Ok(g) => g,
         Err(e) => {
             eprintln!("Error parsing graph: {}", e);
-            return 0;
+            return Ok(T::zero());
         }
     };
 
     // Edge case: start equals target
     if start_vertex == target_vertex {
-        return 1;
+        return Ok(T::one());
     }
 
     // Edge case: start vertex not in graph
     if !graph.contains_key(start_vertex) {
-        return 0;
-    }
-
-    count_paths(start_vertex, target_vertex, &graph)
-}
-
-/// Helper struct to manage state for path counting with required vertices
-/// Groups related parameters to reduce function argument count
-struct PathCounter<'a> {
-    graph: &'a HashMap<String, Vec<String>>,
-    target: &'a str,
-    required_map: &'a HashMap<String, usize>,
-    all_required_mask: u64,
-    memo: HashMap<(String, u64), u64>,
-    visiting: HashSet<String>,
-}
-
-impl<'a> PathCounter<'a> {
-    fn new(
-        graph: &'a HashMap<String, Vec<String>>,
-        target: &'a str,
-        required_map: &'a HashMap<String, usize>,
-        all_required_mask: u64,
-    ) -> Self {
-        Self {
-            graph,
-            target,
-            required_map,
-            all_required_mask,
-            memo: HashMap::new(),
-            visiting: HashSet::new(),
-        }
+        return Ok(T::zero());
     }
 
-    /// Count paths from current vertex to target with required vertices constraint
-    fn count_paths(&mut self, current: &str, visited_required_mask: u64) -> u64 {
-        // Update visited mask if current is a required vertex
-        let current_mask = if let Some(&idx) = self.required_map.get(current) {
-            visited_required_mask | (1u64 << idx)
-        } else {
-            visited_required_mask
-        };
-
-        // Base case: reached target
-        if current == self.target {
-            // Only count if all required vertices were visited
-            return if current_mask == self.all_required_mask {
-                1u64
-            } else {
-                0u64
-            };
-        }
-
-        // Check memo cache
-        let state = (current.to_string(), current_mask);
-        if let Some(&count) = self.memo.get(&state) {
-            return count;
-        }
-
-        // Cycle detection
-        if self.visiting.contains(current) {
-            return 0u64;
-        }
-
-        // Get neighbors
-        let neighbors = match self.graph.get(current) {
-            Some(n) if !n.is_empty() => n,
-            _ => {
-                self.memo.insert(state, 0u64);
-                return 0u64;
-            }
-        };
-
-        self.visiting.insert(current.to_string());
-
-        let mut total = 0u64;
-        for neighbor in neighbors {
-            total += self.count_paths(neighbor, current_mask);
-        }
-
-        self.visiting.remove(current);
-        self.memo.insert(state, total);
-        total
+    if let Some(count) = count_paths_dense(&graph, start_vertex, target_vertex, &[] as &[&str])? {
+        return Ok(count);
     }
+    count_paths(start_vertex, target_vertex, &graph)
 }
 
 /// Part 2: Count paths that pass through all required vertices (in any order)
-fn part2<S: AsRef<str>, R: AsRef<str>>(
+pub fn part2<T: PathCount, S: AsRef<str>, R: AsRef<str>>(
     start_vertex: &str,
     target_vertex: &str,
     required_vertices: &[R],
     input: &[S],
-) -> u64 {
+) -> Result<T, Overflow> {
     let graph = match parse_graph(input) {
         Ok(g) => g,
         Err(e) => {
             eprintln!("Error parsing graph: {}", e);
-            return 0;
+            return Ok(T::zero());
         }
     };
 
     // Edge case: start equals target
     if start_vertex == target_vertex {
         // Only valid if no required vertices (or all are start/target)
-        return if required_vertices.is_empty() { 1 } else { 0 };
+        return Ok(if required_vertices.is_empty() {
+            T::one()
+        } else {
+            T::zero()
+        });
     }
 
     // Edge case: start vertex not in graph
     if !graph.contains_key(start_vertex) {
-        return 0;
+        return Ok(T::zero());
     }
 
-    // Create mapping of required vertices to bit indices (for bitmask)
-    let required_map: HashMap<String, usize> = required_vertices
-        .iter()
-        .enumerate()
-        .map(|(i, v)| (v.as_ref().to_string(), i))
-        .collect();
-
-    let num_required = required_vertices.len();
-    let all_required_mask = if num_required == 0 {
-        0u64
-    } else {
-        (1u64 << num_required) - 1
-    };
+    if let Some(count) = count_paths_dense(&graph, start_vertex, target_vertex, required_vertices)?
+    {
+        return Ok(count);
+    }
 
-    let mut counter = PathCounter::new(&graph, target_vertex, &required_map, all_required_mask);
-    counter.count_paths(start_vertex, 0)
+    count_paths_through(start_vertex, target_vertex, required_vertices, &graph)
 }
 
 #[cfg(test)]
@@ -308,38 +300,38 @@ mod tests {
             "hhh: ccc fff iii".to_string(),
             "iii: out".to_string(),
         ];
-        assert_eq!(part1("you", "out", &input), 5);
+        assert_eq!(part1::<u64, _>("you", "out", &input), Ok(5));
     }
 
     #[test]
     fn test_part1_empty_input() {
         let input: Vec<String> = vec![];
-        assert_eq!(part1("start", "end", &input), 0);
+        assert_eq!(part1::<u64, _>("start", "end", &input), Ok(0));
     }
 
     #[test]
     fn test_part1_start_equals_target() {
         let input = vec!["a: b".to_string()];
-        assert_eq!(part1("same", "same", &input), 1);
+        assert_eq!(part1::<u64, _>("same", "same", &input), Ok(1));
     }
 
     #[test]
     fn test_part1_single_direct_path() {
         let input = vec!["a: b".to_string()];
-        assert_eq!(part1("a", "b", &input), 1);
+        assert_eq!(part1::<u64, _>("a", "b", &input), Ok(1));
     }
 
     #[test]
     fn test_part1_no_path_exists() {
         let input = vec!["a: b".to_string(), "c: d".to_string()];
-        assert_eq!(part1("a", "d", &input), 0);
+        assert_eq!(part1::<u64, _>("a", "d", &input), Ok(0));
     }
 
     #[test]
     fn test_part1_multiple_paths_diamond() {
         // Diamond pattern: a -> b,c -> d (2 paths)
         let input = vec!["a: b c".to_string(), "b: d".to_string(), "c: d".to_string()];
-        assert_eq!(part1("a", "d", &input), 2);
+        assert_eq!(part1::<u64, _>("a", "d", &input), Ok(2));
     }
 
     #[test]
@@ -351,20 +343,20 @@ mod tests {
             "c: e".to_string(),
             "d: e".to_string(),
         ];
-        assert_eq!(part1("a", "e", &input), 3);
+        assert_eq!(part1::<u64, _>("a", "e", &input), Ok(3));
     }
 
     #[test]
     fn test_part1_cycle_no_target() {
         // a -> b -> c -> b (cycle), no path to target
         let input = vec!["a: b".to_string(), "b: c".to_string(), "c: b".to_string()];
-        assert_eq!(part1("a", "target", &input), 0);
+        assert_eq!(part1::<u64, _>("a", "target", &input), Ok(0));
     }
 
     #[test]
     fn test_part1_start_not_in_graph() {
         let input = vec!["a: b".to_string()];
-        assert_eq!(part1("missing", "b", &input), 0);
+        assert_eq!(part1::<u64, _>("missing", "b", &input), Ok(0));
     }
 
     #[test]
@@ -383,14 +375,14 @@ mod tests {
             "e: target".to_string(),
             "f: target".to_string(),
         ];
-        assert_eq!(part1("a", "target", &input), 3);
+        assert_eq!(part1::<u64, _>("a", "target", &input), Ok(3));
     }
 
     #[test]
     fn test_part1_single_vertex_is_target() {
         // Graph with only target vertex, no path from elsewhere
         let input = vec!["other: somewhere".to_string()];
-        assert_eq!(part1("start", "target", &input), 0);
+        assert_eq!(part1::<u64, _>("start", "target", &input), Ok(0));
     }
 
     #[test]
@@ -406,7 +398,7 @@ mod tests {
             "e: g".to_string(),
             "f: g".to_string(),
         ];
-        assert_eq!(part1("a", "g", &input), 4);
+        assert_eq!(part1::<u64, _>("a", "g", &input), Ok(4));
     }
 
     #[test]
@@ -418,7 +410,7 @@ mod tests {
             "c: d".to_string(),
             "d: e".to_string(),
         ];
-        assert_eq!(part1("a", "e", &input), 1);
+        assert_eq!(part1::<u64, _>("a", "e", &input), Ok(1));
     }
 
     #[test]
@@ -430,21 +422,30 @@ mod tests {
             "b: c".to_string(),
             "c: b target".to_string(),
         ];
-        assert_eq!(part1("a", "target", &input), 1);
+        assert_eq!(part1::<u64, _>("a", "target", &input), Ok(1));
+    }
+
+    #[test]
+    fn test_part2_cycle_through_required_vertex() {
+        // a -> r -> a is skipped, so a path may not loop back through r
+        let input = ["a: r target", "r: a target"];
+        assert_eq!(part2::<u64, _, _>("a", "target", &["r"], &input), Ok(1));
+        assert_eq!(part1::<u64, _>("a", "target", &input), Ok(2));
+        assert_eq!(part2::<u64, _, _>("r", "target", &["a"], &input), Ok(1));
     }
 
     #[test]
     fn test_part1_malformed_input_no_colon() {
         // Malformed input should result in 0 paths (with error message)
         let input = vec!["a b c".to_string()];
-        assert_eq!(part1("a", "c", &input), 0);
+        assert_eq!(part1::<u64, _>("a", "c", &input), Ok(0));
     }
 
     #[test]
     fn test_part1_malformed_input_empty_source() {
         // Empty source should result in 0 paths (with error message)
         let input = vec![": b c".to_string()];
-        assert_eq!(part1("", "c", &input), 0);
+        assert_eq!(part1::<u64, _>("", "c", &input), Ok(0));
     }
 
     #[test]
@@ -468,12 +469,12 @@ mod tests {
     #[test]
     fn test_part1_with_str_slices() {
         // Demonstrate generic flexibility: can pass &str slices directly
-        assert_eq!(part1("a", "b", &["a: b"]), 1);
-        assert_eq!(part1("a", "c", &["a: b", "b: c"]), 1);
+        assert_eq!(part1::<u64, _>("a", "b", &["a: b"]), Ok(1));
+        assert_eq!(part1::<u64, _>("a", "c", &["a: b", "b: c"]), Ok(1));
 
         // Diamond pattern with string literals
-        let result = part1("a", "d", &["a: b c", "b: d", "c: d"]);
-        assert_eq!(result, 2);
+        let result: Result<u32, _> = part1("a", "d", &["a: b c", "b: d", "c: d"]);
+        assert_eq!(result, Ok(2));
     }
 
     #[test]
@@ -504,18 +505,60 @@ mod tests {
         // 6. svr->bbb->tty->ccc->ddd->hub->fff->hhh->out
         // 7. svr->bbb->tty->ccc->eee->dac->fff->ggg->out
         // 8. svr->bbb->tty->ccc->eee->dac->fff->hhh->out
-        assert_eq!(part2("svr", "out", &[] as &[&str], &input), 8);
+        assert_eq!(
+            part2::<u64, _, _>("svr", "out", &[] as &[&str], &input),
+            Ok(8)
+        );
 
         // With required vertices fft and dac, only paths 3 and 4 qualify
-        assert_eq!(part2("svr", "out", &["fft", "dac"], &input), 2);
+        assert_eq!(
+            part2::<u64, _, _>("svr", "out", &["fft", "dac"], &input),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn test_graph_dot_marks_a_counted_path() {
+        let input = [
+            "svr: aaa bbb",
+            "aaa: fft",
+            "fft: ccc",
+            "bbb: tty",
+            "tty: ccc",
+            "ccc: ddd eee",
+            "ddd: hub",
+            "hub: fff",
+            "eee: dac",
+            "dac: fff",
+            "fff: ggg hhh",
+            "ggg: out",
+            "hhh: out",
+        ];
+        let dot = graph_dot(&input, "svr", "out", &["dac", "fft"]).unwrap();
+        assert_eq!(dot.matches("fillcolor=gold").count(), 2, "{}", dot);
+        // Paths 3 and 4 share everything up to fff
+        for edge in [
+            "\"aaa\" -> \"fft\"",
+            "\"eee\" -> \"dac\"",
+            "\"dac\" -> \"fff\"",
+        ] {
+            assert!(dot.contains(&format!("{} [color=red", edge)), "{}", dot);
+        }
+        assert_eq!(dot.matches("penwidth").count(), 8, "{}", dot);
+        assert_eq!(dot.matches(" -> ").count(), 16);
+
+        // Without a qualifying path only the required vertices stand out
+        let dot = graph_dot(&input, "bbb", "out", &["fft"]).unwrap();
+        assert!(!dot.contains("red"), "{}", dot);
+        assert!(graph_dot(&["no colon"], "svr", "out", &[]).is_err());
     }
 
     #[test]
     fn test_part2_no_required_vertices() {
         // With no required vertices, should match part1
         let input = vec!["a: b c", "b: d", "c: d"];
-        assert_eq!(part2("a", "d", &[] as &[&str], &input), 2);
-        assert_eq!(part1("a", "d", &input), 2);
+        assert_eq!(part2::<u64, _, _>("a", "d", &[] as &[&str], &input), Ok(2));
+        assert_eq!(part1::<u64, _>("a", "d", &input), Ok(2));
     }
 
     #[test]
@@ -524,31 +567,31 @@ mod tests {
         let input = vec!["a: b c", "b: d", "c: d"];
 
         // Must pass through b (only 1 path: a->b->d)
-        assert_eq!(part2("a", "d", &["b"], &input), 1);
+        assert_eq!(part2::<u64, _, _>("a", "d", &["b"], &input), Ok(1));
 
         // Must pass through c (only 1 path: a->c->d)
-        assert_eq!(part2("a", "d", &["c"], &input), 1);
+        assert_eq!(part2::<u64, _, _>("a", "d", &["c"], &input), Ok(1));
     }
 
     #[test]
     fn test_part2_impossible_required_vertex() {
         // Required vertex not reachable
         let input = vec!["a: b", "b: c", "x: y"];
-        assert_eq!(part2("a", "c", &["x"], &input), 0);
+        assert_eq!(part2::<u64, _, _>("a", "c", &["x"], &input), Ok(0));
     }
 
     #[test]
     fn test_part2_required_vertex_is_start() {
         // Start vertex is in required list
         let input = vec!["a: b", "b: c"];
-        assert_eq!(part2("a", "c", &["a"], &input), 1);
+        assert_eq!(part2::<u64, _, _>("a", "c", &["a"], &input), Ok(1));
     }
 
     #[test]
     fn test_part2_required_vertex_is_target() {
         // Target vertex is in required list
         let input = vec!["a: b", "b: c"];
-        assert_eq!(part2("a", "c", &["c"], &input), 1);
+        assert_eq!(part2::<u64, _, _>("a", "c", &["c"], &input), Ok(1));
     }
 
     #[test]
@@ -567,16 +610,22 @@ mod tests {
 
         // 4 paths total: a->b->d->f->g->target, a->b->d->f->h->target,
         //                a->c->e->f->g->target, a->c->e->f->h->target
-        assert_eq!(part2("a", "target", &[] as &[&str], &input), 4);
+        assert_eq!(
+            part2::<u64, _, _>("a", "target", &[] as &[&str], &input),
+            Ok(4)
+        );
 
         // Require passing through d (eliminates c path) = 2 paths
-        assert_eq!(part2("a", "target", &["d"], &input), 2);
+        assert_eq!(part2::<u64, _, _>("a", "target", &["d"], &input), Ok(2));
 
         // Require passing through e (eliminates b path) = 2 paths
-        assert_eq!(part2("a", "target", &["e"], &input), 2);
+        assert_eq!(part2::<u64, _, _>("a", "target", &["e"], &input), Ok(2));
 
         // Require passing through both d and e = 0 paths (impossible)
-        assert_eq!(part2("a", "target", &["d", "e"], &input), 0);
+        assert_eq!(
+            part2::<u64, _, _>("a", "target", &["d", "e"], &input),
+            Ok(0)
+        );
     }
 
     #[test]
@@ -585,16 +634,16 @@ mod tests {
         let input = vec!["a: b", "b: c", "c: d"];
 
         // Must pass through b and c (only 1 path)
-        assert_eq!(part2("a", "d", &["b", "c"], &input), 1);
+        assert_eq!(part2::<u64, _, _>("a", "d", &["b", "c"], &input), Ok(1));
 
         // Must pass through b only
-        assert_eq!(part2("a", "d", &["b"], &input), 1);
+        assert_eq!(part2::<u64, _, _>("a", "d", &["b"], &input), Ok(1));
     }
 
     #[test]
     fn test_part2_empty_input() {
         let input: Vec<String> = vec![];
-        assert_eq!(part2("a", "b", &[] as &[&str], &input), 0);
+        assert_eq!(part2::<u64, _, _>("a", "b", &[] as &[&str], &input), Ok(0));
     }
 
     #[test]
@@ -604,10 +653,83 @@ mod tests {
 
         // Specifying ["b", "c"] vs ["c", "b"] should give same result
         // (both b and c must be visited, order doesn't matter)
-        assert_eq!(part2("a", "d", &["b", "c"], &input), 1);
-        assert_eq!(part2("a", "d", &["c", "b"], &input), 1);
+        assert_eq!(part2::<u64, _, _>("a", "d", &["b", "c"], &input), Ok(1));
+        assert_eq!(part2::<u64, _, _>("a", "d", &["c", "b"], &input), Ok(1));
 
         // The bitmask approach means order of specification is irrelevant
         // Both create the same requirement: visit both b and c
     }
+
+    /// A chain of `n` diamonds from `v0` to `vn`: 2^n paths.
+    fn diamond_chain(n: usize) -> Vec<String> {
+        (0..n)
+            .flat_map(|i| {
+                [
+                    format!("v{}: l{} r{}", i, i, i),
+                    format!("l{}: v{}", i, i + 1),
+                    format!("r{}: v{}", i, i + 1),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_part1_overflow_is_reported() {
+        let input = diamond_chain(64);
+        assert_eq!(
+            part1::<u32, _>("v0", "v32", &input),
+            Err(Overflow { type_name: "u32" })
+        );
+        assert_eq!(part1::<u64, _>("v0", "v63", &input), Ok(1 << 63));
+        assert!(part1::<u64, _>("v0", "v64", &input).is_err());
+        assert_eq!(part1::<u128, _>("v0", "v64", &input), Ok(1 << 64));
+    }
+
+    #[test]
+    fn test_part2_overflow_is_reported() {
+        let input = diamond_chain(70);
+        assert!(part2::<u64, _, _>("v0", "v70", &["v10"], &input).is_err());
+        assert_eq!(
+            part2::<u128, _, _>("v0", "v70", &["v10", "l20"], &input),
+            Ok(1 << 69)
+        );
+    }
+
+    #[test]
+    fn test_dense_count_matches_dfs() {
+        let input = diamond_chain(10);
+        let graph = parse_graph(&input).unwrap();
+        let dense = count_paths_dense::<u64, &str>(&graph, "v0", "v10", &[]).unwrap();
+        assert_eq!(
+            dense,
+            Some(count_paths::<u64>("v0", "v10", &graph).unwrap())
+        );
+        assert_eq!(dense, Some(1 << 10));
+
+        assert_eq!(
+            count_paths_dense(&graph, "v0", "v10", &["l3", "v7"]).unwrap(),
+            Some(count_paths_through::<u64, _>("v0", "v10", &["l3", "v7"], &graph).unwrap())
+        );
+        assert_eq!(
+            count_paths_dense::<u64, _>(&graph, "v0", "v10", &["nowhere"]),
+            Ok(Some(0))
+        );
+
+        // Cycles between start and target and oversized graphs use the DFS.
+        let cyclic = parse_graph(&["a: b", "b: a c", "c: d"]).unwrap();
+        assert_eq!(
+            count_paths_dense::<u64, &str>(&cyclic, "a", "d", &[]),
+            Ok(None)
+        );
+        assert_eq!(
+            part1::<u64, _>("a", "d", &["a: b", "b: a c", "c: d"]),
+            Ok(1)
+        );
+        let large_input = diamond_chain(30);
+        let large = parse_graph(&large_input).unwrap();
+        assert_eq!(
+            count_paths_dense::<u64, &str>(&large, "v0", "v30", &[]),
+            Ok(None)
+        );
+    }
 }
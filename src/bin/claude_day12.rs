@@ -1,25 +1,38 @@
-use rust_advent::Point2d;
-use std::collections::{HashMap, HashSet};
+use rayon::prelude::*;
+use rust_advent::geo::{GridRect, Mat2};
+use rust_advent::search::{Bump, InlineVec, Span, with_stack_size};
+use rust_advent::viz::{Color, Highlight, Visualizer, render_highlighted};
+use rust_advent::{Error, Point2d};
+use std::collections::HashSet;
 use std::fmt;
 
+/// Upper bound on the failed states remembered per region, to cap memory use
+const MAX_TABLE_ENTRIES: usize = 1 << 20;
+
+/// The search recurses once per scanned cell, so regions with more cells
+/// than this are searched on a thread with [`DEEP_STACK_BYTES`] of stack
+const DEEP_REGION_CELLS: usize = 4096;
+const DEEP_STACK_BYTES: usize = 256 << 20;
+
+/// Transposition table keys up to this many words are stored inline: enough
+/// for regions of up to 128 cells with six shapes
+const STATE_KEY_INLINE_WORDS: usize = 8;
+
 /// Custom error type for puzzle parsing and solving
-#[derive(Debug, Clone)]
-enum PuzzleError {
-    InvalidShape { line: usize, reason: String },
-    InvalidRegion { line: String, reason: String },
-    EmptyShape { id: usize },
+#[derive(Debug)]
+pub enum PuzzleError {
+    /// Malformed shape or region text, with the span at fault
+    Parse(Error),
+    EmptyShape {
+        id: usize,
+    },
     InvalidInput(String),
 }
 
 impl fmt::Display for PuzzleError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            PuzzleError::InvalidShape { line, reason } => {
-                write!(f, "Invalid shape at line {}: {}", line, reason)
-            }
-            PuzzleError::InvalidRegion { line, reason } => {
-                write!(f, "Invalid region '{}': {}", line, reason)
-            }
+            PuzzleError::Parse(err) => write!(f, "Parse error at {}", err),
             PuzzleError::EmptyShape { id } => {
                 write!(f, "Shape {} has no occupied cells", id)
             }
@@ -31,15 +44,71 @@ impl fmt::Display for PuzzleError {
 impl std::error::Error for PuzzleError {}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let inputs = rust_advent::read_file_as_lines("12")?;
-    let result = part1(&inputs)?;
+    let verbose = std::env::args().any(|arg| arg == "--verbose");
+    let source = rust_advent::read_file_as_string("12")?;
+    let inputs: Vec<String> = source.lines().map(str::to_string).collect();
+    let (result, stats) = match solve_part1(&inputs) {
+        Ok(solved) => solved,
+        Err(PuzzleError::Parse(err)) => {
+            eprintln!("{}", err.render(&source));
+            std::process::exit(1);
+        }
+        Err(err) => return Err(err.into()),
+    };
     println!("Part 1: {}", result);
+    if verbose {
+        eprintln!("{}", stats);
+    }
+    let mut viz = Visualizer::from_args()?;
+    if viz.is_enabled() {
+        visualize_regions(&inputs, &mut viz)?;
+    }
+    Ok(())
+}
+
+/// Draws the arrangement found for each region for `--visualize`, adding one
+/// piece per frame.  Each piece is marked with its shape id and gets its own
+/// color.
+fn visualize_regions(input: &[String], viz: &mut Visualizer) -> Result<(), PuzzleError> {
+    let (shapes, regions) = parse_input(input)?;
+    let variants = build_variant_table(&shapes);
+    for (idx, region) in regions.iter().enumerate() {
+        let empty = vec![vec![b'.'; region.width as usize]; region.height as usize];
+        let label = format!("region {} ({}x{})", idx + 1, region.width, region.height);
+        let Some(mut arrangement) = can_fit_region(region, &shapes, &variants).0 else {
+            viz.frame(&format!("{}: does not fit", label), |ansi| {
+                render_highlighted(&empty, &[], ansi)
+            });
+            continue;
+        };
+        // Pieces are recorded as the search unwinds, so the last was placed first
+        arrangement.reverse();
+        for shown in 0..=arrangement.len() {
+            let title = format!("{}: piece {} of {}", label, shown, arrangement.len());
+            viz.frame(&title, |ansi| {
+                let highlights: Vec<_> = arrangement[..shown]
+                    .iter()
+                    .enumerate()
+                    .map(|(n, (shape_id, cells))| {
+                        let cells = cells.iter().map(|p| (p.y as usize, p.x as usize));
+                        Highlight::new(Color::nth(n), cells).with_glyph(shape_glyph(*shape_id))
+                    })
+                    .collect();
+                render_highlighted(&empty, &highlights, ansi)
+            });
+        }
+    }
     Ok(())
 }
 
+/// A shape id as one character: `0`-`9`, then `a`-`z`, then `#`
+fn shape_glyph(id: usize) -> u8 {
+    char::from_digit(id as u32, 36).map_or(b'#', |c| c as u8)
+}
+
 /// Represents a 2D shape with normalized positions (min x,y at 0,0)
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Shape {
+pub struct Shape {
     id: usize,
     positions: Vec<Point2d>,
     width: i32,
@@ -56,7 +125,7 @@ struct ShapeVariant {
 
 /// Represents a rectangular region with shape requirements
 #[derive(Debug, Clone)]
-struct Region {
+pub struct Region {
     width: i32,
     height: i32,
     shape_counts: Vec<usize>,
@@ -68,10 +137,78 @@ struct Grid {
     width: i32,
     height: i32,
     cells: Vec<Vec<bool>>,
+    /// The same occupancy as a bitboard, used as the transposition table key
+    bits: BitGrid,
     empty_count: usize,
 }
 
-fn part1(input: &[String]) -> Result<u32, PuzzleError> {
+/// Cell occupancy packed row-major into 64-bit words
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BitGrid {
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    fn new(cells: usize) -> Self {
+        BitGrid {
+            words: vec![0; cells.div_ceil(64)],
+        }
+    }
+
+    fn set(&mut self, index: usize, occupied: bool) {
+        if occupied {
+            self.words[index / 64] |= 1 << (index % 64);
+        } else {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    fn words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+/// Transposition table usage, summed over all regions
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct TableStats {
+    lookups: u64,
+    hits: u64,
+    stored: u64,
+}
+
+impl std::ops::Add for TableStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        TableStats {
+            lookups: self.lookups + other.lookups,
+            hits: self.hits + other.hits,
+            stored: self.stored + other.stored,
+        }
+    }
+}
+
+impl fmt::Display for TableStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rate = if self.lookups == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f64 / self.lookups as f64
+        };
+        write!(
+            f,
+            "Transposition table: {}/{} hits ({:.1}%), {} states stored",
+            self.hits, self.lookups, rate, self.stored
+        )
+    }
+}
+
+pub fn part1(input: &[String]) -> Result<u32, PuzzleError> {
+    solve_part1(input).map(|(count, _)| count)
+}
+
+/// Count the satisfiable regions, also returning transposition table usage
+fn solve_part1(input: &[String]) -> Result<(u32, TableStats), PuzzleError> {
     let (shapes, regions) = parse_input(input)?;
 
     if shapes.is_empty() {
@@ -80,18 +217,26 @@ fn part1(input: &[String]) -> Result<u32, PuzzleError> {
         ));
     }
 
-    let mut satisfied_count = 0;
-    for region in regions {
-        if can_fit_region(&region, &shapes) {
-            satisfied_count += 1;
-        }
-    }
+    // Variants depend only on the shape, so every region shares one table
+    let variants = build_variant_table(&shapes);
 
-    Ok(satisfied_count)
+    // Regions are independent; check them in parallel
+    let (satisfied_count, stats) = regions
+        .par_iter()
+        .map(|region| {
+            let (arrangement, stats) = can_fit_region(region, &shapes, &variants);
+            (u32::from(arrangement.is_some()), stats)
+        })
+        .reduce(
+            || (0, TableStats::default()),
+            |(count_a, stats_a), (count_b, stats_b)| (count_a + count_b, stats_a + stats_b),
+        );
+
+    Ok((satisfied_count, stats))
 }
 
 /// Parse the entire input into shapes and regions
-fn parse_input(lines: &[String]) -> Result<(Vec<Shape>, Vec<Region>), PuzzleError> {
+pub fn parse_input(lines: &[String]) -> Result<(Vec<Shape>, Vec<Region>), PuzzleError> {
     let mut shapes = Vec::new();
     let mut regions = Vec::new();
     let mut i = 0;
@@ -105,18 +250,22 @@ fn parse_input(lines: &[String]) -> Result<(Vec<Shape>, Vec<Region>), PuzzleErro
         }
 
         // Check if this is a shape (format: "N:")
-        if line.ends_with(':') && line.len() > 1 {
-            if let Ok(id) = line[..line.len() - 1].parse::<usize>() {
-                let start_line = i;
-                let shape = parse_shape(lines, &mut i, id, start_line)?;
-                shapes.push(shape);
-                continue;
-            }
+        if line.ends_with(':')
+            && line.len() > 1
+            && let Ok(id) = line[..line.len() - 1].parse::<usize>()
+        {
+            let start_line = i;
+            let shape = parse_shape(lines, &mut i, id, start_line)?;
+            shapes.push(shape);
+            continue;
         }
 
         // Check if this is a region (format: "WxH: ...")
         if line.contains('x') && line.contains(':') {
-            let region = parse_region(line)?;
+            let region = parse_region(&lines[i]).map_err(|err| match err {
+                PuzzleError::Parse(err) => PuzzleError::Parse(err.on_line(i + 1)),
+                other => other,
+            })?;
             regions.push(region);
         }
 
@@ -151,12 +300,14 @@ fn parse_shape(
         }
 
         // Validate that line only contains valid characters
-        for ch in line.chars() {
+        for (x, ch) in line.char_indices() {
             if ch != '#' && ch != '.' && !ch.is_whitespace() {
-                return Err(PuzzleError::InvalidShape {
-                    line: *start + 1,
-                    reason: format!("Invalid character '{}' in shape pattern", ch),
-                });
+                return Err(PuzzleError::Parse(Error::parse_span(
+                    *start + 1,
+                    x + 1,
+                    ch.len_utf8(),
+                    format!("Invalid character '{}' in shape pattern", ch),
+                )));
             }
         }
 
@@ -165,10 +316,13 @@ fn parse_shape(
     }
 
     if pattern_lines.is_empty() {
-        return Err(PuzzleError::InvalidShape {
-            line: start_line + 1,
-            reason: "Shape has no pattern lines".to_string(),
-        });
+        let header = &lines[start_line];
+        return Err(PuzzleError::Parse(Error::at_token(
+            start_line + 1,
+            header,
+            header.trim(),
+            "Shape has no pattern lines",
+        )));
     }
 
     // Parse the pattern to extract positions
@@ -197,40 +351,43 @@ fn parse_shape(
     })
 }
 
-/// Parse a single region specification
+/// Parse a single region specification.  Errors number `line` as line 1;
+/// the caller moves them to its real line.
 fn parse_region(line: &str) -> Result<Region, PuzzleError> {
+    let error =
+        |token: &str, reason: String| PuzzleError::Parse(Error::at_token(1, line, token, reason));
+
     let parts: Vec<&str> = line.split(':').collect();
     if parts.len() != 2 {
-        return Err(PuzzleError::InvalidRegion {
-            line: line.to_string(),
-            reason: "Expected format 'WxH: count0 count1 ...'".to_string(),
-        });
+        return Err(error(
+            line.trim(),
+            "Expected format 'WxH: count0 count1 ...'".to_string(),
+        ));
     }
 
     // Parse dimensions "WxH"
-    let dims: Vec<&str> = parts[0].trim().split('x').collect();
+    let dims_str = parts[0].trim();
+    let dims: Vec<&str> = dims_str.split('x').collect();
     if dims.len() != 2 {
-        return Err(PuzzleError::InvalidRegion {
-            line: line.to_string(),
-            reason: format!("Invalid dimensions '{}', expected 'WxH'", parts[0]),
-        });
+        return Err(error(
+            dims_str,
+            format!("Invalid dimensions '{}', expected 'WxH'", parts[0]),
+        ));
     }
 
-    let width = dims[0].parse::<i32>().map_err(|_| PuzzleError::InvalidRegion {
-        line: line.to_string(),
-        reason: format!("Invalid width '{}'", dims[0]),
-    })?;
+    let width = dims[0]
+        .parse::<i32>()
+        .map_err(|_| error(dims[0], format!("Invalid width '{}'", dims[0])))?;
 
-    let height = dims[1].parse::<i32>().map_err(|_| PuzzleError::InvalidRegion {
-        line: line.to_string(),
-        reason: format!("Invalid height '{}'", dims[1]),
-    })?;
+    let height = dims[1]
+        .parse::<i32>()
+        .map_err(|_| error(dims[1], format!("Invalid height '{}'", dims[1])))?;
 
     if width <= 0 || height <= 0 {
-        return Err(PuzzleError::InvalidRegion {
-            line: line.to_string(),
-            reason: format!("Dimensions must be positive, got {}x{}", width, height),
-        });
+        return Err(error(
+            dims_str,
+            format!("Dimensions must be positive, got {}x{}", width, height),
+        ));
     }
 
     // Parse shape counts
@@ -240,10 +397,10 @@ fn parse_region(line: &str) -> Result<Region, PuzzleError> {
         .collect();
 
     if counts.is_empty() {
-        return Err(PuzzleError::InvalidRegion {
-            line: line.to_string(),
-            reason: "No shape counts specified".to_string(),
-        });
+        return Err(error(
+            parts[1].trim(),
+            "No shape counts specified".to_string(),
+        ));
     }
 
     Ok(Region {
@@ -253,93 +410,54 @@ fn parse_region(line: &str) -> Result<Region, PuzzleError> {
     })
 }
 
-/// Normalize shape positions to have min x,y at (0,0) - single pass optimization
+/// Normalize shape positions to have min x,y at (0,0)
 fn normalize_positions(positions: &[Point2d]) -> (Vec<Point2d>, i32, i32) {
-    if positions.is_empty() {
+    let Some(bounds) = GridRect::bounding(positions.iter().copied()) else {
         return (Vec::new(), 0, 0);
-    }
-
-    // Single pass to find bounds
-    let (min_x, min_y, max_x, max_y) = positions.iter().fold(
-        (i32::MAX, i32::MAX, i32::MIN, i32::MIN),
-        |(min_x, min_y, max_x, max_y), p| {
-            (
-                min_x.min(p.x),
-                min_y.min(p.y),
-                max_x.max(p.x),
-                max_y.max(p.y),
-            )
-        },
-    );
+    };
+    let min = bounds.min();
 
     let normalized: Vec<Point2d> = positions
         .iter()
         .map(|p| Point2d {
-            x: p.x - min_x,
-            y: p.y - min_y,
+            x: p.x - min.x,
+            y: p.y - min.y,
         })
         .collect();
 
-    let width = max_x - min_x + 1;
-    let height = max_y - min_y + 1;
-
-    (normalized, width, height)
-}
-
-/// Rotate positions 90 degrees clockwise
-fn rotate_90(positions: &[Point2d], _width: i32, height: i32) -> Vec<Point2d> {
-    positions
-        .iter()
-        .map(|p| Point2d {
-            x: height - 1 - p.y,
-            y: p.x,
-        })
-        .collect()
+    // Shapes are drawn in a few rows, so their sides fit in i32
+    (normalized, bounds.width() as i32, bounds.height() as i32)
 }
 
-/// Flip positions horizontally
-fn flip_horizontal(positions: &[Point2d], width: i32) -> Vec<Point2d> {
-    positions
-        .iter()
-        .map(|p| Point2d {
-            x: width - 1 - p.x,
-            y: p.y,
-        })
-        .collect()
+/// Apply a rotation or reflection to positions, moving the result back to the origin
+fn transform(positions: &[Point2d], matrix: Mat2) -> ShapeVariant {
+    let moved: Vec<Point2d> = positions.iter().map(|&p| matrix * p).collect();
+    let (positions, width, height) = normalize_positions(&moved);
+    ShapeVariant {
+        positions,
+        width,
+        height,
+    }
 }
 
-/// Generate all unique transformations of a shape
+/// Generate all unique transformations of a shape: each of 4 rotations, then its mirror image
 fn generate_all_variants(shape: &Shape) -> Vec<ShapeVariant> {
-    let mut variants = Vec::new();
-    let mut current_positions = shape.positions.clone();
-    let mut current_width = shape.width;
-    let mut current_height = shape.height;
-
-    // Generate 4 rotations
-    for _ in 0..4 {
-        // Add current rotation
-        variants.push(ShapeVariant {
-            positions: current_positions.clone(),
-            width: current_width,
-            height: current_height,
-        });
-
-        // Add flipped version
-        let flipped = flip_horizontal(&current_positions, current_width);
-        variants.push(ShapeVariant {
-            positions: flipped,
-            width: current_width,
-            height: current_height,
-        });
-
-        // Rotate for next iteration
-        current_positions = rotate_90(&current_positions, current_width, current_height);
-        std::mem::swap(&mut current_width, &mut current_height);
-    }
+    let variants = (0..4)
+        .flat_map(|turns| {
+            let rotation = Mat2::ROTATE_CW.pow(turns);
+            [rotation, Mat2::FLIP_HORIZONTAL * rotation]
+        })
+        .map(|matrix| transform(&shape.positions, matrix))
+        .collect();
 
     deduplicate_variants(variants)
 }
 
+/// Generate the variants of every shape, indexed by shape id
+fn build_variant_table(shapes: &[Shape]) -> Vec<Vec<ShapeVariant>> {
+    shapes.iter().map(generate_all_variants).collect()
+}
+
 /// Deduplicate shape variants (remove symmetric duplicates)
 fn deduplicate_variants(variants: Vec<ShapeVariant>) -> Vec<ShapeVariant> {
     let mut seen = HashSet::new();
@@ -347,11 +465,8 @@ fn deduplicate_variants(variants: Vec<ShapeVariant>) -> Vec<ShapeVariant> {
 
     for variant in variants {
         // Create a normalized representation for comparison using tuples
-        let mut sorted_positions: Vec<(i32, i32)> = variant
-            .positions
-            .iter()
-            .map(|p| (p.x, p.y))
-            .collect();
+        let mut sorted_positions: Vec<(i32, i32)> =
+            variant.positions.iter().map(|p| (p.x, p.y)).collect();
         sorted_positions.sort();
 
         let key = (sorted_positions, variant.width, variant.height);
@@ -363,6 +478,17 @@ fn deduplicate_variants(variants: Vec<ShapeVariant>) -> Vec<ShapeVariant> {
     unique
 }
 
+/// The region's cells, from the origin to the far corner
+fn grid_bounds(grid: &Grid) -> GridRect {
+    GridRect::from_corners(
+        Point2d { x: 0, y: 0 },
+        Point2d {
+            x: grid.width - 1,
+            y: grid.height - 1,
+        },
+    )
+}
+
 /// Create a new empty grid
 fn create_grid(width: i32, height: i32) -> Grid {
     let empty_count = (width * height) as usize;
@@ -370,18 +496,26 @@ fn create_grid(width: i32, height: i32) -> Grid {
         width,
         height,
         cells: vec![vec![false; width as usize]; height as usize],
+        bits: BitGrid::new(empty_count),
         empty_count,
     }
 }
 
+/// Mark a single cell as occupied or empty, keeping the bitboard in sync
+fn set_cell(grid: &mut Grid, x: usize, y: usize, occupied: bool) {
+    grid.cells[y][x] = occupied;
+    grid.bits.set(y * grid.width as usize + x, occupied);
+}
+
 /// Check if a shape variant can be placed at the given origin
 fn can_place(grid: &Grid, variant: &ShapeVariant, origin: Point2d) -> bool {
+    let bounds = grid_bounds(grid);
     for pos in &variant.positions {
         let x = origin.x + pos.x;
         let y = origin.y + pos.y;
 
         // Check bounds
-        if x < 0 || y < 0 || x >= grid.width || y >= grid.height {
+        if !bounds.contains(Point2d { x, y }) {
             return false;
         }
 
@@ -399,7 +533,7 @@ fn place_piece(grid: &mut Grid, variant: &ShapeVariant, origin: Point2d) {
     for pos in &variant.positions {
         let x = (origin.x + pos.x) as usize;
         let y = (origin.y + pos.y) as usize;
-        grid.cells[y][x] = true;
+        set_cell(grid, x, y, true);
     }
     grid.empty_count -= variant.positions.len();
 }
@@ -409,7 +543,7 @@ fn remove_piece(grid: &mut Grid, variant: &ShapeVariant, origin: Point2d) {
     for pos in &variant.positions {
         let x = (origin.x + pos.x) as usize;
         let y = (origin.y + pos.y) as usize;
-        grid.cells[y][x] = false;
+        set_cell(grid, x, y, false);
     }
     grid.empty_count += variant.positions.len();
 }
@@ -419,123 +553,383 @@ fn count_empty_cells(grid: &Grid) -> usize {
     grid.empty_count
 }
 
-/// Try to fit all required pieces into the region
-fn can_fit_region(region: &Region, shapes: &[Shape]) -> bool {
-    // Build list of pieces to place
-    let mut pieces = build_piece_list(region);
+/// A shape variant and the origin it is placed at
+type Placement<'a> = (&'a ShapeVariant, Point2d);
+
+/// Every placement of one shape's variants inside a region, indexed by the
+/// cells each placement covers.
+struct ShapePlacements {
+    size: usize,
+    /// `covers[y * width + x]` is the span of the placement arena listing the
+    /// placements that cover cell (x, y).
+    covers: Vec<Span>,
+}
+
+/// Precomputed tables shared by every step of one region's search
+struct SearchTables<'a> {
+    /// Backing storage for every shape's placement lists
+    arena: Bump<Placement<'a>>,
+    placements: Vec<ShapePlacements>,
+    /// Shape ids, larger pieces first since they have fewer ways to fit
+    order: Vec<usize>,
+    /// Cells in the order they are decided.  Walking along the shorter side
+    /// first keeps the frontier of partly filled cells narrow.
+    scan: Vec<Point2d>,
+}
+
+/// Index all in-bounds placements of `variants` on an empty grid, storing
+/// each cell's list in `arena`
+fn build_placements<'a>(
+    grid: &Grid,
+    variants: &'a [ShapeVariant],
+    size: usize,
+    arena: &mut Bump<Placement<'a>>,
+) -> ShapePlacements {
+    // Visiting a variant's cells in reverse row-major order yields the origins
+    // that cover a given cell in row-major order, the order the search tries
+    let reversed: Vec<Vec<Point2d>> = variants
+        .iter()
+        .map(|variant| {
+            let mut positions = variant.positions.clone();
+            positions.sort_by_key(|p| std::cmp::Reverse((p.y, p.x)));
+            positions
+        })
+        .collect();
 
-    if pieces.is_empty() {
-        return true; // No pieces to place
+    let bounds = grid_bounds(grid);
+    let covers = (0..grid.height)
+        .flat_map(|y| (0..grid.width).map(move |x| Point2d { x, y }))
+        .map(|cell| {
+            arena.alloc(
+                variants
+                    .iter()
+                    .zip(&reversed)
+                    .flat_map(|(variant, positions)| {
+                        // Origins that put one of the variant's cells on `cell`
+                        positions.iter().filter_map(move |pos| {
+                            let origin = Point2d {
+                                x: cell.x - pos.x,
+                                y: cell.y - pos.y,
+                            };
+                            let placed = GridRect::from_corners(
+                                origin,
+                                Point2d {
+                                    x: origin.x + variant.width - 1,
+                                    y: origin.y + variant.height - 1,
+                                },
+                            );
+                            let in_bounds = placed.corners().iter().all(|&c| bounds.contains(c));
+                            in_bounds.then_some((variant, origin))
+                        })
+                    }),
+            )
+        })
+        .collect();
+    ShapePlacements { size, covers }
+}
+
+/// List the grid's cells column by column when the grid is wider than tall,
+/// row by row otherwise
+fn build_scan_order(grid: &Grid) -> Vec<Point2d> {
+    if grid.width > grid.height {
+        (0..grid.width)
+            .flat_map(|x| (0..grid.height).map(move |y| Point2d { x, y }))
+            .collect()
+    } else {
+        (0..grid.height)
+            .flat_map(|y| (0..grid.width).map(move |x| Point2d { x, y }))
+            .collect()
     }
+}
 
-    // Generate all variants for required shapes (using entry API to avoid double lookup)
-    let mut all_variants = HashMap::new();
-    for (shape_id, _) in &pieces {
-        all_variants.entry(*shape_id).or_insert_with(|| {
-            if *shape_id < shapes.len() {
-                generate_all_variants(&shapes[*shape_id])
-            } else {
-                Vec::new()
-            }
-        });
+/// A transposition table key: the occupied bitboard followed by the count
+/// of each remaining shape.  Small regions fit without a heap allocation.
+type StateKey = InlineVec<u64, STATE_KEY_INLINE_WORDS>;
+
+/// Transposition table of search states already proven unsolvable.  A state
+/// is the occupied bitboard plus the count of each remaining shape; cells
+/// before the cursor are always occupied, so nothing else affects the result.
+struct FailedStates {
+    states: HashSet<StateKey>,
+    /// Scratch buffer for building lookup keys without allocating
+    key: StateKey,
+    stats: TableStats,
+}
+
+impl FailedStates {
+    fn new() -> Self {
+        FailedStates {
+            states: HashSet::new(),
+            key: StateKey::new(),
+            stats: TableStats::default(),
+        }
     }
 
-    // Sort pieces by constraint (most constrained first)
-    // This dramatically improves backtracking performance
-    pieces.sort_by_key(|(shape_id, _)| {
-        let shape_size = shapes
-            .get(*shape_id)
-            .map(|s| s.positions.len())
-            .unwrap_or(0);
-        let variant_count = all_variants.get(shape_id).map(|v| v.len()).unwrap_or(1);
+    fn load_key(&mut self, grid: &Grid, remaining: &[usize]) {
+        self.key.clear();
+        self.key.extend_from_slice(grid.bits.words());
+        self.key.extend(remaining.iter().map(|&count| count as u64));
+    }
 
-        // Sort by: larger pieces first, then fewer variants first
-        // Using Reverse to get descending order for size, ascending for variant count
-        (std::cmp::Reverse(shape_size), variant_count)
-    });
+    fn contains(&mut self, grid: &Grid, remaining: &[usize]) -> bool {
+        self.load_key(grid, remaining);
+        self.stats.lookups += 1;
+        let hit = self.states.contains(&self.key[..]);
+        if hit {
+            self.stats.hits += 1;
+        }
+        hit
+    }
+
+    fn insert(&mut self, grid: &Grid, remaining: &[usize]) {
+        if self.states.len() >= MAX_TABLE_ENTRIES {
+            return;
+        }
+        self.load_key(grid, remaining);
+        if self.states.insert(self.key.clone()) {
+            self.stats.stored += 1;
+        }
+    }
+}
+
+/// A piece of a finished arrangement: its shape id and the cells it covers
+type PlacedPiece = (usize, Vec<Point2d>);
+
+/// Try to fit all required pieces into the region.  `variants` holds the
+/// variants of each shape, as built by [`build_variant_table`].  Returns the
+/// arrangement found, if any, and how the transposition table was used.
+fn can_fit_region(
+    region: &Region,
+    shapes: &[Shape],
+    variants: &[Vec<ShapeVariant>],
+) -> (Option<Vec<PlacedPiece>>, TableStats) {
+    let mut remaining = region.shape_counts.clone();
+    let cells_needed: usize = remaining
+        .iter()
+        .enumerate()
+        .map(|(shape_id, &count)| count * shapes.get(shape_id).map_or(0, |s| s.positions.len()))
+        .sum();
+
+    // A region asking for a shape that doesn't exist can never be satisfied
+    if remaining
+        .iter()
+        .enumerate()
+        .any(|(shape_id, &count)| count > 0 && shape_id >= shapes.len())
+    {
+        return (None, TableStats::default());
+    }
+    // Regions may list fewer counts than there are shapes
+    remaining.resize(shapes.len(), 0);
+    if cells_needed == 0 {
+        return (Some(Vec::new()), TableStats::default()); // No pieces to place
+    }
 
     // Create grid
     let mut grid = create_grid(region.width, region.height);
+    if cells_needed > count_empty_cells(&grid) {
+        return (None, TableStats::default());
+    }
 
-    // Try to place all pieces
-    try_place_pieces(&mut grid, &pieces, 0, &all_variants, shapes)
-}
+    let mut order: Vec<usize> = (0..shapes.len()).collect();
+    order.sort_by_key(|&shape_id| std::cmp::Reverse(shapes[shape_id].positions.len()));
+    let mut arena = Bump::new();
+    // Index placements only for the shapes this region uses
+    let placements = variants
+        .iter()
+        .zip(shapes)
+        .zip(&remaining)
+        .map(|((variants, shape), &count)| {
+            let variants = if count > 0 { &variants[..] } else { &[] };
+            build_placements(&grid, variants, shape.positions.len(), &mut arena)
+        })
+        .collect();
+    let tables = SearchTables {
+        arena,
+        placements,
+        order,
+        scan: build_scan_order(&grid),
+    };
 
-/// Expand region requirements into a list of individual pieces
-fn build_piece_list(region: &Region) -> Vec<(usize, usize)> {
-    let mut pieces = Vec::new();
-    for (shape_id, &count) in region.shape_counts.iter().enumerate() {
-        for piece_index in 0..count {
-            pieces.push((shape_id, piece_index));
-        }
-    }
-    pieces
+    let mut failed = FailedStates::new();
+    let mut arrangement = Vec::new();
+    let mut search = || {
+        try_place_pieces(
+            &mut grid,
+            0,
+            &mut remaining,
+            cells_needed,
+            &tables,
+            &mut failed,
+            &mut arrangement,
+        )
+    };
+    let fits = if tables.scan.len() > DEEP_REGION_CELLS {
+        with_stack_size(DEEP_STACK_BYTES, search)
+    } else {
+        search()
+    };
+    (fits.then_some(arrangement), failed.stats)
 }
 
-/// Main backtracking function to place all pieces
+/// Main backtracking function to place all pieces.
+///
+/// Cells before `cursor` in the scan order are already decided.  The first
+/// undecided cell is either covered by one of the placements indexed for it,
+/// or left as a hole if there is enough spare room; this never tries the same
+/// arrangement in two different placement orders.  Different arrangements
+/// that reach the same state are caught by the `failed` table.  On success
+/// the pieces are added to `arrangement` as the recursion unwinds.
 fn try_place_pieces(
     grid: &mut Grid,
-    pieces: &[(usize, usize)],
-    current_idx: usize,
-    all_variants: &HashMap<usize, Vec<ShapeVariant>>,
-    shapes: &[Shape],
+    cursor: usize,
+    remaining: &mut [usize],
+    cells_needed: usize,
+    tables: &SearchTables,
+    failed: &mut FailedStates,
+    arrangement: &mut Vec<PlacedPiece>,
 ) -> bool {
     // Base case: all pieces placed
-    if current_idx >= pieces.len() {
+    if cells_needed == 0 {
         return true;
     }
 
     // Early pruning: check if remaining pieces can possibly fit
-    let remaining_cells_needed: usize = pieces[current_idx..]
-        .iter()
-        .filter_map(|(sid, _)| shapes.get(*sid))
-        .map(|s| s.positions.len())
-        .sum();
-
     let empty_cells = count_empty_cells(grid);
-    if remaining_cells_needed > empty_cells {
+    if cells_needed > empty_cells || failed.contains(grid, remaining) {
         return false;
     }
 
-    let (shape_id, _piece_index) = pieces[current_idx];
-
-    // Get variants for this shape
-    let variants = match all_variants.get(&shape_id) {
-        Some(v) => v,
-        None => return false,
+    // Most constrained cell: the first one not yet decided
+    let Some(cursor) = (cursor..tables.scan.len()).find(|&k| {
+        let p = tables.scan[k];
+        !grid.cells[p.y as usize][p.x as usize]
+    }) else {
+        return false;
     };
+    let cell = tables.scan[cursor];
+    let cell_index = (cell.y * grid.width + cell.x) as usize;
 
-    // Try all variants
-    for variant in variants {
-        // Try all possible positions
-        // Note: Could optimize further by only trying positions near first empty cell,
-        // but that requires more sophisticated logic to maintain correctness
-        for y in 0..=grid.height - variant.height {
-            for x in 0..=grid.width - variant.width {
-                let origin = Point2d { x, y };
-
-                if can_place(grid, variant, origin) {
-                    // Place the piece
-                    place_piece(grid, variant, origin);
-
-                    // Recurse
-                    if try_place_pieces(grid, pieces, current_idx + 1, all_variants, shapes) {
-                        return true;
-                    }
-
-                    // Backtrack
-                    remove_piece(grid, variant, origin);
+    for &shape_id in &tables.order {
+        if remaining[shape_id] == 0 {
+            continue;
+        }
+        let shape_placements = &tables.placements[shape_id];
+        for &(variant, origin) in &tables.arena[shape_placements.covers[cell_index]] {
+            if can_place(grid, variant, origin) {
+                // Place the piece
+                place_piece(grid, variant, origin);
+                remaining[shape_id] -= 1;
+
+                // Recurse
+                let placed = try_place_pieces(
+                    grid,
+                    cursor + 1,
+                    remaining,
+                    cells_needed - shape_placements.size,
+                    tables,
+                    failed,
+                    arrangement,
+                );
+
+                // Backtrack
+                remaining[shape_id] += 1;
+                remove_piece(grid, variant, origin);
+                if placed {
+                    let cells = variant
+                        .positions
+                        .iter()
+                        .map(|p| Point2d {
+                            x: origin.x + p.x,
+                            y: origin.y + p.y,
+                        })
+                        .collect();
+                    arrangement.push((shape_id, cells));
+                    return true;
                 }
             }
         }
     }
 
+    // Leave the cell empty, if the remaining pieces still fit without it
+    if empty_cells > cells_needed {
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        set_cell(grid, x, y, true);
+        grid.empty_count -= 1;
+        let placed = try_place_pieces(
+            grid,
+            cursor + 1,
+            remaining,
+            cells_needed,
+            tables,
+            failed,
+            arrangement,
+        );
+        set_cell(grid, x, y, false);
+        grid.empty_count += 1;
+        if placed {
+            return true;
+        }
+    }
+
+    failed.insert(grid, remaining);
     false
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use rust_advent::{Counter, example_input};
+
+    /// A polyomino grown from one cell, each step adding a neighbour of an
+    /// earlier cell in the direction chosen.
+    fn polyomino(steps: &[(usize, u8)]) -> Shape {
+        let mut cells = vec![point(0, 0)];
+        for &(from, dir) in steps {
+            let base = cells[from % cells.len()];
+            let (dx, dy) = [(1, 0), (0, 1), (-1, 0), (0, -1)][usize::from(dir % 4)];
+            let next = point(base.x + dx, base.y + dy);
+            if !cells.contains(&next) {
+                cells.push(next);
+            }
+        }
+        let (positions, width, height) = normalize_positions(&cells);
+        Shape {
+            id: 0,
+            positions,
+            width,
+            height,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_orientation_count_divides_symmetry_group(
+            steps in prop::collection::vec((0usize..16, 0u8..4), 0..10)
+        ) {
+            let shape = polyomino(&steps);
+            let variants = generate_all_variants(&shape);
+            prop_assert!([1, 2, 4, 8].contains(&variants.len()), "{} variants", variants.len());
+
+            let mut seen = HashSet::new();
+            for variant in &variants {
+                prop_assert_eq!(variant.positions.len(), shape.positions.len());
+                let in_bounds = variant.positions.iter().all(|p| {
+                    (0..variant.width).contains(&p.x) && (0..variant.height).contains(&p.y)
+                });
+                prop_assert!(in_bounds);
+                let mut cells: Vec<_> = variant.positions.iter().map(|p| (p.x, p.y)).collect();
+                cells.sort();
+                prop_assert!(seen.insert(cells), "duplicate variant");
+            }
+        }
+    }
+
+    fn fits(region: &Region, shapes: &[Shape]) -> bool {
+        can_fit_region(region, shapes, &build_variant_table(shapes))
+            .0
+            .is_some()
+    }
 
     fn point(x: i32, y: i32) -> Point2d {
         Point2d { x, y }
@@ -556,7 +950,7 @@ mod tests {
         // L-shape: ##
         //          #.
         let positions = vec![point(0, 0), point(1, 0), point(0, 1)];
-        let rotated = rotate_90(&positions, 2, 2);
+        let rotated = transform(&positions, Mat2::ROTATE_CW).positions;
 
         // After 90° rotation: #.
         //                     ##
@@ -568,7 +962,7 @@ mod tests {
         // L-shape: ##
         //          #.
         let positions = vec![point(0, 0), point(1, 0), point(0, 1)];
-        let flipped = flip_horizontal(&positions, 2);
+        let flipped = transform(&positions, Mat2::FLIP_HORIZONTAL).positions;
 
         // After flip: ##
         //             .#
@@ -719,7 +1113,7 @@ mod tests {
             shape_counts: vec![1],
         };
 
-        assert!(can_fit_region(&region, &shapes));
+        assert!(fits(&region, &shapes));
     }
 
     #[test]
@@ -738,7 +1132,25 @@ mod tests {
             shape_counts: vec![1],
         };
 
-        assert!(!can_fit_region(&region, &shapes));
+        assert!(!fits(&region, &shapes));
+    }
+
+    #[test]
+    fn test_deep_region() {
+        // One cell per piece, so the search recurses once per cell, past
+        // DEEP_REGION_CELLS
+        let shapes = vec![Shape {
+            id: 0,
+            positions: vec![point(0, 0)],
+            width: 1,
+            height: 1,
+        }];
+        let region = Region {
+            width: 100,
+            height: 50,
+            shape_counts: vec![5000],
+        };
+        assert!(fits(&region, &shapes));
     }
 
     #[test]
@@ -758,7 +1170,7 @@ mod tests {
             shape_counts: vec![2],
         };
 
-        assert!(can_fit_region(&region, &shapes));
+        assert!(fits(&region, &shapes));
     }
 
     #[test]
@@ -778,7 +1190,7 @@ mod tests {
             shape_counts: vec![1],
         };
 
-        assert!(can_fit_region(&region, &shapes));
+        assert!(fits(&region, &shapes));
     }
 
     #[test]
@@ -797,51 +1209,199 @@ mod tests {
             shape_counts: vec![0],
         };
 
-        assert!(can_fit_region(&region, &shapes));
+        assert!(fits(&region, &shapes));
     }
 
     #[test]
     fn test_problem_example() {
-        let input = vec![
-            "0:".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "1:".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            ".##".to_string(),
-            "".to_string(),
-            "2:".to_string(),
-            ".##".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "3:".to_string(),
-            "##.".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "4:".to_string(),
-            "###".to_string(),
-            "#..".to_string(),
-            "###".to_string(),
-            "".to_string(),
-            "5:".to_string(),
-            "###".to_string(),
-            ".#.".to_string(),
-            "###".to_string(),
-            "".to_string(),
-            "4x4: 0 0 0 0 2 0".to_string(),
-            "12x5: 1 0 1 0 2 2".to_string(),
-            "12x5: 1 0 1 0 3 2".to_string(),
-        ];
+        let input = example_input!("12/example1");
 
         let result = part1(&input).unwrap();
         assert_eq!(result, 2);
     }
 
+    #[test]
+    fn test_arrangement_is_valid() {
+        let input = example_input!("12/example1");
+        let (shapes, regions) = parse_input(&input).unwrap();
+        let variants = build_variant_table(&shapes);
+        let mut fitted = 0;
+        for region in &regions {
+            let Some(arrangement) = can_fit_region(region, &shapes, &variants).0 else {
+                continue;
+            };
+            fitted += 1;
+            let mut counts = Counter::new();
+            let mut covered = HashSet::new();
+            for (shape_id, cells) in &arrangement {
+                counts.add(*shape_id);
+                assert_eq!(cells.len(), shapes[*shape_id].positions.len());
+                for cell in cells {
+                    assert!((0..region.width).contains(&cell.x), "{:?}", cell);
+                    assert!((0..region.height).contains(&cell.y), "{:?}", cell);
+                    assert!(covered.insert((cell.x, cell.y)), "{:?} covered twice", cell);
+                }
+            }
+            assert_eq!(
+                counts,
+                Counter::from_counts(region.shape_counts.iter().copied().enumerate())
+            );
+        }
+        assert_eq!(fitted, 2);
+    }
+
+    #[test]
+    fn test_fit_requires_holes() {
+        // Two L-trominoes in a 3x3 region always leave three cells empty
+        let shapes = vec![Shape {
+            id: 0,
+            positions: vec![point(0, 0), point(0, 1), point(1, 1)],
+            width: 2,
+            height: 2,
+        }];
+        let region = |width, height, count| Region {
+            width,
+            height,
+            shape_counts: vec![count],
+        };
+
+        assert!(fits(&region(3, 3, 2), &shapes));
+        // Enough area, but a 3x3 square can't be tiled by L-trominoes
+        assert!(!fits(&region(3, 3, 3), &shapes));
+        assert!(fits(&region(2, 6, 4), &shapes));
+        assert!(fits(&region(4, 4, 4), &shapes));
+        assert!(!fits(&region(1, 9, 1), &shapes));
+    }
+
+    #[test]
+    fn test_missing_shape_counts() {
+        let shapes = vec![Shape {
+            id: 0,
+            positions: vec![point(0, 0)],
+            width: 1,
+            height: 1,
+        }];
+        let region = |shape_counts| Region {
+            width: 2,
+            height: 2,
+            shape_counts,
+        };
+
+        assert!(fits(&region(vec![1, 0]), &shapes));
+        assert!(!fits(&region(vec![1, 1]), &shapes));
+
+        // Fewer counts than shapes leaves the rest at zero, even for a
+        // shape the search tries first
+        let domino = Shape {
+            id: 1,
+            positions: vec![point(0, 0), point(1, 0)],
+            width: 2,
+            height: 1,
+        };
+        let two = vec![shapes[0].clone(), domino];
+        assert!(fits(&region(vec![3]), &two));
+        assert!(fits(&region(vec![]), &two));
+    }
+
+    #[test]
+    fn test_build_placements() {
+        // An L tromino on a 3x2 grid fits at origins (0, 0) and (1, 0)
+        let variant = ShapeVariant {
+            positions: vec![point(0, 0), point(0, 1), point(1, 1)],
+            width: 2,
+            height: 2,
+        };
+        let variants = [variant];
+        let mut arena = Bump::new();
+        let placements = build_placements(&create_grid(3, 2), &variants, 3, &mut arena);
+        let origins = |x: i32, y: i32| {
+            let span = placements.covers[(y * 3 + x) as usize];
+            arena[span].iter().map(|&(_, o)| o).collect::<Vec<_>>()
+        };
+
+        assert_eq!(placements.covers.len(), 6);
+        assert_eq!(origins(0, 0), vec![point(0, 0)]);
+        assert_eq!(origins(1, 0), vec![point(1, 0)]);
+        assert_eq!(origins(2, 0), vec![]);
+        // Origins covering a cell come out in row-major order
+        assert_eq!(origins(1, 1), vec![point(0, 0), point(1, 0)]);
+        assert_eq!(arena.len(), 6);
+    }
+
+    #[test]
+    fn test_scan_order_follows_short_side() {
+        let wide = build_scan_order(&create_grid(3, 2));
+        assert_eq!(
+            wide,
+            vec![
+                point(0, 0),
+                point(0, 1),
+                point(1, 0),
+                point(1, 1),
+                point(2, 0),
+                point(2, 1)
+            ]
+        );
+
+        let tall = build_scan_order(&create_grid(2, 3));
+        assert_eq!(
+            tall,
+            vec![
+                point(0, 0),
+                point(1, 0),
+                point(0, 1),
+                point(1, 1),
+                point(0, 2),
+                point(1, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bitboard_tracks_cells() {
+        let mut grid = create_grid(9, 8);
+        assert_eq!(grid.bits.words(), [0, 0]);
+
+        let variant = ShapeVariant {
+            positions: vec![point(0, 0), point(1, 0)],
+            width: 2,
+            height: 1,
+        };
+        place_piece(&mut grid, &variant, point(0, 7));
+        set_cell(&mut grid, 1, 0, true);
+        // Cells (0,7) and (1,7) are bits 63 and 64
+        assert_eq!(grid.bits.words(), [(1 << 63) | (1 << 1), 1]);
+
+        remove_piece(&mut grid, &variant, point(0, 7));
+        set_cell(&mut grid, 1, 0, false);
+        assert_eq!(grid.bits.words(), [0, 0]);
+    }
+
+    #[test]
+    fn test_table_stats() {
+        let input: Vec<String> = [
+            "0:", "###", "##.", "##.", "", "4:", "###", "#..", "###", "", "12x5: 4", "4x4: 0 2",
+            "3x3: 2",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let (count, stats) = solve_part1(&input).unwrap();
+        assert_eq!(count, 2);
+        assert!(stats.lookups > 0);
+        assert!(stats.hits <= stats.lookups);
+
+        let stats = TableStats {
+            lookups: 8,
+            hits: 2,
+            stored: 5,
+        };
+        assert_eq!(
+            (stats + stats).to_string(),
+            "Transposition table: 4/16 hits (25.0%), 10 states stored"
+        );
+    }
+
     #[test]
     fn test_count_empty_cells() {
         let mut grid = create_grid(3, 3);
@@ -872,24 +1432,12 @@ mod tests {
             shape_counts: vec![3],
         };
 
-        assert!(can_fit_region(&region, &shapes));
+        assert!(fits(&region, &shapes));
     }
 
     #[test]
     fn test_parse_full_example() {
-        let input = vec![
-            "0:".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "4:".to_string(),
-            "###".to_string(),
-            "#..".to_string(),
-            "###".to_string(),
-            "".to_string(),
-            "4x4: 0 0 0 0 2 0".to_string(),
-        ];
+        let input = example_input!("12/example1_shapes_0_and_4");
 
         let (shapes, regions) = parse_input(&input).unwrap();
 
@@ -939,10 +1487,34 @@ mod tests {
         let result = parse_region("5x10:");
         assert!(result.is_err());
         match result {
-            Err(PuzzleError::InvalidRegion { reason, .. }) => {
-                assert!(reason.contains("No shape counts"));
+            Err(PuzzleError::Parse(err)) => {
+                assert!(err.to_string().contains("No shape counts"));
+            }
+            _ => panic!("Expected Parse error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_points_at_token() {
+        let source = "0:\n#\n\n4x4: 1\n4xq: 0 1";
+        let input: Vec<String> = source.lines().map(str::to_string).collect();
+        match parse_input(&input) {
+            Err(PuzzleError::Parse(err)) => assert_eq!(
+                err.render(source),
+                "error: Invalid height 'q'\n --> line 5, column 3\n  |\n5 | 4xq: 0 1\n  |   ^"
+            ),
+            other => panic!("Expected Parse error, got {:?}", other),
+        }
+
+        let input = vec!["0:".to_string(), "#a".to_string()];
+        match parse_input(&input) {
+            Err(PuzzleError::Parse(err)) => {
+                assert_eq!(
+                    err.to_string(),
+                    "line 2, column 2: Invalid character 'a' in shape pattern"
+                );
             }
-            _ => panic!("Expected InvalidRegion error"),
+            other => panic!("Expected Parse error, got {:?}", other),
         }
     }
 }
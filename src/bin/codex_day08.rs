@@ -1,15 +1,16 @@
-use rust_advent::Point;
+use rust_advent::{Point, product_checked};
 
 fn main() -> std::io::Result<()> {
     let inputs = rust_advent::read_points("08")?;
-    println!("Part 1: {}", part1(1000, 3, &inputs));
-    println!("Part 2: {}", part2(&inputs));
+    let overflow = || std::io::Error::new(std::io::ErrorKind::InvalidData, "answer overflows");
+    println!("Part 1: {}", part1(1000, 3, &inputs).ok_or_else(overflow)?);
+    println!("Part 2: {}", part2(&inputs).ok_or_else(overflow)?);
     Ok(())
 }
 
-fn part1(n: usize, m: usize, inputs: &[Point]) -> usize {
+pub fn part1(n: usize, m: usize, inputs: &[Point]) -> Option<usize> {
     if n == 0 || m == 0 || inputs.is_empty() {
-        return 0;
+        return Some(0);
     }
 
     let mut heap = std::collections::BinaryHeap::new();
@@ -19,11 +20,11 @@ fn part1(n: usize, m: usize, inputs: &[Point]) -> usize {
             let entry = (dist, i, j);
             if heap.len() < n {
                 heap.push(entry);
-            } else if let Some(&top) = heap.peek() {
-                if entry < top {
-                    heap.pop();
-                    heap.push(entry);
-                }
+            } else if let Some(&top) = heap.peek()
+                && entry < top
+            {
+                heap.pop();
+                heap.push(entry);
             }
         }
     }
@@ -43,14 +44,14 @@ fn part1(n: usize, m: usize, inputs: &[Point]) -> usize {
     sizes.sort_unstable_by(|a, b| b.cmp(a));
     let take = m.min(sizes.len());
     if take == 0 {
-        return 0;
+        return Some(0);
     }
-    sizes.into_iter().take(take).product()
+    product_checked(sizes.into_iter().take(take))
 }
 
-fn part2(inputs: &[Point]) -> usize {
+pub fn part2(inputs: &[Point]) -> Option<usize> {
     if inputs.len() < 2 {
-        return 0;
+        return Some(0);
     }
 
     let mut edges = Vec::new();
@@ -70,11 +71,11 @@ fn part2(inputs: &[Point]) -> usize {
             if components == 1 {
                 let xa = inputs[a].x as i64;
                 let xb = inputs[b].x as i64;
-                return (xa * xb) as usize;
+                return product_checked([xa, xb]).and_then(|x| usize::try_from(x).ok());
             }
         }
     }
-    0
+    Some(0)
 }
 
 fn squared_distance(a: &Point, b: &Point) -> i64 {
@@ -136,8 +137,8 @@ mod tests {
     #[test]
     fn example_small_n1() {
         let inputs = vec![pt(0, 0, 0), pt(2, 2, 2), pt(2, 3, 2)];
-        assert_eq!(part1(1, 1, &inputs), 2);
-        assert_eq!(part1(1, 2, &inputs), 2);
+        assert_eq!(part1(1, 1, &inputs), Some(2));
+        assert_eq!(part1(1, 2, &inputs), Some(2));
     }
 
     #[test]
@@ -164,27 +165,27 @@ mod tests {
             pt(984, 92, 344),
             pt(425, 690, 689),
         ];
-        assert_eq!(part1(3, 1, &inputs), 3);
-        assert_eq!(part1(3, 2, &inputs), 6);
+        assert_eq!(part1(3, 1, &inputs), Some(3));
+        assert_eq!(part1(3, 2, &inputs), Some(6));
     }
 
     #[test]
     fn connects_all_when_n_exceeds_pairs() {
         let inputs = vec![pt(0, 0, 0), pt(1, 0, 0), pt(2, 0, 0), pt(3, 0, 0)];
-        assert_eq!(part1(10, 1, &inputs), 4);
+        assert_eq!(part1(10, 1, &inputs), Some(4));
     }
 
     #[test]
     fn multiple_components_product() {
         let inputs = vec![pt(0, 0, 0), pt(10, 0, 0), pt(20, 0, 0), pt(21, 0, 0)];
-        assert_eq!(part1(1, 2, &inputs), 2);
-        assert_eq!(part1(2, 2, &inputs), 4);
+        assert_eq!(part1(1, 2, &inputs), Some(2));
+        assert_eq!(part1(2, 2, &inputs), Some(4));
     }
 
     #[test]
     fn part2_example_small() {
         let inputs = vec![pt(1, 1, 1), pt(2, 3, 4), pt(3, 5, 6)];
-        assert_eq!(part2(&inputs), 2);
+        assert_eq!(part2(&inputs), Some(2));
     }
 
     #[test]
@@ -211,12 +212,22 @@ mod tests {
             pt(984, 92, 344),
             pt(425, 690, 689),
         ];
-        assert_eq!(part2(&inputs), 25272);
+        assert_eq!(part2(&inputs), Some(25272));
     }
 
     #[test]
     fn part2_extra_case() {
         let inputs = vec![pt(0, 0, 0), pt(1, 0, 0), pt(0, 1, 0), pt(10, 0, 0)];
-        assert_eq!(part2(&inputs), 10);
+        assert_eq!(part2(&inputs), Some(10));
+    }
+
+    #[test]
+    fn part2_negative_product() {
+        // The last edge joins x=-5 and x=1
+        let inputs = vec![pt(-5, 0, 0), pt(1, 0, 0), pt(4, 0, 0)];
+        assert_eq!(part2(&inputs), None);
+        // Two negative coordinates still give a usable product
+        let inputs = vec![pt(-10, 0, 0), pt(-2, 0, 0), pt(-1, 0, 0)];
+        assert_eq!(part2(&inputs), Some(20));
     }
 }
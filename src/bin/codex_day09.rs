@@ -1,4 +1,5 @@
 use rust_advent::Point2d;
+use rust_advent::geo::GridRect;
 
 fn main() -> std::io::Result<()> {
     let inputs = rust_advent::read_points2d("09")?;
@@ -7,18 +8,16 @@ fn main() -> std::io::Result<()> {
     Ok(())
 }
 
-fn part1(inputs: &[Point2d]) -> usize {
+pub fn part1(inputs: &[Point2d]) -> usize {
     if inputs.len() < 2 {
         return 0;
     }
 
-    let mut max_area: i64 = 0;
+    let mut max_area: u64 = 0;
     for i in 0..inputs.len() {
         let a = &inputs[i];
         for b in &inputs[(i + 1)..] {
-            let dx = (a.x as i64 - b.x as i64).abs() + 1;
-            let dy = (a.y as i64 - b.y as i64).abs() + 1;
-            let area = dx * dy;
+            let area = GridRect::from_corners(*a, *b).inclusive_area();
             if area > max_area {
                 max_area = area;
             }
@@ -28,25 +27,25 @@ fn part1(inputs: &[Point2d]) -> usize {
     max_area as usize
 }
 
-fn part2(inputs: &[Point2d]) -> usize {
+pub fn part2(inputs: &[Point2d]) -> usize {
     if inputs.len() < 2 {
         return 0;
     }
 
     let edges = build_edges(inputs);
 
-    let mut max_area: i64 = 0;
+    let mut max_area: u64 = 0;
     for i in 0..inputs.len() {
         let a = &inputs[i];
         for b in &inputs[(i + 1)..] {
-            let min_x = (a.x.min(b.x)) as i64;
-            let max_x = (a.x.max(b.x)) as i64;
-            let min_y = (a.y.min(b.y)) as i64;
-            let max_y = (a.y.max(b.y)) as i64;
-            let area = (max_x - min_x + 1) * (max_y - min_y + 1);
+            let rect = GridRect::from_corners(*a, *b);
+            let area = rect.inclusive_area();
             if area <= max_area {
                 continue;
             }
+            let (min, max) = (rect.min(), rect.max());
+            let (min_x, max_x) = (i64::from(min.x), i64::from(max.x));
+            let (min_y, max_y) = (i64::from(min.y), i64::from(max.y));
             if rectangle_inside(min_x, max_x, min_y, max_y, &edges) {
                 max_area = area;
             }
@@ -86,10 +85,10 @@ fn build_edges(points: &[Point2d]) -> Vec<Edge> {
             );
         }
         edges.push(Edge {
-            x1: a.x as i64,
-            y1: a.y as i64,
-            x2: b.x as i64,
-            y2: b.y as i64,
+            x1: i64::from(a.x),
+            y1: i64::from(a.y),
+            x2: i64::from(b.x),
+            y2: i64::from(b.y),
         });
     }
     edges
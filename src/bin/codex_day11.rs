@@ -1,16 +1,14 @@
-fn main() -> std::io::Result<()> {
+use rust_advent::{Error, Result};
+
+fn main() -> Result<()> {
     let inputs = rust_advent::read_file_as_lines("11")?;
-    let part1_value = part1("you", "out", &inputs)
-        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
-    println!("Part 1: {}", part1_value);
-    let part2_value = part2("svr", "out", &["dac", "fft"], &inputs)
-        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
-    println!("Part 2: {}", part2_value);
+    println!("Part 1: {}", part1("you", "out", &inputs)?);
+    println!("Part 2: {}", part2("svr", "out", &["dac", "fft"], &inputs)?);
     Ok(())
 }
 
 /// Part 1: Beam splitter
-fn part1(start_vertex: &str, target_vertex: &str, input: &[String]) -> Result<u64, String> {
+pub fn part1(start_vertex: &str, target_vertex: &str, input: &[String]) -> Result<u64> {
     let mut graph = parse_graph(input)?;
     graph.entry(start_vertex.to_string()).or_default();
     graph.entry(target_vertex.to_string()).or_default();
@@ -20,17 +18,17 @@ fn part1(start_vertex: &str, target_vertex: &str, input: &[String]) -> Result<u6
 
     let start_idx = *index_map
         .get(start_vertex)
-        .ok_or_else(|| format!("missing start vertex: {start_vertex}"))?;
+        .ok_or_else(|| Error::NoSolution(format!("missing start vertex: {start_vertex}")))?;
     let target_idx = *index_map
         .get(target_vertex)
-        .ok_or_else(|| format!("missing target vertex: {target_vertex}"))?;
+        .ok_or_else(|| Error::NoSolution(format!("missing target vertex: {target_vertex}")))?;
 
     let required_bits = vec![None; nodes.len()];
     let mut memo = std::collections::HashMap::new();
     count_paths_with_required(start_idx, target_idx, 0, 0, &adj, &required_bits, &mut memo)
 }
 
-fn parse_graph(input: &[String]) -> Result<std::collections::HashMap<String, Vec<String>>, String> {
+fn parse_graph(input: &[String]) -> Result<std::collections::HashMap<String, Vec<String>>> {
     let mut graph: std::collections::HashMap<String, Vec<String>> =
         std::collections::HashMap::new();
     for (line_idx, line) in input.iter().enumerate() {
@@ -40,13 +38,10 @@ fn parse_graph(input: &[String]) -> Result<std::collections::HashMap<String, Vec
         }
         let (src, rest) = line
             .split_once(':')
-            .ok_or_else(|| format!("invalid line {}: missing ':'", line_idx + 1))?;
+            .ok_or_else(|| Error::parse(line_idx + 1, "missing ':'"))?;
         let src = src.trim();
         if src.is_empty() {
-            return Err(format!(
-                "invalid line {}: empty source vertex",
-                line_idx + 1
-            ));
+            return Err(Error::parse(line_idx + 1, "empty source vertex"));
         }
         let targets: Vec<String> = rest
             .split_whitespace()
@@ -64,12 +59,12 @@ fn parse_graph(input: &[String]) -> Result<std::collections::HashMap<String, Vec
     Ok(graph)
 }
 
-fn part2<R: AsRef<str>>(
+pub fn part2<R: AsRef<str>>(
     start_vertex: &str,
     target_vertex: &str,
     required_vertices: &[R],
     input: &[String],
-) -> Result<u64, String> {
+) -> Result<u64> {
     let mut graph = parse_graph(input)?;
     graph.entry(start_vertex.to_string()).or_default();
     graph.entry(target_vertex.to_string()).or_default();
@@ -86,10 +81,12 @@ fn part2<R: AsRef<str>>(
         let name = vertex.as_ref();
         let idx = *index_map
             .get(name)
-            .ok_or_else(|| format!("missing required vertex: {name}"))?;
+            .ok_or_else(|| Error::NoSolution(format!("missing required vertex: {name}")))?;
         if required_bits[idx].is_none() {
             if next_bit >= 64 {
-                return Err("too many required vertices for bitmask".to_string());
+                return Err(Error::Unsupported(
+                    "too many required vertices for bitmask".to_string(),
+                ));
             }
             required_bits[idx] = Some(next_bit);
             next_bit += 1;
@@ -103,10 +100,10 @@ fn part2<R: AsRef<str>>(
     };
     let start_idx = *index_map
         .get(start_vertex)
-        .ok_or_else(|| format!("missing start vertex: {start_vertex}"))?;
+        .ok_or_else(|| Error::NoSolution(format!("missing start vertex: {start_vertex}")))?;
     let target_idx = *index_map
         .get(target_vertex)
-        .ok_or_else(|| format!("missing target vertex: {target_vertex}"))?;
+        .ok_or_else(|| Error::NoSolution(format!("missing target vertex: {target_vertex}")))?;
 
     let start_mask = apply_required_bit(0, start_idx, &required_bits);
     if start_idx == target_idx {
@@ -151,7 +148,7 @@ fn build_indexed_graph(
     (nodes, adj, index_map)
 }
 
-fn detect_cycle(adj: &[Vec<usize>], nodes: &[String]) -> Result<(), String> {
+fn detect_cycle(adj: &[Vec<usize>], nodes: &[String]) -> Result<()> {
     let mut state = vec![0u8; adj.len()];
     for node in 0..adj.len() {
         if state[node] == 0 {
@@ -161,16 +158,14 @@ fn detect_cycle(adj: &[Vec<usize>], nodes: &[String]) -> Result<(), String> {
     Ok(())
 }
 
-fn dfs_cycle(
-    node: usize,
-    adj: &[Vec<usize>],
-    nodes: &[String],
-    state: &mut [u8],
-) -> Result<(), String> {
+fn dfs_cycle(node: usize, adj: &[Vec<usize>], nodes: &[String], state: &mut [u8]) -> Result<()> {
     state[node] = 1;
     for &next in &adj[node] {
         if state[next] == 1 {
-            return Err(format!("cycle detected involving node: {}", nodes[next]));
+            return Err(Error::Unsupported(format!(
+                "cycle detected involving node: {}",
+                nodes[next]
+            )));
         }
         if state[next] == 0 {
             dfs_cycle(next, adj, nodes, state)?;
@@ -195,7 +190,7 @@ fn count_paths_with_required(
     adj: &[Vec<usize>],
     required_bits: &[Option<u8>],
     memo: &mut std::collections::HashMap<(usize, u64), u64>,
-) -> Result<u64, String> {
+) -> Result<u64> {
     let mask = apply_required_bit(mask, node, required_bits);
     if node == target {
         return Ok(if mask == full_mask { 1 } else { 0 });
@@ -209,7 +204,7 @@ fn count_paths_with_required(
             count_paths_with_required(next, target, mask, full_mask, adj, required_bits, memo)?;
         total = total
             .checked_add(count)
-            .ok_or_else(|| "path count overflow".to_string())?;
+            .ok_or_else(|| Error::Unsupported("path count overflow".to_string()))?;
     }
     memo.insert((node, mask), total);
     Ok(total)
@@ -278,23 +273,24 @@ mod tests {
 
     #[test]
     fn parse_rejects_missing_colon() {
-        let input = lines(&["a b"]);
+        let input = lines(&["a: b", "a b"]);
         let err = parse_graph(&input).unwrap_err();
-        assert!(err.contains("missing ':'"));
+        assert!(matches!(err, Error::Parse { line: 2, .. }));
+        assert!(err.to_string().contains("missing ':'"));
     }
 
     #[test]
     fn parse_rejects_empty_source() {
         let input = lines(&[": b"]);
         let err = parse_graph(&input).unwrap_err();
-        assert!(err.contains("empty source vertex"));
+        assert!(err.to_string().contains("empty source vertex"));
     }
 
     #[test]
     fn cycle_is_error() {
         let input = lines(&["a: b", "b: a"]);
         let err = part1("a", "c", &input).unwrap_err();
-        assert!(err.contains("cycle detected"));
+        assert!(err.to_string().contains("cycle detected"));
     }
 
     #[test]
@@ -365,6 +361,6 @@ mod tests {
     fn part2_cycle_is_error() {
         let input = lines(&["a: b", "b: a"]);
         let err = part2::<&str>("a", "b", &[], &input).unwrap_err();
-        assert!(err.contains("cycle detected"));
+        assert!(err.to_string().contains("cycle detected"));
     }
 }
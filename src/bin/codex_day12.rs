@@ -1,10 +1,13 @@
+use rust_advent::Point2d;
+use rust_advent::geo::Mat2;
+
 fn main() -> std::io::Result<()> {
     let inputs = rust_advent::read_file_as_lines("12")?;
     println!("Part 1: {}", part1(&inputs));
     Ok(())
 }
 
-fn part1(input: &[String]) -> u32 {
+pub fn part1(input: &[String]) -> u32 {
     let (shapes, regions) = match parse_input(input) {
         Ok(parsed) => parsed,
         Err(err) => {
@@ -219,18 +222,17 @@ fn generate_orientations(points: &[(i32, i32)]) -> Vec<Orientation> {
 
     for rot in 0..4 {
         for flip in [false, true] {
+            let rotation = Mat2::ROTATE_CCW.pow(rot);
+            let matrix = if flip {
+                Mat2::FLIP_HORIZONTAL * rotation
+            } else {
+                rotation
+            };
             let mut transformed: Vec<(i32, i32)> = points
                 .iter()
                 .map(|&(x, y)| {
-                    let (rx, ry) = match rot {
-                        0 => (x, y),
-                        1 => (y, -x),
-                        2 => (-x, -y),
-                        3 => (-y, x),
-                        _ => unreachable!(),
-                    };
-                    let rx = if flip { -rx } else { rx };
-                    (rx, ry)
+                    let moved = matrix * Point2d { x, y };
+                    (moved.x, moved.y)
                 })
                 .collect();
 
@@ -292,10 +294,8 @@ fn can_fit_region(region: &Region, shapes: &[Shape]) -> bool {
     }
 
     let mut counts = vec![0usize; shapes.len()];
-    for i in 0..counts.len() {
-        if i < region.counts.len() {
-            counts[i] = region.counts[i];
-        }
+    for (count, &required) in counts.iter_mut().zip(&region.counts) {
+        *count = required;
     }
 
     let type_data = build_type_data(region, shapes);
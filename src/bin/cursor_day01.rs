@@ -15,7 +15,7 @@ fn main() -> std::io::Result<()> {
 ///          as a single character direction (L or R) followed by a number of clicks.
 /// Returns:
 ///   The number of times the dial is pointing at 0 after a rotation.
-fn part1(inputs: &[String]) -> i32 {
+pub fn part1(inputs: &[String]) -> i32 {
     let mut position = 50;
     let mut count = 0;
 
@@ -55,7 +55,7 @@ fn part1(inputs: &[String]) -> i32 {
 ///          as a single character direction (L or R) followed by a number of clicks.
 /// Returns:
 ///   The number of times the dial is pointing at 0 at any point during a rotation.
-fn part2(inputs: &[String]) -> i32 {
+pub fn part2(inputs: &[String]) -> i32 {
     let mut position = 50;
     let mut count = 0;
 
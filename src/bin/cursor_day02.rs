@@ -14,7 +14,7 @@ fn main() -> std::io::Result<()> {
 ///
 /// For example 1-22,30-50 contains the values 11, 22, 33, and 44
 /// which sum to 110.
-fn part1(ranges: &str) -> u64 {
+pub fn part1(ranges: &str) -> u64 {
     let mut total = 0u64;
 
     for range_str in ranges.split(',') {
@@ -22,10 +22,10 @@ fn part1(ranges: &str) -> u64 {
         if let Some((start_str, end_str)) = range_str.split_once('-') {
             let start: u64 = start_str
                 .parse()
-                .expect(&format!("Could not parse start: {}", start_str));
+                .unwrap_or_else(|_| panic!("Could not parse start: {}", start_str));
             let end: u64 = end_str
                 .parse()
-                .expect(&format!("Could not parse end: {}", end_str));
+                .unwrap_or_else(|_| panic!("Could not parse end: {}", end_str));
             total += sum_invalid_ids_in_range(start, end);
         }
     }
@@ -41,7 +41,7 @@ fn part1(ranges: &str) -> u64 {
 ///
 /// For example, 12341234 is invalid (1234 repeated twice),
 /// and 1111111 is invalid (1 repeated seven times).
-fn part2(ranges: &str) -> u64 {
+pub fn part2(ranges: &str) -> u64 {
     let mut total = 0u64;
 
     for range_str in ranges.split(',') {
@@ -49,10 +49,10 @@ fn part2(ranges: &str) -> u64 {
         if let Some((start_str, end_str)) = range_str.split_once('-') {
             let start: u64 = start_str
                 .parse()
-                .expect(&format!("Could not parse start: {}", start_str));
+                .unwrap_or_else(|_| panic!("Could not parse start: {}", start_str));
             let end: u64 = end_str
                 .parse()
-                .expect(&format!("Could not parse end: {}", end_str));
+                .unwrap_or_else(|_| panic!("Could not parse end: {}", end_str));
             total += sum_invalid_ids_in_range_part2(start, end);
         }
     }
@@ -67,7 +67,7 @@ fn is_invalid_id(n: u64) -> bool {
     let len = s.len();
 
     // Must have even number of digits
-    if len % 2 != 0 {
+    if !len.is_multiple_of(2) {
         return false;
     }
 
@@ -102,7 +102,7 @@ fn is_invalid_id_part2(n: u64) -> bool {
     // (we need at least 2 repetitions, so pattern length can be at most len/2)
     for pattern_len in 1..=len / 2 {
         // The length must be a multiple of pattern_len for it to be a valid repetition
-        if len % pattern_len != 0 {
+        if !len.is_multiple_of(pattern_len) {
             continue;
         }
 
@@ -1,6 +1,6 @@
 /// Day 3.
 fn main() -> std::io::Result<()> {
-    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03")?;
+    let inputs: Vec<Vec<u8>> = rust_advent::read_number_grid("03", rust_advent::ParseMode::Strict)?;
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
         match args[1].as_str() {
@@ -25,7 +25,7 @@ fn main() -> std::io::Result<()> {
 /// For example, in the row [1, 2, 5, 2, 1] the largest number is 52.
 /// This function returns the sum of the largest numbers for each row
 /// over all provided rows.
-pub fn part1(grid: &Vec<Vec<u8>>) -> u64 {
+pub fn part1(grid: &[Vec<u8>]) -> u64 {
     grid.iter()
         .map(|row| {
             let mut max_value = 0u64;
@@ -46,7 +46,7 @@ pub fn part1(grid: &Vec<Vec<u8>>) -> u64 {
 /// can be formed by selecting 12 digits from the row in order.
 /// This function returns the sum of the largest numbers for each row
 /// over all provided rows.
-pub fn part2(grid: &Vec<Vec<u8>>) -> u64 {
+pub fn part2(grid: &[Vec<u8>]) -> u64 {
     grid.iter()
         .map(|row| {
             if row.len() < 12 {
@@ -19,13 +19,16 @@ fn count_adjacent_objects(grid: &[&[u8]], i: usize, j: usize, rows: usize, cols:
         let nj = j as i32 + dj;
 
         // Check bounds before converting to usize
-        if ni >= 0 && ni < rows as i32 && nj >= 0 && nj < cols as i32 {
-            if grid[ni as usize][nj as usize] == b'@' {
-                count += 1;
-                // Early exit: once we have 4 neighbors, no need to check more
-                if count >= 4 {
-                    break;
-                }
+        if ni >= 0
+            && ni < rows as i32
+            && nj >= 0
+            && nj < cols as i32
+            && grid[ni as usize][nj as usize] == b'@'
+        {
+            count += 1;
+            // Early exit: once we have 4 neighbors, no need to check more
+            if count >= 4 {
+                break;
             }
         }
     }
@@ -46,13 +49,16 @@ fn count_adjacent_objects_mut(
         let nj = j as i32 + dj;
 
         // Check bounds before converting to usize
-        if ni >= 0 && ni < rows as i32 && nj >= 0 && nj < cols as i32 {
-            if grid[ni as usize][nj as usize] == b'@' {
-                count += 1;
-                // Early exit: once we have 4 neighbors, no need to check more
-                if count >= 4 {
-                    break;
-                }
+        if ni >= 0
+            && ni < rows as i32
+            && nj >= 0
+            && nj < cols as i32
+            && grid[ni as usize][nj as usize] == b'@'
+        {
+            count += 1;
+            // Early exit: once we have 4 neighbors, no need to check more
+            if count >= 4 {
+                break;
             }
         }
     }
@@ -72,7 +78,7 @@ fn main() -> std::io::Result<()> {
 /// an empty space, count the number of objects that have fewer than 4 adjacent
 /// objects, not including itself, and where grid positions are adjacent
 /// horizontally, vertically, and diagonally.
-fn part1(inputs: &[String]) -> usize {
+pub fn part1(inputs: &[String]) -> usize {
     if inputs.is_empty() {
         return 0;
     }
@@ -85,11 +91,10 @@ fn part1(inputs: &[String]) -> usize {
 
     let mut count = 0;
 
-    for i in 0..rows {
-        let row = grid[i];
-        for j in 0..cols {
+    for (i, row) in grid.iter().enumerate() {
+        for (j, &cell) in row.iter().enumerate().take(cols) {
             // Only process '@' characters
-            if row[j] != b'@' {
+            if cell != b'@' {
                 continue;
             }
 
@@ -112,7 +117,7 @@ fn part1(inputs: &[String]) -> usize {
 /// it possible to remove additional objects -- which should also be removed.
 ///
 /// The return value should be the total number of objects removed.
-fn part2(inputs: &[String]) -> usize {
+pub fn part2(inputs: &[String]) -> usize {
     if inputs.is_empty() {
         return 0;
     }
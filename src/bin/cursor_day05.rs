@@ -11,7 +11,7 @@ fn main() -> std::io::Result<()> {
 ///
 /// Efficiency: Merges overlapping ranges first to reduce the number of range checks per value.
 /// This is particularly beneficial when many ranges overlap.
-fn part1(input: &rust_advent::RangeData) -> usize {
+pub fn part1(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() || input.values.is_empty() {
         return 0;
     }
@@ -36,7 +36,7 @@ fn part1(input: &rust_advent::RangeData) -> usize {
 ///
 /// Efficiency: Merges and sums in a single pass without allocating a vector for merged ranges,
 /// saving O(m) space and eliminating a second iteration over the merged ranges.
-fn part2(input: &rust_advent::RangeData) -> usize {
+pub fn part2(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() {
         return 0;
     }
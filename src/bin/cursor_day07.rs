@@ -6,7 +6,7 @@ fn main() -> std::io::Result<()> {
 }
 
 /// Part 1: Beam splitter
-fn part1(input: &[String]) -> u64 {
+pub fn part1(input: &[String]) -> u64 {
     if input.is_empty() {
         return 0;
     }
@@ -67,9 +67,70 @@ fn part1(input: &[String]) -> u64 {
     split_splitters.len() as u64
 }
 
+/// Part 2: Count possible paths (beam takes either left or right at each splitter)
+pub fn part2(input: &[String]) -> u64 {
+    if input.is_empty() || input.len() == 1 {
+        // Need at least 2 rows for a path to propagate
+        return 0;
+    }
+
+    let rows = input.len();
+    let cols = input[0].len();
+
+    // Find the start position 'S'
+    let mut start_col = 0;
+    for (col, ch) in input[0].chars().enumerate() {
+        if ch == 'S' {
+            start_col = col;
+            break;
+        }
+    }
+
+    // Track the number of paths reaching each position (row, col)
+    // Use u64 to handle large numbers of paths
+    let mut paths = vec![vec![0u64; cols]; rows];
+
+    // Initialize: one path starts at 'S' position
+    paths[0][start_col] = 1;
+
+    // Process each row, propagating paths downward
+    for row in 0..rows - 1 {
+        for col in 0..cols {
+            if paths[row][col] > 0 {
+                // Check what's in the next row at this column
+                let next_char = input[row + 1].chars().nth(col).unwrap();
+
+                match next_char {
+                    '.' => {
+                        // Path continues straight down
+                        paths[row + 1][col] += paths[row][col];
+                    }
+                    '^' => {
+                        // Path hits a splitter - splits into two paths (one left, one right)
+                        // Each path from current position creates one path going left and one going right
+                        if col > 0 {
+                            paths[row + 1][col - 1] += paths[row][col];
+                        }
+                        if col < cols - 1 {
+                            paths[row + 1][col + 1] += paths[row][col];
+                        }
+                    }
+                    _ => {
+                        // 'S' shouldn't appear in later rows, but handle gracefully
+                    }
+                }
+            }
+        }
+    }
+
+    // Sum all paths in the last row
+    paths[rows - 1].iter().sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_advent::example_input;
 
     #[test]
     fn test_example1() {
@@ -95,24 +156,7 @@ mod tests {
 
     #[test]
     fn test_example3() {
-        let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
-        ];
+        let input = example_input!("07/example1");
         assert_eq!(part1(&input), 21);
     }
 
@@ -217,24 +261,7 @@ mod tests {
 
     #[test]
     fn test_part2_example3() {
-        let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
-        ];
+        let input = example_input!("07/example1");
         // 40 paths
         assert_eq!(part2(&input), 40);
     }
@@ -331,63 +358,3 @@ mod tests {
         assert_eq!(part2(&input), 4);
     }
 }
-
-/// Part 2: Count possible paths (beam takes either left or right at each splitter)
-fn part2(input: &[String]) -> u64 {
-    if input.is_empty() || input.len() == 1 {
-        // Need at least 2 rows for a path to propagate
-        return 0;
-    }
-
-    let rows = input.len();
-    let cols = input[0].len();
-
-    // Find the start position 'S'
-    let mut start_col = 0;
-    for (col, ch) in input[0].chars().enumerate() {
-        if ch == 'S' {
-            start_col = col;
-            break;
-        }
-    }
-
-    // Track the number of paths reaching each position (row, col)
-    // Use u64 to handle large numbers of paths
-    let mut paths = vec![vec![0u64; cols]; rows];
-
-    // Initialize: one path starts at 'S' position
-    paths[0][start_col] = 1;
-
-    // Process each row, propagating paths downward
-    for row in 0..rows - 1 {
-        for col in 0..cols {
-            if paths[row][col] > 0 {
-                // Check what's in the next row at this column
-                let next_char = input[row + 1].chars().nth(col).unwrap();
-
-                match next_char {
-                    '.' => {
-                        // Path continues straight down
-                        paths[row + 1][col] += paths[row][col];
-                    }
-                    '^' => {
-                        // Path hits a splitter - splits into two paths (one left, one right)
-                        // Each path from current position creates one path going left and one going right
-                        if col > 0 {
-                            paths[row + 1][col - 1] += paths[row][col];
-                        }
-                        if col < cols - 1 {
-                            paths[row + 1][col + 1] += paths[row][col];
-                        }
-                    }
-                    _ => {
-                        // 'S' shouldn't appear in later rows, but handle gracefully
-                    }
-                }
-            }
-        }
-    }
-
-    // Sum all paths in the last row
-    paths[rows - 1].iter().sum()
-}
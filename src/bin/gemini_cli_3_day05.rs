@@ -10,7 +10,7 @@ fn main() -> std::io::Result<()> {
 /// The algorithm first merges overlapping ranges into a set of disjoint, sorted ranges.
 /// Then, it checks each value against these disjoint ranges using binary search for efficiency.
 /// Complexity: O(R log R + V log R), where R is the number of ranges and V is the number of values.
-fn part1(input: &rust_advent::RangeData) -> usize {
+pub fn part1(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() || input.values.is_empty() {
         return 0;
     }
@@ -64,7 +64,7 @@ fn part1(input: &rust_advent::RangeData) -> usize {
 /// The algorithm merges overlapping ranges and calculates the sum "on the fly".
 /// This avoids allocating a secondary vector for merged ranges.
 /// Complexity: O(R log R), where R is the number of ranges.
-fn part2(input: &rust_advent::RangeData) -> usize {
+pub fn part2(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() {
         return 0;
     }
@@ -15,7 +15,7 @@ fn main() -> std::io::Result<()> {
 ///          as a single character direction (L or R) followed by a number of clicks.
 /// Returns:
 ///   The number of times the dial is pointing at 0 after a rotation.
-fn part1(inputs: &[String]) -> usize {
+pub fn part1(inputs: &[String]) -> usize {
     let mut current_pos: i32 = 50;
     let mut zero_count: usize = 0;
 
@@ -65,7 +65,7 @@ fn part1(inputs: &[String]) -> usize {
 ///          as a single character direction (L or R) followed by a number of clicks.
 /// Returns:
 ///   The number of times the dial is pointing at 0 at any point during a rotation.
-fn part2(inputs: &[String]) -> i64 {
+pub fn part2(inputs: &[String]) -> i64 {
     let mut current_pos: i64 = 50;
     let mut zero_count: i64 = 0;
 
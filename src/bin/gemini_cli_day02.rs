@@ -14,7 +14,7 @@ fn main() -> std::io::Result<()> {
 ///
 /// For example 1-22,30-50 contains the values 11, 22, 33, and 44
 /// which sum to 110.
-fn part1(ranges: &str) -> u64 {
+pub fn part1(ranges: &str) -> u64 {
     let parsed_ranges = parse_ranges(ranges);
     let mut total_sum: u128 = 0;
 
@@ -63,7 +63,7 @@ fn sum_invalid_in_range(start: u64, end: u64) -> u64 {
 
         // We need x * M >= start => x >= ceil(start / M)
         // (start + M - 1) / M
-        let min_x_needed = (start_u128 + m - 1) / m;
+        let min_x_needed = start_u128.div_ceil(m);
 
         // We need x * M <= end => x <= floor(end / M)
         let max_x_needed = end_u128 / m;
@@ -86,7 +86,7 @@ fn sum_invalid_in_range(start: u64, end: u64) -> u64 {
 ///
 /// An ID is invalid if it is made only of some sequence of digits repeated at least twice.
 /// Returns the sum of all invalid IDs in the given ranges.
-fn part2(ranges: &str) -> u64 {
+pub fn part2(ranges: &str) -> u64 {
     let parsed_ranges = parse_ranges(ranges);
     let mut total_sum: u128 = 0;
 
@@ -138,9 +138,9 @@ fn sum_invalid_in_range_part2(start: u64, end: u64) -> u64 {
         for i in 1..num_subsets {
             let mut subset = Vec::new();
             let mut set_bits = 0;
-            for bit in 0..num_primes {
+            for (bit, &prime) in primes.iter().enumerate() {
                 if (i >> bit) & 1 == 1 {
-                    subset.push(primes[bit]);
+                    subset.push(prime);
                     set_bits += 1;
                 }
             }
@@ -186,7 +186,7 @@ fn sum_multiples_in_range(
     r_max: u128,
 ) -> u128 {
     // X * m >= r_min => X >= ceil(r_min / m)
-    let min_x_needed = (r_min + m - 1) / m;
+    let min_x_needed = r_min.div_ceil(m);
     // X * m <= r_max => X <= floor(r_max / m)
     let max_x_needed = r_max / m;
 
@@ -207,9 +207,9 @@ fn get_distinct_prime_factors(mut n: u32) -> Vec<u32> {
     let mut factors = Vec::new();
     let mut d = 2;
     while d * d <= n {
-        if n % d == 0 {
+        if n.is_multiple_of(d) {
             factors.push(d);
-            while n % d == 0 {
+            while n.is_multiple_of(d) {
                 n /= d;
             }
         }
@@ -328,7 +328,7 @@ mod tests {
         assert_eq!(get_distinct_prime_factors(6), vec![2, 3]);
         assert_eq!(get_distinct_prime_factors(12), vec![2, 3]);
         assert_eq!(get_distinct_prime_factors(5), vec![5]);
-        assert_eq!(get_distinct_prime_factors(1), vec![]);
+        assert_eq!(get_distinct_prime_factors(1), Vec::<u32>::new());
 
         assert_eq!(lcm(2, 3), 6);
         assert_eq!(lcm(4, 6), 12);
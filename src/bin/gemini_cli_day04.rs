@@ -14,7 +14,7 @@ fn main() -> std::io::Result<()> {
 const OBJECT: u8 = b'@';
 const THRESHOLD: usize = 4;
 
-fn part1(inputs: &[String]) -> usize {
+pub fn part1(inputs: &[String]) -> usize {
     let rows = inputs.len();
     if rows == 0 {
         return 0;
@@ -35,7 +35,7 @@ fn part1(inputs: &[String]) -> usize {
     count
 }
 
-fn part2(inputs: &[String]) -> usize {
+pub fn part2(inputs: &[String]) -> usize {
     let rows = inputs.len();
     if rows == 0 {
         return 0;
@@ -52,10 +52,8 @@ fn part2(inputs: &[String]) -> usize {
         // Identify all objects to remove in this generation
         for r in 0..rows {
             for c in 0..cols {
-                if grid[r][c] == OBJECT {
-                    if has_fewer_neighbors_than(&grid, r, c, THRESHOLD) {
-                        to_remove.push((r, c));
-                    }
+                if grid[r][c] == OBJECT && has_fewer_neighbors_than(&grid, r, c, THRESHOLD) {
+                    to_remove.push((r, c));
                 }
             }
         }
@@ -104,12 +102,15 @@ fn has_fewer_neighbors_than<R: AsRef<[u8]>>(
             let nr = r as isize + dr;
             let nc = c as isize + dc;
 
-            if nr >= 0 && nr < rows as isize && nc >= 0 && nc < cols as isize {
-                if grid[nr as usize].as_ref()[nc as usize] == OBJECT {
-                    count += 1;
-                    if count >= threshold {
-                        return false;
-                    }
+            if nr >= 0
+                && nr < rows as isize
+                && nc >= 0
+                && nc < cols as isize
+                && grid[nr as usize].as_ref()[nc as usize] == OBJECT
+            {
+                count += 1;
+                if count >= threshold {
+                    return false;
                 }
             }
         }
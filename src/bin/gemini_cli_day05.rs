@@ -38,7 +38,7 @@ fn merge_ranges(ranges: &[(isize, isize)]) -> Vec<(isize, isize)> {
 /// one input.range, where each range is an inclusive interval [start, end].
 /// Ranges may overlap, but a value that is in multiple ranges should only
 /// count once.
-fn part1(input: &rust_advent::RangeData) -> usize {
+pub fn part1(input: &rust_advent::RangeData) -> usize {
     let merged_ranges = merge_ranges(&input.ranges);
 
     input
@@ -58,7 +58,7 @@ fn part1(input: &rust_advent::RangeData) -> usize {
         .count()
 }
 
-fn part2(input: &rust_advent::RangeData) -> usize {
+pub fn part2(input: &rust_advent::RangeData) -> usize {
     if input.ranges.is_empty() {
         return 0;
     }
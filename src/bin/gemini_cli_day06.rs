@@ -1,3 +1,4 @@
+use rust_advent::Checked;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -15,7 +16,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 /// Part 1: Homework
 ///
 /// Converts lines into homework problems, then performs the problems.
-fn part1(input: &[String]) -> Result<i64, String> {
+pub fn part1(input: &[String]) -> Result<i64, String> {
     if input.is_empty() {
         return Err("Input is empty".to_string());
     }
@@ -49,12 +50,12 @@ fn part1(input: &[String]) -> Result<i64, String> {
         ));
     }
 
-    let mut accumulators: Vec<i64> = Vec::with_capacity(m);
-    for (_, token) in first_line_tokens.iter().enumerate() {
+    let mut accumulators: Vec<Checked<i64>> = Vec::with_capacity(m);
+    for token in first_line_tokens.iter() {
         let num = token
             .parse::<i64>()
             .map_err(|_| format!("Invalid number '{}' at line 1", token))?;
-        accumulators.push(num);
+        accumulators.push(Checked::new(num));
     }
 
     // Process remaining lines
@@ -83,10 +84,14 @@ fn part1(input: &[String]) -> Result<i64, String> {
         }
     }
 
-    Ok(accumulators.iter().sum())
+    accumulators
+        .into_iter()
+        .sum::<Checked<i64>>()
+        .get()
+        .ok_or_else(|| "Total overflows i64".to_string())
 }
 
-fn part2(input: &[String]) -> i64 {
+pub fn part2(input: &[String]) -> i64 {
     // 1. Validation
     if input.len() < 4 {
         panic!("Input must have at least 3 lines of numbers and 1 line of operators");
@@ -114,7 +119,7 @@ fn part2(input: &[String]) -> i64 {
         }
     }
 
-    let mut total_sum: i64 = 0;
+    let mut total_sum = Checked::new(0i64);
 
     // 4. Process Each Problem
     for &start_col in &problem_starts {
@@ -157,8 +162,8 @@ fn part2(input: &[String]) -> i64 {
         for col in (start_col..end_col).rev() {
             // Build number string from rows 0 to num_rows-1
             let mut num_str = String::new();
-            for r in 0..num_rows {
-                let ch = padded_input[r][col];
+            for row in &padded_input[..num_rows] {
+                let ch = row[col];
                 if !ch.is_whitespace() {
                     num_str.push(ch);
                 }
@@ -175,7 +180,7 @@ fn part2(input: &[String]) -> i64 {
             continue;
         }
 
-        let mut result = numbers[0];
+        let mut result = Checked::new(numbers[0]);
         for &num in &numbers[1..] {
             match op_char {
                 '+' => result += num,
@@ -186,7 +191,7 @@ fn part2(input: &[String]) -> i64 {
         total_sum += result;
     }
 
-    total_sum
+    total_sum.get().expect("Total overflows i64")
 }
 
 #[cfg(test)]
@@ -297,4 +302,15 @@ mod tests {
         // Total: 1058 + 3253600 + 625 + 8544 = 3263827
         assert_eq!(part2(&input), 3263827);
     }
+
+    #[test]
+    fn test_error_overflow() {
+        let input = vec![
+            "4294967296 1".to_string(),
+            "4294967296 1".to_string(),
+            "2 1".to_string(),
+            "* +".to_string(),
+        ];
+        assert_eq!(part1(&input), Err("Total overflows i64".to_string()));
+    }
 }
@@ -5,7 +5,7 @@ fn main() -> std::io::Result<()> {
     Ok(())
 }
 
-fn part1(input: &[String]) -> u64 {
+pub fn part1(input: &[String]) -> u64 {
     if input.is_empty() {
         return 0;
     }
@@ -68,7 +68,7 @@ fn part1(input: &[String]) -> u64 {
     split_count
 }
 
-fn part2(input: &[String]) -> u64 {
+pub fn part2(input: &[String]) -> u64 {
     if input.is_empty() {
         return 0;
     }
@@ -135,6 +135,7 @@ fn part2(input: &[String]) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_advent::example_input;
 
     #[test]
     fn test_part1_example_1() {
@@ -160,24 +161,7 @@ mod tests {
 
     #[test]
     fn test_part1_example_3_large() {
-        let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
-        ];
+        let input = example_input!("07/example1");
         assert_eq!(part1(&input), 21);
     }
 
@@ -215,24 +199,7 @@ mod tests {
 
     #[test]
     fn test_part2_example_3_large() {
-        let input = vec![
-            ".......S.......".to_string(),
-            "...............".to_string(),
-            ".......^.......".to_string(),
-            "...............".to_string(),
-            "......^.^......".to_string(),
-            "...............".to_string(),
-            ".....^.^.^.....".to_string(),
-            "...............".to_string(),
-            "....^.^...^....".to_string(),
-            "...............".to_string(),
-            "...^.^...^.^...".to_string(),
-            "...............".to_string(),
-            "..^...^.....^..".to_string(),
-            "...............".to_string(),
-            ".^.^.^.^.^...^.".to_string(),
-            "...............".to_string(),
-        ];
+        let input = example_input!("07/example1");
         assert_eq!(part2(&input), 40);
     }
 }
@@ -1,11 +1,12 @@
 use rayon::prelude::*;
-use rust_advent::Point;
-use std::collections::BinaryHeap;
+use rust_advent::parallel::BoundedHeap;
+use rust_advent::{Point, product_checked};
 
 fn main() -> std::io::Result<()> {
     let inputs = rust_advent::read_points("08")?;
-    println!("Part 1: {}", part1(1000, 3, &inputs));
-    println!("Part 2: {}", part2(&inputs));
+    let overflow = || std::io::Error::new(std::io::ErrorKind::InvalidData, "answer overflows");
+    println!("Part 1: {}", part1(1000, 3, &inputs).ok_or_else(overflow)?);
+    println!("Part 2: {}", part2(&inputs).ok_or_else(overflow)?);
     Ok(())
 }
 
@@ -48,78 +49,48 @@ impl Dsu {
     }
 }
 
-fn part1(n: usize, m: usize, inputs: &[Point]) -> usize {
+pub fn part1(n: usize, m: usize, inputs: &[Point]) -> Option<usize> {
     if n == 0 {
         return calculate_result(m, inputs.len(), &Dsu::new(inputs.len()));
     }
 
     let num_points = inputs.len();
-    let max_edges = if num_points > 0 {
-        num_points.saturating_mul(num_points - 1) / 2
-    } else {
-        0
-    };
-    let heap_capacity = n.min(max_edges).saturating_add(1);
 
+    // Pairs are ranked by (distance, i, j), so which of several equally
+    // close pairs get connected does not depend on the thread split.
     let final_heap = (0..num_points)
         .into_par_iter()
         .fold(
-            || BinaryHeap::with_capacity(heap_capacity),
+            || BoundedHeap::new(n),
             |mut local_heap, i| {
                 let p1 = &inputs[i];
                 for (j, p2) in inputs.iter().enumerate().skip(i + 1) {
                     let dist_sq = (p1.x as i64 - p2.x as i64).pow(2)
                         + (p1.y as i64 - p2.y as i64).pow(2)
                         + (p1.z as i64 - p2.z as i64).pow(2);
-
-                    if local_heap.len() < n {
-                        local_heap.push((dist_sq, i, j));
-                    } else if let Some(&(max_dist, _, _)) = local_heap.peek()
-                        && dist_sq < max_dist
-                    {
-                        local_heap.pop();
-                        local_heap.push((dist_sq, i, j));
-                    }
+                    local_heap.push((dist_sq, i, j));
                 }
                 local_heap
             },
         )
-        .reduce(BinaryHeap::new, |mut h1, mut h2| {
-            if h1.is_empty() {
-                return h2;
-            }
-            if h2.is_empty() {
-                return h1;
-            }
-            while let Some(item) = h2.pop() {
-                if h1.len() < n {
-                    h1.push(item);
-                } else if let Some(&max) = h1.peek()
-                    && item.0 < max.0
-                {
-                    h1.pop();
-                    h1.push(item);
-                }
-            }
-            h1
-        });
+        .reduce(|| BoundedHeap::new(n), BoundedHeap::merge);
 
     let mut dsu = Dsu::new(num_points);
-    for (_, u, v) in final_heap {
+    for (_, u, v) in final_heap.into_sorted_vec() {
         dsu.union(u, v);
     }
 
     calculate_result(m, num_points, &dsu)
 }
 
-fn calculate_result(m: usize, num_points: usize, dsu: &Dsu) -> usize {
+fn calculate_result(m: usize, num_points: usize, dsu: &Dsu) -> Option<usize> {
     let mut component_sizes: Vec<usize> = (0..num_points)
         .filter(|&i| dsu.parent[i] == i)
         .map(|i| dsu.size[i])
         .collect();
 
     component_sizes.sort_unstable_by(|a, b| b.cmp(a));
-    component_sizes.iter().take(m).product()
+    product_checked(component_sizes.into_iter().take(m))
 }
 
 #[derive(Clone, Copy, Default)]
@@ -155,10 +126,10 @@ fn radix_sort_edges_safe(edges: &mut Vec<Edge>) {
     }
 }
 
-fn part2(inputs: &[Point]) -> usize {
+pub fn part2(inputs: &[Point]) -> Option<usize> {
     let num_points = inputs.len();
     if num_points < 2 {
-        return 0;
+        return Some(0);
     }
 
     let mut edges: Vec<Edge> = (0..num_points)
@@ -179,6 +150,8 @@ fn part2(inputs: &[Point]) -> usize {
         })
         .collect();
 
+    // The radix sort is stable and edges arrive in (i, j) order, so equal
+    // distances stay in index order, as in part 1.
     radix_sort_edges_safe(&mut edges);
 
     let mut dsu = Dsu::new(num_points);
@@ -188,11 +161,12 @@ fn part2(inputs: &[Point]) -> usize {
         if dsu.union(edge.u, edge.v) {
             components -= 1;
             if components == 1 {
-                return (inputs[edge.u].x as usize) * (inputs[edge.v].x as usize);
+                let x = |idx: usize| usize::try_from(inputs[idx].x).ok();
+                return product_checked([x(edge.u)?, x(edge.v)?]);
             }
         }
     }
-    0
+    Some(0)
 }
 
 #[cfg(test)]
@@ -206,8 +180,8 @@ mod tests {
             Point { x: 2, y: 2, z: 2 },
             Point { x: 2, y: 3, z: 2 },
         ];
-        assert_eq!(part1(1, 1, &inputs), 2);
-        assert_eq!(part1(1, 2, &inputs), 2);
+        assert_eq!(part1(1, 1, &inputs), Some(2));
+        assert_eq!(part1(1, 2, &inputs), Some(2));
     }
 
     #[test]
@@ -314,8 +288,8 @@ mod tests {
                 z: 689,
             },
         ];
-        assert_eq!(part1(3, 1, &inputs), 3);
-        assert_eq!(part1(3, 2, &inputs), 6);
+        assert_eq!(part1(3, 1, &inputs), Some(3));
+        assert_eq!(part1(3, 2, &inputs), Some(6));
     }
 
     #[test]
@@ -325,7 +299,7 @@ mod tests {
             Point { x: 2, y: 3, z: 4 },
             Point { x: 3, y: 5, z: 6 },
         ];
-        assert_eq!(part2(&inputs), 2);
+        assert_eq!(part2(&inputs), Some(2));
     }
 
     #[test]
@@ -335,14 +309,14 @@ mod tests {
             Point { x: 2, y: 2, z: 2 },
             Point { x: 5, y: 5, z: 5 },
         ];
-        assert_eq!(part1(10, 1, &inputs), 3);
+        assert_eq!(part1(10, 1, &inputs), Some(3));
     }
 
     #[test]
     fn test_part1_m_zero() {
         let inputs = vec![Point { x: 0, y: 0, z: 0 }, Point { x: 1, y: 0, z: 0 }];
-        assert_eq!(part1(0, 0, &inputs), 1);
-        assert_eq!(part1(1, 0, &inputs), 1);
+        assert_eq!(part1(0, 0, &inputs), Some(1));
+        assert_eq!(part1(1, 0, &inputs), Some(1));
     }
 
     #[test]
@@ -352,7 +326,7 @@ mod tests {
             Point { x: 1, y: 0, z: 0 },
             Point { x: 2, y: 0, z: 0 },
         ];
-        assert_eq!(part1(usize::MAX, 1, &inputs), 3);
+        assert_eq!(part1(usize::MAX, 1, &inputs), Some(3));
     }
 
     #[test]
@@ -362,14 +336,14 @@ mod tests {
             Point { x: 10, y: 0, z: 0 },
             Point { x: 20, y: 0, z: 0 },
         ];
-        assert_eq!(part1(0, usize::MAX, &inputs), 1);
+        assert_eq!(part1(0, usize::MAX, &inputs), Some(1));
     }
 
     #[test]
     fn test_part1_single_point() {
         let inputs = vec![Point { x: 0, y: 0, z: 0 }];
-        assert_eq!(part1(10, 1, &inputs), 1);
-        assert_eq!(part1(0, 5, &inputs), 1);
+        assert_eq!(part1(10, 1, &inputs), Some(1));
+        assert_eq!(part1(0, 5, &inputs), Some(1));
     }
 
     #[test]
@@ -476,7 +450,7 @@ mod tests {
                 z: 689,
             },
         ];
-        assert_eq!(part2(&inputs), 25272);
+        assert_eq!(part2(&inputs), Some(25272));
     }
 
     #[test]
@@ -486,7 +460,7 @@ mod tests {
             Point { x: 0, y: 0, z: 0 },
             Point { x: 10, y: 0, z: 0 },
         ];
-        assert_eq!(part1(1, 1, &inputs), 2);
+        assert_eq!(part1(1, 1, &inputs), Some(2));
     }
 
     #[test]
@@ -496,14 +470,14 @@ mod tests {
             Point { x: 2, y: 2, z: 2 },
             Point { x: 5, y: 5, z: 5 },
         ];
-        assert_eq!(part1(0, 1, &inputs), 1);
-        assert_eq!(part1(0, 3, &inputs), 1);
+        assert_eq!(part1(0, 1, &inputs), Some(1));
+        assert_eq!(part1(0, 3, &inputs), Some(1));
     }
 
     #[test]
     fn test_part1_insufficient_m() {
         let inputs = vec![Point { x: 0, y: 0, z: 0 }, Point { x: 10, y: 0, z: 0 }];
-        assert_eq!(part1(0, 5, &inputs), 1);
+        assert_eq!(part1(0, 5, &inputs), Some(1));
     }
 
     #[test]
@@ -514,7 +488,7 @@ mod tests {
             Point { x: 100, y: 0, z: 0 },
             Point { x: 101, y: 0, z: 0 },
         ];
-        assert_eq!(part2(&inputs), 100);
+        assert_eq!(part2(&inputs), Some(100));
     }
 
     #[test]
@@ -524,6 +498,49 @@ mod tests {
             Point { x: 20, y: 0, z: 0 },
             Point { x: 40, y: 0, z: 0 },
         ];
-        assert_eq!(part2(&inputs), 800);
+        assert_eq!(part2(&inputs), Some(800));
+    }
+
+    #[test]
+    fn test_part2_negative_x() {
+        // The last edge joins x=-5 and x=1, whose product is not a usize
+        let inputs = vec![
+            Point { x: -5, y: 0, z: 0 },
+            Point { x: 1, y: 0, z: 0 },
+            Point { x: 4, y: 0, z: 0 },
+        ];
+        assert_eq!(part2(&inputs), None);
+    }
+
+    #[test]
+    fn test_part1_ties_are_deterministic() {
+        // A 4x4x4 lattice has 144 pairs at distance 1; connecting 50 of them
+        // depends on which ties win.
+        let inputs: Vec<Point> = (0..64)
+            .map(|i| Point {
+                x: i % 4,
+                y: i / 4 % 4,
+                z: i / 16,
+            })
+            .collect();
+
+        let mut pairs: Vec<(i64, usize, usize)> = (0..64)
+            .flat_map(|i| (i + 1..64).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let (a, b) = (&inputs[i], &inputs[j]);
+                let d = |u: i32, v: i32| (u as i64 - v as i64).pow(2);
+                (d(a.x, b.x) + d(a.y, b.y) + d(a.z, b.z), i, j)
+            })
+            .collect();
+        pairs.sort_unstable();
+        let mut dsu = Dsu::new(inputs.len());
+        for &(_, i, j) in &pairs[..50] {
+            dsu.union(i, j);
+        }
+        let expected = calculate_result(3, inputs.len(), &dsu);
+
+        for _ in 0..20 {
+            assert_eq!(part1(50, 3, &inputs), expected);
+        }
     }
 }
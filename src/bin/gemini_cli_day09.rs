@@ -1,4 +1,7 @@
+use rust_advent::Error;
 use rust_advent::Point2d;
+use rust_advent::geo::{GridRect, RectilinearPolygon};
+use rust_advent::sweep::Events;
 
 fn main() -> std::io::Result<()> {
     let inputs = rust_advent::read_points2d("09")?;
@@ -11,8 +14,7 @@ fn main() -> std::io::Result<()> {
 }
 
 fn cross_product(o: &Point2d, a: &Point2d, b: &Point2d) -> i64 {
-    (a.x as i64 - o.x as i64) * (b.y as i64 - o.y as i64)
-        - (a.y as i64 - o.y as i64) * (b.x as i64 - o.x as i64)
+    a.dx_i64(*o) * b.dy_i64(*o) - a.dy_i64(*o) * b.dx_i64(*o)
 }
 
 /// Computes the convex hull of the given points using the Monotone Chain algorithm.
@@ -78,7 +80,7 @@ fn get_convex_hull(points: &[Point2d]) -> Vec<Point2d> {
     hull
 }
 
-fn part1(inputs: &[Point2d]) -> usize {
+pub fn part1(inputs: &[Point2d]) -> usize {
     if inputs.len() < 2 {
         return 0;
     }
@@ -92,12 +94,7 @@ fn part1(inputs: &[Point2d]) -> usize {
         for j in i + 1..hull.len() {
             let p1 = &hull[i];
             let p2 = &hull[j];
-            // Use u64 for calculation to prevent overflow on 32-bit systems
-            // or with very large coordinates.
-            // MUST cast to i64 before subtraction to avoid i32 overflow if points are far apart.
-            let width = (p1.x as i64 - p2.x as i64).abs() as u64 + 1;
-            let height = (p1.y as i64 - p2.y as i64).abs() as u64 + 1;
-            let area = width * height;
+            let area = GridRect::from_corners(*p1, *p2).inclusive_area();
             if area > max_area {
                 max_area = area;
             }
@@ -106,10 +103,14 @@ fn part1(inputs: &[Point2d]) -> usize {
     max_area as usize
 }
 
-fn part2(inputs: &[Point2d]) -> Result<usize, String> {
+pub fn part2(inputs: &[Point2d]) -> Result<usize, String> {
     if inputs.len() < 4 {
         return Ok(0);
     }
+    // The sweep below assumes a simple rectilinear loop
+    RectilinearPolygon::new(inputs.to_vec())
+        .validate()
+        .map_err(Error::from)?;
 
     // 1. Collect unique sorted coordinates (Coordinate Compression)
     let mut xs: Vec<i32> = inputs.iter().map(|p| p.x).collect();
@@ -131,14 +132,10 @@ fn part2(inputs: &[Point2d]) -> Result<usize, String> {
         return Ok(0);
     }
 
-    // 2. Identify Vertical Edges of the Polygon
-    // Store as (x, y_min, y_max). Vertices connect inputs[i] -> inputs[i+1].
-    struct VEdge {
-        x: i32,
-        y_min: i32,
-        y_max: i32,
-    }
-    let mut v_edges = Vec::new();
+    // 2. Sweep up through the Y bands, keeping the X of each vertical edge
+    // spanning the band active. Every vertex ends a vertical edge, so each
+    // band boundary in ys is an event coordinate.
+    let mut edges = Events::new();
     let len = inputs.len();
     for i in 0..len {
         let p1 = &inputs[i];
@@ -147,54 +144,35 @@ fn part2(inputs: &[Point2d]) -> Result<usize, String> {
             // Vertical edge
             let y_min = std::cmp::min(p1.y, p2.y);
             let y_max = std::cmp::max(p1.y, p2.y);
-            v_edges.push(VEdge {
-                x: p1.x,
-                y_min,
-                y_max,
-            });
-        } else if p1.y != p2.y {
-            // Not rectilinear!
-            return Err(format!(
-                "Input polygon is not rectilinear: segment {:?} -> {:?}",
-                p1, p2
-            ));
+            edges.add_interval(y_min as i64, y_max as i64, p1.x);
         }
     }
 
-    // 3. Build Grid Status (Sweep Line)
+    // 3. Build Grid Status
     // grid[x_idx][y_idx] is true if the cell [xs[x], xs[x+1]] x [ys[y], ys[y+1]] is INSIDE.
     // Dimensions: (m-1) x (n-1)
     let mut grid = vec![vec![0u8; n - 1]; m - 1];
 
-    for j in 0..n - 1 {
-        // Current Y band: ys[j] to ys[j+1]
-        let y_start = ys[j];
-        let y_end = ys[j + 1];
-
-        // Find relevant edges covering this band.
-        // A vertical edge covers this band if edge.y_min <= y_start AND edge.y_max >= y_end.
-        // Since y coordinates are from the set of vertex coordinates, edges start/end exactly at band boundaries.
-        let mut row_edges: Vec<&VEdge> = v_edges
-            .iter()
-            .filter(|e| e.y_min <= y_start && e.y_max >= y_end)
-            .collect();
-        row_edges.sort_by_key(|e| e.x);
+    edges.sweep(|y, next, active| {
+        if next.is_none() {
+            return;
+        }
+        // Current Y band: y to the next event
+        let j = get_idx(y as i32, &ys);
+        let mut row_edges = active.iter().peekable();
 
         let mut parity = 0; // 0: outside, 1: inside
-        let mut edge_idx = 0;
 
         for i in 0..m - 1 {
             // Current X cell: xs[i] to xs[i+1]
             // We need to check how many edges are to the LEFT of this cell.
             // Since edges are on grid lines, we process edges at x <= xs[i].
-
-            while edge_idx < row_edges.len() && row_edges[edge_idx].x <= xs[i] {
+            while row_edges.next_if(|&&x| x <= xs[i]).is_some() {
                 parity ^= 1;
-                edge_idx += 1;
             }
             grid[i][j] = parity;
         }
-    }
+    });
 
     // 4. Build 2D Prefix Sums
     // prefix[i][j] stores sum of grid[0..i][0..j]
@@ -240,9 +218,8 @@ fn part2(inputs: &[Point2d]) -> Result<usize, String> {
 
             if total_cells > 0 {
                 if count_valid(ix_min, iy_min, ix_max, iy_max) == total_cells {
-                    let width = (p1.x as i64 - p2.x as i64).abs() as u64 + 1;
-                    let height = (p1.y as i64 - p2.y as i64).abs() as u64 + 1;
-                    max_area = std::cmp::max(max_area, width * height);
+                    max_area =
+                        std::cmp::max(max_area, GridRect::from_corners(*p1, *p2).inclusive_area());
                 }
             } else {
                 // For degenerate rectangles (lines/points), a single point is always valid with area 1.
@@ -397,6 +374,34 @@ mod tests {
         assert_eq!(part2(&inputs).unwrap(), 8);
     }
 
+    #[test]
+    fn test_part2_rejects_invalid_polygons() {
+        let corners = |list: &[(i32, i32)]| -> Vec<Point2d> {
+            list.iter().map(|&(x, y)| Point2d { x, y }).collect()
+        };
+        let diagonal = corners(&[(0, 0), (4, 0), (4, 4), (1, 5)]);
+        assert_eq!(
+            part2(&diagonal).unwrap_err(),
+            "unsupported: edge 2 from Point2d { x: 4, y: 4 } to Point2d { x: 1, y: 5 } is not rectilinear"
+        );
+        let figure_eight = corners(&[(0, 0), (2, 0), (2, 4), (4, 4), (4, 2), (0, 2)]);
+        assert!(part2(&figure_eight).is_err());
+        let slot = corners(&[
+            (0, 0),
+            (6, 0),
+            (6, 6),
+            (0, 6),
+            (0, 4),
+            (6, 4),
+            (6, 2),
+            (0, 2),
+        ]);
+        assert_eq!(
+            part2(&slot).unwrap_err(),
+            "unsupported: edges 1 and 4 intersect"
+        );
+    }
+
     #[test]
     fn test_part2_dumbbell() {
         // Two 3x3 boxes connected by a 1x1 pipe.
@@ -1,13 +1,24 @@
-use rayon::prelude::*;
-use std::collections::{HashMap, VecDeque};
+use rust_advent::Error;
+use rust_advent::gf2::{self, Gf2Matrix};
+use rust_advent::json;
+use rust_advent::memo::Memoizer;
+use rust_advent::parallel::solve_lines;
+use rust_advent::solution::Context;
+use rust_advent::validate::{ensure_at_most, ensure_in_range, ensure_len};
+use std::collections::VecDeque;
+
+/// Most part 2 subproblems remembered per line; hard lines evict the least
+/// recently used instead of growing without bound.
+const MAX_MEMO_ENTRIES: usize = 1 << 20;
 
 fn main() -> std::io::Result<()> {
     let inputs = rust_advent::read_file_as_lines("10")?;
-    match part1(&inputs) {
+    let ctx = Context::new();
+    match part1(&inputs, &ctx) {
         Ok(v) => println!("Part 1: {}", v),
         Err(e) => eprintln!("Part 1 Error: {}", e),
     }
-    match part2(&inputs) {
+    match part2(&inputs, &ctx) {
         Ok(v) => println!("Part 2: {}", v),
         Err(e) => eprintln!("Part 2 Error: {}", e),
     }
@@ -45,9 +56,7 @@ impl Problem {
             return Err("Endstate cannot be empty".to_string());
         }
         let num_positions = endstate_content.len();
-        if num_positions > 32 {
-            return Err(format!("Too many positions: {}", num_positions));
-        }
+        ensure_at_most("positions", num_positions, 32).map_err(Error::from)?;
 
         let mut target = 0u32;
         for (i, c) in endstate_content.chars().enumerate() {
@@ -84,12 +93,7 @@ impl Problem {
                         let idx = num_str
                             .parse::<usize>()
                             .map_err(|_| "Invalid number in step")?;
-                        if idx >= num_positions {
-                            return Err(format!(
-                                "Step index {} out of bounds (size {})",
-                                idx, num_positions
-                            ));
-                        }
+                        ensure_in_range("position", idx, 0..num_positions).map_err(Error::from)?;
                         step_mask |= 1 << idx;
                     }
                 }
@@ -101,16 +105,9 @@ impl Problem {
             return Err("No steps provided".to_string());
         }
 
-        if steps.len() >= 64 {
-            return Err(format!("Too many steps: {}", steps.len()));
-        }
-
-        if !target_counts.is_empty() && target_counts.len() != num_positions {
-            return Err(format!(
-                "Target counts length {} does not match positions {}",
-                target_counts.len(),
-                num_positions
-            ));
+        ensure_at_most("steps", steps.len(), gf2::MAX_COLUMNS).map_err(Error::from)?;
+        if !target_counts.is_empty() {
+            ensure_len("targets", &target_counts, num_positions).map_err(Error::from)?;
         }
 
         Ok(Problem {
@@ -124,16 +121,14 @@ impl Problem {
 
 /// Part 1: Minimum flips to reach endstate.
 /// Each step can be used 0 or 1 times (GF(2) logic).
-fn part1(input: &[String]) -> Result<u64, String> {
-    let results: Result<Vec<u64>, String> = input
-        .par_iter()
-        .map(|line| {
-            let p = Problem::parse(line)?;
-            solve_part1(&p).ok_or_else(|| format!("No solution found for: {}", line))
-        })
-        .collect();
-
-    Ok(results?.iter().sum())
+pub fn part1(input: &[String], ctx: &Context) -> Result<u64, String> {
+    let results = solve_lines(input, ctx, |line| {
+        let p = Problem::parse(line)?;
+        solve_part1(&p).ok_or_else(|| "No solution found".to_string())
+    })
+    .map_err(|e| e.to_string())?;
+
+    Ok(results.iter().sum())
 }
 
 /// Solves Part 1 using a hybrid strategy of BFS and Meet-in-the-Middle on Kernel Basis.
@@ -204,144 +199,42 @@ fn solve_part1_bfs(p: &Problem) -> Option<u64> {
 
 /// Solves Part 1 by finding the kernel of the step matrix and searching for a minimum-weight combination.
 fn solve_part1_mim(p: &Problem) -> Option<u64> {
-    let n = p.num_positions;
-    let m = p.steps.len();
-
-    // Build GF(2) matrix
-    let mut matrix = vec![0u64; n];
-    for (r, row) in matrix.iter_mut().enumerate().take(n) {
-        let mut row_val = 0u64;
-        for (c, step) in p.steps.iter().enumerate() {
-            if (step >> r) & 1 == 1 {
-                row_val |= 1 << c;
-            }
-        }
-        if (p.target >> r) & 1 == 1 {
-            row_val |= 1 << m;
-        }
-        *row = row_val;
-    }
-
-    // Gaussian Elimination to find Basis and Kernel
-    let mut pivots = Vec::new();
-    let mut next_row = 0;
-    let mut pivot_cols = vec![false; m];
-
-    for (c, is_pivot) in pivot_cols.iter_mut().enumerate().take(m) {
-        if next_row >= n {
-            break;
-        }
-        let mut pivot_row = None;
-        for (r, row) in matrix.iter().enumerate().take(n).skip(next_row) {
-            if (row >> c) & 1 == 1 {
-                pivot_row = Some(r);
-                break;
-            }
-        }
-        if let Some(pr) = pivot_row {
-            matrix.swap(next_row, pr);
-            let pivot_val = matrix[next_row];
-            for (r, row) in matrix.iter_mut().enumerate().take(n) {
-                if r != next_row && (*row >> c) & 1 == 1 {
-                    *row ^= pivot_val;
-                }
-            }
-            pivots.push((next_row, c));
-            *is_pivot = true;
-            next_row += 1;
-        }
-    }
-
-    // Consistency check
-    for row in matrix.iter().take(n).skip(next_row) {
-        if (row >> m) & 1 == 1 {
-            return None;
-        }
-    }
-
-    // Identify free variables and construct kernel basis
-    let mut free_cols = Vec::new();
-    for (c, &is_pivot) in pivot_cols.iter().enumerate().take(m) {
-        if !is_pivot {
-            free_cols.push(c);
-        }
-    }
-
-    let mut d_mask = 0u64;
-    for &(r, c) in &pivots {
-        if (matrix[r] >> m) & 1 == 1 {
-            d_mask |= 1 << c;
-        }
-    }
-
-    let mut kernel_basis = Vec::new();
-    for &f in &free_cols {
-        let mut vec = 1u64 << f;
-        for &(r, c) in &pivots {
-            if (matrix[r] >> f) & 1 == 1 {
-                vec |= 1 << c;
-            }
-        }
-        kernel_basis.push(vec);
-    }
-
+    // Pressing two equal steps cancels out, so repeats only grow the kernel.
+    let mut steps = p.steps.clone();
+    steps.retain(|&s| s != 0);
+    steps.sort_unstable();
+    steps.dedup();
+    let matrix = Gf2Matrix::from_columns(&steps, p.num_positions).ok()?;
     // Meet-in-the-Middle search on the kernel subspace
-    let k = kernel_basis.len();
-    if k == 0 {
-        return Some(d_mask.count_ones() as u64);
-    }
-
-    let k1 = k / 2;
-    let k2 = k - k1;
-    let basis1 = &kernel_basis[0..k1];
-    let basis2 = &kernel_basis[k1..k];
-
-    let mut sums2 = Vec::with_capacity(1 << k2);
-    sums2.push(0u64);
-    for &b in basis2 {
-        let len = sums2.len();
-        for i in 0..len {
-            sums2.push(sums2[i] ^ b);
-        }
-    }
-
-    let mut min_weight = u32::MAX;
-    let mut sums1 = vec![0u64];
-    for &b in basis1 {
-        let len = sums1.len();
-        for i in 0..len {
-            sums1.push(sums1[i] ^ b);
-        }
-    }
-
-    for val1 in sums1 {
-        let target_for_part2 = d_mask ^ val1;
-        for &val2 in &sums2 {
-            let w = (target_for_part2 ^ val2).count_ones();
-            if w < min_weight {
-                min_weight = w;
-            }
-        }
-    }
-
-    Some(min_weight as u64)
+    let solution = matrix.solve(p.target)?;
+    Some(solution.min_weight() as u64)
 }
 
 /// Part 2: Minimum total steps to reach exact target counts.
 /// Steps can be used any non-negative integral number of times (Diophantine system).
-fn part2(input: &[String]) -> Result<u64, String> {
-    let results: Result<Vec<u64>, String> = input
-        .par_iter()
-        .map(|line| {
-            let p = Problem::parse(line)?;
-            if p.target_counts.is_empty() {
-                return Err("Missing target counts for Part 2".to_string());
-            }
-            solve_part2(&p).ok_or_else(|| format!("No solution found for: {}", line))
-        })
-        .collect();
-
-    Ok(results?.iter().sum())
+pub fn part2(input: &[String], ctx: &Context) -> Result<u64, String> {
+    let results = solve_lines(input, ctx, |line| {
+        let p = Problem::parse(line)?;
+        if p.target_counts.is_empty() {
+            return Err("Missing target counts for Part 2".to_string());
+        }
+        let mut memo = Memoizer::new().with_max_entries(MAX_MEMO_ENTRIES);
+        let presses = solve_part2(&p, &mut memo);
+        if ctx.is_tracing() {
+            let stats = memo.stats();
+            ctx.trace(json!({
+                "part": 2u64,
+                "memo_hits": stats.hits,
+                "memo_misses": stats.misses,
+                "memo_evictions": stats.evictions,
+                "memo_peak": stats.peak_entries,
+            }));
+        }
+        presses.ok_or_else(|| "No solution found".to_string())
+    })
+    .map_err(|e| e.to_string())?;
+
+    Ok(results.iter().sum())
 }
 
 /// Solves Part 2 using a recursive approach based on parity decomposition.
@@ -359,7 +252,9 @@ fn part2(input: &[String]) -> Result<u64, String> {
 ///    is guaranteed to be even at every position.
 /// 3. Divide the residual by 2 and recurse.
 /// 4. The total cost is (steps in configuration) + 2 * (cost of recursive subproblem).
-fn solve_part2(p: &Problem) -> Option<u64> {
+///
+/// Subproblems are remembered in `memo`, which may be capped.
+fn solve_part2(p: &Problem, memo: &mut Memoizer<Vec<u32>, Option<u64>>) -> Option<u64> {
     // 1. Preprocess steps: remove 0s and duplicates to reduce search space.
     let mut distinct_steps = p.steps.clone();
     distinct_steps.retain(|&s| s != 0);
@@ -378,182 +273,48 @@ fn solve_part2(p: &Problem) -> Option<u64> {
     // 2. Initialize Solver and Memoization table.
     // The solver handles the linear algebra over GF(2) to find parity matches.
     let solver = GF2Solver::new(&distinct_steps, p.num_positions);
-    let mut memo = HashMap::new();
-
-    solve_part2_recursive_parity(p.target_counts.clone(), &solver, &mut memo)
+    solve_part2_recursive_parity(p.target_counts.clone(), &solver, memo)
 }
 
 struct GF2Solver {
     n: usize,
     m: usize,
     steps: Vec<u32>,
-    kernel_basis: Vec<u64>,
+    matrix: Gf2Matrix,
 }
 
 impl GF2Solver {
     /// Constructs a solver for the given steps.
-    /// Performs Gaussian Elimination to find the basis of the kernel (null space).
     fn new(steps: &[u32], n: usize) -> Self {
-        let m = steps.len();
-        // Compute Kernel Basis using Gaussian Elimination on rows
-        let mut matrix = vec![0u64; n];
-        for (r, row) in matrix.iter_mut().enumerate().take(n) {
-            let mut row_val = 0u64;
-            for (c, &step) in steps.iter().enumerate() {
-                if (step >> r) & 1 == 1 {
-                    row_val |= 1 << c;
-                }
-            }
-            *row = row_val;
-        }
-
-        let mut pivots = Vec::new(); // (row, col)
-        let mut next_row = 0;
-        let mut pivot_cols = vec![false; m];
-
-        // Make a copy for kernel computation
-        let mut k_matrix = matrix.clone();
-
-        for (c, is_pivot) in pivot_cols.iter_mut().enumerate().take(m) {
-            if next_row >= n {
-                break;
-            }
-            let mut pivot_row = None;
-            for (r, row) in k_matrix.iter().enumerate().take(n).skip(next_row) {
-                if (row >> c) & 1 == 1 {
-                    pivot_row = Some(r);
-                    break;
-                }
-            }
-            if let Some(pr) = pivot_row {
-                k_matrix.swap(next_row, pr);
-                let pivot_val = k_matrix[next_row];
-                for (r, row) in k_matrix.iter_mut().enumerate().take(n) {
-                    if r != next_row && (*row >> c) & 1 == 1 {
-                        *row ^= pivot_val;
-                    }
-                }
-                pivots.push((next_row, c));
-                *is_pivot = true;
-                next_row += 1;
-            }
-        }
-
-        let mut kernel_basis = Vec::new();
-        for (c, &is_pivot) in pivot_cols.iter().enumerate().take(m) {
-            if !is_pivot {
-                // Free variable c.
-                // Basis vector has 1 at c, and for each pivot col p_c,
-                // value is determined by the row equation.
-                let mut vec = 1u64 << c;
-                for &(r, p_c) in &pivots {
-                    if (k_matrix[r] >> c) & 1 == 1 {
-                        vec |= 1u64 << p_c;
-                    }
-                }
-                kernel_basis.push(vec);
-            }
-        }
-
+        let matrix = Gf2Matrix::from_columns(steps, n).expect("parsing limits steps and positions");
         Self {
             n,
-            m,
+            m: steps.len(),
             steps: steps.to_vec(),
-            kernel_basis,
+            matrix,
         }
     }
 
     /// Returns all step combinations (bitmasks) `c` such that `Matrix * c = target_pattern` (mod 2).
     /// Returns an empty vector if the system is inconsistent.
-    fn solve(&self, target_pattern: u32) -> Vec<u64> {
-        let n = self.n;
-        let m = self.m;
-
-        // Build augmented matrix for GE: M | target
-        let mut matrix = vec![0u64; n];
-        for (r, row) in matrix.iter_mut().enumerate().take(n) {
-            let mut row_val = 0u64;
-            for (c, &step) in self.steps.iter().enumerate() {
-                if (step >> r) & 1 == 1 {
-                    row_val |= 1 << c;
-                }
-            }
-            if (target_pattern >> r) & 1 == 1 {
-                row_val |= 1 << m; // Augment column
-            }
-            *row = row_val;
-        }
-
-        // GE
-        let mut pivots = Vec::new();
-        let mut next_row = 0;
-
-        for c in 0..m {
-            if next_row >= n {
-                break;
-            }
-            let mut pivot_row = None;
-            for (r, row) in matrix.iter().enumerate().take(n).skip(next_row) {
-                if (row >> c) & 1 == 1 {
-                    pivot_row = Some(r);
-                    break;
-                }
-            }
-            if let Some(pr) = pivot_row {
-                matrix.swap(next_row, pr);
-                let pivot_val = matrix[next_row];
-                for (r, row) in matrix.iter_mut().enumerate().take(n) {
-                    if r != next_row && (*row >> c) & 1 == 1 {
-                        *row ^= pivot_val;
-                    }
-                }
-                pivots.push((next_row, c));
-                next_row += 1;
-            }
-        }
-
-        // Check consistency
-        for row in matrix.iter().take(n).skip(next_row) {
-            if (row >> m) & 1 == 1 {
-                return Vec::new(); // Inconsistent
-            }
-        }
-
-        // Find particular solution (set free vars to 0)
-        let mut particular = 0u64;
-        for &(r, c) in pivots.iter().rev() {
-            if (matrix[r] >> m) & 1 == 1 {
-                particular |= 1 << c;
-            }
-        }
-
-        // Generate all solutions
-        let mut solutions = Vec::new();
-        solutions.push(particular);
-
-        // Expand kernel to find all valid parity patterns
-        for &k_vec in &self.kernel_basis {
-            let len = solutions.len();
-            for i in 0..len {
-                solutions.push(solutions[i] ^ k_vec);
-            }
-        }
-
-        solutions
+    fn solve(&self, target_pattern: u32) -> Vec<u128> {
+        self.matrix
+            .solve(target_pattern)
+            .map_or_else(Vec::new, |solution| solution.all())
     }
 }
 
 fn solve_part2_recursive_parity(
     target: Vec<u32>,
     solver: &GF2Solver,
-    memo: &mut HashMap<Vec<u32>, Option<u64>>,
+    memo: &mut Memoizer<Vec<u32>, Option<u64>>,
 ) -> Option<u64> {
     // Base case: target is all zeros, cost is 0.
     if target.iter().all(|&x| x == 0) {
         return Some(0);
     }
     // Memoization check
-    if let Some(&res) = memo.get(&target) {
+    if let Some(res) = memo.get(&target) {
         return res;
     }
 
@@ -690,58 +451,58 @@ mod tests {
     fn test_part2_example_1() {
         let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
         let p = Problem::parse(input).unwrap();
-        assert_eq!(solve_part2(&p), Some(10));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(10));
     }
 
     #[test]
     fn test_part2_example_2() {
         let input = "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}";
         let p = Problem::parse(input).unwrap();
-        assert_eq!(solve_part2(&p), Some(12));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(12));
     }
 
     #[test]
     fn test_part2_example_3() {
         let input = "[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
         let p = Problem::parse(input).unwrap();
-        assert_eq!(solve_part2(&p), Some(11));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(11));
     }
 
     #[test]
     fn test_part2_trivial_target() {
         let p = Problem::parse("[.] (0) {0}").unwrap();
-        assert_eq!(solve_part2(&p), Some(0));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(0));
     }
 
     #[test]
     fn test_part2_no_solution() {
         let p = Problem::parse("[..] (0,1) {1,0}").unwrap();
-        assert_eq!(solve_part2(&p), None);
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), None);
     }
 
     #[test]
     fn test_part2_redundant_steps() {
         let p = Problem::parse("[.] (0) (0) {2}").unwrap();
-        assert_eq!(solve_part2(&p), Some(2));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(2));
     }
 
     #[test]
     fn test_part2_optimization_free_variable() {
         let p = Problem::parse("[..] (0) (1) (0,1) {10,10}").unwrap();
-        assert_eq!(solve_part2(&p), Some(10));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(10));
     }
 
     #[test]
     fn test_part2_large_target_scaling() {
         let p = Problem::parse("[.] (0) {100}").unwrap();
-        assert_eq!(solve_part2(&p), Some(100));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(100));
     }
 
     #[test]
     fn test_part2_second_failure() {
         let input = "[#..#....#] (2,4,6,8) (1,3,4) (0,1,2,4,5,7,8) (4,5,6,8) (1,2,3,5,6) (2,6,7,8) (0,2,3,4,5,6,7) (0,1,2,4,6,7,8) (0,2,3,4,6,7) (0,3,7,8) {65,49,88,60,82,65,88,67,78}";
         let p = Problem::parse(input).unwrap();
-        let result = solve_part2(&p);
+        let result = solve_part2(&p, &mut Memoizer::new());
         assert_eq!(result, Some(121));
     }
 
@@ -749,20 +510,22 @@ mod tests {
     fn test_part2_hard_case() {
         let input = "[#..##.###.] (0,1,2,3,5,6,7,8) (0,1,2,4,6,7,8,9) (5,8,9) (3,4,6,7) (3,5,6) (1,4,8,9) (2,3,7,8,9) (0,1,2,6,7,8) (0,6,9) (0,5,7,8,9) (0,2,3,4,6,7,8,9) (1,4,6,9) (1,2,5,6) {225,56,230,208,204,28,256,231,235,246}";
         let p = Problem::parse(input).unwrap();
-        assert_eq!(solve_part2(&p), Some(283));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(283));
     }
 
-    // Disabled due to poor performance.
     #[test]
-    #[ignore]
-    fn test_part2_seeded_worst_case_demo() {
-        let input = "[..........] \
-(0) (1) (2) (3) (4) (5) (6) (7) (8) (9) \
-(0,1) (1,2) (2,3) (3,4) (4,5) (5,6) (6,7) (7,8) (8,9) (0,9) \
-(0,2) (1,3) (2,4) (3,5) (4,6) (5,7) (6,8) (7,9) (0,5) (1,6) \
-{2,2,2,2,2,2,2,2,2,2}";
+    fn test_part2_capped_memo() {
+        // Evicting subproblems costs time, not correctness
+        let input = "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {70,50,120,70,20}";
         let p = Problem::parse(input).unwrap();
-        assert!(solve_part2(&p).is_some());
+        let mut unbounded = Memoizer::new();
+        let expected = solve_part2(&p, &mut unbounded);
+        assert!(expected.is_some());
+        let mut capped = Memoizer::new().with_max_entries(2);
+        assert_eq!(solve_part2(&p, &mut capped), expected);
+        assert!(capped.len() <= 2);
+        assert!(capped.stats().evictions > 0);
+        assert!(capped.stats().misses >= unbounded.stats().misses);
     }
 
     // --- Parsing Tests ---
@@ -786,4 +549,41 @@ mod tests {
     fn test_parsing_bad_step_format() {
         assert!(Problem::parse("[.#] (a)").is_err());
     }
+
+    #[test]
+    fn test_more_than_64_steps() {
+        let steps = "(3) (1,3) (2) (2,3) (0,2) (0,1) ".repeat(12);
+        let p = Problem::parse(&format!("[.##.] {}{{3,5,4,7}}", steps)).unwrap();
+        assert_eq!(p.steps.len(), 72);
+        assert_eq!(solve_part1(&p), Some(2));
+        assert_eq!(solve_part2(&p, &mut Memoizer::new()), Some(10));
+        // Past 20 positions part 1 searches the kernel instead
+        let wide = format!("[.#{}#] {}(0,21) (1)", ".".repeat(19), "(21) ".repeat(70));
+        assert_eq!(solve_part1(&Problem::parse(&wide).unwrap()), Some(2));
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        assert_eq!(
+            Problem::parse("[.#] (0) (2) {1,1}").err().as_deref(),
+            Some("invalid input: position 2 out of range 0..2")
+        );
+        assert_eq!(
+            Problem::parse("[.#] (0) {1}").err().as_deref(),
+            Some("invalid input: expected 2 targets, found 1")
+        );
+        let lights = ".".repeat(33);
+        assert_eq!(
+            Problem::parse(&format!("[{}] (0)", lights))
+                .err()
+                .as_deref(),
+            Some("invalid input: too many positions: 33 (max 32)")
+        );
+        assert_eq!(
+            Problem::parse(&format!("[#] {}", "(0) ".repeat(129)))
+                .err()
+                .as_deref(),
+            Some("invalid input: too many steps: 129 (max 128)")
+        );
+    }
 }
@@ -84,7 +84,7 @@ impl SearchState {
 }
 
 /// Part 1: Beam splitter
-fn part1(start_vertex: &str, target_vertex: &str, input: &[String]) -> Result<u64, String> {
+pub fn part1(start_vertex: &str, target_vertex: &str, input: &[String]) -> Result<u64, String> {
     let (graph, name_to_id) = parse_graph(input)?;
 
     let start_id = match name_to_id.get(start_vertex) {
@@ -107,7 +107,7 @@ fn part1(start_vertex: &str, target_vertex: &str, input: &[String]) -> Result<u6
     state.count_paths(start_id, target_id, &graph)
 }
 
-fn part2<R: AsRef<str>>(
+pub fn part2<R: AsRef<str>>(
     start_vertex: &str,
     target_vertex: &str,
     required_vertices: &[R],
@@ -148,9 +148,9 @@ fn part2<R: AsRef<str>>(
         state.reset_allowed();
 
         let mut subset_size = 0;
-        for bit in 0..k {
+        for (bit, &id) in required_ids.iter().enumerate() {
             if (i >> bit) & 1 == 1 {
-                state.allowed[required_ids[bit]] = false;
+                state.allowed[id] = false;
                 subset_size += 1;
             }
         }
@@ -1,3 +1,4 @@
+use rust_advent::geo::Mat2;
 use std::collections::{HashMap, HashSet};
 
 fn main() -> std::io::Result<()> {
@@ -14,7 +15,7 @@ fn main() -> std::io::Result<()> {
     }
 }
 
-fn part1(input: &[String]) -> Result<u32, String> {
+pub fn part1(input: &[String]) -> Result<u32, String> {
     let (shapes, regions) = parse_input(input)?;
     let mut solved_count = 0;
 
@@ -139,20 +140,22 @@ fn generate_variants(cells: &[Point]) -> Vec<Variant> {
     let mut unique_variants = HashSet::new();
     let mut variants = Vec::new();
 
-    let mut current = cells.to_vec();
-    
-    // Try all 4 rotations
-    for _ in 0..4 {
-        add_variant(&mut unique_variants, &mut variants, &current);
-        
-        // Flip and add
-        let flipped: Vec<Point> = current.iter().map(|p| Point { r: p.r, c: -p.c }).collect();
-        add_variant(&mut unique_variants, &mut variants, &flipped);
-
-        // Rotate 90 degrees clockwise: (r, c) -> (c, -r)
-        current = current.iter().map(|p| Point { r: p.c, c: -p.r }).collect();
+    // Try all 4 rotations clockwise, each followed by its mirror image
+    for turns in 0..4 {
+        let rotation = Mat2::ROTATE_CW.pow(turns);
+        for matrix in [rotation, Mat2::FLIP_HORIZONTAL * rotation] {
+            // Columns run along x and rows along y
+            let moved: Vec<Point> = cells
+                .iter()
+                .map(|p| {
+                    let [c, r] = matrix * [i64::from(p.c), i64::from(p.r)];
+                    Point { r: r as i32, c: c as i32 }
+                })
+                .collect();
+            add_variant(&mut unique_variants, &mut variants, &moved);
+        }
     }
-    
+
     variants
 }
 
@@ -314,63 +317,18 @@ fn place(grid: &mut [bool], width: usize, r: i32, c: i32, variant: &Variant, val
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_advent::example_input;
 
     #[test]
     fn test_parse_and_solve_example() {
-        let input = vec![
-            "0:".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "4:".to_string(),
-            "###".to_string(),
-            "#..".to_string(),
-            "###".to_string(),
-            "".to_string(),
-            "4x4: 0 0 0 0 2 0".to_string(),
-        ];
+        let input = example_input!("12/example1_shapes_0_and_4");
         
         assert_eq!(part1(&input), Ok(1));
     }
 
     #[test]
     fn test_larger_example() {
-        let input = vec![
-            "0:".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "1:".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            ".##".to_string(),
-            "".to_string(),
-            "2:".to_string(),
-            ".##".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "3:".to_string(),
-            "##.".to_string(),
-            "###".to_string(),
-            "##.".to_string(),
-            "".to_string(),
-            "4:".to_string(),
-            "###".to_string(),
-            "#..".to_string(),
-            "###".to_string(),
-            "".to_string(),
-            "5:".to_string(),
-            "###".to_string(),
-            ".#.".to_string(),
-            "###".to_string(),
-            "".to_string(),
-            "4x4: 0 0 0 0 2 0".to_string(),
-            "12x5: 1 0 1 0 2 2".to_string(),
-            "12x5: 1 0 1 0 3 2".to_string(),
-        ];
+        let input = example_input!("12/example1");
         
         // 4x4 (Shape 4 x2) -> Yes (1)
         // 12x5 (0x1, 2x1, 4x2, 5x2) -> Yes (2)
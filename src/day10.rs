@@ -0,0 +1,1126 @@
+//! Shared solvers for day 10.  Part 1 wants the fewest button presses that
+//! toggle the lights into a pattern; part 2 the fewest presses that bring
+//! every counter to its target, where each button adds one to a fixed set of
+//! counters.
+//!
+//! Part 1 is a breadth-first search over light states; see [`LightSearch`].
+//! [`min_light_presses_sat`] answers it again as a SAT problem, one parity
+//! constraint per light, to cross-check the search.
+//!
+//! For part 2 the canonical algorithm is parity decomposition.  A
+//! breadth-first search over counter values is also available; it wins when
+//! targets are tiny but the buttons leave many free choices of parity.
+//! [`Strategy::select`] picks between them.
+//!
+//! Buttons, lights and counter patterns are [`CounterSet`]s: `u32` masks
+//! for machines of up to [`MAX_COUNTERS`] counters, the fast path, and
+//! [`WideMask`]s for larger ones.  [`Machine::parse_wide`] reads any size,
+//! and [`Machine::narrow`] moves a machine that fits back onto `u32`.
+
+use crate::sat::Cnf;
+use crate::solution::{Cancelled, Context, InputSize};
+use crate::validate::{Violation, ensure_at_most, ensure_in_range};
+use crate::{Error, FastSet, FixedBitSet, Outcome, WideMask};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+/// Most counters a machine on `u32` masks may have.
+pub const MAX_COUNTERS: usize = 32;
+/// Most buttons a machine may have; parity choices are packed into `u64`.
+pub const MAX_BUTTONS: usize = 64;
+/// Largest counter state space the BFS strategy will index densely.
+const BFS_DENSE_LIMIT: u64 = 1 << 24;
+/// Most lights any button toggles that the part 1 search tracks in a bit
+/// set of 2^n bits; beyond it the visited states go in a hash set.
+const DENSE_TOGGLED_LIGHTS: u32 = 28;
+
+/// A set of counters or lights, one bit each.
+pub trait CounterSet: Clone + Ord + Hash + fmt::Debug {
+    /// Most counters a set can hold.
+    const CAPACITY: usize;
+
+    /// An empty set with room for counters `0..width`.
+    fn empty(width: usize) -> Self;
+    fn insert(&mut self, counter: usize);
+    fn contains(&self, counter: usize) -> bool;
+    fn is_empty(&self) -> bool;
+    fn count(&self) -> usize;
+    /// The members in increasing order.
+    fn ones(&self) -> impl Iterator<Item = usize> + '_;
+    /// Replaces `self` with its symmetric difference with `other`.
+    fn toggle(&mut self, other: &Self);
+    /// How many counters the two sets share.
+    fn overlap(&self, other: &Self) -> usize;
+    /// The set of `counters`, with room for counters `0..width`.
+    fn from_counters(width: usize, counters: impl IntoIterator<Item = usize>) -> Self {
+        let mut set = Self::empty(width);
+        for counter in counters {
+            set.insert(counter);
+        }
+        set
+    }
+}
+
+impl CounterSet for u32 {
+    const CAPACITY: usize = MAX_COUNTERS;
+
+    fn empty(_width: usize) -> Self {
+        0
+    }
+
+    fn insert(&mut self, counter: usize) {
+        *self |= 1 << counter;
+    }
+
+    fn contains(&self, counter: usize) -> bool {
+        counter < MAX_COUNTERS && (self >> counter) & 1 == 1
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == 0
+    }
+
+    fn count(&self) -> usize {
+        self.count_ones() as usize
+    }
+
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut bits = *self;
+        std::iter::from_fn(move || {
+            (bits != 0).then(|| {
+                let counter = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                counter
+            })
+        })
+    }
+
+    fn toggle(&mut self, other: &Self) {
+        *self ^= other;
+    }
+
+    fn overlap(&self, other: &Self) -> usize {
+        (self & other).count_ones() as usize
+    }
+}
+
+impl CounterSet for WideMask {
+    const CAPACITY: usize = usize::MAX;
+
+    fn empty(width: usize) -> Self {
+        WideMask::new(width)
+    }
+
+    fn insert(&mut self, counter: usize) {
+        WideMask::insert(self, counter);
+    }
+
+    fn contains(&self, counter: usize) -> bool {
+        WideMask::contains(self, counter)
+    }
+
+    fn is_empty(&self) -> bool {
+        WideMask::is_empty(self)
+    }
+
+    fn count(&self) -> usize {
+        self.count_ones()
+    }
+
+    fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.iter_ones()
+    }
+
+    fn toggle(&mut self, other: &Self) {
+        *self = &*self ^ other;
+    }
+
+    fn overlap(&self, other: &Self) -> usize {
+        (self & other).count_ones()
+    }
+}
+
+/// A day 10 machine: `buttons[i]` holds counter `j` if pressing button `i`
+/// increments counter `j`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine<S = u32> {
+    buttons: Vec<S>,
+    targets: Vec<u32>,
+}
+
+impl<S: CounterSet> Machine<S> {
+    pub fn new(buttons: Vec<S>, targets: Vec<u32>) -> Result<Self, Violation> {
+        ensure_at_most("counters", targets.len(), S::CAPACITY)?;
+        ensure_at_most("buttons", buttons.len(), MAX_BUTTONS)?;
+        for button in &buttons {
+            if let Some(highest) = button.ones().last() {
+                ensure_in_range("button counter", highest, 0..targets.len())?;
+            }
+        }
+        Ok(Machine { buttons, targets })
+    }
+
+    /// Parses a puzzle line such as `[.##.] (3) (1,3) (2) {3,5,4,7}`.  The
+    /// light diagram only matters for part 1 and is skipped here.  Errors
+    /// point at the offending token as if `line` were line 1; see
+    /// [`Error::on_line`].
+    fn parse_sets(line: &str) -> Result<Self, Error> {
+        let error = |token: &str, message: String| Error::at_token(1, line, token, message);
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some(lights) if lights.starts_with('[') && lights.ends_with(']') => {}
+            first => {
+                let token = first.unwrap_or(line);
+                return Err(error(token, "missing light diagram".to_string()));
+            }
+        }
+        let number = |n: &str| -> Result<u32, Error> {
+            let n = n.trim();
+            n.parse()
+                .map_err(|_| error(n, format!("invalid number '{}'", n)))
+        };
+
+        // Counter lists until the targets say how many counters there are
+        let mut lists = Vec::new();
+        let mut targets = None;
+        for part in parts {
+            if targets.is_some() {
+                return Err(error(part, format!("unexpected '{}' after targets", part)));
+            }
+            if let Some(list) = part.strip_prefix('(').and_then(|p| p.strip_suffix(')')) {
+                let mut counters = Vec::new();
+                for n in list.split(',') {
+                    let counter = number(n)? as usize;
+                    if counter >= S::CAPACITY {
+                        let message = format!("counter {} out of range", counter);
+                        return Err(error(n.trim(), message));
+                    }
+                    counters.push(counter);
+                }
+                lists.push(counters);
+            } else if let Some(list) = part.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+                targets = Some(list.split(',').map(number).collect::<Result<Vec<_>, _>>()?);
+            } else {
+                return Err(error(part, format!("unexpected '{}'", part)));
+            }
+        }
+        let targets =
+            targets.ok_or_else(|| error(&line[line.len()..], "missing targets".to_string()))?;
+        let invalid = |err: Violation| error(line.trim(), err.to_string());
+        ensure_at_most("counters", targets.len(), S::CAPACITY).map_err(invalid)?;
+        let mut buttons = Vec::with_capacity(lists.len());
+        for counters in lists {
+            let mut button = S::empty(targets.len());
+            for counter in counters {
+                ensure_in_range("button counter", counter, 0..targets.len()).map_err(invalid)?;
+                button.insert(counter);
+            }
+            buttons.push(button);
+        }
+        Machine::new(buttons, targets).map_err(invalid)
+    }
+
+    pub fn buttons(&self) -> &[S] {
+        &self.buttons
+    }
+
+    pub fn targets(&self) -> &[u32] {
+        &self.targets
+    }
+
+    /// Buttons with no effect or identical to an earlier button never help.
+    fn distinct_buttons(&self) -> Vec<S> {
+        let mut buttons: Vec<S> = self
+            .buttons
+            .iter()
+            .filter(|b| !b.is_empty())
+            .cloned()
+            .collect();
+        buttons.sort_unstable();
+        buttons.dedup();
+        buttons
+    }
+}
+
+impl Machine {
+    /// Parses a machine of at most [`MAX_COUNTERS`] counters; see
+    /// [`Machine::parse_wide`] for larger ones.
+    pub fn parse(line: &str) -> Result<Self, Error> {
+        Self::parse_sets(line)
+    }
+}
+
+impl Machine<WideMask> {
+    /// Parses a machine with any number of counters.
+    pub fn parse_wide(line: &str) -> Result<Self, Error> {
+        Self::parse_sets(line)
+    }
+
+    /// The same machine on `u32` masks, if it has few enough counters.
+    pub fn narrow(&self) -> Option<Machine> {
+        (self.targets.len() <= MAX_COUNTERS).then(|| Machine {
+            buttons: self
+                .buttons
+                .iter()
+                .map(|button| u32::from_counters(MAX_COUNTERS, button.ones()))
+                .collect(),
+            targets: self.targets.clone(),
+        })
+    }
+}
+
+/// The most lights in any line's diagram, for [`Solution::limits`] of
+/// solvers that take the puzzle's lines.  Lines without a diagram count as
+/// none.
+///
+/// [`Solution::limits`]: crate::solution::Solution::limits
+pub fn input_size(lines: &[String]) -> InputSize {
+    let lights = |line: &String| {
+        let diagram = line.split_whitespace().next()?;
+        Some(diagram.strip_prefix('[')?.strip_suffix(']')?.len())
+    };
+    InputSize {
+        positions: lines.iter().filter_map(lights).max().unwrap_or(0),
+        ..InputSize::default()
+    }
+}
+
+/// Breadth-first search for part 1.  Keeps its visited set and queue between
+/// calls, so solving many machines with one search allocates only when a
+/// machine needs more room than any before it.
+#[derive(Debug, Default)]
+pub struct LightSearch {
+    visited: FixedBitSet,
+    sparse: FastSet<u128>,
+    queue: VecDeque<(u128, u64)>,
+    buttons: Vec<u128>,
+}
+
+impl LightSearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fewest presses that toggle the lights from all off to `goal`, where
+    /// pressing `buttons[i]` toggles the lights set in it.  Fails if the
+    /// buttons toggle more than 128 lights between them.
+    pub fn min_presses<S: CounterSet>(
+        &mut self,
+        buttons: &[S],
+        goal: S,
+    ) -> Result<Outcome, String> {
+        // Lights no button touches stay off, so drop them from the state,
+        // which then needs a bit only for each light some button toggles.
+        let mut touched: Vec<usize> = buttons.iter().flat_map(|b| b.ones()).collect();
+        touched.sort_unstable();
+        touched.dedup();
+        if goal
+            .ones()
+            .any(|light| touched.binary_search(&light).is_err())
+        {
+            return Ok(Outcome::Unsolvable);
+        }
+        let width = touched.len() as u32;
+        if width > u128::BITS {
+            return Err(format!(
+                "buttons toggle {} lights (max {})",
+                width,
+                u128::BITS
+            ));
+        }
+        let compact = |lights: &S| {
+            lights.ones().fold(0u128, |out, light| {
+                out | touched.binary_search(&light).map_or(0, |i| 1 << i)
+            })
+        };
+        let goal = compact(&goal);
+        if goal == 0 {
+            return Ok(Outcome::Answer(0));
+        }
+
+        let Self {
+            visited,
+            sparse,
+            queue,
+            buttons: compacted,
+        } = self;
+        compacted.clear();
+        compacted.extend(buttons.iter().map(compact));
+        let dense = width <= DENSE_TOGGLED_LIGHTS;
+        if dense {
+            visited.reset(1 << width);
+        } else {
+            sparse.clear();
+        }
+        let mut visit = |state: u128| match dense {
+            true => visited.insert(state as usize),
+            false => sparse.insert(state),
+        };
+        queue.clear();
+        visit(0);
+        queue.push_back((0, 0));
+        while let Some((state, presses)) = queue.pop_front() {
+            for &button in compacted.iter() {
+                let next = state ^ button;
+                if next == goal {
+                    return Ok(Outcome::Answer(presses + 1));
+                }
+                if visit(next) {
+                    queue.push_back((next, presses + 1));
+                }
+            }
+        }
+        Ok(Outcome::Unsolvable)
+    }
+}
+
+/// Part 1 as satisfiability: variable `i` is pressing button `i` once
+/// (twice undoes it), each light needs an odd number of presses among the
+/// buttons toggling it if it ends lit and an even number otherwise, and the
+/// fewest presses is the model with the fewest variables true.  Lights
+/// toggled by more than [`Cnf::MAX_XOR_VARS`] buttons are refused.
+pub fn min_light_presses_sat<S: CounterSet>(buttons: &[S], goal: S) -> Result<Outcome, Violation> {
+    let mut cnf = Cnf::new(buttons.len())?;
+    let mut lights: Vec<usize> = buttons
+        .iter()
+        .chain([&goal])
+        .flat_map(|b| b.ones())
+        .collect();
+    lights.sort_unstable();
+    lights.dedup();
+    for light in lights {
+        let toggling = buttons
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.contains(light))
+            .fold(0u64, |vars, (i, _)| vars | 1 << i);
+        cnf.add_xor(toggling, goal.contains(light))?;
+    }
+    Ok(match cnf.solve_min_true() {
+        Some(model) => Outcome::Answer(u64::from(model.count_ones())),
+        None => Outcome::Unsolvable,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Split presses by parity and recurse on the halved remainder.
+    Parity,
+    /// Breadth-first search over counter values.
+    Bfs,
+}
+
+impl Strategy {
+    /// Picks the strategy with the smaller estimated work.  Parity
+    /// decomposition tries up to 2^(free buttons) parity choices at each of
+    /// log2(max target) levels; the BFS visits every counter state up to the
+    /// targets.
+    pub fn select<S: CounterSet>(machine: &Machine<S>) -> Self {
+        let buttons = machine.distinct_buttons();
+        let free = buttons.len() - Parity::new(&buttons, machine.targets.len()).rank;
+        let levels = 32
+            - machine
+                .targets
+                .iter()
+                .max()
+                .map_or(0, |t| t.leading_zeros());
+        let parity_work = 1u64
+            .checked_shl(free as u32)
+            .and_then(|choices| choices.checked_mul(u64::from(levels.max(1))))
+            .unwrap_or(u64::MAX);
+        match bfs_state_count(&machine.targets) {
+            Some(states) if states < parity_work && states <= BFS_DENSE_LIMIT => Strategy::Bfs,
+            _ => Strategy::Parity,
+        }
+    }
+}
+
+/// Fewest presses that reach every target exactly.  Uses the strategy chosen
+/// by [`Strategy::select`].
+pub fn min_presses<S: CounterSet>(
+    machine: &Machine<S>,
+    ctx: &Context,
+) -> Result<Outcome, Cancelled> {
+    min_presses_with(machine, Strategy::select(machine), ctx)
+}
+
+pub fn min_presses_with<S: CounterSet>(
+    machine: &Machine<S>,
+    strategy: Strategy,
+    ctx: &Context,
+) -> Result<Outcome, Cancelled> {
+    let buttons = machine.distinct_buttons();
+    if machine.targets.iter().all(|&t| t == 0) {
+        return Ok(Outcome::Answer(0));
+    }
+    let presses = match strategy {
+        Strategy::Parity => {
+            let solver = Parity::new(&buttons, machine.targets.len());
+            solver.min_presses(machine.targets.clone(), &mut HashMap::new(), ctx)?
+        }
+        Strategy::Bfs => bfs(&buttons, &machine.targets, ctx)?,
+    };
+    Ok(presses.into())
+}
+
+/// The number of counter states between zero and `targets`, if it fits.
+fn bfs_state_count(targets: &[u32]) -> Option<u64> {
+    targets
+        .iter()
+        .try_fold(1u64, |states, &t| states.checked_mul(u64::from(t) + 1))
+}
+
+/// Linear algebra over GF(2) for the parity step: which sets of buttons,
+/// each pressed once, flip exactly the counters in a given pattern.
+struct Parity<'a, S> {
+    buttons: &'a [S],
+    counters: usize,
+    /// `combos[r]` records which original counters were summed into row `r`,
+    /// so the reduced right-hand side for any pattern is a parity lookup.
+    combos: Vec<S>,
+    /// `(row, column)` of each pivot.
+    pivots: Vec<(usize, usize)>,
+    rank: usize,
+    /// Basis of the button combinations that flip nothing.
+    kernel: Vec<u64>,
+}
+
+impl<'a, S: CounterSet> Parity<'a, S> {
+    fn new(buttons: &'a [S], counters: usize) -> Self {
+        // Row-reduce the button matrix: one row per counter, one bit per button.
+        let mut rows: Vec<u64> = (0..counters)
+            .map(|r| {
+                buttons
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| b.contains(r))
+                    .fold(0u64, |row, (c, _)| row | 1 << c)
+            })
+            .collect();
+        let mut combos: Vec<S> = (0..counters)
+            .map(|r| S::from_counters(counters, [r]))
+            .collect();
+
+        let mut pivots = Vec::new();
+        for column in 0..buttons.len() {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..counters).find(|&r| (rows[r] >> column) & 1 == 1) else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+            combos.swap(rank, pivot);
+            for r in 0..counters {
+                if r != rank && (rows[r] >> column) & 1 == 1 {
+                    rows[r] ^= rows[rank];
+                    let pivot_combo = combos[rank].clone();
+                    combos[r].toggle(&pivot_combo);
+                }
+            }
+            pivots.push((rank, column));
+        }
+
+        let pivot_columns: u64 = pivots.iter().fold(0, |mask, &(_, c)| mask | 1 << c);
+        let kernel = (0..buttons.len())
+            .filter(|&c| (pivot_columns >> c) & 1 == 0)
+            .map(|free| {
+                pivots
+                    .iter()
+                    .filter(|&&(r, _)| (rows[r] >> free) & 1 == 1)
+                    .fold(1u64 << free, |v, &(_, c)| v | 1 << c)
+            })
+            .collect();
+
+        Parity {
+            buttons,
+            counters,
+            rank: pivots.len(),
+            combos,
+            pivots,
+            kernel,
+        }
+    }
+
+    /// Every set of buttons whose single presses flip exactly `pattern`.
+    fn solutions(&self, pattern: &S) -> Vec<u64> {
+        let rhs = |r: usize| self.combos[r].overlap(pattern) % 2 == 1;
+        if (self.rank..self.counters).any(rhs) {
+            return Vec::new();
+        }
+        let particular = self
+            .pivots
+            .iter()
+            .filter(|&&(r, _)| rhs(r))
+            .fold(0u64, |v, &(_, c)| v | 1 << c);
+        let mut solutions = vec![particular];
+        for &basis in &self.kernel {
+            for i in 0..solutions.len() {
+                solutions.push(solutions[i] ^ basis);
+            }
+        }
+        solutions
+    }
+
+    /// Any solution presses each button an odd or even number of times; the
+    /// odd presses must flip the odd targets, and what remains is even, so
+    /// halve it and recurse.
+    fn min_presses(
+        &self,
+        targets: Vec<u32>,
+        memo: &mut HashMap<Vec<u32>, Option<u64>>,
+        ctx: &Context,
+    ) -> Result<Option<u64>, Cancelled> {
+        if targets.iter().all(|&t| t == 0) {
+            return Ok(Some(0));
+        }
+        if let Some(&known) = memo.get(&targets) {
+            return Ok(known);
+        }
+        ctx.check()?;
+
+        let odd = targets.iter().enumerate().filter(|(_, t)| *t % 2 == 1);
+        let pattern = S::from_counters(self.counters, odd.map(|(counter, _)| counter));
+        let mut best: Option<u64> = None;
+        'choices: for choice in self.solutions(&pattern) {
+            let mut remainder = targets.clone();
+            for (b, button) in self.buttons.iter().enumerate() {
+                if (choice >> b) & 1 == 0 {
+                    continue;
+                }
+                for counter in button.ones() {
+                    let Some(next) = remainder[counter].checked_sub(1) else {
+                        continue 'choices;
+                    };
+                    remainder[counter] = next;
+                }
+            }
+            for value in &mut remainder {
+                *value /= 2;
+            }
+            if let Some(rest) = self.min_presses(remainder, memo, ctx)? {
+                let total = u64::from(choice.count_ones()) + 2 * rest;
+                best = Some(best.map_or(total, |b| b.min(total)));
+            }
+        }
+        memo.insert(targets, best);
+        Ok(best)
+    }
+}
+
+/// Breadth-first search from all-zero counters, indexing states by their
+/// mixed-radix encoding.  Dense when the state space is small enough.
+fn bfs<S: CounterSet>(
+    buttons: &[S],
+    targets: &[u32],
+    ctx: &Context,
+) -> Result<Option<u64>, Cancelled> {
+    let radices: Vec<u64> = targets.iter().map(|&t| u64::from(t) + 1).collect();
+    let mut place = 1u64;
+    let places: Vec<u64> = radices
+        .iter()
+        .map(|&r| {
+            let current = place;
+            place = place.saturating_mul(r);
+            current
+        })
+        .collect();
+    let goal: u64 = targets
+        .iter()
+        .zip(&places)
+        .map(|(&t, &p)| u64::from(t) * p)
+        .sum();
+
+    let dense = bfs_state_count(targets).is_some_and(|states| states <= BFS_DENSE_LIMIT);
+    let mut seen_dense = FixedBitSet::new(if dense { place as usize } else { 0 });
+    let mut seen_sparse = HashSet::new();
+    let mut visit = |state: u64| {
+        if dense {
+            seen_dense.insert(state as usize)
+        } else {
+            seen_sparse.insert(state)
+        }
+    };
+
+    visit(0);
+    let mut queue = VecDeque::from([(vec![0u32; targets.len()], 0u64, 0u64)]);
+    while let Some((counters, state, presses)) = queue.pop_front() {
+        ctx.check()?;
+        'buttons: for button in buttons {
+            let mut next = counters.clone();
+            let mut next_state = state;
+            for counter in button.ones() {
+                if next[counter] == targets[counter] {
+                    continue 'buttons;
+                }
+                next[counter] += 1;
+                next_state += places[counter];
+            }
+            if next_state == goal {
+                return Ok(Some(presses + 1));
+            }
+            if visit(next_state) {
+                queue.push_back((next, next_state, presses + 1));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use proptest::collection::vec;
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+
+    /// Builds a machine from button counter lists, as written in the puzzle.
+    fn machine(buttons: &[&[usize]], targets: &[u32]) -> Machine {
+        let buttons = buttons
+            .iter()
+            .map(|counters| counters.iter().fold(0u32, |mask, &c| mask | 1 << c))
+            .collect();
+        Machine::new(buttons, targets.to_vec()).unwrap()
+    }
+
+    fn solve_all(machine: &Machine) -> Vec<Outcome> {
+        [Strategy::Parity, Strategy::Bfs]
+            .iter()
+            .map(|&s| min_presses_with(machine, s, &Context::new()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_examples() {
+        let examples = [
+            (
+                machine(
+                    &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]],
+                    &[3, 5, 4, 7],
+                ),
+                10,
+            ),
+            (
+                machine(
+                    &[&[0, 2, 3, 4], &[2, 3], &[0, 4], &[0, 1, 2], &[1, 2, 3, 4]],
+                    &[7, 5, 12, 7, 2],
+                ),
+                12,
+            ),
+            (
+                machine(
+                    &[&[0, 1, 2, 3, 4], &[0, 3, 4], &[0, 1, 2, 4, 5], &[1, 2]],
+                    &[10, 11, 11, 5, 10, 5],
+                ),
+                11,
+            ),
+        ];
+        for (machine, expected) in examples {
+            assert_eq!(solve_all(&machine), vec![Outcome::Answer(expected); 2]);
+            assert_eq!(
+                min_presses(&machine, &Context::new()),
+                Ok(Outcome::Answer(expected))
+            );
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(
+            solve_all(&machine(&[&[0]], &[0])),
+            vec![Outcome::Answer(0); 2]
+        );
+        assert_eq!(
+            solve_all(&machine(&[&[0, 1]], &[1, 0])),
+            vec![Outcome::Unsolvable; 2]
+        );
+        assert_eq!(
+            solve_all(&machine(&[&[0], &[0]], &[2])),
+            vec![Outcome::Answer(2); 2]
+        );
+        assert_eq!(
+            solve_all(&machine(&[&[], &[1]], &[0, 3])),
+            vec![Outcome::Answer(3); 2]
+        );
+        assert_eq!(
+            solve_all(&machine(&[&[0]], &[100])),
+            vec![Outcome::Answer(100); 2]
+        );
+        assert_eq!(
+            solve_all(&machine(&[&[0], &[1], &[0, 1]], &[10, 10])),
+            vec![Outcome::Answer(10); 2]
+        );
+    }
+
+    #[test]
+    fn test_hard_case() {
+        let machine = machine(
+            &[
+                &[0, 1, 2, 3, 5, 6, 7, 8],
+                &[0, 1, 2, 4, 6, 7, 8, 9],
+                &[5, 8, 9],
+                &[3, 4, 6, 7],
+                &[3, 5, 6],
+                &[1, 4, 8, 9],
+                &[2, 3, 7, 8, 9],
+                &[0, 1, 2, 6, 7, 8],
+                &[0, 6, 9],
+                &[0, 5, 7, 8, 9],
+                &[0, 2, 3, 4, 6, 7, 8, 9],
+                &[1, 4, 6, 9],
+                &[1, 2, 5, 6],
+            ],
+            &[225, 56, 230, 208, 204, 28, 256, 231, 235, 246],
+        );
+        assert_eq!(Strategy::select(&machine), Strategy::Parity);
+        assert_eq!(
+            min_presses(&machine, &Context::new()),
+            Ok(Outcome::Answer(283))
+        );
+    }
+
+    #[test]
+    fn test_many_free_buttons_selects_bfs() {
+        // Thirty buttons on ten counters leave twenty free parity choices.
+        let mut buttons: Vec<Vec<usize>> = (0..10).map(|i| vec![i]).collect();
+        buttons.extend((0..10).map(|i| vec![i, (i + 1) % 10]));
+        buttons.extend((0..8).map(|i| vec![i, i + 2]));
+        buttons.extend([vec![0, 5], vec![1, 6]]);
+        let buttons: Vec<&[usize]> = buttons.iter().map(|b| &b[..]).collect();
+        let machine = machine(&buttons, &[2; 10]);
+
+        assert_eq!(Strategy::select(&machine), Strategy::Bfs);
+        assert_eq!(
+            min_presses(&machine, &Context::new()),
+            Ok(Outcome::Answer(10))
+        );
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        let mut next = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        for _ in 0..200 {
+            let counters = 1 + next(5) as usize;
+            let buttons = (0..1 + next(6))
+                .map(|_| next(1 << counters) as u32)
+                .collect();
+            let targets = (0..counters).map(|_| next(8) as u32).collect();
+            let machine = Machine::new(buttons, targets).unwrap();
+            let results = solve_all(&machine);
+            assert_eq!(results[0], results[1], "{:?}", machine);
+        }
+    }
+
+    /// The counters flipped by pressing each button in `choice` once.
+    fn flipped(buttons: &[u32], choice: u64) -> u32 {
+        buttons
+            .iter()
+            .enumerate()
+            .filter(|(b, _)| (choice >> b) & 1 == 1)
+            .fold(0, |pattern, (_, &button)| pattern ^ button)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parity_solutions_solve_the_system(
+            counters in 1usize..8,
+            buttons in vec(0u32..1 << 7, 0..10),
+            pattern in 0u32..1 << 7,
+        ) {
+            let buttons: Vec<u32> = buttons.iter().map(|&b| b & ((1 << counters) - 1)).collect();
+            let pattern = pattern & ((1 << counters) - 1);
+            let solver = Parity::new(&buttons, counters);
+            let solutions = solver.solutions(&pattern);
+
+            for &choice in &solutions {
+                prop_assert_eq!(flipped(&buttons, choice), pattern, "choice {:b}", choice);
+            }
+            // Exactly the subsets a brute force finds, each once
+            let mut expected: Vec<u64> = (0..1u64 << buttons.len())
+                .filter(|&choice| flipped(&buttons, choice) == pattern)
+                .collect();
+            let mut found = solutions.clone();
+            expected.sort_unstable();
+            found.sort_unstable();
+            prop_assert_eq!(found, expected);
+            prop_assert!(solutions.is_empty() || solutions.len() == 1 << solver.kernel.len());
+        }
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let machine = machine(&[&[0], &[0, 1]], &[5, 3]);
+        let ctx = Context::new();
+        ctx.cancellation().cancel();
+        for strategy in [Strategy::Parity, Strategy::Bfs] {
+            assert_eq!(min_presses_with(&machine, strategy, &ctx), Err(Cancelled));
+        }
+    }
+
+    #[test]
+    fn test_light_search() {
+        let mut search = LightSearch::new();
+        let buttons = [0b1000, 0b1010, 0b0100, 0b1100, 0b0101, 0b0011];
+        assert_eq!(search.min_presses(&buttons, 0b0110), Ok(Outcome::Answer(2)));
+        assert_eq!(search.min_presses(&[0b1], 0b0), Ok(Outcome::Answer(0)));
+        assert_eq!(search.min_presses(&[0b1], 0b10), Ok(Outcome::Unsolvable));
+        assert_eq!(
+            search.min_presses(&[0b11, 0b110], 0b010),
+            Ok(Outcome::Unsolvable)
+        );
+        assert_eq!(
+            search.min_presses(&[0b11, 0b110], 0b101),
+            Ok(Outcome::Answer(2))
+        );
+        // Only the toggled lights count towards the visited set's size.
+        assert_eq!(
+            search.min_presses(&[1 << 31, 1], 1 << 31 | 1),
+            Ok(Outcome::Answer(2))
+        );
+        // Past the dense visited set's reach, the hash set takes over
+        let mut many: Vec<u32> = (0..31).map(|light| 1 << light).collect();
+        many.push(u32::MAX);
+        assert_eq!(search.min_presses(&many, u32::MAX), Ok(Outcome::Answer(1)));
+        assert_eq!(
+            search.min_presses(&many, 1 << 30 | 1),
+            Ok(Outcome::Answer(2))
+        );
+        assert_eq!(
+            search.min_presses(&many[..30], 1 << 29 | 1 << 3 | 1),
+            Ok(Outcome::Answer(3))
+        );
+        // A reused search gives the same answers.
+        assert_eq!(search.min_presses(&buttons, 0b0110), Ok(Outcome::Answer(2)));
+    }
+
+    #[test]
+    fn test_light_presses_sat_matches_search() {
+        let buttons = [0b1000, 0b1010, 0b0100, 0b1100, 0b0101, 0b0011];
+        assert_eq!(
+            min_light_presses_sat(&buttons, 0b0110),
+            Ok(Outcome::Answer(2))
+        );
+        assert_eq!(
+            min_light_presses_sat(&[0b11, 0b110], 0b010),
+            Ok(Outcome::Unsolvable)
+        );
+        assert_eq!(min_light_presses_sat(&[], 0), Ok(Outcome::Answer(0)));
+
+        let mut rng = Lcg::new(10);
+        let mut search = LightSearch::new();
+        for _ in 0..200 {
+            let count = rng.between(1..=10) as usize;
+            let buttons: Vec<u32> = (0..count).map(|_| rng.below(1 << 8) as u32).collect();
+            let goal = rng.below(1 << 8) as u32;
+            assert_eq!(
+                min_light_presses_sat(&buttons, goal),
+                Ok(search.min_presses(&buttons, goal).unwrap()),
+                "{buttons:?} {goal}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_machines_match_narrow() {
+        let mut rng = Lcg::new(16);
+        let mut search = LightSearch::new();
+        let ctx = Context::new();
+        for _ in 0..200 {
+            let counters = rng.between(1..=6) as usize;
+            let lists: Vec<Vec<usize>> = (0..rng.between(1..=6))
+                .map(|_| (0..counters).filter(|_| rng.below(2) == 0).collect())
+                .collect();
+            let targets: Vec<u32> = (0..counters).map(|_| rng.below(8) as u32).collect();
+            let narrow = Machine::new(
+                lists
+                    .iter()
+                    .map(|l| u32::from_counters(counters, l.iter().copied()))
+                    .collect(),
+                targets.clone(),
+            )
+            .unwrap();
+            let wide = Machine::new(
+                lists
+                    .iter()
+                    .map(|l| WideMask::from_counters(counters, l.iter().copied()))
+                    .collect(),
+                targets,
+            )
+            .unwrap();
+            assert_eq!(wide.narrow().as_ref(), Some(&narrow));
+            for strategy in [Strategy::Parity, Strategy::Bfs] {
+                assert_eq!(
+                    min_presses_with(&wide, strategy, &ctx),
+                    min_presses_with(&narrow, strategy, &ctx),
+                    "{:?}",
+                    narrow
+                );
+            }
+
+            let lit: Vec<usize> = (0..counters).filter(|_| rng.below(2) == 0).collect();
+            let goal = u32::from_counters(counters, lit.iter().copied());
+            let wide_goal = WideMask::from_counters(counters, lit.iter().copied());
+            let expected = search.min_presses(narrow.buttons(), goal);
+            assert_eq!(
+                search.min_presses(wide.buttons(), wide_goal.clone()),
+                expected
+            );
+            assert_eq!(
+                min_light_presses_sat(wide.buttons(), wide_goal).ok(),
+                expected.ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_machine() {
+        // 130 counters, in pairs each with a button of its own
+        let buttons: Vec<String> = (0..65)
+            .map(|i| format!("({},{})", 2 * i, 2 * i + 1))
+            .collect();
+        let targets: Vec<String> = (0..130).map(|c| (c / 2 % 3).to_string()).collect();
+        let line = format!(
+            "[.] {} {{{}}}",
+            buttons[..64].join(" "),
+            targets[..128].join(",")
+        );
+        assert!(Machine::parse(&line).is_err());
+        let machine = Machine::parse_wide(&line).unwrap();
+        assert_eq!(machine.targets().len(), 128);
+        assert!(machine.narrow().is_none());
+        let expected = (0..64).map(|i| i % 3).sum::<u64>();
+        assert_eq!(
+            min_presses(&machine, &Context::new()),
+            Ok(Outcome::Answer(expected))
+        );
+        let goal = WideMask::from_counters(128, [1, 0, 127, 126]);
+        assert_eq!(
+            min_light_presses_sat(machine.buttons(), goal),
+            Ok(Outcome::Answer(2))
+        );
+        // All 128 lights in the search's state
+        let mut search = LightSearch::new();
+        let goal = WideMask::from_counters(128, [0, 1, 126, 127]);
+        assert_eq!(
+            search.min_presses(machine.buttons(), goal),
+            Ok(Outcome::Answer(2))
+        );
+        let few = &machine.buttons()[..10];
+        let goal = WideMask::from_counters(128, [0, 1, 18, 19]);
+        assert_eq!(search.min_presses(few, goal), Ok(Outcome::Answer(2)));
+        let goal = WideMask::from_counters(128, [100]);
+        assert_eq!(search.min_presses(few, goal), Ok(Outcome::Unsolvable));
+        let too_many = [WideMask::from_counters(130, 0..129)];
+        assert!(
+            search
+                .min_presses(&too_many, WideMask::from_counters(130, [0]))
+                .is_err()
+        );
+
+        let span = |line: &str| match Machine::parse_wide(line) {
+            Err(Error::Parse {
+                column, message, ..
+            }) => (column, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(
+            span("[#] (0,200) {1,1}"),
+            (Some(1), "button counter 200 out of range 0..2".to_string())
+        );
+        let too_many = format!("[#] (0) {{{}}}", vec!["1"; 33].join(","));
+        assert_eq!(
+            Machine::parse(&too_many).unwrap_err().to_string(),
+            "line 1, column 1: too many counters: 33 (max 32)"
+        );
+        assert!(Machine::parse_wide(&too_many).is_ok());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Machine::parse("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}").unwrap(),
+            machine(
+                &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]],
+                &[3, 5, 4, 7]
+            )
+        );
+        for bad in [
+            "",
+            "(0) {1}",
+            "[#] (0)",
+            "[#] (0) {1} (0)",
+            "[#] (x) {1}",
+            "[#] (32) {1}",
+            "[#] (1) {1}",
+            "[#] 0 {1}",
+            "[] {}",
+        ] {
+            assert!(Machine::parse(bad).is_err(), "{:?} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn test_input_size() {
+        let lines = [
+            "[.##.] (3) (1,3) {3,5,4,7}",
+            "",
+            "[#####.] (0) {1}",
+            "(0) {1}",
+        ]
+        .map(String::from);
+        assert_eq!(input_size(&lines).positions, 6);
+        assert_eq!(input_size(&[]), InputSize::default());
+    }
+
+    #[test]
+    fn test_parse_error_spans() {
+        let span = |line: &str| match Machine::parse(line) {
+            Err(Error::Parse { column, width, .. }) => (column, width),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(span("[#] (0,x) {1}"), (Some(8), 1));
+        assert_eq!(span("[#] (0,32) {1}"), (Some(8), 2));
+        assert_eq!(span("[#] (0) {1} (0)"), (Some(13), 3));
+        assert_eq!(span("[#] (0)"), (Some(8), 0));
+        assert_eq!(span("  (0) {1}"), (Some(3), 3));
+        assert_eq!(span("[#] (1) {1}"), (Some(1), 11));
+
+        let line = "[.#] (0) (1,y) {1,1}";
+        let err = Machine::parse(line).unwrap_err().on_line(3);
+        let source = format!("[#] (0) {{1}}\n\n{}", line);
+        assert_eq!(
+            err.render(&source),
+            "error: invalid number 'y'\n --> line 3, column 13\n  |\n3 | [.#] (0) (1,y) {1,1}\n  |             ^"
+        );
+    }
+
+    #[test]
+    fn test_machine_validation() {
+        assert_eq!(
+            Machine::new(vec![0b100], vec![1, 1])
+                .unwrap_err()
+                .to_string(),
+            "button counter 2 out of range 0..2"
+        );
+        assert_eq!(
+            Machine::new(vec![1; 65], vec![1]).unwrap_err(),
+            Violation::TooMany {
+                what: "buttons",
+                count: 65,
+                max: MAX_BUTTONS
+            }
+        );
+        assert_eq!(
+            Machine::<u32>::new(vec![], vec![0; 33])
+                .unwrap_err()
+                .to_string(),
+            "too many counters: 33 (max 32)"
+        );
+        assert!(Machine::new(vec![u32::MAX], vec![1; 32]).is_ok());
+    }
+}
@@ -0,0 +1,325 @@
+//! The error type shared by the input readers and the solvers.
+
+use crate::geo::PolygonError;
+use crate::homework::Overflow;
+use crate::solution::Cancelled;
+use crate::validate::Violation;
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong reading or solving a puzzle.
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be read.
+    Io(io::Error),
+    /// The input was read but is malformed.  `line` and `column` are 1-based
+    /// and `column` is a byte offset; `width` is how many bytes from there
+    /// are at fault.
+    Parse {
+        line: usize,
+        column: Option<usize>,
+        width: usize,
+        message: String,
+    },
+    /// The input is well formed but breaks a limit the solver relies on.
+    Invalid(Violation),
+    /// The input is well formed but has no answer.
+    NoSolution(String),
+    /// The input needs something this solver does not handle.
+    Unsupported(String),
+    /// The solver was stopped before it finished, usually by a timeout.
+    Cancelled,
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// A parse error somewhere on `line`.
+    pub fn parse(line: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            line,
+            column: None,
+            width: 0,
+            message: message.into(),
+        }
+    }
+
+    /// A parse error at a known `column` of `line`.
+    pub fn parse_at(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self::parse_span(line, column, 1, message)
+    }
+
+    /// A parse error covering `width` bytes from `column` of `line`.
+    pub fn parse_span(
+        line: usize,
+        column: usize,
+        width: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Error::Parse {
+            line,
+            column: Some(column),
+            width,
+            message: message.into(),
+        }
+    }
+
+    /// A parse error covering `token`, which must be a slice of `text`, the
+    /// text of line `line`.
+    pub fn at_token(line: usize, text: &str, token: &str, message: impl Into<String>) -> Self {
+        let offset = (token.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        if offset + token.len() <= text.len() {
+            Self::parse_span(line, offset + 1, token.len(), message)
+        } else {
+            Self::parse(line, message)
+        }
+    }
+
+    /// Moves a parse error to `line`, for parsers that see one line at a
+    /// time and number it 1.  Other errors are returned unchanged.
+    pub fn on_line(self, line: usize) -> Self {
+        match self {
+            Error::Parse {
+                column,
+                width,
+                message,
+                ..
+            } => Error::Parse {
+                line,
+                column,
+                width,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// Formats the error for a user, quoting the offending line of `source`
+    /// with the faulty span underlined:
+    ///
+    /// ```text
+    /// error: invalid range end '7x'
+    ///  --> line 2, column 3
+    ///   |
+    /// 2 | 5-7x
+    ///   |   ^^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let Error::Parse {
+            line,
+            column,
+            width,
+            message,
+        } = self
+        else {
+            return format!("error: {}", self);
+        };
+        let Some(text) = line.checked_sub(1).and_then(|idx| source.lines().nth(idx)) else {
+            return format!("error: {}", self);
+        };
+
+        let gutter = " ".repeat(line.to_string().len());
+        let mut out = format!("error: {}\n{}--> line {}", message, gutter, line);
+        if let Some(column) = column {
+            out += &format!(", column {}", column);
+        }
+        out += &format!("\n{} |\n{} | {}", gutter, line, text);
+
+        let start = column.unwrap_or(0).saturating_sub(1);
+        if let (Some(_), Some(before)) = (column, text.get(..start)) {
+            // Keep tabs so the carets line up however the terminal shows them
+            let indent: String = before
+                .chars()
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect();
+            let underlined = text
+                .get(start..(start + width).min(text.len()))
+                .map_or(0, |span| span.chars().count());
+            out += &format!("\n{} | {}{}", gutter, indent, "^".repeat(underlined.max(1)));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse {
+                line,
+                column: Some(column),
+                message,
+                ..
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            Error::Parse {
+                line,
+                column: None,
+                message,
+                ..
+            } => write!(f, "line {}: {}", line, message),
+            Error::Invalid(violation) => write!(f, "invalid input: {}", violation),
+            Error::NoSolution(message) => write!(f, "no solution: {}", message),
+            Error::Unsupported(message) => write!(f, "unsupported: {}", message),
+            Error::Cancelled => write!(f, "{}", Cancelled),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Invalid(violation) => Some(violation),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<Violation> for Error {
+    fn from(violation: Violation) -> Self {
+        Error::Invalid(violation)
+    }
+}
+
+impl From<Overflow> for Error {
+    fn from(overflow: Overflow) -> Self {
+        Error::Unsupported(overflow.to_string())
+    }
+}
+
+impl From<PolygonError> for Error {
+    fn from(error: PolygonError) -> Self {
+        Error::Unsupported(error.to_string())
+    }
+}
+
+impl From<Cancelled> for Error {
+    fn from(_: Cancelled) -> Self {
+        Error::Cancelled
+    }
+}
+
+/// Lets binaries whose `main` returns `std::io::Result` keep using `?`.
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Io(err) => err,
+            Error::Unsupported(_) => io::Error::new(io::ErrorKind::Unsupported, err.to_string()),
+            Error::Cancelled => io::Error::new(io::ErrorKind::TimedOut, err.to_string()),
+            _ => io::Error::new(io::ErrorKind::InvalidData, err.to_string()),
+        }
+    }
+}
+
+/// Lets solvers that report errors as `String` keep using `?`.
+impl From<Error> for String {
+    fn from(err: Error) -> Self {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::parse(3, "bad digit").to_string(),
+            "line 3: bad digit"
+        );
+        assert_eq!(
+            Error::parse_at(3, 7, "bad digit").to_string(),
+            "line 3, column 7: bad digit"
+        );
+        assert_eq!(
+            Error::NoSolution("target unreachable".to_string()).to_string(),
+            "no solution: target unreachable"
+        );
+        assert_eq!(
+            Error::Unsupported("more than 64 lights".to_string()).to_string(),
+            "unsupported: more than 64 lights"
+        );
+        assert_eq!(
+            Error::from(crate::validate::ensure_at_most("lights", 65, 64).unwrap_err()).to_string(),
+            "invalid input: too many lights: 65 (max 64)"
+        );
+    }
+
+    #[test]
+    fn test_io_round_trip() {
+        let err = Error::from(io::Error::new(io::ErrorKind::NotFound, "no input"));
+        assert!(std::error::Error::source(&err).is_some());
+        assert_eq!(io::Error::from(err).kind(), io::ErrorKind::NotFound);
+
+        let err = io::Error::from(Error::parse(1, "empty"));
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 1: empty");
+        let err = io::Error::from(Error::Unsupported("hex".to_string()));
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(String::from(Error::parse(2, "x")), "line 2: x");
+        let err = io::Error::from(Error::from(Cancelled));
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(err.to_string(), "cancelled");
+    }
+
+    #[test]
+    fn test_at_token() {
+        let text = "5-7x";
+        let err = Error::at_token(2, text, &text[2..], "invalid range end");
+        assert!(matches!(
+            err,
+            Error::Parse {
+                line: 2,
+                column: Some(3),
+                width: 2,
+                ..
+            }
+        ));
+        // A token from elsewhere still reports the line
+        let err = Error::at_token(2, text, "7x", "invalid range end");
+        assert_eq!(err.to_string(), "line 2: invalid range end");
+        assert_eq!(
+            Error::parse_at(1, 4, "x").on_line(9).to_string(),
+            "line 9, column 4: x"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "1-4\n5-7x\n\n3";
+        let text = source.lines().nth(1).unwrap();
+        let err = Error::at_token(2, text, &text[2..], "invalid range end '7x'");
+        assert_eq!(
+            err.render(source),
+            "error: invalid range end '7x'\n --> line 2, column 3\n  |\n2 | 5-7x\n  |   ^^"
+        );
+
+        // Without a column the line is quoted but not underlined
+        assert_eq!(
+            Error::parse(4, "bad value").render(source),
+            "error: bad value\n --> line 4\n  |\n4 | 3"
+        );
+        // Tabs before the span are kept, and wide characters count once
+        let source = "\té: #?";
+        assert_eq!(
+            Error::parse_span(1, 7, 1, "bad cell").render(source),
+            "error: bad cell\n --> line 1, column 7\n  |\n1 | \té: #?\n  | \t    ^"
+        );
+        // Lines past the end of the source fall back to the plain message
+        assert_eq!(
+            Error::parse(12, "truncated").render(source),
+            "error: line 12: truncated"
+        );
+        assert_eq!(
+            Error::NoSolution("stuck".to_string()).render(source),
+            "error: no solution: stuck"
+        );
+    }
+}
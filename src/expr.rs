@@ -0,0 +1,335 @@
+//! Arithmetic expressions with configurable precedence, for puzzles where
+//! the homework's operators bind differently from school arithmetic.  A
+//! [`Precedence`] table says which symbols are operators and how tightly
+//! each binds, and [`eval`] parses and evaluates an expression under it in
+//! `i128` by precedence climbing (a Pratt parser):
+//!
+//! ```
+//! use rust_advent::expr::{Precedence, eval};
+//!
+//! assert_eq!(eval("1 + 2 * 3", &Precedence::standard()).unwrap(), 7);
+//! assert_eq!(eval("1 + 2 * 3", &Precedence::flat()).unwrap(), 9);
+//! let addition_first = Precedence::new()
+//!     .with_operator('+', 2, i128::checked_add)
+//!     .with_operator('*', 1, i128::checked_mul);
+//! assert_eq!(eval("2 * 3 + (4 * 5)", &addition_first).unwrap(), 46);
+//! ```
+//!
+//! Day 6's worksheet is the flat case with one operator per problem; see
+//! [`homework`](crate::homework).
+//!
+//! Numbers are unsigned integers, optionally negated with a prefix `-`
+//! that binds tighter than any operator.  Every operator is left
+//! associative.  Errors point at the offending column, ready for
+//! [`Error::render`].
+
+use crate::error::{Error, Result};
+
+/// Applies an operator, or `None` when the result has no `i128` value,
+/// as on overflow or division by zero.
+pub type Apply = fn(i128, i128) -> Option<i128>;
+
+/// The binary operators an expression may use and how tightly each binds:
+/// higher precedence binds first.
+#[derive(Debug, Clone, Default)]
+pub struct Precedence {
+    operators: Vec<(char, u8, Apply)>,
+}
+
+impl Precedence {
+    /// A table with no operators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// School arithmetic: `*`, `/` and `%` before `+` and `-`.  Division
+    /// rounds towards zero.
+    pub fn standard() -> Self {
+        Precedence::new()
+            .with_operator('+', 1, i128::checked_add)
+            .with_operator('-', 1, i128::checked_sub)
+            .with_operator('*', 2, i128::checked_mul)
+            .with_operator('/', 2, i128::checked_div)
+            .with_operator('%', 2, i128::checked_rem)
+    }
+
+    /// The operators of [`Precedence::standard`], all binding equally, so
+    /// an expression is evaluated left to right.
+    pub fn flat() -> Self {
+        let mut table = Self::standard();
+        for (_, precedence, _) in &mut table.operators {
+            *precedence = 1;
+        }
+        table
+    }
+
+    /// Adds operator `symbol`, replacing any already using it.  Digits,
+    /// parentheses and whitespace cannot be operators.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symbol` is one of those.
+    pub fn with_operator(mut self, symbol: char, precedence: u8, apply: Apply) -> Self {
+        assert!(
+            !(symbol.is_ascii_digit() || symbol.is_whitespace() || "()".contains(symbol)),
+            "'{}' cannot be an operator",
+            symbol
+        );
+        self.operators.retain(|&(other, _, _)| other != symbol);
+        self.operators.push((symbol, precedence, apply));
+        self
+    }
+
+    fn get(&self, symbol: char) -> Option<(u8, Apply)> {
+        self.operators
+            .iter()
+            .find(|&&(other, _, _)| other == symbol)
+            .map(|&(_, precedence, apply)| (precedence, apply))
+    }
+}
+
+/// The value of the one-line expression `input` under `precedence`.
+/// Errors are on line 1.
+pub fn eval(input: &str, precedence: &Precedence) -> Result<i128> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        precedence,
+    };
+    let value = parser.expression(0)?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(')') => Err(parser.error_here("unmatched ')'")),
+        Some(ch) => Err(parser.error_here(format!("expected an operator, found '{}'", ch))),
+    }
+}
+
+/// The value of each non-blank line of `input`, as in a homework sheet of
+/// one expression per line.
+pub fn eval_lines(input: &str, precedence: &Precedence) -> Result<Vec<i128>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| eval(line, precedence).map_err(|err| err.on_line(idx + 1)))
+        .collect()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    /// Byte offset of the next character.
+    pos: usize,
+    precedence: &'a Precedence,
+}
+
+impl Parser<'_> {
+    /// The next character that is not whitespace, skipping up to it.
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+        self.input[self.pos..].chars().next()
+    }
+
+    fn error_here(&self, message: impl Into<String>) -> Error {
+        let width = self.input[self.pos..]
+            .chars()
+            .next()
+            .map_or(1, char::len_utf8);
+        Error::parse_span(1, self.pos + 1, width, message)
+    }
+
+    /// An expression whose operators all bind at least as tightly as
+    /// `min`, stopping before the first that does not.
+    fn expression(&mut self, min: u16) -> Result<i128> {
+        let mut lhs = self.operand()?;
+        while let Some(symbol) = self.peek() {
+            let Some((precedence, apply)) = self.precedence.get(symbol) else {
+                break;
+            };
+            if u16::from(precedence) < min {
+                break;
+            }
+            let at = self.pos;
+            self.pos += symbol.len_utf8();
+            // One more than the operator's own, for left associativity
+            let rhs = self.expression(u16::from(precedence) + 1)?;
+            lhs = apply(lhs, rhs).ok_or_else(|| {
+                let message = format!("{} {} {} has no i128 value", lhs, symbol, rhs);
+                Error::parse_span(1, at + 1, symbol.len_utf8(), message)
+            })?;
+        }
+        Ok(lhs)
+    }
+
+    /// A number, a negated operand or a parenthesized expression.
+    fn operand(&mut self) -> Result<i128> {
+        match self.peek() {
+            None => Err(Error::parse_at(
+                1,
+                self.input.len() + 1,
+                "expected a number, found the end",
+            )),
+            Some('(') => {
+                let open = self.pos;
+                self.pos += 1;
+                let value = self.expression(0)?;
+                if self.peek() != Some(')') {
+                    return Err(Error::parse_at(1, open + 1, "unclosed '('"));
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            Some('-') => {
+                let minus = self.pos;
+                self.pos += 1;
+                let value = self.operand()?;
+                value.checked_neg().ok_or_else(|| {
+                    let message = format!("-{} has no i128 value", value);
+                    Error::parse_span(1, minus + 1, self.pos - minus, message)
+                })
+            }
+            Some(ch) if ch.is_ascii_digit() => {
+                let start = self.pos;
+                let digits = self.input[self.pos..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map_or(self.input.len(), |len| self.pos + len);
+                let number = &self.input[self.pos..digits];
+                self.pos = digits;
+                number.parse().map_err(|_| {
+                    Error::parse_span(1, start + 1, number.len(), "number does not fit i128")
+                })
+            }
+            Some(ch) => Err(self.error_here(format!("expected a number, found '{}'", ch))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use crate::homework::{Operator, Problem};
+
+    fn addition_first() -> Precedence {
+        Precedence::new()
+            .with_operator('+', 2, i128::checked_add)
+            .with_operator('*', 1, i128::checked_mul)
+    }
+
+    #[test]
+    fn test_precedence_tables() {
+        let flat = Precedence::flat();
+        let cases = [
+            ("1 + 2 * 3 + 4 * 5 + 6", 71, 231),
+            ("1 + (2 * 3) + (4 * (5 + 6))", 51, 51),
+            ("2 * 3 + (4 * 5)", 26, 46),
+            ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 437, 1445),
+            (
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                13632,
+                23340,
+            ),
+        ];
+        for (input, left_to_right, plus_first) in cases {
+            assert_eq!(eval(input, &flat).unwrap(), left_to_right, "{}", input);
+            assert_eq!(
+                eval(input, &addition_first()).unwrap(),
+                plus_first,
+                "{}",
+                input
+            );
+        }
+
+        let standard = Precedence::standard();
+        assert_eq!(eval("1 + 2 * 3", &standard).unwrap(), 7);
+        assert_eq!(eval("10 - 4 - 3", &standard).unwrap(), 3);
+        assert_eq!(eval("100 / 10 / 5", &standard).unwrap(), 2);
+        assert_eq!(eval("2 * -3 - -(1 + 1)", &standard).unwrap(), -4);
+        assert_eq!(eval("17 % 5*2", &standard).unwrap(), 4);
+        assert_eq!(eval(" 42 ", &standard).unwrap(), 42);
+        assert_eq!(
+            eval("99999999999999999999 * 99999999999999999", &standard).unwrap(),
+            9_999_999_999_999_999_899_900_000_000_000_000_001
+        );
+    }
+
+    #[test]
+    fn test_custom_operators() {
+        // Concatenation binding loosest, and a two-byte symbol
+        let concat = |a: i128, b: i128| {
+            let digits = b.checked_ilog10().unwrap_or(0) + 1;
+            a.checked_mul(10i128.checked_pow(digits)?)?.checked_add(b)
+        };
+        let table = Precedence::standard()
+            .with_operator('|', 0, concat)
+            .with_operator('×', 2, i128::checked_mul);
+        assert_eq!(eval("12 | 3 + 4", &table).unwrap(), 127);
+        assert_eq!(eval("2 × 3 | 0", &table).unwrap(), 60);
+        // Replacing an operator changes its precedence
+        let table = Precedence::standard().with_operator('+', 3, i128::checked_add);
+        assert_eq!(eval("2 * 3 + 4", &table).unwrap(), 14);
+    }
+
+    #[test]
+    fn test_errors() {
+        let standard = Precedence::standard();
+        let column = |input: &str| match eval(input, &standard) {
+            Err(Error::Parse {
+                column, message, ..
+            }) => (column.unwrap(), message),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(column("1 +").0, 4);
+        assert_eq!(column("(1 + 2").0, 1);
+        assert_eq!(column("(1 + 2").1, "unclosed '('");
+        assert_eq!(column("1 + 2)").1, "unmatched ')'");
+        assert_eq!(
+            column("1 $ 2"),
+            (3, "expected an operator, found '$'".into())
+        );
+        assert_eq!(column("1 2").1, "expected an operator, found '2'");
+        assert_eq!(column("()").1, "expected a number, found ')'");
+        assert_eq!(column("").1, "expected a number, found the end");
+        assert_eq!(column("7 + 7 / 0"), (7, "7 / 0 has no i128 value".into()));
+        assert_eq!(column("1 + 170141183460469231731687303715884105728").0, 5);
+        assert_eq!(column("170141183460469231731687303715884105727 + 1").0, 41);
+
+        let err = eval_lines("1 + 2\n\n3 * (4\n", &standard).unwrap_err();
+        assert_eq!(
+            err.render("1 + 2\n\n3 * (4\n"),
+            "error: unclosed '('\n --> line 3, column 5\n  |\n3 | 3 * (4\n  |     ^"
+        );
+        assert_eq!(eval_lines("1 + 2\n\n3 * 4\n", &standard).unwrap(), [3, 12]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be an operator")]
+    fn test_digit_operator() {
+        Precedence::new().with_operator('7', 1, i128::checked_add);
+    }
+
+    #[test]
+    fn test_matches_homework() {
+        let mut rng = Lcg::new(15);
+        for _ in 0..100 {
+            let problem = Problem {
+                operator: if rng.below(2) == 0 {
+                    Operator::Add
+                } else {
+                    Operator::Multiply
+                },
+                numbers: (0..rng.between(1..=5))
+                    .map(|_| rng.below(1000) as i64)
+                    .collect(),
+            };
+            let symbol = if problem.operator == Operator::Add {
+                " + "
+            } else {
+                " * "
+            };
+            let numbers: Vec<String> = problem.numbers.iter().map(i64::to_string).collect();
+            let value = eval(&numbers.join(symbol), &Precedence::flat()).unwrap();
+            assert_eq!(Some(value), problem.evaluate().map(i128::from));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! C entry points for [`playground::solve`](crate::playground::solve), so
+//! the solvers can be called from other languages, such as a Python notebook
+//! through `ctypes`, without running a binary per input.
+//!
+//! Build a shared library with
+//!
+//! ```text
+//! cargo rustc --lib --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! The declarations below are in `include/rust_advent.h`, which the build
+//! regenerates whenever the `ffi` feature is on.
+
+use crate::playground;
+use std::ffi::{CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+
+/// The answer was written to `out`.
+pub const ADVENT_OK: i32 = 0;
+/// The input could not be solved; `out` holds the reason.
+pub const ADVENT_ERROR: i32 = 1;
+/// A pointer was null or the input was not UTF-8; `out` is untouched if it
+/// was the null one.
+pub const ADVENT_INVALID_ARGUMENT: i32 = 2;
+
+/// Solves `part` of `day` for the `len` bytes of UTF-8 at `input`, and
+/// points `*out` at a NUL-terminated string with the answer, or with the
+/// error for any status other than `ADVENT_OK`.  The string belongs to the
+/// caller, who must release it with `advent_free_string`.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, and `out` to writable space
+/// for a pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn advent_solve(
+    year: u32,
+    day: u32,
+    part: u32,
+    input: *const u8,
+    len: usize,
+    out: *mut *mut c_char,
+) -> i32 {
+    if out.is_null() {
+        return ADVENT_INVALID_ARGUMENT;
+    }
+    let (status, message) = if input.is_null() {
+        (ADVENT_INVALID_ARGUMENT, "input is null".to_string())
+    } else {
+        // SAFETY: the caller promises `len` readable bytes at `input`
+        let bytes = unsafe { std::slice::from_raw_parts(input, len) };
+        match std::str::from_utf8(bytes) {
+            Ok(text) => solve(year, day, part, text),
+            Err(err) => (
+                ADVENT_INVALID_ARGUMENT,
+                format!("input is not UTF-8: {}", err),
+            ),
+        }
+    };
+    // SAFETY: `out` is not null, and the caller promises it is writable
+    unsafe { *out = to_c_string(message) };
+    status
+}
+
+/// Releases a string returned by `advent_solve`.  Null is ignored.
+///
+/// # Safety
+///
+/// `text` must be null or a string from `advent_solve` not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn advent_free_string(text: *mut c_char) {
+    if !text.is_null() {
+        // SAFETY: the caller promises `text` came from `to_c_string`
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
+
+/// Solves without letting a panic unwind into the caller's frames.
+fn solve(year: u32, day: u32, part: u32, input: &str) -> (i32, String) {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        playground::solve(year, day, part, input)
+    }));
+    match result {
+        Ok(Ok(answer)) => (ADVENT_OK, answer.to_string()),
+        Ok(Err(err)) => (ADVENT_ERROR, err.render(input)),
+        Err(_) => (ADVENT_ERROR, "the solver panicked".to_string()),
+    }
+}
+
+/// `text` as a C string the caller owns.  Interior NULs, which C cannot
+/// represent, are dropped.
+fn to_c_string(text: String) -> *mut c_char {
+    let text = CString::new(text).unwrap_or_else(|err| {
+        CString::new(
+            err.into_vec()
+                .into_iter()
+                .filter(|&b| b != 0)
+                .collect::<Vec<_>>(),
+        )
+        .expect("NULs removed")
+    });
+    text.into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::ptr;
+
+    /// Calls `advent_solve` as C would, returning the status and the text.
+    fn call(year: u32, day: u32, part: u32, input: &[u8]) -> (i32, String) {
+        let mut out = ptr::null_mut();
+        let status =
+            unsafe { advent_solve(year, day, part, input.as_ptr(), input.len(), &mut out) };
+        assert!(!out.is_null());
+        let text = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_string();
+        unsafe { advent_free_string(out) };
+        (status, text)
+    }
+
+    #[test]
+    fn test_solve() {
+        let machines = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/golden/day10_example.txt"
+        ));
+        assert_eq!(call(2025, 10, 1, machines), (ADVENT_OK, "7".to_string()));
+        assert_eq!(call(2025, 10, 2, machines), (ADVENT_OK, "33".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        let (status, text) = call(2025, 10, 1, b"[.#] (9) {1,1}");
+        assert_eq!(status, ADVENT_ERROR);
+        assert!(text.starts_with("error: "), "{}", text);
+        assert!(text.contains("[.#] (9) {1,1}"), "{}", text);
+
+        assert_eq!(call(2025, 10, 1, b"\xff").0, ADVENT_INVALID_ARGUMENT);
+        assert_eq!(call(2025, 4, 1, b"").0, ADVENT_ERROR);
+
+        let mut out = ptr::null_mut();
+        let status = unsafe { advent_solve(2025, 10, 1, ptr::null(), 0, &mut out) };
+        assert_eq!(status, ADVENT_INVALID_ARGUMENT);
+        unsafe { advent_free_string(out) };
+        let status = unsafe { advent_solve(2025, 10, 1, b"".as_ptr(), 0, ptr::null_mut()) };
+        assert_eq!(status, ADVENT_INVALID_ARGUMENT);
+        unsafe { advent_free_string(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn test_interior_nul_dropped() {
+        let text = to_c_string("a\0b".to_string());
+        assert_eq!(unsafe { CStr::from_ptr(text) }.to_bytes(), b"ab");
+        unsafe { advent_free_string(text) };
+    }
+}
@@ -0,0 +1,533 @@
+//! Random but valid puzzle inputs, for benchmarks that scale past the size
+//! of the real inputs and for tests that compare solvers on more than the
+//! examples.  Every generator draws from an [`Lcg`], so a seed always gives
+//! the same input.
+//!
+//! The `known_*` generators build inputs whose answer follows from how they
+//! were built, and return it alongside the input.
+
+use crate::{Point, Point2d, RangeData};
+use std::ops::RangeInclusive;
+
+/// A small linear congruential generator; good enough to scatter inputs and
+/// needs no extra dependency.
+#[derive(Debug, Clone)]
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    /// A value in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// A value in `range`.
+    pub fn between(&mut self, range: RangeInclusive<u64>) -> u64 {
+        range.start() + self.below(range.end() - range.start() + 1)
+    }
+
+    /// Puts `items` in a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.below(idx as u64 + 1) as usize);
+        }
+    }
+}
+
+/// Day 1 dial rotations such as `L68`.
+pub fn random_rotations(rng: &mut Lcg, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let direction = if rng.below(2) == 0 { 'L' } else { 'R' };
+            format!("{}{}", direction, rng.between(1..=250))
+        })
+        .collect()
+}
+
+/// Day 2 comma-separated ID ranges such as `11-22,95-115`.
+pub fn random_id_ranges(rng: &mut Lcg, count: usize) -> String {
+    let ranges: Vec<String> = (0..count)
+        .map(|_| {
+            let start = rng.between(1..=1_000_000);
+            format!("{}-{}", start, start + rng.below(200))
+        })
+        .collect();
+    ranges.join(",")
+}
+
+/// Day 3 battery banks: rows of digits 1 to 9, long enough to pick twelve.
+pub fn random_digit_grid(rng: &mut Lcg, rows: usize, width: usize) -> Vec<Vec<u8>> {
+    (0..rows)
+        .map(|_| (0..width).map(|_| rng.between(1..=9) as u8).collect())
+        .collect()
+}
+
+/// Day 4 paper rolls: `@` in about `percent` of the cells, `.` elsewhere.
+pub fn random_roll_grid(rng: &mut Lcg, rows: usize, width: usize, percent: u64) -> Vec<String> {
+    (0..rows)
+        .map(|_| {
+            (0..width)
+                .map(|_| if rng.below(100) < percent { '@' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+/// Day 5 inclusive ranges and values, all in `0..1_100`.
+pub fn random_range_data(rng: &mut Lcg, ranges: usize, values: usize) -> RangeData {
+    RangeData {
+        ranges: (0..ranges)
+            .map(|_| {
+                let start = rng.below(1_000) as isize;
+                (start, start + rng.below(50) as isize)
+            })
+            .collect(),
+        values: (0..values).map(|_| rng.below(1_100) as isize).collect(),
+    }
+}
+
+/// A day 6 worksheet of `problems` side by side, each `rows` numbers tall
+/// and up to four digits wide, with the operator under its left edge.  One
+/// number fills its column and the others sit anywhere within it, so no
+/// blank column falls inside a problem.
+pub fn random_worksheet(rng: &mut Lcg, problems: usize, rows: usize) -> Vec<String> {
+    let mut lines = vec![String::new(); rows + 1];
+    for problem in 0..problems {
+        if problem > 0 {
+            for line in &mut lines {
+                line.push(' ');
+            }
+        }
+        let width = rng.between(1..=4) as usize;
+        let widest = rng.below(rows as u64) as usize;
+        for (row, line) in lines[..rows].iter_mut().enumerate() {
+            let digits = if row == widest {
+                width
+            } else {
+                rng.between(1..=width as u64) as usize
+            };
+            let number = rng.between(10u64.pow(digits as u32 - 1)..=10u64.pow(digits as u32) - 1);
+            let left = rng.below((width - digits + 1) as u64) as usize;
+            line.push_str(&format!(
+                "{}{}{}",
+                " ".repeat(left),
+                number,
+                " ".repeat(width - digits - left)
+            ));
+        }
+        let operator = if rng.below(2) == 0 { '+' } else { '*' };
+        lines[rows].push_str(&format!("{}{}", operator, " ".repeat(width - 1)));
+    }
+    lines
+}
+
+/// A day 7 manifold `width` columns wide: `S` centred on the top row, then
+/// alternating empty rows and rows with a splitter in roughly one column in
+/// `spacing`, as in the puzzle input.  The outer columns are kept clear,
+/// since solvers disagree on beams split off the edge.
+pub fn random_splitter_grid(rng: &mut Lcg, width: usize, rows: usize, spacing: u64) -> Vec<String> {
+    let mut grid = Vec::with_capacity(rows);
+    grid.push(format!(
+        "{}S{}",
+        ".".repeat(width / 2),
+        ".".repeat(width - width / 2 - 1)
+    ));
+    for row in 1..rows {
+        let line = (0..width)
+            .map(|col| {
+                if row % 2 == 0 && col > 0 && col + 1 < width && rng.below(spacing) == 0 {
+                    '^'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        grid.push(line);
+    }
+    grid
+}
+
+/// A day 8 point cloud: `count` points with coordinates in `0..span`,
+/// spread evenly like the puzzle input.
+pub fn random_points(rng: &mut Lcg, count: usize, span: u64) -> Vec<Point> {
+    (0..count)
+        .map(|_| Point {
+            x: rng.below(span) as i32,
+            y: rng.below(span) as i32,
+            z: rng.below(span) as i32,
+        })
+        .collect()
+}
+
+/// `count` points with coordinates in `0..span`.
+pub fn random_points2d(rng: &mut Lcg, count: usize, span: u64) -> Vec<Point2d> {
+    (0..count)
+        .map(|_| Point2d {
+            x: rng.below(span) as i32,
+            y: rng.below(span) as i32,
+        })
+        .collect()
+}
+
+/// The corners, in order, of a day 9 rectilinear polygon: `columns` bars of
+/// different heights standing on the x axis, like a histogram.
+pub fn random_histogram_polygon(rng: &mut Lcg, columns: usize) -> Vec<Point2d> {
+    let mut x = rng.between(1..=5) as i32;
+    let mut corners = vec![Point2d { x, y: 0 }];
+    let mut previous = 0;
+    for _ in 0..columns {
+        let mut height = rng.between(1..=12) as i32;
+        if height == previous {
+            height += 1;
+        }
+        corners.push(Point2d { x, y: height });
+        x += rng.between(1..=6) as i32;
+        corners.push(Point2d { x, y: height });
+        previous = height;
+    }
+    corners.push(Point2d { x, y: 0 });
+    corners
+}
+
+/// A day 10 machine line such as `[.##.] (3) (1,3) {3,5,4,7}` and the
+/// fewest presses for each part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownMachine {
+    pub line: String,
+    /// Fewest presses that set the lights.
+    pub light_presses: u64,
+    /// Fewest presses that reach the joltages.
+    pub joltage_presses: u64,
+}
+
+/// A day 10 machine in which every button has a counter of its own that no
+/// other button touches.  Only one set of presses then sets the lights, and
+/// only one reaches the joltages, so their sizes are the fewest presses.
+/// Each button is pressed up to `max_presses` times for the joltages.
+/// Needs `buttons <= counters`.
+pub fn known_machine(
+    rng: &mut Lcg,
+    counters: usize,
+    buttons: usize,
+    max_presses: u64,
+) -> KnownMachine {
+    assert!(
+        buttons <= counters,
+        "every button needs a counter of its own"
+    );
+    let mut order: Vec<usize> = (0..counters).collect();
+    rng.shuffle(&mut order);
+    let (own, shared) = order.split_at(buttons);
+    let buttons: Vec<Vec<usize>> = own
+        .iter()
+        .map(|&counter| {
+            let mut touched = vec![counter];
+            touched.extend(shared.iter().filter(|_| rng.below(3) == 0));
+            touched.sort_unstable();
+            touched
+        })
+        .collect();
+    let pressed: Vec<u64> = buttons.iter().map(|_| rng.below(2)).collect();
+    let presses: Vec<u64> = buttons.iter().map(|_| rng.below(max_presses + 1)).collect();
+    KnownMachine {
+        line: machine_line(counters, &buttons, &pressed, &presses),
+        light_presses: pressed.iter().sum(),
+        joltage_presses: presses.iter().sum(),
+    }
+}
+
+/// Formats a machine whose lights are lit by pressing button `i`
+/// `pressed[i]` times and whose joltages are reached by pressing it
+/// `presses[i]` times.  `buttons[i]` lists the counters button `i`
+/// increments, in increasing order.
+fn machine_line(
+    counters: usize,
+    buttons: &[Vec<usize>],
+    pressed: &[u64],
+    presses: &[u64],
+) -> String {
+    let mut lights = vec![false; counters];
+    let mut targets = vec![0u64; counters];
+    for ((button, &pressed), &presses) in buttons.iter().zip(pressed).zip(presses) {
+        for &counter in button {
+            lights[counter] ^= pressed % 2 == 1;
+            targets[counter] += presses;
+        }
+    }
+
+    let lights: String = lights
+        .iter()
+        .map(|&lit| if lit { '#' } else { '.' })
+        .collect();
+    let buttons: Vec<String> = buttons
+        .iter()
+        .map(|button| {
+            let counters: Vec<String> = button.iter().map(usize::to_string).collect();
+            format!("({})", counters.join(","))
+        })
+        .collect();
+    let targets: Vec<String> = targets.iter().map(u64::to_string).collect();
+    format!(
+        "[{}] {} {{{}}}",
+        lights,
+        buttons.join(" "),
+        targets.join(",")
+    )
+}
+
+/// A day 11 device list and its path counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceGraph {
+    pub lines: Vec<String>,
+    /// Paths from `you` to `out`.
+    pub paths_from_you: u64,
+    /// Paths from `svr` to `out` that visit both `dac` and `fft`.
+    pub paths_via_dac_fft: u64,
+}
+
+/// The most devices [`random_device_graph`] adds.  With the five named
+/// devices that makes 64, and a graph of 64 nodes has fewer than 2^63 paths
+/// between any two, so the counts fit in a `u64`.
+pub const MAX_DEVICES: usize = 59;
+
+/// A day 11 device list such as `aaa: you hhh`, acyclic, with `devices`
+/// devices besides `svr` and `you`, which come first, `out`, which comes
+/// last, and `dac` and `fft` somewhere between.  Path counts are taken over
+/// the order the devices were laid out in.
+pub fn random_device_graph(rng: &mut Lcg, devices: usize) -> DeviceGraph {
+    assert!(
+        devices <= MAX_DEVICES,
+        "too many devices for u64 path counts"
+    );
+    let mut names = vec!["svr".to_string(), "you".to_string()];
+    let letters = |rng: &mut Lcg| -> String {
+        (0..3)
+            .map(|_| char::from(b'g' + rng.below(20) as u8))
+            .collect()
+    };
+    while names.len() < devices + 2 {
+        let name = letters(rng);
+        if !names.contains(&name) && !["dac", "fft", "out"].contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+    for name in ["dac", "fft"] {
+        let at = 2 + rng.below((names.len() - 1) as u64) as usize;
+        names.insert(at, name.to_string());
+    }
+    names.push("out".to_string());
+
+    let mut outputs: Vec<Vec<usize>> = Vec::with_capacity(names.len());
+    let mut lines = Vec::new();
+    for idx in 0..names.len() - 1 {
+        let mut successors: Vec<usize> = (idx + 1..names.len())
+            .filter(|_| rng.below(3) == 0)
+            .collect();
+        if successors.is_empty() {
+            successors.push(idx + 1);
+        }
+        let listed: Vec<&str> = successors.iter().map(|&to| names[to].as_str()).collect();
+        lines.push(format!("{}: {}", names[idx], listed.join(" ")));
+        outputs.push(successors);
+    }
+    outputs.push(Vec::new());
+
+    // Edges only run forward, so counting back from `to` covers every path.
+    let paths = |from: usize, to: usize| -> u64 {
+        let mut counts = vec![0u64; names.len()];
+        counts[to] = 1;
+        for node in (from..to).rev() {
+            counts[node] = outputs[node].iter().map(|&next| counts[next]).sum();
+        }
+        counts[from]
+    };
+    let position = |name: &str| names.iter().position(|n| n == name).unwrap();
+    let (dac, fft, out) = (position("dac"), position("fft"), names.len() - 1);
+    let (first, second) = (dac.min(fft), dac.max(fft));
+    DeviceGraph {
+        lines,
+        paths_from_you: paths(1, out),
+        paths_via_dac_fft: paths(0, first) * paths(first, second) * paths(second, out),
+    }
+}
+
+/// A day 12 region line asking for `presents` presents spread at random over
+/// `shapes` kinds.
+pub fn random_region(
+    rng: &mut Lcg,
+    width: usize,
+    height: usize,
+    shapes: usize,
+    presents: usize,
+) -> String {
+    let mut counts = vec![0usize; shapes];
+    for _ in 0..presents {
+        counts[rng.below(shapes as u64) as usize] += 1;
+    }
+    region_line(width, height, &counts)
+}
+
+/// A day 12 region line and whether its presents fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownRegion {
+    pub line: String,
+    pub fits: bool,
+}
+
+/// A day 12 region up to `blocks` 3x3 blocks wide and tall, for shapes that
+/// each fit in a 3x3 block and cover `shape_cells[i]` cells.  Either the
+/// presents fit one to a block, or they cover more cells than the region
+/// has, as in the puzzle input; which is chosen at random.
+pub fn known_region(rng: &mut Lcg, shape_cells: &[usize], blocks: u64) -> KnownRegion {
+    let (across, down) = (rng.between(1..=blocks), rng.between(1..=blocks));
+    let width = (3 * across + rng.below(3)) as usize;
+    let height = (3 * down + rng.below(3)) as usize;
+    let capacity = across * down;
+    let fits = rng.below(2) == 0;
+
+    let presents = if fits {
+        capacity.div_ceil(2) + rng.below(capacity / 2 + 1)
+    } else {
+        u64::MAX
+    };
+
+    let mut counts = vec![0usize; shape_cells.len()];
+    let mut cells = 0;
+    for _ in 0..presents {
+        if !fits && cells > width * height {
+            break;
+        }
+        let shape = rng.below(shape_cells.len() as u64) as usize;
+        counts[shape] += 1;
+        cells += shape_cells[shape];
+    }
+    KnownRegion {
+        line: region_line(width, height, &counts),
+        fits,
+    }
+}
+
+fn region_line(width: usize, height: usize, counts: &[usize]) -> String {
+    let counts: Vec<String> = counts.iter().map(usize::to_string).collect();
+    format!("{}x{}: {}", width, height, counts.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day10::{LightSearch, Machine, min_presses};
+    use crate::graph::DiGraph;
+    use crate::solution::Context;
+    use crate::{Outcome, Point2d};
+
+    #[test]
+    fn test_shuffle_is_a_permutation() {
+        let mut rng = Lcg::new(7);
+        let mut items: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+        assert!((0..1000).all(|_| (3..=5).contains(&rng.between(3..=5))));
+    }
+
+    #[test]
+    fn test_known_machine_optimum() {
+        let mut rng = Lcg::new(10);
+        let mut search = LightSearch::new();
+        for _ in 0..200 {
+            let counters = rng.between(1..=8) as usize;
+            let buttons = rng.between(1..=counters as u64) as usize;
+            let known = known_machine(&mut rng, counters, buttons, 20);
+            let machine = Machine::parse(&known.line).unwrap();
+
+            let lights = known.line[1..=counters]
+                .bytes()
+                .enumerate()
+                .fold(0, |goal, (idx, b)| goal | u32::from(b == b'#') << idx);
+            assert_eq!(
+                search.min_presses(machine.buttons(), lights),
+                Ok(Outcome::Answer(known.light_presses)),
+                "{}",
+                known.line
+            );
+            assert_eq!(
+                min_presses(&machine, &Context::new()),
+                Ok(Outcome::Answer(known.joltage_presses)),
+                "{}",
+                known.line
+            );
+        }
+    }
+
+    #[test]
+    fn test_device_graph_path_counts() {
+        let mut rng = Lcg::new(11);
+        for devices in (0..=MAX_DEVICES).step_by(3) {
+            let generated = random_device_graph(&mut rng, devices);
+            let mut graph = DiGraph::new();
+            for line in &generated.lines {
+                let (from, outputs) = line.split_once(": ").unwrap();
+                for to in outputs.split(' ') {
+                    graph.add_edge(from, to);
+                }
+            }
+            let id = |name| graph.id(name).unwrap();
+            let bits = graph.to_bitset_adjacency().unwrap();
+            assert_eq!(
+                bits.count_paths::<u64>(id("you"), id("out")),
+                Ok(Some(generated.paths_from_you))
+            );
+            let required = 1 << id("dac") | 1 << id("fft");
+            assert_eq!(
+                bits.count_paths_through::<u64>(id("svr"), id("out"), required),
+                Ok(Some(generated.paths_via_dac_fft))
+            );
+        }
+    }
+
+    #[test]
+    fn test_known_region() {
+        let mut rng = Lcg::new(12);
+        let shape_cells = [7, 7, 7, 7, 7, 7];
+        let (mut fitting, mut overfull) = (0, 0);
+        for _ in 0..200 {
+            let region = known_region(&mut rng, &shape_cells, 8);
+            let (size, counts) = region.line.split_once(": ").unwrap();
+            let (width, height) = size.split_once('x').unwrap();
+            let (width, height): (usize, usize) = (width.parse().unwrap(), height.parse().unwrap());
+            let presents: usize = counts.split(' ').map(|n| n.parse::<usize>().unwrap()).sum();
+            if region.fits {
+                assert!(presents <= (width / 3) * (height / 3), "{}", region.line);
+                fitting += 1;
+            } else {
+                assert!(presents * 7 > width * height, "{}", region.line);
+                overfull += 1;
+            }
+        }
+        assert!(fitting > 50 && overfull > 50);
+    }
+
+    #[test]
+    fn test_histogram_polygon_is_rectilinear() {
+        let mut rng = Lcg::new(9);
+        for columns in 1..20 {
+            let corners: Vec<Point2d> = random_histogram_polygon(&mut rng, columns);
+            assert_eq!(corners.len(), 2 * columns + 2);
+            for (a, b) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+                assert!(a.x == b.x || a.y == b.y, "{:?} -> {:?}", a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Registering two clouds of 3D points seen from different frames, as
+//! when scanners each report the beacons around them relative to
+//! themselves, in an unknown orientation.  [`align_point_clouds`] finds the
+//! rotation, one of the 24 of [`Mat3::rotations`], and the translation
+//! that lay enough of one cloud onto the other.
+//!
+//! Trying every rotation against every pair of points is slow, so each
+//! point is first fingerprinted by its squared distances to the rest of
+//! its cloud.  Distances survive rotation and translation, so a point and
+//! its partner in an overlap of `k` points share at least `k - 1` of them,
+//! and only such pairs are tried.
+//!
+//! ```
+//! use rust_advent::geo::{Mat3, align_point_clouds};
+//!
+//! let a = [[0, 0, 0], [4, 1, 0], [1, 5, 2], [9, 9, 9]];
+//! // The first three seen turned about z and moved
+//! let b = [[10, 0, 0], [11, -4, 0], [15, -1, 2]];
+//! let alignment = align_point_clouds(&a, &b, 3).unwrap();
+//! assert_eq!(alignment.rotation, Mat3::ROTATE_Z);
+//! assert_eq!(alignment.apply([10, 0, 0]), [0, 0, 0]);
+//! ```
+//!
+//! Coordinates are assumed puzzle sized: squared distances are taken in
+//! `i64`.
+
+use crate::geo::Mat3;
+use crate::{FastMap, FastSet};
+
+/// A rigid motion from one cloud's frame into another's: rotate by
+/// `rotation`, then add `translation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Alignment {
+    pub rotation: Mat3,
+    pub translation: [i64; 3],
+}
+
+impl Alignment {
+    pub const IDENTITY: Alignment = Alignment {
+        rotation: Mat3::IDENTITY,
+        translation: [0; 3],
+    };
+
+    pub fn apply(&self, point: [i64; 3]) -> [i64; 3] {
+        let [x, y, z] = self.rotation * point;
+        let [dx, dy, dz] = self.translation;
+        [x + dx, y + dy, z + dz]
+    }
+
+    /// `self` after `first`, taking `first`'s source frame into `self`'s
+    /// target, as when chaining scanners back to the first.
+    pub fn after(&self, first: &Alignment) -> Alignment {
+        Alignment {
+            rotation: self.rotation * first.rotation,
+            translation: self.apply(first.translation),
+        }
+    }
+}
+
+/// An alignment taking at least `min_overlap` points of `b` onto points of
+/// `a`, or `None` if there is none.  Points within each cloud should be
+/// distinct.  When several alignments qualify, which is returned is
+/// unspecified but deterministic.
+pub fn align_point_clouds(a: &[[i64; 3]], b: &[[i64; 3]], min_overlap: usize) -> Option<Alignment> {
+    if min_overlap == 0 {
+        return Some(Alignment::IDENTITY);
+    }
+    if a.len() < min_overlap || b.len() < min_overlap {
+        return None;
+    }
+    // An overlap of k points shares k * (k - 1) / 2 pairwise distances
+    let mut distances: FastMap<i64, usize> = FastMap::default();
+    for d in pair_distances(a) {
+        *distances.entry(d).or_default() += 1;
+    }
+    let mut shared = 0;
+    for d in pair_distances(b) {
+        if let Some(count @ 1..) = distances.get_mut(&d) {
+            *count -= 1;
+            shared += 1;
+        }
+    }
+    if shared < min_overlap * (min_overlap - 1) / 2 {
+        return None;
+    }
+
+    let targets: FastSet<[i64; 3]> = a.iter().copied().collect();
+    let fingerprints_a: Vec<Vec<i64>> = a.iter().map(|&p| fingerprint(p, a)).collect();
+    let fingerprints_b: Vec<Vec<i64>> = b.iter().map(|&p| fingerprint(p, b)).collect();
+    let rotations = Mat3::rotations();
+    for (&pa, fa) in a.iter().zip(&fingerprints_a) {
+        for (&pb, fb) in b.iter().zip(&fingerprints_b) {
+            if common(fa, fb) + 1 < min_overlap {
+                continue;
+            }
+            for &rotation in &rotations {
+                let [x, y, z] = rotation * pb;
+                let alignment = Alignment {
+                    rotation,
+                    translation: [pa[0] - x, pa[1] - y, pa[2] - z],
+                };
+                let landed = b
+                    .iter()
+                    .filter(|&&p| targets.contains(&alignment.apply(p)))
+                    .count();
+                if landed >= min_overlap {
+                    return Some(alignment);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn distance(p: [i64; 3], q: [i64; 3]) -> i64 {
+    (0..3).map(|i| (p[i] - q[i]).pow(2)).sum()
+}
+
+fn pair_distances(points: &[[i64; 3]]) -> impl Iterator<Item = i64> + '_ {
+    points
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, &p)| points[i + 1..].iter().map(move |&q| distance(p, q)))
+}
+
+/// The squared distances from `point` to the other `points`, sorted.
+fn fingerprint(point: [i64; 3], points: &[[i64; 3]]) -> Vec<i64> {
+    let mut distances: Vec<i64> = points
+        .iter()
+        .filter(|&&q| q != point)
+        .map(|&q| distance(point, q))
+        .collect();
+    distances.sort_unstable();
+    distances
+}
+
+/// How many values two sorted lists share, counting repeats.
+fn common(a: &[i64], b: &[i64]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use std::collections::HashSet;
+
+    fn random_cloud(rng: &mut Lcg, count: usize) -> Vec<[i64; 3]> {
+        let mut seen = HashSet::new();
+        while seen.len() < count {
+            seen.insert([0; 3].map(|_| rng.below(2001) as i64 - 1000));
+        }
+        let mut cloud: Vec<[i64; 3]> = seen.into_iter().collect();
+        cloud.sort_unstable();
+        cloud
+    }
+
+    #[test]
+    fn test_recovers_hidden_alignment() {
+        let mut rng = Lcg::new(18);
+        for rotation in Mat3::rotations() {
+            let a = random_cloud(&mut rng, 25);
+            let hidden = Alignment {
+                rotation,
+                translation: [0; 3].map(|_| rng.below(4001) as i64 - 2000),
+            };
+            // b sees 12 of a's points from its own frame, plus 10 of its own
+            let inverse = Alignment {
+                rotation: hidden.rotation.transpose(),
+                translation: [0; 3],
+            };
+            let mut b: Vec<[i64; 3]> = a[..12]
+                .iter()
+                .map(|&p| {
+                    let moved = [0, 1, 2].map(|i| p[i] - hidden.translation[i]);
+                    inverse.apply(moved)
+                })
+                .collect();
+            b.extend(
+                random_cloud(&mut rng, 10)
+                    .into_iter()
+                    .map(|p| p.map(|v| v + 5000)),
+            );
+            rng.shuffle(&mut b);
+
+            let found = align_point_clouds(&a, &b, 12).unwrap();
+            assert_eq!(found, hidden);
+            assert_eq!(align_point_clouds(&a, &b, 13), None);
+        }
+    }
+
+    #[test]
+    fn test_after_chains_frames() {
+        let first = Alignment {
+            rotation: Mat3::ROTATE_X,
+            translation: [1, 2, 3],
+        };
+        let second = Alignment {
+            rotation: Mat3::ROTATE_Y.pow(3),
+            translation: [-5, 0, 7],
+        };
+        let chained = second.after(&first);
+        for p in [[0, 0, 0], [4, -2, 9]] {
+            assert_eq!(chained.apply(p), second.apply(first.apply(p)));
+        }
+        assert_eq!(Alignment::IDENTITY.after(&first), first);
+    }
+
+    #[test]
+    fn test_degenerate_overlaps() {
+        let a = [[0, 0, 0], [1, 2, 3]];
+        assert_eq!(align_point_clouds(&a, &[], 0), Some(Alignment::IDENTITY));
+        assert_eq!(align_point_clouds(&a, &[[7, 7, 7]], 2), None);
+        let one = align_point_clouds(&a, &[[7, 7, 7]], 1).unwrap();
+        assert!(a.contains(&one.apply([7, 7, 7])));
+        // Same distances, but mirrored: no rotation matches
+        let tetra = [[0, 0, 0], [1, 0, 0], [0, 2, 0], [0, 0, 3]];
+        let mirrored = tetra.map(|[x, y, z]| [-x, y, z]);
+        assert!(align_point_clouds(&tetra, &tetra, 4).is_some());
+        assert_eq!(align_point_clouds(&tetra, &mirrored, 4), None);
+    }
+}
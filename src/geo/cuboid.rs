@@ -0,0 +1,236 @@
+//! Axis-aligned cuboids of grid cells in 3D, for puzzles that switch
+//! whole blocks of a reactor on and off.  Like the areas in [`geo`](super),
+//! volumes count cells: the cuboid from `[0, 0, 0]` to `[1, 1, 1]` holds
+//! eight.
+//!
+//! ```
+//! use rust_advent::geo::{Aabb3, union_volume};
+//!
+//! let a = Aabb3::new([10, 10, 10], [12, 12, 12]).unwrap();
+//! let b = Aabb3::new([11, 11, 11], [13, 13, 13]).unwrap();
+//! assert_eq!(a.intersect(&b).map(|both| both.volume()), Some(8));
+//! assert_eq!(union_volume(&[a, b]), 27 + 27 - 8);
+//! // Switching a corner off
+//! let off = Aabb3::new([9, 9, 9], [11, 11, 11]).unwrap();
+//! let rest = a.subtract(&off);
+//! assert_eq!(rest.iter().map(Aabb3::volume).sum::<u128>(), 27 - 8);
+//! ```
+
+/// The cells from `min` to `max` on every axis, both included, so a cuboid
+/// is never empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb3 {
+    min: [i64; 3],
+    max: [i64; 3],
+}
+
+impl Aabb3 {
+    /// The cuboid from `min` to `max`, or `None` if it would be empty.
+    pub fn new(min: [i64; 3], max: [i64; 3]) -> Option<Self> {
+        (0..3)
+            .all(|axis| min[axis] <= max[axis])
+            .then_some(Aabb3 { min, max })
+    }
+
+    /// The cuboid with opposite corners `p` and `q`, in either order.
+    pub fn from_corners(p: [i64; 3], q: [i64; 3]) -> Self {
+        Aabb3 {
+            min: std::array::from_fn(|axis| p[axis].min(q[axis])),
+            max: std::array::from_fn(|axis| p[axis].max(q[axis])),
+        }
+    }
+
+    pub fn min(&self) -> [i64; 3] {
+        self.min
+    }
+
+    pub fn max(&self) -> [i64; 3] {
+        self.max
+    }
+
+    pub fn contains(&self, point: [i64; 3]) -> bool {
+        (0..3).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    /// Number of cells.  Only a cuboid spanning most of `i64` on every axis
+    /// overflows; that trips a debug assertion and saturates in release
+    /// builds.
+    pub fn volume(&self) -> u128 {
+        let volume = (0..3)
+            .map(|axis| self.max[axis].abs_diff(self.min[axis]) as u128 + 1)
+            .try_fold(1u128, u128::checked_mul);
+        debug_assert!(volume.is_some(), "{:?} overflows u128", self);
+        volume.unwrap_or(u128::MAX)
+    }
+
+    /// The cells in both, if any.
+    pub fn intersect(&self, other: &Aabb3) -> Option<Aabb3> {
+        Aabb3::new(
+            std::array::from_fn(|axis| self.min[axis].max(other.min[axis])),
+            std::array::from_fn(|axis| self.max[axis].min(other.max[axis])),
+        )
+    }
+
+    /// The cells of `self` outside `other`, as at most six disjoint
+    /// cuboids: slabs below and above the overlap on each axis in turn.
+    pub fn subtract(&self, other: &Aabb3) -> Vec<Aabb3> {
+        let Some(overlap) = self.intersect(other) else {
+            return vec![*self];
+        };
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in 0..3 {
+            if rest.min[axis] < overlap.min[axis] {
+                let mut below = rest;
+                below.max[axis] = overlap.min[axis] - 1;
+                pieces.push(below);
+            }
+            if overlap.max[axis] < rest.max[axis] {
+                let mut above = rest;
+                above.min[axis] = overlap.max[axis] + 1;
+                pieces.push(above);
+            }
+            rest.min[axis] = overlap.min[axis];
+            rest.max[axis] = overlap.max[axis];
+        }
+        pieces
+    }
+}
+
+/// Number of cells in at least one of `cuboids`.  Their edges split each
+/// axis into slabs, and each slab of `x` then `y` sums the merged `z`
+/// intervals of the cuboids covering it, so this takes `O(n^3 log n)` time
+/// in the number of cuboids however large they are.
+pub fn union_volume(cuboids: &[Aabb3]) -> u128 {
+    // Half-open edges, in i128 so `max + 1` cannot overflow
+    let edges = |cuboids: &[&Aabb3], axis: usize| {
+        let mut edges: Vec<i128> = cuboids
+            .iter()
+            .flat_map(|c| [i128::from(c.min[axis]), i128::from(c.max[axis]) + 1])
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    };
+
+    let all: Vec<&Aabb3> = cuboids.iter().collect();
+    let mut total = 0u128;
+    for x in edges(&all, 0).windows(2) {
+        let slab = covering(&all, 0, x[0], x[1]);
+        for y in edges(&slab, 1).windows(2) {
+            let column = covering(&slab, 1, y[0], y[1]);
+            let mut spans: Vec<(i128, i128)> = column
+                .iter()
+                .map(|c| (i128::from(c.min[2]), i128::from(c.max[2]) + 1))
+                .collect();
+            spans.sort_unstable();
+            let mut length = 0u128;
+            let mut reached = i128::MIN;
+            for (from, to) in spans {
+                let from = from.max(reached);
+                if from < to {
+                    length += (to - from) as u128;
+                    reached = to;
+                }
+            }
+            total += (x[1] - x[0]) as u128 * (y[1] - y[0]) as u128 * length;
+        }
+    }
+    total
+}
+
+/// The `cuboids` spanning all of `from..to` on `axis`.
+fn covering<'a>(cuboids: &[&'a Aabb3], axis: usize, from: i128, to: i128) -> Vec<&'a Aabb3> {
+    cuboids
+        .iter()
+        .copied()
+        .filter(|c| i128::from(c.min[axis]) <= from && to <= i128::from(c.max[axis]) + 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use std::collections::HashSet;
+
+    fn cuboid(min: [i64; 3], max: [i64; 3]) -> Aabb3 {
+        Aabb3::new(min, max).unwrap()
+    }
+
+    fn random_cuboid(rng: &mut Lcg) -> Aabb3 {
+        let mut corner = || [0; 3].map(|_| rng.below(9) as i64 - 4);
+        Aabb3::from_corners(corner(), corner())
+    }
+
+    fn cells(c: &Aabb3) -> impl Iterator<Item = [i64; 3]> + '_ {
+        (c.min[0]..=c.max[0]).flat_map(move |x| {
+            (c.min[1]..=c.max[1]).flat_map(move |y| (c.min[2]..=c.max[2]).map(move |z| [x, y, z]))
+        })
+    }
+
+    #[test]
+    fn test_basics() {
+        assert_eq!(Aabb3::new([0, 0, 1], [0, 0, 0]), None);
+        let c = Aabb3::from_corners([2, -1, 5], [0, 3, 5]);
+        assert_eq!((c.min(), c.max()), ([0, -1, 5], [2, 3, 5]));
+        assert_eq!(c.volume(), 15);
+        assert!(c.contains([1, 3, 5]) && !c.contains([1, 3, 6]));
+        assert_eq!(cuboid([0, 0, 0], [1, 1, 1]).volume(), 8);
+        assert_eq!(
+            cuboid([0, 0, 0], [9, 9, 9]).intersect(&cuboid([5, 9, -3], [20, 20, 0])),
+            Some(cuboid([5, 9, 0], [9, 9, 0]))
+        );
+        assert_eq!(c.intersect(&cuboid([3, 0, 0], [4, 9, 9])), None);
+        let huge = cuboid([i64::MIN, 0, 0], [i64::MAX, 1, 0]);
+        assert_eq!(huge.volume(), 1 << 65);
+        assert_eq!(union_volume(&[huge, huge]), 1 << 65);
+        assert_eq!(union_volume(&[]), 0);
+    }
+
+    #[test]
+    fn test_subtract_and_union_match_cells() {
+        let mut rng = Lcg::new(19);
+        for _ in 0..100 {
+            let a = random_cuboid(&mut rng);
+            let b = random_cuboid(&mut rng);
+            let pieces = a.subtract(&b);
+            assert!(pieces.len() <= 6);
+            let mut covered = HashSet::new();
+            for piece in &pieces {
+                for cell in cells(piece) {
+                    assert!(covered.insert(cell), "pieces overlap at {:?}", cell);
+                }
+            }
+            let expected: HashSet<[i64; 3]> = cells(&a).filter(|&p| !b.contains(p)).collect();
+            assert_eq!(covered, expected);
+
+            let cuboids: Vec<Aabb3> = (0..rng.between(1..=6))
+                .map(|_| random_cuboid(&mut rng))
+                .collect();
+            let union: HashSet<[i64; 3]> = cuboids.iter().flat_map(cells).collect();
+            assert_eq!(union_volume(&cuboids), union.len() as u128);
+        }
+    }
+
+    #[test]
+    fn test_reboot() {
+        // Switching cuboids on and off, keeping the lit cells as disjoint
+        // cuboids
+        let steps = [
+            (true, cuboid([10, 10, 10], [12, 12, 12])),
+            (true, cuboid([11, 11, 11], [13, 13, 13])),
+            (false, cuboid([9, 9, 9], [11, 11, 11])),
+            (true, cuboid([10, 10, 10], [10, 10, 10])),
+        ];
+        let mut lit: Vec<Aabb3> = Vec::new();
+        for (on, step) in steps {
+            lit = lit.iter().flat_map(|c| c.subtract(&step)).collect();
+            if on {
+                lit.push(step);
+            }
+        }
+        assert_eq!(lit.iter().map(Aabb3::volume).sum::<u128>(), 39);
+        assert_eq!(union_volume(&lit), 39);
+    }
+}
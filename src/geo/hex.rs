@@ -0,0 +1,196 @@
+//! Hexagonal grids in axial coordinates.  A hex `(q, r)` has cube
+//! coordinates `(q, r, s)` with `q + r + s = 0`, and distances and rings are
+//! simplest in those.  Hexes are pointy topped: rows of hexes run east to
+//! west, so the six neighbours are east, west and the four diagonals, as in
+//! tile flipping puzzles with directions `e`, `se`, `sw`, `w`, `nw`, `ne`.
+//! Flat topped puzzles work the same with the axes relabelled.
+
+use std::ops::{Add, Mul, Sub};
+
+/// A hex in axial coordinates, `q` increasing to the east and `r` to the
+/// south east.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HexPoint {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl HexPoint {
+    /// The steps to the six neighbours, anticlockwise from east: east,
+    /// north east, north west, west, south west and south east.
+    pub const DIRECTIONS: [HexPoint; 6] = [
+        HexPoint { q: 1, r: 0 },
+        HexPoint { q: 1, r: -1 },
+        HexPoint { q: 0, r: -1 },
+        HexPoint { q: -1, r: 0 },
+        HexPoint { q: -1, r: 1 },
+        HexPoint { q: 0, r: 1 },
+    ];
+
+    pub fn new(q: i32, r: i32) -> Self {
+        HexPoint { q, r }
+    }
+
+    /// The hex at cube coordinates `(x, y, z)`, or `None` unless they sum
+    /// to zero.
+    pub fn from_cube(x: i32, y: i32, z: i32) -> Option<Self> {
+        (i64::from(x) + i64::from(y) + i64::from(z) == 0).then_some(HexPoint { q: x, r: y })
+    }
+
+    /// The third cube coordinate, `-q - r`.
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// `(q, r, s)`, summing to zero.
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The six hexes sharing an edge with this one, in the order of
+    /// [`HexPoint::DIRECTIONS`].
+    pub fn neighbours(self) -> [HexPoint; 6] {
+        Self::DIRECTIONS.map(|step| self + step)
+    }
+
+    /// The fewest steps between neighbours from `self` to `other`: the
+    /// largest difference of a cube coordinate.  Widened so far-apart hexes
+    /// cannot overflow.
+    pub fn distance(self, other: HexPoint) -> u64 {
+        let dq = i64::from(self.q) - i64::from(other.q);
+        let dr = i64::from(self.r) - i64::from(other.r);
+        dq.unsigned_abs()
+            .max(dr.unsigned_abs())
+            .max((dq + dr).unsigned_abs())
+    }
+
+    /// The `6 * radius` hexes at exactly `radius` steps, anticlockwise
+    /// from the south west corner; just `self` at radius zero.
+    pub fn ring(self, radius: i32) -> impl Iterator<Item = HexPoint> {
+        assert!(radius >= 0, "negative radius {}", radius);
+        let sides = if radius == 0 { 1 } else { 6 };
+        (0..sides).flat_map(move |side| {
+            let corner = self + Self::DIRECTIONS[(side + 4) % 6] * radius;
+            (0..radius.max(1)).map(move |step| corner + Self::DIRECTIONS[side] * step)
+        })
+    }
+
+    /// Every hex within `radius` steps, ring by ring outwards from `self`:
+    /// `1 + 3 * radius * (radius + 1)` of them.
+    pub fn spiral(self, radius: i32) -> impl Iterator<Item = HexPoint> {
+        (0..=radius).flat_map(move |ring| self.ring(ring))
+    }
+}
+
+impl Add for HexPoint {
+    type Output = HexPoint;
+
+    fn add(self, other: HexPoint) -> HexPoint {
+        HexPoint {
+            q: self.q + other.q,
+            r: self.r + other.r,
+        }
+    }
+}
+
+impl Sub for HexPoint {
+    type Output = HexPoint;
+
+    fn sub(self, other: HexPoint) -> HexPoint {
+        HexPoint {
+            q: self.q - other.q,
+            r: self.r - other.r,
+        }
+    }
+}
+
+impl Mul<i32> for HexPoint {
+    type Output = HexPoint;
+
+    fn mul(self, times: i32) -> HexPoint {
+        HexPoint {
+            q: self.q * times,
+            r: self.r * times,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_cube_coordinates() {
+        let hex = HexPoint::new(3, -5);
+        assert_eq!(hex.cube(), (3, -5, 2));
+        assert_eq!(HexPoint::from_cube(3, -5, 2), Some(hex));
+        assert_eq!(HexPoint::from_cube(3, -5, 1), None);
+        assert_eq!(HexPoint::from_cube(i32::MAX, i32::MAX, 1), None);
+    }
+
+    #[test]
+    fn test_neighbours_and_distance() {
+        let origin = HexPoint::default();
+        let around = origin.neighbours();
+        assert_eq!(around.iter().collect::<HashSet<_>>().len(), 6);
+        for (idx, &hex) in around.iter().enumerate() {
+            assert_eq!(origin.distance(hex), 1);
+            assert_eq!(hex.distance(around[(idx + 1) % 6]), 1);
+            assert_eq!(hex.distance(around[(idx + 3) % 6]), 2);
+        }
+
+        // Directions from a tile flipping puzzle: nwwswee, back to the start
+        let [e, _, nw, w, sw, _] = HexPoint::DIRECTIONS;
+        assert_eq!(nw + w + sw + e + e, origin);
+        assert_eq!(HexPoint::new(2, -7).distance(HexPoint::new(-1, 3)), 10);
+        assert_eq!(
+            HexPoint::new(i32::MIN, 0).distance(HexPoint::new(i32::MAX, 0)),
+            u32::MAX as u64
+        );
+    }
+
+    #[test]
+    fn test_ring_and_spiral() {
+        let centre = HexPoint::new(4, -2);
+        assert_eq!(centre.ring(0).collect::<Vec<_>>(), [centre]);
+        for radius in 1..5 {
+            let ring: Vec<HexPoint> = centre.ring(radius).collect();
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+            assert!(
+                ring.iter()
+                    .all(|&hex| centre.distance(hex) == radius as u64)
+            );
+            // Each hex of a ring neighbours the next
+            for (idx, &hex) in ring.iter().enumerate() {
+                assert_eq!(hex.distance(ring[(idx + 1) % ring.len()]), 1);
+            }
+        }
+        assert_eq!(
+            centre.ring(1).next(),
+            Some(centre + HexPoint::DIRECTIONS[4])
+        );
+
+        let spiral: Vec<HexPoint> = centre.spiral(3).collect();
+        assert_eq!(spiral.len(), 1 + 3 * 3 * 4);
+        assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), spiral.len());
+        assert!(
+            spiral
+                .windows(2)
+                .all(|pair| centre.distance(pair[0]) <= centre.distance(pair[1]))
+        );
+        let mut expected = Vec::new();
+        for q in -7..=7 {
+            for r in -7..=7 {
+                let hex = centre + HexPoint::new(q, r);
+                if centre.distance(hex) <= 3 {
+                    expected.push(hex);
+                }
+            }
+        }
+        let mut sorted = spiral;
+        sorted.sort();
+        assert_eq!(sorted, expected);
+    }
+}
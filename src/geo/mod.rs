@@ -0,0 +1,426 @@
+//! Plane geometry over integer points beyond the convex hull in the crate
+//! root: the largest rectangle spanned by a pair of points, rectangles of
+//! tiles in [`rect`], rectilinear polygons in [`rectilinear`], hexagonal
+//! grids in [`hex`], and rotation and reflection matrices in
+//! [`transform`].  Beyond the plane, [`align`] registers 3D point clouds
+//! seen from different frames and [`cuboid`] measures unions of
+//! axis-aligned boxes.
+//!
+//! Areas count tiles, as in day 9: the rectangle with corners `(0, 0)` and
+//! `(2, 1)` covers six, see [`GridRect::inclusive_area`].
+
+use crate::{Point2d, rect_area_inclusive};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+pub mod align;
+pub mod cuboid;
+pub mod hex;
+pub mod rect;
+pub mod rectilinear;
+pub mod transform;
+
+pub use align::{Alignment, align_point_clouds};
+pub use cuboid::{Aabb3, union_volume};
+pub use hex::HexPoint;
+pub use rect::GridRect;
+pub use rectilinear::{PolygonError, RectilinearPolygon};
+pub use transform::{Mat, Mat2, Mat3};
+
+/// The pair of `points` spanning the axis-aligned rectangle of largest
+/// [`rect_area_inclusive`], or `None` with fewer than two points.
+///
+/// The best rectangle has its lower left corner on the staircase of points
+/// with nothing below and to their left, and its upper right corner on the
+/// staircase with nothing above and to their right (or the same with left
+/// and right swapped).  Along those staircases the best partner moves
+/// monotonically, so divide and conquer finds it in `O(n log n)` instead of
+/// trying all pairs.  Passing the convex hull instead of every point gives
+/// the same answer.
+pub fn max_area_rect_over_pairs(points: &[Point2d]) -> Option<(Point2d, Point2d)> {
+    if points.len() < 2 {
+        return None;
+    }
+    // `!x` reverses the order of x without overflowing, keeping widths
+    let mirror = |p: &Point2d| Point2d { x: !p.x, y: p.y };
+    let mirrored: Vec<Point2d> = points.iter().map(mirror).collect();
+    let straight = best_diagonal_pair(points);
+    let crossed = best_diagonal_pair(&mirrored).map(|(p, q)| (mirror(&p), mirror(&q)));
+    [straight, crossed]
+        .into_iter()
+        .flatten()
+        .max_by_key(|&(p, q)| rect_area_inclusive(p, q))
+}
+
+/// The best pair with one point below and to the left of the other.
+fn best_diagonal_pair(points: &[Point2d]) -> Option<(Point2d, Point2d)> {
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable_by_key(|p| (p.x, p.y));
+
+    // Lower left staircase: x increasing, y strictly decreasing
+    let mut lower: Vec<Point2d> = Vec::new();
+    for &p in &sorted {
+        if lower.last().is_none_or(|last| p.y < last.y) {
+            lower.push(p);
+        }
+    }
+    // Upper right staircase: x increasing, y strictly decreasing
+    let mut upper: Vec<Point2d> = Vec::new();
+    for &p in sorted.iter().rev() {
+        if upper.last().is_none_or(|last| p.y > last.y) {
+            upper.push(p);
+        }
+    }
+    upper.reverse();
+
+    let mut best = None;
+    best_partners(&lower, &upper, 0, lower.len(), 0, upper.len(), &mut best);
+    best.map(|(_, pair)| pair)
+}
+
+/// The area of the rectangle from `p` up and right to `q`, negative when
+/// `q` is not up and right of `p`.  Both sides short counts as the worst,
+/// keeping the best partner monotone along the staircases.
+fn signed_area(p: Point2d, q: Point2d) -> i128 {
+    let width = i128::from(q.x) - i128::from(p.x) + 1;
+    let height = i128::from(q.y) - i128::from(p.y) + 1;
+    if width <= 0 && height <= 0 {
+        i128::MIN
+    } else {
+        width * height
+    }
+}
+
+/// Finds the best partner in `upper[lo..hi]` for each of `lower[from..to]`.
+fn best_partners(
+    lower: &[Point2d],
+    upper: &[Point2d],
+    from: usize,
+    to: usize,
+    lo: usize,
+    hi: usize,
+    best: &mut Option<(i128, (Point2d, Point2d))>,
+) {
+    if from >= to || lo >= hi {
+        return;
+    }
+    let mid = (from + to) / 2;
+    let p = lower[mid];
+    let (partner, area) = (lo..hi)
+        .map(|idx| (idx, signed_area(p, upper[idx])))
+        .max_by_key(|&(idx, area)| (area, std::cmp::Reverse(idx)))
+        .unwrap();
+    if area > 0 && best.is_none_or(|(most, _)| area > most) {
+        *best = Some((area, (p, upper[partner])));
+    }
+    best_partners(lower, upper, from, mid, lo, partner + 1, best);
+    best_partners(lower, upper, mid + 1, to, partner, hi, best);
+}
+
+/// The largest rectangle of the tiles from `min` to `max` holding none of
+/// the `blocked` tiles, as its lower left and upper right corners, or
+/// `None` if every tile is blocked.  Blocked tiles outside are ignored.
+///
+/// A largest rectangle cannot grow, so each side rests on a blocked tile or
+/// on the bounds.  Taking each blocked tile in turn as the one above, the
+/// rectangles under it narrow as they deepen, each step cut at the highest
+/// blocked tile still beneath, found by a range maximum over the tiles
+/// lower down.  That is `O((n + m) log n)` for the `m` rectangles that
+/// cannot grow, with a tile's steps stopped once the narrowed width cannot
+/// beat the best so far.  For scattered tiles `m` is `O(n log n)` on
+/// average, but it reaches `n²` at worst, so this is quadratic at worst
+/// rather than `O(n log n)`.  Divide and conquer with matrix searching
+/// bounds the worst case by `O(n log² n)`; that is not done here.
+pub fn largest_empty_rect(
+    min: Point2d,
+    max: Point2d,
+    blocked: &[Point2d],
+) -> Option<(Point2d, Point2d)> {
+    if min.x > max.x || min.y > max.y {
+        return None;
+    }
+    let inside = |p: &Point2d| (min.x..=max.x).contains(&p.x) && (min.y..=max.y).contains(&p.y);
+    let mut points: Vec<Point2d> = blocked.iter().copied().filter(inside).collect();
+    points.sort_unstable_by_key(|p| (p.x, p.y));
+    points.dedup();
+    // Walls just outside the bounds, wide enough not to overflow
+    let (left, right) = (i64::from(min.x) - 1, i64::from(max.x) + 1);
+    let (floor, ceiling) = (i64::from(min.y) - 1, i64::from(max.y) + 1);
+    let mut best: Option<(u64, (Point2d, Point2d))> = None;
+
+    // Up to the ceiling and down to the floor, between neighbouring columns
+    let xs = points.iter().map(|p| i64::from(p.x));
+    let walls: Vec<i64> = std::iter::once(left)
+        .chain(xs)
+        .chain(std::iter::once(right))
+        .collect();
+    for pair in walls.windows(2) {
+        offer_rect(&mut best, pair[0], pair[1], floor, ceiling);
+    }
+
+    // Up to the ceiling from each blocked tile, between the nearest columns
+    // blocked higher up on either side
+    let mut by_height = points.clone();
+    by_height.sort_unstable_by_key(|p| std::cmp::Reverse(p.y));
+    let mut higher: BTreeSet<i32> = BTreeSet::new();
+    for level in by_height.chunk_by(|a, b| a.y == b.y) {
+        for p in level {
+            if higher.contains(&p.x) {
+                continue;
+            }
+            let l = higher
+                .range(..p.x)
+                .next_back()
+                .map_or(left, |&x| i64::from(x));
+            let r = higher.range(p.x..).next().map_or(right, |&x| i64::from(x));
+            offer_rect(&mut best, l, r, i64::from(p.y), ceiling);
+        }
+        higher.extend(level.iter().map(|p| p.x));
+    }
+
+    // Down from each blocked tile, narrowing at each blocked tile below
+    let mut lower = HighestBelow::new(points.len());
+    by_height.reverse();
+    for level in by_height.chunk_by(|a, b| a.y == b.y) {
+        for p in level {
+            let (top, x) = (i64::from(p.y), i64::from(p.x));
+            let (mut l, mut r) = (left, right);
+            loop {
+                let reach = (r - l - 1) as u64 * (top - floor - 1) as u64;
+                if best.is_some_and(|(most, _)| reach <= most) {
+                    break;
+                }
+                let from = points.partition_point(|q| i64::from(q.x) <= l);
+                let to = points.partition_point(|q| i64::from(q.x) < r);
+                let Some(idx) = lower.highest(from, to) else {
+                    offer_rect(&mut best, l, r, floor, top);
+                    break;
+                };
+                let q = points[idx];
+                offer_rect(&mut best, l, r, i64::from(q.y), top);
+                match i64::from(q.x).cmp(&x) {
+                    Ordering::Less => l = i64::from(q.x),
+                    Ordering::Greater => r = i64::from(q.x),
+                    Ordering::Equal => break,
+                }
+            }
+        }
+        for p in level {
+            let idx = points.binary_search_by_key(&(p.x, p.y), |q| (q.x, q.y));
+            lower.insert(idx.unwrap(), p.y);
+        }
+    }
+    best.map(|(_, corners)| corners)
+}
+
+/// Keeps the tiles strictly between walls `l` and `r` and between `b` and
+/// `t` if they are the most yet.
+fn offer_rect(best: &mut Option<(u64, (Point2d, Point2d))>, l: i64, r: i64, b: i64, t: i64) {
+    if r - l < 2 || t - b < 2 {
+        return;
+    }
+    let area = (r - l - 1) as u64 * (t - b - 1) as u64;
+    if best.is_none_or(|(most, _)| area > most) {
+        let corner = |x: i64, y: i64| Point2d {
+            x: x as i32,
+            y: y as i32,
+        };
+        *best = Some((area, (corner(l + 1, b + 1), corner(r - 1, t - 1))));
+    }
+}
+
+/// A range maximum over positions, each empty until given a height.
+struct HighestBelow {
+    size: usize,
+    /// Leaves from `size`, each node the highest of its two children
+    tree: Vec<Option<(i32, usize)>>,
+}
+
+impl HighestBelow {
+    fn new(len: usize) -> Self {
+        let size = len.next_power_of_two();
+        HighestBelow {
+            size,
+            tree: vec![None; 2 * size],
+        }
+    }
+
+    fn insert(&mut self, idx: usize, height: i32) {
+        let mut node = idx + self.size;
+        self.tree[node] = Some((height, idx));
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = self.tree[2 * node].max(self.tree[2 * node + 1]);
+        }
+    }
+
+    /// The position in `from..to` with the greatest height.
+    fn highest(&self, from: usize, to: usize) -> Option<usize> {
+        let (mut lo, mut hi) = (from + self.size, to + self.size);
+        let mut best = None;
+        while lo < hi {
+            if lo % 2 == 1 {
+                best = best.max(self.tree[lo]);
+                lo += 1;
+            }
+            if hi % 2 == 1 {
+                hi -= 1;
+                best = best.max(self.tree[hi]);
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+        best.map(|(_, idx)| idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convex_hull;
+    use crate::generators::{Lcg, random_points2d};
+
+    fn brute_force(points: &[Point2d]) -> u64 {
+        let mut best = 0;
+        for (idx, &p) in points.iter().enumerate() {
+            for &q in &points[idx + 1..] {
+                best = best.max(rect_area_inclusive(p, q));
+            }
+        }
+        best
+    }
+
+    fn area(pair: Option<(Point2d, Point2d)>) -> u64 {
+        pair.map_or(0, |(p, q)| rect_area_inclusive(p, q))
+    }
+
+    #[test]
+    fn test_max_area_rect_over_pairs() {
+        let points: Vec<Point2d> = [
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]
+        .map(|(x, y)| Point2d { x, y })
+        .to_vec();
+        let (p, q) = max_area_rect_over_pairs(&points).unwrap();
+        assert_eq!(rect_area_inclusive(p, q), 50);
+        assert!(points.contains(&p) && points.contains(&q));
+
+        assert_eq!(max_area_rect_over_pairs(&points[..1]), None);
+        let line = [Point2d { x: 0, y: 5 }, Point2d { x: 9, y: 5 }];
+        assert_eq!(area(max_area_rect_over_pairs(&line)), 10);
+        let same = [Point2d { x: 3, y: 3 }; 2];
+        assert_eq!(area(max_area_rect_over_pairs(&same)), 1);
+    }
+
+    #[test]
+    fn test_max_area_rect_matches_brute_force() {
+        let mut rng = Lcg::new(9);
+        for count in [2, 3, 5, 10, 40, 150] {
+            for _ in 0..20 {
+                let mut points = random_points2d(&mut rng, count, 60);
+                for p in &mut points {
+                    p.x -= 30;
+                }
+                let expected = brute_force(&points);
+                assert_eq!(
+                    area(max_area_rect_over_pairs(&points)),
+                    expected,
+                    "{:?}",
+                    points
+                );
+                let hull = convex_hull(&points);
+                if hull.len() >= 2 {
+                    assert_eq!(area(max_area_rect_over_pairs(&hull)), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_area_rect_extreme_coordinates() {
+        // Upper left to lower right, across the whole range of x
+        let points = [
+            Point2d { x: i32::MIN, y: 0 },
+            Point2d { x: i32::MAX, y: -1 },
+            Point2d { x: 0, y: 0 },
+        ];
+        assert_eq!(area(max_area_rect_over_pairs(&points)), 1 << 33);
+    }
+
+    /// Tries every rectangle of the bounds against every blocked tile.
+    fn brute_force_empty(min: Point2d, max: Point2d, blocked: &[Point2d]) -> u64 {
+        let tiles: Vec<Point2d> = (min.x..=max.x)
+            .flat_map(|x| (min.y..=max.y).map(move |y| Point2d { x, y }))
+            .collect();
+        let mut best = 0;
+        for &p in &tiles {
+            for &q in &tiles {
+                let within = |b: &Point2d| {
+                    (p.x.min(q.x)..=p.x.max(q.x)).contains(&b.x)
+                        && (p.y.min(q.y)..=p.y.max(q.y)).contains(&b.y)
+                };
+                if !blocked.iter().any(within) {
+                    best = best.max(rect_area_inclusive(p, q));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_largest_empty_rect() {
+        let point = |x, y| Point2d { x, y };
+        let (min, max) = (point(0, 0), point(9, 4));
+        assert_eq!(largest_empty_rect(min, max, &[]), Some((min, max)));
+        let blocked = [point(3, 2), point(6, 0), point(20, 20)];
+        assert_eq!(
+            largest_empty_rect(min, max, &blocked),
+            Some((point(4, 1), point(9, 4)))
+        );
+        let all = [point(0, 0), point(0, 1), point(1, 0), point(1, 1)];
+        assert_eq!(largest_empty_rect(point(0, 0), point(1, 1), &all), None);
+        assert_eq!(largest_empty_rect(point(1, 0), point(0, 0), &[]), None);
+    }
+
+    #[test]
+    fn test_largest_empty_rect_matches_brute_force() {
+        let mut rng = Lcg::new(27);
+        let mut point = || Point2d {
+            x: rng.below(9) as i32 - 4,
+            y: rng.below(9) as i32 - 4,
+        };
+        for count in [1, 2, 5, 10, 30, 80] {
+            for _ in 0..20 {
+                let (a, b) = (point(), point());
+                let min = Point2d {
+                    x: a.x.min(b.x),
+                    y: a.y.min(b.y),
+                };
+                let max = Point2d {
+                    x: a.x.max(b.x),
+                    y: a.y.max(b.y),
+                };
+                let blocked: Vec<Point2d> = (0..count).map(|_| point()).collect();
+                let found = largest_empty_rect(min, max, &blocked);
+                assert_eq!(
+                    area(found),
+                    brute_force_empty(min, max, &blocked),
+                    "{:?} {:?} {:?}",
+                    min,
+                    max,
+                    blocked
+                );
+                if let Some((p, q)) = found {
+                    assert!(min.x <= p.x && q.x <= max.x && min.y <= p.y && q.y <= max.y);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Axis-aligned rectangles of grid tiles, the plane's counterpart of
+//! [`Aabb3`](super::Aabb3).  Both corners are tiles of the rectangle, so
+//! the one from `(0, 0)` to `(2, 1)` covers six, as in day 9.
+//!
+//! ```
+//! use rust_advent::Point2d;
+//! use rust_advent::geo::GridRect;
+//!
+//! let a = GridRect::from_corners(Point2d { x: 2, y: 5 }, Point2d { x: 11, y: 1 });
+//! assert_eq!(a.inclusive_area(), 50);
+//! let b = GridRect::from_corners(Point2d { x: 9, y: 0 }, Point2d { x: 20, y: 3 });
+//! assert_eq!(a.intersect(&b).map(|both| both.inclusive_area()), Some(9));
+//! ```
+
+use crate::Point2d;
+
+/// The tiles from `min` to `max` on both axes, both included, so a
+/// rectangle is never empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridRect {
+    min: Point2d,
+    max: Point2d,
+}
+
+impl GridRect {
+    /// The rectangle from `min` to `max`, or `None` if it would be empty.
+    pub fn new(min: Point2d, max: Point2d) -> Option<Self> {
+        (min.x <= max.x && min.y <= max.y).then_some(GridRect { min, max })
+    }
+
+    /// The rectangle with opposite corners `p` and `q`, in either order.
+    pub fn from_corners(p: Point2d, q: Point2d) -> Self {
+        GridRect {
+            min: Point2d {
+                x: p.x.min(q.x),
+                y: p.y.min(q.y),
+            },
+            max: Point2d {
+                x: p.x.max(q.x),
+                y: p.y.max(q.y),
+            },
+        }
+    }
+
+    /// The smallest rectangle holding every one of `points`, or `None` if
+    /// there are none.
+    pub fn bounding(points: impl IntoIterator<Item = Point2d>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(
+            points.fold(GridRect::from_corners(first, first), |b, p| GridRect {
+                min: Point2d {
+                    x: b.min.x.min(p.x),
+                    y: b.min.y.min(p.y),
+                },
+                max: Point2d {
+                    x: b.max.x.max(p.x),
+                    y: b.max.y.max(p.y),
+                },
+            }),
+        )
+    }
+
+    pub fn min(&self) -> Point2d {
+        self.min
+    }
+
+    pub fn max(&self) -> Point2d {
+        self.max
+    }
+
+    /// Tiles along `x`.
+    pub fn width(&self) -> u64 {
+        self.max.dx_i64(self.min).unsigned_abs() + 1
+    }
+
+    /// Tiles along `y`.
+    pub fn height(&self) -> u64 {
+        self.max.dy_i64(self.min).unsigned_abs() + 1
+    }
+
+    /// Number of tiles, counting both edges.  Only a rectangle spanning the
+    /// full `i32` range on both axes overflows; that trips a debug
+    /// assertion and saturates in release builds.
+    pub fn inclusive_area(&self) -> u64 {
+        let area = self.width().checked_mul(self.height());
+        debug_assert!(area.is_some(), "{:?} overflows u64", self);
+        area.unwrap_or(u64::MAX)
+    }
+
+    pub fn contains(&self, p: Point2d) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// The tiles in both, if any.
+    pub fn intersect(&self, other: &GridRect) -> Option<GridRect> {
+        GridRect::new(
+            Point2d {
+                x: self.min.x.max(other.min.x),
+                y: self.min.y.max(other.min.y),
+            },
+            Point2d {
+                x: self.max.x.min(other.max.x),
+                y: self.max.y.min(other.max.y),
+            },
+        )
+    }
+
+    /// The four corner tiles, starting at `min` and going along `x` first,
+    /// so counter-clockwise with `y` pointing up.  A rectangle one tile
+    /// wide or tall repeats corners.
+    pub fn corners(&self) -> [Point2d; 4] {
+        let (min, max) = (self.min, self.max);
+        [
+            min,
+            Point2d { x: max.x, y: min.y },
+            max,
+            Point2d { x: min.x, y: max.y },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+
+    fn point(x: i32, y: i32) -> Point2d {
+        Point2d { x, y }
+    }
+
+    fn random_rect(rng: &mut Lcg) -> GridRect {
+        let mut corner = || point(rng.below(9) as i32 - 4, rng.below(9) as i32 - 4);
+        GridRect::from_corners(corner(), corner())
+    }
+
+    fn tiles(r: &GridRect) -> Vec<Point2d> {
+        (r.min.x..=r.max.x)
+            .flat_map(|x| (r.min.y..=r.max.y).map(move |y| point(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn test_basics() {
+        assert_eq!(GridRect::new(point(1, 0), point(0, 0)), None);
+        let r = GridRect::from_corners(point(3, -1), point(0, 2));
+        assert_eq!((r.min(), r.max()), (point(0, -1), point(3, 2)));
+        assert_eq!((r.width(), r.height(), r.inclusive_area()), (4, 4, 16));
+        assert_eq!(
+            r.corners(),
+            [point(0, -1), point(3, -1), point(3, 2), point(0, 2)]
+        );
+        assert!(r.corners().iter().all(|&c| r.contains(c)));
+        assert!(!r.contains(point(4, 0)));
+        let line = GridRect::from_corners(point(i32::MIN, 7), point(i32::MAX, 7));
+        assert_eq!(line.inclusive_area(), 1 << 32);
+        assert_eq!(
+            GridRect::bounding([point(5, 1), point(-2, 3), point(0, 0)]),
+            GridRect::new(point(-2, 0), point(5, 3))
+        );
+        assert_eq!(GridRect::bounding([]), None);
+    }
+
+    #[test]
+    fn test_intersect_matches_tiles() {
+        let mut rng = Lcg::new(25);
+        for _ in 0..200 {
+            let a = random_rect(&mut rng);
+            let b = random_rect(&mut rng);
+            let both: Vec<Point2d> = tiles(&a).into_iter().filter(|&p| b.contains(p)).collect();
+            match a.intersect(&b) {
+                Some(overlap) => {
+                    assert_eq!(tiles(&overlap), both);
+                    assert_eq!(overlap.inclusive_area(), both.len() as u64);
+                }
+                None => assert!(both.is_empty()),
+            }
+            assert_eq!(a.inclusive_area(), tiles(&a).len() as u64);
+        }
+    }
+}
@@ -0,0 +1,691 @@
+//! Polygons whose edges are all horizontal or vertical, such as day 9's
+//! loop of red tiles.  A polygon covers whole tiles: its boundary tiles and
+//! every tile inside them.
+//!
+//! A rectangle counts as covered when all of it lies within the boundary
+//! drawn through the tile centres, as the edge-crossing tests of the day 9
+//! solvers check.  Two edges one tile apart leave no tiles between them,
+//! but the sliver between still lies outside: a rectangle reaching across
+//! such a zero-width notch is not covered, even though each of its tiles
+//! is on the boundary.
+
+use crate::{Point2d, rect_area_inclusive};
+use std::fmt;
+
+/// Why a list of corners is not a simple rectilinear polygon.  Corners and
+/// edges are numbered from 0 in input order; edge `i` runs from corner `i`
+/// to the next, and the last edge back to corner 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolygonError {
+    /// There are `count` corners, fewer than the four a polygon needs.
+    TooFewCorners { count: usize },
+    /// Edge `index` has no length: its two ends are both `corner`, as when
+    /// the first corner is listed again at the end.
+    RepeatedCorner { index: usize, corner: Point2d },
+    /// The last corner shares no coordinate with the first, so the edge
+    /// closing the loop cannot be drawn along an axis.
+    Unclosed { first: Point2d, last: Point2d },
+    /// Edge `index` from `from` to `to` is neither horizontal nor vertical.
+    Diagonal {
+        index: usize,
+        from: Point2d,
+        to: Point2d,
+    },
+    /// The edges meeting at corner `index` carry on in a straight line or
+    /// double back, rather than turning.
+    NoTurn { index: usize, corner: Point2d },
+    /// The corners turn `quarter_turns` quarter turns in all, rather than
+    /// once around clockwise or anticlockwise.
+    Winding { quarter_turns: i64 },
+    /// Edges `first` and `second` cross or touch.
+    SelfIntersection { first: usize, second: usize },
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolygonError::TooFewCorners { count } => {
+                write!(f, "polygon has {} corners, expected at least 4", count)
+            }
+            PolygonError::RepeatedCorner { index, corner } => {
+                write!(f, "edge {} starts and ends at {:?}", index, corner)
+            }
+            PolygonError::Unclosed { first, last } => write!(
+                f,
+                "last corner {:?} is not in line with first corner {:?}",
+                last, first
+            ),
+            PolygonError::Diagonal { index, from, to } => {
+                write!(
+                    f,
+                    "edge {} from {:?} to {:?} is not rectilinear",
+                    index, from, to
+                )
+            }
+            PolygonError::NoTurn { index, corner } => {
+                write!(f, "polygon does not turn at corner {} {:?}", index, corner)
+            }
+            PolygonError::Winding { quarter_turns } => write!(
+                f,
+                "corners make {} quarter turns, expected 4 or -4",
+                quarter_turns
+            ),
+            PolygonError::SelfIntersection { first, second } => {
+                write!(f, "edges {} and {} intersect", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+/// A closed rectilinear polygon given by its corners in order; the last
+/// corner joins back to the first.  The corners are taken as given: each
+/// consecutive pair is expected to share an `x` or a `y`, and the edges
+/// not to cross, which [`validate`](Self::validate) checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RectilinearPolygon {
+    vertices: Vec<Point2d>,
+}
+
+impl RectilinearPolygon {
+    pub fn new(vertices: Vec<Point2d>) -> Self {
+        RectilinearPolygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point2d] {
+        &self.vertices
+    }
+
+    /// The edges as pairs of consecutive corners, closing the loop.
+    pub fn edges(&self) -> impl Iterator<Item = (Point2d, Point2d)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |idx| (self.vertices[idx], self.vertices[(idx + 1) % n]))
+    }
+
+    /// Checks that the corners make a simple rectilinear polygon, which the
+    /// other methods assume without checking: every edge, including the
+    /// one closing the loop, is horizontal or vertical, the polygon turns
+    /// at every corner and once around in all, and no two edges meet
+    /// except neighbours at their shared corner.  Reports the first
+    /// problem found, checking in that order; the last check is `O(n²)`.
+    pub fn validate(&self) -> Result<(), PolygonError> {
+        let corners = &self.vertices;
+        let n = corners.len();
+        if n < 4 {
+            return Err(PolygonError::TooFewCorners { count: n });
+        }
+        for (index, (from, to)) in self.edges().enumerate() {
+            if from == to {
+                return Err(PolygonError::RepeatedCorner {
+                    index,
+                    corner: from,
+                });
+            }
+            if from.x != to.x && from.y != to.y {
+                return Err(if index == n - 1 {
+                    PolygonError::Unclosed {
+                        first: to,
+                        last: from,
+                    }
+                } else {
+                    PolygonError::Diagonal { index, from, to }
+                });
+            }
+        }
+
+        let mut quarter_turns = 0;
+        for (index, &corner) in corners.iter().enumerate() {
+            let before = corners[(index + n - 1) % n];
+            let after = corners[(index + 1) % n];
+            let (dx_in, dy_in) = (
+                corner.dx_i64(before).signum(),
+                corner.dy_i64(before).signum(),
+            );
+            let (dx_out, dy_out) = (after.dx_i64(corner).signum(), after.dy_i64(corner).signum());
+            let turn = dx_in * dy_out - dy_in * dx_out;
+            if turn == 0 {
+                return Err(PolygonError::NoTurn { index, corner });
+            }
+            quarter_turns += turn;
+        }
+        if quarter_turns.abs() != 4 {
+            return Err(PolygonError::Winding { quarter_turns });
+        }
+
+        // Axis-aligned edges meet exactly when their bounding boxes do
+        let bounds: Vec<_> = self
+            .edges()
+            .map(|(a, b)| (a.x.min(b.x), a.x.max(b.x), a.y.min(b.y), a.y.max(b.y)))
+            .collect();
+        for first in 0..n {
+            // Neighbours share a corner, and turning keeps them apart beyond it
+            let last = if first == 0 { n - 1 } else { n };
+            for second in first + 2..last {
+                let (a, b) = (bounds[first], bounds[second]);
+                if a.0 <= b.1 && b.0 <= a.1 && a.2 <= b.3 && b.2 <= a.3 {
+                    return Err(PolygonError::SelfIntersection { first, second });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The pair of corners spanning the largest rectangle whose tiles the
+    /// polygon covers, or `None` with fewer than three corners.
+    ///
+    /// Only corners matter, so the plane is compressed to a grid of the
+    /// corners' coordinates and the gaps between them; each compressed cell
+    /// is wholly covered or wholly not.  A prefix sum over uncovered cells
+    /// then checks each pair in constant time: `O(n²)` overall, against
+    /// `O(n³)` for testing every pair against every edge.
+    pub fn largest_vertex_rectangle(&self) -> Option<(Point2d, Point2d)> {
+        if self.vertices.len() < 3 {
+            return None;
+        }
+        let grid = CompressedGrid::new(self);
+        let mut best: Option<(u64, (Point2d, Point2d))> = None;
+        for (idx, &p) in self.vertices.iter().enumerate() {
+            for &q in &self.vertices[idx + 1..] {
+                let area = rect_area_inclusive(p, q);
+                if best.is_some_and(|(most, _)| area <= most) {
+                    continue;
+                }
+                if grid.covers(p, q) {
+                    best = Some((area, (p, q)));
+                }
+            }
+        }
+        best.map(|(_, pair)| pair)
+    }
+
+    /// The opposite corners of the largest rectangle whose tiles the polygon
+    /// covers, wherever its corners fall, or `None` with fewer than three
+    /// corners.  Unlike [`largest_vertex_rectangle`](Self::largest_vertex_rectangle),
+    /// a rectangle may run from wall to wall between corners, such as along
+    /// a corridor.
+    ///
+    /// A largest rectangle is made of whole compressed cells, so this is
+    /// the largest all-covered rectangle of the compressed grid with each
+    /// cell weighted by its tiles: a weighted histogram per row, `O(n²)`.
+    pub fn largest_inscribed_rectangle(&self) -> Option<(Point2d, Point2d)> {
+        if self.vertices.len() < 3 {
+            return None;
+        }
+        CompressedGrid::new(self).largest_rectangle()
+    }
+}
+
+/// The polygon's coverage over compressed coordinates.  Index `2 * i` is
+/// the `i`th distinct corner coordinate and `2 * i + 1` the open gap after
+/// it, which may hold no tiles at all.  Each compressed cell, tiles or
+/// not, lies wholly inside the boundary or wholly outside it.
+struct CompressedGrid {
+    xs: Vec<i32>,
+    ys: Vec<i32>,
+    covered: Vec<Vec<bool>>,
+    /// `uncovered[r][c]` counts uncovered cells in rows `..r`, columns `..c`.
+    uncovered: Vec<Vec<u32>>,
+}
+
+impl CompressedGrid {
+    fn new(polygon: &RectilinearPolygon) -> Self {
+        let mut xs: Vec<i32> = polygon.vertices.iter().map(|p| p.x).collect();
+        let mut ys: Vec<i32> = polygon.vertices.iter().map(|p| p.y).collect();
+        xs.sort_unstable();
+        xs.dedup();
+        ys.sort_unstable();
+        ys.dedup();
+        let (width, height) = (2 * xs.len() - 1, 2 * ys.len() - 1);
+
+        let mut covered = vec![vec![false; width]; height];
+        // Boundary tiles
+        for (a, b) in polygon.edges() {
+            let (c0, c1) = ordered(index_of(&xs, a.x), index_of(&xs, b.x));
+            let (r0, r1) = ordered(index_of(&ys, a.y), index_of(&ys, b.y));
+            for row in &mut covered[r0..=r1] {
+                row[c0..=c1].fill(true);
+            }
+        }
+        // Interior cells, empty gaps included: an odd number of vertical
+        // edges to the right, each counted over the half-open span of
+        // compressed rows [low, high)
+        let verticals: Vec<(usize, usize, usize)> = polygon
+            .edges()
+            .filter(|(a, b)| a.x == b.x)
+            .map(|(a, b)| {
+                let (low, high) = ordered(index_of(&ys, a.y), index_of(&ys, b.y));
+                (index_of(&xs, a.x), low, high)
+            })
+            .collect();
+        for (row_idx, row) in covered.iter_mut().enumerate() {
+            let mut crossings: Vec<usize> = verticals
+                .iter()
+                .filter(|&&(_, low, high)| low <= row_idx && row_idx < high)
+                .map(|&(col, _, _)| col)
+                .collect();
+            crossings.sort_unstable();
+            let mut right = crossings.len();
+            for (col, cell) in row.iter_mut().enumerate() {
+                while right > 0 && crossings[crossings.len() - right] <= col {
+                    right -= 1;
+                }
+                if right % 2 == 1 {
+                    *cell = true;
+                }
+            }
+        }
+
+        let mut uncovered = vec![vec![0u32; width + 1]; height + 1];
+        for r in 0..height {
+            for c in 0..width {
+                uncovered[r + 1][c + 1] = uncovered[r][c + 1] + uncovered[r + 1][c]
+                    - uncovered[r][c]
+                    + u32::from(!covered[r][c]);
+            }
+        }
+        CompressedGrid {
+            xs,
+            ys,
+            covered,
+            uncovered,
+        }
+    }
+
+    /// Whether every tile of the rectangle between corners `p` and `q` is
+    /// covered.
+    fn covers(&self, p: Point2d, q: Point2d) -> bool {
+        let (c0, c1) = ordered(index_of(&self.xs, p.x), index_of(&self.xs, q.x));
+        let (r0, r1) = ordered(index_of(&self.ys, p.y), index_of(&self.ys, q.y));
+        let sum = &self.uncovered;
+        sum[r1 + 1][c1 + 1] + sum[r0][c0] == sum[r0][c1 + 1] + sum[r1 + 1][c0]
+    }
+
+    /// The largest all-covered rectangle, row by row: each column's covered
+    /// run ending at the row is a bar, and the best rectangle with its
+    /// bottom on the row is as tall as some bar and spans the bars around
+    /// it at least as tall.
+    fn largest_rectangle(&self) -> Option<(Point2d, Point2d)> {
+        let width = self.covered[0].len();
+        // Tiles left of each column
+        let mut before = vec![0u64; width + 1];
+        for col in 0..width {
+            before[col + 1] = before[col] + tiles(&self.xs, col);
+        }
+        let mut bars = vec![(0u64, 0usize); width];
+        let mut best: Option<(u64, (Point2d, Point2d))> = None;
+        for (row, covered) in self.covered.iter().enumerate() {
+            for (bar, &covered) in bars.iter_mut().zip(covered) {
+                *bar = match covered {
+                    true => (bar.0 + tiles(&self.ys, row), bar.1 + 1),
+                    false => (0, 0),
+                };
+            }
+            let left = reach(bars.iter().map(|bar| bar.0));
+            let mut right = reach(bars.iter().rev().map(|bar| bar.0));
+            right.reverse();
+            for (col, &(tall, cells)) in bars.iter().enumerate() {
+                let (first, last) = (col - left[col], col + right[col]);
+                let area = tall * (before[last + 1] - before[first]);
+                if area == 0 || best.is_some_and(|(most, _)| area <= most) {
+                    continue;
+                }
+                let low = Point2d {
+                    x: span(&self.xs, first).0,
+                    y: span(&self.ys, row + 1 - cells).0,
+                };
+                let high = Point2d {
+                    x: span(&self.xs, last).1,
+                    y: span(&self.ys, row).1,
+                };
+                best = Some((area, (low, high)));
+            }
+        }
+        best.map(|(_, pair)| pair)
+    }
+}
+
+/// For each bar, how many bars just before it are at least as tall.
+fn reach(bars: impl Iterator<Item = u64>) -> Vec<usize> {
+    let mut reach = Vec::new();
+    // The bars still in view, strictly taller towards the top
+    let mut stack: Vec<(usize, u64)> = Vec::new();
+    for (idx, tall) in bars.enumerate() {
+        while stack.last().is_some_and(|&(_, top)| top >= tall) {
+            stack.pop();
+        }
+        reach.push(stack.last().map_or(idx, |&(start, _)| idx - start - 1));
+        stack.push((idx, tall));
+    }
+    reach
+}
+
+/// The first and last tiles of compressed cell `idx`; the last comes
+/// before the first for an empty gap.
+fn span(coords: &[i32], idx: usize) -> (i32, i32) {
+    let value = coords[idx / 2];
+    match idx % 2 {
+        0 => (value, value),
+        _ => (value + 1, coords[idx / 2 + 1] - 1),
+    }
+}
+
+/// The number of tiles in compressed cell `idx`.
+fn tiles(coords: &[i32], idx: usize) -> u64 {
+    let (first, last) = span(coords, idx);
+    (i64::from(last) - i64::from(first) + 1) as u64
+}
+
+/// The compressed index of a corner coordinate.
+fn index_of(coords: &[i32], value: i32) -> usize {
+    2 * coords
+        .binary_search(&value)
+        .expect("not a corner coordinate")
+}
+
+fn ordered(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{Lcg, random_histogram_polygon};
+
+    fn polygon(corners: &[(i32, i32)]) -> RectilinearPolygon {
+        RectilinearPolygon::new(corners.iter().map(|&(x, y)| Point2d { x, y }).collect())
+    }
+
+    fn area(pair: Option<(Point2d, Point2d)>) -> u64 {
+        pair.map_or(0, |(p, q)| rect_area_inclusive(p, q))
+    }
+
+    /// Whether the point at half-tile coordinates `(x / 2, y / 2)` is on
+    /// an edge or has an odd number of vertical edges to its right.
+    fn inside_halves(polygon: &RectilinearPolygon, x: i64, y: i64) -> bool {
+        let edges: Vec<[i64; 4]> = polygon
+            .edges()
+            .map(|(a, b)| {
+                let (a, b) = (
+                    (2 * i64::from(a.x), 2 * i64::from(a.y)),
+                    (2 * i64::from(b.x), 2 * i64::from(b.y)),
+                );
+                [a.0.min(b.0), a.0.max(b.0), a.1.min(b.1), a.1.max(b.1)]
+            })
+            .collect();
+        let on_edge = edges
+            .iter()
+            .any(|e| (e[0]..=e[1]).contains(&x) && (e[2]..=e[3]).contains(&y));
+        let crossings = edges
+            .iter()
+            .filter(|e| e[0] == e[1] && e[0] > x && e[2] <= y && y < e[3])
+            .count();
+        on_edge || crossings % 2 == 1
+    }
+
+    /// Every point of the rectangle between `p` and `q`, at half-tile
+    /// steps, is inside: no part of it lies outside the boundary, not even
+    /// between two edges one tile apart.
+    fn covers_slowly(polygon: &RectilinearPolygon, p: Point2d, q: Point2d) -> bool {
+        let halves = |a: i32, b: i32| 2 * i64::from(a.min(b))..=2 * i64::from(a.max(b));
+        halves(p.x, q.x).all(|x| halves(p.y, q.y).all(|y| inside_halves(polygon, x, y)))
+    }
+
+    fn brute_force(polygon: &RectilinearPolygon) -> u64 {
+        let corners = polygon.vertices();
+        let mut best = 0;
+        for (idx, &p) in corners.iter().enumerate() {
+            for &q in &corners[idx + 1..] {
+                if covers_slowly(polygon, p, q) {
+                    best = best.max(rect_area_inclusive(p, q));
+                }
+            }
+        }
+        best
+    }
+
+    /// Every rectangle of tiles within the polygon's bounding box, checked
+    /// at half-tile steps like [`covers_slowly`].
+    fn brute_force_inscribed(polygon: &RectilinearPolygon) -> u64 {
+        let corners = polygon.vertices();
+        let x0 = corners.iter().map(|p| p.x).min().unwrap();
+        let y0 = corners.iter().map(|p| p.y).min().unwrap();
+        let width = (corners.iter().map(|p| p.x).max().unwrap() - x0 + 1) as usize;
+        let height = (corners.iter().map(|p| p.y).max().unwrap() - y0 + 1) as usize;
+        let tile = |col: usize, row: usize| Point2d {
+            x: x0 + col as i32,
+            y: y0 + row as i32,
+        };
+        // Uncovered half-tile points, two per tile along each axis
+        let (columns, rows) = (2 * width - 1, 2 * height - 1);
+        let mut uncovered = vec![vec![0u64; columns + 1]; rows + 1];
+        for row in 0..rows {
+            for col in 0..columns {
+                let x = 2 * i64::from(x0) + col as i64;
+                let y = 2 * i64::from(y0) + row as i64;
+                uncovered[row + 1][col + 1] = uncovered[row][col + 1] + uncovered[row + 1][col]
+                    - uncovered[row][col]
+                    + u64::from(!inside_halves(polygon, x, y));
+            }
+        }
+        let mut best = 0;
+        for (top, bottom) in (0..height).flat_map(|top| (top..height).map(move |b| (top, b))) {
+            for (left, right) in (0..width).flat_map(|left| (left..width).map(move |r| (left, r))) {
+                let sum = &uncovered;
+                let (t, b, l, r) = (2 * top, 2 * bottom + 1, 2 * left, 2 * right + 1);
+                if sum[b][r] + sum[t][l] == sum[t][r] + sum[b][l] {
+                    best = best.max(rect_area_inclusive(tile(left, top), tile(right, bottom)));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_example() {
+        let example = polygon(&[
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]);
+        assert_eq!(area(example.largest_vertex_rectangle()), 24);
+        assert_eq!(example.edges().count(), 8);
+        assert_eq!(area(example.largest_inscribed_rectangle()), 30);
+        assert_eq!(brute_force_inscribed(&example), 30);
+    }
+
+    #[test]
+    fn test_inscribed_dumbbell() {
+        // Two boxes joined by a corridor two tiles high: the corridor's
+        // full length needs corners that are not input corners
+        let dumbbell = polygon(&[
+            (0, 0),
+            (3, 0),
+            (3, 1),
+            (6, 1),
+            (6, 0),
+            (9, 0),
+            (9, 3),
+            (6, 3),
+            (6, 2),
+            (3, 2),
+            (3, 3),
+            (0, 3),
+        ]);
+        assert_eq!(area(dumbbell.largest_vertex_rectangle()), 16);
+        assert_eq!(
+            dumbbell.largest_inscribed_rectangle(),
+            Some((Point2d { x: 0, y: 1 }, Point2d { x: 9, y: 2 }))
+        );
+        assert_eq!(
+            polygon(&[(0, 0), (5, 0)]).largest_inscribed_rectangle(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(polygon(&square).validate(), Ok(()));
+        let reversed: Vec<_> = square.iter().rev().copied().collect();
+        assert_eq!(polygon(&reversed).validate(), Ok(()));
+        let mut rng = Lcg::new(3);
+        for columns in 1..20 {
+            let histogram = RectilinearPolygon::new(random_histogram_polygon(&mut rng, columns));
+            assert_eq!(histogram.validate(), Ok(()), "{:?}", histogram);
+        }
+
+        let corner = |x, y| Point2d { x, y };
+        let cases = [
+            (
+                &[(0, 0), (4, 0), (4, 4)][..],
+                PolygonError::TooFewCorners { count: 3 },
+            ),
+            (
+                &[(0, 0), (4, 0), (4, 4), (0, 4), (0, 0)],
+                PolygonError::RepeatedCorner {
+                    index: 4,
+                    corner: corner(0, 0),
+                },
+            ),
+            (
+                &[(0, 0), (4, 0), (4, 4), (1, 5)],
+                PolygonError::Diagonal {
+                    index: 2,
+                    from: corner(4, 4),
+                    to: corner(1, 5),
+                },
+            ),
+            (
+                &[(0, 0), (4, 0), (4, 4), (1, 4), (1, 2)],
+                PolygonError::Unclosed {
+                    first: corner(0, 0),
+                    last: corner(1, 2),
+                },
+            ),
+            (
+                &[(0, 0), (2, 0), (4, 0), (4, 4), (0, 4)],
+                PolygonError::NoTurn {
+                    index: 1,
+                    corner: corner(2, 0),
+                },
+            ),
+            (
+                &[(0, 0), (4, 0), (2, 0), (2, 4), (0, 4)],
+                PolygonError::NoTurn {
+                    index: 1,
+                    corner: corner(4, 0),
+                },
+            ),
+            // A figure of eight: the two loops turn opposite ways
+            (
+                &[(0, 0), (2, 0), (2, 4), (4, 4), (4, 2), (0, 2)],
+                PolygonError::Winding { quarter_turns: 0 },
+            ),
+            // Turns once around, but the slot cut from the left runs into
+            // the right wall
+            (
+                &[
+                    (0, 0),
+                    (6, 0),
+                    (6, 6),
+                    (0, 6),
+                    (0, 4),
+                    (6, 4),
+                    (6, 2),
+                    (0, 2),
+                ],
+                PolygonError::SelfIntersection {
+                    first: 1,
+                    second: 4,
+                },
+            ),
+        ];
+        for (corners, expected) in cases {
+            assert_eq!(polygon(corners).validate(), Err(expected), "{:?}", corners);
+        }
+        assert_eq!(
+            crate::Error::from(PolygonError::SelfIntersection {
+                first: 1,
+                second: 5
+            })
+            .to_string(),
+            "unsupported: edges 1 and 5 intersect"
+        );
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(polygon(&[(0, 0), (5, 0)]).largest_vertex_rectangle(), None);
+        // A notch one tile wide still blocks the rectangle across it
+        let notched = polygon(&[
+            (0, 0),
+            (4, 0),
+            (4, 4),
+            (3, 4),
+            (3, 1),
+            (1, 1),
+            (1, 4),
+            (0, 4),
+        ]);
+        assert_eq!(area(notched.largest_vertex_rectangle()), 10);
+        assert_eq!(brute_force(&notched), 10);
+    }
+
+    #[test]
+    fn test_zero_width_notch() {
+        // Columns 9 and 10 are both boundary, but the notch between the
+        // edges at x = 9 and x = 10 is outside from y = 7 up
+        let notched = polygon(&[
+            (4, 0),
+            (4, 9),
+            (9, 9),
+            (9, 7),
+            (10, 7),
+            (10, 10),
+            (15, 10),
+            (15, 0),
+        ]);
+        assert!(!covers_slowly(
+            &notched,
+            Point2d { x: 4, y: 9 },
+            Point2d { x: 15, y: 0 }
+        ));
+        assert_eq!(brute_force(&notched), 66);
+        assert_eq!(area(notched.largest_vertex_rectangle()), 66);
+        assert_eq!(
+            area(notched.largest_inscribed_rectangle()),
+            brute_force_inscribed(&notched)
+        );
+    }
+
+    #[test]
+    fn test_matches_tile_by_tile() {
+        let mut rng = Lcg::new(21);
+        for columns in [1, 2, 3, 6, 12] {
+            for _ in 0..10 {
+                let histogram =
+                    RectilinearPolygon::new(random_histogram_polygon(&mut rng, columns));
+                assert_eq!(
+                    area(histogram.largest_vertex_rectangle()),
+                    brute_force(&histogram),
+                    "{:?}",
+                    histogram
+                );
+                let (p, q) = histogram.largest_inscribed_rectangle().unwrap();
+                assert!(covers_slowly(&histogram, p, q), "{:?}", histogram);
+                assert_eq!(
+                    rect_area_inclusive(p, q),
+                    brute_force_inscribed(&histogram),
+                    "{:?}",
+                    histogram
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Small square matrices of `i64` for the rotations and reflections
+//! puzzles keep asking for: turning a present to fit a region, folding
+//! paper or rotating a scanner.  A [`Mat`] acts on column vectors `[i64;
+//! N]` by multiplication, so a sequence of transforms composes into one
+//! matrix instead of a chain of coordinate swaps.
+//!
+//! Coordinates follow the grid convention of the rest of the crate: `x`
+//! to the right and `y` down, so [`Mat2::ROTATE_CW`] turns clockwise as
+//! drawn on screen.
+//!
+//! ```
+//! use rust_advent::geo::Mat2;
+//!
+//! let turned = Mat2::ROTATE_CW * [2, 1];
+//! assert_eq!(turned, [-1, 2]);
+//! assert_eq!(Mat2::ROTATE_CW.pow(4), Mat2::IDENTITY);
+//! assert_eq!(Mat2::FLIP_HORIZONTAL * Mat2::FLIP_VERTICAL, Mat2::ROTATE_180);
+//! ```
+
+use crate::Point2d;
+use std::ops::Mul;
+
+/// An `N` by `N` matrix, `self.0[row][column]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mat<const N: usize>(pub [[i64; N]; N]);
+
+pub type Mat2 = Mat<2>;
+pub type Mat3 = Mat<3>;
+
+impl<const N: usize> Mat<N> {
+    pub const IDENTITY: Self = {
+        let mut rows = [[0; N]; N];
+        let mut i = 0;
+        while i < N {
+            rows[i][i] = 1;
+            i += 1;
+        }
+        Mat(rows)
+    };
+
+    pub fn transpose(self) -> Self {
+        Mat(std::array::from_fn(|r| {
+            std::array::from_fn(|c| self.0[c][r])
+        }))
+    }
+
+    /// `self` multiplied by itself `exp` times.
+    pub fn pow(self, exp: u32) -> Self {
+        (0..exp).fold(Self::IDENTITY, |product, _| product * self)
+    }
+}
+
+impl Mat2 {
+    /// A quarter turn clockwise: right becomes down.
+    pub const ROTATE_CW: Mat2 = Mat([[0, -1], [1, 0]]);
+    /// A quarter turn anticlockwise: right becomes up.
+    pub const ROTATE_CCW: Mat2 = Mat([[0, 1], [-1, 0]]);
+    pub const ROTATE_180: Mat2 = Mat([[-1, 0], [0, -1]]);
+    /// Mirrors left to right, negating `x`.
+    pub const FLIP_HORIZONTAL: Mat2 = Mat([[-1, 0], [0, 1]]);
+    /// Mirrors top to bottom, negating `y`.
+    pub const FLIP_VERTICAL: Mat2 = Mat([[1, 0], [0, -1]]);
+    /// Swaps `x` and `y`, mirroring in the main diagonal.
+    pub const TRANSPOSE: Mat2 = Mat([[0, 1], [1, 0]]);
+
+    /// The eight symmetries of a square, in the order of
+    /// [`grid::symmetries`](crate::grid::symmetries): the four clockwise
+    /// rotations, then the same after [`Mat2::FLIP_HORIZONTAL`].
+    pub fn symmetries() -> [Mat2; 8] {
+        std::array::from_fn(|i| {
+            let flip = if i < 4 {
+                Mat2::IDENTITY
+            } else {
+                Mat2::FLIP_HORIZONTAL
+            };
+            Mat2::ROTATE_CW.pow(i as u32 % 4) * flip
+        })
+    }
+
+    pub fn determinant(self) -> i64 {
+        let [[a, b], [c, d]] = self.0;
+        a * d - b * c
+    }
+}
+
+impl Mat3 {
+    /// A quarter turn about the `x` axis, taking `y` to `z`.
+    pub const ROTATE_X: Mat3 = Mat([[1, 0, 0], [0, 0, -1], [0, 1, 0]]);
+    /// A quarter turn about the `y` axis, taking `z` to `x`.
+    pub const ROTATE_Y: Mat3 = Mat([[0, 0, 1], [0, 1, 0], [-1, 0, 0]]);
+    /// A quarter turn about the `z` axis, taking `x` to `y`.
+    pub const ROTATE_Z: Mat3 = Mat([[0, -1, 0], [1, 0, 0], [0, 0, 1]]);
+
+    /// The 24 rotations that keep the axes on axes, starting with the
+    /// identity: each of six directions for `x`, with four turns about it.
+    pub fn rotations() -> [Mat3; 24] {
+        let mut all = Vec::with_capacity(24);
+        for face in [
+            Mat3::IDENTITY,
+            Mat3::ROTATE_Z,
+            Mat3::ROTATE_Z.pow(2),
+            Mat3::ROTATE_Z.pow(3),
+            Mat3::ROTATE_Y,
+            Mat3::ROTATE_Y.pow(3),
+        ] {
+            for turns in 0..4 {
+                all.push(face * Mat3::ROTATE_X.pow(turns));
+            }
+        }
+        all.try_into().expect("six faces of four turns")
+    }
+
+    pub fn determinant(self) -> i64 {
+        let [[a, b, c], [d, e, f], [g, h, i]] = self.0;
+        a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g)
+    }
+}
+
+impl<const N: usize> Mul for Mat<N> {
+    type Output = Mat<N>;
+
+    fn mul(self, other: Mat<N>) -> Mat<N> {
+        Mat(std::array::from_fn(|r| {
+            std::array::from_fn(|c| (0..N).map(|k| self.0[r][k] * other.0[k][c]).sum())
+        }))
+    }
+}
+
+impl<const N: usize> Mul<[i64; N]> for Mat<N> {
+    type Output = [i64; N];
+
+    fn mul(self, v: [i64; N]) -> [i64; N] {
+        std::array::from_fn(|r| (0..N).map(|k| self.0[r][k] * v[k]).sum())
+    }
+}
+
+/// # Panics
+///
+/// Panics if a coordinate of the image does not fit an `i32`.
+impl Mul<Point2d> for Mat2 {
+    type Output = Point2d;
+
+    fn mul(self, p: Point2d) -> Point2d {
+        let [x, y] = self * [i64::from(p.x), i64::from(p.y)];
+        let narrow = |v: i64| i32::try_from(v).expect("transformed point out of i32 range");
+        Point2d {
+            x: narrow(x),
+            y: narrow(y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::symmetries;
+    use crate::parse_ascii_grid;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_mat2() {
+        assert_eq!(Mat2::ROTATE_CW * [1, 0], [0, 1]);
+        assert_eq!(Mat2::ROTATE_CCW * Mat2::ROTATE_CW, Mat2::IDENTITY);
+        assert_eq!(Mat2::ROTATE_CW.pow(2), Mat2::ROTATE_180);
+        assert_eq!(Mat2::ROTATE_CW.transpose(), Mat2::ROTATE_CCW);
+        assert_eq!(Mat2::TRANSPOSE, Mat2::FLIP_HORIZONTAL * Mat2::ROTATE_CW);
+        assert_eq!(Mat2::FLIP_VERTICAL.pow(2), Mat2::IDENTITY);
+        assert_eq!(
+            Mat2::ROTATE_CCW * Point2d { x: 3, y: -4 },
+            Point2d { x: -4, y: -3 }
+        );
+
+        let all = Mat2::symmetries();
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 8);
+        for m in all {
+            assert_eq!(m.determinant().abs(), 1);
+            assert_eq!(m * m.transpose(), Mat2::IDENTITY);
+        }
+        // A group: closed under multiplication
+        for a in all {
+            for b in all {
+                assert!(all.contains(&(a * b)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_symmetries_match_grid() {
+        // Transforming each cell's position gives the same images as turning
+        // the grid itself, once moved back to the origin.
+        let grid = parse_ascii_grid("ab.\n..c\n");
+        let cells: Vec<([i64; 2], u8)> = grid
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(_, &ch)| ch != b'.')
+                    .map(move |(x, &ch)| ([x as i64, y as i64], ch))
+            })
+            .collect();
+        for (m, image) in Mat2::symmetries().into_iter().zip(symmetries(&grid)) {
+            let moved: Vec<([i64; 2], u8)> = cells.iter().map(|&(p, ch)| (m * p, ch)).collect();
+            let min_x = moved.iter().map(|(p, _)| p[0]).min().unwrap();
+            let min_y = moved.iter().map(|(p, _)| p[1]).min().unwrap();
+            for ([x, y], ch) in moved {
+                assert_eq!(image[(y - min_y) as usize][(x - min_x) as usize], ch);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mat3_rotations() {
+        assert_eq!(Mat3::ROTATE_X * [0, 1, 0], [0, 0, 1]);
+        assert_eq!(Mat3::ROTATE_Y * [0, 0, 1], [1, 0, 0]);
+        assert_eq!(Mat3::ROTATE_Z * [1, 0, 0], [0, 1, 0]);
+        let all = Mat3::rotations();
+        assert_eq!(all[0], Mat3::IDENTITY);
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 24);
+        for m in all {
+            assert_eq!(m.determinant(), 1);
+            assert_eq!(m * m.transpose(), Mat3::IDENTITY);
+            assert!(all.contains(&(m * Mat3::ROTATE_Z)));
+        }
+        let mirror = Mat([[-1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+        assert_eq!(mirror.determinant(), -1);
+        assert!(!all.contains(&mirror));
+    }
+}
@@ -0,0 +1,242 @@
+//! Linear systems over GF(2), the integers modulo 2, for puzzles where
+//! pressing a button twice undoes it.  A [`Gf2Matrix`] has one column per
+//! unknown, up to [`MAX_COLUMNS`] of them packed into `u128` rows, and one
+//! row per equation.  [`Gf2Matrix::solve`] row-reduces it and returns every
+//! solution as a [`Gf2Solution`]: one particular solution and a basis of
+//! the kernel, so the solutions are the particular one plus any sum of
+//! basis vectors.
+//!
+//! ```
+//! use rust_advent::gf2::Gf2Matrix;
+//!
+//! // Three buttons toggling lights {0, 1}, {1, 2} and {0, 2}
+//! let matrix = Gf2Matrix::from_columns(&[0b011, 0b110, 0b101], 3).unwrap();
+//! let solution = matrix.solve(0b101).unwrap();
+//! assert_eq!(solution.kernel, [0b111]);
+//! assert_eq!(solution.min_weight(), 1);
+//! assert!(matrix.solve(0b001).is_none());
+//! ```
+
+use crate::validate::{Violation, ensure_at_most};
+
+/// Most columns a [`Gf2Matrix`] may have; rows are `u128` masks.
+pub const MAX_COLUMNS: usize = 128;
+
+/// Most rows [`Gf2Matrix::from_columns`] takes, one per bit of a `u32`.
+pub const MAX_ROWS: usize = 32;
+
+/// A matrix over GF(2): `rows[r]` has bit `c` set if entry `(r, c)` is 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Matrix {
+    rows: Vec<u128>,
+    columns: usize,
+}
+
+impl Gf2Matrix {
+    /// The matrix whose column `c` has bit `r` of `columns[c]` in row `r`,
+    /// as when `columns[c]` is the set of lights button `c` toggles.
+    pub fn from_columns(columns: &[u32], rows: usize) -> Result<Self, Violation> {
+        ensure_at_most("columns", columns.len(), MAX_COLUMNS)?;
+        ensure_at_most("rows", rows, MAX_ROWS)?;
+        let rows = (0..rows)
+            .map(|r| {
+                columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| (*column >> r) & 1 == 1)
+                    .fold(0u128, |row, (c, _)| row | 1 << c)
+            })
+            .collect();
+        Ok(Gf2Matrix {
+            rows,
+            columns: columns.len(),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Every `x` with `self * x == rhs`, where bit `r` of `rhs` is the
+    /// right-hand side of row `r`, or `None` if there is none.
+    pub fn solve(&self, rhs: u32) -> Option<Gf2Solution> {
+        let mut rows: Vec<(u128, bool)> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(r, &row)| (row, (rhs >> r) & 1 == 1))
+            .collect();
+        // Reduced row echelon form, remembering each pivot's column
+        let mut pivots = Vec::new();
+        for column in 0..self.columns {
+            let rank = pivots.len();
+            let Some(found) = (rank..rows.len()).find(|&r| (rows[r].0 >> column) & 1 == 1) else {
+                continue;
+            };
+            rows.swap(rank, found);
+            let (pivot, pivot_rhs) = rows[rank];
+            for (r, (row, row_rhs)) in rows.iter_mut().enumerate() {
+                if r != rank && (*row >> column) & 1 == 1 {
+                    *row ^= pivot;
+                    *row_rhs ^= pivot_rhs;
+                }
+            }
+            pivots.push(column);
+        }
+        if rows[pivots.len()..].iter().any(|&(_, rhs)| rhs) {
+            return None;
+        }
+
+        // Free columns set to zero, each pivot takes its row's right-hand side
+        let particular = pivots
+            .iter()
+            .zip(&rows)
+            .filter(|(_, (_, rhs))| *rhs)
+            .fold(0u128, |x, (&column, _)| x | 1 << column);
+        let is_pivot = pivots
+            .iter()
+            .fold(0u128, |mask, &column| mask | 1 << column);
+        let kernel = (0..self.columns)
+            .filter(|&free| (is_pivot >> free) & 1 == 0)
+            .map(|free| {
+                pivots
+                    .iter()
+                    .zip(&rows)
+                    .filter(|(_, (row, _))| (row >> free) & 1 == 1)
+                    .fold(1u128 << free, |x, (&column, _)| x | 1 << column)
+            })
+            .collect();
+        Some(Gf2Solution { particular, kernel })
+    }
+
+    /// A basis of the solutions of `self * x == 0`.
+    pub fn kernel(&self) -> Vec<u128> {
+        self.solve(0)
+            .map(|solution| solution.kernel)
+            .unwrap_or_default()
+    }
+}
+
+/// The solutions of a system: `particular` plus any sum of `kernel`
+/// vectors, each a `u128` mask over the columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Solution {
+    pub particular: u128,
+    pub kernel: Vec<u128>,
+}
+
+impl Gf2Solution {
+    /// Every solution, `2^kernel.len()` of them, starting with
+    /// `particular`.
+    pub fn all(&self) -> Vec<u128> {
+        let mut solutions = Vec::with_capacity(1 << self.kernel.len());
+        solutions.push(self.particular);
+        for &vector in &self.kernel {
+            for i in 0..solutions.len() {
+                solutions.push(solutions[i] ^ vector);
+            }
+        }
+        solutions
+    }
+
+    /// The fewest ones in any solution, meeting in the middle: the sums of
+    /// each half of the kernel are enumerated separately, so this takes
+    /// about `2^(kernel.len() / 2)` space.
+    pub fn min_weight(&self) -> u32 {
+        let sums = |basis: &[u128]| {
+            let mut sums = vec![0u128];
+            for &vector in basis {
+                for i in 0..sums.len() {
+                    sums.push(sums[i] ^ vector);
+                }
+            }
+            sums
+        };
+        let (low, high) = self.kernel.split_at(self.kernel.len() / 2);
+        let high = sums(high);
+        sums(low)
+            .into_iter()
+            .flat_map(|a| {
+                high.iter()
+                    .map(move |&b| (self.particular ^ a ^ b).count_ones())
+            })
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+
+    /// The rows `x` sets when each of its columns toggles `columns[c]`.
+    fn apply(columns: &[u32], x: u128) -> u32 {
+        columns
+            .iter()
+            .enumerate()
+            .filter(|(c, _)| (x >> c) & 1 == 1)
+            .fold(0, |rows, (_, &column)| rows ^ column)
+    }
+
+    #[test]
+    fn test_solve_matches_brute_force() {
+        let mut rng = Lcg::new(17);
+        for _ in 0..200 {
+            let rows = rng.between(1..=6) as usize;
+            let columns: Vec<u32> = (0..rng.between(0..=8))
+                .map(|_| rng.below(1 << rows) as u32)
+                .collect();
+            let rhs = rng.below(1 << rows) as u32;
+            let matrix = Gf2Matrix::from_columns(&columns, rows).unwrap();
+            let mut expected: Vec<u128> = (0..1u128 << columns.len())
+                .filter(|&x| apply(&columns, x) == rhs)
+                .collect();
+            match matrix.solve(rhs) {
+                None => assert!(expected.is_empty(), "{:?} {:b}", columns, rhs),
+                Some(solution) => {
+                    let mut found = solution.all();
+                    found.sort_unstable();
+                    expected.sort_unstable();
+                    assert_eq!(found, expected, "{:?} {:b}", columns, rhs);
+                    let fewest = expected.iter().map(|x| x.count_ones()).min();
+                    assert_eq!(Some(solution.min_weight()), fewest);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_many_columns() {
+        // Column c toggles row c % 32: four columns per row
+        let columns: Vec<u32> = (0..MAX_COLUMNS).map(|c| 1 << (c % 32)).collect();
+        let matrix = Gf2Matrix::from_columns(&columns, 32).unwrap();
+        assert_eq!((matrix.rows(), matrix.columns()), (32, 128));
+        let solution = matrix.solve(u32::MAX).unwrap();
+        assert_eq!(solution.particular, u128::from(u32::MAX));
+        assert_eq!(solution.kernel.len(), 96);
+        assert!(solution.kernel.iter().all(|&v| apply(&columns, v) == 0));
+        assert!(solution.kernel.contains(&(1 << 127 | 1 << 31)));
+        assert_eq!(apply(&columns, solution.particular), u32::MAX);
+        assert_eq!(matrix.kernel(), solution.kernel);
+    }
+
+    #[test]
+    fn test_limits() {
+        assert_eq!(
+            Gf2Matrix::from_columns(&[1; 129], 1).unwrap_err(),
+            Violation::TooMany {
+                what: "columns",
+                count: 129,
+                max: MAX_COLUMNS
+            }
+        );
+        assert!(Gf2Matrix::from_columns(&[], 33).is_err());
+        let empty = Gf2Matrix::from_columns(&[], 0).unwrap();
+        assert_eq!(empty.solve(0).unwrap().all(), [0]);
+    }
+}
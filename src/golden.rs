@@ -0,0 +1,131 @@
+//! Golden-file tests: a solution's printed output for a checked-in example
+//! input, compared against a stored snapshot so refactors cannot change what
+//! a day prints without the snapshot changing with it.
+//!
+//! Examples live in `tests/golden/` as `<name>.txt`, next to their snapshots
+//! `<name>.out`.  Running the tests with `UPDATE_GOLDEN=1` writes the
+//! snapshots from the current output instead of comparing against them.
+
+use crate::solution::{Context, Solution, write_parts};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Set to `1` to rewrite the snapshots rather than check them.
+pub const UPDATE_VAR: &str = "UPDATE_GOLDEN";
+
+/// Returns the directory holding the golden examples and snapshots.
+pub fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+}
+
+/// Runs `solution` on the example `name` and panics unless both parts print
+/// exactly as in its snapshot.
+pub fn assert_golden<S: Solution>(solution: &S, name: &str) {
+    let dir = golden_dir();
+    let example = dir.join(name).with_extension("txt");
+    let text = fs::read_to_string(&example)
+        .unwrap_or_else(|err| panic!("cannot read {}: {}", example.display(), err));
+    let output = render(solution, &text)
+        .unwrap_or_else(|err| panic!("day {} failed on {}: {}", S::DAY, example.display(), err));
+    let update = std::env::var(UPDATE_VAR).is_ok_and(|value| value == "1");
+    if let Err(message) = verify(&output, &dir.join(name).with_extension("out"), update) {
+        panic!("{}", message);
+    }
+}
+
+/// What `run` would print for `text`.
+fn render<S: Solution>(solution: &S, text: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let input = solution.parse(text)?;
+    let mut out = Vec::new();
+    write_parts(solution, &input, &Context::new(), &mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+/// Checks `output` against the snapshot at `path`, or writes it there when
+/// `update` is set.
+fn verify(output: &str, path: &Path, update: bool) -> Result<(), String> {
+    if update {
+        return fs::write(path, output)
+            .map_err(|err| format!("cannot write {}: {}", path.display(), err));
+    }
+    let expected = fs::read_to_string(path).map_err(|err| {
+        format!(
+            "cannot read {}: {} (run with {}=1 to create it)",
+            path.display(),
+            err,
+            UPDATE_VAR
+        )
+    })?;
+    if output == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "output differs from {} (run with {}=1 if the change is intended)\n\
+             --- expected\n{}--- actual\n{}",
+            path.display(),
+            UPDATE_VAR,
+            expected,
+            output
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, Outcome};
+    use std::error::Error;
+
+    /// Sums the numbers for part 1 and has no answer for part 2.
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: &'static str = "00";
+        type Input = Vec<u64>;
+
+        fn parse(&self, input: &str) -> Result<Vec<u64>, Box<dyn Error>> {
+            Ok(input
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(&self, input: &Vec<u64>, _ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+            Ok(input.iter().sum::<u64>().into())
+        }
+
+        fn part2(&self, _input: &Vec<u64>, _ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+            Ok(Outcome::Unsolvable.into())
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(&Sum, "1 2\n3\n").unwrap(),
+            "Part 1: 6\nPart 2: no solution\n"
+        );
+        assert!(render(&Sum, "1 x").is_err());
+    }
+
+    #[test]
+    fn test_verify_and_update() {
+        let path = std::env::temp_dir().join(format!("golden-{}.out", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let err = verify("Part 1: 6\n", &path, false).unwrap_err();
+        assert!(err.contains("UPDATE_GOLDEN=1 to create it"), "{}", err);
+
+        verify("Part 1: 6\n", &path, true).unwrap();
+        assert_eq!(verify("Part 1: 6\n", &path, false), Ok(()));
+        let err = verify("Part 1: 7\n", &path, false).unwrap_err();
+        assert!(
+            err.ends_with("--- expected\nPart 1: 6\n--- actual\nPart 1: 7\n"),
+            "{}",
+            err
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,879 @@
+//! Directed graphs over interned node names, plus a bitset form for graphs
+//! small enough that each node's successors fit in one `u64` and a
+//! compressed sparse row form, [`CompactGraph`], for graphs with hundreds
+//! of thousands of edges.  Graphs can be written out in Graphviz's DOT
+//! language to be drawn with `dot -Tsvg`.
+//!
+//! Puzzles that push values along a dependency graph, such as the cost of
+//! building each part or the most that can be gathered on the way to it,
+//! fold them over [`DiGraph::topological_order`] with
+//! [`DiGraph::dp_over_toposort`]; [`DiGraph::longest_path_dag`] is one such
+//! fold.
+
+use crate::paths::{Overflow, PathCount};
+use crate::{FastMap, FastSet};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Write as _;
+
+/// A directed graph whose nodes are identified by name and stored by id.
+///
+/// With the `serde` feature a graph is `{"names": [...], "successors":
+/// [[...], ...]}`, each node's successors listed by id in insertion order,
+/// so the same graph always serializes the same way.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "DiGraphData", try_from = "DiGraphData")
+)]
+pub struct DiGraph {
+    ids: FastMap<String, usize>,
+    names: Vec<String>,
+    successors: Vec<Vec<usize>>,
+}
+
+/// The serialized form of a [`DiGraph`]: its names and successor lists
+/// without the name index, which is rebuilt on the way back in.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DiGraphData {
+    names: Vec<String>,
+    successors: Vec<Vec<usize>>,
+}
+
+#[cfg(feature = "serde")]
+impl From<DiGraph> for DiGraphData {
+    fn from(graph: DiGraph) -> Self {
+        DiGraphData {
+            names: graph.names,
+            successors: graph.successors,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DiGraphData> for DiGraph {
+    type Error = String;
+
+    fn try_from(data: DiGraphData) -> Result<Self, String> {
+        if data.names.len() != data.successors.len() {
+            return Err(format!(
+                "{} names but {} successor lists",
+                data.names.len(),
+                data.successors.len()
+            ));
+        }
+        let mut graph = DiGraph::new();
+        for name in &data.names {
+            if graph.id(name).is_some() {
+                return Err(format!("node '{}' appears twice", name));
+            }
+            graph.intern(name);
+        }
+        if let Some(&id) = data
+            .successors
+            .iter()
+            .flatten()
+            .find(|&&id| id >= graph.len())
+        {
+            return Err(format!("edge to unknown node {}", id));
+        }
+        graph.successors = data.successors;
+        Ok(graph)
+    }
+}
+
+impl DiGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `name`, adding a node for it if it is new.
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        self.successors.push(Vec::new());
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str) {
+        let from = self.intern(from);
+        let to = self.intern(to);
+        self.successors[from].push(to);
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn successors(&self, id: usize) -> &[usize] {
+        &self.successors[id]
+    }
+
+    /// The graph as one successor bitmask per node.  `None` if it has more
+    /// than [`BitsetAdjacency::MAX_NODES`] nodes or repeats an edge, since a
+    /// bit cannot count an edge twice.
+    pub fn to_bitset_adjacency(&self) -> Option<BitsetAdjacency> {
+        if self.len() > BitsetAdjacency::MAX_NODES {
+            return None;
+        }
+        let mut rows = vec![0u64; self.len()];
+        let mut cols = vec![0u64; self.len()];
+        for (from, successors) in self.successors.iter().enumerate() {
+            for &to in successors {
+                if rows[from] & (1 << to) != 0 {
+                    return None;
+                }
+                rows[from] |= 1 << to;
+                cols[to] |= 1 << from;
+            }
+        }
+        Some(BitsetAdjacency { rows, cols })
+    }
+
+    /// The graph in compressed sparse row form, keeping each node's id and
+    /// the order of its successors.  `None` if there are more than
+    /// [`CompactGraph::MAX_NODES`] nodes.
+    pub fn to_compact(&self) -> Option<CompactGraph> {
+        if self.len() > CompactGraph::MAX_NODES {
+            return None;
+        }
+        let edges: Vec<(u32, u32)> = self
+            .successors
+            .iter()
+            .enumerate()
+            .flat_map(|(from, successors)| {
+                successors.iter().map(move |&to| (from as u32, to as u32))
+            })
+            .collect();
+        Some(CompactGraph::from_edges(self.len(), &edges))
+    }
+
+    /// One path from `start` to `target` that visits every node in
+    /// `required`, as the nodes along it, or `None` if there is none.  Nodes
+    /// are not repeated, and a search that failed from a node with the same
+    /// required nodes still to visit is not tried again, which is exact for
+    /// acyclic graphs.
+    pub fn find_path(&self, start: usize, target: usize, required: &[usize]) -> Option<Vec<usize>> {
+        let mut path = vec![start];
+        let mut dead = FastSet::default();
+        self.extend_path(target, required, &mut path, &mut dead)
+            .then_some(path)
+    }
+
+    fn extend_path(
+        &self,
+        target: usize,
+        required: &[usize],
+        path: &mut Vec<usize>,
+        dead: &mut FastSet<(usize, Vec<usize>)>,
+    ) -> bool {
+        let node = *path.last().expect("paths start with a node");
+        let missing: Vec<usize> = required
+            .iter()
+            .copied()
+            .filter(|r| !path.contains(r))
+            .collect();
+        if node == target {
+            return missing.is_empty();
+        }
+        if dead.contains(&(node, missing.clone())) {
+            return false;
+        }
+        for &next in &self.successors[node] {
+            if path.contains(&next) {
+                continue;
+            }
+            path.push(next);
+            if self.extend_path(target, required, path, dead) {
+                return true;
+            }
+            path.pop();
+        }
+        dead.insert((node, missing));
+        false
+    }
+
+    /// Every node, each before all of its successors, or `None` if the
+    /// graph has a cycle.  Of the nodes ready at each step the lowest id
+    /// comes first, so the order is the same every run.
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut waiting = vec![0usize; self.len()];
+        for &to in self.successors.iter().flatten() {
+            waiting[to] += 1;
+        }
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.len())
+            .filter(|&node| waiting[node] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(node);
+            for &next in &self.successors[node] {
+                waiting[next] -= 1;
+                if waiting[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Pushes values along the edges in topological order: each node starts
+    /// at `init(node)`, and once a node's value is final, `fold(to_value,
+    /// from_value, (from, to))` merges it into each successor's.  Returns
+    /// every node's final value by id, or `None` if the graph has a cycle.
+    ///
+    /// Counting paths from `s` starts `s` at one and everything else at
+    /// zero, and folds by adding.
+    pub fn dp_over_toposort<T>(
+        &self,
+        mut init: impl FnMut(usize) -> T,
+        mut fold: impl FnMut(&mut T, &T, (usize, usize)),
+    ) -> Option<Vec<T>> {
+        let order = self.topological_order()?;
+        let mut values: Vec<T> = (0..self.len()).map(&mut init).collect();
+        for from in order {
+            for &to in &self.successors[from] {
+                // A DAG has no self loops, so the two are distinct
+                let (value, to_value) = if from < to {
+                    let (low, high) = values.split_at_mut(to);
+                    (&low[from], &mut high[0])
+                } else {
+                    let (low, high) = values.split_at_mut(from);
+                    (&high[0], &mut low[to])
+                };
+                fold(to_value, value, (from, to));
+            }
+        }
+        Some(values)
+    }
+
+    /// The heaviest path from `start` to `target`, with `weight(from, to)`
+    /// for each edge, as its total weight and its nodes.  `None` if
+    /// `target` cannot be reached or the graph has a cycle, which would
+    /// make paths unboundedly long; tell the two apart with
+    /// [`Self::topological_order`].
+    pub fn longest_path_dag(
+        &self,
+        start: usize,
+        target: usize,
+        mut weight: impl FnMut(usize, usize) -> i64,
+    ) -> Option<(i64, Vec<usize>)> {
+        // The best weight to each node and the node before it
+        let best = self.dp_over_toposort(
+            |node| (node == start).then_some((0, start)),
+            |to_best: &mut Option<(i64, usize)>, from_best, (from, to)| {
+                if let Some((so_far, _)) = *from_best {
+                    let total = so_far + weight(from, to);
+                    if to_best.is_none_or(|(most, _)| total > most) {
+                        *to_best = Some((total, from));
+                    }
+                }
+            },
+        )?;
+        let (total, _) = best[target]?;
+        let mut path = vec![target];
+        while let Some(&node) = path.last().filter(|&&node| node != start) {
+            path.push(best[node]?.1);
+        }
+        path.reverse();
+        Some((total, path))
+    }
+
+    /// The graph in Graphviz's DOT language.  Nodes in `required` are
+    /// filled, and the edges and nodes along `path` drawn in red.
+    pub fn to_dot(&self, required: &[usize], path: Option<&[usize]>) -> String {
+        let path = path.unwrap_or(&[]);
+        let on_path: FastSet<(usize, usize)> =
+            path.windows(2).map(|step| (step[0], step[1])).collect();
+
+        let mut out = String::from("digraph {\n");
+        for (id, name) in self.names.iter().enumerate() {
+            let mut attrs = Vec::new();
+            if required.contains(&id) {
+                attrs.push("style=filled fillcolor=gold");
+            }
+            if path.contains(&id) {
+                attrs.push("color=red");
+            }
+            if attrs.is_empty() {
+                let _ = writeln!(out, "  {};", quote(name));
+            } else {
+                let _ = writeln!(out, "  {} [{}];", quote(name), attrs.join(" "));
+            }
+        }
+        for (from, successors) in self.successors.iter().enumerate() {
+            for &to in successors {
+                let style = if on_path.contains(&(from, to)) {
+                    " [color=red penwidth=2]"
+                } else {
+                    ""
+                };
+                let _ = writeln!(
+                    out,
+                    "  {} -> {}{};",
+                    quote(&self.names[from]),
+                    quote(&self.names[to]),
+                    style
+                );
+            }
+        }
+        out += "}\n";
+        out
+    }
+}
+
+/// `name` as a DOT identifier, quoted so any name is allowed.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Adjacency of a graph with at most 64 nodes: bit `w` of `row(v)` is set
+/// when there is an edge `v -> w`.  Reachability and path counting work a
+/// whole row at a time instead of following edges one by one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitsetAdjacency {
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+}
+
+impl BitsetAdjacency {
+    pub const MAX_NODES: usize = 64;
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Successors of `node` as a bitmask.
+    pub fn row(&self, node: usize) -> u64 {
+        self.rows[node]
+    }
+
+    /// Every node reachable from `start`, including `start`.
+    pub fn reachable_from(&self, start: usize) -> u64 {
+        closure(&self.rows, start, None)
+    }
+
+    /// Every node that can reach `target`, including `target`.
+    pub fn reaching(&self, target: usize) -> u64 {
+        closure(&self.cols, target, None)
+    }
+
+    /// Counts paths from `start` to `target`; see [`Self::count_paths_through`].
+    pub fn count_paths<T: PathCount>(
+        &self,
+        start: usize,
+        target: usize,
+    ) -> Result<Option<T>, Overflow> {
+        self.count_paths_through(start, target, 0)
+    }
+
+    /// Counts paths from `start` that end on reaching `target` and pass
+    /// through every node in the `required` bitmask.  Returns `None` when a
+    /// cycle lies on some such path, since the count is then unbounded.
+    ///
+    /// Nodes are resolved sinks first, each summing its successors' counts
+    /// per subset of required nodes seen, so the cost grows with
+    /// `2^required.count_ones()`.
+    pub fn count_paths_through<T: PathCount>(
+        &self,
+        start: usize,
+        target: usize,
+        required: u64,
+    ) -> Result<Option<T>, Overflow> {
+        // Only nodes on some start -> target path matter; paths stop at the
+        // target, so its own successors are never followed.
+        let relevant = closure(&self.rows, start, Some(target)) & self.reaching(target);
+        if relevant & (1 << start) == 0 || required & !relevant != 0 {
+            return Ok(Some(T::zero()));
+        }
+
+        let mut required_slot = [0usize; Self::MAX_NODES];
+        for (idx, node) in bits(required).enumerate() {
+            required_slot[node] = 1 << idx;
+        }
+        let slots = 1usize << required.count_ones();
+        let successors = |node: usize| {
+            if node == target {
+                0
+            } else {
+                self.rows[node] & relevant
+            }
+        };
+
+        let mut counts: Vec<Vec<T>> = vec![Vec::new(); self.len()];
+        let mut remaining = relevant;
+        while remaining != 0 {
+            let ready = bits(remaining)
+                .filter(|&node| successors(node) & remaining == 0)
+                .fold(0u64, |mask, node| mask | (1 << node));
+            if ready == 0 {
+                return Ok(None);
+            }
+            for node in bits(ready) {
+                let mut here = vec![T::zero(); slots];
+                if node == target {
+                    here[0] = T::one();
+                }
+                for next in bits(successors(node)) {
+                    for (count, more) in here.iter_mut().zip(&counts[next]) {
+                        count.add_paths(more)?;
+                    }
+                }
+                // No path below an acyclic node can revisit it, so its own
+                // slot bit is clear in every non-zero count.
+                let slot = required_slot[node];
+                if slot != 0 {
+                    for seen in (0..slots).filter(|seen| seen & slot == 0) {
+                        here.swap(seen, seen | slot);
+                    }
+                }
+                counts[node] = here;
+            }
+            remaining &= !ready;
+        }
+        Ok(counts[start].pop())
+    }
+}
+
+/// A directed graph in compressed sparse row form: the successors of node
+/// `v` are `targets[offsets[v]..offsets[v + 1]]`, so every edge sits in one
+/// flat array of `u32` ids and walking a node's successors reads memory in
+/// order.  Built once and never edited, for graphs too large for the
+/// per-node `Vec`s of a [`DiGraph`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompactGraph {
+    offsets: Vec<usize>,
+    targets: Vec<u32>,
+}
+
+impl CompactGraph {
+    pub const MAX_NODES: usize = u32::MAX as usize;
+
+    /// The graph on nodes `0..nodes` with the given `(from, to)` edges, each
+    /// node's successors in the order given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nodes` exceeds [`Self::MAX_NODES`] or an edge names a node
+    /// outside `0..nodes`.
+    pub fn from_edges(nodes: usize, edges: &[(u32, u32)]) -> Self {
+        assert!(nodes <= Self::MAX_NODES, "too many nodes: {}", nodes);
+        // Counting sort by source: count, prefix sum, then place
+        let mut offsets = vec![0usize; nodes + 1];
+        for &(from, to) in edges {
+            assert!(
+                (from as usize) < nodes && (to as usize) < nodes,
+                "edge {} -> {} outside 0..{}",
+                from,
+                to,
+                nodes
+            );
+            offsets[from as usize + 1] += 1;
+        }
+        for v in 0..nodes {
+            offsets[v + 1] += offsets[v];
+        }
+        let mut next = offsets.clone();
+        let mut targets = vec![0u32; edges.len()];
+        for &(from, to) in edges {
+            targets[next[from as usize]] = to;
+            next[from as usize] += 1;
+        }
+        CompactGraph { offsets, targets }
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn successors(&self, node: u32) -> &[u32] {
+        let node = node as usize;
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    pub fn out_degree(&self, node: u32) -> usize {
+        let node = node as usize;
+        self.offsets[node + 1] - self.offsets[node]
+    }
+
+    /// The same nodes with every edge reversed, so successors become
+    /// predecessors.
+    pub fn transpose(&self) -> CompactGraph {
+        let edges: Vec<(u32, u32)> = (0..self.len() as u32)
+            .flat_map(|from| self.successors(from).iter().map(move |&to| (to, from)))
+            .collect();
+        CompactGraph::from_edges(self.len(), &edges)
+    }
+
+    /// The fewest edges from `start` to each node by breadth-first search,
+    /// or `None` for nodes it cannot reach.
+    pub fn bfs_distances(&self, start: u32) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.len()];
+        distances[start as usize] = Some(0);
+        let mut queue = std::collections::VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let next_distance = distances[node as usize].map(|d| d + 1);
+            for &next in self.successors(node) {
+                if distances[next as usize].is_none() {
+                    distances[next as usize] = next_distance;
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+}
+
+/// Nodes reachable from `from` along `edges`, never expanding `stop`.
+fn closure(edges: &[u64], from: usize, stop: Option<usize>) -> u64 {
+    let mut seen = 1u64 << from;
+    let mut frontier = seen;
+    while frontier != 0 {
+        let next = bits(frontier)
+            .filter(|&node| Some(node) != stop)
+            .fold(0, |next, node| next | edges[node]);
+        frontier = next & !seen;
+        seen |= next;
+    }
+    seen
+}
+
+/// Indices of the set bits of `mask`, lowest first.
+fn bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let bit = mask.trailing_zeros() as usize;
+        mask &= mask - 1;
+        Some(bit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+
+    fn graph(edges: &[(&str, &str)]) -> DiGraph {
+        let mut graph = DiGraph::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    /// Every node `i` links to every node `j > i`.
+    fn complete_dag(n: usize) -> DiGraph {
+        let mut graph = DiGraph::new();
+        for i in 0..n {
+            graph.intern(&i.to_string());
+            for j in i + 1..n {
+                graph.add_edge(&i.to_string(), &j.to_string());
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn test_interning() {
+        let mut graph = graph(&[("a", "b"), ("a", "c"), ("b", "c")]);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.intern("b"), 1);
+        assert_eq!(graph.id("c"), Some(2));
+        assert_eq!(graph.id("d"), None);
+        assert_eq!(graph.name(0), "a");
+        assert_eq!(graph.successors(0), &[1, 2]);
+
+        let adjacency = graph.to_bitset_adjacency().unwrap();
+        assert_eq!(adjacency.row(0), 0b110);
+        assert_eq!(adjacency.reachable_from(1), 0b110);
+        assert_eq!(adjacency.reaching(1), 0b011);
+    }
+
+    #[test]
+    fn test_compact_graph_matches_digraph() {
+        let mut rng = Lcg::new(20);
+        for _ in 0..20 {
+            let nodes = rng.between(1..=30) as usize;
+            let mut graph = DiGraph::new();
+            for v in 0..nodes {
+                graph.intern(&v.to_string());
+            }
+            for _ in 0..rng.below(3 * nodes as u64) {
+                let from = rng.below(nodes as u64).to_string();
+                let to = rng.below(nodes as u64).to_string();
+                graph.add_edge(&from, &to);
+            }
+            let compact = graph.to_compact().unwrap();
+            assert_eq!(compact.len(), nodes);
+            for v in 0..nodes {
+                let successors: Vec<usize> = compact
+                    .successors(v as u32)
+                    .iter()
+                    .map(|&w| w as usize)
+                    .collect();
+                assert_eq!(successors, graph.successors(v));
+            }
+
+            // Breadth-first distances against repeated relaxation
+            let mut expected = vec![None; nodes];
+            expected[0] = Some(0u32);
+            for _ in 0..nodes {
+                for v in 0..nodes {
+                    if let Some(d) = expected[v] {
+                        for &w in graph.successors(v) {
+                            if expected[w].is_none_or(|old| old > d + 1) {
+                                expected[w] = Some(d + 1);
+                            }
+                        }
+                    }
+                }
+            }
+            assert_eq!(compact.bfs_distances(0), expected);
+        }
+    }
+
+    #[test]
+    fn test_compact_graph() {
+        let compact = CompactGraph::from_edges(4, &[(2, 0), (0, 1), (2, 3), (0, 2)]);
+        assert_eq!((compact.len(), compact.edge_count()), (4, 4));
+        assert_eq!(compact.successors(0), &[1, 2]);
+        assert_eq!(compact.successors(2), &[0, 3]);
+        assert_eq!(compact.out_degree(3), 0);
+        let reversed = compact.transpose();
+        assert_eq!(reversed.successors(0), &[2]);
+        assert_eq!(reversed.successors(2), &[0]);
+        assert_eq!(reversed.transpose(), compact);
+        assert_eq!(compact.bfs_distances(1), [None, Some(0), None, None]);
+        assert!(CompactGraph::default().is_empty());
+        assert!(CompactGraph::from_edges(0, &[]).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let diamond = graph(&[("s", "a"), ("s", "b"), ("a", "t"), ("b", "t")]);
+        let text = serde_json::to_string(&diamond).unwrap();
+        assert_eq!(
+            text,
+            r#"{"names":["s","a","b","t"],"successors":[[1,2],[3],[3],[]]}"#
+        );
+        let back: DiGraph = serde_json::from_str(&text).unwrap();
+        assert_eq!(back.id("t"), Some(3));
+        assert_eq!(back.successors(0), &[1, 2]);
+
+        let bad = [
+            r#"{"names":["s"],"successors":[]}"#,
+            r#"{"names":["s","s"],"successors":[[],[]]}"#,
+            r#"{"names":["s"],"successors":[[1]]}"#,
+        ];
+        for text in bad {
+            assert!(serde_json::from_str::<DiGraph>(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_find_path() {
+        let diamond = graph(&[("s", "a"), ("s", "b"), ("a", "t"), ("b", "t"), ("t", "x")]);
+        let [s, a, b, t, x] = ["s", "a", "b", "t", "x"].map(|name| diamond.id(name).unwrap());
+        assert_eq!(diamond.find_path(s, t, &[]), Some(vec![s, a, t]));
+        assert_eq!(diamond.find_path(s, t, &[b]), Some(vec![s, b, t]));
+        assert_eq!(diamond.find_path(s, t, &[a, b]), None);
+        assert_eq!(diamond.find_path(s, x, &[b]), Some(vec![s, b, t, x]));
+        assert_eq!(diamond.find_path(x, s, &[]), None);
+        assert_eq!(diamond.find_path(s, s, &[]), Some(vec![s]));
+
+        // Cycles are not followed round
+        let cyclic = graph(&[("s", "a"), ("a", "s"), ("a", "t")]);
+        assert_eq!(cyclic.find_path(0, 2, &[]), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let diamond = graph(&[("s", "a"), ("s", "b"), ("a", "t"), ("b", "t")]);
+        assert_eq!(
+            diamond.to_dot(&[], None),
+            "digraph {\n  \"s\";\n  \"a\";\n  \"b\";\n  \"t\";\n  \"s\" -> \"a\";\n  \
+             \"s\" -> \"b\";\n  \"a\" -> \"t\";\n  \"b\" -> \"t\";\n}\n"
+        );
+
+        let dot = diamond.to_dot(&[2], Some(&[0, 2, 3]));
+        assert!(
+            dot.contains("\"b\" [style=filled fillcolor=gold color=red];"),
+            "{}",
+            dot
+        );
+        assert!(dot.contains("\"a\";"), "{}", dot);
+        assert!(
+            dot.contains("\"s\" -> \"b\" [color=red penwidth=2];"),
+            "{}",
+            dot
+        );
+        assert!(dot.contains("\"s\" -> \"a\";"), "{}", dot);
+        assert_eq!(dot.matches("penwidth").count(), 2);
+
+        let odd = graph(&[("say \"hi\"", "back\\slash")]);
+        assert!(
+            odd.to_dot(&[], None)
+                .contains(r#""say \"hi\"" -> "back\\slash";"#)
+        );
+    }
+
+    #[test]
+    fn test_bitset_adjacency_limits() {
+        assert!(complete_dag(64).to_bitset_adjacency().is_some());
+        assert!(complete_dag(65).to_bitset_adjacency().is_none());
+        assert!(
+            graph(&[("a", "b"), ("a", "b")])
+                .to_bitset_adjacency()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let diamond = graph(&[("s", "a"), ("s", "b"), ("a", "t"), ("b", "t"), ("t", "x")]);
+        let adjacency = diamond.to_bitset_adjacency().unwrap();
+        let [s, a, t, x] = ["s", "a", "t", "x"].map(|name| diamond.id(name).unwrap());
+        assert_eq!(adjacency.count_paths::<u64>(s, t), Ok(Some(2)));
+        assert_eq!(adjacency.count_paths::<u64>(a, t), Ok(Some(1)));
+        assert_eq!(adjacency.count_paths::<u64>(t, t), Ok(Some(1)));
+        assert_eq!(adjacency.count_paths::<u64>(x, t), Ok(Some(0)));
+        assert_eq!(
+            adjacency.count_paths_through::<u64>(s, t, 1 << a),
+            Ok(Some(1))
+        );
+        assert_eq!(
+            adjacency.count_paths_through::<u64>(s, t, 1 << x),
+            Ok(Some(0))
+        );
+    }
+
+    #[test]
+    fn test_count_paths_dense() {
+        // Paths from 0 to n-1 choose any subset of the nodes in between.
+        let adjacency = complete_dag(60).to_bitset_adjacency().unwrap();
+        assert_eq!(adjacency.count_paths::<u64>(0, 59), Ok(Some(1 << 58)));
+        // Requiring two of the inner nodes leaves 2^56 choices.
+        assert_eq!(
+            adjacency.count_paths_through::<u64>(0, 59, (1 << 10) | (1 << 40)),
+            Ok(Some(1 << 56))
+        );
+        assert_eq!(
+            adjacency.count_paths::<u32>(0, 59),
+            Err(Overflow { type_name: "u32" })
+        );
+    }
+
+    #[test]
+    fn test_count_paths_cycles() {
+        // A cycle between start and target makes the count unbounded...
+        let cyclic = graph(&[("s", "a"), ("a", "b"), ("b", "a"), ("b", "t")]);
+        let adjacency = cyclic.to_bitset_adjacency().unwrap();
+        assert_eq!(adjacency.count_paths::<u64>(0, 3), Ok(None));
+
+        // ...but cycles off every path, or through the target, do not.
+        let harmless = graph(&[("s", "t"), ("s", "x"), ("x", "x"), ("t", "y"), ("y", "t")]);
+        let adjacency = harmless.to_bitset_adjacency().unwrap();
+        assert_eq!(adjacency.count_paths::<u64>(0, 1), Ok(Some(1)));
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let diamond = graph(&[("s", "b"), ("s", "a"), ("b", "t"), ("a", "t")]);
+        assert_eq!(diamond.topological_order(), Some(vec![0, 1, 2, 3]));
+        let order = complete_dag(10).topological_order().unwrap();
+        assert_eq!(order, (0..10).collect::<Vec<_>>());
+        let cyclic = graph(&[("s", "a"), ("a", "b"), ("b", "a")]);
+        assert_eq!(cyclic.topological_order(), None);
+        assert_eq!(DiGraph::new().topological_order(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_dp_over_toposort() {
+        // Counting paths agrees with the bitset count
+        let dag = complete_dag(12);
+        let counts = dag
+            .dp_over_toposort(|node| u64::from(node == 0), |to, from, _| *to += from)
+            .unwrap();
+        let adjacency = dag.to_bitset_adjacency().unwrap();
+        for (node, &count) in counts.iter().enumerate() {
+            assert_eq!(adjacency.count_paths::<u64>(0, node), Ok(Some(count)));
+        }
+
+        // Edges are folded in both directions of id
+        let backwards = graph(&[("t", "b"), ("b", "a"), ("s", "t")]);
+        let depths = backwards
+            .dp_over_toposort(|_| 0, |to, from, _| *to = (*to).max(from + 1))
+            .unwrap();
+        assert_eq!(depths, [1, 2, 3, 0]);
+
+        let cyclic = graph(&[("a", "b"), ("b", "a")]);
+        assert!(cyclic.dp_over_toposort(|_| 0, |_, _, _| ()).is_none());
+    }
+
+    #[test]
+    fn test_longest_path_dag() {
+        let dag = graph(&[
+            ("s", "a"),
+            ("s", "b"),
+            ("a", "t"),
+            ("b", "c"),
+            ("c", "t"),
+            ("t", "x"),
+        ]);
+        let [s, a, b, c, t, x] = ["s", "a", "b", "c", "t", "x"].map(|name| dag.id(name).unwrap());
+        let weight = |from: usize, _| if from == a { 10 } else { 1 };
+        assert_eq!(
+            dag.longest_path_dag(s, t, weight),
+            Some((11, vec![s, a, t]))
+        );
+        assert_eq!(
+            dag.longest_path_dag(s, t, |_, _| 1),
+            Some((3, vec![s, b, c, t]))
+        );
+        // Negative weights are no different
+        assert_eq!(
+            dag.longest_path_dag(s, t, |_, _| -1),
+            Some((-2, vec![s, a, t]))
+        );
+        assert_eq!(dag.longest_path_dag(t, t, |_, _| 1), Some((0, vec![t])));
+        assert_eq!(dag.longest_path_dag(x, s, |_, _| 1), None);
+
+        let cyclic = graph(&[("s", "t"), ("t", "u"), ("u", "t")]);
+        assert_eq!(cyclic.longest_path_dag(0, 1, |_, _| 1), None);
+    }
+}
@@ -0,0 +1,819 @@
+//! Shortest paths across grids such as those from
+//! [`parse_ascii_grid`](crate::parse_ascii_grid) and
+//! [`parse_to_number_grid`](crate::parse_to_number_grid).  A maze is a cost
+//! closure that blocks walls; a weighted grid is one that reads each cell:
+//!
+//! ```
+//! use rust_advent::grid::{Connectivity, shortest_path};
+//!
+//! let maze = rust_advent::parse_ascii_grid("S.#\n..#\n#.E\n");
+//! let path = shortest_path(&maze, (0, 0), (2, 2), Connectivity::Four, |&cell| {
+//!     (cell != b'#').then_some(1)
+//! })
+//! .unwrap();
+//! assert_eq!(path.cost, 4);
+//! ```
+//!
+//! Cells are `(row, column)` pairs, and rows may differ in length.
+//!
+//! The grid's symmetries, for puzzles that match tiles or patterns in any
+//! orientation, are here too: [`symmetries`] gives the eight rotations and
+//! reflections, [`canonicalize`] picks one to stand for them all, and
+//! [`find_pattern`] finds a pattern with blanks, such as a sea monster.
+//! These need rows of equal length.
+//!
+//! For fencing puzzles, [`connected_components`] splits a grid into regions
+//! of like cells in one flood fill, and each region's cells give its
+//! [`region_perimeter`], its [`region_corner_count`] (equal to its number
+//! of straight sides) and its outer and inner [`region_boundaries`].
+//!
+//! [`GridExt`] reads a grid by columns as easily as by rows, and
+//! [`read_vertical_numbers`] reads digits written top to bottom, as in
+//! transposed text:
+//!
+//! ```
+//! use rust_advent::grid::{GridExt, read_vertical_numbers};
+//!
+//! let grid = rust_advent::parse_ascii_grid("12\n3\n45\n");
+//! let middle: Vec<Option<&u8>> = grid.column(1).collect();
+//! assert_eq!(middle, [Some(&b'2'), None, Some(&b'5')]);
+//! assert_eq!(read_vertical_numbers::<u64>(&grid, 0..2).unwrap(), [Some(134), Some(25)]);
+//! ```
+
+use crate::error::{Error, Result};
+use crate::{CheckedInt, FastMap, FastSet};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+/// Which cells count as next to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, down, left and right.
+    Four,
+    /// The four of [`Connectivity::Four`] and the diagonals.
+    Eight,
+}
+
+impl Connectivity {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        const FOUR: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+        const EIGHT: [(isize, isize); 8] = [
+            (-1, 0),
+            (1, 0),
+            (0, -1),
+            (0, 1),
+            (-1, -1),
+            (-1, 1),
+            (1, -1),
+            (1, 1),
+        ];
+        match self {
+            Connectivity::Four => &FOUR,
+            Connectivity::Eight => &EIGHT,
+        }
+    }
+}
+
+/// The cells of `grid` next to `cell`.
+pub fn neighbours<T>(
+    grid: &[Vec<T>],
+    (row, col): (usize, usize),
+    connectivity: Connectivity,
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    connectivity.offsets().iter().filter_map(move |&(dr, dc)| {
+        let next = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+        grid.get(next.0)?.get(next.1)?;
+        Some(next)
+    })
+}
+
+/// A cheapest route between two cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridPath {
+    /// The sum of the costs of every cell entered, leaving out the start.
+    pub cost: u64,
+    /// Every cell on the way, from the start to the goal.
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// The cheapest path from `start` to `goal`, by Dijkstra's algorithm.
+/// `cost` gives the price of stepping onto a cell, or `None` if the cell is
+/// blocked.  Returns `None` when the goal cannot be reached or either end
+/// is off the grid.
+pub fn shortest_path<T>(
+    grid: &[Vec<T>],
+    start: (usize, usize),
+    goal: (usize, usize),
+    connectivity: Connectivity,
+    mut cost: impl FnMut(&T) -> Option<u64>,
+) -> Option<GridPath> {
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let index = |(row, col): (usize, usize)| row * width + col;
+    grid.get(start.0)?.get(start.1)?;
+    grid.get(goal.0)?.get(goal.1)?;
+
+    let mut dist = vec![u64::MAX; grid.len() * width];
+    let mut came_from = vec![usize::MAX; grid.len() * width];
+    // Improvements push a new entry; stale ones are skipped as they surface
+    let mut heap = BinaryHeap::new();
+    dist[index(start)] = 0;
+    heap.push(Reverse((0, start)));
+    while let Some(Reverse((so_far, cell))) = heap.pop() {
+        if cell == goal {
+            break;
+        }
+        if so_far > dist[index(cell)] {
+            continue;
+        }
+        for next in neighbours(grid, cell, connectivity) {
+            let Some(step) = cost(&grid[next.0][next.1]) else {
+                continue;
+            };
+            let total = so_far.saturating_add(step);
+            if total < dist[index(next)] {
+                dist[index(next)] = total;
+                came_from[index(next)] = index(cell);
+                heap.push(Reverse((total, next)));
+            }
+        }
+    }
+
+    let total = dist[index(goal)];
+    if total == u64::MAX {
+        return None;
+    }
+    let mut cells = vec![goal];
+    let mut at = index(goal);
+    while at != index(start) {
+        at = came_from[at];
+        cells.push((at / width, at % width));
+    }
+    cells.reverse();
+    Some(GridPath { cost: total, cells })
+}
+
+/// The grid turned a quarter turn clockwise.
+///
+/// # Panics
+///
+/// Panics if the rows differ in length.
+pub fn rotate_clockwise<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    let mut rotated = transpose(grid);
+    for row in &mut rotated {
+        row.reverse();
+    }
+    rotated
+}
+
+/// The grid mirrored left to right.
+pub fn flip_horizontal<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    grid.iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// The grid with rows and columns swapped.
+///
+/// # Panics
+///
+/// Panics if the rows differ in length.
+pub fn transpose<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    let width = grid.first().map_or(0, Vec::len);
+    assert!(
+        grid.iter().all(|row| row.len() == width),
+        "cannot turn a grid with rows of different lengths"
+    );
+    (0..width)
+        .map(|col| grid.iter().map(|row| row[col].clone()).collect())
+        .collect()
+}
+
+/// The eight images of the grid under rotation and reflection: the four
+/// rotations clockwise from the grid as given, then the same of its mirror
+/// image.  Symmetric grids repeat some.
+///
+/// # Panics
+///
+/// Panics if the rows differ in length.
+pub fn symmetries<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<Vec<T>>> {
+    let mut images = Vec::with_capacity(8);
+    for start in [grid.to_vec(), flip_horizontal(grid)] {
+        let mut image = start;
+        for _ in 0..4 {
+            let next = rotate_clockwise(&image);
+            images.push(image);
+            image = next;
+        }
+    }
+    images
+}
+
+/// The least of the grid's [`symmetries`], comparing row by row, so two
+/// grids are the same up to rotation and reflection exactly when their
+/// canonical forms are equal.
+///
+/// # Panics
+///
+/// Panics if the rows differ in length.
+pub fn canonicalize<T: Clone + Ord>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    symmetries(grid).into_iter().min().unwrap()
+}
+
+/// The top left cells of every place `needle` matches `haystack`, in
+/// reading order.  `None` in the needle matches anything; matches may
+/// overlap, and an empty needle matches nowhere.  Search each of the
+/// needle's [`symmetries`] to find it in any orientation.
+pub fn find_pattern<T: PartialEq>(
+    haystack: &[Vec<T>],
+    needle: &[Vec<Option<T>>],
+) -> Vec<(usize, usize)> {
+    let needle_width = needle.iter().map(Vec::len).max().unwrap_or(0);
+    if needle_width == 0 {
+        return Vec::new();
+    }
+    let matches_at = |row: usize, col: usize| {
+        needle.iter().enumerate().all(|(dr, cells)| {
+            cells.iter().enumerate().all(|(dc, cell)| match cell {
+                None => true,
+                Some(want) => haystack[row + dr].get(col + dc) == Some(want),
+            })
+        })
+    };
+    let mut found = Vec::new();
+    let rows = (haystack.len() + 1).saturating_sub(needle.len());
+    for (row, cells) in haystack.iter().enumerate().take(rows) {
+        for col in 0..(cells.len() + 1).saturating_sub(needle_width) {
+            if matches_at(row, col) {
+                found.push((row, col));
+            }
+        }
+    }
+    found
+}
+
+/// The regions of `grid`, each the cells reachable from one another
+/// through neighbours that are `same` as each other.  Regions come in the
+/// reading order of their first cell, with their cells in reading order.
+pub fn connected_components<T>(
+    grid: &[Vec<T>],
+    connectivity: Connectivity,
+    mut same: impl FnMut(&T, &T) -> bool,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut seen: Vec<Vec<bool>> = grid.iter().map(|row| vec![false; row.len()]).collect();
+    let mut regions = Vec::new();
+    for (row, cells) in grid.iter().enumerate() {
+        for col in 0..cells.len() {
+            if seen[row][col] {
+                continue;
+            }
+            seen[row][col] = true;
+            let mut region = vec![(row, col)];
+            let mut stack = vec![(row, col)];
+            while let Some(cell) = stack.pop() {
+                for next in neighbours(grid, cell, connectivity) {
+                    if !seen[next.0][next.1] && same(&grid[cell.0][cell.1], &grid[next.0][next.1]) {
+                        seen[next.0][next.1] = true;
+                        region.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            region.sort_unstable();
+            regions.push(region);
+        }
+    }
+    regions
+}
+
+/// The cell beside `(row, col)` one step along `(dr, dc)`, or `None` above
+/// or left of the grid, where no region reaches.
+fn step((row, col): (usize, usize), (dr, dc): (isize, isize)) -> Option<(usize, usize)> {
+    Some((row.checked_add_signed(dr)?, col.checked_add_signed(dc)?))
+}
+
+/// The number of unit edges between `cells` and the cells outside them,
+/// holes included.
+pub fn region_perimeter(cells: &[(usize, usize)]) -> usize {
+    let region: FastSet<(usize, usize)> = cells.iter().copied().collect();
+    region
+        .iter()
+        .map(|&cell| {
+            Connectivity::Four
+                .offsets()
+                .iter()
+                .filter(|&&offset| step(cell, offset).is_none_or(|next| !region.contains(&next)))
+                .count()
+        })
+        .sum()
+}
+
+/// The number of corners on the boundaries of `cells`, which is also the
+/// number of straight sides.  Where two cells of the region touch only
+/// diagonally, the point between them is two corners.
+pub fn region_corner_count(cells: &[(usize, usize)]) -> usize {
+    let region: FastSet<(usize, usize)> = cells.iter().copied().collect();
+    let inside =
+        |cell: (usize, usize), offset| step(cell, offset).is_some_and(|c| region.contains(&c));
+    let mut corners = 0;
+    for &cell in &region {
+        for (dr, dc) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+            let vertical = inside(cell, (dr, 0));
+            let horizontal = inside(cell, (0, dc));
+            // Sticking out, or the inside of an L
+            if (!vertical && !horizontal) || (vertical && horizontal && !inside(cell, (dr, dc))) {
+                corners += 1;
+            }
+        }
+    }
+    corners
+}
+
+/// One closed boundary of a region, as the lattice points where it turns.
+/// Point `(row, col)` is the top left corner of cell `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boundary {
+    /// Whether this is the outside edge of the region rather than a hole.
+    pub outer: bool,
+    /// The corners in order, clockwise on the screen for the outer
+    /// boundary and anticlockwise around holes, so the region is always on
+    /// the right.  The first is the leftmost of the topmost.
+    pub corners: Vec<(usize, usize)>,
+}
+
+impl Boundary {
+    /// The number of unit edges around the boundary.
+    pub fn len(&self) -> usize {
+        let next = self.corners.iter().cycle().skip(1);
+        self.corners
+            .iter()
+            .zip(next)
+            .map(|(a, b)| a.0.abs_diff(b.0) + a.1.abs_diff(b.1))
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.corners.is_empty()
+    }
+}
+
+/// The boundaries of `cells`, one per closed loop: the outer boundary of a
+/// region connected through edges comes first, then one per hole.  Cells
+/// touching only diagonally keep separate boundaries, so the outer
+/// boundaries are those of the region's four-connected parts.
+pub fn region_boundaries(cells: &[(usize, usize)]) -> Vec<Boundary> {
+    // Headings clockwise from east, as (row, column) steps
+    const HEADINGS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+    let region: FastSet<(usize, usize)> = cells.iter().copied().collect();
+    let outside =
+        |cell: (usize, usize), offset| step(cell, offset).is_none_or(|c| !region.contains(&c));
+
+    // Unit edges keyed by their start point, heading so the region is on
+    // the right: along the top of a cell eastwards, down its right side...
+    let mut edges: FastMap<(usize, usize), Vec<usize>> = FastMap::default();
+    for &(row, col) in &region {
+        let sides = [
+            ((-1, 0), (row, col), 0),
+            ((0, 1), (row, col + 1), 1),
+            ((1, 0), (row + 1, col + 1), 2),
+            ((0, -1), (row + 1, col), 3),
+        ];
+        for (offset, start, heading) in sides {
+            if outside((row, col), offset) {
+                edges.entry(start).or_default().push(heading);
+            }
+        }
+    }
+
+    let mut starts: Vec<(usize, usize)> = edges.keys().copied().collect();
+    starts.sort_unstable();
+    let mut boundaries = Vec::new();
+    for first in starts {
+        while let Some(&start_heading) = edges.get(&first).and_then(|headings| headings.first()) {
+            // Every unit edge of the loop, as its start and heading
+            let mut path = Vec::new();
+            let (mut at, mut heading) = (first, start_heading);
+            loop {
+                let outgoing = edges.get_mut(&at).unwrap();
+                outgoing.retain(|&h| h != heading);
+                if outgoing.is_empty() {
+                    edges.remove(&at);
+                }
+                path.push((at, heading));
+                at = step(at, HEADINGS[heading]).unwrap();
+                // Turning right first keeps diagonally touching cells apart
+                let closes = |h: usize| at == first && h == start_heading;
+                heading = [1, 0, 3]
+                    .into_iter()
+                    .map(|turn| (heading + turn) % 4)
+                    .find(|&h| closes(h) || edges.get(&at).is_some_and(|hs| hs.contains(&h)))
+                    .unwrap();
+                if closes(heading) {
+                    break;
+                }
+            }
+
+            let mut corners: Vec<(usize, usize)> = (0..path.len())
+                .filter(|&idx| path[idx].1 != path[(idx + path.len() - 1) % path.len()].1)
+                .map(|idx| path[idx].0)
+                .collect();
+            let top_left = (0..corners.len()).min_by_key(|&idx| corners[idx]).unwrap();
+            corners.rotate_left(top_left);
+            // Twice the signed area, positive for clockwise on the screen
+            let area: i64 = path
+                .iter()
+                .map(|&(from, heading)| {
+                    let to = step(from, HEADINGS[heading]).unwrap();
+                    from.1 as i64 * to.0 as i64 - to.1 as i64 * from.0 as i64
+                })
+                .sum();
+            boundaries.push(Boundary {
+                outer: area > 0,
+                corners,
+            });
+        }
+    }
+    boundaries
+}
+
+/// Row and column views of a grid kept as a list of rows.  Rows may
+/// differ in length; a column reads `None` from rows too short to reach it.
+pub trait GridExt<T> {
+    /// The length of the longest row.
+    fn width(&self) -> usize;
+
+    /// The rows, top to bottom.
+    fn rows<'a>(&'a self) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a;
+
+    /// The cells of column `col`, top to bottom.
+    fn column<'a>(&'a self, col: usize) -> impl Iterator<Item = Option<&'a T>>
+    where
+        T: 'a;
+
+    /// Every column up to [`GridExt::width`], left to right.
+    fn columns<'a>(&'a self) -> impl Iterator<Item = impl Iterator<Item = Option<&'a T>>>
+    where
+        T: 'a;
+}
+
+impl<T> GridExt<T> for [Vec<T>] {
+    fn width(&self) -> usize {
+        self.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    fn rows<'a>(&'a self) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a,
+    {
+        self.iter().map(Vec::as_slice)
+    }
+
+    fn column<'a>(&'a self, col: usize) -> impl Iterator<Item = Option<&'a T>>
+    where
+        T: 'a,
+    {
+        self.iter().map(move |row| row.get(col))
+    }
+
+    fn columns<'a>(&'a self) -> impl Iterator<Item = impl Iterator<Item = Option<&'a T>>>
+    where
+        T: 'a,
+    {
+        (0..self.width()).map(|col| self.column(col))
+    }
+}
+
+/// The number written down each of `columns`, reading its digits top to
+/// bottom and skipping anything else, or `None` for a column with no
+/// digits.  A number too large for `T` is an error at its last digit.
+pub fn read_vertical_numbers<T: CheckedInt + From<u8>>(
+    grid: &[Vec<u8>],
+    columns: Range<usize>,
+) -> Result<Vec<Option<T>>> {
+    let ten = T::from(10);
+    columns
+        .map(|col| {
+            let mut number = None;
+            for (row, cell) in grid.column(col).enumerate() {
+                let Some(&digit) = cell.filter(|b| b.is_ascii_digit()) else {
+                    continue;
+                };
+                let value = number
+                    .unwrap_or(T::ZERO)
+                    .checked_mul(ten)
+                    .and_then(|n| n.checked_add(T::from(digit - b'0')));
+                number = Some(value.ok_or_else(|| {
+                    let message = format!(
+                        "number in this column overflows {}",
+                        std::any::type_name::<T>()
+                    );
+                    Error::parse_at(row + 1, col + 1, message)
+                })?);
+            }
+            Ok(number)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use crate::{ParseMode, parse_ascii_grid, parse_to_number_grid};
+
+    fn open(cell: &u8) -> Option<u64> {
+        (*cell != b'#').then_some(1)
+    }
+
+    #[test]
+    fn test_maze() {
+        let maze = parse_ascii_grid("S...\n##.#\n...#\n.#..\n");
+        let path = shortest_path(&maze, (0, 0), (3, 3), Connectivity::Four, open).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.cells.len(), 7);
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        assert_eq!(path.cells.last(), Some(&(3, 3)));
+        for pair in path.cells.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1, "{:?}", pair);
+            assert_ne!(maze[b.0][b.1], b'#');
+        }
+
+        let diagonal = shortest_path(&maze, (0, 0), (3, 3), Connectivity::Eight, open).unwrap();
+        assert_eq!(diagonal.cost, 4);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let costs = parse_to_number_grid(
+            "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n\
+             1319128137\n1359912421\n3125421639\n1293138521\n2311944581\n",
+            ParseMode::Strict,
+        )
+        .unwrap();
+        let path = shortest_path(&costs, (0, 0), (9, 9), Connectivity::Four, |&c| {
+            Some(u64::from(c))
+        })
+        .unwrap();
+        assert_eq!(path.cost, 40);
+        let entered: u64 = path.cells[1..]
+            .iter()
+            .map(|&(row, col)| u64::from(costs[row][col]))
+            .sum();
+        assert_eq!(entered, 40);
+    }
+
+    #[test]
+    fn test_unreachable_and_trivial() {
+        let walled = parse_ascii_grid("..#\n.#.\n#..\n");
+        assert_eq!(
+            shortest_path(&walled, (0, 0), (2, 2), Connectivity::Four, open),
+            None
+        );
+        assert!(shortest_path(&walled, (0, 0), (2, 2), Connectivity::Eight, open).is_some());
+        assert_eq!(
+            shortest_path(&walled, (0, 0), (5, 0), Connectivity::Four, open),
+            None
+        );
+        assert_eq!(
+            shortest_path(&walled, (1, 0), (1, 0), Connectivity::Four, open),
+            Some(GridPath {
+                cost: 0,
+                cells: vec![(1, 0)]
+            })
+        );
+    }
+
+    #[test]
+    fn test_symmetries() {
+        let grid = parse_ascii_grid("ab\ncd\nef\n");
+        assert_eq!(rotate_clockwise(&grid), parse_ascii_grid("eca\nfdb\n"));
+        assert_eq!(flip_horizontal(&grid), parse_ascii_grid("ba\ndc\nfe\n"));
+        assert_eq!(transpose(&grid), parse_ascii_grid("ace\nbdf\n"));
+
+        let images = symmetries(&grid);
+        assert_eq!(images.len(), 8);
+        assert_eq!(images[0], grid);
+        let mut distinct = images.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 8);
+        let canonical = canonicalize(&grid);
+        assert_eq!(canonical, parse_ascii_grid("ab\ncd\nef\n"));
+        for image in &images {
+            assert_eq!(canonicalize(image), canonical);
+        }
+        assert_ne!(canonicalize(&parse_ascii_grid("ab\ncc\nef\n")), canonical);
+
+        // A symmetric grid repeats its images
+        let plus = parse_ascii_grid(".#.\n###\n.#.\n");
+        assert!(symmetries(&plus).iter().all(|image| *image == plus));
+        assert!(symmetries::<u8>(&[]).iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    #[should_panic(expected = "rows of different lengths")]
+    fn test_rotate_ragged() {
+        rotate_clockwise(&parse_ascii_grid("ab\nc\n"));
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let monster: Vec<Vec<Option<u8>>> =
+            parse_ascii_grid("                  # \n#    ##    ##    ###\n #  #  #  #  #  #   \n")
+                .into_iter()
+                .map(|row| row.into_iter().map(|c| (c == b'#').then_some(c)).collect())
+                .collect();
+        // One monster, drawn in with its top left at row 1, column 2
+        let mut sea = vec![vec![b'.'; 24]; 5];
+        for (dr, cells) in monster.iter().enumerate() {
+            for (dc, &cell) in cells.iter().enumerate() {
+                if let Some(cell) = cell {
+                    sea[1 + dr][2 + dc] = cell;
+                }
+            }
+        }
+        assert_eq!(find_pattern(&sea, &monster), [(1, 2)]);
+        let flipped = flip_horizontal(&sea);
+        assert_eq!(find_pattern(&flipped, &monster), []);
+        let found: usize = symmetries(&monster)
+            .iter()
+            .map(|image| find_pattern(&flipped, image).len())
+            .sum();
+        assert_eq!(found, 1);
+
+        let dots = parse_ascii_grid("...\n...\n");
+        let pair = [vec![Some(b'.'), Some(b'.')]];
+        assert_eq!(find_pattern(&dots, &pair), [(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(find_pattern(&dots, &[vec![None; 4]]), []);
+        assert_eq!(find_pattern(&dots, &[]), []);
+    }
+
+    #[test]
+    fn test_grid_views() {
+        let grid = parse_ascii_grid("abc\nd\nef\n");
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.rows().map(<[u8]>::len).collect::<Vec<_>>(), [3, 1, 2]);
+        let columns: Vec<String> = grid
+            .columns()
+            .map(|column| column.map(|c| c.map_or('.', |&b| b as char)).collect())
+            .collect();
+        assert_eq!(columns, ["ade", "b.f", "c.."]);
+        assert_eq!(grid.column(7).flatten().count(), 0);
+        let empty: Vec<Vec<u8>> = Vec::new();
+        assert_eq!(empty.width(), 0);
+        assert_eq!(empty.columns().count(), 0);
+    }
+
+    #[test]
+    fn test_read_vertical_numbers() {
+        let grid = parse_ascii_grid("1 4x\n2 5\n3  6\n");
+        assert_eq!(
+            read_vertical_numbers::<u64>(&grid, 0..5).unwrap(),
+            [Some(123), None, Some(45), Some(6), None]
+        );
+        let nines = vec![vec![b'9']; 3];
+        assert_eq!(
+            read_vertical_numbers::<u8>(&nines[..2], 0..1).unwrap(),
+            [Some(99)]
+        );
+        let err = read_vertical_numbers::<u8>(&nines, 0..1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: number in this column overflows u8"
+        );
+    }
+
+    #[test]
+    fn test_neighbours_on_ragged_rows() {
+        let grid = vec![vec![0; 3], vec![0; 1]];
+        let mut around: Vec<_> = neighbours(&grid, (0, 1), Connectivity::Eight).collect();
+        around.sort();
+        assert_eq!(around, [(0, 0), (0, 2), (1, 0)]);
+    }
+
+    /// The sum over regions of area times perimeter, and of area times
+    /// sides.
+    fn fencing(garden: &str) -> (usize, usize) {
+        let grid = parse_ascii_grid(garden);
+        connected_components(&grid, Connectivity::Four, |a, b| a == b)
+            .iter()
+            .map(|cells| {
+                (
+                    cells.len() * region_perimeter(cells),
+                    cells.len() * region_corner_count(cells),
+                )
+            })
+            .fold((0, 0), |(a, b), (c, d)| (a + c, b + d))
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let grid = parse_ascii_grid("AAAA\nBBCD\nBBCC\nEEEC\n");
+        let regions = connected_components(&grid, Connectivity::Four, |a, b| a == b);
+        assert_eq!(regions.len(), 5);
+        assert_eq!(regions[0], [(0, 0), (0, 1), (0, 2), (0, 3)]);
+        assert_eq!(regions[2], [(1, 2), (2, 2), (2, 3), (3, 3)]);
+        assert_eq!(region_perimeter(&regions[2]), 10);
+        assert_eq!(region_corner_count(&regions[2]), 8);
+        assert_eq!(fencing("AAAA\nBBCD\nBBCC\nEEEC\n"), (140, 80));
+
+        let diagonal = parse_ascii_grid("#.\n.#\n");
+        let hash = |a: &u8, b: &u8| a == b && *a == b'#';
+        assert_eq!(
+            connected_components(&diagonal, Connectivity::Four, hash).len(),
+            4
+        );
+        assert_eq!(
+            connected_components(&diagonal, Connectivity::Eight, hash).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_region_sides() {
+        assert_eq!(fencing("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO\n"), (772, 436));
+        assert_eq!(fencing("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE\n").1, 236);
+        assert_eq!(
+            fencing("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA\n").1,
+            368
+        );
+        assert_eq!(region_perimeter(&[]), 0);
+        assert_eq!(region_corner_count(&[]), 0);
+        // Touching diagonally, the shared point is a corner of each cell
+        assert_eq!(region_corner_count(&[(0, 0), (1, 1)]), 8);
+    }
+
+    #[test]
+    fn test_region_boundaries() {
+        let ring = [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+        ];
+        let boundaries = region_boundaries(&ring);
+        assert_eq!(
+            boundaries,
+            [
+                Boundary {
+                    outer: true,
+                    corners: vec![(0, 0), (0, 3), (3, 3), (3, 0)],
+                },
+                Boundary {
+                    outer: false,
+                    corners: vec![(1, 1), (2, 1), (2, 2), (1, 2)],
+                },
+            ]
+        );
+        assert_eq!(boundaries[0].len(), 12);
+        assert_eq!(boundaries[1].len(), 4);
+
+        // Two blocks touching at a corner make one hole, kept apart from
+        // the region's cells touching at that corner
+        let grid = parse_ascii_grid("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA\n");
+        let a = &connected_components(&grid, Connectivity::Four, |a, b| a == b)[0];
+        let boundaries = region_boundaries(a);
+        assert_eq!(boundaries.iter().filter(|b| b.outer).count(), 1);
+        assert_eq!(boundaries.len(), 2);
+        assert_eq!(
+            boundaries[1].corners,
+            [
+                (1, 3),
+                (3, 3),
+                (3, 1),
+                (5, 1),
+                (5, 3),
+                (3, 3),
+                (3, 5),
+                (1, 5)
+            ]
+        );
+        assert!(region_boundaries(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_region_metrics_agree() {
+        let mut rng = Lcg::new(13);
+        for _ in 0..100 {
+            let grid: Vec<Vec<u64>> = (0..rng.between(1..=7))
+                .map(|_| (0..6).map(|_| rng.below(3)).collect())
+                .collect();
+            for cells in connected_components(&grid, Connectivity::Four, |a, b| a == b) {
+                let boundaries = region_boundaries(&cells);
+                assert!(boundaries[0].outer, "{grid:?}");
+                assert_eq!(boundaries.iter().filter(|b| b.outer).count(), 1);
+                let length: usize = boundaries.iter().map(Boundary::len).sum();
+                assert_eq!(length, region_perimeter(&cells));
+                let corners: usize = boundaries.iter().map(|b| b.corners.len()).sum();
+                assert_eq!(corners, region_corner_count(&cells), "{grid:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Append-only log of runner executions, stored as one JSON object per line,
+//! so timing and answer changes can be tracked across commits.
+
+use crate::json::{self, Value};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// Default history file name, relative to the crate root.
+pub const HISTORY_FILE: &str = ".advent-history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Failed,
+    TimedOut,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Failed => "failed",
+            Status::TimedOut => "timeout",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "ok" => Ok(Status::Ok),
+            "failed" => Ok(Status::Failed),
+            "timeout" => Ok(Status::TimedOut),
+            _ => Err(format!("unknown status '{}'", value)),
+        }
+    }
+}
+
+/// One execution of a solution binary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Short git hash, suffixed with `-dirty` for uncommitted changes.
+    pub commit: Option<String>,
+    pub bin: String,
+    pub elapsed: Duration,
+    pub status: Status,
+    /// `(label, value)` pairs such as `("Part 1", "42")`.
+    pub answers: Vec<(String, String)>,
+}
+
+impl Record {
+    pub fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("timestamp".to_string(), self.timestamp.into()),
+            (
+                "commit".to_string(),
+                self.commit.clone().map_or(Value::Null, Value::from),
+            ),
+            ("bin".to_string(), self.bin.as_str().into()),
+            (
+                "elapsed_ms".to_string(),
+                (self.elapsed.as_secs_f64() * 1000.0).into(),
+            ),
+            ("status".to_string(), self.status.as_str().into()),
+            (
+                "answers".to_string(),
+                Value::Object(
+                    self.answers
+                        .iter()
+                        .map(|(label, value)| (label.clone(), value.as_str().into()))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let field = |name: &str| value.get(name).ok_or(format!("missing field '{}'", name));
+        let invalid = |name: &str| format!("invalid field '{}'", name);
+
+        let elapsed_ms = field("elapsed_ms")?
+            .as_f64()
+            .filter(|ms| *ms >= 0.0)
+            .ok_or_else(|| invalid("elapsed_ms"))?;
+        let answers = field("answers")?
+            .as_object()
+            .ok_or_else(|| invalid("answers"))?
+            .iter()
+            .map(|(label, value)| {
+                value
+                    .as_str()
+                    .map(|v| (label.clone(), v.to_string()))
+                    .ok_or_else(|| invalid("answers"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Record {
+            timestamp: field("timestamp")?
+                .as_u64()
+                .ok_or_else(|| invalid("timestamp"))?,
+            commit: match field("commit")? {
+                Value::Null => None,
+                commit => Some(
+                    commit
+                        .as_str()
+                        .ok_or_else(|| invalid("commit"))?
+                        .to_string(),
+                ),
+            },
+            bin: field("bin")?
+                .as_str()
+                .ok_or_else(|| invalid("bin"))?
+                .to_string(),
+            elapsed: Duration::from_secs_f64(elapsed_ms / 1000.0),
+            status: Status::parse(field("status")?.as_str().ok_or_else(|| invalid("status"))?)?,
+            answers,
+        })
+    }
+}
+
+/// Appends a record to the history file, creating it if needed.
+pub fn append(path: &Path, record: &Record) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", record.to_json())
+}
+
+/// Loads every record from the history file.  A missing file is an empty
+/// history.
+pub fn load(path: &Path) -> std::io::Result<Vec<Record>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    parse_history(&content).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+fn parse_history(content: &str) -> Result<Vec<Record>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            json::parse(line)
+                .and_then(|value| Record::from_json(&value))
+                .map_err(|err| format!("Invalid history record at line {}: {}", idx + 1, err))
+        })
+        .collect()
+}
+
+/// Formats a Unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds_of_day = timestamp % 86_400;
+    // Civil-from-days conversion for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> Record {
+        Record {
+            timestamp: 1_765_000_000,
+            commit: Some("496ae92".to_string()),
+            bin: "claude_day10".to_string(),
+            elapsed: Duration::from_micros(12_500),
+            status: Status::Ok,
+            answers: vec![
+                ("Part 1".to_string(), "7".to_string()),
+                ("Part 2".to_string(), "33".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let record = record();
+        let parsed = Record::from_json(&json::parse(&record.to_json().to_string()).unwrap());
+        assert_eq!(parsed.unwrap(), record);
+
+        let record = Record {
+            commit: None,
+            status: Status::TimedOut,
+            answers: Vec::new(),
+            ..record
+        };
+        let parsed = Record::from_json(&json::parse(&record.to_json().to_string()).unwrap());
+        assert_eq!(parsed.unwrap(), record);
+    }
+
+    #[test]
+    fn test_parse_history_reports_line() {
+        let good = record().to_json().to_string();
+        let content = format!("{}\n\n{}\n{{\"bin\":\"x\"}}\n", good, good);
+        let err = parse_history(&content).unwrap_err();
+        assert!(err.contains("line 4"), "{}", err);
+        assert!(err.contains("missing field"), "{}", err);
+
+        assert_eq!(
+            parse_history(&format!("{}\n{}", good, good)).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_append_and_load() {
+        let path =
+            std::env::temp_dir().join(format!("advent-history-test-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(load(&path).unwrap(), Vec::new());
+        append(&path, &record()).unwrap();
+        append(&path, &record()).unwrap();
+        let records = load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(records, vec![record(), record()]);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_timestamp(1_765_000_000), "2025-12-06 05:46");
+    }
+}
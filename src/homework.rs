@@ -0,0 +1,568 @@
+//! Day 6's worksheet: rows of numbers above a row of operators, each
+//! problem combining its numbers with its operator.
+//!
+//! Parsing and evaluation are kept apart so both can be tested without a
+//! puzzle input.  [`parse_rows`] reads the numbers as written (part 1),
+//! [`parse_columns`] reads each character column top to bottom as one
+//! number (part 2), and [`grand_total`] adds up the answers.  Part 2's
+//! fixed-width cells come from the operator row alone, by
+//! [`column_spans`].
+
+use crate::error::{Error, Result};
+use crate::grid::read_vertical_numbers;
+use crate::validate::{ensure_len, ensure_rectangular};
+use crate::{Checked, product_checked, sum_checked};
+use std::fmt;
+use std::ops::Range;
+
+/// How a problem combines its numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Multiply,
+}
+
+impl Operator {
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '+' => Some(Operator::Add),
+            '*' => Some(Operator::Multiply),
+            _ => None,
+        }
+    }
+}
+
+/// One problem on the worksheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub operator: Operator,
+    pub numbers: Vec<i64>,
+}
+
+impl Problem {
+    /// The problem's answer, or `None` if it overflows.
+    pub fn evaluate(&self) -> Option<i64> {
+        let numbers = self.numbers.iter().copied();
+        match self.operator {
+            Operator::Add => sum_checked(numbers),
+            Operator::Multiply => product_checked(numbers),
+        }
+    }
+}
+
+/// Which part of the grand total overflowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// The answer to the problem with this 1-based index.
+    Problem(usize),
+    /// The sum of the answers.
+    Total,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Overflow::Problem(idx) => write!(f, "Problem {} overflows i64", idx),
+            Overflow::Total => write!(f, "Total overflows i64"),
+        }
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// The sum of every problem's answer.
+pub fn grand_total(problems: &[Problem]) -> Result<i64, Overflow> {
+    let mut total = Checked::new(0i64);
+    for (idx, problem) in problems.iter().enumerate() {
+        total += problem.evaluate().ok_or(Overflow::Problem(idx + 1))?;
+    }
+    total.get().ok_or(Overflow::Total)
+}
+
+/// Splits the worksheet into its number rows and its operator row, which
+/// must come last below at least two rows of numbers.
+fn split_rows<S: AsRef<str>>(lines: &[S]) -> Result<(&[S], &str)> {
+    match lines {
+        [rows @ .., operators] if rows.len() >= 2 => Ok((rows, operators.as_ref())),
+        _ => Err(Error::parse(
+            lines.len().max(1),
+            format!(
+                "need at least two rows of numbers above the operators, got {} lines",
+                lines.len()
+            ),
+        )),
+    }
+}
+
+/// Reads the worksheet as whitespace-separated numbers, one problem per
+/// column of numbers, with a whitespace-separated operator under each.
+pub fn parse_rows<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Problem>> {
+    let (rows, operator_line) = split_rows(lines)?;
+    let operator_line_no = rows.len() + 1;
+
+    let rows = rows
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let line = line.as_ref();
+            line.split_whitespace()
+                .map(|token| {
+                    token.parse::<i64>().map_err(|_| {
+                        Error::at_token(idx + 1, line, token, format!("invalid number '{}'", token))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let operators = operator_line
+        .split_whitespace()
+        .map(|token| {
+            let mut chars = token.chars();
+            match (chars.next().and_then(Operator::from_char), chars.next()) {
+                (Some(operator), None) => Ok(operator),
+                _ => Err(Error::at_token(
+                    operator_line_no,
+                    operator_line,
+                    token,
+                    format!("invalid operator '{}'", token),
+                )),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let width = ensure_rectangular(&rows).map_err(Error::Invalid)?;
+    ensure_len("operators", &operators, width).map_err(Error::Invalid)?;
+
+    Ok(operators
+        .into_iter()
+        .enumerate()
+        .map(|(col, operator)| Problem {
+            operator,
+            numbers: rows.iter().map(|row| row[col]).collect(),
+        })
+        .collect())
+}
+
+/// One problem's place on a worksheet read by columns: its operator, and
+/// the byte columns from that operator up to the next one, or to the end
+/// of the widest line for the last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpan {
+    pub operator: Operator,
+    pub columns: Range<usize>,
+}
+
+/// Splits the worksheet into fixed-width cells from its operator row:
+/// every operator starts a problem, and only spaces may come between
+/// them.  Where each problem ends depends only on the operator row and
+/// the widest line, never on where the numbers happen to sit.
+pub fn column_spans<S: AsRef<str>>(lines: &[S]) -> Result<Vec<ColumnSpan>> {
+    let (rows, operator_line) = split_rows(lines)?;
+    let operator_line_no = rows.len() + 1;
+
+    let mut starts = Vec::new();
+    for (col, ch) in operator_line.char_indices() {
+        if ch == ' ' {
+            continue;
+        }
+        let operator = Operator::from_char(ch).ok_or_else(|| {
+            Error::parse_span(
+                operator_line_no,
+                col + 1,
+                ch.len_utf8(),
+                format!("invalid operator '{}'", ch),
+            )
+        })?;
+        starts.push((col, operator));
+    }
+    if starts.is_empty() {
+        return Err(Error::parse(operator_line_no, "no operators found"));
+    }
+    let width = lines
+        .iter()
+        .map(|line| line.as_ref().len())
+        .max()
+        .unwrap_or(0);
+    Ok(starts
+        .iter()
+        .enumerate()
+        .map(|(idx, &(start, operator))| ColumnSpan {
+            operator,
+            columns: start..starts.get(idx + 1).map_or(width, |&(next, _)| next),
+        })
+        .collect())
+}
+
+/// The part of `line` in `span`'s columns: shorter than the span, or
+/// empty, where the line stops early.
+pub fn cell<'a>(line: &'a str, span: &ColumnSpan) -> &'a str {
+    let end = span.columns.end.min(line.len());
+    line.get(span.columns.start.min(end)..end).unwrap_or("")
+}
+
+/// Reads the worksheet column by column: each problem's cells come from
+/// [`column_spans`], and each character column within them, read top to
+/// bottom, is one number.  Numbers are listed right to left.  Lines
+/// shorter than the widest are treated as padded with spaces.
+pub fn parse_columns<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Problem>> {
+    let spans = column_spans(lines)?;
+    let (rows, _) = split_rows(lines)?;
+    let operator_line_no = rows.len() + 1;
+    let first = spans[0].columns.start;
+
+    for (idx, line) in rows.iter().enumerate() {
+        let line = line.as_ref();
+        if let Some((col, ch)) = line
+            .char_indices()
+            .find(|&(_, ch)| ch != ' ' && !ch.is_ascii_digit())
+        {
+            return Err(Error::parse_span(
+                idx + 1,
+                col + 1,
+                ch.len_utf8(),
+                format!("invalid digit '{}'", ch),
+            ));
+        }
+        if let Some(col) = line.bytes().take(first).position(|b| b != b' ') {
+            return Err(Error::parse_at(
+                idx + 1,
+                col + 1,
+                "number left of the first operator",
+            ));
+        }
+    }
+
+    let grid: Vec<Vec<u8>> = rows
+        .iter()
+        .map(|line| line.as_ref().bytes().collect())
+        .collect();
+    let mut problems = Vec::with_capacity(spans.len());
+    for span in &spans {
+        let mut numbers: Vec<i64> = read_vertical_numbers(&grid, span.columns.clone())?
+            .into_iter()
+            .flatten()
+            .collect();
+        numbers.reverse();
+        if numbers.is_empty() {
+            return Err(Error::parse_at(
+                operator_line_no,
+                span.columns.start + 1,
+                "operator has no numbers above it",
+            ));
+        }
+        problems.push(Problem {
+            operator: span.operator,
+            numbers,
+        });
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   +  ";
+
+    fn lines(text: &str) -> Vec<&str> {
+        text.lines().collect()
+    }
+
+    /// Parses `text` with `parse` and totals it, as the solvers do.
+    fn solve(parse: fn(&[String]) -> Result<Vec<Problem>>, text: &str) -> Result<i64, String> {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        Ok(grand_total(&parse(&lines)?).map_err(Error::from)?)
+    }
+
+    #[test]
+    fn test_parse_rows_example() {
+        let problems = parse_rows(&lines(EXAMPLE)).unwrap();
+        assert_eq!(problems.len(), 4);
+        assert_eq!(
+            problems[0],
+            Problem {
+                operator: Operator::Multiply,
+                numbers: vec![123, 45, 6],
+            }
+        );
+        assert_eq!(
+            problems[3],
+            Problem {
+                operator: Operator::Add,
+                numbers: vec![64, 23, 314],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_columns_example() {
+        let problems = parse_columns(&lines(EXAMPLE)).unwrap();
+        let numbers: Vec<_> = problems.iter().map(|p| p.numbers.clone()).collect();
+        assert_eq!(
+            numbers,
+            vec![
+                vec![356, 24, 1],
+                vec![8, 248, 369],
+                vec![175, 581, 32],
+                vec![4, 431, 623],
+            ]
+        );
+        assert_eq!(problems[1].operator, Operator::Add);
+    }
+
+    #[test]
+    fn test_column_spans() {
+        let spans = column_spans(&lines(EXAMPLE)).unwrap();
+        let columns: Vec<_> = spans.iter().map(|span| span.columns.clone()).collect();
+        assert_eq!(columns, [0..4, 4..8, 8..12, 12..15]);
+        let cells: Vec<Vec<&str>> = EXAMPLE
+            .lines()
+            .map(|line| spans.iter().map(|span| cell(line, span)).collect())
+            .collect();
+        assert_eq!(cells[0], ["123 ", "328 ", " 51 ", "64"]);
+        assert_eq!(cells[1], [" 45 ", "64  ", "387 ", "23"]);
+        assert_eq!(cells[2], ["  6 ", "98  ", "215 ", "314"]);
+
+        // Lines stopping early leave their last cells short or empty
+        let short = ["64  113", "23  422", "431 101", "720  5", "*   +"];
+        let spans = column_spans(&short).unwrap();
+        assert_eq!(
+            spans,
+            [
+                ColumnSpan {
+                    operator: Operator::Multiply,
+                    columns: 0..4,
+                },
+                ColumnSpan {
+                    operator: Operator::Add,
+                    columns: 4..7,
+                },
+            ]
+        );
+        assert_eq!(cell(short[3], &spans[1]), " 5");
+        assert_eq!(cell(short[4], &spans[1]), "+");
+        assert_eq!(cell("12", &spans[1]), "");
+
+        assert!(column_spans(&["1", "2", "  "]).is_err());
+        assert!(column_spans(&["1", "2", "+-"]).is_err());
+    }
+
+    #[test]
+    fn test_rows_table() {
+        let cases: &[(&str, &str, Result<i64, &str>)] = &[
+            ("example", EXAMPLE, Ok(4277556)),
+            ("two rows", "10 20\n5 3\n+ *", Ok(75)),
+            ("one column", "10\n20\n30\n+", Ok(60)),
+            ("zero product", "5 10\n0 2\n* *", Ok(20)),
+            ("negatives", "-1 2 -3\n4 -5 6\n-7 8 -9\n+ * +", Ok(-90)),
+            ("extra spaces", "  1   2\n3 4  \n +   * ", Ok(12)),
+            ("no problems", "\n\n\n", Ok(0)),
+            (
+                "too few lines",
+                "1 2\n+ *",
+                Err("line 2: need at least two rows of numbers above the operators, got 2 lines"),
+            ),
+            (
+                "empty",
+                "",
+                Err("line 1: need at least two rows of numbers above the operators, got 0 lines"),
+            ),
+            (
+                "invalid number",
+                "1 2 abc\n4 5 6\n+ * *",
+                Err("line 1, column 5: invalid number 'abc'"),
+            ),
+            (
+                "number too large",
+                "1\n9223372036854775808\n+",
+                Err("line 2, column 1: invalid number '9223372036854775808'"),
+            ),
+            (
+                "invalid operator",
+                "1 2 3\n4 5 6\n+ - *",
+                Err("line 3, column 3: invalid operator '-'"),
+            ),
+            (
+                "joined operators",
+                "1 2\n3 4\n+*",
+                Err("line 3, column 1: invalid operator '+*'"),
+            ),
+            (
+                "ragged",
+                "1 2 3\n4 5\n+ * *",
+                Err("invalid input: row 2 has length 2, expected 3"),
+            ),
+            (
+                "too few operators",
+                "1 2 3\n4 5 6\n+ *",
+                Err("invalid input: expected 3 operators, found 2"),
+            ),
+            (
+                "too many operators",
+                "1\n2\n+ *",
+                Err("invalid input: expected 1 operators, found 2"),
+            ),
+            (
+                "problem overflows",
+                "2147483647 1\n2147483647 1\n2147483647 1\n* +",
+                Err("unsupported: Problem 1 overflows i64"),
+            ),
+            (
+                "total overflows",
+                "2147483647 2147483647\n2147483647 2147483647\n2 2\n* *",
+                Err("unsupported: Total overflows i64"),
+            ),
+        ];
+        for &(name, text, expected) in cases {
+            assert_eq!(
+                solve(parse_rows, text),
+                expected.map_err(str::to_string),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_columns_table() {
+        let nines = format!("{}\n+", vec!["9"; 18].join("\n"));
+        let too_many_nines = format!("{}\n+", vec!["9"; 19].join("\n"));
+        // `problems` side by side, each the square of a column reading
+        // 3037000499, just below the square root of i64::MAX
+        let squares = |problems: usize| {
+            let rows = "3037000499"
+                .chars()
+                .map(|d| vec![format!("{d}{d}"); problems].join(" "));
+            let operators = vec!["* "; problems].join(" ");
+            rows.chain([operators]).collect::<Vec<_>>().join("\n")
+        };
+        let cases: &[(&str, &str, Result<i64, &str>)] = &[
+            ("example", EXAMPLE, Ok(3263827)),
+            (
+                "short lines",
+                "64  113\n23  422\n431 101\n720  5\n*   +",
+                Ok(270621707),
+            ),
+            ("one problem", "123\n456\n+", Ok(36 + 25 + 14)),
+            ("blank columns", "1  2\n3  4\n+  *", Ok(13 + 24)),
+            ("adjacent problems", "12\n34\n*+", Ok(13 + 24)),
+            ("long operator row", "1\n2\n+  ", Ok(12)),
+            ("zero", "0\n0\n*", Ok(0)),
+            ("eighteen digits", &nines, Ok(999_999_999_999_999_999)),
+            ("square", &squares(1), Ok(3037000499 * 3037000499)),
+            (
+                "too few lines",
+                "123\n+",
+                Err("line 2: need at least two rows of numbers above the operators, got 2 lines"),
+            ),
+            (
+                "no operators",
+                "123\n456\n   ",
+                Err("line 3: no operators found"),
+            ),
+            (
+                "invalid operator",
+                "123\n456\n-",
+                Err("line 3, column 1: invalid operator '-'"),
+            ),
+            (
+                "operator after tab",
+                "123\n456\n\t+",
+                Err("line 3, column 1: invalid operator '\t'"),
+            ),
+            (
+                "invalid digit",
+                "1x3\n456\n+",
+                Err("line 1, column 2: invalid digit 'x'"),
+            ),
+            (
+                "minus sign",
+                "123\n-56\n+",
+                Err("line 2, column 1: invalid digit '-'"),
+            ),
+            (
+                "number before operators",
+                "12\n34\n +",
+                Err("line 1, column 1: number left of the first operator"),
+            ),
+            (
+                "operator without numbers",
+                "1  \n2  \n+ *",
+                Err("line 3, column 3: operator has no numbers above it"),
+            ),
+            (
+                "column overflows",
+                &too_many_nines,
+                Err("line 19, column 1: number in this column overflows i64"),
+            ),
+            (
+                "problem overflows",
+                "9999999\n9999999\n9999999\n*      ",
+                Err("unsupported: Problem 1 overflows i64"),
+            ),
+            (
+                "total overflows",
+                &squares(2),
+                Err("unsupported: Total overflows i64"),
+            ),
+        ];
+        for &(name, text, expected) in cases {
+            assert_eq!(
+                solve(parse_columns, text),
+                expected.map_err(str::to_string),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_spans() {
+        let span = |err: Error| match err {
+            Error::Parse { column, width, .. } => (column, width),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        let err = parse_rows(&lines("1 22x 3\n4 5 6\n+ + +")).unwrap_err();
+        assert_eq!(span(err), (Some(3), 3));
+        let err = parse_columns(&lines("1é\n23\n+")).unwrap_err();
+        assert_eq!(span(err), (Some(2), 2));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let problem = |operator, numbers: &[i64]| Problem {
+            operator,
+            numbers: numbers.to_vec(),
+        };
+        assert_eq!(problem(Operator::Add, &[]).evaluate(), Some(0));
+        assert_eq!(problem(Operator::Multiply, &[]).evaluate(), Some(1));
+        assert_eq!(problem(Operator::Multiply, &[-3, 4]).evaluate(), Some(-12));
+        assert_eq!(problem(Operator::Add, &[i64::MAX, 1]).evaluate(), None);
+        assert_eq!(
+            problem(Operator::Multiply, &[i64::MIN, -1]).evaluate(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_grand_total() {
+        let add = |numbers: &[i64]| Problem {
+            operator: Operator::Add,
+            numbers: numbers.to_vec(),
+        };
+        assert_eq!(grand_total(&[]), Ok(0));
+        assert_eq!(grand_total(&[add(&[1, 2]), add(&[3])]), Ok(6));
+        assert_eq!(
+            grand_total(&[add(&[1]), add(&[i64::MAX, 1])]),
+            Err(Overflow::Problem(2))
+        );
+        assert_eq!(
+            grand_total(&[add(&[i64::MAX]), add(&[1])]),
+            Err(Overflow::Total)
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! A summary of what a puzzle input looks like, for choosing an algorithm
+//! before a long solve and for noticing the wrong day's file.  The input is
+//! read with the parser for the day's [`InputShape`], so a file that does
+//! not parse is reported as an error rather than summarized.
+//!
+//! ```
+//! use rust_advent::inspect::inspect;
+//!
+//! let report = inspect(9, "7,1\n11,1\n11,7\n").unwrap();
+//! assert_eq!(report.get("points"), Some("3"));
+//! assert_eq!(report.get("x"), Some("7 to 11"));
+//! ```
+
+use crate::day10::{CounterSet, Machine, Strategy};
+use crate::meta::{self, InputShape};
+use crate::{Error, FastMap, FastSet, Result, pipeline};
+use std::fmt;
+
+/// Statistics about one input, in the order they were gathered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub title: String,
+    pub rows: Vec<(&'static str, String)>,
+    /// Things about the input that look wrong without stopping the report.
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    /// The value of the row named `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.rows
+            .iter()
+            .find(|(row, _)| *row == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn row(&mut self, name: &'static str, value: impl ToString) {
+        self.rows.push((name, value.to_string()));
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.title)?;
+        let width = self.rows.iter().map(|(name, _)| name.len()).max();
+        for (name, value) in &self.rows {
+            writeln!(
+                f,
+                "  {:<width$}  {}",
+                name,
+                value,
+                width = width.unwrap_or(0)
+            )?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+/// The smallest, median and largest of some counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spread {
+    pub min: u64,
+    pub median: u64,
+    pub max: u64,
+}
+
+impl Spread {
+    /// The spread of `values`, or `None` if there are none.
+    pub fn of(mut values: Vec<u64>) -> Option<Spread> {
+        values.sort_unstable();
+        Some(Spread {
+            min: *values.first()?,
+            median: values[values.len() / 2],
+            max: *values.last()?,
+        })
+    }
+}
+
+impl fmt::Display for Spread {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{} to {}, median {}", self.min, self.max, self.median)
+        }
+    }
+}
+
+/// Summarizes `input` as day `day`'s puzzle input.  Fails for days the
+/// year does not have and for inputs the day's parser rejects.
+pub fn inspect(day: u32, input: &str) -> Result<Report> {
+    let info = meta::day_info(day)
+        .ok_or_else(|| Error::Unsupported(format!("there is no day {}", day)))?;
+    let mut report = Report {
+        title: format!("Day {}: {} ({})", day, info.title, info.input),
+        rows: Vec::new(),
+        warnings: Vec::new(),
+    };
+    let lines: Vec<&str> = input.lines().collect();
+    let blank = lines.iter().filter(|line| line.trim().is_empty()).count();
+    report.row("lines", lines.len());
+    if blank > 0 {
+        report.row("blank lines", blank);
+    }
+    if let Some(note) = meta::shape_mismatch(info.input, input) {
+        report.warnings.push(note);
+    }
+    match info.input {
+        InputShape::Machines => machines(&lines, &mut report)?,
+        InputShape::DigitGrid | InputShape::CharGrid => grid(&lines, &mut report),
+        InputShape::Points2d => {
+            let parsed = crate::parse_points2d(input)?;
+            points(parsed.iter().map(|p| vec![p.x, p.y]), &mut report);
+        }
+        InputShape::Points3d => {
+            let parsed = crate::parse_points(input)?;
+            points(parsed.iter().map(|p| vec![p.x, p.y, p.z]), &mut report);
+        }
+        InputShape::Graph => graph(&lines, &mut report)?,
+        _ => numbers(input, &mut report),
+    }
+    Ok(report)
+}
+
+/// Day 10: how big the machines are and which part 2 strategy each would
+/// get from [`Strategy::select`].
+fn machines(lines: &[&str], report: &mut Report) -> Result<()> {
+    let (mut counters, mut buttons, mut button_sizes, mut targets) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let (mut parity, mut bfs) = (0, 0);
+    for (number, line) in pipeline::lines(lines.iter().copied())
+        .non_empty()
+        .numbered()
+    {
+        let machine = Machine::parse_wide(line).map_err(|err| err.on_line(number))?;
+        counters.push(machine.targets().len() as u64);
+        buttons.push(machine.buttons().len() as u64);
+        button_sizes.extend(machine.buttons().iter().map(|b| b.count() as u64));
+        targets.extend(machine.targets().iter().map(|&t| u64::from(t)));
+        let strategy = match machine.narrow() {
+            Some(narrow) => Strategy::select(&narrow),
+            None => Strategy::select(&machine),
+        };
+        match strategy {
+            Strategy::Parity => parity += 1,
+            Strategy::Bfs => bfs += 1,
+        }
+    }
+    report.row("machines", counters.len());
+    let spreads = [
+        ("counters", counters),
+        ("buttons", buttons),
+        ("counters per button", button_sizes),
+        ("targets", targets),
+    ];
+    for (name, values) in spreads {
+        if let Some(spread) = Spread::of(values) {
+            report.row(name, spread);
+        }
+    }
+    report.row("part 2 strategy", format!("parity {}, bfs {}", parity, bfs));
+    Ok(())
+}
+
+/// Grids: their size, whether every row is as wide, and the cells used.
+fn grid(lines: &[&str], report: &mut Report) {
+    let rows: Vec<&[u8]> = lines
+        .iter()
+        .map(|line| line.trim_end().as_bytes())
+        .filter(|row| !row.is_empty())
+        .collect();
+    report.row("rows", rows.len());
+    if let Some(widths) = Spread::of(rows.iter().map(|row| row.len() as u64).collect()) {
+        report.row("columns", widths);
+        if widths.min != widths.max {
+            report
+                .warnings
+                .push("rows differ in width; the grid is not rectangular".to_string());
+        }
+    }
+    let mut cells: FastMap<u8, usize> = FastMap::default();
+    for &cell in rows.iter().copied().flatten() {
+        *cells.entry(cell).or_default() += 1;
+    }
+    let mut cells: Vec<(u8, usize)> = cells.into_iter().collect();
+    cells.sort_unstable();
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|&(cell, count)| format!("'{}' {}", cell.escape_ascii(), count))
+        .collect();
+    report.row("cells", cells.join(", "));
+}
+
+/// Points: how many, the range of each axis and any repeats.
+fn points(points: impl Iterator<Item = Vec<i32>>, report: &mut Report) {
+    let points: Vec<Vec<i32>> = points.collect();
+    report.row("points", points.len());
+    for (axis, name) in ["x", "y", "z"].into_iter().enumerate() {
+        let values = points.iter().filter_map(|p| p.get(axis).copied());
+        let (Some(min), Some(max)) = (values.clone().min(), values.max()) else {
+            continue;
+        };
+        report.row(name, format!("{} to {}", min, max));
+    }
+    let distinct: FastSet<&Vec<i32>> = points.iter().collect();
+    if distinct.len() < points.len() {
+        report.warnings.push(format!(
+            "{} points are repeats",
+            points.len() - distinct.len()
+        ));
+    }
+}
+
+/// Day 11: devices, connections and how many outputs each device has.
+fn graph(lines: &[&str], report: &mut Report) -> Result<()> {
+    let mut devices: FastSet<&str> = FastSet::default();
+    let mut outputs = Vec::new();
+    for (number, line) in pipeline::lines(lines.iter().copied())
+        .non_empty()
+        .numbered()
+    {
+        let (device, targets) = line
+            .split_once(':')
+            .ok_or_else(|| Error::parse(number, "expected 'device: outputs'"))?;
+        devices.insert(device.trim());
+        let targets: Vec<&str> = targets.split_whitespace().collect();
+        outputs.push(targets.len() as u64);
+        devices.extend(targets);
+    }
+    report.row("devices", devices.len());
+    report.row("connections", outputs.iter().sum::<u64>());
+    if let Some(spread) = Spread::of(outputs) {
+        report.row("outputs per device", spread);
+    }
+    Ok(())
+}
+
+/// Other days: the numbers in the input, whatever separates them.
+fn numbers(input: &str, report: &mut Report) {
+    let magnitudes: Vec<u64> = input
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|digits| !digits.is_empty())
+        .map(|digits| digits.parse().unwrap_or(u64::MAX))
+        .collect();
+    report.row("numbers", magnitudes.len());
+    if let Some(spread) = Spread::of(magnitudes) {
+        report.row("magnitudes", spread);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machines() {
+        let report = inspect(10, include_str!("../tests/golden/day10_example.txt")).unwrap();
+        assert_eq!(report.title, "Day 10: Factory (one machine per line)");
+        assert_eq!(report.get("machines"), Some("3"));
+        assert_eq!(report.get("counters"), Some("4 to 6, median 5"));
+        assert_eq!(report.get("buttons"), Some("4 to 6, median 5"));
+        assert!(report.get("part 2 strategy").is_some());
+        assert!(report.warnings.is_empty());
+
+        let err = inspect(10, "[.#] (0) {1,1}\n[.#] (7) {1,1}\n").unwrap_err();
+        assert!(matches!(err, Error::Parse { line: 2, .. }), "{:?}", err);
+    }
+
+    #[test]
+    fn test_wrong_file() {
+        // Day 8's points handed to day 3
+        let report = inspect(3, "1,2,3\n4,5,6\n").unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("x,y,z point"));
+        assert!(inspect(8, "1,2\n").is_err());
+        assert!(inspect(13, "").is_err());
+    }
+
+    #[test]
+    fn test_grid_and_display() {
+        let report = inspect(4, "..@\n@@.\n.\n").unwrap();
+        assert_eq!(report.get("rows"), Some("3"));
+        assert_eq!(report.get("cells"), Some("'.' 4, '@' 3"));
+        assert_eq!(
+            report.to_string(),
+            "Day 4: Printing Department (a grid of cells)\n  \
+             lines    3\n  \
+             rows     3\n  \
+             columns  1 to 3, median 3\n  \
+             cells    '.' 4, '@' 3\n\
+             warning: rows differ in width; the grid is not rectangular\n"
+        );
+    }
+
+    #[test]
+    fn test_points_and_graph() {
+        let report = inspect(8, "1,2,3\n-4,5,6\n1,2,3\n").unwrap();
+        assert_eq!(report.get("z"), Some("3 to 6"));
+        assert_eq!(report.warnings, ["1 points are repeats"]);
+
+        let report = inspect(11, "you: a b\na: out\nb: out\n").unwrap();
+        assert_eq!(report.get("devices"), Some("4"));
+        assert_eq!(report.get("connections"), Some("4"));
+        assert_eq!(report.get("outputs per device"), Some("1 to 2, median 1"));
+    }
+
+    #[test]
+    fn test_numbers() {
+        let report = inspect(2, "11-22,95-115\n").unwrap();
+        assert_eq!(report.get("numbers"), Some("4"));
+        assert_eq!(report.get("magnitudes"), Some("11 to 115, median 95"));
+        assert_eq!(Spread::of(Vec::new()), None);
+    }
+}
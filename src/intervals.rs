@@ -0,0 +1,314 @@
+//! Maps from disjoint half-open ranges of `i64` to values, for puzzles that
+//! remap whole ranges at once, such as seeds to soil to fertilizer.  An
+//! [`IntervalMap`] keeps its ranges disjoint: inserting over part of an
+//! existing range splits it and overwrites the overlap.
+//!
+//! Ranges are half open, `start..end`, as in [`sweep`](crate::sweep); the
+//! inclusive `3-5` of [`RangeData`](crate::RangeData) is `3..6`.
+//!
+//! With `i64` values read as offsets, the map is a piecewise shift that
+//! leaves unmapped numbers alone, and [`IntervalMap::map_range`] and
+//! [`IntervalMap::compose`] push whole ranges through one map or fold a
+//! chain of maps into one.
+//!
+//! ```
+//! use rust_advent::intervals::IntervalMap;
+//!
+//! // 98..100 moves to 50..52 and 50..98 to 52..100
+//! let soil: IntervalMap<i64> = [(98..100, -48), (50..98, 2)].into_iter().collect();
+//! assert_eq!(soil.map(79), 81);
+//! assert_eq!(soil.map(10), 10);
+//! assert_eq!(soil.map_range(45..55), [45..50, 52..57]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Values over disjoint ranges, each keyed by its start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalMap<V> {
+    ranges: BTreeMap<i64, (i64, V)>,
+}
+
+impl<V> Default for IntervalMap<V> {
+    fn default() -> Self {
+        IntervalMap {
+            ranges: BTreeMap::new(),
+        }
+    }
+}
+
+impl<V: Clone> IntervalMap<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of disjoint ranges stored.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The ranges and their values, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (Range<i64>, &V)> {
+        self.ranges
+            .iter()
+            .map(|(&start, (end, value))| (start..*end, value))
+    }
+
+    /// The value at `point`, if any range covers it.
+    pub fn get(&self, point: i64) -> Option<&V> {
+        self.ranges
+            .range(..=point)
+            .next_back()
+            .filter(|(_, (end, _))| point < *end)
+            .map(|(_, (_, value))| value)
+    }
+
+    /// Sets `range` to `value`, trimming or splitting the ranges it
+    /// overlaps.  Empty ranges change nothing.
+    pub fn insert(&mut self, range: Range<i64>, value: V) {
+        if range.is_empty() {
+            return;
+        }
+        // A range starting before `range` may reach into it, or past it
+        if let Some((&start, (end, old))) = self.ranges.range_mut(..range.start).next_back()
+            && *end > range.start
+        {
+            let (end, old) = (*end, old.clone());
+            self.ranges.insert(start, (range.start, old.clone()));
+            if end > range.end {
+                self.ranges.insert(range.end, (end, old));
+            }
+        }
+        // Ranges starting inside `range` go, keeping any tail beyond it
+        let inside: Vec<i64> = self
+            .ranges
+            .range(range.start..range.end)
+            .map(|(&start, _)| start)
+            .collect();
+        for start in inside {
+            let (end, old) = self.ranges.remove(&start).expect("collected above");
+            if end > range.end {
+                self.ranges.insert(range.end, (end, old));
+            }
+        }
+        self.ranges.insert(range.start, (range.end, value));
+    }
+
+    /// The parts of `range` covered by the map, each with its value, in
+    /// order.  Uncovered parts are left out.
+    pub fn translate(&self, range: Range<i64>) -> Vec<(Range<i64>, V)> {
+        self.pieces(range)
+            .into_iter()
+            .filter_map(|(piece, value)| Some((piece, value?.clone())))
+            .collect()
+    }
+
+    /// All of `range` cut where the map's ranges start and end, each piece
+    /// with its value or `None` where uncovered.
+    fn pieces(&self, range: Range<i64>) -> Vec<(Range<i64>, Option<&V>)> {
+        let mut pieces = Vec::new();
+        if range.is_empty() {
+            return pieces;
+        }
+        let first = self
+            .ranges
+            .range(..=range.start)
+            .next_back()
+            .map_or(range.start, |(&start, _)| start);
+        let mut at = range.start;
+        for (&start, (end, value)) in self.ranges.range(first..range.end) {
+            if *end <= at {
+                continue;
+            }
+            if at < start {
+                pieces.push((at..start, None));
+                at = start;
+            }
+            let until = (*end).min(range.end);
+            pieces.push((at..until, Some(value)));
+            at = until;
+        }
+        if at < range.end {
+            pieces.push((at..range.end, None));
+        }
+        pieces
+    }
+}
+
+impl<V: Clone> FromIterator<(Range<i64>, V)> for IntervalMap<V> {
+    /// Inserts each range in turn, so later ranges win where they overlap.
+    fn from_iter<I: IntoIterator<Item = (Range<i64>, V)>>(iter: I) -> Self {
+        let mut map = IntervalMap::new();
+        for (range, value) in iter {
+            map.insert(range, value);
+        }
+        map
+    }
+}
+
+/// Maps whose values are offsets: `point` goes to `point + offset`, and
+/// points no range covers stay where they are.
+impl IntervalMap<i64> {
+    pub fn map(&self, point: i64) -> i64 {
+        point + self.get(point).copied().unwrap_or(0)
+    }
+
+    /// The image of `range`, one range per piece of the map it crosses, in
+    /// the order of `range` rather than sorted or merged.
+    pub fn map_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        self.pieces(range)
+            .into_iter()
+            .map(|(piece, offset)| {
+                let offset = offset.copied().unwrap_or(0);
+                piece.start + offset..piece.end + offset
+            })
+            .collect()
+    }
+
+    /// The map applying `self` and then `then`, so that
+    /// `self.compose(&then).map(p) == then.map(self.map(p))`.  Pieces
+    /// with a total offset of zero are left out, since unmapped points
+    /// stay put anyway.
+    pub fn compose(&self, then: &IntervalMap<i64>) -> IntervalMap<i64> {
+        let mut composed = IntervalMap::new();
+        let mut keep = |range: Range<i64>, offset: i64| {
+            if offset != 0 {
+                composed.insert(range, offset);
+            }
+        };
+        // Points `self` moves, cut where their images cross `then`
+        for (range, &offset) in self.iter() {
+            for (image, next) in then.pieces(range.start + offset..range.end + offset) {
+                keep(
+                    image.start - offset..image.end - offset,
+                    offset + next.copied().unwrap_or(0),
+                );
+            }
+        }
+        // Points `self` leaves alone go wherever `then` sends them
+        for (range, &next) in then.iter() {
+            for (piece, offset) in self.pieces(range) {
+                if offset.is_none() {
+                    keep(piece, next);
+                }
+            }
+        }
+        composed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+
+    fn random_map(rng: &mut Lcg) -> IntervalMap<i64> {
+        (0..rng.below(6))
+            .map(|_| {
+                let start = rng.below(40) as i64;
+                let len = rng.below(10) as i64;
+                (start..start + len, rng.below(21) as i64 - 10)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_matches_array() {
+        let mut rng = Lcg::new(22);
+        for _ in 0..200 {
+            let mut map = IntervalMap::new();
+            let mut expected = [None; 50];
+            for value in 0..rng.between(1..=6) {
+                let start = rng.below(45) as i64;
+                let end = start + rng.below(8) as i64;
+                map.insert(start..end, value);
+                for slot in &mut expected[start as usize..end as usize] {
+                    *slot = Some(value);
+                }
+            }
+            for (point, expected) in expected.iter().enumerate() {
+                assert_eq!(map.get(point as i64), expected.as_ref());
+            }
+            // Stored ranges stay sorted, disjoint and non-empty
+            let ranges: Vec<Range<i64>> = map.iter().map(|(range, _)| range).collect();
+            assert!(ranges.iter().all(|range| !range.is_empty()));
+            assert!(ranges.windows(2).all(|pair| pair[0].end <= pair[1].start));
+
+            let pieces = map.translate(3..40);
+            let covered: i64 = pieces
+                .iter()
+                .map(|(range, _)| range.end - range.start)
+                .sum();
+            assert_eq!(covered as usize, expected[3..40].iter().flatten().count());
+            for (range, value) in pieces {
+                assert!(range.clone().all(|p| map.get(p) == Some(&value)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_and_translate() {
+        let mut map = IntervalMap::new();
+        map.insert(0..10, 'a');
+        map.insert(3..5, 'b');
+        map.insert(8..12, 'c');
+        map.insert(20..20, 'z');
+        let stored: Vec<(Range<i64>, char)> = map.iter().map(|(r, &v)| (r, v)).collect();
+        assert_eq!(
+            stored,
+            [(0..3, 'a'), (3..5, 'b'), (5..8, 'a'), (8..12, 'c')]
+        );
+        assert_eq!(
+            map.translate(4..15),
+            [(4..5, 'b'), (5..8, 'a'), (8..12, 'c')]
+        );
+        assert_eq!(map.translate(-5..-1), []);
+        map.insert(-1..13, 'd');
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_compose_matches_pointwise() {
+        let mut rng = Lcg::new(23);
+        for _ in 0..200 {
+            let first = random_map(&mut rng);
+            let second = random_map(&mut rng);
+            let both = first.compose(&second);
+            for p in -20..70 {
+                assert_eq!(both.map(p), second.map(first.map(p)), "{}", p);
+            }
+            let mut images: Vec<i64> = first.map_range(-5..60).into_iter().flatten().collect();
+            let mut expected: Vec<i64> = (-5..60).map(|p| first.map(p)).collect();
+            images.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(images, expected);
+        }
+    }
+
+    #[test]
+    fn test_seed_chain() {
+        // seed-to-soil then soil-to-fertilizer, as destination, source, length
+        let parse = |lines: &[[i64; 3]]| -> IntervalMap<i64> {
+            lines
+                .iter()
+                .map(|&[to, from, len]| (from..from + len, to - from))
+                .collect()
+        };
+        let soil = parse(&[[50, 98, 2], [52, 50, 48]]);
+        let fertilizer = parse(&[[0, 15, 37], [37, 52, 2], [39, 0, 15]]);
+        let chain = soil.compose(&fertilizer);
+        let seeds = [79, 14, 55, 13];
+        assert_eq!(seeds.map(|seed| chain.map(seed)), [81, 53, 57, 52]);
+        let lowest = chain
+            .map_range(79..93)
+            .into_iter()
+            .map(|range| range.start)
+            .min();
+        assert_eq!(lowest, Some(81));
+    }
+}
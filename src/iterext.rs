@@ -0,0 +1,181 @@
+//! Iterator adapters for the "compare neighbouring items" and "split into
+//! groups" steps that std leaves to slices:
+//!
+//! ```
+//! use rust_advent::iterext::IterExt;
+//!
+//! let depths = [199, 200, 208, 210, 200];
+//! let rises = depths.iter().copied().pairwise_diffs().filter(|&d| d > 0).count();
+//! assert_eq!(rises, 3);
+//! let sums: Vec<i32> = depths.iter().copied().windows_exact().map(|[a, b, c]| a + b + c).collect();
+//! assert_eq!(sums, [607, 618, 618]);
+//! ```
+//!
+//! Unlike `slice::windows` these work on any iterator, so a parsed stream of
+//! lines never has to be collected first.
+
+use std::collections::VecDeque;
+use std::ops::Sub;
+
+/// Adapters available on every iterator.
+pub trait IterExt: Iterator + Sized {
+    /// Every run of `N` consecutive items, as arrays, sliding by one.  Yields
+    /// nothing if there are fewer than `N` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    fn windows_exact<const N: usize>(self) -> WindowsExact<Self, N>
+    where
+        Self::Item: Clone,
+    {
+        assert!(N > 0, "windows_exact needs a window of at least one item");
+        WindowsExact {
+            inner: self,
+            window: VecDeque::with_capacity(N),
+        }
+    }
+
+    /// Splits the items into groups at each item matching `is_separator`,
+    /// dropping the separators.  Empty groups are skipped, so runs of
+    /// separators and separators at either end act like one: the usual
+    /// shape of blank-line separated blocks.
+    fn chunks_separated_by<P>(self, is_separator: P) -> ChunksSeparatedBy<Self, P>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        ChunksSeparatedBy {
+            inner: self,
+            is_separator,
+        }
+    }
+
+    /// The difference between each item and the one before it, so `n`
+    /// items give `n - 1` differences.
+    fn pairwise_diffs(self) -> PairwiseDiffs<Self>
+    where
+        Self::Item: Sub + Clone,
+    {
+        PairwiseDiffs {
+            inner: self,
+            previous: None,
+        }
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+/// See [`IterExt::windows_exact`].
+#[derive(Debug, Clone)]
+pub struct WindowsExact<I: Iterator, const N: usize> {
+    inner: I,
+    window: VecDeque<I::Item>,
+}
+
+impl<I, const N: usize> Iterator for WindowsExact<I, N>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<[I::Item; N]> {
+        if self.window.len() == N {
+            self.window.pop_front();
+        }
+        while self.window.len() < N {
+            self.window.push_back(self.inner.next()?);
+        }
+        Some(std::array::from_fn(|idx| self.window[idx].clone()))
+    }
+}
+
+/// See [`IterExt::chunks_separated_by`].
+#[derive(Debug, Clone)]
+pub struct ChunksSeparatedBy<I, P> {
+    inner: I,
+    is_separator: P,
+}
+
+impl<I, P> Iterator for ChunksSeparatedBy<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        let mut chunk = Vec::new();
+        for item in self.inner.by_ref() {
+            if !(self.is_separator)(&item) {
+                chunk.push(item);
+            } else if !chunk.is_empty() {
+                return Some(chunk);
+            }
+        }
+        (!chunk.is_empty()).then_some(chunk)
+    }
+}
+
+/// See [`IterExt::pairwise_diffs`].
+#[derive(Debug, Clone)]
+pub struct PairwiseDiffs<I: Iterator> {
+    inner: I,
+    previous: Option<I::Item>,
+}
+
+impl<I> Iterator for PairwiseDiffs<I>
+where
+    I: Iterator,
+    I::Item: Sub + Clone,
+{
+    type Item = <I::Item as Sub>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.previous.is_none() {
+            self.previous = Some(self.inner.next()?);
+        }
+        let current = self.inner.next()?;
+        let previous = self.previous.replace(current.clone())?;
+        Some(current - previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_exact() {
+        let pairs: Vec<[u8; 2]> = b"abcd".iter().copied().windows_exact().collect();
+        assert_eq!(pairs, [*b"ab", *b"bc", *b"cd"]);
+        let singles: Vec<[i32; 1]> = [1, 2].into_iter().windows_exact().collect();
+        assert_eq!(singles, [[1], [2]]);
+        assert_eq!([1, 2].into_iter().windows_exact::<3>().count(), 0);
+        assert_eq!(std::iter::empty::<i32>().windows_exact::<2>().count(), 0);
+    }
+
+    #[test]
+    fn test_chunks_separated_by() {
+        let text = "\n1000\n2000\n\n\n3000\n\n";
+        let groups: Vec<Vec<&str>> = text
+            .lines()
+            .chunks_separated_by(|line| line.is_empty())
+            .collect();
+        assert_eq!(groups, [vec!["1000", "2000"], vec!["3000"]]);
+        let groups: Vec<Vec<i32>> = [0, 0]
+            .into_iter()
+            .chunks_separated_by(|&n| n == 0)
+            .collect();
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_pairwise_diffs() {
+        let diffs: Vec<i64> = [3i64, 1, 4, 1, 5].into_iter().pairwise_diffs().collect();
+        assert_eq!(diffs, [-2, 3, -3, 4]);
+        assert_eq!([7].into_iter().pairwise_diffs().count(), 0);
+        let floats: Vec<f64> = [0.5, 2.0].into_iter().pairwise_diffs().collect();
+        assert_eq!(floats, [1.5]);
+    }
+}
@@ -1,90 +1,369 @@
+//! Shared input parsing and algorithms for the Advent of Code 2025
+//! solutions in `src/bin`.
+//!
+//! Everything that reads files or runs on rayon needs the default `native`
+//! feature.  Without it the library only parses strings, which is enough to
+//! build it for wasm32-unknown-unknown and solve from a browser; see
+//! [`playground::solve`].
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+#[cfg(feature = "native")]
 use std::fs::File;
+use std::hash::{BuildHasherDefault, Hasher};
+#[cfg(feature = "native")]
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub mod day10;
+pub mod error;
+pub mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod generators;
+pub mod geo;
+pub mod gf2;
+#[cfg(feature = "native")]
+pub mod golden;
+pub mod graph;
+pub mod grid;
+#[cfg(feature = "native")]
+pub mod history;
+pub mod homework;
+pub mod inspect;
+pub mod intervals;
+pub mod iterext;
+pub mod json;
+pub mod memo;
+pub mod meta;
+pub mod modint;
+#[cfg(feature = "native")]
+pub mod parallel;
+pub mod paths;
+pub mod pipeline;
+pub mod playground;
+pub mod progress;
+#[cfg(feature = "native")]
+pub mod puzzle;
+#[cfg(feature = "python")]
+mod python;
+pub mod rngs;
+pub mod sat;
+pub mod search;
+#[cfg(feature = "native")]
+pub mod serve;
+pub mod sim;
+pub mod solution;
+#[cfg(feature = "native")]
+pub mod stats;
+pub mod strings;
+pub mod sweep;
+pub mod trace;
+pub mod validate;
+pub mod viz;
+pub mod vm;
+
+/// Turns a unit struct into a solution binary, given free functions
+/// `part1` and `part2` that take the parsed input and a
+/// [`Context`](solution::Context) and return a `Result<T, E>` for any `T`
+/// that converts into an [`Answer`]:
+///
+/// ```ignore
+/// #[advent(year = 2025, day = 10, variant = "claude")]
+/// struct Day10;
+///
+/// fn part1(input: &[String], ctx: &Context) -> Result<Outcome, Box<dyn Error>> { ... }
+/// fn part2(input: &[String], ctx: &Context) -> Result<Outcome, Box<dyn Error>> { ... }
+/// ```
+///
+/// This implements [`Solution`](solution::Solution) for the struct and adds
+/// a `main` that hands it to [`solution::run`].  The input is its lines
+/// unless `input = Type, parse = function` say otherwise, and `description
+/// = "..."` gives the notes for `advent report`.  `max_positions = N` and
+/// `max_grid_width = N` set the [`Limits`](solution::Limits), checked
+/// against what `input_size = function` measures of the parsed input.  The
+/// build fails if the binary is not named `<variant>_day<DD>`, which the
+/// runner relies on.
+pub use advent_macros::advent;
+pub use error::{Error, Result};
+pub use playground::solve;
 
 const INPUT_BASE_PATH: &str = "/Users/alexconley/Programming/Advent Of Code/2025/input";
 
-/// Returns the path to the input file for the given day.
-fn get_input_path(day: &str) -> PathBuf {
+/// Environment variable naming a file to read in place of the day's input,
+/// so a solution binary can be run on an example without editing it.
+pub const INPUT_VAR: &str = "ADVENT_INPUT";
+
+/// Returns the directory holding the puzzle input files.
+pub fn get_input_dir() -> &'static Path {
+    Path::new(INPUT_BASE_PATH)
+}
+
+/// Returns the path to the input file for the given day, or the file named
+/// by [`INPUT_VAR`] if it is set.
+pub fn get_input_path(day: &str) -> PathBuf {
+    if let Some(path) = std::env::var_os(INPUT_VAR) {
+        return PathBuf::from(path);
+    }
     let mut path = Path::new(INPUT_BASE_PATH).join(day);
     path.set_extension("txt");
     path
 }
 
 /// Reads the input file for the given day as a single string.
-pub fn read_file_as_string(day: &str) -> std::io::Result<String> {
-    std::fs::read_to_string(get_input_path(day))
+#[cfg(feature = "native")]
+pub fn read_file_as_string(day: &str) -> Result<String> {
+    Ok(std::fs::read_to_string(get_input_path(day))?)
 }
 
 /// Reads the input file for the given day as a vector of strings, one for each line.
-pub fn read_file_as_lines(day: &str) -> std::io::Result<Vec<String>> {
-    BufReader::new(File::open(get_input_path(day))?)
+#[cfg(feature = "native")]
+pub fn read_file_as_lines(day: &str) -> Result<Vec<String>> {
+    Ok(BufReader::new(File::open(get_input_path(day))?)
         .lines()
-        .collect()
+        .collect::<std::io::Result<_>>()?)
+}
+
+/// The lines of a checked-in example input, embedded at compile time so
+/// tests need no input directory.  `example_input!("12/example1")` reads
+/// `tests/examples/12/example1.txt` and gives a `Vec<String>`.
+#[macro_export]
+macro_rules! example_input {
+    ($name:literal) => {
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/examples/",
+            $name,
+            ".txt"
+        ))
+        .lines()
+        .map(str::to_string)
+        .collect::<Vec<String>>()
+    };
+}
+
+/// A day's input kept in one buffer.  Lines are borrowed from it instead of
+/// each being copied into its own `String`, for solvers that only need them
+/// while parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleInput {
+    text: String,
+}
+
+impl PuzzleInput {
+    /// Reads the input file for the given day.
+    #[cfg(feature = "native")]
+    pub fn read(day: &str) -> Result<Self> {
+        Ok(Self::from(read_file_as_string(day)?))
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The lines without their terminators, as [`read_file_as_lines`] would
+    /// return them, ready for the combinators in [`pipeline`].
+    pub fn lines(&self) -> pipeline::Lines<impl Iterator<Item = (usize, &str)>> {
+        pipeline::lines(self.text.lines())
+    }
+}
+
+impl From<String> for PuzzleInput {
+    fn from(text: String) -> Self {
+        PuzzleInput { text }
+    }
+}
+
+impl From<&str> for PuzzleInput {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
+
+/// Parses every whitespace-separated field of `line`.
+pub fn parse_fields<T: FromStr>(line: &str) -> Result<Vec<T>, T::Err> {
+    line.split_whitespace().map(str::parse).collect()
+}
+
+/// Splits `line` at the first `separator` and trims both halves.
+pub fn split_trimmed(line: &str, separator: char) -> Option<(&str, &str)> {
+    line.split_once(separator)
+        .map(|(head, tail)| (head.trim(), tail.trim()))
+}
+
+/// Parses one whitespace-separated token of `line`, reporting its position
+/// if it is not a valid `T`.
+fn parse_token<T: FromStr>(line_number: usize, line: &str, token: &str) -> Result<T>
+where
+    T::Err: fmt::Display,
+{
+    token.parse().map_err(|err| {
+        Error::at_token(
+            line_number,
+            line,
+            token,
+            format!(
+                "'{}' is not a valid {} ({})",
+                token,
+                std::any::type_name::<T>(),
+                err
+            ),
+        )
+    })
 }
 
-pub fn read_int_pairs(day: &str) -> std::io::Result<(Vec<i32>, Vec<i32>)> {
-    let reader = BufReader::new(File::open(get_input_path(day))?);
+pub fn parse_int_pairs(input: &str) -> Result<(Vec<i32>, Vec<i32>)> {
     let mut v1 = Vec::new();
     let mut v2 = Vec::new();
-    for line in reader.lines() {
-        let line = line?;
+    for (idx, line) in input.lines().enumerate() {
         let mut parts = line.split_whitespace();
-        v1.push(
-            parts
-                .next()
-                .expect("No first number")
-                .parse()
-                .expect("First number is not an integer"),
-        );
-        v2.push(
-            parts
-                .next()
-                .expect("No second number")
-                .parse()
-                .expect("Second number is not an integer"),
-        );
+        let (Some(first), Some(second)) = (parts.next(), parts.next()) else {
+            return Err(Error::parse(
+                idx + 1,
+                format!("expected two numbers, got '{}'", line),
+            ));
+        };
+        v1.push(parse_token(idx + 1, line, first)?);
+        v2.push(parse_token(idx + 1, line, second)?);
     }
     Ok((v1, v2))
 }
 
+#[cfg(feature = "native")]
+pub fn read_int_pairs(day: &str) -> Result<(Vec<i32>, Vec<i32>)> {
+    parse_int_pairs(&read_file_as_string(day)?)
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point2d {
     pub x: i32,
     pub y: i32,
 }
 
-pub fn read_points2d(day: &str) -> std::io::Result<Vec<Point2d>> {
-    let reader = BufReader::new(File::open(get_input_path(day))?);
+impl Point2d {
+    /// `self.x - other.x`, widened first so far-apart points cannot
+    /// overflow `i32`.
+    pub fn dx_i64(self, other: Point2d) -> i64 {
+        i64::from(self.x) - i64::from(other.x)
+    }
+
+    /// `self.y - other.y`, widened first so far-apart points cannot
+    /// overflow `i32`.
+    pub fn dy_i64(self, other: Point2d) -> i64 {
+        i64::from(self.y) - i64::from(other.y)
+    }
+}
+
+/// Number of grid cells in the axis-aligned rectangle with opposite corners
+/// `p` and `q`, counting both edges: `(|dx| + 1) * (|dy| + 1)`.  Shorthand
+/// for [`GridRect::inclusive_area`](geo::GridRect::inclusive_area).
+pub fn rect_area_inclusive(p: Point2d, q: Point2d) -> u64 {
+    geo::GridRect::from_corners(p, q).inclusive_area()
+}
+
+/// Andrew's monotone chain convex hull algorithm.
+/// Returns the convex hull points in counter-clockwise order.
+/// Time complexity: O(n log n)
+pub fn convex_hull(points: &[Point2d]) -> Vec<Point2d> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    // Sort points lexicographically (first by x, then by y)
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
+
+    // Remove duplicates
+    sorted.dedup();
+
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    // Cross product to determine turn direction
+    // Positive = counter-clockwise, Negative = clockwise, Zero = collinear
+    let cross = |o: &Point2d, a: &Point2d, b: &Point2d| -> i64 {
+        a.dx_i64(*o) * b.dy_i64(*o) - a.dy_i64(*o) * b.dx_i64(*o)
+    };
+
+    // Build lower hull
+    let mut lower = Vec::new();
+    for p in &sorted {
+        while lower.len() >= 2 && cross(&lower[lower.len() - 2], &lower[lower.len() - 1], p) <= 0 {
+            lower.pop();
+        }
+        lower.push(*p);
+    }
+
+    // Build upper hull
+    let mut upper = Vec::new();
+    for p in sorted.iter().rev() {
+        while upper.len() >= 2 && cross(&upper[upper.len() - 2], &upper[upper.len() - 1], p) <= 0 {
+            upper.pop();
+        }
+        upper.push(*p);
+    }
+
+    // Remove last point of each half because it's repeated
+    lower.pop();
+    upper.pop();
+
+    // Concatenate lower and upper hull
+    lower.extend(upper);
+    lower
+}
+
+/// Adds what `input` looks like to a parse error from a parser expecting
+/// `expected`, when it is recognizably something else.
+fn note_shape(err: Error, expected: meta::InputShape, input: &str) -> Error {
+    match (err, meta::shape_mismatch(expected, input)) {
+        (
+            Error::Parse {
+                line,
+                column,
+                width,
+                message,
+            },
+            Some(note),
+        ) => Error::Parse {
+            line,
+            column,
+            width,
+            message: format!("{}; {}", message, note),
+        },
+        (err, _) => err,
+    }
+}
+
+/// Parses one `x,y` point per line.
+pub fn parse_points2d(input: &str) -> Result<Vec<Point2d>> {
     let mut res = Vec::new();
-    for (idx, line) in reader.lines().enumerate() {
-        let line = line?;
+    for (idx, line) in input.lines().enumerate() {
         let parts = line
             .split(',')
             .map(|part| part.trim())
             .filter(|part| !part.is_empty())
             .collect::<Vec<&str>>();
         if parts.len() != 2 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
+            let err = Error::parse(
+                idx + 1,
                 format!(
-                    "Invalid point at line {}: expected 2 values separated by commas, got {} ({})",
-                    idx + 1,
+                    "Invalid point: expected 2 values separated by commas, got {} ({})",
                     parts.len(),
                     line
                 ),
-            ));
+            );
+            return Err(note_shape(err, meta::InputShape::Points2d, input));
         }
         let parse_coord = |value: &str, label: &str| {
             value.parse::<i32>().map_err(|err| {
-                std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
+                Error::parse(
+                    idx + 1,
                     format!(
-                        "Invalid point at line {}: {} value '{}' is not an i32 ({})",
-                        idx + 1,
-                        label,
-                        value,
-                        err
+                        "Invalid point: {} value '{}' is not an i32 ({})",
+                        label, value, err
                     ),
                 )
             })
@@ -97,44 +376,46 @@ pub fn read_points2d(day: &str) -> std::io::Result<Vec<Point2d>> {
     Ok(res)
 }
 
+#[cfg(feature = "native")]
+pub fn read_points2d(day: &str) -> Result<Vec<Point2d>> {
+    parse_points2d(&read_file_as_string(day)?)
+}
+
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: i32,
     pub y: i32,
     pub z: i32,
 }
 
-pub fn read_points(day: &str) -> std::io::Result<Vec<Point>> {
-    let reader = BufReader::new(File::open(get_input_path(day))?);
+/// Parses one `x,y,z` point per line.
+pub fn parse_points(input: &str) -> Result<Vec<Point>> {
     let mut res = Vec::new();
-    for (idx, line) in reader.lines().enumerate() {
-        let line = line?;
+    for (idx, line) in input.lines().enumerate() {
         let parts = line
             .split(',')
             .map(|part| part.trim())
             .filter(|part| !part.is_empty())
             .collect::<Vec<&str>>();
         if parts.len() != 3 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
+            let err = Error::parse(
+                idx + 1,
                 format!(
-                    "Invalid point at line {}: expected 3 values separated by commas, got {} ({})",
-                    idx + 1,
+                    "Invalid point: expected 3 values separated by commas, got {} ({})",
                     parts.len(),
                     line
                 ),
-            ));
+            );
+            return Err(note_shape(err, meta::InputShape::Points3d, input));
         }
         let parse_coord = |value: &str, label: &str| {
             value.parse::<i32>().map_err(|err| {
-                std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
+                Error::parse(
+                    idx + 1,
                     format!(
-                        "Invalid point at line {}: {} value '{}' is not an i32 ({})",
-                        idx + 1,
-                        label,
-                        value,
-                        err
+                        "Invalid point: {} value '{}' is not an i32 ({})",
+                        label, value, err
                     ),
                 )
             })
@@ -148,95 +429,275 @@ pub fn read_points(day: &str) -> std::io::Result<Vec<Point>> {
     Ok(res)
 }
 
-pub fn read_numbers_with_whitespace(day: &str) -> std::io::Result<Vec<u64>> {
-    Ok(read_file_as_string(day)?
-        .split_whitespace()
-        .map(|s| s.parse::<u64>().expect("Value is not an u64"))
-        .collect())
+#[cfg(feature = "native")]
+pub fn read_points(day: &str) -> Result<Vec<Point>> {
+    parse_points(&read_file_as_string(day)?)
 }
 
-pub fn read_number_grid_with_whitespace(day: &str) -> std::io::Result<Vec<Vec<i32>>> {
-    BufReader::new(File::open(get_input_path(day))?)
-        .lines()
-        .map(|line| {
-            Ok(line?
-                .split_whitespace()
-                .map(|s| s.parse::<i32>().expect("Value is not an i32"))
-                .collect::<Vec<i32>>())
-        })
-        .collect()
+/// A point with `D` coordinates, for puzzles whose rules work the same in
+/// any number of dimensions, such as Conway cubes in three and four.
+/// [`Point2d`] and [`Point`] convert to and from `PointN<2>` and
+/// `PointN<3>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PointN<const D: usize>(pub [i32; D]);
+
+/// A point in four dimensions.
+pub type Point4d = PointN<4>;
+
+impl<const D: usize> PointN<D> {
+    pub const ORIGIN: PointN<D> = PointN([0; D]);
+
+    /// The `3^D - 1` points differing from this one by at most one in each
+    /// coordinate, diagonals included.
+    pub fn neighbours(self) -> impl Iterator<Item = PointN<D>> {
+        let count = 3usize.pow(D as u32);
+        (0..count)
+            .filter(move |&offset| offset != count / 2)
+            .map(move |mut offset| {
+                let mut next = self;
+                for coord in &mut next.0 {
+                    *coord += (offset % 3) as i32 - 1;
+                    offset /= 3;
+                }
+                next
+            })
+    }
+
+    /// The sum of the coordinate differences, widened so far-apart points
+    /// cannot overflow.
+    pub fn manhattan(self, other: PointN<D>) -> u64 {
+        self.0
+            .iter()
+            .zip(other.0)
+            .map(|(&a, b)| (i64::from(a) - i64::from(b)).unsigned_abs())
+            .sum()
+    }
 }
 
-pub fn read_ascii_grid(day: &str) -> std::io::Result<Vec<Vec<u8>>> {
-    BufReader::new(File::open(get_input_path(day))?)
-        .lines()
-        .map(|line| Ok(line?.as_bytes().to_vec()))
-        .collect()
+impl<const D: usize> std::ops::Add for PointN<D> {
+    type Output = PointN<D>;
+
+    fn add(mut self, other: PointN<D>) -> PointN<D> {
+        for (coord, delta) in self.0.iter_mut().zip(other.0) {
+            *coord += delta;
+        }
+        self
+    }
+}
+
+impl<const D: usize> std::ops::Sub for PointN<D> {
+    type Output = PointN<D>;
+
+    fn sub(mut self, other: PointN<D>) -> PointN<D> {
+        for (coord, delta) in self.0.iter_mut().zip(other.0) {
+            *coord -= delta;
+        }
+        self
+    }
+}
+
+impl From<Point2d> for PointN<2> {
+    fn from(p: Point2d) -> Self {
+        PointN([p.x, p.y])
+    }
+}
+
+impl From<PointN<2>> for Point2d {
+    fn from(PointN([x, y]): PointN<2>) -> Self {
+        Point2d { x, y }
+    }
+}
+
+impl From<Point> for PointN<3> {
+    fn from(p: Point) -> Self {
+        PointN([p.x, p.y, p.z])
+    }
+}
+
+impl From<PointN<3>> for Point {
+    fn from(PointN([x, y, z]): PointN<3>) -> Self {
+        Point { x, y, z }
+    }
+}
+
+pub fn parse_numbers_with_whitespace(input: &str) -> Result<Vec<u64>> {
+    let mut numbers = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        for token in line.split_whitespace() {
+            numbers.push(parse_token(idx + 1, line, token)?);
+        }
+    }
+    Ok(numbers)
+}
+
+#[cfg(feature = "native")]
+pub fn read_numbers_with_whitespace(day: &str) -> Result<Vec<u64>> {
+    parse_numbers_with_whitespace(&read_file_as_string(day)?)
 }
 
-pub fn parse_to_number_grid(input: &str) -> Vec<Vec<u8>> {
+pub fn parse_number_grid_with_whitespace(input: &str) -> Result<Vec<Vec<i32>>> {
     input
         .lines()
-        .map(|line| {
-            line.trim()
-                .chars()
-                .filter_map(|c| c.to_digit(10).map(|d| d as u8))
+        .enumerate()
+        .map(|(idx, line)| {
+            line.split_whitespace()
+                .map(|token| parse_token(idx + 1, line, token))
                 .collect()
         })
-        .filter(|line: &Vec<u8>| !line.is_empty())
         .collect()
 }
 
-pub fn read_number_grid(day: &str) -> std::io::Result<Vec<Vec<u8>>> {
-    Ok(parse_to_number_grid(&read_file_as_string(day)?))
+#[cfg(feature = "native")]
+pub fn read_number_grid_with_whitespace(day: &str) -> Result<Vec<Vec<i32>>> {
+    parse_number_grid_with_whitespace(&read_file_as_string(day)?)
+}
+
+/// The bytes of each line, with no checks on what they are.
+pub fn parse_ascii_grid(input: &str) -> Vec<Vec<u8>> {
+    input.lines().map(|line| line.as_bytes().to_vec()).collect()
+}
+
+#[cfg(feature = "native")]
+pub fn read_ascii_grid(day: &str) -> Result<Vec<Vec<u8>>> {
+    Ok(parse_ascii_grid(&read_file_as_string(day)?))
+}
+
+/// How a grid reader treats input it does not expect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Anything but the expected cells is an error, except whitespace around
+    /// a row and blank lines at the end.
+    #[default]
+    Strict,
+    /// Unexpected characters and blank lines are skipped.
+    Lenient,
+}
+
+/// Parses rows of single digits such as `8119`.
+pub fn parse_to_number_grid(input: &str, mode: ParseMode) -> Result<Vec<Vec<u8>>> {
+    if mode == ParseMode::Lenient {
+        return Ok(input
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .chars()
+                    .filter_map(|c| c.to_digit(10).map(|d| d as u8))
+                    .collect()
+            })
+            .filter(|line: &Vec<u8>| !line.is_empty())
+            .collect());
+    }
+
+    let mut grid = Vec::new();
+    let mut blank_line = None;
+    for (idx, line) in input.lines().enumerate() {
+        let row = line.trim();
+        if row.is_empty() {
+            blank_line.get_or_insert(idx + 1);
+            continue;
+        }
+        if let Some(blank) = blank_line {
+            return Err(Error::parse(blank, "blank line inside digit grid"));
+        }
+        let start = line.len() - line.trim_start().len();
+        let digits = row
+            .char_indices()
+            .map(|(offset, ch)| match ch.to_digit(10) {
+                Some(digit) => Ok(digit as u8),
+                None => Err(Error::parse_span(
+                    idx + 1,
+                    start + offset + 1,
+                    ch.len_utf8(),
+                    format!("expected a digit, found '{}'", ch),
+                )),
+            })
+            .collect::<Result<_>>()
+            .map_err(|err| note_shape(err, meta::InputShape::DigitGrid, input))?;
+        grid.push(digits);
+    }
+    Ok(grid)
+}
+
+#[cfg(feature = "native")]
+pub fn read_number_grid(day: &str, mode: ParseMode) -> Result<Vec<Vec<u8>>> {
+    parse_to_number_grid(&read_file_as_string(day)?, mode)
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RangeData {
     pub ranges: Vec<(isize, isize)>,
     pub values: Vec<isize>,
 }
 
-fn parse_range_data(input: &str) -> Result<RangeData, String> {
+/// Parses a block of inclusive ranges such as `3-5`, a blank line, then a
+/// block of values.
+pub fn parse_range_data(input: &str) -> Result<RangeData> {
+    // 1-based line number of a slice of `input`
+    let line_of = |part: &str| {
+        let offset = part.as_ptr() as usize - input.as_ptr() as usize;
+        input[..offset].matches('\n').count() + 1
+    };
+    // An error underlining a slice of `input`
+    let error_at = |token: &str, message: String| {
+        let offset = token.as_ptr() as usize - input.as_ptr() as usize;
+        let line_start = input[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        Error::parse_span(
+            line_of(token),
+            offset - line_start + 1,
+            token.len(),
+            message,
+        )
+    };
+
     let parts: Vec<&str> = input
         .split("\n\n")
         .filter(|s| !s.trim().is_empty())
         .collect();
     if parts.len() != 2 {
-        return Err("Input must have two sections separated by empty lines".to_string());
+        return Err(Error::parse(
+            parts.get(2).map_or(1, |part| line_of(part)),
+            "Input must have two sections separated by empty lines",
+        ));
     }
 
     let ranges_str = parts[0].trim();
     let values_str = parts[1].trim();
 
-    let ranges: Result<Vec<(isize, isize)>, String> = ranges_str
+    let ranges: Result<Vec<(isize, isize)>> = ranges_str
         .lines()
         .map(|line| {
             let mut split = line.split('-');
-            let start: isize = split
-                .next()
-                .ok_or_else(|| "Missing start of range".to_string())?
+            let (Some(start), Some(end)) = (split.next(), split.next()) else {
+                return Err(error_at(line, "Missing end of range".to_string()));
+            };
+            let start_value: isize = start
                 .parse()
-                .map_err(|_| format!("Invalid range start in {}", line))?;
-            let end: isize = split
-                .next()
-                .ok_or_else(|| "Missing end of range".to_string())?
+                .map_err(|_| error_at(start, format!("Invalid range start '{}'", start)))?;
+            let end_value: isize = end
                 .parse()
-                .map_err(|_| format!("Invalid range end in {}", line))?;
-            if start > end {
-                return Err(format!("Invalid range: start > end ({}- {})", start, end));
+                .map_err(|_| error_at(end, format!("Invalid range end '{}'", end)))?;
+            if start_value > end_value {
+                return Err(error_at(
+                    line,
+                    format!(
+                        "Invalid range: start > end ({}- {})",
+                        start_value, end_value
+                    ),
+                ));
             }
-            Ok((start, end))
+            Ok((start_value, end_value))
         })
         .collect();
     let ranges = ranges?;
 
-    let values: Result<Vec<isize>, String> = values_str
+    let values: Result<Vec<isize>> = values_str
         .lines()
         .map(|line| {
-            line.trim()
+            let value = line.trim();
+            value
                 .parse()
-                .map_err(|_| format!("Invalid value {}", line))
+                .map_err(|_| error_at(value, format!("Invalid value '{}'", value)))
         })
         .collect();
     let values = values?;
@@ -244,38 +705,1431 @@ fn parse_range_data(input: &str) -> Result<RangeData, String> {
     Ok(RangeData { ranges, values })
 }
 
-pub fn read_range_data(day: &str) -> std::io::Result<RangeData> {
-    let content = read_file_as_string(day)?;
-    parse_range_data(&content).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+#[cfg(feature = "native")]
+pub fn read_range_data(day: &str) -> Result<RangeData> {
+    parse_range_data(&read_file_as_string(day)?)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A fixed-capacity set of small integers, one bit per value.  Used as a
+/// dense visited set where a `HashSet` would hash and allocate per insert.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixedBitSet {
+    words: Vec<u64>,
+    len: usize,
+}
 
-    #[test]
-    fn test_parse_range_data() {
-        let input = "1-4\n7-11\n\n2\n9";
-        let expected = RangeData {
-            ranges: vec![(1, 4), (7, 11)],
-            values: vec![2, 9],
-        };
-        assert_eq!(parse_range_data(input).unwrap(), expected);
+impl FixedBitSet {
+    /// Creates an empty set that can hold `0..len`.
+    pub fn new(len: usize) -> Self {
+        FixedBitSet {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
     }
 
-    #[test]
-    fn test_parse_range_data_with_extra_newline() {
-        let input = "1-4\n7-11\n\n\n2\n9";
-        let expected = RangeData {
-            ranges: vec![(1, 4), (7, 11)],
-            values: vec![2, 9],
-        };
-        assert_eq!(parse_range_data(input).unwrap(), expected);
+    /// The capacity of the set, not the number of members.
+    pub fn len(&self) -> usize {
+        self.len
     }
 
-    #[test]
-    fn test_parse_invalid_range() {
-        let input = "5-4\n\n1";
-        assert!(parse_range_data(input).is_err());
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Empties the set and resizes it to hold `0..len`, keeping the existing
+    /// allocation when it is large enough.
+    pub fn reset(&mut self, len: usize) {
+        self.words.clear();
+        self.words.resize(len.div_ceil(64), 0);
+        self.len = len;
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.len && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    /// Adds `value`, returning whether it was newly inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not below the capacity.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(
+            value < self.len,
+            "value {} out of range for FixedBitSet of length {}",
+            value,
+            self.len
+        );
+        let word = &mut self.words[value / 64];
+        let bit = 1 << (value % 64);
+        let inserted = *word & bit == 0;
+        *word |= bit;
+        inserted
+    }
+
+    /// The number of members.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+/// A row of `width` bits in 64-bit words, for masks over more columns than
+/// one `u64` holds.  Shifts and bitwise operators act on the whole row as
+/// if it were one integer, bit `i` being column `i`; bits shifted past
+/// either end are dropped.
+///
+/// # Panics
+///
+/// The binary operators panic if the two masks differ in width.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WideMask {
+    words: Box<[u64]>,
+    width: usize,
+}
+
+impl WideMask {
+    /// A mask of `width` bits, all clear.
+    pub fn new(width: usize) -> Self {
+        WideMask {
+            words: vec![0; width.div_ceil(64)].into_boxed_slice(),
+            width,
+        }
+    }
+
+    /// A mask of `width` bits with only `bit` set.
+    pub fn single(width: usize, bit: usize) -> Self {
+        let mut mask = Self::new(width);
+        mask.insert(bit);
+        mask
+    }
+
+    /// A mask of `width` bits with each of `ones` set.
+    pub fn from_ones(width: usize, ones: impl IntoIterator<Item = usize>) -> Self {
+        let mut mask = Self::new(width);
+        for bit in ones {
+            mask.insert(bit);
+        }
+        mask
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `bit` is not below the width.
+    pub fn insert(&mut self, bit: usize) {
+        assert!(
+            bit < self.width,
+            "bit {} out of range for WideMask of width {}",
+            bit,
+            self.width
+        );
+        self.words[bit / 64] |= 1 << (bit % 64);
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        bit < self.width && self.words[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// The set bits in increasing order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    idx * 64 + bit
+                })
+            })
+        })
+    }
+
+    /// Clears the bits of the last word past the width.
+    fn trim(mut self) -> Self {
+        if let Some(last) = self.words.last_mut()
+            && !self.width.is_multiple_of(64)
+        {
+            *last &= (1 << (self.width % 64)) - 1;
+        }
+        self
+    }
+
+    fn zip_with(&self, other: &WideMask, op: impl Fn(u64, u64) -> u64) -> WideMask {
+        assert_eq!(self.width, other.width, "WideMask widths differ");
+        let words = self
+            .words
+            .iter()
+            .zip(&other.words)
+            .map(|(&a, &b)| op(a, b))
+            .collect();
+        WideMask {
+            words,
+            width: self.width,
+        }
+    }
+}
+
+impl std::ops::BitAnd for &WideMask {
+    type Output = WideMask;
+
+    fn bitand(self, other: &WideMask) -> WideMask {
+        self.zip_with(other, |a, b| a & b)
+    }
+}
+
+impl std::ops::BitOr for &WideMask {
+    type Output = WideMask;
+
+    fn bitor(self, other: &WideMask) -> WideMask {
+        self.zip_with(other, |a, b| a | b)
+    }
+}
+
+impl std::ops::BitXor for &WideMask {
+    type Output = WideMask;
+
+    fn bitxor(self, other: &WideMask) -> WideMask {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+}
+
+impl std::ops::BitOrAssign<&WideMask> for WideMask {
+    fn bitor_assign(&mut self, other: &WideMask) {
+        assert_eq!(self.width, other.width, "WideMask widths differ");
+        for (word, &other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+}
+
+/// Moves every bit `shift` columns up, towards the high end.
+impl std::ops::Shl<usize> for &WideMask {
+    type Output = WideMask;
+
+    fn shl(self, shift: usize) -> WideMask {
+        let (skip, bits) = (shift / 64, shift % 64);
+        let mut shifted = WideMask::new(self.width);
+        for idx in skip..self.words.len() {
+            let from = idx - skip;
+            shifted.words[idx] = self.words[from] << bits;
+            if bits > 0 && from > 0 {
+                shifted.words[idx] |= self.words[from - 1] >> (64 - bits);
+            }
+        }
+        shifted.trim()
+    }
+}
+
+/// Moves every bit `shift` columns down, towards column zero.
+impl std::ops::Shr<usize> for &WideMask {
+    type Output = WideMask;
+
+    fn shr(self, shift: usize) -> WideMask {
+        let (skip, bits) = (shift / 64, shift % 64);
+        let mut shifted = WideMask::new(self.width);
+        for idx in 0..self.words.len().saturating_sub(skip) {
+            let from = idx + skip;
+            shifted.words[idx] = self.words[from] >> bits;
+            if bits > 0 && from + 1 < self.words.len() {
+                shifted.words[idx] |= self.words[from + 1] << (64 - bits);
+            }
+        }
+        shifted
+    }
+}
+
+/// The hash function used by rustc (FxHash): a multiply and rotate per word.
+/// Much faster than the default SipHash on the small integer and string keys
+/// puzzle states use, but with no resistance to adversarial keys.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const FX_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0u8; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// A `HashMap` using [`FxHasher`], for memo tables and visited sets.  Build
+/// one with `FastMap::default()`.
+pub type FastMap<K, V> = HashMap<K, V, BuildHasherDefault<FxHasher>>;
+
+/// A `HashSet` using [`FxHasher`].  Build one with `FastSet::default()`.
+pub type FastSet<T> = HashSet<T, BuildHasherDefault<FxHasher>>;
+
+/// A min-heap of keys by priority that can lower a queued key's priority in
+/// place.  Dijkstra's algorithm with a plain `BinaryHeap` pushes a second
+/// entry whenever it finds a shorter distance and skips the stale ones as
+/// they surface; this keeps one entry per key instead, at the cost of a map
+/// from each key to its place in the heap.
+///
+/// That map is consulted on every swap, so on grids, where each cell is
+/// improved only a few times, lazy deletion stays two to three times
+/// faster (see `benches/heap_benchmark.rs`).  The indexed heap pays off
+/// when keys are improved many times each, or when the queue's size must
+/// stay bounded by the number of keys.
+#[derive(Debug, Clone)]
+pub struct IndexedBinaryHeap<K, P> {
+    entries: Vec<(K, P)>,
+    positions: FastMap<K, usize>,
+}
+
+impl<K, P> Default for IndexedBinaryHeap<K, P> {
+    fn default() -> Self {
+        IndexedBinaryHeap {
+            entries: Vec::new(),
+            positions: FastMap::default(),
+        }
+    }
+}
+
+impl<K: Eq + std::hash::Hash + Clone, P: Ord> IndexedBinaryHeap<K, P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// The priority `key` is queued with, if it is queued.
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|&idx| &self.entries[idx].1)
+    }
+
+    /// Queues `key`, or moves it to `priority` if it is already queued,
+    /// returning the priority it had.
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        match self.positions.get(&key) {
+            Some(&idx) => {
+                let old = std::mem::replace(&mut self.entries[idx].1, priority);
+                if self.entries[idx].1 < old {
+                    self.sift_up(idx);
+                } else {
+                    self.sift_down(idx);
+                }
+                Some(old)
+            }
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, priority));
+                self.sift_up(self.entries.len() - 1);
+                None
+            }
+        }
+    }
+
+    /// Queues `key` at `priority` unless it is already queued at that
+    /// priority or lower: the relaxation step of Dijkstra's algorithm.
+    /// Returns whether the queue changed.
+    pub fn decrease(&mut self, key: K, priority: P) -> bool {
+        match self.positions.get(&key) {
+            Some(&idx) if self.entries[idx].1 <= priority => false,
+            Some(&idx) => {
+                self.entries[idx].1 = priority;
+                self.sift_up(idx);
+                true
+            }
+            None => {
+                self.push(key, priority);
+                true
+            }
+        }
+    }
+
+    /// The key with the lowest priority, without removing it.
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.entries.first().map(|(key, priority)| (key, priority))
+    }
+
+    /// Removes and returns the key with the lowest priority.  Ties come out
+    /// in no particular order.
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let last = self.entries.len() - 1;
+        self.swap(0, last);
+        let (key, priority) = self.entries.pop()?;
+        self.positions.remove(&key);
+        if !self.entries.is_empty() {
+            self.sift_down(0);
+        }
+        Some((key, priority))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.entries.swap(a, b);
+        *self.positions.get_mut(&self.entries[a].0).unwrap() = a;
+        *self.positions.get_mut(&self.entries[b].0).unwrap() = b;
+    }
+
+    fn sift_up(&mut self, mut idx: usize) {
+        while idx > 0 {
+            let parent = (idx - 1) / 2;
+            if self.entries[idx].1 >= self.entries[parent].1 {
+                break;
+            }
+            self.swap(idx, parent);
+            idx = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut idx: usize) {
+        loop {
+            let mut smallest = idx;
+            for child in [2 * idx + 1, 2 * idx + 2] {
+                if child < self.entries.len() && self.entries[child].1 < self.entries[smallest].1 {
+                    smallest = child;
+                }
+            }
+            if smallest == idx {
+                break;
+            }
+            self.swap(idx, smallest);
+            idx = smallest;
+        }
+    }
+}
+
+/// A multiset: how many times each item has been seen.  Items whose count
+/// drops to zero are removed, so two counters are equal when every item has
+/// the same count in both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + std::hash::Hash> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Eq + std::hash::Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Eq + std::hash::Hash> Counter<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a counter from `(item, count)` pairs, such as a list of counts
+    /// indexed by item with `.enumerate()`.
+    pub fn from_counts(counts: impl IntoIterator<Item = (T, usize)>) -> Self {
+        let mut counter = Self::new();
+        for (item, count) in counts {
+            counter.add_n(item, count);
+        }
+        counter
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, count: usize) {
+        if count > 0 {
+            *self.counts.entry(item).or_default() += count;
+        }
+    }
+
+    /// Removes up to `count` copies of `item`, returning how many there were
+    /// to remove.
+    pub fn remove_n(&mut self, item: &T, count: usize) -> usize {
+        let Some(have) = self.counts.get_mut(item) else {
+            return 0;
+        };
+        let removed = count.min(*have);
+        *have -= removed;
+        if *have == 0 {
+            self.counts.remove(item);
+        }
+        removed
+    }
+
+    /// How many times `item` has been added, zero if never.
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// The number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The number of items, counting repeats.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Each distinct item with its count, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// Adds every count in `other` to this counter.
+    pub fn merge(&mut self, other: &Counter<T>)
+    where
+        T: Clone,
+    {
+        for (item, count) in other.iter() {
+            self.add_n(item.clone(), count);
+        }
+    }
+
+    /// Whether this counter has at least as many of every item as `other`,
+    /// as when checking an inventory against a recipe.
+    pub fn covers(&self, other: &Counter<T>) -> bool {
+        other.iter().all(|(item, count)| self.get(item) >= count)
+    }
+
+    /// The `n` most frequent items, most frequent first; ties are broken
+    /// by the smaller item so the order is the same from run to run.
+    pub fn most_common(&self, n: usize) -> Vec<(&T, usize)>
+    where
+        T: Ord,
+    {
+        let mut items: Vec<(&T, usize)> = self.iter().collect();
+        items.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        items.truncate(n);
+        items
+    }
+}
+
+impl<T: Eq + std::hash::Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + std::hash::Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Eq + std::hash::Hash> IntoIterator for Counter<T> {
+    type Item = (T, usize);
+    type IntoIter = std::collections::hash_map::IntoIter<T, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<T: Eq + std::hash::Hash> std::ops::AddAssign for Counter<T> {
+    fn add_assign(&mut self, other: Self) {
+        for (item, count) in other {
+            self.add_n(item, count);
+        }
+    }
+}
+
+impl<T: Eq + std::hash::Hash> std::ops::Add for Counter<T> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+/// Removes `other`'s items, stopping each count at zero.
+impl<T: Eq + std::hash::Hash> std::ops::SubAssign for Counter<T> {
+    fn sub_assign(&mut self, other: Self) {
+        for (item, count) in other {
+            self.remove_n(&item, count);
+        }
+    }
+}
+
+impl<T: Eq + std::hash::Hash> std::ops::Sub for Counter<T> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+/// What a solver concluded about its input: the answer, a finding that
+/// there is none, or an answer too large for a `u64`.  Errors are left for
+/// input the solver cannot handle, so a runner can tell those apart from
+/// puzzles that legitimately have no solution.
+///
+/// With the `serde` feature an outcome is `{"answer": 42}`, `"unsolvable"`
+/// or `"overflow"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Outcome {
+    Answer(u64),
+    Unsolvable,
+    Overflow,
+}
+
+impl Outcome {
+    pub fn answer(self) -> Option<u64> {
+        match self {
+            Outcome::Answer(answer) => Some(answer),
+            _ => None,
+        }
+    }
+}
+
+impl From<Option<u64>> for Outcome {
+    fn from(answer: Option<u64>) -> Self {
+        answer.map_or(Outcome::Unsolvable, Outcome::Answer)
+    }
+}
+
+/// Adds answers, for totals over many lines.  One unsolvable line makes the
+/// total unsolvable, even if the sum has already overflowed.
+impl std::ops::Add for Outcome {
+    type Output = Outcome;
+
+    fn add(self, other: Outcome) -> Outcome {
+        match (self, other) {
+            (Outcome::Answer(a), Outcome::Answer(b)) => {
+                a.checked_add(b).map_or(Outcome::Overflow, Outcome::Answer)
+            }
+            (Outcome::Unsolvable, _) | (_, Outcome::Unsolvable) => Outcome::Unsolvable,
+            _ => Outcome::Overflow,
+        }
+    }
+}
+
+impl std::iter::Sum for Outcome {
+    fn sum<I: Iterator<Item = Outcome>>(iter: I) -> Self {
+        iter.fold(Outcome::Answer(0), |total, outcome| total + outcome)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Answer(answer) => write!(f, "{}", answer),
+            Outcome::Unsolvable => write!(f, "no solution"),
+            Outcome::Overflow => write!(f, "answer overflows u64"),
+        }
+    }
+}
+
+/// A part's answer, whatever type the solver computed it in, so every part
+/// can return the same type.  Displays as the bare value.
+///
+/// Non-negative signed values are stored as `UInt`, so an answer compares
+/// equal however it was computed or parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Answer {
+    Int(i128),
+    UInt(u128),
+    Text(String),
+}
+
+macro_rules! answer_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::UInt(value as u128)
+            }
+        }
+    )*};
+}
+
+macro_rules! answer_from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                match u128::try_from(value) {
+                    Ok(value) => Answer::UInt(value),
+                    Err(_) => Answer::Int(value as i128),
+                }
+            }
+        }
+    )*};
+}
+
+answer_from_unsigned!(u8, u16, u32, u64, u128, usize);
+answer_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+/// A part with no answer becomes the text it prints as.
+impl From<Outcome> for Answer {
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Answer(answer) => answer.into(),
+            _ => Answer::Text(outcome.to_string()),
+        }
+    }
+}
+
+/// Reads an answer as printed: a number if it is one, otherwise text.
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match (text.parse::<u128>(), text.parse::<i128>()) {
+            (Ok(value), _) => Answer::UInt(value),
+            (_, Ok(value)) => Answer::Int(value),
+            _ => Answer::Text(text.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::UInt(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// Integers whose addition and multiplication can be checked for overflow.
+pub trait CheckedInt: Copy {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_int {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedInt for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_int!(i32, i64, i128, u8, u32, u64, u128, usize);
+
+/// An accumulator that remembers whether any step overflowed, so a long
+/// running total can use `+=` and be checked once at the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checked<T>(Option<T>);
+
+impl<T: CheckedInt> Checked<T> {
+    pub fn new(value: T) -> Self {
+        Checked(Some(value))
+    }
+
+    /// The total, or `None` if any step overflowed.
+    pub fn get(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T: CheckedInt> Default for Checked<T> {
+    fn default() -> Self {
+        Checked::new(T::ZERO)
+    }
+}
+
+impl<T: CheckedInt> std::ops::AddAssign<T> for Checked<T> {
+    fn add_assign(&mut self, other: T) {
+        self.0 = self.0.and_then(|value| value.checked_add(other));
+    }
+}
+
+/// Adds another checked total, overflowing if either already has.
+impl<T: CheckedInt> std::ops::AddAssign for Checked<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.0 = self.0.zip(other.0).and_then(|(a, b)| a.checked_add(b));
+    }
+}
+
+impl<T: CheckedInt> std::ops::MulAssign<T> for Checked<T> {
+    fn mul_assign(&mut self, other: T) {
+        self.0 = self.0.and_then(|value| value.checked_mul(other));
+    }
+}
+
+impl<T: CheckedInt> std::iter::Sum<T> for Checked<T> {
+    fn sum<I: Iterator<Item = T>>(iter: I) -> Self {
+        iter.fold(Checked::new(T::ZERO), |mut total, value| {
+            total += value;
+            total
+        })
+    }
+}
+
+impl<T: CheckedInt> std::iter::Sum for Checked<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Checked::new(T::ZERO), |mut total, value| {
+            total += value;
+            total
+        })
+    }
+}
+
+impl<T: CheckedInt> std::iter::Product<T> for Checked<T> {
+    fn product<I: Iterator<Item = T>>(iter: I) -> Self {
+        iter.fold(Checked::new(T::ONE), |mut total, value| {
+            total *= value;
+            total
+        })
+    }
+}
+
+/// Sums `values`, or `None` if the sum overflows.
+pub fn sum_checked<T: CheckedInt>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ZERO, T::checked_add)
+}
+
+/// Multiplies `values`, or `None` if the product overflows.
+pub fn product_checked<T: CheckedInt>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, T::checked_mul)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_example_input() {
+        let grid = example_input!("07/example1");
+        assert_eq!(grid.len(), 16);
+        assert_eq!(grid[0], ".......S.......");
+        // Blank lines are kept as empty strings
+        let shapes = example_input!("12/example1");
+        assert_eq!(shapes[4], "");
+        assert_eq!(shapes.last().unwrap(), "12x5: 1 0 1 0 3 2");
+    }
+
+    #[test]
+    fn test_parse_range_data() {
+        let input = "1-4\n7-11\n\n2\n9";
+        let expected = RangeData {
+            ranges: vec![(1, 4), (7, 11)],
+            values: vec![2, 9],
+        };
+        assert_eq!(parse_range_data(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_range_data_with_extra_newline() {
+        let input = "1-4\n7-11\n\n\n2\n9";
+        let expected = RangeData {
+            ranges: vec![(1, 4), (7, 11)],
+            values: vec![2, 9],
+        };
+        assert_eq!(parse_range_data(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_invalid_range() {
+        let input = "5-4\n\n1";
+        assert!(parse_range_data(input).is_err());
+    }
+
+    #[test]
+    fn test_parse_range_data_error_lines() {
+        let line = |input: &str| match parse_range_data(input) {
+            Err(Error::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(line("1-4\n7-x\n\n2"), 2);
+        assert_eq!(line("\n1-4\n\n\n2\n9\nz"), 7);
+        assert_eq!(line("1-4\n\n2\n\n3"), 5);
+        assert_eq!(line("1-4"), 1);
+        assert_eq!(
+            parse_range_data("1-4\n9-7\n\n1").unwrap_err().to_string(),
+            "line 2, column 1: Invalid range: start > end (9- 7)"
+        );
+
+        let input = "1-4\n15-\n\n2";
+        assert_eq!(
+            parse_range_data(input).unwrap_err().render(input),
+            "error: Invalid range end ''\n --> line 2, column 4\n  |\n2 | 15-\n  |    ^"
+        );
+        let input = "1-4\n\n2\n 1x";
+        assert_eq!(
+            parse_range_data(input).unwrap_err().render(input),
+            "error: Invalid value '1x'\n --> line 4, column 2\n  |\n4 |  1x\n  |  ^^"
+        );
+    }
+
+    #[test]
+    fn test_parse_int_pairs() {
+        assert_eq!(
+            parse_int_pairs("3   4\n-4 3\n").unwrap(),
+            (vec![3, -4], vec![4, 3])
+        );
+
+        let err = parse_int_pairs("3 4\n4 x3\n").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Parse {
+                line: 2,
+                column: Some(3),
+                ..
+            }
+        ));
+        assert!(
+            err.to_string().contains("'x3' is not a valid i32"),
+            "{}",
+            err
+        );
+        let err = parse_int_pairs("3 4\n\n5 6").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected two numbers, got ''");
+        assert!(parse_int_pairs("1 2\n3").is_err());
+    }
+
+    #[test]
+    fn test_parse_numbers_with_whitespace() {
+        assert_eq!(
+            parse_numbers_with_whitespace("1 2\n\n  3\t4\n").unwrap(),
+            vec![1, 2, 3, 4]
+        );
+        let err = parse_numbers_with_whitespace("1 2\n3 -4").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: '-4' is not a valid u64 (invalid digit found in string)"
+        );
+    }
+
+    #[test]
+    fn test_parse_to_number_grid() {
+        let input = "  123\n456 \n\n";
+        let expected = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(
+            parse_to_number_grid(input, ParseMode::Strict).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_to_number_grid(input, ParseMode::Lenient).unwrap(),
+            expected
+        );
+
+        let input = "123\n4x6\n\n789";
+        assert_eq!(
+            parse_to_number_grid(input, ParseMode::Lenient).unwrap(),
+            vec![vec![1, 2, 3], vec![4, 6], vec![7, 8, 9]]
+        );
+        let err = parse_to_number_grid(input, ParseMode::Strict).unwrap_err();
+        assert_eq!(
+            err.render(input),
+            "error: expected a digit, found 'x'\n --> line 2, column 2\n  |\n2 | 4x6\n  |  ^"
+        );
+        let err = parse_to_number_grid("12\n\n34", ParseMode::Strict).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: blank line inside digit grid");
+        let err = parse_to_number_grid(" 1 2", ParseMode::Strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 3: expected a digit, found ' '"
+        );
+
+        let err = parse_to_number_grid("1,2,3\n4,5,6\n", ParseMode::Strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 2: expected a digit, found ','; the input looks like one \
+             x,y,z point per line, not a grid of digits; is it the right day's?"
+        );
+        let err = parse_points2d("1,2,3\n").unwrap_err();
+        assert!(
+            err.to_string().contains("looks like one x,y,z point"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_number_grid_with_whitespace() {
+        assert_eq!(
+            parse_number_grid_with_whitespace("1 2 3\n\n-4 5\n").unwrap(),
+            vec![vec![1, 2, 3], vec![], vec![-4, 5]]
+        );
+        let err = parse_number_grid_with_whitespace("1 2\n3 99999999999").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Parse {
+                line: 2,
+                column: Some(3),
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("'99999999999'"), "{}", err);
+    }
+
+    #[test]
+    fn test_puzzle_input_lines() {
+        let input = PuzzleInput::from("a: b c\r\n\n12 -3  4\n");
+        let lines: Vec<&str> = input.lines().collect();
+        assert_eq!(lines, vec!["a: b c", "", "12 -3  4"]);
+        assert_eq!(input.text().len(), 18);
+
+        assert_eq!(split_trimmed(lines[0], ':'), Some(("a", "b c")));
+        assert_eq!(split_trimmed(lines[1], ':'), None);
+        assert_eq!(parse_fields::<i32>(lines[2]), Ok(vec![12, -3, 4]));
+        assert_eq!(parse_fields::<i32>(lines[1]), Ok(vec![]));
+        assert!(parse_fields::<u32>(lines[2]).is_err());
+    }
+
+    #[test]
+    fn test_fixed_bit_set() {
+        let mut set = FixedBitSet::new(130);
+        assert_eq!(set.len(), 130);
+        assert_eq!(set.count(), 0);
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(129));
+        assert!(!set.insert(64));
+        assert!(set.contains(129));
+        assert!(!set.contains(1));
+        assert!(!set.contains(130));
+        assert_eq!(set.count(), 3);
+
+        set.reset(10);
+        assert_eq!(set.len(), 10);
+        assert_eq!(set.count(), 0);
+        assert!(!set.contains(0));
+        assert!(FixedBitSet::new(0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_fixed_bit_set_insert_out_of_range() {
+        FixedBitSet::new(64).insert(64);
+    }
+
+    #[test]
+    fn test_wide_mask() {
+        let mut rng = generators::Lcg::new(1150);
+        for width in [1, 5, 63, 64, 65, 128, 130, 300, 500] {
+            let ones: BTreeSet<usize> = (0..width / 3)
+                .map(|_| rng.below(width as u64) as usize)
+                .collect();
+            let other: BTreeSet<usize> = (0..width / 2)
+                .map(|_| rng.below(width as u64) as usize)
+                .collect();
+            let mask = WideMask::from_ones(width, ones.iter().copied());
+            let other_mask = WideMask::from_ones(width, other.iter().copied());
+            assert_eq!(mask.width(), width);
+            assert_eq!(mask.count_ones(), ones.len());
+            assert_eq!(mask.iter_ones().collect::<BTreeSet<_>>(), ones);
+            assert!(!mask.contains(width));
+
+            let as_set = |mask: WideMask| mask.iter_ones().collect::<BTreeSet<_>>();
+            assert_eq!(as_set(&mask | &other_mask), &ones | &other);
+            assert_eq!(as_set(&mask & &other_mask), &ones & &other);
+            assert_eq!(as_set(&mask ^ &other_mask), &ones ^ &other);
+            for shift in [0, 1, 7, 63, 64, 65, 130, width] {
+                let up: BTreeSet<usize> = ones
+                    .iter()
+                    .map(|&b| b + shift)
+                    .filter(|&b| b < width)
+                    .collect();
+                let down: BTreeSet<usize> =
+                    ones.iter().filter_map(|&b| b.checked_sub(shift)).collect();
+                assert_eq!(as_set(&mask << shift), up, "{} << {}", width, shift);
+                assert_eq!(as_set(&mask >> shift), down, "{} >> {}", width, shift);
+            }
+
+            let mut union = mask.clone();
+            union |= &other_mask;
+            assert_eq!(union, &mask | &other_mask);
+        }
+        assert!(WideMask::new(200).is_empty());
+        assert!(!WideMask::single(200, 199).is_empty());
+        assert_eq!(WideMask::new(0).iter_ones().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "widths differ")]
+    fn test_wide_mask_width_mismatch() {
+        let _ = &WideMask::new(64) | &WideMask::new(65);
+    }
+
+    #[test]
+    fn test_fx_hasher() {
+        use std::hash::{BuildHasher, Hash};
+
+        let hash = |value: &dyn Fn(&mut FxHasher)| {
+            let mut hasher = FxHasher::default();
+            value(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&|h| 7u32.hash(h)), hash(&|h| 7u64.hash(h)));
+        assert_ne!(hash(&|h| 7u64.hash(h)), hash(&|h| 8u64.hash(h)));
+        // Tail bytes after the last full word still count
+        assert_ne!(
+            hash(&|h| h.write(b"abcdefghi")),
+            hash(&|h| h.write(b"abcdefghj"))
+        );
+        assert_ne!(hash(&|h| "ab".hash(h)), hash(&|h| "ba".hash(h)));
+
+        let build = BuildHasherDefault::<FxHasher>::default();
+        assert_eq!(build.hash_one("state"), build.hash_one("state"));
+    }
+
+    #[test]
+    fn test_fast_map_and_set() {
+        let mut map: FastMap<(String, u64), u64> = FastMap::default();
+        map.insert(("svr".to_string(), 3), 5);
+        *map.entry(("svr".to_string(), 3)).or_insert(0) += 1;
+        assert_eq!(map.get(&("svr".to_string(), 3)), Some(&6));
+
+        let set: FastSet<Vec<u32>> = (0..100).map(|i| vec![i, i * 2]).collect();
+        assert_eq!(set.len(), 100);
+        assert!(set.contains(&vec![7, 14]));
+    }
+
+    #[test]
+    fn test_point2d_wide_ops() {
+        let far = Point2d {
+            x: i32::MAX,
+            y: i32::MIN,
+        };
+        let near = Point2d { x: -1, y: 1 };
+        assert_eq!(far.dx_i64(near), i64::from(i32::MAX) + 1);
+        assert_eq!(near.dy_i64(far), 1 - i64::from(i32::MIN));
+        assert_eq!(near.dx_i64(near), 0);
+
+        assert_eq!(rect_area_inclusive(near, near), 1);
+        assert_eq!(
+            rect_area_inclusive(Point2d { x: 2, y: 5 }, Point2d { x: 11, y: 1 }),
+            50
+        );
+        assert_eq!(
+            rect_area_inclusive(far, near),
+            ((1 << 31) + 1) * ((1 << 31) + 2)
+        );
+        let corner = Point2d { x: i32::MIN, y: 0 };
+        assert_eq!(
+            rect_area_inclusive(corner, Point2d { x: i32::MAX, y: 0 }),
+            1 << 32
+        );
+    }
+
+    #[test]
+    fn test_point_n() {
+        let p = PointN([1, -2, 3]);
+        let around: FastSet<PointN<3>> = p.neighbours().collect();
+        assert_eq!(around.len(), 26);
+        assert!(!around.contains(&p));
+        assert!(
+            around
+                .iter()
+                .all(|&q| (q - p).0.iter().all(|d| d.abs() <= 1))
+        );
+        assert_eq!(Point4d::ORIGIN.neighbours().count(), 80);
+        assert_eq!(
+            PointN::<1>([5]).neighbours().collect::<Vec<_>>(),
+            [PointN([4]), PointN([6])]
+        );
+        assert_eq!(PointN::<0>([]).neighbours().count(), 0);
+
+        assert_eq!(p + PointN([1, 1, 1]), PointN([2, -1, 4]));
+        assert_eq!(p.manhattan(PointN::ORIGIN), 6);
+        assert_eq!(
+            PointN([i32::MIN]).manhattan(PointN([i32::MAX])),
+            u32::MAX as u64
+        );
+
+        let flat = Point2d { x: 4, y: -7 };
+        assert_eq!(Point2d::from(PointN::from(flat)), flat);
+        assert_eq!(PointN::from(Point { x: 1, y: -2, z: 3 }), p);
+        assert_eq!(Point::from(p), Point { x: 1, y: -2, z: 3 });
+    }
+
+    #[test]
+    fn test_indexed_binary_heap() {
+        let mut heap = IndexedBinaryHeap::new();
+        assert_eq!(heap.push("c", 5), None);
+        heap.push("a", 3);
+        heap.push("b", 9);
+        heap.push("d", 1);
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.peek(), Some((&"d", &1)));
+
+        assert!(heap.decrease("b", 2));
+        assert!(!heap.decrease("c", 7));
+        assert!(heap.decrease("e", 4));
+        assert_eq!(heap.priority(&"c"), Some(&5));
+        // push moves a key either way
+        assert_eq!(heap.push("d", 6), Some(1));
+
+        let order: Vec<(&str, i32)> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(order, [("b", 2), ("a", 3), ("e", 4), ("c", 5), ("d", 6)]);
+        assert!(heap.is_empty());
+        assert!(!heap.contains(&"a"));
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn test_indexed_binary_heap_matches_sort() {
+        let mut rng = generators::Lcg::new(3);
+        let mut heap = IndexedBinaryHeap::new();
+        let mut expected = HashMap::new();
+        for _ in 0..500 {
+            let key = rng.below(100);
+            let priority = rng.below(1000);
+            if rng.below(2) == 0 {
+                heap.push(key, priority);
+                expected.insert(key, priority);
+            } else if heap.decrease(key, priority) {
+                expected.insert(key, priority);
+            }
+        }
+        let mut expected: Vec<(u64, u64)> = expected.into_iter().collect();
+        expected.sort_by_key(|&(key, priority)| (priority, key));
+        let mut popped: Vec<(u64, u64)> = std::iter::from_fn(|| heap.pop()).collect();
+        assert!(popped.is_sorted_by_key(|&(_, priority)| priority));
+        popped.sort_by_key(|&(key, priority)| (priority, key));
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    fn test_counter() {
+        let mut letters: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(letters.get(&'a'), 5);
+        assert_eq!(letters.get(&'z'), 0);
+        assert_eq!(letters.len(), 5);
+        assert_eq!(letters.total(), 11);
+        assert_eq!(letters.most_common(3), [(&'a', 5), (&'b', 2), (&'r', 2)]);
+        assert_eq!(letters.most_common(10).len(), 5);
+
+        assert_eq!(letters.remove_n(&'b', 5), 2);
+        assert_eq!(letters.get(&'b'), 0);
+        assert_eq!(letters.len(), 4);
+        letters.add('b');
+        letters.merge(&"bz".chars().collect());
+        assert_eq!(letters.get(&'b'), 2);
+        assert_eq!(letters.get(&'z'), 1);
+    }
+
+    #[test]
+    fn test_counter_arithmetic() {
+        let stock = Counter::from_counts([("bolt", 4), ("nut", 2), ("gear", 0)]);
+        let recipe = Counter::from_counts([("bolt", 3), ("nut", 1)]);
+        assert_eq!(stock.len(), 2);
+        assert!(stock.covers(&recipe));
+        assert!(!recipe.covers(&stock));
+
+        let left = stock.clone() - recipe.clone();
+        assert_eq!(left, Counter::from_counts([("bolt", 1), ("nut", 1)]));
+        // Counts stop at zero and the item is dropped
+        assert_eq!(left.clone() - recipe.clone(), Counter::new());
+        assert_eq!(left + recipe, stock);
+    }
+
+    #[test]
+    fn test_outcome_sum() {
+        let outcomes = [Some(3), Some(4)].map(Outcome::from);
+        assert_eq!(outcomes.into_iter().sum::<Outcome>(), Outcome::Answer(7));
+        assert_eq!(std::iter::empty().sum::<Outcome>(), Outcome::Answer(0));
+        assert_eq!(
+            Outcome::Answer(u64::MAX) + Outcome::Answer(1),
+            Outcome::Overflow
+        );
+        assert_eq!(Outcome::Overflow + Outcome::Answer(1), Outcome::Overflow);
+        assert_eq!(Outcome::Overflow + Outcome::Unsolvable, Outcome::Unsolvable);
+        assert_eq!(
+            [Some(1), None, Some(2)]
+                .map(Outcome::from)
+                .into_iter()
+                .sum::<Outcome>(),
+            Outcome::Unsolvable
+        );
+        assert_eq!(Outcome::Answer(5).answer(), Some(5));
+        assert_eq!(Outcome::Unsolvable.to_string(), "no solution");
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::from(7u32), Answer::UInt(7));
+        assert_eq!(Answer::from(7i64), Answer::from(7usize));
+        assert_eq!(Answer::from(-7i64), Answer::Int(-7));
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from("EAST"), Answer::Text("EAST".to_string()));
+        assert_eq!(Answer::from(Outcome::Answer(3)), Answer::UInt(3));
+        assert_eq!(Answer::from(Outcome::Unsolvable).to_string(), "no solution");
+        for text in ["42", "-42", "no solution", "1,2"] {
+            let answer: Answer = text.parse().unwrap();
+            assert_eq!(answer.to_string(), text);
+        }
+        assert_eq!("-3".parse::<Answer>().unwrap(), Answer::from(-3i8));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_formats() {
+        let outcomes = [Outcome::Answer(42), Outcome::Unsolvable, Outcome::Overflow];
+        let text = serde_json::to_string(&outcomes).unwrap();
+        assert_eq!(text, r#"[{"answer":42},"unsolvable","overflow"]"#);
+        assert_eq!(
+            serde_json::from_str::<[Outcome; 3]>(&text).unwrap(),
+            outcomes
+        );
+
+        let answers = [Answer::Int(-1), Answer::UInt(2), Answer::from("x")];
+        let text = serde_json::to_string(&answers).unwrap();
+        assert_eq!(text, r#"[{"int":-1},{"uint":2},{"text":"x"}]"#);
+        assert_eq!(serde_json::from_str::<[Answer; 3]>(&text).unwrap(), answers);
+
+        let point = Point2d { x: -1, y: 2 };
+        assert_eq!(serde_json::to_string(&point).unwrap(), r#"{"x":-1,"y":2}"#);
+        let point: Point = serde_json::from_str(r#"{"x":1,"y":2,"z":3}"#).unwrap();
+        assert_eq!(point, Point { x: 1, y: 2, z: 3 });
+
+        let data = parse_range_data("3-5\n\n4\n").unwrap();
+        let text = serde_json::to_string(&data).unwrap();
+        assert_eq!(text, r#"{"ranges":[[3,5]],"values":[4]}"#);
+        assert_eq!(serde_json::from_str::<RangeData>(&text).unwrap(), data);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(sum_checked([1u64, 2, 3]), Some(6));
+        assert_eq!(sum_checked(Vec::<u64>::new()), Some(0));
+        assert_eq!(sum_checked([i64::MAX, 1]), None);
+        assert_eq!(sum_checked([i64::MAX, -1, 1]), Some(i64::MAX));
+        assert_eq!(product_checked([2usize, 3, 7]), Some(42));
+        assert_eq!(product_checked(Vec::<usize>::new()), Some(1));
+        assert_eq!(product_checked([1usize << 32, 1 << 32]), None);
+        assert_eq!(product_checked([0i32, i32::MAX, i32::MAX]), Some(0));
+    }
+
+    #[test]
+    fn test_checked_accumulator() {
+        let mut total = Checked::new(u8::MAX - 1);
+        total += 1;
+        assert_eq!(total.get(), Some(u8::MAX));
+        // Once overflowed, the total stays overflowed
+        total += 1;
+        total *= 0;
+        assert_eq!(total.get(), None);
+
+        assert_eq!(
+            (1..=20u64).product::<Checked<u64>>().get(),
+            Some(2432902008176640000)
+        );
+        assert_eq!((1..=21u64).product::<Checked<u64>>().get(), None);
+        assert_eq!(
+            [5u32, 6].into_iter().sum::<Checked<u32>>(),
+            Checked::new(11)
+        );
+        assert_eq!(Checked::<i64>::default().get(), Some(0));
+
+        let mut overflowed = Checked::new(u32::MAX);
+        overflowed *= 2;
+        assert_eq!(
+            [Checked::new(1u32), Checked::new(2)]
+                .into_iter()
+                .sum::<Checked<u32>>()
+                .get(),
+            Some(3)
+        );
+        assert_eq!(
+            [Checked::new(1u32), overflowed]
+                .into_iter()
+                .sum::<Checked<u32>>()
+                .get(),
+            None
+        );
     }
 }
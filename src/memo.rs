@@ -0,0 +1,233 @@
+//! A memo table for recursive solvers that counts its hits and misses and
+//! can be capped.  Memoizing on whole states, such as the remaining
+//! counter targets of day 10, can grow without bound on hard inputs;
+//! [`Memoizer::with_max_entries`] evicts the least recently used entry
+//! once the cap is reached, trading repeated work for bounded memory, and
+//! [`Memoizer::stats`] shows afterwards how well the table did.
+//!
+//! ```
+//! use rust_advent::memo::Memoizer;
+//!
+//! let mut memo = Memoizer::new().with_max_entries(2);
+//! memo.insert("a", 1);
+//! memo.insert("b", 2);
+//! assert_eq!(memo.get(&"a"), Some(1));
+//! // "b" is now the least recently used
+//! memo.insert("c", 3);
+//! assert_eq!(memo.get(&"b"), None);
+//! let stats = memo.stats();
+//! assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 1, 1));
+//! ```
+
+use crate::FastMap;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// How a [`Memoizer`] has been used so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    /// Lookups that found a value.
+    pub hits: u64,
+    /// Lookups that found nothing, whether never stored or evicted.
+    pub misses: u64,
+    /// Entries dropped to stay within the cap.
+    pub evictions: u64,
+    /// Most entries held at once.
+    pub peak_entries: usize,
+}
+
+impl MemoStats {
+    /// The fraction of lookups that hit, or zero before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses, {} evicted, peak {} entries",
+            self.hits, self.misses, self.evictions, self.peak_entries
+        )
+    }
+}
+
+/// A map from states to solved values, optionally capped at a number of
+/// entries with least-recently-used eviction.  Uncapped, it is a
+/// [`FastMap`] plus counters; capped, each lookup or insert also moves the
+/// entry to the back of a recency queue, at logarithmic cost.
+#[derive(Debug, Clone)]
+pub struct Memoizer<K, V> {
+    /// Each entry's value and when it was last used.
+    entries: FastMap<K, (V, u64)>,
+    /// Keys by last use, oldest first; only kept when capped.
+    recency: BTreeMap<u64, K>,
+    max_entries: Option<usize>,
+    clock: u64,
+    stats: MemoStats,
+}
+
+impl<K, V> Default for Memoizer<K, V> {
+    fn default() -> Self {
+        Memoizer {
+            entries: FastMap::default(),
+            recency: BTreeMap::new(),
+            max_entries: None,
+            clock: 0,
+            stats: MemoStats::default(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memoizer<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caps the table at `max` entries, evicting the least recently used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn with_max_entries(mut self, max: usize) -> Self {
+        assert!(max > 0, "a memo table must hold at least one entry");
+        self.max_entries = Some(max);
+        self
+    }
+
+    pub fn max_entries(&self) -> Option<usize> {
+        self.max_entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// The value stored for `key`, counting a hit or a miss.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let Some((value, used)) = self.entries.get_mut(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.stats.hits += 1;
+        let value = value.clone();
+        if self.max_entries.is_some() {
+            self.clock += 1;
+            let key = self
+                .recency
+                .remove(used)
+                .expect("capped entries are queued");
+            *used = self.clock;
+            self.recency.insert(self.clock, key);
+        }
+        Some(value)
+    }
+
+    /// Stores `value` for `key`, evicting the least recently used entry if
+    /// the table is full.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+        if let Some(max) = self.max_entries {
+            if let Some((_, used)) = self.entries.get(&key) {
+                self.recency.remove(used);
+            } else if self.entries.len() >= max {
+                let (_, oldest) = self.recency.pop_first().expect("a full table has entries");
+                self.entries.remove(&oldest);
+                self.stats.evictions += 1;
+            }
+            self.recency.insert(self.clock, key.clone());
+        }
+        self.entries.insert(key, (value, self.clock));
+        self.stats.peak_entries = self.stats.peak_entries.max(self.entries.len());
+    }
+
+    /// Drops every entry, keeping the cap and the statistics.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_lru_matches_queue() {
+        // A capped table against a plain list, most recently used last
+        let mut rng = Lcg::new(24);
+        let mut memo = Memoizer::new().with_max_entries(5);
+        let mut queue: VecDeque<(u64, u64)> = VecDeque::new();
+        for step in 0..2000 {
+            let key = rng.below(12);
+            let found = queue.iter().position(|&(k, _)| k == key);
+            if rng.below(2) == 0 {
+                let expected = found.map(|i| {
+                    let entry = queue.remove(i).unwrap();
+                    queue.push_back(entry);
+                    entry.1
+                });
+                assert_eq!(memo.get(&key), expected);
+            } else {
+                if let Some(i) = found {
+                    queue.remove(i);
+                } else if queue.len() == 5 {
+                    queue.pop_front();
+                }
+                queue.push_back((key, step));
+                memo.insert(key, step);
+            }
+            assert_eq!(memo.len(), queue.len());
+        }
+        let stats = memo.stats();
+        assert!(stats.hits > 0 && stats.misses > 0 && stats.evictions > 0);
+        assert_eq!(stats.peak_entries, 5);
+    }
+
+    #[test]
+    fn test_uncapped() {
+        let mut memo: Memoizer<u32, u32> = Memoizer::new();
+        assert_eq!(memo.stats().hit_rate(), 0.0);
+        for n in 0..100 {
+            memo.insert(n, n * n);
+        }
+        assert_eq!(memo.get(&7), Some(49));
+        assert_eq!(memo.get(&100), None);
+        memo.insert(7, 0);
+        assert_eq!(memo.get(&7), Some(0));
+        let stats = memo.stats();
+        assert_eq!(
+            stats,
+            MemoStats {
+                hits: 2,
+                misses: 1,
+                evictions: 0,
+                peak_entries: 100
+            }
+        );
+        assert_eq!(
+            stats.to_string(),
+            "2 hits, 1 misses, 0 evicted, peak 100 entries"
+        );
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats().hits, 2);
+    }
+}
@@ -0,0 +1,181 @@
+//! What each day of the year looks like: its title, whether it has a
+//! second part, and the shape of its input.  The runner lists the days in
+//! its usage, and parsers use the shapes to say what an input looks like
+//! when it is not what they expected.
+
+use std::fmt;
+
+/// The layout of a day's input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputShape {
+    /// One `L68` or `R5` rotation per line.
+    Rotations,
+    /// Comma separated `low-high` ranges on one line.
+    IdRanges,
+    /// Rows of single digits.
+    DigitGrid,
+    /// Rows of single-character cells, such as `.` and `@`.
+    CharGrid,
+    /// A block of `low-high` ranges, a blank line, then one value per line.
+    RangesAndValues,
+    /// Columns of numbers with a row of operators underneath.
+    Worksheet,
+    /// One `x,y` point per line.
+    Points2d,
+    /// One `x,y,z` point per line.
+    Points3d,
+    /// One machine per line: a light diagram, buttons and joltages.
+    Machines,
+    /// One `device: output output ...` line per device.
+    Graph,
+    /// Numbered shapes drawn in `#` and `.`, then `WxH: counts` regions.
+    ShapesAndRegions,
+}
+
+impl InputShape {
+    /// Guesses the shape of `text` from its first lines, for the shapes
+    /// that can be told apart on sight.
+    pub fn detect(text: &str) -> Option<InputShape> {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .take(20)
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        let all = |test: &dyn Fn(&str) -> bool| lines.iter().all(|line| test(line));
+        fn fields(line: &str, n: usize) -> bool {
+            let parts: Vec<&str> = line.split(',').collect();
+            parts.len() == n && parts.iter().all(|part| part.trim().parse::<i64>().is_ok())
+        }
+        if all(&|line| fields(line, 2)) {
+            Some(InputShape::Points2d)
+        } else if all(&|line| fields(line, 3)) {
+            Some(InputShape::Points3d)
+        } else if all(&|line| {
+            line.split_once(':')
+                .is_some_and(|(name, _)| !name.contains(' '))
+        }) {
+            Some(InputShape::Graph)
+        } else if all(&|line| line.bytes().all(|b| b.is_ascii_digit())) {
+            Some(InputShape::DigitGrid)
+        } else if all(&|line| line.starts_with('[') && line.ends_with('}')) {
+            Some(InputShape::Machines)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for InputShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            InputShape::Rotations => "one rotation per line",
+            InputShape::IdRanges => "a line of ID ranges",
+            InputShape::DigitGrid => "a grid of digits",
+            InputShape::CharGrid => "a grid of cells",
+            InputShape::RangesAndValues => "ranges, a blank line, then values",
+            InputShape::Worksheet => "a worksheet of columns",
+            InputShape::Points2d => "one x,y point per line",
+            InputShape::Points3d => "one x,y,z point per line",
+            InputShape::Machines => "one machine per line",
+            InputShape::Graph => "one device and its outputs per line",
+            InputShape::ShapesAndRegions => "shapes followed by regions",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// One day's puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayInfo {
+    pub day: u32,
+    pub title: &'static str,
+    /// The last day has only one part.
+    pub has_part2: bool,
+    pub input: InputShape,
+}
+
+const fn day(day: u32, title: &'static str, input: InputShape) -> DayInfo {
+    DayInfo {
+        day,
+        title,
+        has_part2: true,
+        input,
+    }
+}
+
+/// Every day of the year, in order.
+pub const DAYS: [DayInfo; 12] = [
+    day(1, "Secret Entrance", InputShape::Rotations),
+    day(2, "Gift Shop", InputShape::IdRanges),
+    day(3, "Lobby", InputShape::DigitGrid),
+    day(4, "Printing Department", InputShape::CharGrid),
+    day(5, "Cafeteria", InputShape::RangesAndValues),
+    day(6, "Trash Compactor", InputShape::Worksheet),
+    day(7, "Laboratories", InputShape::CharGrid),
+    day(8, "Playground", InputShape::Points3d),
+    day(9, "Movie Theater", InputShape::Points2d),
+    day(10, "Factory", InputShape::Machines),
+    day(11, "Reactor", InputShape::Graph),
+    DayInfo {
+        has_part2: false,
+        ..day(12, "Christmas Tree Farm", InputShape::ShapesAndRegions)
+    },
+];
+
+/// The entry for `day`, if the year has one.
+pub fn day_info(day: u32) -> Option<&'static DayInfo> {
+    DAYS.iter().find(|info| info.day == day)
+}
+
+/// A note for a parser expecting `expected` when `text` is recognizably
+/// something else, such as a points file handed to a grid parser.
+pub fn shape_mismatch(expected: InputShape, text: &str) -> Option<String> {
+    let found = InputShape::detect(text).filter(|&found| found != expected)?;
+    Some(format!(
+        "the input looks like {}, not {}; is it the right day's?",
+        found, expected
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days() {
+        for (idx, info) in DAYS.iter().enumerate() {
+            assert_eq!(info.day as usize, idx + 1);
+        }
+        assert_eq!(day_info(10).unwrap().title, "Factory");
+        assert!(day_info(11).unwrap().has_part2);
+        assert!(!day_info(12).unwrap().has_part2);
+        assert_eq!(day_info(13), None);
+    }
+
+    #[test]
+    fn test_detect() {
+        let detect = InputShape::detect;
+        assert_eq!(detect("1,2\n-3,4\n"), Some(InputShape::Points2d));
+        assert_eq!(detect("1,2,3\n4,5,6\n"), Some(InputShape::Points3d));
+        assert_eq!(detect("8119\n2345\n"), Some(InputShape::DigitGrid));
+        assert_eq!(detect("aaa: bbb ccc\nbbb: out\n"), Some(InputShape::Graph));
+        assert_eq!(
+            detect(include_str!("../tests/golden/day10_example.txt")),
+            Some(InputShape::Machines)
+        );
+        assert_eq!(detect("L68\nR5\n"), None);
+        assert_eq!(detect("\n\n"), None);
+    }
+
+    #[test]
+    fn test_shape_mismatch() {
+        assert_eq!(shape_mismatch(InputShape::DigitGrid, "1234\n"), None);
+        assert_eq!(shape_mismatch(InputShape::DigitGrid, "L68\n"), None);
+        let note = shape_mismatch(InputShape::DigitGrid, "1,2,3\n").unwrap();
+        assert!(note.contains("x,y,z point"), "{}", note);
+    }
+}
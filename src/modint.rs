@@ -0,0 +1,241 @@
+//! Arithmetic modulo a constant.  [`ModInt`] reduces after every
+//! operation, so a puzzle asking for a hash, a worry level or a count
+//! modulo some `M` cannot forget to, and products are taken in `u128` so
+//! any modulus up to `u64::MAX` works.
+//!
+//! ```
+//! use rust_advent::modint::ModInt;
+//!
+//! type M = ModInt<1_000_000_007>;
+//! let x = M::new(3).pow(200) - M::new(5);
+//! assert_eq!(x * x.inverse().unwrap(), M::new(1));
+//! ```
+
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// An integer modulo `M`, always kept in `0..M`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub const ZERO: Self = ModInt(0);
+    pub const ONE: Self = ModInt(1 % M);
+
+    pub fn new(value: u64) -> Self {
+        ModInt(value % M)
+    }
+
+    /// The remainder of `value`, counting negative values up from `M`.
+    pub fn new_signed(value: i64) -> Self {
+        ModInt(i128::from(value).rem_euclid(i128::from(M)) as u64)
+    }
+
+    /// The remainder modulo `M`.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// `self` to the power `exp`, by repeated squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut result = Self::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// The `x` with `self * x == 1`, or `None` unless `self` and `M` are
+    /// coprime.  Found by the extended Euclidean algorithm, so `M` need
+    /// not be prime.
+    pub fn inverse(self) -> Option<Self> {
+        let (mut r0, mut r1) = (i128::from(M), i128::from(self.0));
+        let (mut t0, mut t1) = (0i128, 1i128);
+        while r1 != 0 {
+            let quotient = r0 / r1;
+            (r0, r1) = (r1, r0 - quotient * r1);
+            (t0, t1) = (t1, t0 - quotient * t1);
+        }
+        (r0 == 1).then(|| ModInt(t0.rem_euclid(i128::from(M)) as u64))
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        ModInt::new(value)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(value: i64) -> Self {
+        ModInt::new_signed(value)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        ModInt(((u128::from(self.0) + u128::from(other.0)) % u128::from(M)) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        ModInt((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        ModInt(((u128::from(self.0) * u128::from(other.0)) % u128::from(M)) as u64)
+    }
+}
+
+/// # Panics
+///
+/// Panics if the divisor has no [`ModInt::inverse`].
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let inverse = other.inverse();
+        self.mul(inverse.unwrap_or_else(|| panic!("{} has no inverse modulo {}", other, M)))
+    }
+}
+
+macro_rules! impl_assign_ops {
+    ($($trait:ident $method:ident $op:tt),*) => {
+        $(
+            impl<const M: u64> $trait for ModInt<M> {
+                fn $method(&mut self, other: Self) {
+                    *self = *self $op other;
+                }
+            }
+        )*
+    };
+}
+
+impl_assign_ops!(
+    AddAssign add_assign +,
+    SubAssign sub_assign -,
+    MulAssign mul_assign *,
+    DivAssign div_assign /
+);
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, Mul::mul)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+
+    const P: u64 = 1_000_000_007;
+
+    #[test]
+    fn test_arithmetic() {
+        type M = ModInt<P>;
+        assert_eq!(M::new(P + 5), M::new(5));
+        assert_eq!(M::new_signed(-1), M::new(P - 1));
+        assert_eq!(M::from(-3i64) + M::from(3u64), M::ZERO);
+        assert_eq!(M::new(2) - M::new(5), M::new(P - 3));
+        assert_eq!(-M::ZERO, M::ZERO);
+        assert_eq!(M::new(P - 1) * M::new(P - 1), M::ONE);
+        assert_eq!(M::new(2).pow(10), M::new(1024));
+        assert_eq!(M::new(7).pow(0), M::ONE);
+        // Fermat's little theorem
+        assert_eq!(M::new(123_456).pow(P - 1), M::ONE);
+        assert_eq!((1..=20).map(M::new).product::<M>(), M::new(146_326_063));
+        assert_eq!((1..=4).map(M::new).sum::<M>(), M::new(10));
+
+        let mut x = M::new(10);
+        x += M::new(5);
+        x *= M::new(3);
+        x -= M::new(45);
+        assert_eq!(x, M::ZERO);
+        assert_eq!(M::new(42).to_string(), "42");
+        assert_eq!(ModInt::<1>::ONE, ModInt::<1>::ZERO);
+    }
+
+    #[test]
+    fn test_inverse() {
+        type M = ModInt<P>;
+        assert_eq!(M::ZERO.inverse(), None);
+        for value in [1, 2, 3, 1000, P - 1] {
+            let x = M::new(value);
+            assert_eq!(x * x.inverse().unwrap(), M::ONE);
+            assert_eq!(M::new(6) / x * x, M::new(6));
+        }
+        // A composite modulus has inverses only for coprime values
+        type N = ModInt<12>;
+        assert_eq!(N::new(5).inverse(), Some(N::new(5)));
+        assert_eq!(N::new(7).inverse(), Some(N::new(7)));
+        assert_eq!(N::new(4).inverse(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "has no inverse")]
+    fn test_divide_by_zero() {
+        let _ = ModInt::<7>::ONE / ModInt::ZERO;
+    }
+
+    #[test]
+    fn test_large_modulus() {
+        const BIG: u64 = u64::MAX - 58; // the largest prime below 2^64
+        type M = ModInt<BIG>;
+        let mut rng = Lcg::new(14);
+        for _ in 0..100 {
+            let (a, b) = (rng.below(u64::MAX), rng.below(u64::MAX));
+            let big = u128::from(BIG);
+            let (x, y) = (M::new(a), M::new(b));
+            assert_eq!(
+                (x + y).value() as u128,
+                (u128::from(a) + u128::from(b)) % big
+            );
+            assert_eq!(
+                (x * y).value() as u128,
+                (u128::from(a) * u128::from(b)) % big
+            );
+            assert_eq!(x - y + y, x);
+            if x != M::ZERO {
+                assert_eq!(x * x.inverse().unwrap(), M::ONE);
+            }
+        }
+        assert_eq!(M::new_signed(-1), M::new(BIG - 1));
+        assert_eq!(M::new_signed(i64::MIN).value(), BIG - (1 << 63));
+    }
+}
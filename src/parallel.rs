@@ -0,0 +1,393 @@
+//! Solving independent input lines in parallel, and reductions whose result
+//! does not depend on how rayon splits the work.
+
+use crate::solution::Context;
+use rayon::prelude::*;
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fmt;
+
+/// A failure on one input line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    /// 1-based line number.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Every line that failed, in input order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineErrors {
+    pub errors: Vec<LineError>,
+}
+
+impl fmt::Display for LineErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, error) in self.errors.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "line {}: {}", error.line, error.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for LineErrors {}
+
+/// Solves each line in parallel, returning the answers in input order or
+/// every error labelled with its line number.
+pub fn solve_lines<S, T, E, F>(lines: &[S], ctx: &Context, solve: F) -> Result<Vec<T>, LineErrors>
+where
+    S: AsRef<str> + Sync,
+    T: Send,
+    E: fmt::Display,
+    F: Fn(&str) -> Result<T, E> + Sync,
+{
+    solve_lines_with(lines, ctx, || (), |_, line| solve(line))
+}
+
+/// Like [`solve_lines`], but each worker thread gets scratch state from
+/// `init` that it reuses across the lines it solves.
+pub fn solve_lines_with<S, T, E, W, I, F>(
+    lines: &[S],
+    ctx: &Context,
+    init: I,
+    solve: F,
+) -> Result<Vec<T>, LineErrors>
+where
+    S: AsRef<str> + Sync,
+    T: Send,
+    E: fmt::Display,
+    I: Fn() -> W + Sync,
+    F: Fn(&mut W, &str) -> Result<T, E> + Sync,
+{
+    let results: Vec<Result<T, String>> = within_budget(ctx, || {
+        lines
+            .par_iter()
+            .map_init(&init, |scratch, line| {
+                solve(scratch, line.as_ref()).map_err(|err| err.to_string())
+            })
+            .collect()
+    });
+
+    let mut answers = Vec::with_capacity(results.len());
+    let mut errors = Vec::new();
+    for (idx, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(answer) => answers.push(answer),
+            Err(message) => errors.push(LineError {
+                line: idx + 1,
+                message,
+            }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(answers)
+    } else {
+        Err(LineErrors { errors })
+    }
+}
+
+/// Keeps the `limit` smallest items pushed into it.  Items are ranked by
+/// their whole `Ord`, so with keys such as `(distance, i, j)` equal distances
+/// go to the lower index pair, and heaps filled on different threads merge to
+/// the same items however the work was split.
+#[derive(Debug, Clone)]
+pub struct BoundedHeap<T> {
+    heap: BinaryHeap<T>,
+    limit: usize,
+}
+
+impl<T: Ord> BoundedHeap<T> {
+    pub fn new(limit: usize) -> Self {
+        BoundedHeap {
+            heap: BinaryHeap::new(),
+            limit,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Whether a new item must beat [`Self::max`] to be kept.
+    pub fn is_full(&self) -> bool {
+        self.heap.len() >= self.limit
+    }
+
+    /// The largest item kept.
+    pub fn max(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    pub fn push(&mut self, item: T) {
+        if !self.is_full() {
+            self.heap.push(item);
+        } else if let Some(mut top) = self.heap.peek_mut()
+            && item < *top
+        {
+            *top = item;
+        }
+    }
+
+    /// Combines two heaps with the same limit, keeping the smallest items of
+    /// both.  Suits `ParallelIterator::reduce`.
+    pub fn merge(self, other: Self) -> Self {
+        let (mut larger, smaller) = if self.len() >= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        for item in smaller.heap {
+            larger.push(item);
+        }
+        larger
+    }
+
+    /// The items kept, smallest first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap.into_sorted_vec()
+    }
+}
+
+/// The `n` smallest items of `items`, smallest first; see [`BoundedHeap`].
+pub fn n_smallest<T, I>(items: I, n: usize) -> Vec<T>
+where
+    T: Ord + Send,
+    I: ParallelIterator<Item = T>,
+{
+    items
+        .fold(
+            || BoundedHeap::new(n),
+            |mut heap, item| {
+                heap.push(item);
+                heap
+            },
+        )
+        .reduce(|| BoundedHeap::new(n), BoundedHeap::merge)
+        .into_sorted_vec()
+}
+
+/// Runs `op` on a pool no larger than the context's thread budget.
+fn within_budget<R: Send>(ctx: &Context, op: impl FnOnce() -> R + Send) -> R {
+    let budget = ctx.thread_budget();
+    if budget >= rayon::current_num_threads() {
+        return op();
+    }
+    match rayon::ThreadPoolBuilder::new().num_threads(budget).build() {
+        Ok(pool) => pool.install(op),
+        // Oversubscribing beats failing outright.
+        Err(_) => op(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn parse(line: &str) -> Result<u64, String> {
+        line.parse()
+            .map_err(|_| format!("'{}' is not a number", line))
+    }
+
+    /// Runs `op` on another thread and fails the test if it has not finished
+    /// within a generous limit, so a deadlock fails rather than hangs.
+    fn finishes<R: Send + 'static>(op: impl FnOnce() -> R + Send + 'static) -> R {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || sender.send(op()));
+        receiver
+            .recv_timeout(Duration::from_secs(60))
+            .expect("deadlocked or panicked")
+    }
+
+    #[test]
+    fn test_solve_lines_preserves_order() {
+        let lines: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+        let answers = solve_lines(&lines, &Context::new(), parse).unwrap();
+        assert_eq!(answers, (0..1000).collect::<Vec<u64>>());
+        assert_eq!(
+            solve_lines(&[] as &[&str], &Context::new(), parse),
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn test_solve_lines_reports_every_error() {
+        let err = solve_lines(&["1", "x", "3", "", "5"], &Context::new(), parse).unwrap_err();
+        assert_eq!(
+            err.errors,
+            vec![
+                LineError {
+                    line: 2,
+                    message: "'x' is not a number".to_string()
+                },
+                LineError {
+                    line: 4,
+                    message: "'' is not a number".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            err.to_string(),
+            "line 2: 'x' is not a number; line 4: '' is not a number"
+        );
+    }
+
+    #[test]
+    fn test_solve_lines_respects_thread_budget() {
+        let lines = vec!["1"; 64];
+        let ctx = Context::new().with_thread_budget(1);
+        let threads = solve_lines(&lines, &ctx, |_| {
+            Ok::<_, String>(rayon::current_num_threads())
+        })
+        .unwrap();
+        assert!(threads.iter().all(|&n| n == 1));
+    }
+
+    #[test]
+    fn test_solve_lines_with_scratch() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let lines: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+        let inits = AtomicUsize::new(0);
+        let init = || {
+            inits.fetch_add(1, Ordering::Relaxed);
+            Vec::new()
+        };
+        let answers = solve_lines_with(
+            &lines,
+            &Context::new(),
+            init,
+            |seen: &mut Vec<u64>, line| {
+                seen.push(parse(line)?);
+                Ok::<_, String>(seen.len())
+            },
+        )
+        .unwrap();
+        assert_eq!(answers.len(), 1000);
+        assert!(inits.load(Ordering::Relaxed) < 1000);
+    }
+
+    #[test]
+    fn test_bounded_heap() {
+        let mut heap = BoundedHeap::new(3);
+        for item in [5, 1, 4, 1, 9, 2] {
+            heap.push(item);
+        }
+        assert!(heap.is_full());
+        assert_eq!(heap.max(), Some(&2));
+        assert_eq!(heap.clone().into_sorted_vec(), vec![1, 1, 2]);
+
+        let mut other = BoundedHeap::new(3);
+        other.push(0);
+        assert_eq!(heap.merge(other).into_sorted_vec(), vec![0, 1, 1]);
+        assert!(BoundedHeap::<u8>::new(0).is_full());
+    }
+
+    #[test]
+    fn test_n_smallest_breaks_ties_by_index() {
+        // Many equal distances: only the index pair decides which survive.
+        let pairs = (0..200usize)
+            .into_par_iter()
+            .flat_map_iter(|i| (i + 1..200).map(move |j| ((j - i) % 3, i, j)));
+        let expected: Vec<_> = (0..200)
+            .flat_map(|j| (0..j).map(move |i| ((j - i) % 3, i, j)))
+            .filter(|&(distance, _, _)| distance == 0)
+            .collect::<BinaryHeap<_>>()
+            .into_sorted_vec()
+            .into_iter()
+            .take(500)
+            .collect();
+        for threads in [1, 2, 7] {
+            let ctx = Context::new().with_thread_budget(threads);
+            let smallest = within_budget(&ctx, || n_smallest(pairs.clone(), 500));
+            assert_eq!(smallest, expected);
+        }
+    }
+
+    #[test]
+    fn test_nested_budgets_do_not_deadlock() {
+        // Each line runs its own parallel work, some of it on a smaller pool
+        // than the one solving the lines, and some with every pool busy.
+        let lines: Vec<String> = (0..64).map(|i| i.to_string()).collect();
+        let expected: Vec<u64> = (0..64).map(|i| (0..=i).sum()).collect();
+        for (outer, inner) in [(1, 1), (1, 4), (4, 1), (3, 2), (8, 8)] {
+            let lines = lines.clone();
+            let answers = finishes(move || {
+                let ctx = Context::new().with_thread_budget(outer);
+                solve_lines(&lines, &ctx, |line| {
+                    let n = parse(line)?;
+                    let inner_ctx = Context::new().with_thread_budget(inner);
+                    let sums = within_budget(&inner_ctx, || {
+                        (0..=n).into_par_iter().map(|k| vec![k]).collect::<Vec<_>>()
+                    });
+                    Ok::<_, String>(sums.into_iter().flatten().sum::<u64>())
+                })
+            });
+            assert_eq!(
+                answers.unwrap(),
+                expected,
+                "outer={} inner={}",
+                outer,
+                inner
+            );
+        }
+    }
+
+    #[test]
+    fn test_n_smallest_matches_sequential_on_any_pool() {
+        // Few distinct keys so almost every comparison is a tie on the first
+        // field; the result must still be exactly the sequential one.
+        let mut rng = Lcg::new(99);
+        for round in 0..20 {
+            let len = rng.between(0..=3000) as usize;
+            let items: Vec<(u64, usize)> = (0..len).map(|idx| (rng.below(4), idx)).collect();
+            let n = rng.between(0..=len as u64 + 10) as usize;
+            let mut expected = items.clone();
+            expected.sort_unstable();
+            expected.truncate(n);
+            for threads in [1, 2, 3, 8] {
+                let items = items.clone();
+                let smallest = finishes(move || {
+                    let ctx = Context::new().with_thread_budget(threads);
+                    within_budget(&ctx, || n_smallest(items.into_par_iter(), n))
+                });
+                assert_eq!(smallest, expected, "round={} threads={}", round, threads);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounded_heap_merge_is_order_independent() {
+        let mut rng = Lcg::new(7);
+        let items: Vec<u64> = (0..500).map(|_| rng.below(50)).collect();
+        let mut expected = items.clone();
+        expected.sort_unstable();
+        expected.truncate(40);
+        // Split into chunks at random and merge them in a shuffled order
+        for _ in 0..20 {
+            let mut heaps = Vec::new();
+            let mut rest = &items[..];
+            while !rest.is_empty() {
+                let (chunk, tail) = rest.split_at(rng.between(1..=rest.len() as u64) as usize);
+                let mut heap = BoundedHeap::new(40);
+                chunk.iter().for_each(|&item| heap.push(item));
+                heaps.push(heap);
+                rest = tail;
+            }
+            rng.shuffle(&mut heaps);
+            let merged = heaps
+                .into_iter()
+                .fold(BoundedHeap::new(40), BoundedHeap::merge);
+            assert_eq!(merged.into_sorted_vec(), expected);
+        }
+    }
+}
@@ -0,0 +1,430 @@
+//! Accumulators for counting paths through a graph.  Path counts grow
+//! exponentially with depth, so solvers count through [`PathCount`] and get
+//! an [`Overflow`] error instead of a silently wrapped total, or count
+//! [`Modular`] when only the remainder is wanted.
+//!
+//! [`count_paths_memo`] counts by depth-first search with a memo over any
+//! graph given by a successor function, and [`Cycles`] says what to do on
+//! meeting a cycle: skip it or report it.
+
+use crate::modint::ModInt;
+use crate::{FastMap, FastSet};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+/// A non-negative count that reports overflow on addition.
+pub trait PathCount: Clone + PartialEq + fmt::Debug + fmt::Display {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    /// Adds `other` into `self`, or reports the overflow.
+    fn add_paths(&mut self, other: &Self) -> Result<(), Overflow> {
+        *self = self.checked_add(other).ok_or(Overflow {
+            type_name: std::any::type_name::<Self>(),
+        })?;
+        Ok(())
+    }
+}
+
+macro_rules! impl_path_count {
+    ($($t:ty),*) => {
+        $(
+            impl PathCount for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *other)
+                }
+            }
+        )*
+    };
+}
+
+impl_path_count!(u32, u64, u128);
+
+#[cfg(feature = "bigint")]
+impl PathCount for num_bigint::BigUint {
+    fn zero() -> Self {
+        num_bigint::BigUint::ZERO
+    }
+
+    fn one() -> Self {
+        num_bigint::BigUint::from(1u8)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+}
+
+/// A count modulo `M`, for puzzles that ask for the answer modulo a prime.
+/// Never overflows.
+pub type Modular<const M: u64> = ModInt<M>;
+
+impl<const M: u64> PathCount for ModInt<M> {
+    fn zero() -> Self {
+        ModInt::ZERO
+    }
+
+    fn one() -> Self {
+        ModInt::ONE
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(*self + *other)
+    }
+}
+
+/// Returned when a path count does not fit its accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "path count overflows {}", self.type_name)
+    }
+}
+
+impl Error for Overflow {}
+
+/// Sums path counts, stopping at the first overflow.
+pub fn checked_sum<'a, T: PathCount + 'a>(
+    counts: impl IntoIterator<Item = &'a T>,
+) -> Result<T, Overflow> {
+    counts.into_iter().try_fold(T::zero(), |mut total, count| {
+        total.add_paths(count)?;
+        Ok(total)
+    })
+}
+
+/// What [`count_paths_memo`] does on following an edge back onto the
+/// path it is extending.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Cycles {
+    /// Drop the edge.  The count is then of paths in the acyclic graph
+    /// left once every such edge is dropped, so each path counted is
+    /// simple, but on a cyclic graph which edges go depends on the order
+    /// of successors: `a -> b -> a` loses `b -> a` from `a` and `a -> b`
+    /// from `b`.  On an acyclic graph nothing is dropped.
+    #[default]
+    Skip,
+    /// Fail with [`CountError::Cycle`].
+    Reject,
+}
+
+/// Why [`count_paths_memo`] failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CountError<K> {
+    Overflow(Overflow),
+    /// The vertices of a cycle met under [`Cycles::Reject`], in order from
+    /// the first one the search entered.
+    Cycle(Vec<K>),
+}
+
+impl<K> From<Overflow> for CountError<K> {
+    fn from(overflow: Overflow) -> Self {
+        CountError::Overflow(overflow)
+    }
+}
+
+impl<K> fmt::Display for CountError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CountError::Overflow(overflow) => overflow.fmt(f),
+            CountError::Cycle(cycle) => write!(f, "cycle of {} vertices", cycle.len()),
+        }
+    }
+}
+
+impl<K: fmt::Debug> Error for CountError<K> {}
+
+/// The number of paths from `start` to a target state, each state's count
+/// computed once.  A path ends at the first target it reaches.
+///
+/// States may carry more than a vertex, such as the set of required
+/// vertices already passed; `vertex` picks out the vertex, and a path may
+/// not repeat one.  `cycles` says what happens when it would.  A cycle the
+/// search never enters, because it is unreachable or only reached through
+/// a target, is never noticed.
+pub fn count_paths_memo<S, K, T, I>(
+    start: S,
+    cycles: Cycles,
+    vertex: impl Fn(&S) -> K,
+    successors: impl FnMut(&S) -> I,
+    is_target: impl Fn(&S) -> bool,
+) -> Result<T, CountError<K>>
+where
+    S: Clone + Eq + Hash,
+    K: Clone + Eq + Hash,
+    T: PathCount,
+    I: IntoIterator<Item = S>,
+{
+    MemoSearch {
+        cycles,
+        vertex,
+        successors,
+        is_target,
+        memo: FastMap::default(),
+        path: Vec::new(),
+        on_path: FastSet::default(),
+    }
+    .count(start)
+}
+
+struct MemoSearch<S, K, T, V, F, G> {
+    cycles: Cycles,
+    vertex: V,
+    successors: F,
+    is_target: G,
+    memo: FastMap<S, T>,
+    /// The vertices the search is extending, first to last.
+    path: Vec<K>,
+    on_path: FastSet<K>,
+}
+
+impl<S, K, T, I, V, F, G> MemoSearch<S, K, T, V, F, G>
+where
+    S: Clone + Eq + Hash,
+    K: Clone + Eq + Hash,
+    T: PathCount,
+    I: IntoIterator<Item = S>,
+    V: Fn(&S) -> K,
+    F: FnMut(&S) -> I,
+    G: Fn(&S) -> bool,
+{
+    /// Depth-first with an explicit stack of the states being counted, so
+    /// long chains cannot overflow the call stack.
+    fn count(&mut self, start: S) -> Result<T, CountError<K>> {
+        let mut stack: Vec<Frame<S, I::IntoIter, T>> = Vec::new();
+        let mut done = self.enter(start, &mut stack)?;
+        loop {
+            if let Some(count) = done.take() {
+                match stack.last_mut() {
+                    None => return Ok(count),
+                    Some(frame) => frame.total.add_paths(&count)?,
+                }
+            }
+            let frame = stack.last_mut().expect("an unfinished state is stacked");
+            if let Some(next) = frame.successors.next() {
+                done = self.enter(next, &mut stack)?;
+                continue;
+            }
+            let Frame { state, total, .. } = stack.pop().expect("just looked at it");
+            if let Some(vertex) = self.path.pop() {
+                self.on_path.remove(&vertex);
+            }
+            // Cached even if an edge below was dropped, so later visits agree
+            // with this one and the count is that of one fixed acyclic graph
+            self.memo.insert(state, total.clone());
+            done = Some(total);
+        }
+    }
+
+    /// The count of `state` if it is known without looking further, or
+    /// `None` after stacking it to count its successors.
+    fn enter(
+        &mut self,
+        state: S,
+        stack: &mut Vec<Frame<S, I::IntoIter, T>>,
+    ) -> Result<Option<T>, CountError<K>> {
+        if (self.is_target)(&state) {
+            return Ok(Some(T::one()));
+        }
+        if let Some(count) = self.memo.get(&state) {
+            return Ok(Some(count.clone()));
+        }
+        let vertex = (self.vertex)(&state);
+        if self.on_path.contains(&vertex) {
+            return match self.cycles {
+                Cycles::Skip => Ok(Some(T::zero())),
+                Cycles::Reject => {
+                    let entered = self.path.iter().position(|other| *other == vertex);
+                    Err(CountError::Cycle(
+                        self.path[entered.unwrap_or(0)..].to_vec(),
+                    ))
+                }
+            };
+        }
+
+        self.on_path.insert(vertex.clone());
+        self.path.push(vertex);
+        stack.push(Frame {
+            successors: (self.successors)(&state).into_iter(),
+            state,
+            total: T::zero(),
+        });
+        Ok(None)
+    }
+}
+
+/// A state whose successors are still being counted.
+struct Frame<S, It, T> {
+    state: S,
+    successors: It,
+    total: T,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_sum() {
+        assert_eq!(checked_sum::<u64>(&[]), Ok(0));
+        assert_eq!(checked_sum(&[1u32, 2, 3]), Ok(6));
+        assert_eq!(
+            checked_sum(&[u32::MAX, 1]),
+            Err(Overflow { type_name: "u32" })
+        );
+        assert_eq!(
+            checked_sum(&[u64::MAX, 1]).map_err(|e| e.to_string()),
+            Err("path count overflows u64".to_string())
+        );
+        assert_eq!(checked_sum(&[u128::from(u64::MAX), 1]), Ok(1u128 << 64));
+    }
+
+    #[test]
+    fn test_modular() {
+        type Mod7 = Modular<7>;
+        assert_eq!(checked_sum(&[Mod7::new(5), Mod7::new(4)]), Ok(Mod7::new(2)));
+        assert_eq!(Mod7::one().value(), 1);
+        assert_eq!(Modular::<1>::one().value(), 0);
+        let big = Modular::<{ u64::MAX }>::new(u64::MAX - 1);
+        assert_eq!(checked_sum(&[big, big]).unwrap().value(), u64::MAX - 2);
+        assert_eq!(Mod7::new(40).to_string(), "5");
+    }
+
+    fn count<T: PathCount>(
+        edges: &[(&'static str, &'static str)],
+        start: &'static str,
+        cycles: Cycles,
+    ) -> Result<T, CountError<&'static str>> {
+        let mut graph: FastMap<&str, Vec<&str>> = FastMap::default();
+        for &(from, to) in edges {
+            graph.entry(from).or_default().push(to);
+        }
+        count_paths_memo(
+            start,
+            cycles,
+            |&vertex| vertex,
+            |vertex| graph.get(vertex).cloned().unwrap_or_default(),
+            |&vertex| vertex == "t",
+        )
+    }
+
+    #[test]
+    fn test_count_paths_memo_acyclic() {
+        let diamond = [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("d", "t")];
+        for cycles in [Cycles::Skip, Cycles::Reject] {
+            assert_eq!(count::<u32>(&diamond, "a", cycles), Ok(2));
+            assert_eq!(count::<u32>(&diamond, "t", cycles), Ok(1));
+            assert_eq!(count::<u32>(&diamond, "z", cycles), Ok(0));
+        }
+
+        // 2^40 paths through a chain of diamonds, 2i -> 2i + 1 -> 2i + 2 and
+        // 2i -> 2i + 2, each state counted once
+        fn diamonds<T: PathCount>(target: u32) -> Result<T, CountError<u32>> {
+            let chain = |vertex: &u32| match vertex % 2 {
+                0 => vec![vertex + 1, vertex + 2],
+                _ => vec![vertex + 1],
+            };
+            count_paths_memo(0, Cycles::Reject, |&v| v, chain, |&v| v == target)
+        }
+        assert_eq!(diamonds::<u64>(80), Ok(1 << 40));
+        assert_eq!(
+            diamonds::<u32>(80),
+            Err(CountError::Overflow(Overflow { type_name: "u32" }))
+        );
+    }
+
+    #[test]
+    fn test_count_paths_memo_deep_chain() {
+        // A million vertices in a row, far deeper than a recursive search
+        // could go on a test thread's stack
+        let n = 1_000_000u32;
+        let chain = |&v: &u32| if v < n { vec![v + 1] } else { vec![] };
+        let count: Result<u64, _> = count_paths_memo(0, Cycles::Reject, |&v| v, chain, |&v| v == n);
+        assert_eq!(count, Ok(1));
+        // Back to the start from the end
+        let ring = |&v: &u32| vec![(v + 1) % n];
+        let cycle = count_paths_memo::<_, _, u64, _>(0, Cycles::Reject, |&v| v, ring, |_| false);
+        assert!(matches!(cycle, Err(CountError::Cycle(c)) if c.len() == n as usize));
+    }
+
+    #[test]
+    fn test_count_paths_memo_cycles() {
+        // A cycle on the way to the target
+        let edges = [("a", "b"), ("b", "c"), ("c", "b"), ("c", "t")];
+        assert_eq!(count::<u32>(&edges, "a", Cycles::Skip), Ok(1));
+        let err = count::<u32>(&edges, "a", Cycles::Reject).unwrap_err();
+        assert_eq!(err, CountError::Cycle(vec!["b", "c"]));
+        assert_eq!(err.to_string(), "cycle of 2 vertices");
+
+        // A cycle that never reaches the target, and one back to the start
+        let edges = [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "d")];
+        assert_eq!(count::<u32>(&edges, "a", Cycles::Skip), Ok(0));
+        assert_eq!(
+            count::<u32>(&edges, "a", Cycles::Reject),
+            Err(CountError::Cycle(vec!["a", "b", "c"]))
+        );
+
+        // Both simple paths counted whichever way round the cycle is cut
+        let edges = [("a", "b"), ("b", "a"), ("a", "t"), ("b", "t")];
+        assert_eq!(count::<u32>(&edges, "a", Cycles::Skip), Ok(2));
+        assert_eq!(count::<u32>(&edges, "b", Cycles::Skip), Ok(2));
+        assert!(count::<u32>(&edges, "a", Cycles::Reject).is_err());
+
+        // Paths stop at the target, so a cycle through it goes unnoticed
+        let edges = [("a", "t"), ("t", "a")];
+        assert_eq!(count::<u32>(&edges, "a", Cycles::Reject), Ok(1));
+    }
+
+    #[test]
+    fn test_count_paths_memo_states() {
+        // Paths from a to t through r, tracked in the state; r -> a would
+        // revisit a with a new state, but not a new vertex
+        let graph: FastMap<&str, Vec<&str>> =
+            [("a", vec!["r", "t"]), ("r", vec!["a", "t"]), ("t", vec![])]
+                .into_iter()
+                .collect();
+        let through_r = |cycles| {
+            count_paths_memo::<_, _, u32, _>(
+                ("a", false),
+                cycles,
+                |&(vertex, _)| vertex,
+                |&(vertex, seen)| {
+                    graph[vertex]
+                        .iter()
+                        .map(move |&next| (next, seen || next == "r"))
+                        .collect::<Vec<_>>()
+                },
+                |&(vertex, seen)| vertex == "t" && seen,
+            )
+        };
+        assert_eq!(through_r(Cycles::Skip), Ok(1));
+        assert_eq!(
+            through_r(Cycles::Reject),
+            Err(CountError::Cycle(vec!["a", "r"]))
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_uint() {
+        use num_bigint::BigUint;
+
+        let big = BigUint::from(u128::MAX);
+        let total = checked_sum(&[big.clone(), BigUint::one()]).unwrap();
+        assert_eq!(total, big + 1u8);
+    }
+}
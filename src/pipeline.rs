@@ -0,0 +1,135 @@
+//! Combinators for the trim, filter and parse steps most inputs go through
+//! before a solver sees them:
+//!
+//! ```
+//! use rust_advent::PuzzleInput;
+//!
+//! let input = PuzzleInput::from("# sizes\n3\n\n14 # biggest\n".to_string());
+//! let sizes: Vec<u32> = input
+//!     .lines()
+//!     .strip_comments('#')
+//!     .non_empty()
+//!     .parse_each()
+//!     .collect::<Result<_, _>>()?;
+//! assert_eq!(sizes, [3, 14]);
+//! # Ok::<(), rust_advent::Error>(())
+//! ```
+//!
+//! Lines stay borrowed from the input throughout, and each remembers its
+//! 1-based line number so a parse error points at the right line however
+//! many lines were dropped before it.
+
+use crate::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Lines of an input with their line numbers.  Iterates over the text of
+/// each line; [`Lines::numbered`] gives the numbers too.
+#[derive(Debug, Clone)]
+pub struct Lines<I> {
+    inner: I,
+}
+
+/// Numbers `lines` from 1.
+pub fn lines<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Lines<impl Iterator<Item = (usize, &'a str)>> {
+    Lines {
+        inner: lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line)),
+    }
+}
+
+impl<'a, I: Iterator<Item = (usize, &'a str)>> Lines<I> {
+    /// Pairs each line with its 1-based line number.
+    pub fn numbered(self) -> I {
+        self.inner
+    }
+
+    /// Cuts each line at the first `marker`, keeping what comes before it.
+    pub fn strip_comments(self, marker: char) -> Lines<impl Iterator<Item = (usize, &'a str)>> {
+        self.map_text(move |line| line.split_once(marker).map_or(line, |(kept, _)| kept))
+    }
+
+    /// Removes leading and trailing whitespace from each line.
+    pub fn trimmed(self) -> Lines<impl Iterator<Item = (usize, &'a str)>> {
+        self.map_text(str::trim)
+    }
+
+    /// Drops lines that are empty or only whitespace.
+    pub fn non_empty(self) -> Lines<impl Iterator<Item = (usize, &'a str)>> {
+        Lines {
+            inner: self.inner.filter(|(_, line)| !line.trim().is_empty()),
+        }
+    }
+
+    /// Parses each trimmed line as a `T`, failing with the line's number.
+    pub fn parse_each<T>(self) -> impl Iterator<Item = Result<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.inner.map(|(number, line)| {
+            line.trim().parse().map_err(|err| {
+                Error::parse(
+                    number,
+                    format!(
+                        "'{}' is not a valid {} ({})",
+                        line.trim(),
+                        std::any::type_name::<T>(),
+                        err
+                    ),
+                )
+            })
+        })
+    }
+
+    fn map_text(
+        self,
+        f: impl Fn(&'a str) -> &'a str,
+    ) -> Lines<impl Iterator<Item = (usize, &'a str)>> {
+        Lines {
+            inner: self.inner.map(move |(number, line)| (number, f(line))),
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = (usize, &'a str)>> Iterator for Lines<I> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.inner.next().map(|(_, line)| line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combinators() {
+        let text = "  a # one\n\n# only a comment\nb\t\n";
+        let kept: Vec<&str> = lines(text.lines()).strip_comments('#').trimmed().collect();
+        assert_eq!(kept, ["a", "", "", "b"]);
+        let numbered: Vec<(usize, &str)> = lines(text.lines())
+            .strip_comments('#')
+            .non_empty()
+            .numbered()
+            .collect();
+        assert_eq!(numbered, [(1, "  a "), (4, "b\t")]);
+    }
+
+    #[test]
+    fn test_parse_each() {
+        let numbers: Vec<Result<i32>> = lines("1\n\n x \n-4".lines())
+            .non_empty()
+            .parse_each()
+            .collect();
+        assert_eq!(numbers.len(), 3);
+        assert_eq!(*numbers[0].as_ref().unwrap(), 1);
+        assert!(matches!(numbers[1], Err(Error::Parse { line: 3, .. })));
+        assert_eq!(*numbers[2].as_ref().unwrap(), -4);
+    }
+}
@@ -0,0 +1,178 @@
+//! One entry point for the days the library can solve on its own, taking the
+//! puzzle input as a string.  This is what a browser playground calls once
+//! the library is built for wasm32-unknown-unknown without the `native`
+//! feature:
+//!
+//! ```text
+//! cargo build --lib --no-default-features --target wasm32-unknown-unknown
+//! ```
+//!
+//! The other days' solvers live in their binaries and need files and
+//! threads, so only days whose solvers are in the library are offered.
+
+use crate::day10::{self, CounterSet, LightSearch, Machine};
+use crate::homework::{Problem, grand_total, parse_columns, parse_rows};
+use crate::pipeline;
+use crate::solution::Context;
+use crate::{Answer, Error, Outcome, Result, WideMask};
+
+/// The year whose puzzles the library solves.
+pub const YEAR: u32 = 2025;
+
+/// Days [`solve`] accepts.
+pub const DAYS: [u32; 2] = [6, 10];
+
+/// Solves `part` (1 or 2) of `day` for `input`, giving the answer as it
+/// would be printed.  Parse errors carry their line, so a caller can show
+/// them with [`Error::render`].
+pub fn solve(year: u32, day: u32, part: u32, input: &str) -> Result<Answer> {
+    solve_with(year, day, part, input, &Context::new())
+}
+
+/// [`solve`], giving up with [`Error::Cancelled`] once `ctx` is cancelled.
+pub fn solve_with(year: u32, day: u32, part: u32, input: &str, ctx: &Context) -> Result<Answer> {
+    if year != YEAR {
+        return Err(Error::Unsupported(format!(
+            "only {} puzzles can be solved here",
+            YEAR
+        )));
+    }
+    let lines: Vec<&str> = input.lines().collect();
+    match (day, part) {
+        (6, 1) => homework_total(parse_rows(&lines)?),
+        (6, 2) => homework_total(parse_columns(&lines)?),
+        (10, 1) => Ok(light_presses(&lines, ctx)?.into()),
+        (10, 2) => Ok(counter_presses(&lines, ctx)?.into()),
+        (_, 1 | 2) if !DAYS.contains(&day) => Err(Error::Unsupported(format!(
+            "day {} is only solved by its binary",
+            day
+        ))),
+        _ => Err(Error::Unsupported(format!("there is no part {}", part))),
+    }
+}
+
+fn homework_total(problems: Vec<Problem>) -> Result<Answer> {
+    Ok(Answer::from(grand_total(&problems)?))
+}
+
+/// Day 10 part 1: the fewest presses that switch each machine's lights to
+/// its diagram, summed.
+fn light_presses(lines: &[&str], ctx: &Context) -> Result<Outcome> {
+    let mut search = LightSearch::new();
+    let mut total = Outcome::Answer(0);
+    for (number, line) in machine_lines(lines) {
+        ctx.check()?;
+        let machine = Machine::parse_wide(line).map_err(|err| err.on_line(number))?;
+        let diagram = line.split_whitespace().next().unwrap_or("");
+        let lights = diagram.trim_matches(['[', ']']).as_bytes();
+        let lit = lights
+            .iter()
+            .enumerate()
+            .filter(|&(_, &light)| light == b'#')
+            .map(|(light, _)| light);
+        let width = lights.len().max(machine.targets().len());
+        let presses = match machine.narrow().filter(|_| width <= day10::MAX_COUNTERS) {
+            Some(narrow) => search.min_presses(narrow.buttons(), u32::from_counters(width, lit)),
+            None => search.min_presses(machine.buttons(), WideMask::from_counters(width, lit)),
+        };
+        let presses = presses
+            .map_err(|message| Error::Unsupported(format!("line {}: {}", number, message)))?;
+        total = total + presses;
+    }
+    Ok(total)
+}
+
+/// Day 10 part 2: the fewest presses that bring every counter to its
+/// target, summed.  Machines of up to [`day10::MAX_COUNTERS`] counters
+/// take the `u32` path.
+fn counter_presses(lines: &[&str], ctx: &Context) -> Result<Outcome> {
+    let mut total = Outcome::Answer(0);
+    for (number, line) in machine_lines(lines) {
+        let machine = Machine::parse_wide(line).map_err(|err| err.on_line(number))?;
+        let presses = match machine.narrow() {
+            Some(narrow) => day10::min_presses(&narrow, ctx)?,
+            None => day10::min_presses(&machine, ctx)?,
+        };
+        total = total + presses;
+    }
+    Ok(total)
+}
+
+/// The non-blank lines with their line numbers.
+fn machine_lines<'a>(lines: &'a [&'a str]) -> impl Iterator<Item = (usize, &'a str)> {
+    pipeline::lines(lines.iter().copied())
+        .non_empty()
+        .numbered()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::CancellationToken;
+
+    const WORKSHEET: &str = "\
+123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   +  ";
+
+    const MACHINES: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/day10_example.txt"
+    ));
+
+    #[test]
+    fn test_day6() {
+        assert_eq!(solve(2025, 6, 1, WORKSHEET).unwrap(), Answer::UInt(4277556));
+        assert_eq!(solve(2025, 6, 2, WORKSHEET).unwrap(), Answer::UInt(3263827));
+    }
+
+    #[test]
+    fn test_day10() {
+        assert_eq!(solve(2025, 10, 1, MACHINES).unwrap(), Answer::UInt(7));
+        assert_eq!(solve(2025, 10, 2, MACHINES).unwrap(), Answer::UInt(33));
+
+        // Past 32 counters the machine takes the wide path
+        let wide = format!(
+            "[{}#] (39) (0,39) {{1,{}2}}",
+            ".".repeat(39),
+            "0,".repeat(38)
+        );
+        let machines = format!("{}{}\n", MACHINES, wide);
+        assert_eq!(solve(2025, 10, 1, &machines).unwrap(), Answer::UInt(8));
+        assert_eq!(solve(2025, 10, 2, &machines).unwrap(), Answer::UInt(35));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = solve(2025, 10, 1, "[.#] (0) {1,1}\n[.#] (7) {1,1}").unwrap_err();
+        assert!(matches!(err, Error::Parse { line: 2, .. }), "{:?}", err);
+        assert!(matches!(
+            solve(2024, 6, 1, WORKSHEET),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            solve(2025, 9, 1, ""),
+            Err(Error::Unsupported(message)) if message.contains("day 9")
+        ));
+        assert!(matches!(
+            solve(2025, 6, 3, WORKSHEET),
+            Err(Error::Unsupported(message)) if message.contains("part 3")
+        ));
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let ctx = Context::new().with_cancellation(token);
+        assert!(matches!(
+            solve_with(2025, 10, 1, MACHINES, &ctx),
+            Err(Error::Cancelled)
+        ));
+        assert!(matches!(
+            solve_with(2025, 10, 2, MACHINES, &ctx),
+            Err(Error::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,238 @@
+//! Progress reporting for long-running solvers.
+//!
+//! Solvers tick a [`Task`] obtained from the [`Reporter`] in their context;
+//! whether that renders a progress bar, periodic log lines, or nothing is
+//! decided by whoever runs the solver.  Bars require the `progress` feature
+//! and fall back to log lines without it.
+
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Minimum time between two log lines for the same task.
+const LOG_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressMode {
+    #[default]
+    Hidden,
+    Log,
+    Bar,
+}
+
+impl ProgressMode {
+    /// Bars on an interactive terminal, log lines when stderr is redirected.
+    pub fn detect() -> Self {
+        if std::io::stderr().is_terminal() {
+            ProgressMode::Bar
+        } else {
+            ProgressMode::Log
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "none" => Ok(ProgressMode::Hidden),
+            "log" => Ok(ProgressMode::Log),
+            "bar" => Ok(ProgressMode::Bar),
+            _ => Err(format!(
+                "invalid progress mode '{}' (expected none, log, or bar)",
+                value
+            )),
+        }
+    }
+}
+
+/// Hands out progress tasks rendered according to its mode.
+#[derive(Debug, Clone, Default)]
+pub struct Reporter {
+    mode: ProgressMode,
+}
+
+impl Reporter {
+    pub fn new(mode: ProgressMode) -> Self {
+        Reporter { mode }
+    }
+
+    pub fn hidden() -> Self {
+        Self::default()
+    }
+
+    pub fn mode(&self) -> ProgressMode {
+        self.mode
+    }
+
+    /// Starts a task.  `total` is the expected final position, if known.
+    pub fn task(&self, label: &str, total: Option<u64>) -> Task {
+        let output = match self.mode {
+            ProgressMode::Hidden => Output::Hidden,
+            #[cfg(feature = "progress")]
+            ProgressMode::Bar => Output::Bar(new_bar(label, total)),
+            _ => Output::Log {
+                last_logged: Mutex::new(None),
+            },
+        };
+        Task {
+            label: label.to_string(),
+            total,
+            position: AtomicU64::new(0),
+            output,
+        }
+    }
+}
+
+#[cfg(feature = "progress")]
+fn new_bar(label: &str, total: Option<u64>) -> indicatif::ProgressBar {
+    let bar = match total {
+        Some(total) => indicatif::ProgressBar::new(total).with_style(
+            indicatif::ProgressStyle::with_template(
+                "{prefix} [{elapsed_precise}] {wide_bar} {pos}/{len} {msg}",
+            )
+            .expect("valid progress template"),
+        ),
+        None => indicatif::ProgressBar::new_spinner(),
+    };
+    bar.with_prefix(label.to_string())
+}
+
+#[derive(Debug)]
+enum Output {
+    Hidden,
+    Log {
+        last_logged: Mutex<Option<Instant>>,
+    },
+    #[cfg(feature = "progress")]
+    Bar(indicatif::ProgressBar),
+}
+
+/// A unit of work whose progress is being reported.  Safe to tick from
+/// several threads.
+#[derive(Debug)]
+pub struct Task {
+    label: String,
+    total: Option<u64>,
+    position: AtomicU64,
+    output: Output,
+}
+
+impl Task {
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    pub fn set_position(&self, position: u64) {
+        self.position.store(position, Ordering::Relaxed);
+        self.update();
+    }
+
+    pub fn inc(&self, delta: u64) {
+        self.position.fetch_add(delta, Ordering::Relaxed);
+        self.update();
+    }
+
+    /// Reports a one-off status message.
+    pub fn message(&self, message: &str) {
+        match &self.output {
+            Output::Hidden => {}
+            Output::Log { .. } => eprintln!("{}: {}", self.label, message),
+            #[cfg(feature = "progress")]
+            Output::Bar(bar) => bar.set_message(message.to_string()),
+        }
+    }
+
+    pub fn finish(&self, message: &str) {
+        match &self.output {
+            Output::Hidden => {}
+            Output::Log { .. } => eprintln!("{}: {}", self.label, message),
+            #[cfg(feature = "progress")]
+            Output::Bar(bar) => bar.finish_with_message(message.to_string()),
+        }
+    }
+
+    fn update(&self) {
+        match &self.output {
+            Output::Hidden => {}
+            Output::Log { last_logged } => {
+                let mut last_logged = last_logged.lock().unwrap_or_else(|e| e.into_inner());
+                if last_logged.is_some_and(|t| t.elapsed() < LOG_INTERVAL) {
+                    return;
+                }
+                *last_logged = Some(Instant::now());
+                eprintln!("{}: {}", self.label, self.describe_position());
+            }
+            #[cfg(feature = "progress")]
+            Output::Bar(bar) => bar.set_position(self.position()),
+        }
+    }
+
+    fn describe_position(&self) -> String {
+        match self.total {
+            Some(total) => format!("{}/{}", self.position(), total),
+            None => self.position().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(ProgressMode::parse("none"), Ok(ProgressMode::Hidden));
+        assert_eq!(ProgressMode::parse("log"), Ok(ProgressMode::Log));
+        assert_eq!(ProgressMode::parse("bar"), Ok(ProgressMode::Bar));
+        assert!(ProgressMode::parse("fancy").is_err());
+    }
+
+    #[test]
+    fn test_task_tracks_position() {
+        let task = Reporter::hidden().task("search", Some(10));
+        task.inc(3);
+        task.inc(2);
+        assert_eq!(task.position(), 5);
+        task.set_position(9);
+        assert_eq!(task.position(), 9);
+        assert_eq!(task.describe_position(), "9/10");
+    }
+
+    #[test]
+    fn test_log_task_without_total() {
+        let task = Reporter::new(ProgressMode::Log).task("search", None);
+        task.inc(4);
+        assert_eq!(task.describe_position(), "4");
+        task.finish("done");
+    }
+
+    #[test]
+    fn test_task_is_shared_across_threads() {
+        let task = Reporter::hidden().task("parallel", None);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        task.inc(1);
+                    }
+                });
+            }
+        });
+        assert_eq!(task.position(), 400);
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_log_task_under_nested_rayon() {
+        use rayon::prelude::*;
+
+        // Log output takes a lock on every tick; workers that tick it while
+        // they themselves wait on nested parallel work must not deadlock.
+        let task = Reporter::new(ProgressMode::Log).task("nested", Some(4000));
+        (0..40).into_par_iter().for_each(|_| {
+            (0..100).into_par_iter().for_each(|_| task.inc(1));
+            task.message("chunk done");
+        });
+        assert_eq!(task.position(), 4000);
+        task.finish("done");
+    }
+}
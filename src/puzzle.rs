@@ -0,0 +1,275 @@
+//! A builder for solving one part of a puzzle from a test, bench or
+//! notebook on whatever input is at hand:
+//!
+//! ```no_run
+//! use rust_advent::puzzle::Puzzle;
+//!
+//! let answer = Puzzle::new(2025, 12).variant("claude").example(1).run_part1()?;
+//! # Ok::<(), rust_advent::Error>(())
+//! ```
+//!
+//! Without a variant the part is solved in-process by
+//! [`playground::solve_with`], so only its days are available.  With one,
+//! the release build of `<variant>_day<DD>` is run with its input swapped
+//! through [`INPUT_VAR`]; build it first with `cargo build --release`.
+
+use crate::playground::{self, YEAR};
+use crate::solution::{CancellationToken, Context};
+use crate::{Answer, Error, INPUT_VAR, Result, get_input_path};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Where a puzzle's input comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    /// The day's own input, from [`get_input_path`].
+    Real,
+    /// A checked-in example, numbered from 1.
+    Example(u32),
+    File(PathBuf),
+    Text(String),
+}
+
+/// One day of one year, to be solved on a chosen input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    year: u32,
+    day: u32,
+    variant: Option<String>,
+    source: Source,
+    timeout: Option<Duration>,
+}
+
+impl Puzzle {
+    /// `day` of `year` on its real input, solved in-process.
+    pub fn new(year: u32, day: u32) -> Self {
+        Puzzle {
+            year,
+            day,
+            variant: None,
+            source: Source::Real,
+            timeout: None,
+        }
+    }
+
+    /// Solves with the binary of `variant`, such as `claude` or
+    /// `gemini_cli`.
+    pub fn variant(mut self, variant: &str) -> Self {
+        self.variant = Some(variant.to_string());
+        self
+    }
+
+    /// Uses example `number` of the day; see [`example_path`].
+    pub fn example(mut self, number: u32) -> Self {
+        self.source = Source::Example(number);
+        self
+    }
+
+    /// Uses the contents of `path` as the input.
+    pub fn input_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.source = Source::File(path.into());
+        self
+    }
+
+    /// Uses `text` as the input.
+    pub fn input(mut self, text: impl Into<String>) -> Self {
+        self.source = Source::Text(text.into());
+        self
+    }
+
+    /// Gives up with [`Error::Cancelled`] after `timeout`.  Binaries not yet
+    /// on the `Solution` trait ignore it.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn run_part1(&self) -> Result<Answer> {
+        self.run(1)
+    }
+
+    pub fn run_part2(&self) -> Result<Answer> {
+        self.run(2)
+    }
+
+    /// Solves `part`.  A binary's answer is read back from what it prints.
+    pub fn run(&self, part: u32) -> Result<Answer> {
+        if self.year != YEAR {
+            return Err(Error::Unsupported(format!(
+                "only {} puzzles can be solved here",
+                YEAR
+            )));
+        }
+        match &self.variant {
+            None => {
+                let input = match &self.source {
+                    Source::Text(text) => text.clone(),
+                    _ => std::fs::read_to_string(self.input_path())?,
+                };
+                let cancellation = self
+                    .timeout
+                    .map_or_else(CancellationToken::new, CancellationToken::with_timeout);
+                let ctx = Context::new().with_cancellation(cancellation);
+                playground::solve_with(self.year, self.day, part, &input, &ctx)
+            }
+            Some(variant) => {
+                let stdout = match &self.source {
+                    Source::Text(text) => {
+                        let path = temp_input_path();
+                        std::fs::write(&path, text)?;
+                        let stdout = self.run_bin(variant, &path);
+                        let _ = std::fs::remove_file(&path);
+                        stdout?
+                    }
+                    _ => self.run_bin(variant, &self.input_path())?,
+                };
+                find_answer(&stdout, part)
+            }
+        }
+    }
+
+    /// The file the input is read from; not meaningful for text input.
+    fn input_path(&self) -> PathBuf {
+        match &self.source {
+            Source::Real => get_input_path(&format!("{:02}", self.day)),
+            Source::Example(number) => example_path(self.day, *number),
+            Source::File(path) => path.clone(),
+            Source::Text(_) => PathBuf::new(),
+        }
+    }
+
+    /// Runs the variant's binary on `input`, returning what it printed.
+    fn run_bin(&self, variant: &str, input: &Path) -> Result<String> {
+        let bin = format!("{}_day{:02}", variant, self.day);
+        let path = release_dir().join(&bin);
+        if !path.is_file() {
+            return Err(Error::Unsupported(format!(
+                "{} is not built; run `cargo build --release --bin {}`",
+                bin, bin
+            )));
+        }
+        let mut command = Command::new(&path);
+        command.env(INPUT_VAR, input).arg("--progress=none");
+        if let Some(timeout) = self.timeout {
+            command.arg(format!("--timeout={}ms", timeout.as_millis()));
+        }
+        let output = command.output()?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            // `main` reports its error with `Debug`, as `Cancelled`
+            if stderr.to_ascii_lowercase().contains("cancelled") {
+                return Err(Error::Cancelled);
+            }
+            return Err(Error::Io(io::Error::other(format!(
+                "{} failed: {}",
+                bin,
+                stderr.trim()
+            ))));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Example `number` of `day`: `tests/examples/<DD>/example<number>.txt`.
+pub fn example_path(day: u32, number: u32) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("examples")
+        .join(format!("{:02}", day))
+        .join(format!("example{}.txt", number))
+}
+
+fn release_dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("target"))
+        .join("release")
+}
+
+/// A fresh file name for handing text input to a binary.
+fn temp_input_path() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "advent-input-{}-{}.txt",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// The answer on the `Part <part>:` line of a binary's output.
+fn find_answer(stdout: &str, part: u32) -> Result<Answer> {
+    let label = format!("Part {}", part);
+    stdout
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim() != label {
+                return None;
+            }
+            value.trim().parse().ok()
+        })
+        .ok_or_else(|| Error::NoSolution(format!("no '{}:' line in the output", label)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let puzzle = Puzzle::new(2025, 10).example(1);
+        assert_eq!(puzzle.run_part1().unwrap(), Answer::UInt(7));
+        assert_eq!(puzzle.run_part2().unwrap(), Answer::UInt(33));
+        assert!(matches!(
+            Puzzle::new(2025, 10).example(9).run_part1(),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_input() {
+        let worksheet = "123 328\n 45 64\n  6 98\n*   +  ";
+        assert_eq!(
+            Puzzle::new(2025, 6).input(worksheet).run_part1().unwrap(),
+            Answer::UInt(33700)
+        );
+        let file = example_path(10, 1);
+        assert_eq!(
+            Puzzle::new(2025, 10).input_file(file).run_part1().unwrap(),
+            Answer::UInt(7)
+        );
+        let cancelled = Puzzle::new(2025, 10)
+            .example(1)
+            .timeout(Duration::ZERO)
+            .run_part2();
+        assert!(matches!(cancelled, Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(matches!(
+            Puzzle::new(2024, 10).example(1).run_part1(),
+            Err(Error::Unsupported(_))
+        ));
+        let err = Puzzle::new(2025, 10)
+            .variant("nobody")
+            .example(1)
+            .run_part1()
+            .unwrap_err();
+        assert!(err.to_string().contains("nobody_day10"), "{}", err);
+    }
+
+    #[test]
+    fn test_find_answer() {
+        let stdout = "Parsing...\nPart 1: 7\nPart 2: no solution\n";
+        assert_eq!(find_answer(stdout, 1).unwrap(), Answer::UInt(7));
+        assert_eq!(
+            find_answer(stdout, 2).unwrap(),
+            Answer::Text("no solution".to_string())
+        );
+        assert!(matches!(find_answer("", 1), Err(Error::NoSolution(_))));
+    }
+}
@@ -0,0 +1,109 @@
+//! A Python module, `rust_advent`, for poking at puzzle inputs from a
+//! notebook with the same code the solutions use.  Points cross as tuples,
+//! so a day 8 input can go straight from `parse_points` into a plot.
+//!
+//! Build it as an extension module with
+//!
+//! ```text
+//! cargo rustc --lib --release --features python --crate-type cdylib
+//! cp target/release/librust_advent.so rust_advent.so
+//! ```
+//!
+//! and `import rust_advent` from the directory holding `rust_advent.so`.
+
+use crate::{Point2d, playground};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+#[pymodule]
+fn rust_advent(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("YEAR", playground::YEAR)?;
+    module.add("DAYS", playground::DAYS.to_vec())?;
+    module.add_function(wrap_pyfunction!(solve, module)?)?;
+    module.add_function(wrap_pyfunction!(convex_hull, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_points2d, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_points, module)?)?;
+    Ok(())
+}
+
+/// Solves `part` of `day` for `input`, as `playground::solve`.  Errors are
+/// raised as `ValueError` with the offending line shown.
+#[pyfunction]
+fn solve(year: u32, day: u32, part: u32, input: &str) -> PyResult<String> {
+    playground::solve(year, day, part, input)
+        .map(|answer| answer.to_string())
+        .map_err(|err| value_error(&err, input))
+}
+
+/// The convex hull of `(x, y)` points, counter-clockwise.
+#[pyfunction]
+fn convex_hull(points: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+    let points: Vec<Point2d> = points.into_iter().map(|(x, y)| Point2d { x, y }).collect();
+    crate::convex_hull(&points)
+        .into_iter()
+        .map(|p| (p.x, p.y))
+        .collect()
+}
+
+/// One `(x, y)` tuple per `x,y` line of `input`.
+#[pyfunction]
+fn parse_points2d(input: &str) -> PyResult<Vec<(i32, i32)>> {
+    let points = crate::parse_points2d(input).map_err(|err| value_error(&err, input))?;
+    Ok(points.into_iter().map(|p| (p.x, p.y)).collect())
+}
+
+/// One `(x, y, z)` tuple per `x,y,z` line of `input`.
+#[pyfunction]
+fn parse_points(input: &str) -> PyResult<Vec<(i32, i32, i32)>> {
+    let points = crate::parse_points(input).map_err(|err| value_error(&err, input))?;
+    Ok(points.into_iter().map(|p| (p.x, p.y, p.z)).collect())
+}
+
+fn value_error(err: &crate::Error, input: &str) -> PyErr {
+    PyValueError::new_err(err.render(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convex_hull() {
+        let hull = convex_hull(vec![(0, 0), (4, 0), (2, 1), (4, 4), (0, 4)]);
+        assert_eq!(hull, vec![(0, 0), (4, 0), (4, 4), (0, 4)]);
+    }
+
+    #[test]
+    fn test_parse_points() {
+        assert_eq!(
+            parse_points("1,2,3\n-4,5,6\n").unwrap(),
+            vec![(1, 2, 3), (-4, 5, 6)]
+        );
+        assert_eq!(parse_points2d("7,8\n").unwrap(), vec![(7, 8)]);
+    }
+
+    #[test]
+    fn test_module() {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "rust_advent").unwrap();
+            rust_advent(&module).unwrap();
+            let answer: String = module
+                .getattr("solve")
+                .unwrap()
+                .call1((2025, 6, 1, "1 2\n3 4\n* +\n"))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(answer, "9");
+            let err = module
+                .getattr("parse_points")
+                .unwrap()
+                .call1(("1,2\n",))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            let days: Vec<u32> = module.getattr("DAYS").unwrap().extract().unwrap();
+            assert_eq!(days, playground::DAYS);
+        });
+    }
+}
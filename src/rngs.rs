@@ -0,0 +1,154 @@
+//! Reproducible randomness for solvers that search at random, such as
+//! simulated annealing or random restarts.  [`seeded`] gives a generator
+//! seeded from the run's [`Context`], so the same `--seed` replays the same
+//! search, and differential tests see the same answers on every run.
+//!
+//! Input generators keep the simpler [`Lcg`](crate::generators::Lcg);
+//! solvers draw many more numbers and want the better statistics of PCG.
+
+use crate::solution::Context;
+use std::ops::RangeInclusive;
+
+const MULTIPLIER: u64 = 6364136223846793005;
+
+/// The PCG32 generator (XSH RR variant): 64 bits of state, 32-bit outputs,
+/// and independent streams for the same seed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// A generator on stream 0.
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, 0)
+    }
+
+    /// A generator whose sequence differs from every other `stream` with
+    /// the same seed, for giving each worker of a parallel search its own.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Pcg32 {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+
+    /// A value in `0..n`, without the bias of a plain `% n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "cannot draw from an empty range");
+        // Values below this would make the low results more likely
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return value % n;
+            }
+        }
+    }
+
+    /// A value in `range`.
+    pub fn between(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let span = range.end() - range.start();
+        if span == u64::MAX {
+            return self.next_u64();
+        }
+        range.start() + self.below(span + 1)
+    }
+
+    /// A value in `[0, 1)`, such as for an annealing acceptance test.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Puts `items` in a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.below(idx as u64 + 1) as usize);
+        }
+    }
+}
+
+/// A generator seeded with the run's [`Context::rng_seed`].
+pub fn seeded(ctx: &Context) -> Pcg32 {
+    Pcg32::new(ctx.rng_seed())
+}
+
+/// Like [`seeded`], on its own `stream`.
+pub fn seeded_stream(ctx: &Context, stream: u64) -> Pcg32 {
+    Pcg32::with_stream(ctx.rng_seed(), stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_output() {
+        // From the PCG reference implementation's pcg32-demo
+        let mut rng = Pcg32::with_stream(42, 54);
+        let outputs: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+        assert_eq!(
+            outputs,
+            [
+                0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e
+            ]
+        );
+    }
+
+    #[test]
+    fn test_seeded_from_context() {
+        let ctx = Context::new().with_rng_seed(5);
+        assert_eq!(seeded(&ctx), Pcg32::new(5));
+        assert_eq!(seeded(&ctx).next_u64(), seeded(&ctx).next_u64());
+        assert_ne!(seeded_stream(&ctx, 1).next_u64(), seeded(&ctx).next_u64());
+        assert_ne!(seeded(&Context::new()).next_u64(), seeded(&ctx).next_u64());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Pcg32::new(1);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let value = rng.between(10..=15);
+            assert!((10..=15).contains(&value));
+            seen[value as usize - 10] = true;
+            let fraction = rng.next_f64();
+            assert!((0.0..1.0).contains(&fraction));
+        }
+        assert!(seen.iter().all(|&seen| seen));
+        rng.between(0..=u64::MAX);
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+}
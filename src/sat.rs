@@ -0,0 +1,461 @@
+//! Satisfiability for puzzles that reduce to boolean constraints.
+//! [`TwoSat`] decides conjunctions of two-literal clauses in linear time
+//! through the strongly connected components of their implication graph.
+//! [`Cnf`] is a small DPLL solver for any clauses over at most 64
+//! variables, each clause a pair of bit masks; it can also find the model
+//! with the fewest true variables, and takes parity (XOR) constraints.
+
+use crate::validate::{Violation, ensure_at_most};
+use std::ops::Not;
+
+/// A variable or its negation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lit(usize);
+
+impl Lit {
+    /// Variable `var` being true.
+    pub fn pos(var: usize) -> Self {
+        Lit(2 * var)
+    }
+
+    /// Variable `var` being false.
+    pub fn neg(var: usize) -> Self {
+        Lit(2 * var + 1)
+    }
+
+    pub fn var(self) -> usize {
+        self.0 / 2
+    }
+
+    pub fn is_negated(self) -> bool {
+        self.0 % 2 == 1
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// A conjunction of clauses of at most two literals.
+#[derive(Debug, Clone, Default)]
+pub struct TwoSat {
+    /// Edges of the implication graph, indexed by literal.
+    implications: Vec<Vec<usize>>,
+}
+
+impl TwoSat {
+    pub fn new(vars: usize) -> Self {
+        TwoSat {
+            implications: vec![Vec::new(); 2 * vars],
+        }
+    }
+
+    pub fn vars(&self) -> usize {
+        self.implications.len() / 2
+    }
+
+    /// Requires `a` or `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either names a variable past [`Self::vars`].
+    pub fn add_clause(&mut self, a: Lit, b: Lit) {
+        self.implications[(!a).0].push(b.0);
+        self.implications[(!b).0].push(a.0);
+    }
+
+    /// Requires `b` whenever `a`.
+    pub fn add_implication(&mut self, a: Lit, b: Lit) {
+        self.add_clause(!a, b);
+    }
+
+    /// Requires `a`.
+    pub fn add_unit(&mut self, a: Lit) {
+        self.add_clause(a, a);
+    }
+
+    /// A value for every variable meeting every clause, or `None` if some
+    /// variable implies its own negation and back.
+    pub fn solve(&self) -> Option<Vec<bool>> {
+        let component = strongly_connected(&self.implications);
+        (0..self.vars())
+            .map(|var| {
+                let (yes, no) = (component[2 * var], component[2 * var + 1]);
+                // Components are numbered sinks first; the literal nearer the
+                // sinks cannot lead to its negation
+                (yes != no).then_some(yes < no)
+            })
+            .collect()
+    }
+}
+
+/// The strongly connected component of each node by Tarjan's algorithm,
+/// numbered in reverse topological order.  Iterative, so long implication
+/// chains cannot overflow the stack.
+fn strongly_connected(graph: &[Vec<usize>]) -> Vec<usize> {
+    let mut tarjan = Tarjan {
+        index: vec![UNSEEN; graph.len()],
+        low: vec![0; graph.len()],
+        on_stack: vec![false; graph.len()],
+        stack: Vec::new(),
+        visited: 0,
+    };
+    let mut component = vec![UNSEEN; graph.len()];
+    let mut components = 0;
+    // Nodes being visited, with the next edge of each to follow
+    let mut calls: Vec<(usize, usize)> = Vec::new();
+    for root in 0..graph.len() {
+        if tarjan.index[root] != UNSEEN {
+            continue;
+        }
+        tarjan.visit(root);
+        calls.push((root, 0));
+        while let Some(&(node, edge)) = calls.last() {
+            if let Some(&next) = graph[node].get(edge) {
+                calls.last_mut().unwrap().1 += 1;
+                if tarjan.index[next] == UNSEEN {
+                    tarjan.visit(next);
+                    calls.push((next, 0));
+                } else if tarjan.on_stack[next] {
+                    tarjan.low[node] = tarjan.low[node].min(tarjan.index[next]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                tarjan.low[parent] = tarjan.low[parent].min(tarjan.low[node]);
+            }
+            if tarjan.low[node] == tarjan.index[node] {
+                while let Some(member) = tarjan.stack.pop() {
+                    tarjan.on_stack[member] = false;
+                    component[member] = components;
+                    if member == node {
+                        break;
+                    }
+                }
+                components += 1;
+            }
+        }
+    }
+    component
+}
+
+const UNSEEN: usize = usize::MAX;
+
+struct Tarjan {
+    index: Vec<usize>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    visited: usize,
+}
+
+impl Tarjan {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = self.visited;
+        self.low[node] = self.visited;
+        self.visited += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+    }
+}
+
+/// A clause over variables `0..64`: met when a variable in `positive` is
+/// true or one in `negative` is false.  Both empty is never met.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Clause {
+    pub positive: u64,
+    pub negative: u64,
+}
+
+impl Clause {
+    /// Whether the model, bit `i` set for variable `i` true, meets the
+    /// clause.
+    pub fn is_met_by(self, model: u64) -> bool {
+        self.positive & model != 0 || self.negative & !model != 0
+    }
+}
+
+/// A conjunction of [`Clause`]s, solved by DPLL: unit propagation, then
+/// trying each value of a variable from the first clause not yet met.
+#[derive(Debug, Clone, Default)]
+pub struct Cnf {
+    vars: usize,
+    clauses: Vec<Clause>,
+}
+
+impl Cnf {
+    /// Most variables a formula may have; models are `u64` masks.
+    pub const MAX_VARS: usize = 64;
+    /// Most variables an XOR constraint may have, as it takes
+    /// `2^(n - 1)` clauses.
+    pub const MAX_XOR_VARS: usize = 16;
+
+    pub fn new(vars: usize) -> Result<Self, Violation> {
+        ensure_at_most("variables", vars, Self::MAX_VARS)?;
+        Ok(Cnf {
+            vars,
+            clauses: Vec::new(),
+        })
+    }
+
+    pub fn vars(&self) -> usize {
+        self.vars
+    }
+
+    pub fn clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+
+    /// Adds `clause`, unless it has a variable both ways and so is always
+    /// met.
+    pub fn add_clause(&mut self, clause: Clause) {
+        if clause.positive & clause.negative == 0 {
+            self.clauses.push(clause);
+        }
+    }
+
+    /// Requires an odd number of the variables in `vars` to be true when
+    /// `odd`, and an even number otherwise, by ruling out every assignment
+    /// of the wrong parity.
+    pub fn add_xor(&mut self, vars: u64, odd: bool) -> Result<(), Violation> {
+        ensure_at_most(
+            "variables in an XOR",
+            vars.count_ones() as usize,
+            Self::MAX_XOR_VARS,
+        )?;
+        // Every subset of `vars`, as the variables set true
+        let mut subset = vars;
+        loop {
+            if (subset.count_ones() % 2 == 1) != odd {
+                self.add_clause(Clause {
+                    positive: vars & !subset,
+                    negative: subset,
+                });
+            }
+            if subset == 0 {
+                return Ok(());
+            }
+            subset = (subset - 1) & vars;
+        }
+    }
+
+    /// Some model meeting every clause, bit `i` set for variable `i` true.
+    pub fn solve(&self) -> Option<u64> {
+        self.search(false)
+    }
+
+    /// The model meeting every clause with the fewest variables true, by
+    /// branch and bound over the DPLL search.
+    pub fn solve_min_true(&self) -> Option<u64> {
+        self.search(true)
+    }
+
+    fn search(&self, minimize: bool) -> Option<u64> {
+        let mut dpll = Dpll {
+            clauses: &self.clauses,
+            minimize,
+            best: None,
+        };
+        dpll.branch(0, 0);
+        dpll.best
+    }
+}
+
+struct Dpll<'a> {
+    clauses: &'a [Clause],
+    minimize: bool,
+    best: Option<u64>,
+}
+
+impl Dpll<'_> {
+    /// Extends the partial model: `assigned` holds the variables given a
+    /// value, and `values` which of those are true.
+    fn branch(&mut self, mut assigned: u64, mut values: u64) {
+        let met = |clause: &Clause, assigned: u64, values: u64| {
+            clause.positive & assigned & values != 0 || clause.negative & assigned & !values != 0
+        };
+        // A clause with one variable left forces it
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for clause in self.clauses {
+                if met(clause, assigned, values) {
+                    continue;
+                }
+                let open = (clause.positive | clause.negative) & !assigned;
+                if open == 0 {
+                    return;
+                }
+                if open.is_power_of_two() {
+                    assigned |= open;
+                    if clause.positive & open != 0 {
+                        values |= open;
+                    }
+                    changed = true;
+                }
+            }
+        }
+        if self.minimize
+            && self
+                .best
+                .is_some_and(|best| values.count_ones() >= best.count_ones())
+        {
+            return;
+        }
+        let Some(clause) = self
+            .clauses
+            .iter()
+            .find(|clause| !met(clause, assigned, values))
+        else {
+            // Variables still free are left false
+            self.best = Some(values);
+            return;
+        };
+        let open = (clause.positive | clause.negative) & !assigned;
+        let var = open & open.wrapping_neg();
+        self.branch(assigned | var, values);
+        if self.minimize || self.best.is_none() {
+            self.branch(assigned | var, values | var);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+
+    fn random_clauses(rng: &mut Lcg, vars: u64, count: usize, width: usize) -> Vec<Clause> {
+        (0..count)
+            .map(|_| {
+                let mut clause = Clause::default();
+                for _ in 0..width {
+                    let var = 1 << rng.below(vars);
+                    if rng.below(2) == 0 {
+                        clause.positive |= var;
+                    } else {
+                        clause.negative |= var;
+                    }
+                }
+                clause
+            })
+            .collect()
+    }
+
+    /// Every model of a formula over `vars` variables, in increasing order.
+    fn models(clauses: &[Clause], vars: u64) -> Vec<u64> {
+        (0..1 << vars)
+            .filter(|&model| clauses.iter().all(|clause| clause.is_met_by(model)))
+            .collect()
+    }
+
+    #[test]
+    fn test_lit() {
+        let a = Lit::pos(3);
+        assert_eq!(a.var(), 3);
+        assert!(!a.is_negated());
+        assert_eq!(!a, Lit::neg(3));
+        assert!((!a).is_negated());
+        assert_eq!(!!a, a);
+    }
+
+    #[test]
+    fn test_two_sat() {
+        // Exactly one of 0 and 1, 1 implies 2, and not 2
+        let mut formula = TwoSat::new(3);
+        formula.add_clause(Lit::pos(0), Lit::pos(1));
+        formula.add_clause(Lit::neg(0), Lit::neg(1));
+        formula.add_implication(Lit::pos(1), Lit::pos(2));
+        formula.add_unit(Lit::neg(2));
+        assert_eq!(formula.solve(), Some(vec![true, false, false]));
+
+        formula.add_unit(Lit::pos(1));
+        assert_eq!(formula.solve(), None);
+        assert_eq!(TwoSat::new(0).solve(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_two_sat_matches_brute_force() {
+        let mut rng = Lcg::new(17);
+        for _ in 0..300 {
+            let vars = 1 + rng.below(8);
+            let count = rng.below(14) as usize;
+            let clauses = random_clauses(&mut rng, vars, count, 2);
+            let mut formula = TwoSat::new(vars as usize);
+            for clause in &clauses {
+                let lits: Vec<Lit> = (0..vars as usize)
+                    .flat_map(|var| {
+                        let pos = (clause.positive >> var & 1 == 1).then(|| Lit::pos(var));
+                        let neg = (clause.negative >> var & 1 == 1).then(|| Lit::neg(var));
+                        pos.into_iter().chain(neg)
+                    })
+                    .collect();
+                formula.add_clause(lits[0], *lits.last().unwrap());
+            }
+            let expected = models(&clauses, vars);
+            match formula.solve() {
+                Some(values) => {
+                    let model = (0..vars as usize)
+                        .filter(|&var| values[var])
+                        .fold(0, |model, var| model | 1 << var);
+                    assert!(expected.contains(&model), "{:?}", clauses);
+                }
+                None => assert!(expected.is_empty(), "{:?}", clauses),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cnf_matches_brute_force() {
+        let mut rng = Lcg::new(8);
+        for _ in 0..300 {
+            let vars = 1 + rng.below(10);
+            let count = rng.below(30) as usize;
+            let clauses = random_clauses(&mut rng, vars, count, 3);
+            let mut cnf = Cnf::new(vars as usize).unwrap();
+            for &clause in &clauses {
+                cnf.add_clause(clause);
+            }
+            let expected = models(&clauses, vars);
+            match cnf.solve() {
+                Some(model) => assert!(expected.contains(&model), "{:?}", clauses),
+                None => assert!(expected.is_empty(), "{:?}", clauses),
+            }
+            let fewest = expected.iter().map(|model| model.count_ones()).min();
+            assert_eq!(
+                cnf.solve_min_true().map(u64::count_ones),
+                fewest,
+                "{:?}",
+                clauses
+            );
+        }
+    }
+
+    #[test]
+    fn test_cnf_xor() {
+        let mut cnf = Cnf::new(4).unwrap();
+        cnf.add_xor(0b0111, true).unwrap();
+        cnf.add_xor(0b1100, false).unwrap();
+        assert_eq!(cnf.clauses().len(), 4 + 2);
+        assert_eq!(models(cnf.clauses(), 4), [0b0001, 0b0010, 0b1100, 0b1111]);
+        assert_eq!(cnf.solve_min_true().map(u64::count_ones), Some(1));
+
+        cnf.add_xor(0, true).unwrap();
+        assert_eq!(cnf.solve(), None);
+        assert!(Cnf::new(64).is_ok());
+        assert!(Cnf::new(65).is_err());
+        assert_eq!(
+            cnf.add_xor(u64::MAX, true),
+            Err(Violation::TooMany {
+                what: "variables in an XOR",
+                count: 64,
+                max: 16
+            })
+        );
+    }
+}
@@ -0,0 +1,921 @@
+//! Backtracking searches: [`Backtrack`], a depth-first search over states
+//! with pruning, counting and iterative deepening, and the allocation
+//! helpers such searches lean on, a bump arena that packs many small slices
+//! into one buffer and a vector that keeps short states inline instead of
+//! on the heap.
+//!
+//! [`Backtrack`] keeps its own stack rather than recursing, so a chain of a
+//! million states is as safe as a bushy tree.  Solvers that do recurse can
+//! run on a bigger stack with [`with_stack_size`].
+//!
+//! For puzzles asking for the least value that works ("the fewest seconds
+//! until..."), where every larger value works too, [`binary_search_answer`]
+//! and [`exponential_search`] find it in logarithmically many checks.
+
+use crate::solution::{CancellationToken, Cancelled};
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut, Index};
+
+/// A handle to a slice allocated in a [`Bump`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Bump arena for slices of `T`.  Each allocation appends to one shared
+/// buffer and returns a [`Span`]; everything is freed together when the
+/// arena is cleared or dropped.  Replaces a `Vec<Vec<T>>` of many short
+/// lists with a single allocation.
+#[derive(Debug, Clone)]
+pub struct Bump<T> {
+    items: Vec<T>,
+}
+
+impl<T> Default for Bump<T> {
+    fn default() -> Self {
+        Bump { items: Vec::new() }
+    }
+}
+
+impl<T> Bump<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Bump {
+            items: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Copies `items` into the arena.
+    pub fn alloc(&mut self, items: impl IntoIterator<Item = T>) -> Span {
+        let start = self.items.len();
+        self.items.extend(items);
+        Span {
+            start,
+            end: self.items.len(),
+        }
+    }
+
+    pub fn get(&self, span: Span) -> &[T] {
+        &self.items[span.start..span.end]
+    }
+
+    /// Total items allocated so far.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Frees every allocation, keeping the buffer for reuse.  Spans handed
+    /// out earlier must not be used afterwards.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<T> Index<Span> for Bump<T> {
+    type Output = [T];
+
+    fn index(&self, span: Span) -> &[T] {
+        self.get(span)
+    }
+}
+
+/// A vector that stores up to `N` items inline and moves to the heap only
+/// when it grows past that.  Meant for search states such as bitboards and
+/// piece counts, which are cloned and hashed often and are usually short.
+#[derive(Clone)]
+pub struct InlineVec<T: Copy + Default, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+#[derive(Clone)]
+enum Storage<T, const N: usize> {
+    Inline { len: usize, items: [T; N] },
+    Heap(Vec<T>),
+}
+
+impl<T: Copy + Default, const N: usize> InlineVec<T, N> {
+    pub fn new() -> Self {
+        InlineVec {
+            storage: Storage::Inline {
+                len: 0,
+                items: [T::default(); N],
+            },
+        }
+    }
+
+    pub fn from_slice(items: &[T]) -> Self {
+        let mut vec = Self::new();
+        vec.extend_from_slice(items);
+        vec
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        match &self.storage {
+            Storage::Inline { len, items } => &items[..*len],
+            Storage::Heap(items) => items,
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match &mut self.storage {
+            Storage::Inline { len, items } => &mut items[..*len],
+            Storage::Heap(items) => items,
+        }
+    }
+
+    /// Whether the items have moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.extend_from_slice(&[item]);
+    }
+
+    pub fn extend_from_slice(&mut self, more: &[T]) {
+        match &mut self.storage {
+            Storage::Inline { len, items } if *len + more.len() <= N => {
+                items[*len..*len + more.len()].copy_from_slice(more);
+                *len += more.len();
+            }
+            Storage::Inline { len, items } => {
+                let mut heap = Vec::with_capacity((*len + more.len()).max(2 * N));
+                heap.extend_from_slice(&items[..*len]);
+                heap.extend_from_slice(more);
+                self.storage = Storage::Heap(heap);
+            }
+            Storage::Heap(items) => items.extend_from_slice(more),
+        }
+    }
+
+    /// Removes every item.  A spilled vector keeps its heap buffer.
+    pub fn clear(&mut self) {
+        match &mut self.storage {
+            Storage::Inline { len, .. } => *len = 0,
+            Storage::Heap(items) => items.clear(),
+        }
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for InlineVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Deref for InlineVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> DerefMut for InlineVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Borrow<[T]> for InlineVec<T, N> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Extend<T> for InlineVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T: Copy + Default, const N: usize> FromIterator<T> for InlineVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T: Copy + Default + PartialEq, const N: usize> PartialEq for InlineVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Copy + Default + Eq, const N: usize> Eq for InlineVec<T, N> {}
+
+/// Hashes like the equivalent slice, so a `HashSet<InlineVec<T, N>>` can be
+/// queried with a `&[T]`.
+impl<T: Copy + Default + Hash, const N: usize> Hash for InlineVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: Copy + Default + fmt::Debug, const N: usize> fmt::Debug for InlineVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// What a backtracking search finds out about a state from its `choices`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node<I> {
+    /// The state is a solution.
+    Solved,
+    /// The states one choice away; none means a dead end.
+    Branch(I),
+}
+
+/// Whether a search stops at its first solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    FirstSolution,
+    CountAll,
+}
+
+/// How much work a search did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// States expanded, solutions included.
+    pub nodes: u64,
+    /// States cut off by the prune test.
+    pub prunes: u64,
+    pub solutions: u64,
+    /// Deepest state reached, the starting state being depth 0.
+    pub max_depth: usize,
+}
+
+impl SearchStats {
+    fn add(&mut self, other: SearchStats) {
+        self.nodes += other.nodes;
+        self.prunes += other.prunes;
+        self.solutions += other.solutions;
+        self.max_depth = self.max_depth.max(other.max_depth);
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} pruned, {} solutions, depth {}",
+            self.nodes, self.prunes, self.solutions, self.max_depth
+        )
+    }
+}
+
+/// How often, in nodes, a search checks for cancellation.
+const CHECK_INTERVAL: u64 = 1024;
+
+/// A depth-first search over states.  Each state's successors come from a
+/// `choices` function, states failing a `prune` test are skipped along
+/// with everything below them, and each solution is handed to
+/// `on_solution`:
+///
+/// ```
+/// use rust_advent::search::{Backtrack, Mode, Node};
+///
+/// // Subsets of [3, 5, 7] summing to 10, as (next index, sum so far)
+/// let items = [3, 5, 7];
+/// let stats = Backtrack::new(Mode::CountAll)
+///     .run(
+///         (0, 0),
+///         |&(next, sum)| match (sum, items.get(next)) {
+///             (10, _) => Node::Solved,
+///             (_, None) => Node::Branch(vec![]),
+///             (_, Some(item)) => Node::Branch(vec![(next + 1, sum + item), (next + 1, sum)]),
+///         },
+///         |&(_, sum)| sum > 10,
+///         |_| {},
+///     )
+///     .unwrap();
+/// assert_eq!(stats.solutions, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Backtrack {
+    mode: Mode,
+    depth_limit: Option<usize>,
+    cancellation: CancellationToken,
+}
+
+impl Backtrack {
+    pub fn new(mode: Mode) -> Self {
+        Backtrack {
+            mode,
+            depth_limit: None,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// Treats states deeper than `depth` as dead ends.
+    pub fn with_depth_limit(mut self, depth: usize) -> Self {
+        self.depth_limit = Some(depth);
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Searches from `start`, giving up with `Err(Cancelled)` if the search
+    /// is cancelled first.
+    pub fn run<S, I>(
+        &self,
+        start: S,
+        mut choices: impl FnMut(&S) -> Node<I>,
+        mut prune: impl FnMut(&S) -> bool,
+        mut on_solution: impl FnMut(&S),
+    ) -> Result<SearchStats, Cancelled>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut stats = SearchStats::default();
+        self.search(
+            &start,
+            self.depth_limit,
+            &mut choices,
+            &mut prune,
+            &mut on_solution,
+            &mut stats,
+        )?;
+        Ok(stats)
+    }
+
+    /// Iterative deepening: searches with depth limits 0, 1, ... up to
+    /// `max_depth` until one finds a solution, so the first solution found
+    /// is a shallowest one.  Returns the depth it was found at, if any, and
+    /// the work done across every pass.
+    pub fn run_deepening<S, I>(
+        &self,
+        start: S,
+        max_depth: usize,
+        mut choices: impl FnMut(&S) -> Node<I>,
+        mut prune: impl FnMut(&S) -> bool,
+        mut on_solution: impl FnMut(&S),
+    ) -> Result<(Option<usize>, SearchStats), Cancelled>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut total = SearchStats::default();
+        for depth in 0..=max_depth {
+            let mut pass = SearchStats::default();
+            self.search(
+                &start,
+                Some(depth),
+                &mut choices,
+                &mut prune,
+                &mut on_solution,
+                &mut pass,
+            )?;
+            total.add(pass);
+            if pass.solutions > 0 {
+                return Ok((Some(depth), total));
+            }
+            if pass.max_depth < depth {
+                // The whole tree fit under this limit, so deeper passes
+                // would find nothing new
+                break;
+            }
+        }
+        Ok((None, total))
+    }
+
+    fn search<S, I>(
+        &self,
+        start: &S,
+        depth_limit: Option<usize>,
+        choices: &mut impl FnMut(&S) -> Node<I>,
+        prune: &mut impl FnMut(&S) -> bool,
+        on_solution: &mut impl FnMut(&S),
+        stats: &mut SearchStats,
+    ) -> Result<(), Cancelled>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        if prune(start) {
+            stats.prunes += 1;
+            return Ok(());
+        }
+        let mut visitor = Visitor {
+            mode: self.mode,
+            depth_limit,
+            cancellation: &self.cancellation,
+            choices,
+            prune,
+            on_solution,
+            stats,
+        };
+        visitor.visit(start).map(|_| ())
+    }
+}
+
+/// Runs an uncancellable [`Backtrack`] search with no depth limit.
+pub fn backtrack<S, I>(
+    mode: Mode,
+    start: S,
+    choices: impl FnMut(&S) -> Node<I>,
+    prune: impl FnMut(&S) -> bool,
+    on_solution: impl FnMut(&S),
+) -> SearchStats
+where
+    I: IntoIterator<Item = S>,
+{
+    Backtrack::new(mode)
+        .run(start, choices, prune, on_solution)
+        .expect("search cannot be cancelled")
+}
+
+struct Visitor<'a, C, P, F> {
+    mode: Mode,
+    depth_limit: Option<usize>,
+    cancellation: &'a CancellationToken,
+    choices: &'a mut C,
+    prune: &'a mut P,
+    on_solution: &'a mut F,
+    stats: &'a mut SearchStats,
+}
+
+/// What visiting one state asks of the search.
+enum Visit<I> {
+    /// A solution that ends the search.
+    Stop,
+    /// A solution or dead end to go back from.
+    Leaf,
+    Children(I),
+}
+
+impl<C, P, F> Visitor<'_, C, P, F> {
+    /// Searches below `start` with an explicit stack of children still to
+    /// try, one level per depth, so deep searches cannot overflow the call
+    /// stack.  Returns `Ok(true)` once the search should stop.
+    fn visit<S, I>(&mut self, start: &S) -> Result<bool, Cancelled>
+    where
+        C: FnMut(&S) -> Node<I>,
+        P: FnMut(&S) -> bool,
+        F: FnMut(&S),
+        I: IntoIterator<Item = S>,
+    {
+        let mut stack = match self.enter(start, 0)? {
+            Visit::Stop => return Ok(true),
+            Visit::Leaf => return Ok(false),
+            Visit::Children(children) => vec![children.into_iter()],
+        };
+        while let Some(children) = stack.last_mut() {
+            let Some(child) = children.next() else {
+                stack.pop();
+                continue;
+            };
+            if (self.prune)(&child) {
+                self.stats.prunes += 1;
+                continue;
+            }
+            match self.enter(&child, stack.len())? {
+                Visit::Stop => return Ok(true),
+                Visit::Leaf => {}
+                Visit::Children(children) => stack.push(children.into_iter()),
+            }
+        }
+        Ok(false)
+    }
+
+    fn enter<S, I>(&mut self, state: &S, depth: usize) -> Result<Visit<I>, Cancelled>
+    where
+        C: FnMut(&S) -> Node<I>,
+        F: FnMut(&S),
+    {
+        self.stats.nodes += 1;
+        self.stats.max_depth = self.stats.max_depth.max(depth);
+        if self.stats.nodes.is_multiple_of(CHECK_INTERVAL) && self.cancellation.is_cancelled() {
+            return Err(Cancelled);
+        }
+        match (self.choices)(state) {
+            Node::Solved => {
+                self.stats.solutions += 1;
+                (self.on_solution)(state);
+                Ok(if self.mode == Mode::FirstSolution {
+                    Visit::Stop
+                } else {
+                    Visit::Leaf
+                })
+            }
+            Node::Branch(_) if self.depth_limit.is_some_and(|limit| depth >= limit) => {
+                Ok(Visit::Leaf)
+            }
+            Node::Branch(children) => Ok(Visit::Children(children)),
+        }
+    }
+}
+
+/// Runs `f` on a thread with `bytes` of stack and returns its result, for
+/// recursive solvers that may go deeper than the default stack allows.
+/// The memory is reserved up front but only touched as the recursion
+/// reaches it.  A panic in `f` is passed on to the caller.
+#[cfg(feature = "native")]
+pub fn with_stack_size<T: Send>(bytes: usize, f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(bytes)
+            .spawn_scoped(scope, f)
+            .expect("spawning a search thread")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// Integers the answer searches run over.  The searches work in `i128`,
+/// so midpoints and steps never overflow.
+pub trait SearchInt: Copy {
+    const MAX: Self;
+    fn to_i128(self) -> i128;
+    /// Only called with values between two of `Self`.
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! search_int {
+    ($($t:ty),*) => {
+        $(impl SearchInt for $t {
+            const MAX: Self = <$t>::MAX;
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+            fn from_i128(value: i128) -> Self {
+                value as $t
+            }
+        })*
+    };
+}
+
+search_int!(i32, i64, u32, u64, usize);
+
+/// The least `x` in `lo..=hi` for which `works(x)`, given that `works` is
+/// false up to some point and true from there on; `None` if it is false
+/// throughout or the range is empty.  For the greatest `x` that works when
+/// the order is the other way round, search for the least that fails.
+pub fn binary_search_answer<T: SearchInt>(
+    lo: T,
+    hi: T,
+    mut works: impl FnMut(T) -> bool,
+) -> Option<T> {
+    least_working(lo.to_i128(), hi.to_i128(), &mut |x| works(T::from_i128(x))).map(T::from_i128)
+}
+
+/// Like [`binary_search_answer`] from `start` with no upper bound known:
+/// tries `start`, then steps of 1, 2, 4, ... until `works` holds, then
+/// searches the last step.  Takes about `2 log d` checks for an answer `d`
+/// past `start`, however large the type; `None` if nothing up to `T::MAX`
+/// works.
+pub fn exponential_search<T: SearchInt>(start: T, mut works: impl FnMut(T) -> bool) -> Option<T> {
+    let max = T::MAX.to_i128();
+    let mut failed = start.to_i128() - 1;
+    let mut step = 1;
+    loop {
+        let probe = (failed + step).min(max);
+        if works(T::from_i128(probe)) {
+            let within = least_working(failed + 1, probe - 1, &mut |x| works(T::from_i128(x)));
+            return Some(T::from_i128(within.unwrap_or(probe)));
+        }
+        if probe == max {
+            return None;
+        }
+        failed = probe;
+        step *= 2;
+    }
+}
+
+fn least_working(mut lo: i128, mut hi: i128, works: &mut impl FnMut(i128) -> bool) -> Option<i128> {
+    let mut found = None;
+    while lo <= hi {
+        let mid = (lo + hi).div_euclid(2);
+        if works(mid) {
+            found = Some(mid);
+            hi = mid - 1;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Queens placed so far, one column per row.
+    fn queens(n: usize) -> impl FnMut(&Vec<usize>) -> Node<Vec<Vec<usize>>> {
+        move |placed| {
+            if placed.len() == n {
+                return Node::Solved;
+            }
+            Node::Branch(
+                (0..n)
+                    .map(|col| {
+                        let mut next = placed.clone();
+                        next.push(col);
+                        next
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    #[allow(clippy::ptr_arg)]
+    fn attacked(placed: &Vec<usize>) -> bool {
+        let Some((&col, rest)) = placed.split_last() else {
+            return false;
+        };
+        let row = rest.len();
+        rest.iter()
+            .enumerate()
+            .any(|(r, &c)| c == col || row - r == c.abs_diff(col))
+    }
+
+    #[test]
+    fn test_backtrack_counts_queens() {
+        for (n, expected) in [(1, 1), (4, 2), (6, 4), (8, 92)] {
+            let stats = backtrack(Mode::CountAll, vec![], queens(n), attacked, |_| {});
+            assert_eq!(stats.solutions, expected, "{} queens", n);
+            assert_eq!(stats.max_depth, n);
+        }
+    }
+
+    #[test]
+    fn test_backtrack_prunes() {
+        let pruned = backtrack(Mode::CountAll, vec![], queens(6), attacked, |_| {});
+        let unpruned = backtrack(Mode::CountAll, vec![], queens(6), |_| false, |_| {});
+        assert!(pruned.prunes > 0);
+        assert_eq!(unpruned.prunes, 0);
+        assert!(pruned.nodes < unpruned.nodes);
+        assert_eq!(unpruned.solutions, 6u64.pow(6));
+    }
+
+    #[test]
+    fn test_backtrack_first_solution() {
+        let mut found = vec![];
+        let all = backtrack(Mode::CountAll, vec![], queens(8), attacked, |_| {});
+        let first = backtrack(Mode::FirstSolution, vec![], queens(8), attacked, |state| {
+            found.push(state.clone())
+        });
+        assert_eq!(first.solutions, 1);
+        assert!(first.nodes < all.nodes);
+        assert_eq!(found, [vec![0, 4, 7, 5, 2, 6, 1, 3]]);
+    }
+
+    #[test]
+    fn test_run_deepening() {
+        // Sums of 3s and 5s reaching 15, which depth-first with the 3s
+        // tried first would reach as five 3s rather than three 5s
+        let choices = |&(sum, steps): &(u32, u32)| {
+            if sum == 15 {
+                Node::Solved
+            } else {
+                Node::Branch([3, 5].map(|step| (sum + step, steps * 10 + step)))
+            }
+        };
+        let too_big = |&(sum, _): &(u32, u32)| sum > 15;
+        let mut found = vec![];
+        let first = backtrack(
+            Mode::FirstSolution,
+            (0, 0),
+            choices,
+            too_big,
+            |&(_, steps)| found.push(steps),
+        );
+        let (depth, stats) = Backtrack::new(Mode::FirstSolution)
+            .run_deepening((0, 0), 10, choices, too_big, |&(_, steps)| {
+                found.push(steps)
+            })
+            .unwrap();
+        assert_eq!(first.max_depth, 5);
+        assert_eq!(depth, Some(3));
+        assert_eq!(found, [33333, 555]);
+        assert_eq!(stats.solutions, 1);
+
+        let (depth, _) = Backtrack::new(Mode::FirstSolution)
+            .run_deepening((0, 0), 2, choices, too_big, |_| {})
+            .unwrap();
+        assert_eq!(depth, None);
+    }
+
+    #[test]
+    fn test_run_deepening_stops_when_tree_is_exhausted() {
+        let (depth, stats) = Backtrack::new(Mode::FirstSolution)
+            .run_deepening(
+                0,
+                100,
+                |&depth: &u32| Node::Branch((depth < 3).then_some(depth + 1)),
+                |_| false,
+                |_| {},
+            )
+            .unwrap();
+        assert_eq!(depth, None);
+        assert_eq!(stats.max_depth, 3);
+        // Passes with limits 0 through 4, the last finding nothing deeper
+        assert_eq!(stats.nodes, 1 + 2 + 3 + 4 + 4);
+    }
+
+    #[test]
+    fn test_backtrack_cancellation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let search = Backtrack::new(Mode::CountAll).with_cancellation(token);
+        assert_eq!(
+            search.run(vec![], queens(8), attacked, |_| {}),
+            Err(Cancelled)
+        );
+        // Searches smaller than one check interval finish regardless
+        assert_eq!(
+            search
+                .run(vec![], queens(4), attacked, |_| {})
+                .unwrap()
+                .solutions,
+            2
+        );
+    }
+
+    #[test]
+    fn test_backtrack_depth_limit() {
+        let stats = Backtrack::new(Mode::CountAll)
+            .with_depth_limit(3)
+            .run(vec![], queens(8), attacked, |_| {})
+            .unwrap();
+        assert_eq!(stats.solutions, 0);
+        assert_eq!(stats.max_depth, 3);
+    }
+
+    #[test]
+    fn test_backtrack_deep_chain() {
+        // One child per state for a million levels
+        let n = 1_000_000u32;
+        let stats = backtrack(
+            Mode::CountAll,
+            0u32,
+            |&depth| match depth {
+                _ if depth == n => Node::Solved,
+                _ => Node::Branch(Some(depth + 1)),
+            },
+            |_| false,
+            |_| {},
+        );
+        assert_eq!(stats.solutions, 1);
+        assert_eq!(stats.max_depth, n as usize);
+        assert_eq!(stats.nodes, u64::from(n) + 1);
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_with_stack_size() {
+        fn depth(n: u64) -> u64 {
+            if n == 0 {
+                0
+            } else {
+                1 + depth(std::hint::black_box(n - 1))
+            }
+        }
+        assert_eq!(with_stack_size(256 << 20, || depth(1_000_000)), 1_000_000);
+        let panicked = std::panic::catch_unwind(|| with_stack_size(1 << 20, || panic!("deep")));
+        assert!(panicked.is_err());
+    }
+
+    #[test]
+    fn test_bump() {
+        let mut arena = Bump::new();
+        let a = arena.alloc([1, 2, 3]);
+        let empty = arena.alloc([]);
+        let b = arena.alloc(vec![4, 5]);
+        assert_eq!(&arena[a], &[1, 2, 3]);
+        assert_eq!(&arena[b], &[4, 5]);
+        assert!(empty.is_empty());
+        assert_eq!(arena.get(empty), &[] as &[i32]);
+        assert_eq!(arena.len(), 5);
+
+        arena.clear();
+        assert!(arena.is_empty());
+        let c = arena.alloc([6]);
+        assert_eq!(&arena[c], &[6]);
+    }
+
+    #[test]
+    fn test_inline_vec_spills() {
+        let mut vec: InlineVec<u64, 3> = InlineVec::new();
+        vec.push(1);
+        vec.extend_from_slice(&[2, 3]);
+        assert!(!vec.spilled());
+        assert_eq!(&vec[..], &[1, 2, 3]);
+
+        vec.push(4);
+        assert!(vec.spilled());
+        assert_eq!(&vec[..], &[1, 2, 3, 4]);
+        vec[0] = 9;
+        assert_eq!(vec.first(), Some(&9));
+
+        vec.clear();
+        assert!(vec.is_empty());
+        vec.extend(5..7);
+        assert_eq!(&vec[..], &[5, 6]);
+    }
+
+    #[test]
+    fn test_inline_vec_equality_ignores_storage() {
+        let inline: InlineVec<u64, 4> = InlineVec::from_slice(&[1, 2]);
+        let mut spilled: InlineVec<u64, 4> = (0..5).collect();
+        spilled.clear();
+        spilled.extend_from_slice(&[1, 2]);
+        assert!(spilled.spilled());
+        assert_eq!(inline, spilled);
+        assert_eq!(format!("{:?}", inline), "[1, 2]");
+    }
+
+    #[test]
+    fn test_inline_vec_hash_set_lookup_by_slice() {
+        let mut set: HashSet<InlineVec<u64, 2>> = HashSet::new();
+        set.insert(InlineVec::from_slice(&[1, 2]));
+        set.insert(InlineVec::from_slice(&[1, 2, 3]));
+        assert!(set.contains(&[1, 2][..]));
+        assert!(set.contains(&[1, 2, 3][..]));
+        assert!(!set.contains(&[1][..]));
+        assert!(!set.insert(InlineVec::from_slice(&[1, 2])));
+    }
+
+    #[test]
+    fn test_binary_search_answer() {
+        let mut checks = 0;
+        let found = binary_search_answer(0u64, 1_000_000, |x| {
+            checks += 1;
+            x * x >= 2_000_000
+        });
+        assert_eq!(found, Some(1415));
+        assert!(checks <= 20, "{} checks", checks);
+
+        assert_eq!(binary_search_answer(5i64, 9, |_| true), Some(5));
+        assert_eq!(binary_search_answer(5i64, 9, |x| x >= 9), Some(9));
+        assert_eq!(binary_search_answer(5i64, 9, |_| false), None);
+        assert_eq!(binary_search_answer(7u32, 7, |_| true), Some(7));
+        assert_eq!(binary_search_answer(9u32, 5, |_| true), None);
+        assert_eq!(binary_search_answer(-10i32, 10, |x| x >= -3), Some(-3));
+    }
+
+    #[test]
+    fn test_binary_search_answer_extremes() {
+        for threshold in [i64::MIN, i64::MIN + 1, -1, 0, i64::MAX - 1, i64::MAX] {
+            assert_eq!(
+                binary_search_answer(i64::MIN, i64::MAX, |x| x >= threshold),
+                Some(threshold)
+            );
+        }
+        for threshold in [0, 1, u64::MAX - 1, u64::MAX] {
+            assert_eq!(
+                binary_search_answer(0, u64::MAX, |x| x >= threshold),
+                Some(threshold)
+            );
+        }
+        assert_eq!(binary_search_answer(0, usize::MAX, |_| false), None);
+    }
+
+    #[test]
+    fn test_exponential_search() {
+        assert_eq!(exponential_search(10u64, |_| true), Some(10));
+        assert_eq!(exponential_search(10u64, |x| x >= 11), Some(11));
+        let mut checks = 0;
+        let found = exponential_search(0u64, |x| {
+            checks += 1;
+            x >= 1 << 40
+        });
+        assert_eq!(found, Some(1 << 40));
+        assert!(checks <= 2 * 41 + 1, "{} checks", checks);
+
+        assert_eq!(exponential_search(-100i64, |x| x >= -37), Some(-37));
+        assert_eq!(exponential_search(0u64, |x| x == u64::MAX), Some(u64::MAX));
+        assert_eq!(exponential_search(u32::MAX, |_| true), Some(u32::MAX));
+        assert_eq!(exponential_search(0u32, |_| false), None);
+        assert_eq!(
+            exponential_search(i64::MIN, |x| x == i64::MAX),
+            Some(i64::MAX)
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! A small HTTP front end to [`playground::solve_with`] for `advent serve`.
+//!
+//! `POST /solve/{year}/{day}/{part}` with the puzzle input as the body is
+//! answered with JSON such as
+//!
+//! ```text
+//! {"year":2025,"day":10,"part":1,"answer":"7","elapsed_ms":0.41}
+//! ```
+//!
+//! or `{"error": "..."}` with a 4xx or 5xx status.  Each connection gets one
+//! answer and is closed; there is no keep-alive or chunked encoding.
+
+use crate::Error;
+use crate::json;
+use crate::json::Value;
+use crate::playground::{self, DAYS, YEAR};
+use crate::solution::{CancellationToken, Context};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+/// Largest input accepted, well above any real puzzle input.
+pub const MAX_BODY: usize = 1 << 20;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The parts of a request the server looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Reads one request.  A malformed one is answered with the response to
+    /// send back instead.
+    pub fn read(reader: &mut impl BufRead) -> Result<Request, Response> {
+        let bad_request = |message: &str| Response::error(400, message);
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|_| bad_request("unreadable request"))?;
+        let mut words = line.split_whitespace();
+        let (Some(method), Some(path)) = (words.next(), words.next()) else {
+            return Err(bad_request("malformed request line"));
+        };
+        let (method, path) = (method.to_string(), path.to_string());
+
+        let mut length = 0;
+        loop {
+            line.clear();
+            reader
+                .read_line(&mut line)
+                .map_err(|_| bad_request("unreadable headers"))?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad_request("invalid Content-Length"))?;
+            }
+        }
+        if length > MAX_BODY {
+            return Err(Response::error(
+                413,
+                &format!("input is over {} bytes", MAX_BODY),
+            ));
+        }
+        let mut body = vec![0; length];
+        reader
+            .read_exact(&mut body)
+            .map_err(|_| bad_request("body shorter than Content-Length"))?;
+        Ok(Request { method, path, body })
+    }
+}
+
+/// A status and a JSON body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    pub fn error(status: u16, message: &str) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let body = self.body.to_string();
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            body.len(),
+            body
+        )?;
+        out.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Content Too Large",
+        422 => "Unprocessable Content",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Answers `request`, giving each solve `timeout` before it is cancelled.
+pub fn handle(request: &Request, timeout: Option<Duration>) -> Response {
+    let Some(target) = request.path.strip_prefix("/solve/") else {
+        return Response::error(404, "expected /solve/{year}/{day}/{part}");
+    };
+    let numbers: Vec<Option<u32>> = target.split('/').map(|n| n.parse().ok()).collect();
+    let [Some(year), Some(day), Some(part)] = numbers[..] else {
+        return Response::error(404, "expected /solve/{year}/{day}/{part}");
+    };
+    if request.method != "POST" {
+        return Response::error(405, "send the input with POST");
+    }
+    if year != YEAR || !DAYS.contains(&day) || !(1..=2).contains(&part) {
+        let days: Vec<String> = DAYS.iter().map(u32::to_string).collect();
+        return Response::error(
+            404,
+            &format!(
+                "only parts 1 and 2 of {} days {} can be solved here",
+                YEAR,
+                days.join(", ")
+            ),
+        );
+    }
+    let Ok(input) = std::str::from_utf8(&request.body) else {
+        return Response::error(400, "input is not UTF-8");
+    };
+
+    let cancellation = timeout.map_or_else(CancellationToken::new, CancellationToken::with_timeout);
+    let ctx = Context::new().with_cancellation(cancellation);
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        playground::solve_with(year, day, part, input, &ctx)
+    }));
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    match result {
+        Ok(Ok(answer)) => Response {
+            status: 200,
+            body: json!({
+                "year": u64::from(year),
+                "day": u64::from(day),
+                "part": u64::from(part),
+                "answer": answer.to_string(),
+                "elapsed_ms": elapsed_ms,
+            }),
+        },
+        Ok(Err(err)) => {
+            let status = match err {
+                Error::Parse { .. } | Error::Invalid(_) => 400,
+                Error::Cancelled => 503,
+                _ => 422,
+            };
+            let mut response = Response::error(status, &err.render(input));
+            if let Value::Object(fields) = &mut response.body {
+                fields.push(("elapsed_ms".to_string(), Value::from(elapsed_ms)));
+            }
+            response
+        }
+        Err(_) => Response::error(500, "the solver panicked"),
+    }
+}
+
+/// Answers requests on `listener`, one thread per connection, logging each
+/// request to stderr.  Never returns while the listener accepts.
+pub fn serve(listener: TcpListener, timeout: Option<Duration>) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                std::thread::spawn(move || {
+                    if let Err(err) = answer(stream, timeout) {
+                        eprintln!("warning: failed to answer: {}", err);
+                    }
+                });
+            }
+            Err(err) => eprintln!("warning: failed to accept a connection: {}", err),
+        }
+    }
+}
+
+fn answer(stream: TcpStream, timeout: Option<Duration>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (summary, response) = match Request::read(&mut reader) {
+        Ok(request) => (
+            format!("{} {}", request.method, request.path),
+            handle(&request, timeout),
+        ),
+        Err(response) => ("-".to_string(), response),
+    };
+    eprintln!("{} {}", summary, response.status);
+    response.write_to(&mut &stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACHINES: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/day10_example.txt"
+    ));
+
+    fn post(path: &str, body: &str) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw =
+            "POST /solve/2025/10/1 HTTP/1.1\r\nHost: x\r\ncontent-length: 5\r\n\r\nab\ncdextra";
+        let request = Request::read(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request, post("/solve/2025/10/1", "ab\ncd"));
+
+        let raw = "GET / HTTP/1.1\r\n\r\n";
+        assert!(Request::read(&mut raw.as_bytes()).unwrap().body.is_empty());
+        let raw = "POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\nshort";
+        assert_eq!(Request::read(&mut raw.as_bytes()).unwrap_err().status, 400);
+        let raw = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY + 1
+        );
+        assert_eq!(Request::read(&mut raw.as_bytes()).unwrap_err().status, 413);
+        assert_eq!(
+            Request::read(&mut "\r\n".as_bytes()).unwrap_err().status,
+            400
+        );
+    }
+
+    #[test]
+    fn test_handle_answers() {
+        let response = handle(&post("/solve/2025/10/2", MACHINES), None);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.get("answer").unwrap().as_str(), Some("33"));
+        assert_eq!(response.body.get("part").unwrap().as_u64(), Some(2));
+        assert!(response.body.get("elapsed_ms").unwrap().as_f64().is_some());
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        let status = |request: &Request| handle(request, None).status;
+        assert_eq!(status(&post("/", "")), 404);
+        assert_eq!(status(&post("/solve/2025/10", "")), 404);
+        assert_eq!(status(&post("/solve/2025/9/1", "")), 404);
+        assert_eq!(status(&post("/solve/2025/10/3", "")), 404);
+        assert_eq!(status(&post("/solve/2024/10/1", "")), 404);
+        let get = Request {
+            method: "GET".to_string(),
+            ..post("/solve/2025/10/1", "")
+        };
+        assert_eq!(status(&get), 405);
+
+        let response = handle(&post("/solve/2025/10/1", "[.#] (9) {1,1}"), None);
+        assert_eq!(response.status, 400);
+        let error = response.body.get("error").unwrap().as_str().unwrap();
+        assert!(error.contains("[.#] (9) {1,1}"), "{}", error);
+
+        let response = handle(&post("/solve/2025/10/2", MACHINES), Some(Duration::ZERO));
+        assert_eq!(response.status, 503);
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        Response::error(404, "nope").write_to(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", text);
+        assert!(text.contains("Content-Length: 16\r\n"), "{}", text);
+        assert!(text.ends_with("\r\n\r\n{\"error\":\"nope\"}"), "{}", text);
+    }
+}
@@ -0,0 +1,703 @@
+//! Row-by-row simulations, such as beams falling through a manifold: a
+//! state is carried down the rows of a grid, each row turning it into the
+//! next.  [`row_automaton`] follows one state; [`row_distribution`] follows
+//! every state a branching step can reach, counting the paths to each in
+//! any [`PathCount`] so that a count too large for it is reported rather
+//! than wrapped.
+//!
+//! [`Columns`] is the usual state, a set of occupied columns kept as a
+//! [`WideMask`] however wide the grid, so a step can move every beam at
+//! once with shifts and masks.
+//!
+//! Cellular automata such as Conway's Game of Life step a whole grid at
+//! once under a [`LifeRule`]: [`automaton_step`] for a grid whose edges
+//! stay dead, and [`HashLife`] for the unbounded plane, which memoizes
+//! repeated blocks so that even 2^40 generations take moments.  In three
+//! or more dimensions, as in Conway cubes, [`sparse_automaton_step`] steps
+//! the set of live points directly.
+
+use crate::grid::{Connectivity, neighbours};
+use crate::paths::{Overflow, PathCount};
+use crate::{FastMap, FastSet, PointN, WideMask};
+use std::hash::Hash;
+
+/// A set of columns of a row, as many as the grid is wide.
+pub type Columns = WideMask;
+
+/// Carries `initial` down `rows`, replacing the state with
+/// `transition(state, row)` at each row, and returns the final state.
+pub fn row_automaton<S, R: Copy>(
+    initial: S,
+    rows: impl IntoIterator<Item = R>,
+    mut transition: impl FnMut(&S, R) -> S,
+) -> S {
+    rows.into_iter()
+        .fold(initial, |state, row| transition(&state, row))
+}
+
+/// Follows every path from `initial` down `rows`.  At each row,
+/// `transition(state, row, next)` pushes onto `next` each state one path
+/// through `state` can move to; equal states merge, adding their path
+/// counts.  Returns the states after the last row with the number of
+/// paths reaching each, or the overflow if a count outgrows `C`.
+///
+/// The number of states can grow exponentially; prefer a per-column count
+/// when paths do not interact.
+pub fn row_distribution<C, S, R>(
+    initial: S,
+    rows: impl IntoIterator<Item = R>,
+    mut transition: impl FnMut(&S, R, &mut Vec<S>),
+) -> Result<FastMap<S, C>, Overflow>
+where
+    C: PathCount,
+    S: Eq + Hash,
+    R: Copy,
+{
+    let mut states: FastMap<S, C> = FastMap::default();
+    states.insert(initial, C::one());
+    let mut next = Vec::new();
+    for row in rows {
+        let mut merged: FastMap<S, C> = FastMap::default();
+        for (state, count) in &states {
+            transition(state, row, &mut next);
+            for successor in next.drain(..) {
+                merged
+                    .entry(successor)
+                    .or_insert_with(C::zero)
+                    .add_paths(count)?;
+            }
+        }
+        states = merged;
+    }
+    Ok(states)
+}
+
+/// A life-like rule: a dead cell comes alive when its number of live
+/// neighbours is in the birth set, and a live one stays alive when it is in
+/// the survival set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    /// Bit `n` is set when `n` live neighbours bring a cell alive.
+    birth: u16,
+    /// Bit `n` is set when `n` live neighbours keep a cell alive.
+    survival: u16,
+}
+
+impl LifeRule {
+    /// Conway's Game of Life, B3/S23.
+    pub const CONWAY: LifeRule = LifeRule {
+        birth: 1 << 3,
+        survival: (1 << 2) | (1 << 3),
+    };
+
+    /// A rule that gives birth on 0 neighbours, a B0 rule, brings every
+    /// empty cell alive at once, so only [`automaton_step`], whose grid has
+    /// edges, can follow it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a count is over 8, the most neighbours a cell has.
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let mask = |counts: &[usize]| {
+            counts.iter().fold(0, |mask, &count| {
+                assert!(count <= 8, "a cell has at most 8 neighbours, not {}", count);
+                mask | (1 << count)
+            })
+        };
+        LifeRule {
+            birth: mask(birth),
+            survival: mask(survival),
+        }
+    }
+
+    /// Whether a cell is alive next generation.  Counts over 8, possible
+    /// in more dimensions, are in neither set.
+    pub fn next(self, alive: bool, live_neighbours: usize) -> bool {
+        let counts = if alive { self.survival } else { self.birth };
+        live_neighbours <= 8 && counts & (1 << live_neighbours) != 0
+    }
+
+    /// Whether cells with no live neighbours are born.
+    pub fn births_from_nothing(self) -> bool {
+        self.birth & 1 != 0
+    }
+}
+
+/// The grid one generation on under `rule`, with every cell past its edges
+/// dead.  Rows may differ in length.
+pub fn automaton_step(grid: &[Vec<bool>], rule: LifeRule) -> Vec<Vec<bool>> {
+    grid.iter()
+        .enumerate()
+        .map(|(row, cells)| {
+            (0..cells.len())
+                .map(|col| {
+                    let live = neighbours(grid, (row, col), Connectivity::Eight)
+                        .filter(|&(r, c)| grid[r][c])
+                        .count();
+                    rule.next(cells[col], live)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The live points one generation on under `rule`, in any number of
+/// dimensions, each point's neighbours being the `3^D - 1` of
+/// [`PointN::neighbours`].  Only points next to a live one are looked at,
+/// so the space is unbounded.
+///
+/// # Panics
+///
+/// Panics on a B0 rule, which would bring infinitely many points alive.
+pub fn sparse_automaton_step<const D: usize>(
+    live: &FastSet<PointN<D>>,
+    rule: LifeRule,
+) -> FastSet<PointN<D>> {
+    assert!(
+        !rule.births_from_nothing(),
+        "a B0 rule fills the unbounded space"
+    );
+    let mut counts: FastMap<PointN<D>, usize> = FastMap::default();
+    for &point in live {
+        for next in point.neighbours() {
+            *counts.entry(next).or_insert(0) += 1;
+        }
+    }
+    let mut stepped: FastSet<PointN<D>> = counts
+        .into_iter()
+        .filter(|&(point, count)| rule.next(live.contains(&point), count))
+        .map(|(point, _)| point)
+        .collect();
+    // Live points with no live neighbours were never counted
+    if rule.next(true, 0) {
+        stepped.extend(
+            live.iter()
+                .filter(|p| p.neighbours().all(|n| !live.contains(&n))),
+        );
+    }
+    stepped
+}
+
+type NodeId = usize;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// A square of side `2^level`, made of four squares of the level below in
+/// the order top left, top right, bottom left, bottom right.  Level 0 is a
+/// single cell, [`DEAD`] or [`ALIVE`].
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u32,
+    quadrants: [NodeId; 4],
+    population: u64,
+}
+
+/// A life-like automaton on the unbounded plane, stepped by Gosper's
+/// HashLife.  The plane is a quadtree in which equal squares are stored
+/// once, and the future of each square's middle is remembered, so
+/// patterns that repeat themselves in space or time advance in
+/// logarithmically many steps.
+///
+/// Cells are `(row, column)` pairs, starting from the grid's top left; they
+/// may go negative as the pattern grows.  Nothing is ever forgotten, so
+/// memory grows with the variety of the pattern.
+#[derive(Debug, Clone)]
+pub struct HashLife {
+    rule: LifeRule,
+    nodes: Vec<Node>,
+    interned: FastMap<[NodeId; 4], NodeId>,
+    /// The middle of a node, `2^j` generations on, keyed by node and `j`.
+    successors: FastMap<(NodeId, u32), NodeId>,
+    /// The empty node of each level.
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// The cell at the root's top left.
+    corner: (i64, i64),
+    generation: u64,
+}
+
+impl HashLife {
+    /// The plane with the live cells of `grid`, under `rule`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on a B0 rule, which would bring the whole plane alive.
+    pub fn new(rule: LifeRule, grid: &[Vec<bool>]) -> Self {
+        assert!(
+            !rule.births_from_nothing(),
+            "a B0 rule fills the unbounded plane"
+        );
+        let leaf = |population| Node {
+            level: 0,
+            quadrants: [DEAD; 4],
+            population,
+        };
+        let mut life = HashLife {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            interned: FastMap::default(),
+            successors: FastMap::default(),
+            empty: vec![DEAD],
+            root: DEAD,
+            corner: (0, 0),
+            generation: 0,
+        };
+        let side = grid.iter().map(Vec::len).max().unwrap_or(0);
+        let level = side
+            .max(grid.len())
+            .max(4)
+            .next_power_of_two()
+            .trailing_zeros();
+        life.root = life.build(grid, level, 0, 0);
+        life
+    }
+
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    /// The generations advanced since the start.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The number of live cells.
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    /// The live cells in reading order.
+    pub fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        self.collect(self.root, self.corner, &mut cells);
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Moves the pattern `generations` on, one power of two at a time.
+    /// The plane's coordinates are `i64`, which a single advance of much
+    /// more than 2^60 generations outgrows.
+    pub fn advance(&mut self, generations: u64) {
+        for j in (0..u64::BITS).filter(|&j| generations >> j & 1 == 1) {
+            // A pattern spreads at most a cell a generation, so it must sit
+            // in the middle quarter of a root at least 2^(j + 3) across to
+            // stay inside the successor's middle half
+            while self.nodes[self.root].level < j + 3 || !self.is_padded(self.root) {
+                self.expand();
+            }
+            self.expand();
+            let level = self.nodes[self.root].level;
+            self.root = self.successor(self.root, j);
+            let shift = 1 << (level - 2);
+            self.corner = (self.corner.0 + shift, self.corner.1 + shift);
+        }
+        // Trim the empty border back off
+        while self.nodes[self.root].level > 2 && self.is_padded(self.root) {
+            let level = self.nodes[self.root].level;
+            let [nw, ne, sw, se] = self.nodes[self.root].quadrants;
+            let middle = [(nw, 3), (ne, 2), (sw, 1), (se, 0)].map(|(q, idx)| self.quadrant(q, idx));
+            self.root = self.join(middle);
+            let shift = 1 << (level - 2);
+            self.corner = (self.corner.0 + shift, self.corner.1 + shift);
+        }
+        self.generation += generations;
+    }
+
+    fn build(&mut self, grid: &[Vec<bool>], level: u32, row: usize, col: usize) -> NodeId {
+        if row >= grid.len() {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return match grid[row].get(col) {
+                Some(true) => ALIVE,
+                _ => DEAD,
+            };
+        }
+        let half = 1 << (level - 1);
+        let quadrants = [(0, 0), (0, half), (half, 0), (half, half)]
+            .map(|(dr, dc)| self.build(grid, level - 1, row + dr, col + dc));
+        self.join(quadrants)
+    }
+
+    /// The node made of `quadrants`, stored once however often it is made.
+    fn join(&mut self, quadrants: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.interned.get(&quadrants) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            level: self.nodes[quadrants[0]].level + 1,
+            quadrants,
+            population: quadrants.iter().map(|&q| self.nodes[q].population).sum(),
+        });
+        self.interned.insert(quadrants, id);
+        id
+    }
+
+    fn empty(&mut self, level: u32) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().unwrap();
+            let next = self.join([below; 4]);
+            self.empty.push(next);
+        }
+        self.empty[level as usize]
+    }
+
+    fn quadrant(&self, id: NodeId, idx: usize) -> NodeId {
+        self.nodes[id].quadrants[idx]
+    }
+
+    /// Doubles the root's size, keeping it in the middle.
+    fn expand(&mut self) {
+        let Node {
+            level,
+            quadrants: [nw, ne, sw, se],
+            ..
+        } = self.nodes[self.root];
+        let e = self.empty(level - 1);
+        let quadrants = [[e, e, e, nw], [e, e, ne, e], [e, sw, e, e], [se, e, e, e]];
+        let quadrants = quadrants.map(|quadrant| self.join(quadrant));
+        self.root = self.join(quadrants);
+        let shift = 1 << (level - 1);
+        self.corner = (self.corner.0 - shift, self.corner.1 - shift);
+    }
+
+    /// Whether every live cell of `id` lies in its middle half.
+    fn is_padded(&self, id: NodeId) -> bool {
+        let [nw, ne, sw, se] = self.nodes[id].quadrants;
+        let middle = [(nw, 3), (ne, 2), (sw, 1), (se, 0)]
+            .iter()
+            .map(|&(q, idx)| self.nodes[self.quadrant(q, idx)].population)
+            .sum::<u64>();
+        middle == self.nodes[id].population
+    }
+
+    /// The middle half of `id`, `2^j` generations on; `j` beyond what the
+    /// node's size allows, its level less two, is cut down to that.
+    fn successor(&mut self, id: NodeId, j: u32) -> NodeId {
+        let node = self.nodes[id];
+        if node.population == 0 {
+            return node.quadrants[0];
+        }
+        let j = j.min(node.level - 2);
+        if let Some(&next) = self.successors.get(&(id, j)) {
+            return next;
+        }
+        let next = if node.level == 2 {
+            self.step_4x4(id)
+        } else {
+            let [nw, ne, sw, se] = node.quadrants;
+            let [a, b, c, d] = node.quadrants.map(|q| self.nodes[q].quadrants);
+            // Nine overlapping squares of half the size, in reading order
+            let squares = [
+                nw,
+                self.join([a[1], b[0], a[3], b[2]]),
+                ne,
+                self.join([a[2], a[3], c[0], c[1]]),
+                self.join([a[3], b[2], c[1], d[0]]),
+                self.join([b[2], b[3], d[0], d[1]]),
+                sw,
+                self.join([c[1], d[0], c[3], d[2]]),
+                se,
+            ];
+            let moved = squares.map(|square| self.successor(square, j));
+            let groups = [[0, 1, 3, 4], [1, 2, 4, 5], [3, 4, 6, 7], [4, 5, 7, 8]];
+            let quadrants = if j < node.level - 2 {
+                // Far enough on already: each quadrant is the middle of four
+                groups.map(|[p, q, r, s]| {
+                    self.join([
+                        self.quadrant(moved[p], 3),
+                        self.quadrant(moved[q], 2),
+                        self.quadrant(moved[r], 1),
+                        self.quadrant(moved[s], 0),
+                    ])
+                })
+            } else {
+                // Halfway: move each group of four the rest of the way
+                groups.map(|group| {
+                    let square = self.join(group.map(|idx| moved[idx]));
+                    self.successor(square, j)
+                })
+            };
+            self.join(quadrants)
+        };
+        self.successors.insert((id, j), next);
+        next
+    }
+
+    /// The middle 2x2 of a 4x4 node one generation on, cell by cell.
+    fn step_4x4(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (quadrant, (row, col)) in
+            self.nodes[id]
+                .quadrants
+                .into_iter()
+                .zip([(0, 0), (0, 2), (2, 0), (2, 2)])
+        {
+            for (idx, &cell) in self.nodes[quadrant].quadrants.iter().enumerate() {
+                cells[row + idx / 2][col + idx % 2] = cell == ALIVE;
+            }
+        }
+        let next = |row: usize, col: usize| {
+            let live = (row - 1..=row + 1)
+                .flat_map(|r| (col - 1..=col + 1).map(move |c| (r, c)))
+                .filter(|&(r, c)| (r, c) != (row, col) && cells[r][c])
+                .count();
+            if self.rule.next(cells[row][col], live) {
+                ALIVE
+            } else {
+                DEAD
+            }
+        };
+        let middle = [next(1, 1), next(1, 2), next(2, 1), next(2, 2)];
+        self.join(middle)
+    }
+
+    fn collect(&self, id: NodeId, (row, col): (i64, i64), cells: &mut Vec<(i64, i64)>) {
+        let node = &self.nodes[id];
+        if node.population == 0 {
+            return;
+        }
+        if node.level == 0 {
+            cells.push((row, col));
+            return;
+        }
+        let half = 1 << (node.level - 1);
+        let corners = [
+            (row, col),
+            (row, col + half),
+            (row + half, col),
+            (row + half, col + half),
+        ];
+        for (quadrant, corner) in node.quadrants.into_iter().zip(corners) {
+            self.collect(quadrant, corner, cells);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+    use crate::paths::Modular;
+
+    fn cells(text: &str) -> Vec<Vec<bool>> {
+        text.lines()
+            .map(|line| line.bytes().map(|b| b == b'#').collect())
+            .collect()
+    }
+
+    fn live(grid: &[Vec<bool>]) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        for (row, line) in grid.iter().enumerate() {
+            for (col, &alive) in line.iter().enumerate() {
+                if alive {
+                    cells.push((row as i64, col as i64));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_row_automaton() {
+        // Each row shifts the set right by its amount, dropping columns
+        // that fall off a ten-column grid
+        let end = row_automaton(Columns::single(10, 1), [2, 3, 5], |cols, shift| {
+            cols << shift
+        });
+        assert_eq!(end, Columns::new(10));
+        let end = row_automaton(0, [1, 2, 3], |sum, row| sum + row);
+        assert_eq!(end, 6);
+    }
+
+    #[test]
+    fn test_row_distribution() {
+        // A walk stepping -1 or +1 per row: paths to each position follow
+        // Pascal's triangle
+        let ends = row_distribution(0i32, 0..4, |&pos, _, next| {
+            next.push(pos - 1);
+            next.push(pos + 1);
+        });
+        let mut ends: Vec<(i32, u64)> = ends.unwrap().into_iter().collect();
+        ends.sort();
+        assert_eq!(ends, [(-4, 1), (-2, 4), (0, 6), (2, 4), (4, 1)]);
+
+        // A state with no successors ends its paths
+        let ends = row_distribution::<u64, _, _>(0, 0..3, |&pos, _, next| {
+            if pos < 1 {
+                next.push(pos + 1);
+            }
+        });
+        assert!(ends.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_row_distribution_overflow() {
+        // Every row doubles the paths into a single state
+        let double = |_: &(), _, next: &mut Vec<()>| next.extend([(), ()]);
+        let ends = row_distribution::<u32, _, _>((), 0..31, double).unwrap();
+        assert_eq!(ends[&()], 1 << 31);
+        assert_eq!(
+            row_distribution::<u32, _, _>((), 0..32, double),
+            Err(Overflow { type_name: "u32" })
+        );
+        let ends = row_distribution::<Modular<1000>, _, _>((), 0..20, double).unwrap();
+        assert_eq!(ends[&()].value(), (1 << 20) % 1000);
+    }
+
+    #[test]
+    fn test_life_rule() {
+        assert_eq!(LifeRule::new(&[3], &[2, 3]), LifeRule::CONWAY);
+        assert!(LifeRule::CONWAY.next(false, 3));
+        assert!(!LifeRule::CONWAY.next(false, 2));
+        assert!(LifeRule::CONWAY.next(true, 2));
+        assert!(!LifeRule::CONWAY.next(true, 4));
+        let highlife = LifeRule::new(&[3, 6], &[2, 3]);
+        assert!(highlife.next(false, 6));
+        assert!(!LifeRule::new(&[0], &[0]).next(true, 64));
+        assert!(LifeRule::new(&[0], &[]).births_from_nothing());
+        assert!(!LifeRule::CONWAY.births_from_nothing());
+    }
+
+    #[test]
+    fn test_automaton_step() {
+        let blinker = cells(".....\n..#..\n..#..\n..#..\n.....\n");
+        let turned = automaton_step(&blinker, LifeRule::CONWAY);
+        assert_eq!(turned, cells(".....\n.....\n.###.\n.....\n.....\n"));
+        assert_eq!(automaton_step(&turned, LifeRule::CONWAY), blinker);
+        // Cells past the edge stay dead, so a blinker on the edge dies
+        let edge = cells("###\n...\n");
+        assert_eq!(automaton_step(&edge, LifeRule::CONWAY), cells(".#.\n.#.\n"));
+    }
+
+    /// The live cells of `picture` at the origin of `D` dimensions.
+    fn live_points<const D: usize>(picture: &str) -> FastSet<PointN<D>> {
+        let grid = cells(picture);
+        let mut live = FastSet::default();
+        for (row, line) in grid.iter().enumerate() {
+            for (col, &alive) in line.iter().enumerate() {
+                if alive {
+                    let mut point = PointN::ORIGIN;
+                    point.0[0] = row as i32;
+                    point.0[1] = col as i32;
+                    live.insert(point);
+                }
+            }
+        }
+        live
+    }
+
+    #[test]
+    fn test_sparse_automaton_step() {
+        // Conway cubes: in 3 and 4 dimensions, 6 cycles
+        let mut cubes = live_points::<3>(".#.\n..#\n###\n");
+        let mut hypercubes = live_points::<4>(".#.\n..#\n###\n");
+        for _ in 0..6 {
+            cubes = sparse_automaton_step(&cubes, LifeRule::CONWAY);
+            hypercubes = sparse_automaton_step(&hypercubes, LifeRule::CONWAY);
+        }
+        assert_eq!(cubes.len(), 112);
+        assert_eq!(hypercubes.len(), 848);
+
+        // In two dimensions it agrees with the grid, away from the edges
+        let picture = "......\n..#...\n...#..\n.###..\n......\n......\n";
+        let mut grid = cells(picture);
+        let mut live = live_points::<2>(picture);
+        for _ in 0..4 {
+            grid = automaton_step(&grid, LifeRule::CONWAY);
+            live = sparse_automaton_step(&live, LifeRule::CONWAY);
+        }
+        let expected: FastSet<PointN<2>> = live_points(
+            &grid
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&c| if c { '#' } else { '.' })
+                        .collect::<String>()
+                        + "\n"
+                })
+                .collect::<String>(),
+        );
+        assert_eq!(live, expected);
+
+        // A rule keeping lonely cells alive keeps them
+        let lonely = live_points::<3>("#..\n...\n..#\n");
+        assert_eq!(
+            sparse_automaton_step(&lonely, LifeRule::new(&[], &[0])),
+            lonely
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "B0 rule")]
+    fn test_sparse_automaton_step_rejects_b0() {
+        sparse_automaton_step(&live_points::<3>("#\n"), LifeRule::new(&[0, 3], &[2, 3]));
+    }
+
+    #[test]
+    fn test_hashlife_matches_automaton_step() {
+        let mut rng = Lcg::new(4);
+        for generations in [1, 2, 7, 37] {
+            let soup: Vec<Vec<bool>> = (0..12)
+                .map(|_| (0..16).map(|_| rng.below(3) == 0).collect())
+                .collect();
+            // Wide enough a border that the edges never matter
+            let margin = generations + 2;
+            let mut bounded = vec![vec![false; 16 + 2 * margin]; 12 + 2 * margin];
+            for (row, line) in soup.iter().enumerate() {
+                bounded[row + margin][margin..margin + 16].copy_from_slice(line);
+            }
+            for _ in 0..generations {
+                bounded = automaton_step(&bounded, LifeRule::CONWAY);
+            }
+            let shift = margin as i64;
+            let expected: Vec<_> = live(&bounded)
+                .into_iter()
+                .map(|(row, col)| (row - shift, col - shift))
+                .collect();
+
+            let mut life = HashLife::new(LifeRule::CONWAY, &soup);
+            life.advance(generations as u64);
+            assert_eq!(life.live_cells(), expected, "{} generations", generations);
+            assert_eq!(life.population(), expected.len() as u64);
+            assert_eq!(life.generation(), generations as u64);
+
+            // In steps, the same
+            let mut stepped = HashLife::new(LifeRule::CONWAY, &soup);
+            for _ in 0..generations {
+                stepped.advance(1);
+            }
+            assert_eq!(stepped.live_cells(), expected);
+        }
+    }
+
+    #[test]
+    fn test_hashlife_astronomical() {
+        // A glider moves a cell down and right every four generations
+        let glider = cells(".#.\n..#\n###\n");
+        let mut life = HashLife::new(LifeRule::CONWAY, &glider);
+        life.advance(1 << 40);
+        let shift = 1 << 38;
+        let expected: Vec<_> = live(&glider)
+            .into_iter()
+            .map(|(row, col)| (row + shift, col + shift))
+            .collect();
+        assert_eq!(life.live_cells(), expected);
+
+        let block = cells("##\n##\n");
+        let mut still = HashLife::new(LifeRule::CONWAY, &block);
+        still.advance(u64::MAX >> 4);
+        assert_eq!(still.live_cells(), live(&block));
+        let mut empty = HashLife::new(LifeRule::CONWAY, &[]);
+        empty.advance(1000);
+        assert_eq!(empty.population(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "B0 rule")]
+    fn test_hashlife_rejects_b0() {
+        HashLife::new(LifeRule::new(&[0, 3], &[2, 3]), &cells("#\n"));
+    }
+}
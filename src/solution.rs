@@ -0,0 +1,860 @@
+//! The `Solution` trait and the context handed to solvers by the runner.
+
+use crate::Answer;
+use crate::json::Value;
+use crate::progress::{ProgressMode, Reporter};
+use crate::rngs::{self, Pcg32};
+use crate::trace::Tracer;
+use crate::validate::{Violation, ensure_at_most};
+use crate::viz::{Visualizer, VizOptions};
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Cooperative cancellation flag shared between the runner and a solver.
+///
+/// The token is cancelled either explicitly via [`CancellationToken::cancel`]
+/// or implicitly once its deadline passes.  Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that cancels itself once `timeout` has elapsed.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancellationToken {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: Instant::now().checked_add(timeout),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    /// When the token cancels itself, if it was given a timeout.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+}
+
+/// Returned by a solver that stopped early because it was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// The largest input a solver handles, one bound for each way inputs grow;
+/// `None` is unbounded.  See [`Solution::limits`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Most positions, such as lights or counters, on any line.
+    pub max_positions: Option<usize>,
+    /// Widest grid row.
+    pub max_grid_width: Option<usize>,
+}
+
+impl Limits {
+    /// No bounds at all.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_positions(mut self, max: usize) -> Self {
+        self.max_positions = Some(max);
+        self
+    }
+
+    pub fn with_max_grid_width(mut self, max: usize) -> Self {
+        self.max_grid_width = Some(max);
+        self
+    }
+
+    /// Checks `size` against every bound, reporting the first it breaks.
+    pub fn check(&self, size: &InputSize) -> Result<(), ExceedsLimits> {
+        let bounds = [
+            ("positions", size.positions, self.max_positions),
+            ("grid columns", size.grid_width, self.max_grid_width),
+        ];
+        for (what, count, max) in bounds {
+            if let Some(max) = max {
+                ensure_at_most(what, count, max).map_err(ExceedsLimits)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How large a parsed input is along each axis of [`Limits`], as measured
+/// by [`Solution::input_size`].  Zero where the solution does not measure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputSize {
+    pub positions: usize,
+    pub grid_width: usize,
+}
+
+/// Returned instead of solving an input larger than a solver's
+/// [`Limits`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExceedsLimits(pub Violation);
+
+impl fmt::Display for ExceedsLimits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "input exceeds solver limits: {}", self.0)
+    }
+}
+
+impl Error for ExceedsLimits {}
+
+/// Seed handed to solvers when the run does not choose one, so randomized
+/// solvers give the same answer every run.
+pub const DEFAULT_SEED: u64 = 2025;
+
+/// Options and services available to a solver while it runs.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    cancellation: CancellationToken,
+    progress: Reporter,
+    thread_budget: Option<usize>,
+    rng_seed: Option<u64>,
+    visualizer: Arc<Mutex<Visualizer>>,
+    tracer: Tracer,
+    algorithm: Option<String>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Reporter) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    pub fn with_thread_budget(mut self, threads: usize) -> Self {
+        self.thread_budget = Some(threads.max(1));
+        self
+    }
+
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    pub fn with_visualizer(mut self, visualizer: Visualizer) -> Self {
+        self.visualizer = Arc::new(Mutex::new(visualizer));
+        self
+    }
+
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    /// Asks the solver for one of its [`Solution::strategies`] by name.
+    pub fn with_algorithm(mut self, algorithm: &str) -> Self {
+        self.algorithm = Some(algorithm.to_string());
+        self
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub fn progress(&self) -> &Reporter {
+        &self.progress
+    }
+
+    /// How many threads the solver may keep busy.  Defaults to the size of
+    /// the current rayon pool, or one without the `native` feature; the
+    /// runner lowers it when solutions run side by side so nested
+    /// parallelism does not oversubscribe the machine.
+    pub fn thread_budget(&self) -> usize {
+        #[cfg(feature = "native")]
+        let default = rayon::current_num_threads;
+        #[cfg(not(feature = "native"))]
+        let default = || 1;
+        self.thread_budget.unwrap_or_else(default)
+    }
+
+    /// The strategy the run asked for with `--algo`, if any.  Solvers with
+    /// several [`Solution::strategies`] use it in place of their default.
+    pub fn algorithm(&self) -> Option<&str> {
+        self.algorithm.as_deref()
+    }
+
+    /// The seed for any randomness in the solver; [`DEFAULT_SEED`] unless
+    /// the run was given `--seed`.
+    pub fn rng_seed(&self) -> u64 {
+        self.rng_seed.unwrap_or(DEFAULT_SEED)
+    }
+
+    /// A generator seeded with [`Self::rng_seed`]; see [`rngs::seeded`].
+    pub fn rng(&self) -> Pcg32 {
+        rngs::seeded(self)
+    }
+
+    /// When the run will be cancelled for taking too long, for solvers that
+    /// budget their own effort, such as an anytime search.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.cancellation.deadline()
+    }
+
+    /// Whether [`Self::frame`] draws anything; solvers can skip tracking
+    /// what they would draw otherwise.
+    pub fn is_visualizing(&self) -> bool {
+        self.visualizer
+            .lock()
+            .is_ok_and(|visualizer| visualizer.is_enabled())
+    }
+
+    /// Draws a frame with the run's [`Visualizer`], which is off unless the
+    /// solution was run with `--visualize`, `--animate` or `--record`.
+    pub fn frame(&self, title: &str, render: impl FnOnce(bool) -> String) {
+        if let Ok(mut visualizer) = self.visualizer.lock() {
+            visualizer.frame(title, render);
+        }
+    }
+
+    /// Whether [`Self::trace`] keeps events; solvers can skip building them
+    /// otherwise.
+    pub fn is_tracing(&self) -> bool {
+        self.tracer.is_enabled()
+    }
+
+    /// Records an intermediate decision, such as a piece placed or a pivot
+    /// chosen, in the `--trace` file if the solution was run with one.
+    pub fn trace(&self, event: Value) {
+        self.tracer.trace(&event);
+    }
+
+    /// Returns `Err(Cancelled)` once the run has been cancelled.  Long-running
+    /// solvers should call this periodically from their main loop.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.cancellation.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A solution for one day, split into parsing and the two parts.
+pub trait Solution {
+    /// Name of the day's input file, e.g. `"10"`.
+    const DAY: &'static str;
+
+    type Input;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Box<dyn Error>>;
+
+    /// Solves part 1.  Input with no answer is
+    /// [`Outcome::Unsolvable`](crate::Outcome::Unsolvable),
+    /// converted with `into()`; errors are for input the solver cannot
+    /// handle.
+    fn part1(&self, input: &Self::Input, ctx: &Context) -> Result<Answer, Box<dyn Error>>;
+
+    fn part2(&self, input: &Self::Input, ctx: &Context) -> Result<Answer, Box<dyn Error>>;
+
+    /// Solves both parts in one pass, for days whose parts share most of
+    /// their work.  The runner uses this instead of [`Self::part1`] and
+    /// [`Self::part2`] unless it returns `None`, as it does by default.
+    fn solve_both(
+        &self,
+        _input: &Self::Input,
+        _ctx: &Context,
+    ) -> Option<Result<(Answer, Answer), Box<dyn Error>>> {
+        None
+    }
+
+    /// A sentence or two on the approach, shown as the notes column of
+    /// `advent report`.
+    fn description(&self) -> &'static str {
+        ""
+    }
+
+    /// The largest input the solver handles.  The runner checks
+    /// [`Self::input_size`] against these before solving, so an input that
+    /// is too large is reported as such rather than failing somewhere
+    /// inside the solver.  Unbounded by default.
+    fn limits(&self) -> Limits {
+        Limits::new()
+    }
+
+    /// Measures `input` for [`Self::limits`].
+    fn input_size(&self, _input: &Self::Input) -> InputSize {
+        InputSize::default()
+    }
+
+    /// Names of the algorithms the solver can be asked for with `--algo`,
+    /// default first, so they can be compared without editing code.  A
+    /// solver reads the choice from [`Context::algorithm`].  None by
+    /// default.
+    fn strategies(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Checks that the algorithm `ctx` asks for, if any, is one of
+/// `strategies`.
+pub fn check_algorithm(strategies: &[&str], ctx: &Context) -> Result<(), String> {
+    match ctx.algorithm() {
+        Some(algorithm) if !strategies.contains(&algorithm) => Err(if strategies.is_empty() {
+            format!(
+                "unknown algorithm '{}': this solution has only one",
+                algorithm
+            )
+        } else {
+            format!(
+                "unknown algorithm '{}', expected one of {}",
+                algorithm,
+                strategies.join(", ")
+            )
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Compile-time check behind `#[advent]`: the year is this crate's, and a
+/// solution binary, one named `*_day<DD>`, is named `<variant>_day<DD>` so
+/// the runner can find it.  Other binaries, such as fuzz targets that pull
+/// a solution in with `#[path]`, are not checked.  Panics, failing the
+/// build, otherwise.
+pub const fn check_registration(bin: Option<&str>, year: u32, variant: &str, day: u32) {
+    assert!(
+        year == crate::playground::YEAR,
+        "only 2025 solutions live in this crate"
+    );
+    let Some(bin) = bin else {
+        return;
+    };
+    let (bin, variant) = (bin.as_bytes(), variant.as_bytes());
+    let n = bin.len();
+    let solution_bin = n >= 6
+        && bin[n - 6] == b'_'
+        && bin[n - 5] == b'd'
+        && bin[n - 4] == b'a'
+        && bin[n - 3] == b'y'
+        && bin[n - 2].is_ascii_digit()
+        && bin[n - 1].is_ascii_digit();
+    if !solution_bin {
+        return;
+    }
+    let suffix = [
+        b'_',
+        b'd',
+        b'a',
+        b'y',
+        b'0' + (day / 10) as u8,
+        b'0' + (day % 10) as u8,
+    ];
+    let mut matches = bin.len() == variant.len() + suffix.len();
+    let mut i = 0;
+    while matches && i < bin.len() {
+        let expected = if i < variant.len() {
+            variant[i]
+        } else {
+            suffix[i - variant.len()]
+        };
+        matches = bin[i] == expected;
+        i += 1;
+    }
+    assert!(
+        matches,
+        "#[advent] variant and day do not match the binary's name"
+    );
+}
+
+/// Command line options understood by every solution binary.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    pub timeout: Option<Duration>,
+    /// How to render progress; detected from the terminal when not given.
+    pub progress: Option<ProgressMode>,
+    /// Size of the rayon pool the solver runs in.
+    pub threads: Option<usize>,
+    /// Where to write the solver's trace events as JSON lines.
+    pub trace: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub viz: VizOptions,
+    /// Print [`Solution::description`] instead of solving.
+    pub describe: bool,
+    /// One of [`Solution::strategies`] to run instead of the default.
+    pub algo: Option<String>,
+}
+
+impl RunOptions {
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut options = RunOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if options.viz.take(&arg, &mut args)? {
+                continue;
+            }
+            if arg == "--describe" {
+                options.describe = true;
+                continue;
+            }
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("{} requires a value", arg))?;
+                    (arg, value)
+                }
+            };
+            match name.as_str() {
+                "--timeout" => options.timeout = Some(parse_duration(&value)?),
+                "--progress" => options.progress = Some(ProgressMode::parse(&value)?),
+                "--threads" => {
+                    options.threads = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|&n| n > 0)
+                            .ok_or_else(|| format!("invalid thread count '{}'", value))?,
+                    )
+                }
+                "--trace" => options.trace = Some(PathBuf::from(value)),
+                "--algo" => options.algo = Some(value),
+                "--seed" => {
+                    options.seed = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("invalid seed '{}'", value))?,
+                    )
+                }
+                _ => return Err(format!("unknown option {}", name)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Parses durations such as `500ms`, `30s`, `2m`, or a bare number of seconds.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration '{}'", value);
+    let value = value.trim();
+    let (number, scale_ms) = if let Some(n) = value.strip_suffix("ms") {
+        (n, 1)
+    } else if let Some(n) = value.strip_suffix('s') {
+        (n, 1_000)
+    } else if let Some(n) = value.strip_suffix('m') {
+        (n, 60_000)
+    } else {
+        (value, 1_000)
+    };
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    number
+        .checked_mul(scale_ms)
+        .map(Duration::from_millis)
+        .ok_or_else(invalid)
+}
+
+/// Entry point for solution binaries: reads the day's input, then runs and
+/// prints both parts, honoring the options passed on the command line.  A
+/// part with no solution prints as such; only solver errors stop the run.
+#[cfg(feature = "native")]
+pub fn run<S: Solution>(solution: &S) -> Result<(), Box<dyn Error>> {
+    let options = RunOptions::from_args(std::env::args().skip(1))?;
+    if options.describe {
+        println!("{}", solution.description());
+        if !solution.strategies().is_empty() {
+            println!("Algorithms: {}", solution.strategies().join(", "));
+        }
+        return Ok(());
+    }
+    let cancellation = match options.timeout {
+        Some(timeout) => CancellationToken::with_timeout(timeout),
+        None => CancellationToken::new(),
+    };
+    let progress = options.progress.unwrap_or_else(ProgressMode::detect);
+    let mut ctx = Context::new()
+        .with_cancellation(cancellation)
+        .with_progress(Reporter::new(progress))
+        .with_visualizer(Visualizer::from_options(&options.viz)?);
+    if let Some(seed) = options.seed {
+        ctx = ctx.with_rng_seed(seed);
+    }
+    if let Some(algo) = &options.algo {
+        ctx = ctx.with_algorithm(algo);
+    }
+    if let Some(path) = &options.trace {
+        let tracer = Tracer::create(path)
+            .map_err(|err| format!("cannot trace to {}: {}", path.display(), err))?;
+        ctx = ctx.with_tracer(tracer);
+    }
+    if let Some(threads) = options.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+        ctx = ctx.with_thread_budget(threads);
+    }
+
+    let input = solution.parse(&crate::read_file_as_string(S::DAY)?)?;
+    write_parts(solution, &input, &ctx, &mut std::io::stdout().lock())?;
+    Ok(ctx.tracer.flush()?)
+}
+
+/// Solves both parts of `input`, writing each answer to `out` as soon as it
+/// is known, in the form [`run`] prints.  Solutions with
+/// [`Solution::solve_both`] print both answers once the pass is done.
+/// Input beyond the solution's [`Solution::limits`], or an algorithm it
+/// does not offer, is an error before either part runs.
+pub fn write_parts<S: Solution, W: Write>(
+    solution: &S,
+    input: &S::Input,
+    ctx: &Context,
+    out: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    check_algorithm(solution.strategies(), ctx)?;
+    solution.limits().check(&solution.input_size(input))?;
+    if let Some(answers) = solution.solve_both(input, ctx) {
+        let (part1, part2) = answers?;
+        writeln!(out, "Part 1: {}", part1)?;
+        writeln!(out, "Part 2: {}", part2)?;
+        return Ok(());
+    }
+    writeln!(out, "Part 1: {}", solution.part1(input, ctx)?)?;
+    out.flush()?;
+    writeln!(out, "Part 2: {}", solution.part2(input, ctx)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_cancel_is_shared() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    #[test]
+    fn test_token_deadline() {
+        assert!(CancellationToken::with_timeout(Duration::ZERO).is_cancelled());
+        assert!(!CancellationToken::with_timeout(Duration::from_secs(3600)).is_cancelled());
+    }
+
+    #[test]
+    fn test_context_check() {
+        let ctx = Context::new();
+        assert_eq!(ctx.check(), Ok(()));
+        ctx.cancellation().cancel();
+        assert_eq!(ctx.check(), Err(Cancelled));
+    }
+
+    #[test]
+    fn test_thread_budget() {
+        #[cfg(feature = "native")]
+        assert_eq!(Context::new().thread_budget(), rayon::current_num_threads());
+        #[cfg(not(feature = "native"))]
+        assert_eq!(Context::new().thread_budget(), 1);
+        assert_eq!(Context::new().with_thread_budget(2).thread_budget(), 2);
+        assert_eq!(Context::new().with_thread_budget(0).thread_budget(), 1);
+    }
+
+    #[test]
+    fn test_seed_and_deadline() {
+        let ctx = Context::new();
+        assert_eq!(ctx.rng_seed(), DEFAULT_SEED);
+        assert_eq!(ctx.deadline(), None);
+        let ctx = ctx
+            .with_rng_seed(9)
+            .with_cancellation(CancellationToken::with_timeout(Duration::from_secs(60)));
+        assert_eq!(ctx.rng().next_u64(), Pcg32::new(9).next_u64());
+        assert!(ctx.deadline().is_some_and(|d| d > Instant::now()));
+    }
+
+    #[test]
+    fn test_write_parts_prefers_solve_both() {
+        struct OnePass;
+
+        impl Solution for OnePass {
+            const DAY: &'static str = "00";
+            type Input = u64;
+
+            fn parse(&self, input: &str) -> Result<u64, Box<dyn Error>> {
+                Ok(input.trim().parse()?)
+            }
+
+            fn part1(&self, _input: &u64, _ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+                unreachable!("solve_both answers part 1")
+            }
+
+            fn part2(&self, _input: &u64, _ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+                unreachable!("solve_both answers part 2")
+            }
+
+            fn solve_both(
+                &self,
+                input: &u64,
+                _ctx: &Context,
+            ) -> Option<Result<(Answer, Answer), Box<dyn Error>>> {
+                Some(Ok((Answer::from(*input), Answer::from(input * 2))))
+            }
+        }
+
+        let mut out = Vec::new();
+        write_parts(&OnePass, &21, &Context::new(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "Part 1: 21\nPart 2: 42\n");
+    }
+
+    #[test]
+    fn test_limits() {
+        struct Lights;
+
+        impl Solution for Lights {
+            const DAY: &'static str = "00";
+            type Input = Vec<String>;
+
+            fn parse(&self, input: &str) -> Result<Vec<String>, Box<dyn Error>> {
+                Ok(input.lines().map(str::to_string).collect())
+            }
+
+            fn part1(&self, input: &Vec<String>, _ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+                Ok(Answer::from(input.len() as u64))
+            }
+
+            fn part2(
+                &self,
+                _input: &Vec<String>,
+                _ctx: &Context,
+            ) -> Result<Answer, Box<dyn Error>> {
+                Ok(Answer::from(0u64))
+            }
+
+            fn limits(&self) -> Limits {
+                Limits::new().with_max_positions(4)
+            }
+
+            fn input_size(&self, input: &Vec<String>) -> InputSize {
+                InputSize {
+                    positions: input.iter().map(String::len).max().unwrap_or(0),
+                    ..InputSize::default()
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        let input = Lights.parse("#..#\n.#").unwrap();
+        write_parts(&Lights, &input, &Context::new(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "Part 1: 2\nPart 2: 0\n");
+
+        let mut out = Vec::new();
+        let input = Lights.parse("#..#.").unwrap();
+        let err = write_parts(&Lights, &input, &Context::new(), &mut out).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input exceeds solver limits: too many positions: 5 (max 4)"
+        );
+        assert!(out.is_empty());
+
+        let limits = Limits::new().with_max_grid_width(10);
+        assert_eq!(limits.check(&InputSize::default()), Ok(()));
+        let wide = InputSize {
+            positions: 100,
+            grid_width: 11,
+        };
+        assert_eq!(
+            limits.check(&wide),
+            Err(ExceedsLimits(Violation::TooMany {
+                what: "grid columns",
+                count: 11,
+                max: 10
+            }))
+        );
+    }
+
+    #[test]
+    fn test_algorithm() {
+        struct Either;
+
+        impl Solution for Either {
+            const DAY: &'static str = "00";
+            type Input = u64;
+
+            fn parse(&self, input: &str) -> Result<u64, Box<dyn Error>> {
+                Ok(input.trim().parse()?)
+            }
+
+            fn part1(&self, input: &u64, ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+                Ok(Answer::from(match ctx.algorithm() {
+                    Some("double") => input * 2,
+                    _ => *input,
+                }))
+            }
+
+            fn part2(&self, _input: &u64, _ctx: &Context) -> Result<Answer, Box<dyn Error>> {
+                Ok(Answer::from(0u64))
+            }
+
+            fn strategies(&self) -> &'static [&'static str] {
+                &["same", "double"]
+            }
+        }
+
+        let solve = |ctx: &Context| {
+            let mut out = Vec::new();
+            write_parts(&Either, &21, ctx, &mut out).map(|()| String::from_utf8(out).unwrap())
+        };
+        assert_eq!(Context::new().algorithm(), None);
+        assert_eq!(solve(&Context::new()).unwrap(), "Part 1: 21\nPart 2: 0\n");
+        let ctx = Context::new().with_algorithm("double");
+        assert_eq!(ctx.algorithm(), Some("double"));
+        assert_eq!(solve(&ctx).unwrap(), "Part 1: 42\nPart 2: 0\n");
+        assert_eq!(
+            solve(&Context::new().with_algorithm("triple"))
+                .unwrap_err()
+                .to_string(),
+            "unknown algorithm 'triple', expected one of same, double"
+        );
+        assert_eq!(
+            check_algorithm(&[], &Context::new().with_algorithm("bfs")),
+            Err("unknown algorithm 'bfs': this solution has only one".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check_registration() {
+        check_registration(Some("claude_day07"), 2025, "claude", 7);
+        check_registration(Some("gemini_cli_day12"), 2025, "gemini_cli", 12);
+        check_registration(None, 2025, "claude", 7);
+        // Not a solution binary, as for a fuzz target
+        check_registration(Some("day10_configuration"), 2025, "claude", 10);
+        for (bin, year, variant, day) in [
+            (Some("claude_day07"), 2024, "claude", 7),
+            (Some("claude_day07"), 2025, "codex", 7),
+            (Some("claude_day07"), 2025, "claude", 8),
+            (Some("claude_day17"), 2025, "claude", 7),
+            (Some("day10_configuration"), 2024, "claude", 10),
+        ] {
+            let result = std::panic::catch_unwind(|| check_registration(bin, year, variant, day));
+            assert!(result.is_err(), "{:?} {} {} {}", bin, year, variant, day);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("7"), Ok(Duration::from_secs(7)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("18446744073709551615m").is_err());
+    }
+
+    #[test]
+    fn test_run_options() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(RunOptions::from_args(args(&[])), Ok(RunOptions::default()));
+        assert_eq!(
+            RunOptions::from_args(args(&["--timeout", "30s"]))
+                .unwrap()
+                .timeout,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            RunOptions::from_args(args(&["--timeout=250ms"]))
+                .unwrap()
+                .timeout,
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            RunOptions::from_args(args(&["--progress", "none"]))
+                .unwrap()
+                .progress,
+            Some(ProgressMode::Hidden)
+        );
+        assert!(RunOptions::from_args(args(&["--timeout"])).is_err());
+        assert!(RunOptions::from_args(args(&["--progress=fancy"])).is_err());
+        assert_eq!(
+            RunOptions::from_args(args(&["--threads=3"]))
+                .unwrap()
+                .threads,
+            Some(3)
+        );
+        assert!(RunOptions::from_args(args(&["--threads", "0"])).is_err());
+        assert!(RunOptions::from_args(args(&["--threads", "many"])).is_err());
+        assert!(RunOptions::from_args(args(&["--verbose", "1"])).is_err());
+        assert_eq!(
+            RunOptions::from_args(args(&["--trace", "run.jsonl"]))
+                .unwrap()
+                .trace,
+            Some(PathBuf::from("run.jsonl"))
+        );
+        let options = RunOptions::from_args(args(&["--animate", "--timeout", "1s"])).unwrap();
+        assert_eq!(options.viz.animate, Some(10));
+        assert_eq!(options.timeout, Some(Duration::from_secs(1)));
+        assert_eq!(
+            RunOptions::from_args(args(&["--seed", "7"])).unwrap().seed,
+            Some(7)
+        );
+        assert!(RunOptions::from_args(args(&["--seed=-1"])).is_err());
+        let options = RunOptions::from_args(args(&["--describe", "--threads=2"])).unwrap();
+        assert!(options.describe);
+        assert_eq!(options.threads, Some(2));
+        assert_eq!(
+            RunOptions::from_args(args(&["--algo", "prim"]))
+                .unwrap()
+                .algo,
+            Some("prim".to_string())
+        );
+    }
+
+    #[test]
+    fn test_context_trace() {
+        let ctx = Context::new();
+        assert!(!ctx.is_tracing());
+        ctx.trace(crate::json!({"event": "dropped"}));
+
+        let path = std::env::temp_dir().join(format!("ctx-trace-{}.jsonl", std::process::id()));
+        let ctx = ctx.with_tracer(Tracer::create(&path).unwrap());
+        assert!(ctx.clone().is_tracing());
+        ctx.trace(crate::json!({"event": "pivot", "column": 2usize}));
+        drop(ctx);
+        let written = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written.unwrap(), "{\"event\":\"pivot\",\"column\":2}\n");
+    }
+
+    #[test]
+    fn test_context_frames_off_by_default() {
+        let ctx = Context::new();
+        assert!(!ctx.is_visualizing());
+        ctx.frame("never", |_| panic!("rendered while off"));
+    }
+}
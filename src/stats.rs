@@ -0,0 +1,286 @@
+//! Solve times from an Advent of Code private leaderboard, for `advent
+//! stats`.
+//!
+//! Leaderboards are fetched with `curl` using the session cookie in
+//! `AOC_SESSION`, and each response is cached for 15 minutes, the most
+//! often the site asks scripts to poll.
+
+use crate::json::{self, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+/// Environment variable holding the `session` cookie of a logged in browser.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// How long a fetched leaderboard is reused before fetching it again.
+pub const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Puzzles unlock at midnight US Eastern time, 05:00 UTC.
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+/// One member's entry on a private leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    pub stars: u64,
+    pub local_score: u64,
+    /// `(day, part, timestamp)` for each star, in seconds since the Unix
+    /// epoch.
+    pub stars_at: Vec<(u32, u32, u64)>,
+}
+
+impl Member {
+    /// How long after `day` unlocked the member got `part`.
+    pub fn solve_time(&self, year: u32, day: u32, part: u32) -> Option<Duration> {
+        let &(_, _, at) = self
+            .stars_at
+            .iter()
+            .find(|&&(d, p, _)| d == day && p == part)?;
+        at.checked_sub(unlock_time(year, day))
+            .map(Duration::from_secs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub year: u32,
+    pub owner_id: u64,
+    /// Highest local score first.
+    pub members: Vec<Member>,
+}
+
+impl Leaderboard {
+    /// Parses the JSON served at
+    /// `/{year}/leaderboard/private/view/{id}.json`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let value = json::parse(text)?;
+        let year = u32::try_from(number(&value, "event")?)
+            .map_err(|_| "'event' is not a year".to_string())?;
+        let owner_id = number(&value, "owner_id")?;
+        let entries = field(&value, "members")?
+            .as_object()
+            .ok_or("'members' is not an object")?;
+
+        let mut members = Vec::new();
+        for (_, entry) in entries {
+            let id = number(entry, "id")?;
+            let mut stars_at = Vec::new();
+            let days = field(entry, "completion_day_level")?
+                .as_object()
+                .ok_or("'completion_day_level' is not an object")?;
+            for (day, parts) in days {
+                let day = day.parse().map_err(|_| format!("invalid day '{}'", day))?;
+                for (part, star) in parts.as_object().ok_or("day is not an object")? {
+                    let part = part
+                        .parse()
+                        .map_err(|_| format!("invalid part '{}'", part))?;
+                    stars_at.push((day, part, number(star, "get_star_ts")?));
+                }
+            }
+            stars_at.sort_unstable();
+            members.push(Member {
+                id,
+                // Anonymous members have a null name
+                name: entry
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| format!("(anonymous user #{})", id), str::to_string),
+                stars: number(entry, "stars")?,
+                local_score: number(entry, "local_score")?,
+                stars_at,
+            });
+        }
+        members.sort_by(|a, b| b.local_score.cmp(&a.local_score).then(a.id.cmp(&b.id)));
+        Ok(Leaderboard {
+            year,
+            owner_id,
+            members,
+        })
+    }
+
+    pub fn member(&self, id: u64) -> Option<&Member> {
+        self.members.iter().find(|member| member.id == id)
+    }
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Result<&'a Value, String> {
+    value
+        .get(name)
+        .ok_or_else(|| format!("leaderboard has no '{}'", name))
+}
+
+fn number(value: &Value, name: &str) -> Result<u64, String> {
+    let field = field(value, name)?;
+    // Some fields are sent as strings
+    field
+        .as_u64()
+        .or_else(|| field.as_str()?.parse().ok())
+        .ok_or_else(|| format!("'{}' is not a number", name))
+}
+
+/// When `day` of December `year` unlocked, in seconds since the Unix epoch.
+pub fn unlock_time(year: u32, day: u32) -> u64 {
+    // Days-from-civil for the proleptic Gregorian calendar; December is
+    // month 10 counting from March, so the year needs no adjustment.
+    let year = u64::from(year);
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * 9 + 2) / 5 + u64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    days * 86_400 + UNLOCK_HOUR_UTC * 3600
+}
+
+/// Formats a solve time as `H:MM:SS`, hours growing past 24 as needed.
+pub fn format_solve_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Where the response for leaderboard `id` of `year` is cached.
+pub fn cache_path(cache_dir: &Path, year: u32, id: u64) -> PathBuf {
+    cache_dir.join(format!("leaderboard-{}-{}.json", year, id))
+}
+
+/// The JSON of private leaderboard `id` for `year`, from `cache_dir` if it
+/// was fetched less than [`CACHE_TTL`] ago.
+pub fn fetch_leaderboard(year: u32, id: u64, cache_dir: &Path) -> Result<String, String> {
+    let path = cache_path(cache_dir, year, id);
+    let fresh = std::fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < CACHE_TTL);
+    if fresh && let Ok(text) = std::fs::read_to_string(&path) {
+        return Ok(text);
+    }
+
+    let session = std::env::var(SESSION_VAR).map_err(|_| {
+        format!(
+            "set {} to your adventofcode.com session cookie",
+            SESSION_VAR
+        )
+    })?;
+    let url = format!(
+        "https://adventofcode.com/{}/leaderboard/private/view/{}.json",
+        year, id
+    );
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        // Read from stdin so the cookie stays out of the process list
+        .args(["--cookie", "@-", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            let mut stdin = child.stdin.take().expect("stdin is piped");
+            writeln!(
+                stdin,
+                "Set-Cookie: session={}; Domain=adventofcode.com",
+                session
+            )?;
+            drop(stdin);
+            child.wait_with_output()
+        })
+        .map_err(|err| format!("failed to launch curl: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "failed to fetch {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let text = String::from_utf8(output.stdout).map_err(|_| "response is not UTF-8".to_string())?;
+    // A stale session is redirected to the HTML login page
+    Leaderboard::parse(&text).map_err(|err| {
+        format!(
+            "unexpected response from {} ({}); is {} current?",
+            url, err, SESSION_VAR
+        )
+    })?;
+    std::fs::create_dir_all(cache_dir)
+        .and_then(|()| std::fs::write(&path, &text))
+        .map_err(|err| format!("failed to cache {}: {}", path.display(), err))?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEADERBOARD: &str = r#"{
+        "event": "2025",
+        "owner_id": 7,
+        "members": {
+            "7": {
+                "id": 7, "name": "owner", "stars": 3, "local_score": 10,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1764565500, "star_index": 1},
+                        "2": {"get_star_ts": 1764569100, "star_index": 2}
+                    },
+                    "2": {"1": {"get_star_ts": 1764651600, "star_index": 3}}
+                }
+            },
+            "9": {
+                "id": 9, "name": null, "stars": 0, "local_score": 12,
+                "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse() {
+        let leaderboard = Leaderboard::parse(LEADERBOARD).unwrap();
+        assert_eq!(leaderboard.year, 2025);
+        assert_eq!(leaderboard.owner_id, 7);
+        let names: Vec<&str> = leaderboard
+            .members
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, ["(anonymous user #9)", "owner"]);
+        let owner = leaderboard.member(7).unwrap();
+        assert_eq!(owner.stars_at.len(), 3);
+        assert_eq!(owner.stars_at[2], (2, 1, 1764651600));
+
+        assert!(Leaderboard::parse("<html>").is_err());
+        assert!(Leaderboard::parse(r#"{"event": "2025", "owner_id": 7}"#).is_err());
+    }
+
+    #[test]
+    fn test_solve_times() {
+        // 2025-12-01 05:00 UTC
+        assert_eq!(unlock_time(2025, 1), 1764565200);
+        assert_eq!(unlock_time(2024, 25), 1735102800);
+        let leaderboard = Leaderboard::parse(LEADERBOARD).unwrap();
+        let owner = leaderboard.member(7).unwrap();
+        assert_eq!(owner.solve_time(2025, 1, 1), Some(Duration::from_secs(300)));
+        assert_eq!(
+            owner.solve_time(2025, 1, 2),
+            Some(Duration::from_secs(3900))
+        );
+        assert_eq!(owner.solve_time(2025, 2, 2), None);
+        assert_eq!(format_solve_time(Duration::from_secs(3900)), "1:05:00");
+        assert_eq!(format_solve_time(Duration::from_secs(90_061)), "25:01:01");
+    }
+
+    #[test]
+    fn test_fresh_cache_is_used() {
+        let dir = std::env::temp_dir().join(format!("advent-stats-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(cache_path(&dir, 2025, 7), LEADERBOARD).unwrap();
+        let text = fetch_leaderboard(2025, 7, &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(text.unwrap(), LEADERBOARD);
+    }
+}
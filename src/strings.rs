@@ -0,0 +1,188 @@
+//! String algorithms that come back year after year: run-length encoding
+//! for look-and-say and compression puzzles, longest common subsequence
+//! and edit distance for comparing near-identical lines, and character
+//! signatures for grouping anagrams.
+//!
+//! Everything works on `char`s, not bytes, so non-ASCII input is counted
+//! per character.
+
+use crate::{Error, Result};
+
+/// Each run of a repeated character with its length: `"aaab"` gives
+/// `[('a', 3), ('b', 1)]`.
+pub fn run_lengths(text: &str) -> Vec<(char, usize)> {
+    let mut runs: Vec<(char, usize)> = Vec::new();
+    for ch in text.chars() {
+        match runs.last_mut() {
+            Some((last, count)) if *last == ch => *count += 1,
+            _ => runs.push((ch, 1)),
+        }
+    }
+    runs
+}
+
+/// Run-length encodes `text` as count then character: `"aaab"` becomes
+/// `"3a1b"`.  Digits encode like any other character, so `"111"` becomes
+/// `"31"` as in look-and-say, though such output no longer decodes
+/// unambiguously.
+pub fn rle_encode(text: &str) -> String {
+    run_lengths(text)
+        .into_iter()
+        .map(|(ch, count)| format!("{}{}", count, ch))
+        .collect()
+}
+
+/// Reverses [`rle_encode`] for text without digits: `"3a1b"` becomes
+/// `"aaab"`.  Fails if a character has no count before it.
+pub fn rle_decode(encoded: &str) -> Result<String> {
+    let mut decoded = String::new();
+    let mut count: Option<usize> = None;
+    for (idx, ch) in encoded.char_indices() {
+        if let Some(digit) = ch.to_digit(10) {
+            count = count
+                .unwrap_or(0)
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(digit as usize));
+            if count.is_none() {
+                return Err(Error::parse_at(1, idx + 1, "run length is too large"));
+            }
+            continue;
+        }
+        let Some(n) = count.take() else {
+            return Err(Error::parse_at(
+                1,
+                idx + 1,
+                format!("'{}' has no run length before it", ch),
+            ));
+        };
+        decoded.extend(std::iter::repeat_n(ch, n));
+    }
+    if count.is_some() {
+        return Err(Error::parse_at(
+            1,
+            encoded.len(),
+            "run length with no character after it",
+        ));
+    }
+    Ok(decoded)
+}
+
+/// One of the longest strings whose characters appear in both `a` and `b`
+/// in order, though not necessarily adjacent.  Takes `O(len(a) * len(b))`
+/// time and space.
+pub fn longest_common_subsequence(a: &str, b: &str) -> String {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // lengths[i][j] is the LCS length of a[i..] and b[j..]
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut common = String::with_capacity(lengths[0][0]);
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            common.push(a[i]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+/// The Levenshtein distance between `a` and `b`: the fewest single
+/// character insertions, deletions and substitutions turning one into the
+/// other.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // Distances from a prefix of `a` to each prefix of `b`, one row at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The characters of `text` with their counts, sorted by character.  Two
+/// strings have the same signature exactly when they are anagrams, and
+/// signatures can be hashed to group them.
+pub fn char_signature(text: &str) -> Vec<(char, usize)> {
+    let mut chars: Vec<char> = text.chars().collect();
+    chars.sort_unstable();
+    chars
+        .chunk_by(|a, b| a == b)
+        .map(|run| (run[0], run.len()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_length_encoding() {
+        assert_eq!(run_lengths("aaabcc"), [('a', 3), ('b', 1), ('c', 2)]);
+        assert_eq!(rle_encode("aaabcc"), "3a1b2c");
+        assert_eq!(rle_encode(""), "");
+        // Look-and-say
+        assert_eq!(rle_encode("1211"), "111221");
+        assert_eq!(rle_decode("3a1b12c").unwrap(), "aaabcccccccccccc");
+        assert_eq!(rle_decode("2é").unwrap(), "éé");
+        for text in ["", "x", "zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzy", "abba"] {
+            assert_eq!(rle_decode(&rle_encode(text)).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_rle_decode_errors() {
+        assert!(matches!(
+            rle_decode("3ab"),
+            Err(Error::Parse {
+                column: Some(3),
+                ..
+            })
+        ));
+        assert!(matches!(rle_decode("3a4"), Err(Error::Parse { .. })));
+        assert!(rle_decode("99999999999999999999999a").is_err());
+    }
+
+    #[test]
+    fn test_longest_common_subsequence() {
+        assert_eq!(longest_common_subsequence("ABCBDAB", "BDCABA").len(), 4);
+        assert_eq!(longest_common_subsequence("fghij", "fguij"), "fgij");
+        assert_eq!(longest_common_subsequence("abc", "xyz"), "");
+        assert_eq!(longest_common_subsequence("", "abc"), "");
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("flaw", "lawn"), 2);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+        assert_eq!(levenshtein("naïve", "naive"), 1);
+    }
+
+    #[test]
+    fn test_char_signature() {
+        assert_eq!(char_signature("listen"), char_signature("silent"));
+        assert_ne!(char_signature("listen"), char_signature("enlists"));
+        assert_eq!(char_signature("abca"), [('a', 2), ('b', 1), ('c', 1)]);
+    }
+}
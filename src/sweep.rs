@@ -0,0 +1,324 @@
+//! Sweep lines over one coordinate: [`Events`] collects items entering and
+//! leaving at integer coordinates, and [`Events::sweep`] visits each
+//! coordinate in turn with the items active there, kept in order in an
+//! [`Active`] set.  Unions of segments or rectangles, and the inside of a
+//! rectilinear polygon as in day 9, reduce to a sweep over one axis with the
+//! other axis in the active set.
+//!
+//! Intervals throughout are half open, `start..end`, so an item leaving at
+//! a coordinate is gone before one entering there arrives.  For choosing
+//! among intervals rather than sweeping them, [`max_disjoint`] picks as
+//! many pairwise disjoint ones as possible.
+
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+
+/// Whether an event adds its item to the active set or takes it out.
+/// Removals sort first, keeping intervals half open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Change {
+    Remove,
+    Insert,
+}
+
+/// Events along a sweep line, ordered by coordinate as they are added.
+#[derive(Debug, Clone)]
+pub struct Events<T> {
+    events: Vec<(i64, Change, T)>,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Events { events: Vec::new() }
+    }
+}
+
+impl<T: Ord + Clone> Events<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Adds `item` to the active set at `at`.
+    pub fn insert(&mut self, at: i64, item: T) {
+        self.push(at, Change::Insert, item);
+    }
+
+    /// Takes one copy of `item` out of the active set at `at`.
+    pub fn remove(&mut self, at: i64, item: T) {
+        self.push(at, Change::Remove, item);
+    }
+
+    /// Makes `item` active over `start..end`; empty intervals add nothing.
+    pub fn add_interval(&mut self, start: i64, end: i64, item: T) {
+        if start < end {
+            self.insert(start, item.clone());
+            self.remove(end, item);
+        }
+    }
+
+    /// Keeps the events sorted, so sweeping never has to.  Equal events
+    /// keep the order they were added in.
+    fn push(&mut self, at: i64, change: Change, item: T) {
+        let idx = self.events.partition_point(|(other_at, other_change, _)| {
+            (*other_at, *other_change) <= (at, change)
+        });
+        self.events.insert(idx, (at, change, item));
+    }
+
+    /// Applies the events coordinate by coordinate, calling `visit` after
+    /// each coordinate's events with that coordinate, the next one (`None`
+    /// after the last), and the items active between the two.
+    ///
+    /// # Panics
+    ///
+    /// When an item is removed that is not active.
+    pub fn sweep(&self, mut visit: impl FnMut(i64, Option<i64>, &Active<T>)) {
+        let mut active = Active::default();
+        let mut idx = 0;
+        while idx < self.events.len() {
+            let at = self.events[idx].0;
+            while let Some((_, change, item)) = self.events.get(idx).filter(|e| e.0 == at) {
+                match change {
+                    Change::Insert => active.insert(item.clone()),
+                    Change::Remove => active.remove(item),
+                }
+                idx += 1;
+            }
+            visit(at, self.events.get(idx).map(|e| e.0), &active);
+        }
+    }
+}
+
+/// The items active at a point of a sweep, in order, with repeats.
+#[derive(Debug, Clone)]
+pub struct Active<T> {
+    counts: BTreeMap<T, usize>,
+    len: usize,
+}
+
+impl<T> Default for Active<T> {
+    fn default() -> Self {
+        Active {
+            counts: BTreeMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T: Ord> Active<T> {
+    fn insert(&mut self, item: T) {
+        *self.counts.entry(item).or_insert(0) += 1;
+        self.len += 1;
+    }
+
+    fn remove(&mut self, item: &T)
+    where
+        T: Clone,
+    {
+        match self.counts.entry(item.clone()) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+                self.len -= 1;
+            }
+            Entry::Vacant(_) => panic!("removed an item that is not active"),
+        }
+    }
+
+    /// The number of active items, counting repeats.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.counts.contains_key(item)
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.counts.keys().next()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.counts.keys().next_back()
+    }
+
+    /// The active items in increasing order, repeats included.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.counts
+            .iter()
+            .flat_map(|(item, &count)| std::iter::repeat_n(item, count))
+    }
+}
+
+/// The indices of as many pairwise disjoint `intervals` as possible, each
+/// `start..end`, in order of their ends.  Taking the interval that ends
+/// first and repeating on the ones starting after it is optimal.  Empty
+/// intervals overlap nothing and are always taken.
+pub fn max_disjoint(intervals: &[(i64, i64)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|&idx| (intervals[idx].1, intervals[idx].0));
+    let mut free_from = i64::MIN;
+    let mut chosen = Vec::new();
+    for idx in order {
+        let (start, end) = intervals[idx];
+        if start >= end {
+            chosen.push(idx);
+        } else if start >= free_from {
+            chosen.push(idx);
+            free_from = end;
+        }
+    }
+    chosen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Lcg;
+
+    #[test]
+    fn test_sweep_union_length() {
+        let mut events = Events::new();
+        for (start, end) in [(0, 4), (2, 6), (8, 9), (9, 12), (20, 20)] {
+            events.add_interval(start, end, ());
+        }
+        assert_eq!(events.len(), 8);
+        let mut covered = 0;
+        events.sweep(|at, next, active| {
+            if !active.is_empty() {
+                covered += next.unwrap() - at;
+            }
+        });
+        assert_eq!(covered, 10);
+    }
+
+    #[test]
+    fn test_sweep_active_order() {
+        let mut events = Events::new();
+        events.add_interval(0, 10, 'c');
+        events.add_interval(2, 5, 'a');
+        events.add_interval(2, 8, 'a');
+        events.add_interval(5, 6, 'b');
+        let mut seen = Vec::new();
+        events.sweep(|at, next, active| {
+            seen.push((at, next, active.iter().collect::<String>()));
+        });
+        assert_eq!(
+            seen,
+            [
+                (0, Some(2), "c".to_string()),
+                (2, Some(5), "aac".to_string()),
+                (5, Some(6), "abc".to_string()),
+                (6, Some(8), "ac".to_string()),
+                (8, Some(10), "c".to_string()),
+                (10, None, String::new()),
+            ]
+        );
+
+        // An item leaving where another arrives is not active with it
+        let mut events = Events::new();
+        events.add_interval(3, 5, 1);
+        events.add_interval(0, 3, 0);
+        events.sweep(|at, _, active| {
+            assert!(active.len() <= 1, "{at}");
+            assert_eq!(active.first(), active.last());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "not active")]
+    fn test_sweep_remove_inactive() {
+        let mut events = Events::new();
+        events.remove(0, 1);
+        events.sweep(|_, _, _| {});
+    }
+
+    #[test]
+    fn test_sweep_matches_counting() {
+        let mut rng = Lcg::new(11);
+        for _ in 0..50 {
+            let intervals: Vec<(i64, i64)> = (0..rng.between(1..=12))
+                .map(|_| {
+                    let start = rng.below(30) as i64;
+                    (start, start + rng.below(10) as i64)
+                })
+                .collect();
+            let mut events = Events::new();
+            for (idx, &(start, end)) in intervals.iter().enumerate() {
+                events.add_interval(start, end, idx);
+            }
+            events.sweep(|at, next, active| {
+                for x in at..next.unwrap_or(at + 1) {
+                    let expected: Vec<usize> = (0..intervals.len())
+                        .filter(|&idx| (intervals[idx].0..intervals[idx].1).contains(&x))
+                        .collect();
+                    assert_eq!(active.iter().copied().collect::<Vec<_>>(), expected);
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn test_max_disjoint() {
+        let intervals = [
+            (1, 4),
+            (3, 5),
+            (0, 6),
+            (5, 7),
+            (3, 9),
+            (5, 9),
+            (6, 10),
+            (8, 11),
+        ];
+        assert_eq!(max_disjoint(&intervals), [0, 3, 7]);
+        assert_eq!(max_disjoint(&[]), Vec::<usize>::new());
+        assert_eq!(max_disjoint(&[(0, 5), (2, 2), (5, 6)]), [1, 0, 2]);
+    }
+
+    #[test]
+    fn test_max_disjoint_matches_brute_force() {
+        let mut rng = Lcg::new(12);
+        for _ in 0..100 {
+            let intervals: Vec<(i64, i64)> = (0..rng.between(1..=10))
+                .map(|_| {
+                    let start = rng.below(20) as i64;
+                    (start, start + 1 + rng.below(6) as i64)
+                })
+                .collect();
+            let disjoint = |subset: &[usize]| {
+                subset.iter().enumerate().all(|(i, &a)| {
+                    subset[i + 1..].iter().all(|&b| {
+                        intervals[a].1 <= intervals[b].0 || intervals[b].1 <= intervals[a].0
+                    })
+                })
+            };
+            let best = (0u32..1 << intervals.len())
+                .filter(|mask| {
+                    let subset: Vec<usize> = (0..intervals.len())
+                        .filter(|idx| mask >> idx & 1 == 1)
+                        .collect();
+                    disjoint(&subset)
+                })
+                .map(u32::count_ones)
+                .max()
+                .unwrap();
+            let chosen = max_disjoint(&intervals);
+            assert!(disjoint(&chosen));
+            assert_eq!(chosen.len() as u32, best);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Machine-readable traces of the decisions a solver makes, for working out
+//! after the fact why two variants disagree.
+//!
+//! Solvers send events to the [`Tracer`] in their context with
+//! `ctx.trace(json!({...}))`.  Without `--trace <file>` the tracer is off
+//! and events are dropped; with it each event is written as one line of
+//! JSON, in the order the solver sent them.
+
+use crate::json::Value;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Writes trace events as JSON lines.  Clones share the same output, so a
+/// solver's threads can trace side by side.
+#[derive(Clone, Default)]
+pub struct Tracer {
+    sink: Option<Arc<Mutex<dyn Write + Send>>>,
+}
+
+impl fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tracer")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl Tracer {
+    /// A tracer that drops every event.
+    pub fn off() -> Self {
+        Self::default()
+    }
+
+    pub fn new<W: Write + Send + 'static>(out: W) -> Self {
+        Tracer {
+            sink: Some(Arc::new(Mutex::new(out))),
+        }
+    }
+
+    /// Traces to `path`, replacing any file already there.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Whether events are kept; solvers can skip building them otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// Writes `event` as one line.  Tracing is a debugging aid, so a trace
+    /// that cannot be written does not stop the solver.
+    pub fn trace(&self, event: &Value) {
+        if let Some(sink) = &self.sink
+            && let Ok(mut out) = sink.lock()
+        {
+            let _ = writeln!(out, "{}", event);
+        }
+    }
+
+    /// Writes out any events still buffered.
+    pub fn flush(&self) -> io::Result<()> {
+        match &self.sink {
+            Some(sink) => sink
+                .lock()
+                .map_err(|_| io::Error::other("trace sink poisoned"))?
+                .flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{self, Value};
+
+    #[test]
+    fn test_off_drops_events() {
+        let tracer = Tracer::off();
+        assert!(!tracer.is_enabled());
+        tracer.trace(&Value::Null);
+        assert!(tracer.flush().is_ok());
+    }
+
+    #[test]
+    fn test_events_are_lines() {
+        let path = std::env::temp_dir().join(format!("trace-{}.jsonl", std::process::id()));
+        let tracer = Tracer::create(&path).unwrap();
+        let clone = tracer.clone();
+        tracer.trace(&crate::json!({"event": "placed", "piece": 3usize}));
+        clone.trace(&crate::json!({"event": "frontier", "size": 12usize}));
+        tracer.flush().unwrap();
+        let written = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        let written = written.unwrap();
+        let events: Vec<Value> = written
+            .lines()
+            .map(|line| json::parse(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 2, "{}", written);
+        assert_eq!(events[0].get("piece").and_then(Value::as_u64), Some(3));
+        assert_eq!(
+            events[1].get("event").and_then(Value::as_str),
+            Some("frontier")
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Checks that parsed input meets the limits a solver relies on, such as
+//! every row of a grid having the same width or a count fitting in a
+//! bitmask.  Each check reports a [`Violation`] naming what was wrong, so
+//! solvers with the same limits describe a breach in the same words.
+
+use std::fmt;
+use std::ops::RangeBounds;
+
+/// A constraint the input does not meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// Row `row` (1-based) has `len` items where the first row has
+    /// `expected`.
+    Ragged {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// There are `len` of `what` where exactly `expected` are needed.
+    Length {
+        what: &'static str,
+        len: usize,
+        expected: usize,
+    },
+    /// There are `count` of `what` where at most `max` are supported.
+    TooMany {
+        what: &'static str,
+        count: usize,
+        max: usize,
+    },
+    /// `what` is `value`, which lies outside `range`.
+    OutOfRange {
+        what: &'static str,
+        value: String,
+        range: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Ragged { row, len, expected } => {
+                write!(f, "row {} has length {}, expected {}", row, len, expected)
+            }
+            Violation::Length {
+                what,
+                len,
+                expected,
+            } => write!(f, "expected {} {}, found {}", expected, what, len),
+            Violation::TooMany { what, count, max } => {
+                write!(f, "too many {}: {} (max {})", what, count, max)
+            }
+            Violation::OutOfRange { what, value, range } => {
+                write!(f, "{} {} out of range {}", what, value, range)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Checks that every row of `grid` is as long as the first, returning that
+/// width (0 for an empty grid).
+pub fn ensure_rectangular<T, R: AsRef<[T]>>(grid: &[R]) -> Result<usize, Violation> {
+    let Some(first) = grid.first() else {
+        return Ok(0);
+    };
+    let expected = first.as_ref().len();
+    match grid.iter().position(|row| row.as_ref().len() != expected) {
+        Some(idx) => Err(Violation::Ragged {
+            row: idx + 1,
+            len: grid[idx].as_ref().len(),
+            expected,
+        }),
+        None => Ok(expected),
+    }
+}
+
+/// Checks that there are exactly `expected` items of `what` in `values`.
+pub fn ensure_len<T>(what: &'static str, values: &[T], expected: usize) -> Result<(), Violation> {
+    if values.len() == expected {
+        Ok(())
+    } else {
+        Err(Violation::Length {
+            what,
+            len: values.len(),
+            expected,
+        })
+    }
+}
+
+/// Checks that a count of `what` is at most `max`, typically the number of
+/// bits in the mask that will hold them.
+pub fn ensure_at_most(what: &'static str, count: usize, max: usize) -> Result<(), Violation> {
+    if count <= max {
+        Ok(())
+    } else {
+        Err(Violation::TooMany { what, count, max })
+    }
+}
+
+/// Checks that `value` lies in `range`, returning it so the check can wrap
+/// the expression that produced it.
+pub fn ensure_in_range<T, R>(what: &'static str, value: T, range: R) -> Result<T, Violation>
+where
+    T: PartialOrd + fmt::Display,
+    R: RangeBounds<T> + fmt::Debug,
+{
+    if range.contains(&value) {
+        Ok(value)
+    } else {
+        Err(Violation::OutOfRange {
+            what,
+            value: value.to_string(),
+            range: format!("{:?}", range),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_rectangular() {
+        assert_eq!(
+            ensure_rectangular(&["abc", "def"].map(str::as_bytes)),
+            Ok(3)
+        );
+        assert_eq!(ensure_rectangular::<u8, Vec<u8>>(&[]), Ok(0));
+        assert_eq!(ensure_rectangular(&[vec![0; 0], vec![]]), Ok(0));
+
+        let err = ensure_rectangular(&[vec![1, 2], vec![3, 4], vec![5]]).unwrap_err();
+        assert_eq!(
+            err,
+            Violation::Ragged {
+                row: 3,
+                len: 1,
+                expected: 2
+            }
+        );
+        assert_eq!(err.to_string(), "row 3 has length 1, expected 2");
+    }
+
+    #[test]
+    fn test_ensure_len_and_at_most() {
+        assert_eq!(ensure_len("targets", &[1, 2, 3], 3), Ok(()));
+        assert_eq!(
+            ensure_len("targets", &[1, 2], 3).unwrap_err().to_string(),
+            "expected 3 targets, found 2"
+        );
+
+        assert_eq!(ensure_at_most("steps", 64, 64), Ok(()));
+        assert_eq!(
+            ensure_at_most("steps", 65, 64).unwrap_err().to_string(),
+            "too many steps: 65 (max 64)"
+        );
+        assert_eq!(
+            crate::Error::from(ensure_at_most("x", 1, 0).unwrap_err()).to_string(),
+            "invalid input: too many x: 1 (max 0)"
+        );
+    }
+
+    #[test]
+    fn test_ensure_in_range() {
+        assert_eq!(ensure_in_range("position", 0, 0..32), Ok(0));
+        assert_eq!(ensure_in_range("position", 31usize, 0..32), Ok(31));
+        assert_eq!(ensure_in_range("delta", -1i64, ..=0), Ok(-1));
+        assert_eq!(
+            ensure_in_range("position", 32, 0..32)
+                .unwrap_err()
+                .to_string(),
+            "position 32 out of range 0..32"
+        );
+        assert_eq!(
+            ensure_in_range("scale", 0.5, 1.0..=2.0)
+                .unwrap_err()
+                .to_string(),
+            "scale 0.5 out of range 1.0..=2.0"
+        );
+    }
+}
@@ -0,0 +1,541 @@
+//! Text rendering of grids for debugging solvers: a grid drawn as it was
+//! read, with cells of interest recoloured or replaced, shown one frame at a
+//! time.
+//!
+//! Solvers that can draw themselves take `--visualize`, or `--animate` to
+//! play their frames back in place; see [`VizOptions`].  Frames go to stderr
+//! so answers on stdout are unaffected, and can be recorded for later with
+//! `--record` (see [`cast`]).  Geometry that does not fit a grid can be drawn
+//! as SVG with [`svg`].
+
+pub mod cast;
+pub mod svg;
+
+use cast::{CLEAR_SCREEN, Cast};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Frames per second for `--animate` without a rate.
+const DEFAULT_FPS: u32 = 10;
+
+/// Colors for highlighted cells, drawn with ANSI escape codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    pub const PALETTE: [Color; 6] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+    ];
+
+    /// The `idx`-th color of [`Self::PALETTE`], wrapping around, so items
+    /// numbered in order get distinct colors.
+    pub fn nth(idx: usize) -> Self {
+        Self::PALETTE[idx % Self::PALETTE.len()]
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+
+    /// The color for [`svg`] drawings, darker than the terminal's so it
+    /// shows on a white background.
+    fn hex(self) -> &'static str {
+        match self {
+            Color::Red => "#d62728",
+            Color::Green => "#2ca02c",
+            Color::Yellow => "#bcbd22",
+            Color::Blue => "#1f77b4",
+            Color::Magenta => "#e377c2",
+            Color::Cyan => "#17becf",
+        }
+    }
+}
+
+/// Cells to draw in a color, and optionally with a different character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    /// `(row, column)` of each cell; cells outside the grid are ignored.
+    pub cells: Vec<(usize, usize)>,
+    pub color: Color,
+    pub glyph: Option<u8>,
+}
+
+impl Highlight {
+    pub fn new(color: Color, cells: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Highlight {
+            cells: cells.into_iter().collect(),
+            color,
+            glyph: None,
+        }
+    }
+
+    /// Draws the cells as `glyph` instead of their own character.
+    pub fn with_glyph(mut self, glyph: u8) -> Self {
+        self.glyph = Some(glyph);
+        self
+    }
+}
+
+/// The grid as text, one line per row.
+pub fn render_grid<R: AsRef<[u8]>>(grid: &[R]) -> String {
+    render_highlighted(grid, &[], false)
+}
+
+/// The grid as text with `highlights` applied, later ones drawn over
+/// earlier ones.  Without `ansi` only the glyphs change, which suits logs
+/// and tests.
+pub fn render_highlighted<R: AsRef<[u8]>>(
+    grid: &[R],
+    highlights: &[Highlight],
+    ansi: bool,
+) -> String {
+    let mut cells: Vec<Vec<(u8, Option<Color>)>> = grid
+        .iter()
+        .map(|row| row.as_ref().iter().map(|&b| (b, None)).collect())
+        .collect();
+    for highlight in highlights {
+        for &(row, col) in &highlight.cells {
+            if let Some(cell) = cells.get_mut(row).and_then(|r| r.get_mut(col)) {
+                *cell = (highlight.glyph.unwrap_or(cell.0), Some(highlight.color));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for row in cells {
+        let mut current = None;
+        for (glyph, color) in row {
+            if ansi && color != current {
+                match color {
+                    Some(color) => out += &format!("\x1b[{}m", color.code()),
+                    None => out += "\x1b[0m",
+                }
+                current = color;
+            }
+            out.push(glyph as char);
+        }
+        if current.is_some() {
+            out += "\x1b[0m";
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// What a [`Visualizer`] does with frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VizMode {
+    /// Frames are not drawn at all.
+    #[default]
+    Off,
+    /// Every frame is drawn without stopping.
+    Print,
+    /// Each frame waits for Enter before the solver continues.
+    Step,
+    /// Each frame replaces the last on screen, `fps` times a second.
+    Animate { fps: u32 },
+}
+
+/// The visualization options a solver was run with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VizOptions {
+    /// `--visualize`
+    pub visualize: bool,
+    /// `--animate` or `--animate=<fps>`
+    pub animate: Option<u32>,
+    /// `--record <file>`: where to save the frames as an asciinema cast.
+    pub record: Option<PathBuf>,
+    /// `--dump-svg <file>`: where to draw the puzzle's geometry.
+    pub dump_svg: Option<PathBuf>,
+    /// `--dump-dot <file>`: where to write the puzzle's graph for Graphviz.
+    pub dump_dot: Option<PathBuf>,
+}
+
+impl VizOptions {
+    /// Picks the visualization options out of `args`, ignoring the rest.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut options = VizOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            options.take(&arg, &mut args)?;
+        }
+        Ok(options)
+    }
+
+    /// Applies `arg` if it is a visualization option, reading its value from
+    /// `rest` when needed.  Returns whether `arg` was one.
+    pub fn take<I: Iterator<Item = String>>(
+        &mut self,
+        arg: &str,
+        rest: &mut I,
+    ) -> Result<bool, String> {
+        if arg == "--visualize" {
+            self.visualize = true;
+        } else if arg == "--animate" {
+            self.animate = Some(DEFAULT_FPS);
+        } else if let Some(fps) = arg.strip_prefix("--animate=") {
+            self.animate = Some(
+                fps.parse()
+                    .ok()
+                    .filter(|&fps| fps > 0)
+                    .ok_or_else(|| format!("invalid frame rate '{}'", fps))?,
+            );
+        } else if let Some(path) = path_value(arg, "--record", rest) {
+            self.record = Some(path?);
+        } else if let Some(path) = path_value(arg, "--dump-svg", rest) {
+            self.dump_svg = Some(path?);
+        } else if let Some(path) = path_value(arg, "--dump-dot", rest) {
+            self.dump_dot = Some(path?);
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
+/// The file named by `arg` if it is `name=<file>`, or `name` followed by
+/// the file in `rest`.
+fn path_value<I: Iterator<Item = String>>(
+    arg: &str,
+    name: &str,
+    rest: &mut I,
+) -> Option<Result<PathBuf, String>> {
+    let value = arg.strip_prefix(name)?;
+    if let Some(path) = value.strip_prefix('=') {
+        return Some(Ok(PathBuf::from(path)));
+    }
+    if !value.is_empty() {
+        return None;
+    }
+    Some(
+        rest.next()
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("{} requires a file", name)),
+    )
+}
+
+/// Draws a solver's frames to stderr, and records them when asked to.
+#[derive(Debug, Default)]
+pub struct Visualizer {
+    mode: VizMode,
+    ansi: bool,
+    frames: usize,
+    recording: Option<Cast>,
+    started: Option<Instant>,
+}
+
+impl Visualizer {
+    pub fn new(mode: VizMode) -> Self {
+        Visualizer {
+            mode,
+            ansi: io::stderr().is_terminal(),
+            ..Self::default()
+        }
+    }
+
+    /// Saves every frame to `cast` as well.  Recorded frames are always
+    /// colored, since casts are played back in a terminal.
+    pub fn with_recording(mut self, cast: Cast) -> Self {
+        self.recording = Some(cast);
+        self.ansi = true;
+        self
+    }
+
+    /// Set up from `options`.  `--animate` wins over `--visualize`, which
+    /// steps when run from a terminal and prints every frame otherwise.
+    /// `--record` on its own records without drawing.
+    pub fn from_options(options: &VizOptions) -> Result<Self, String> {
+        let mode = if let Some(fps) = options.animate {
+            VizMode::Animate { fps }
+        } else if !options.visualize {
+            VizMode::Off
+        } else if io::stdin().is_terminal() && io::stderr().is_terminal() {
+            VizMode::Step
+        } else {
+            VizMode::Print
+        };
+        let viz = Self::new(mode);
+        match &options.record {
+            Some(path) => Cast::create(path)
+                .map(|cast| viz.with_recording(cast))
+                .map_err(|err| format!("cannot record to {}: {}", path.display(), err)),
+            None => Ok(viz),
+        }
+    }
+
+    /// Set up from the program's arguments; see [`Self::from_options`].
+    pub fn from_args() -> Result<Self, String> {
+        Self::from_options(&VizOptions::from_args(std::env::args().skip(1))?)
+    }
+
+    /// Whether frames are drawn or recorded; solvers can skip building them
+    /// otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.mode != VizMode::Off || self.recording.is_some()
+    }
+
+    /// Draws a frame.  `render` is only called when frames are drawn, and is
+    /// told whether it may use ANSI colors.
+    pub fn frame(&mut self, title: &str, render: impl FnOnce(bool) -> String) {
+        if !self.is_enabled() {
+            return;
+        }
+        let body = render(self.ansi);
+        let (mut out, mut keys) = (io::stderr().lock(), io::stdin().lock());
+        let _ = self.show(&mut out, &mut keys, title, &body);
+    }
+
+    /// Writes a frame to `out` and the recording and, when stepping, reads
+    /// the reply from `keys`: Enter for the next frame, `c` to print the
+    /// rest without stopping, `q` to stop drawing.
+    fn show<W: Write, B: BufRead>(
+        &mut self,
+        out: &mut W,
+        keys: &mut B,
+        title: &str,
+        body: &str,
+    ) -> io::Result<()> {
+        self.frames += 1;
+        let text = if title.is_empty() {
+            format!("--- frame {}\n{}", self.frames, body)
+        } else {
+            format!("--- frame {}: {}\n{}", self.frames, title, body)
+        };
+        if let Some(cast) = &mut self.recording {
+            // Animations are recorded at their frame rate however long the
+            // solver took to draw them
+            let at = match self.mode {
+                VizMode::Animate { fps } => {
+                    Duration::from_secs_f64((self.frames - 1) as f64 / f64::from(fps))
+                }
+                _ => self.started.get_or_insert_with(Instant::now).elapsed(),
+            };
+            cast.frame(at, &text)?;
+        }
+        match self.mode {
+            VizMode::Off => return Ok(()),
+            VizMode::Print => return write!(out, "{}", text),
+            VizMode::Animate { fps } => {
+                if self.ansi {
+                    write!(out, "{}", CLEAR_SCREEN)?;
+                }
+                write!(out, "{}", text)?;
+                out.flush()?;
+                std::thread::sleep(Duration::from_secs_f64(1.0 / f64::from(fps)));
+                return Ok(());
+            }
+            VizMode::Step => write!(out, "{}", text)?,
+        }
+        write!(out, "[enter] next  [c] continue  [q] quit: ")?;
+        out.flush()?;
+        let mut reply = String::new();
+        if keys.read_line(&mut reply)? == 0 {
+            // Nothing left to read from; stepping is impossible
+            self.mode = VizMode::Print;
+        }
+        match reply.trim() {
+            "c" => self.mode = VizMode::Print,
+            "q" => self.mode = VizMode::Off,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Plays already drawn `frames` to stderr, each replacing the last, at
+/// `fps` frames a second.
+pub fn animate<I: IntoIterator<Item = String>>(frames: I, fps: u32) {
+    let mut viz = Visualizer::new(VizMode::Animate { fps: fps.max(1) });
+    for frame in frames {
+        viz.frame("", |_| frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: [&str; 3] = ["..S..", ".....", "..^.."];
+
+    #[test]
+    fn test_render_grid() {
+        assert_eq!(render_grid(&GRID), "..S..\n.....\n..^..\n");
+        assert_eq!(render_grid(&[] as &[&str]), "");
+    }
+
+    #[test]
+    fn test_highlights_replace_glyphs() {
+        let beams = Highlight::new(Color::Cyan, [(1, 2), (1, 9), (7, 0)]).with_glyph(b'|');
+        let splitters = Highlight::new(Color::Yellow, [(2, 2)]);
+        assert_eq!(
+            render_highlighted(&GRID, &[beams, splitters], false),
+            "..S..\n..|..\n..^..\n"
+        );
+
+        // Later highlights win
+        let first = Highlight::new(Color::Red, [(0, 0)]).with_glyph(b'A');
+        let second = Highlight::new(Color::Blue, [(0, 0)]).with_glyph(b'B');
+        assert_eq!(render_highlighted(&["."], &[first, second], false), "B\n");
+    }
+
+    #[test]
+    fn test_ansi_colors_runs_of_cells() {
+        let piece = Highlight::new(Color::nth(1), [(0, 1), (0, 2)]);
+        assert_eq!(
+            render_highlighted(&["abcd"], &[piece], true),
+            "a\x1b[32mbc\x1b[0md\n"
+        );
+        let edge = Highlight::new(Color::Red, [(0, 3)]);
+        assert_eq!(
+            render_highlighted(&["abcd"], &[edge], true),
+            "abc\x1b[31md\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_palette_wraps() {
+        assert_eq!(Color::nth(0), Color::Red);
+        assert_eq!(Color::nth(5), Color::Cyan);
+        assert_eq!(Color::nth(6), Color::Red);
+    }
+
+    #[test]
+    fn test_off_skips_rendering() {
+        let mut viz = Visualizer::default();
+        assert!(!viz.is_enabled());
+        viz.frame("never", |_| panic!("rendered while off"));
+    }
+
+    #[test]
+    fn test_stepping() {
+        let mut viz = Visualizer::new(VizMode::Step);
+        let mut out = Vec::new();
+        let mut keys = "\nc\n".as_bytes();
+        viz.show(&mut out, &mut keys, "row 1", "S\n").unwrap();
+        assert_eq!(viz.mode, VizMode::Step);
+        viz.show(&mut out, &mut keys, "row 2", "|\n").unwrap();
+        assert_eq!(viz.mode, VizMode::Print);
+        viz.show(&mut out, &mut keys, "row 3", "^\n").unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("[enter] next").count(), 2);
+        assert!(out.ends_with("--- frame 3: row 3\n^\n"), "{}", out);
+
+        let mut viz = Visualizer::new(VizMode::Step);
+        viz.show(&mut Vec::new(), &mut "q\n".as_bytes(), "row 1", "")
+            .unwrap();
+        assert!(!viz.is_enabled());
+    }
+
+    #[test]
+    fn test_viz_options() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            VizOptions::from_args(args(&["--threads=2", "--progress", "none"])),
+            Ok(VizOptions::default())
+        );
+        assert_eq!(
+            VizOptions::from_args(args(&["--visualize", "--animate", "--record", "a.cast"])),
+            Ok(VizOptions {
+                visualize: true,
+                animate: Some(DEFAULT_FPS),
+                record: Some(PathBuf::from("a.cast")),
+                ..VizOptions::default()
+            })
+        );
+        assert_eq!(
+            VizOptions::from_args(args(&["--animate=30", "--record=b.cast"])),
+            Ok(VizOptions {
+                animate: Some(30),
+                record: Some(PathBuf::from("b.cast")),
+                ..VizOptions::default()
+            })
+        );
+        assert_eq!(
+            VizOptions::from_args(args(&["--dump-svg", "out.svg", "--dump-dot=/tmp/g.dot"])),
+            Ok(VizOptions {
+                dump_svg: Some(PathBuf::from("out.svg")),
+                dump_dot: Some(PathBuf::from("/tmp/g.dot")),
+                ..VizOptions::default()
+            })
+        );
+        assert!(VizOptions::from_args(args(&["--dump-svg"])).is_err());
+        assert_eq!(
+            VizOptions::from_args(args(&["--recorder"])),
+            Ok(VizOptions::default())
+        );
+        assert!(VizOptions::from_args(args(&["--animate=0"])).is_err());
+        assert!(VizOptions::from_args(args(&["--animate=fast"])).is_err());
+        assert!(VizOptions::from_args(args(&["--record"])).is_err());
+    }
+
+    #[test]
+    fn test_animation_redraws_in_place() {
+        let mut viz = Visualizer::new(VizMode::Animate { fps: 1000 });
+        viz.ansi = true;
+        let mut out = Vec::new();
+        viz.show(&mut out, &mut "".as_bytes(), "row 1", "S\n")
+            .unwrap();
+        viz.show(&mut out, &mut "".as_bytes(), "", "|\n").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[2J\x1b[H--- frame 1: row 1\nS\n\x1b[2J\x1b[H--- frame 2\n|\n"
+        );
+        assert_eq!(viz.mode, VizMode::Animate { fps: 1000 });
+    }
+
+    #[test]
+    fn test_recording() {
+        let path = std::env::temp_dir().join(format!("viz-cast-{}.cast", std::process::id()));
+        let options = VizOptions {
+            animate: Some(4),
+            record: Some(path.clone()),
+            ..VizOptions::default()
+        };
+        let mut viz = Visualizer::from_options(&options).unwrap();
+        for row in ["S", "|"] {
+            viz.show(&mut Vec::new(), &mut "".as_bytes(), row, "..\n")
+                .unwrap();
+        }
+        drop(viz);
+        let written = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        let written = written.unwrap();
+        let lines: Vec<_> = written.lines().collect();
+        assert_eq!(lines.len(), 3, "{}", written);
+        assert!(lines[2].starts_with("[0.25,"), "{}", written);
+
+        // Recording alone draws nothing but still wants frames
+        let options = VizOptions {
+            record: Some(path.clone()),
+            ..VizOptions::default()
+        };
+        let mut viz = Visualizer::from_options(&options).unwrap();
+        assert!(viz.is_enabled());
+        let mut out = Vec::new();
+        viz.show(&mut out, &mut "".as_bytes(), "row 1", "S\n")
+            .unwrap();
+        drop(viz);
+        let _ = std::fs::remove_file(&path);
+        assert!(out.is_empty());
+    }
+}
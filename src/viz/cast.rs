@@ -0,0 +1,121 @@
+//! Recordings of a solver's frames in asciinema's cast format (version 2),
+//! so an animation can be replayed with `asciinema play` or shared without
+//! rerunning the solver.
+//!
+//! A cast is a JSON header line followed by one JSON array per burst of
+//! terminal output.  Each frame is recorded as a screen clear followed by
+//! its text, so playback redraws it in place.
+
+use crate::json::Value;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Clears the screen and moves the cursor to the top left.
+pub(crate) const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Writes frames to a cast file as they are drawn.
+#[derive(Debug)]
+pub struct Cast<W: Write = BufWriter<File>> {
+    out: W,
+    started: bool,
+}
+
+impl Cast {
+    /// Starts a recording at `path`, replacing any file already there.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Cast::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> Cast<W> {
+    pub fn new(out: W) -> Self {
+        Cast {
+            out,
+            started: false,
+        }
+    }
+
+    /// Records `text` as a frame shown `at` into the recording.  The
+    /// recording's terminal size is taken from the first frame, so later
+    /// frames larger than it are cut off on playback.
+    pub fn frame(&mut self, at: Duration, text: &str) -> io::Result<()> {
+        if !self.started {
+            let (width, height) = size(text);
+            let header = Value::Object(vec![
+                ("version".to_string(), Value::from(2u64)),
+                ("width".to_string(), Value::from(width.max(1) as u64)),
+                ("height".to_string(), Value::from(height.max(1) as u64)),
+            ]);
+            writeln!(self.out, "{}", header)?;
+            self.started = true;
+        }
+        // Terminals need a carriage return to get back to the first column
+        let output = format!("{}{}", CLEAR_SCREEN, text.replace('\n', "\r\n"));
+        let event = Value::Array(vec![
+            Value::from(at.as_secs_f64()),
+            Value::from("o"),
+            Value::from(output),
+        ]);
+        writeln!(self.out, "{}", event)?;
+        self.out.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// The columns and rows `text` takes up on screen, not counting ANSI color
+/// codes.
+fn size(text: &str) -> (usize, usize) {
+    let width = text
+        .lines()
+        .map(|line| {
+            let mut columns = 0;
+            let mut in_escape = false;
+            for c in line.chars() {
+                match c {
+                    '\x1b' => in_escape = true,
+                    'm' if in_escape => in_escape = false,
+                    _ if in_escape => {}
+                    _ => columns += 1,
+                }
+            }
+            columns
+        })
+        .max()
+        .unwrap_or(0);
+    (width, text.lines().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn test_header_and_events() {
+        let mut cast = Cast::new(Vec::new());
+        cast.frame(Duration::ZERO, "ab\n\x1b[31mcde\x1b[0m\n")
+            .unwrap();
+        cast.frame(Duration::from_millis(500), "xy\n").unwrap();
+        let written = String::from_utf8(cast.into_inner()).unwrap();
+        let lines: Vec<_> = written.lines().collect();
+        assert_eq!(lines.len(), 3, "{}", written);
+        assert_eq!(lines[0], r#"{"version":2,"width":3,"height":2}"#);
+
+        let event = json::parse(lines[2]).unwrap();
+        let event = event.as_array().unwrap();
+        assert_eq!(event[0].as_f64(), Some(0.5));
+        assert_eq!(event[1].as_str(), Some("o"));
+        assert_eq!(event[2].as_str(), Some("\x1b[2J\x1b[Hxy\r\n"));
+    }
+
+    #[test]
+    fn test_size_skips_colors() {
+        assert_eq!(size(""), (0, 0));
+        assert_eq!(size("a\x1b[32mbc\x1b[0md\n..\n"), (4, 2));
+    }
+}
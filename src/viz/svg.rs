@@ -0,0 +1,275 @@
+//! SVG drawings of points in the plane, for puzzles such as day 9 whose
+//! geometry is easier to check by eye than by reading coordinates.
+//!
+//! Coordinates are drawn as given, with `y` growing downwards as it does in
+//! the puzzle grids.  Lines keep the same width and dots the same size
+//! however far apart the points are.
+
+use super::Color;
+use crate::Point2d;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+/// Width of the image in pixels; the height follows the drawing's shape.
+const WIDTH_PX: f64 = 800.0;
+
+/// One thing drawn, kept in coordinates until the drawing's bounds are known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Element {
+    Polygon(Vec<Point2d>, Color),
+    Line(Point2d, Point2d, Color),
+    Rect(Point2d, Point2d, Color),
+    Dots(Vec<Point2d>, Color),
+}
+
+/// Shapes to write out as one SVG image, drawn in the order they were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Drawing {
+    elements: Vec<Element>,
+}
+
+impl Drawing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The closed outline through `points` in order, lightly filled.
+    pub fn polygon(mut self, points: &[Point2d], color: Color) -> Self {
+        self.elements.push(Element::Polygon(points.to_vec(), color));
+        self
+    }
+
+    pub fn line(mut self, from: Point2d, to: Point2d, color: Color) -> Self {
+        self.elements.push(Element::Line(from, to, color));
+        self
+    }
+
+    /// The axis-aligned rectangle with opposite corners `p` and `q`.
+    pub fn rect(mut self, p: Point2d, q: Point2d, color: Color) -> Self {
+        self.elements.push(Element::Rect(p, q, color));
+        self
+    }
+
+    /// A dot at each point.
+    pub fn dots(mut self, points: &[Point2d], color: Color) -> Self {
+        self.elements.push(Element::Dots(points.to_vec(), color));
+        self
+    }
+
+    /// Draws `other` over this drawing.
+    pub fn append(mut self, other: Drawing) -> Self {
+        self.elements.extend(other.elements);
+        self
+    }
+
+    /// The smallest and largest coordinates drawn, or `None` if nothing is.
+    fn bounds(&self) -> Option<(Point2d, Point2d)> {
+        let points = self.elements.iter().flat_map(|element| match element {
+            Element::Polygon(points, _) | Element::Dots(points, _) => points.clone(),
+            Element::Line(p, q, _) | Element::Rect(p, q, _) => vec![*p, *q],
+        });
+        points.fold(None, |bounds, p| {
+            let (min, max) = bounds.unwrap_or((p, p));
+            Some((
+                Point2d {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Point2d {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            ))
+        })
+    }
+
+    /// The drawing as an SVG document, framed to fit everything in it.
+    pub fn to_svg(&self) -> String {
+        let (min, max) = self
+            .bounds()
+            .unwrap_or((Point2d { x: 0, y: 0 }, Point2d { x: 0, y: 0 }));
+        let width = (max.dx_i64(min) as f64).max(1.0);
+        let height = (max.dy_i64(min) as f64).max(1.0);
+        let margin = width.max(height) / 50.0;
+        let dot = width.max(height) / 250.0;
+        let height_px = (WIDTH_PX * (height + 2.0 * margin) / (width + 2.0 * margin)).round();
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            WIDTH_PX,
+            height_px,
+            f64::from(min.x) - margin,
+            f64::from(min.y) - margin,
+            width + 2.0 * margin,
+            height + 2.0 * margin
+        );
+        let stroke = |color: Color| {
+            format!(
+                r#"stroke="{}" stroke-width="1.5" vector-effect="non-scaling-stroke""#,
+                color.hex()
+            )
+        };
+        for element in &self.elements {
+            let _ = match element {
+                Element::Polygon(points, color) => writeln!(
+                    out,
+                    r#"<polygon points="{}" fill="{}" fill-opacity="0.1" {}/>"#,
+                    points
+                        .iter()
+                        .map(|p| format!("{},{}", p.x, p.y))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    color.hex(),
+                    stroke(*color)
+                ),
+                Element::Line(p, q, color) => writeln!(
+                    out,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" {}/>"#,
+                    p.x,
+                    p.y,
+                    q.x,
+                    q.y,
+                    stroke(*color)
+                ),
+                Element::Rect(p, q, color) => writeln!(
+                    out,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="0.25" {}/>"#,
+                    p.x.min(q.x),
+                    p.y.min(q.y),
+                    p.dx_i64(*q).abs(),
+                    p.dy_i64(*q).abs(),
+                    color.hex(),
+                    stroke(*color)
+                ),
+                Element::Dots(points, color) => points.iter().try_for_each(|p| {
+                    writeln!(
+                        out,
+                        r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                        p.x,
+                        p.y,
+                        dot,
+                        color.hex()
+                    )
+                }),
+            };
+        }
+        out += "</svg>\n";
+        out
+    }
+
+    /// Writes the drawing to `path` as an SVG file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
+}
+
+/// The polygon through `points` in order, with its corners marked.  Edges
+/// that are neither horizontal nor vertical are drawn over in red: day 9
+/// expects a rectilinear polygon, and these are where that breaks.
+pub fn draw_polygon(points: &[Point2d]) -> Drawing {
+    let mut drawing = Drawing::new().polygon(points, Color::Blue);
+    for (idx, &p) in points.iter().enumerate() {
+        let q = points[(idx + 1) % points.len()];
+        if p.x != q.x && p.y != q.y {
+            drawing = drawing.line(p, q, Color::Red);
+        }
+    }
+    drawing.dots(points, Color::Blue)
+}
+
+/// `points` as dots, with their convex hull `hull` outlined.
+pub fn draw_points_with_hull(points: &[Point2d], hull: &[Point2d]) -> Drawing {
+    Drawing::new()
+        .polygon(hull, Color::Green)
+        .dots(points, Color::Blue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(i32, i32)]) -> Vec<Point2d> {
+        coords.iter().map(|&(x, y)| Point2d { x, y }).collect()
+    }
+
+    #[test]
+    fn test_frame_fits_drawing() {
+        let square = points(&[(0, 0), (100, 0), (100, 50), (0, 50)]);
+        let svg = Drawing::new().polygon(&square, Color::Blue).to_svg();
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="415" viewBox="-2 -2 104 54">"#),
+            "{}",
+            svg
+        );
+        assert!(svg.contains(r#"points="0,0 100,0 100,50 0,50""#), "{}", svg);
+        assert!(svg.ends_with("</svg>\n"));
+
+        // Nothing drawn still gives a valid image
+        let svg = Drawing::new().to_svg();
+        assert!(
+            svg.contains(r#"viewBox="-0.02 -0.02 1.04 1.04""#),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn test_rect_corners_in_any_order() {
+        let (p, q) = (Point2d { x: 7, y: 1 }, Point2d { x: 2, y: 5 });
+        let svg = Drawing::new().rect(p, q, Color::Yellow).to_svg();
+        assert!(
+            svg.contains(r#"<rect x="2" y="1" width="5" height="4""#),
+            "{}",
+            svg
+        );
+    }
+
+    #[test]
+    fn test_diagonal_edges_stand_out() {
+        let red = Color::Red.hex();
+        let rectilinear = points(&[(7, 1), (11, 1), (11, 7), (7, 7)]);
+        assert!(!draw_polygon(&rectilinear).to_svg().contains(red));
+
+        // The closing edge from (11, 7) back to (7, 1) is diagonal
+        let broken = points(&[(7, 1), (11, 1), (11, 7)]);
+        let svg = draw_polygon(&broken).to_svg();
+        assert_eq!(svg.matches(red).count(), 1, "{}", svg);
+        assert!(
+            svg.contains(r#"<line x1="11" y1="7" x2="7" y2="1""#),
+            "{}",
+            svg
+        );
+        assert_eq!(
+            draw_polygon(&[]),
+            Drawing::new()
+                .polygon(&[], Color::Blue)
+                .dots(&[], Color::Blue)
+        );
+    }
+
+    #[test]
+    fn test_points_with_hull() {
+        let cloud = points(&[(0, 0), (4, 0), (2, 1), (2, 4)]);
+        let hull = points(&[(0, 0), (4, 0), (2, 4)]);
+        let svg = draw_points_with_hull(&cloud, &hull)
+            .append(Drawing::new().rect(cloud[0], cloud[2], Color::Magenta))
+            .to_svg();
+        assert_eq!(svg.matches("<circle").count(), 4);
+        assert!(svg.contains(r#"points="0,0 4,0 2,4""#), "{}", svg);
+        // Later drawings go on top
+        assert!(svg.find("<circle").unwrap() < svg.find("<rect").unwrap());
+    }
+
+    #[test]
+    fn test_save() {
+        let path = std::env::temp_dir().join(format!("viz-svg-{}.svg", std::process::id()));
+        let drawing = draw_polygon(&points(&[(0, 0), (3, 0), (3, 3)]));
+        drawing.save(&path).unwrap();
+        let written = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written.unwrap(), drawing.to_svg());
+    }
+}
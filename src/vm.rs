@@ -0,0 +1,430 @@
+//! Register machines for assembly-style puzzles.  A [`Vm`] holds a program
+//! of any instruction type, a register file and a program counter; a
+//! puzzle supplies the instruction set as two closures, one decoding a
+//! line of source and one executing an instruction, and the machine runs
+//! it until it halts, jumps off the program, repeats itself or hits a step
+//! limit.
+//!
+//! ```
+//! use rust_advent::vm::{Flow, Operand, Stop, Vm};
+//!
+//! #[derive(Clone)]
+//! enum Op {
+//!     Inc(usize),
+//!     Jnz(Operand, i64),
+//! }
+//!
+//! let mut vm = Vm::parse("inc a\njnz 1 -1\n", 1, |line| {
+//!     match line.split_whitespace().collect::<Vec<_>>()[..] {
+//!         ["inc", r] => Ok(Op::Inc(Operand::register(r)?)),
+//!         ["jnz", x, by] => Ok(Op::Jnz(Operand::parse(x)?, by.parse().map_err(|_| "bad offset")?)),
+//!         _ => Err(format!("unknown instruction {}", line)),
+//!     }
+//! })
+//! .unwrap()
+//! .with_step_limit(10);
+//! let stop = vm.run(|op, vm| match op {
+//!     Op::Inc(r) => {
+//!         vm.registers_mut()[r] += 1;
+//!         Flow::Next
+//!     }
+//!     Op::Jnz(x, by) if x.get(vm.registers()) != 0 => Flow::Jump(by),
+//!     Op::Jnz(..) => Flow::Next,
+//! });
+//! assert_eq!(stop, Stop::StepLimit);
+//! assert_eq!(vm.registers(), [5]);
+//! ```
+
+use crate::{Error, FastSet};
+
+/// Where execution goes after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// On to the next instruction.
+    Next,
+    /// Forwards or back by this many instructions; `Jump(1)` is `Next`.
+    Jump(i64),
+    /// Stop the machine.
+    Halt,
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// An instruction returned [`Flow::Halt`].
+    Halted,
+    /// The program counter left the program, the usual way to finish.
+    OffEnd,
+    /// The machine came back to a state it had been in, so it would run
+    /// forever.  `pc` is the instruction about to run again, not yet run.
+    Loop { pc: usize },
+    /// The step limit ran out first.
+    StepLimit,
+}
+
+/// A register or a literal, as instruction arguments often are either.
+/// Registers are single lowercase letters, `a` being register 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+impl Operand {
+    /// A register letter or an integer.
+    pub fn parse(token: &str) -> Result<Operand, String> {
+        match Operand::register(token) {
+            Ok(register) => Ok(Operand::Register(register)),
+            Err(_) => token
+                .parse()
+                .map(Operand::Value)
+                .map_err(|_| format!("expected a register or an integer, found '{}'", token)),
+        }
+    }
+
+    /// The index of register `token`, `a` being 0.
+    pub fn register(token: &str) -> Result<usize, String> {
+        match token.as_bytes() {
+            &[letter @ b'a'..=b'z'] => Ok(usize::from(letter - b'a')),
+            _ => Err(format!("expected a register, found '{}'", token)),
+        }
+    }
+
+    /// The operand's value given the registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the register is past the end of `registers`.
+    pub fn get(self, registers: &[i64]) -> i64 {
+        match self {
+            Operand::Register(register) => registers[register],
+            Operand::Value(value) => value,
+        }
+    }
+}
+
+/// Everything a [`Vm`] needs to carry on from where it was.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot<I> {
+    pub program: Vec<I>,
+    pub registers: Vec<i64>,
+    pub pc: usize,
+    pub steps: u64,
+}
+
+/// A program of instructions `I` and the machine running it.
+#[derive(Debug, Clone)]
+pub struct Vm<I> {
+    program: Vec<I>,
+    registers: Vec<i64>,
+    pc: usize,
+    steps: u64,
+    step_limit: Option<u64>,
+}
+
+impl<I: Clone> Vm<I> {
+    /// A machine at the start of `program` with `registers` registers, all
+    /// zero.
+    pub fn new(program: Vec<I>, registers: usize) -> Self {
+        Vm {
+            program,
+            registers: vec![0; registers],
+            pc: 0,
+            steps: 0,
+            step_limit: None,
+        }
+    }
+
+    /// Decodes each non-blank line of `source` into an instruction.  A
+    /// decoding error is reported on its line.
+    pub fn parse(
+        source: &str,
+        registers: usize,
+        mut decode: impl FnMut(&str) -> Result<I, String>,
+    ) -> Result<Self, Error> {
+        let program = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                decode(line.trim()).map_err(|message| Error::parse(idx + 1, message))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Vm::new(program, registers))
+    }
+
+    /// Stops runs after `limit` instructions in total, for programs that
+    /// may run for too long without repeating.
+    pub fn with_step_limit(mut self, limit: u64) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    /// For instruction sets that rewrite the program as it runs.
+    pub fn program_mut(&mut self) -> &mut [I] {
+        &mut self.program
+    }
+
+    pub fn registers(&self) -> &[i64] {
+        &self.registers
+    }
+
+    pub fn registers_mut(&mut self) -> &mut [i64] {
+        &mut self.registers
+    }
+
+    /// The index of the next instruction to run.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The number of instructions run so far.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn snapshot(&self) -> Snapshot<I> {
+        Snapshot {
+            program: self.program.clone(),
+            registers: self.registers.clone(),
+            pc: self.pc,
+            steps: self.steps,
+        }
+    }
+
+    /// Puts the machine back as it was at `snapshot`, keeping its step
+    /// limit.
+    pub fn restore(&mut self, snapshot: &Snapshot<I>) {
+        self.program.clone_from(&snapshot.program);
+        self.registers.clone_from(&snapshot.registers);
+        self.pc = snapshot.pc;
+        self.steps = snapshot.steps;
+    }
+
+    /// Runs one instruction with `execute`, or says why not.
+    pub fn step(&mut self, mut execute: impl FnMut(I, &mut Vm<I>) -> Flow) -> Option<Stop> {
+        if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+            return Some(Stop::StepLimit);
+        }
+        let Some(instruction) = self.program.get(self.pc).cloned() else {
+            return Some(Stop::OffEnd);
+        };
+        self.steps += 1;
+        let offset = match execute(instruction, self) {
+            Flow::Next => 1,
+            Flow::Jump(offset) => offset,
+            Flow::Halt => return Some(Stop::Halted),
+        };
+        // Jumping before the start is off the program too
+        match self.pc.checked_add_signed(offset as isize) {
+            Some(pc) => self.pc = pc,
+            None => {
+                self.pc = self.program.len();
+                return Some(Stop::OffEnd);
+            }
+        }
+        None
+    }
+
+    /// Runs until the program halts, runs off its end or uses up the step
+    /// limit.
+    pub fn run(&mut self, mut execute: impl FnMut(I, &mut Vm<I>) -> Flow) -> Stop {
+        loop {
+            if let Some(stop) = self.step(&mut execute) {
+                return stop;
+            }
+        }
+    }
+
+    /// Like [`Vm::run`], but stops with [`Stop::Loop`] before any
+    /// instruction would run a second time, however the registers have
+    /// changed: the check for puzzles asking for the state just before an
+    /// infinite loop.
+    pub fn run_until_revisit(&mut self, mut execute: impl FnMut(I, &mut Vm<I>) -> Flow) -> Stop {
+        let mut visited = FastSet::default();
+        loop {
+            if !visited.insert(self.pc) && self.pc < self.program.len() {
+                return Stop::Loop { pc: self.pc };
+            }
+            if let Some(stop) = self.step(&mut execute) {
+                return stop;
+            }
+        }
+    }
+
+    /// Like [`Vm::run`], but stops with [`Stop::Loop`] when the program
+    /// counter and registers return to values they had before, which for
+    /// a machine depending on nothing else means it would loop forever.
+    /// Instruction sets that rewrite the program or read input should not
+    /// rely on this.  Remembers every state, so pair it with a step limit
+    /// on long runs.
+    pub fn run_until_repeat(&mut self, mut execute: impl FnMut(I, &mut Vm<I>) -> Flow) -> Stop {
+        let mut seen: FastSet<(usize, Vec<i64>)> = FastSet::default();
+        loop {
+            if !seen.insert((self.pc, self.registers.clone())) {
+                return Stop::Loop { pc: self.pc };
+            }
+            if let Some(stop) = self.step(&mut execute) {
+                return stop;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assembunny, with `tgl` to check programs rewriting themselves.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Bunny {
+        Cpy(Operand, Operand),
+        Inc(Operand),
+        Dec(Operand),
+        Jnz(Operand, Operand),
+        Tgl(Operand),
+    }
+
+    fn decode_bunny(line: &str) -> Result<Bunny, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let arg = |idx: usize| {
+            words
+                .get(idx)
+                .ok_or_else(|| format!("missing argument in '{}'", line))
+                .and_then(|word| Operand::parse(word))
+        };
+        Ok(match words[0] {
+            "cpy" => Bunny::Cpy(arg(1)?, arg(2)?),
+            "inc" => Bunny::Inc(arg(1)?),
+            "dec" => Bunny::Dec(arg(1)?),
+            "jnz" => Bunny::Jnz(arg(1)?, arg(2)?),
+            "tgl" => Bunny::Tgl(arg(1)?),
+            other => return Err(format!("unknown instruction '{}'", other)),
+        })
+    }
+
+    fn execute_bunny(op: Bunny, vm: &mut Vm<Bunny>) -> Flow {
+        let value = |x: Operand, vm: &Vm<Bunny>| x.get(vm.registers());
+        match op {
+            // Instructions made invalid by tgl are skipped
+            Bunny::Cpy(x, Operand::Register(r)) => vm.registers_mut()[r] = value(x, vm),
+            Bunny::Inc(Operand::Register(r)) => vm.registers_mut()[r] += 1,
+            Bunny::Dec(Operand::Register(r)) => vm.registers_mut()[r] -= 1,
+            Bunny::Jnz(x, by) if value(x, vm) != 0 => return Flow::Jump(value(by, vm)),
+            Bunny::Tgl(x) => {
+                let target = vm.pc() as i64 + value(x, vm);
+                if let Some(op) = usize::try_from(target)
+                    .ok()
+                    .and_then(|idx| vm.program_mut().get_mut(idx))
+                {
+                    *op = match op.clone() {
+                        Bunny::Inc(x) => Bunny::Dec(x),
+                        Bunny::Dec(x) | Bunny::Tgl(x) => Bunny::Inc(x),
+                        Bunny::Jnz(x, y) => Bunny::Cpy(x, y),
+                        Bunny::Cpy(x, y) => Bunny::Jnz(x, y),
+                    };
+                }
+            }
+            _ => {}
+        }
+        Flow::Next
+    }
+
+    #[test]
+    fn test_assembunny() {
+        let mut vm = Vm::parse(
+            "cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a\n",
+            4,
+            decode_bunny,
+        )
+        .unwrap();
+        assert_eq!(vm.run(execute_bunny), Stop::OffEnd);
+        assert_eq!(vm.registers(), [42, 0, 0, 0]);
+        assert_eq!(vm.steps(), 5);
+
+        let source = "cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a\n";
+        let mut vm = Vm::parse(source, 4, decode_bunny).unwrap();
+        assert_eq!(vm.run(execute_bunny), Stop::OffEnd);
+        assert_eq!(vm.registers()[0], 3);
+        assert_eq!(vm.program()[3], Bunny::Inc(Operand::Register(0)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Vm::parse("inc a\n\njmp 4\n", 4, decode_bunny).unwrap_err();
+        assert!(err.to_string().contains("line 3"), "{}", err);
+        assert!(Vm::parse("inc 7x\n", 4, decode_bunny).is_err());
+        assert_eq!(Operand::parse("-12"), Ok(Operand::Value(-12)));
+        assert_eq!(Operand::parse("c"), Ok(Operand::Register(2)));
+        assert!(Operand::register("ab").is_err());
+    }
+
+    #[test]
+    fn test_run_until_revisit() {
+        // The handheld console: acc, jmp and nop, stopped before the loop
+        let source = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+        let decode = |line: &str| -> Result<(String, i64), String> {
+            let (op, arg) = line.split_once(' ').ok_or("missing argument")?;
+            Ok((op.to_string(), arg.parse().map_err(|_| "bad argument")?))
+        };
+        let execute = |(op, arg): (String, i64), vm: &mut Vm<(String, i64)>| match op.as_str() {
+            "acc" => {
+                vm.registers_mut()[0] += arg;
+                Flow::Next
+            }
+            "jmp" => Flow::Jump(arg),
+            _ => Flow::Next,
+        };
+        let mut vm = Vm::parse(source, 1, decode).unwrap();
+        assert_eq!(vm.run_until_revisit(execute), Stop::Loop { pc: 1 });
+        assert_eq!(vm.registers(), [5]);
+
+        // Fixed by turning the jmp at 7 into a nop
+        let mut vm = Vm::parse(source, 1, decode).unwrap();
+        vm.program_mut()[7].0 = "nop".to_string();
+        assert_eq!(vm.run_until_revisit(execute), Stop::OffEnd);
+        assert_eq!(vm.registers(), [8]);
+    }
+
+    #[test]
+    fn test_run_until_repeat_and_limits() {
+        // a counts down to zero and then sits in a loop of two
+        let source = "cpy 3 a\ndec a\njnz a -1\njnz 1 0\n";
+        let mut vm = Vm::parse(source, 4, decode_bunny).unwrap();
+        assert_eq!(vm.run_until_repeat(execute_bunny), Stop::Loop { pc: 3 });
+        assert_eq!(vm.registers()[0], 0);
+
+        let mut vm = Vm::parse(source, 4, decode_bunny)
+            .unwrap()
+            .with_step_limit(4);
+        assert_eq!(vm.run(execute_bunny), Stop::StepLimit);
+        assert_eq!((vm.steps(), vm.pc(), vm.registers()[0]), (4, 2, 1));
+
+        // Jumping before the start runs off the program
+        let mut vm = Vm::parse("jnz 1 -5\n", 4, decode_bunny).unwrap();
+        assert_eq!(vm.run(execute_bunny), Stop::OffEnd);
+        assert_eq!(Vm::new(vec![()], 0).run(|_, _| Flow::Halt), Stop::Halted);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut vm = Vm::parse("cpy 2 a\ntgl a\ninc b\ninc b\n", 4, decode_bunny).unwrap();
+        assert_eq!(vm.step(execute_bunny), None);
+        let saved = vm.snapshot();
+        // The tgl turns the second inc into a dec
+        assert_eq!(vm.run(execute_bunny), Stop::OffEnd);
+        assert_eq!(vm.registers(), [2, 0, 0, 0]);
+        assert_eq!(vm.program()[3], Bunny::Dec(Operand::Register(1)));
+
+        vm.restore(&saved);
+        assert_eq!(vm.snapshot(), saved);
+        assert_eq!((vm.pc(), vm.steps()), (1, 1));
+        assert_eq!(vm.program()[3], Bunny::Inc(Operand::Register(1)));
+        assert_eq!(vm.run(execute_bunny), Stop::OffEnd);
+        assert_eq!(vm.registers(), [2, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,140 @@
+//! Checks the code `#[advent]` generates from outside the crate, as a
+//! solution binary sees it.
+
+use rust_advent::Answer;
+use rust_advent::solution::{Context, Solution, write_parts};
+
+// The attribute's `main` is unused beside the test harness's
+#[allow(dead_code)]
+mod lines {
+    use rust_advent::advent;
+    use rust_advent::{Outcome, solution::Context};
+
+    #[advent(
+        year = 2025,
+        day = 1,
+        variant = "example",
+        description = "Counts lines."
+    )]
+    pub struct Day01;
+
+    fn part1(input: &[String], _ctx: &Context) -> Result<Outcome, String> {
+        Ok(Outcome::Answer(input.len() as u64))
+    }
+
+    fn part2(input: &[String], _ctx: &Context) -> Result<Outcome, Box<dyn std::error::Error>> {
+        Ok(Outcome::Answer(
+            input.iter().map(String::len).sum::<usize>() as u64,
+        ))
+    }
+}
+
+#[allow(dead_code)]
+mod numbers {
+    use rust_advent::advent;
+    use rust_advent::{Outcome, solution::Context};
+    use std::num::ParseIntError;
+
+    #[advent(
+        year = 2025,
+        day = 12,
+        variant = "example",
+        input = Vec<u64>,
+        parse = parse_numbers
+    )]
+    pub struct Day12;
+
+    fn parse_numbers(input: &str) -> Result<Vec<u64>, ParseIntError> {
+        input.split_whitespace().map(str::parse).collect()
+    }
+
+    fn part1(input: &[u64], _ctx: &Context) -> Result<u64, String> {
+        Ok(input.iter().sum())
+    }
+
+    fn part2(input: &[u64], ctx: &Context) -> Result<Outcome, String> {
+        ctx.check().map_err(|err| err.to_string())?;
+        Ok(input.iter().max().copied().into())
+    }
+}
+
+#[allow(dead_code)]
+mod limited {
+    use rust_advent::advent;
+    use rust_advent::solution::{Context, InputSize};
+
+    #[advent(
+        year = 2025,
+        day = 4,
+        variant = "example",
+        max_grid_width = 3,
+        input_size = grid_size
+    )]
+    pub struct Day04;
+
+    fn grid_size(input: &[String]) -> InputSize {
+        InputSize {
+            grid_width: input.iter().map(String::len).max().unwrap_or(0),
+            ..InputSize::default()
+        }
+    }
+
+    fn part1(input: &[String], _ctx: &Context) -> Result<u64, String> {
+        Ok(input.len() as u64)
+    }
+
+    fn part2(_input: &[String], _ctx: &Context) -> Result<u64, String> {
+        Ok(0)
+    }
+}
+
+#[test]
+fn test_default_input_is_lines() {
+    let input = lines::Day01.parse("ab\ncde\n").unwrap();
+    assert_eq!(input, ["ab", "cde"]);
+    let mut out = Vec::new();
+    write_parts(&lines::Day01, &input, &Context::new(), &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "Part 1: 2\nPart 2: 5\n");
+    assert_eq!(lines::Day01.description(), "Counts lines.");
+    assert_eq!(<lines::Day01 as Solution>::DAY, "01");
+}
+
+#[test]
+fn test_custom_parse() {
+    let input = numbers::Day12.parse("3 9 4").unwrap();
+    let ctx = Context::new();
+    assert_eq!(
+        numbers::Day12.part1(&input, &ctx).unwrap(),
+        Answer::UInt(16)
+    );
+    assert_eq!(numbers::Day12.part2(&input, &ctx).unwrap(), Answer::UInt(9));
+    assert!(numbers::Day12.parse("3 x").is_err());
+    assert_eq!(numbers::Day12.description(), "");
+    assert_eq!(<numbers::Day12 as Solution>::DAY, "12");
+}
+
+#[test]
+fn test_limits() {
+    use rust_advent::solution::{InputSize, Limits};
+
+    assert_eq!(lines::Day01.limits(), Limits::new());
+    assert_eq!(
+        limited::Day04.limits(),
+        Limits::new().with_max_grid_width(3)
+    );
+    let input = limited::Day04.parse("@.@\n.@.@\n").unwrap();
+    assert_eq!(limited::Day04.input_size(&input).grid_width, 4);
+    let err = write_parts(&limited::Day04, &input, &Context::new(), &mut Vec::new()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "input exceeds solver limits: too many grid columns: 4 (max 3)"
+    );
+    let input = limited::Day04.parse("@.@\n").unwrap();
+    assert_eq!(
+        limited::Day04.input_size(&input),
+        InputSize {
+            positions: 0,
+            grid_width: 3
+        }
+    );
+}
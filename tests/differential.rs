@@ -0,0 +1,750 @@
+//! Feeds the same generated inputs to every variant of a day and checks that
+//! they give the same answers, so a variant that drifts from the others is
+//! caught even where no unit test covers the case.
+//!
+//! Variants known to disagree are listed in [`KNOWN_DIVERGENT`]; they still
+//! run and are reported, but do not fail the test.  A variant that uses up
+//! its [`BUDGET`] on a part is skipped for the rest of that part's inputs.
+//!
+//! This target has no test harness (see `Cargo.toml`); `main` runs each day
+//! and exits non-zero if any disagreed.
+
+use rust_advent::Outcome;
+use rust_advent::generators::{self, Lcg};
+use rust_advent::solution::Context;
+use std::fmt::{Debug, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+/// Includes each binary as a module.  Their tests are compiled out here,
+/// leaving their imports unused.
+macro_rules! variants {
+    ($($name:ident = $path:literal;)*) => {
+        $(
+            #[allow(dead_code, unused_imports)]
+            #[path = $path]
+            mod $name;
+        )*
+    };
+}
+
+variants! {
+    antigravity_day01 = "../src/bin/antigravity_day01.rs";
+    claude_day01 = "../src/bin/claude_day01.rs";
+    cursor_day01 = "../src/bin/cursor_day01.rs";
+    gemini_cli_day01 = "../src/bin/gemini_cli_day01.rs";
+    antigravity_day02 = "../src/bin/antigravity_day02.rs";
+    claude_day02 = "../src/bin/claude_day02.rs";
+    cursor_day02 = "../src/bin/cursor_day02.rs";
+    gemini_cli_day02 = "../src/bin/gemini_cli_day02.rs";
+    antigravity_day03 = "../src/bin/antigravity_day03.rs";
+    claude_day03 = "../src/bin/claude_day03.rs";
+    cursor_day03 = "../src/bin/cursor_day03.rs";
+    gemini_cli_day03 = "../src/bin/gemini_cli_day03.rs";
+    antigravity_day04 = "../src/bin/antigravity_day04.rs";
+    claude_day04 = "../src/bin/claude_day04.rs";
+    cursor_day04 = "../src/bin/cursor_day04.rs";
+    gemini_cli_day04 = "../src/bin/gemini_cli_day04.rs";
+    antigravity_day05 = "../src/bin/antigravity_day05.rs";
+    claude_day05 = "../src/bin/claude_day05.rs";
+    cursor_day05 = "../src/bin/cursor_day05.rs";
+    gemini_cli_day05 = "../src/bin/gemini_cli_day05.rs";
+    gemini_cli_3_day05 = "../src/bin/gemini_cli_3_day05.rs";
+    claude_day06 = "../src/bin/claude_day06.rs";
+    cursor_day06 = "../src/bin/cursor_day06.rs";
+    gemini_cli_day06 = "../src/bin/gemini_cli_day06.rs";
+    antigravity_day07 = "../src/bin/antigravity_day07.rs";
+    claude_day07 = "../src/bin/claude_day07.rs";
+    cursor_day07 = "../src/bin/cursor_day07.rs";
+    gemini_cli_day07 = "../src/bin/gemini_cli_day07.rs";
+    claude_day08 = "../src/bin/claude_day08.rs";
+    codex_day08 = "../src/bin/codex_day08.rs";
+    gemini_cli_day08 = "../src/bin/gemini_cli_day08.rs";
+    claude_day09 = "../src/bin/claude_day09.rs";
+    codex_day09 = "../src/bin/codex_day09.rs";
+    gemini_cli_day09 = "../src/bin/gemini_cli_day09.rs";
+    claude_day10 = "../src/bin/claude_day10.rs";
+    codex_day10 = "../src/bin/codex_day10.rs";
+    gemini_cli_day10 = "../src/bin/gemini_cli_day10.rs";
+    claude_day11 = "../src/bin/claude_day11.rs";
+    codex_day11 = "../src/bin/codex_day11.rs";
+    gemini_cli_day11 = "../src/bin/gemini_cli_day11.rs";
+    claude_day12 = "../src/bin/claude_day12.rs";
+    codex_day12 = "../src/bin/codex_day12.rs";
+    gemini_cli_day12 = "../src/bin/gemini_cli_day12.rs";
+}
+
+/// Inputs generated for each day.
+const CASES: usize = 25;
+
+/// Time a variant may spend on one part before its remaining inputs are
+/// skipped, so exhaustive searches cannot stall the run.
+const BUDGET: Duration = Duration::from_secs(2);
+
+/// `(day, part, variant, reason)` for variants known to give different
+/// answers.
+const KNOWN_DIVERGENT: &[(&str, u8, &str, &str)] = &[];
+
+/// An answer, or why there is none.
+type Answer = Result<i128, String>;
+
+trait IntoAnswer {
+    fn into_answer(self) -> Answer;
+}
+
+macro_rules! impl_into_answer {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAnswer for $t {
+                fn into_answer(self) -> Answer {
+                    i128::try_from(self).map_err(|err| err.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_answer!(i32, i64, u32, u64, u128, usize);
+
+impl<T: IntoAnswer> IntoAnswer for Option<T> {
+    fn into_answer(self) -> Answer {
+        self.map_or_else(|| Err("no answer".to_string()), T::into_answer)
+    }
+}
+
+impl<T: IntoAnswer, E: Display> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Answer {
+        self.map_err(|err| err.to_string())?.into_answer()
+    }
+}
+
+impl IntoAnswer for Outcome {
+    fn into_answer(self) -> Answer {
+        self.answer().ok_or_else(|| self.to_string())?.into_answer()
+    }
+}
+
+/// Checks every variant of a day, returning their disagreements.
+type Day = fn() -> Vec<String>;
+
+/// One variant's solver for a part.
+type Solver<'a, I> = (&'static str, &'a dyn Fn(&I) -> Answer);
+
+/// Runs every variant on every input, returning a description of each input
+/// where a variant not in [`KNOWN_DIVERGENT`] disagrees with the first.
+fn check<I: Debug>(day: &str, part: u8, inputs: &[I], variants: &[Solver<I>]) -> Vec<String> {
+    let known = |name: &str| {
+        KNOWN_DIVERGENT
+            .iter()
+            .find(|&&(d, p, v, _)| d == day && p == part && v == name)
+            .map(|&(_, _, _, reason)| reason)
+    };
+    let mut spent = vec![Duration::ZERO; variants.len()];
+    let mut failures = Vec::new();
+    let mut known_failures = vec![0; variants.len()];
+
+    for input in inputs {
+        let mut answers: Vec<(usize, Answer)> = Vec::new();
+        for (idx, &(_, solve)) in variants.iter().enumerate() {
+            if spent[idx] > BUDGET {
+                continue;
+            }
+            let start = Instant::now();
+            let answer = panic::catch_unwind(AssertUnwindSafe(|| solve(input)))
+                .unwrap_or_else(|_| Err("panicked".to_string()));
+            spent[idx] += start.elapsed();
+            answers.push((idx, answer));
+        }
+
+        let Some((reference, expected)) = answers.first() else {
+            continue;
+        };
+        for (idx, answer) in &answers[1..] {
+            // Variants word their errors differently, so any error counts as
+            // the same lack of an answer.
+            if answer.as_ref().ok() == expected.as_ref().ok() {
+                continue;
+            }
+            let name = variants[*idx].0;
+            if known(name).is_some() {
+                known_failures[*idx] += 1;
+                continue;
+            }
+            failures.push(format!(
+                "day {} part {}: {} gave {:?} but {} gave {:?} for\n{:?}",
+                day, part, variants[*reference].0, expected, name, answer, input
+            ));
+        }
+    }
+
+    for (idx, &(name, _)) in variants.iter().enumerate() {
+        if spent[idx] > BUDGET {
+            eprintln!(
+                "day {} part {}: {} skipped after using its {:?} budget",
+                day, part, name, BUDGET
+            );
+        }
+        if let Some(reason) = known(name) {
+            eprintln!(
+                "day {} part {}: {} disagreed on {} of {} inputs (known: {})",
+                day,
+                part,
+                name,
+                known_failures[idx],
+                inputs.len(),
+                reason
+            );
+        }
+    }
+    failures
+}
+
+/// `CASES` inputs drawn from `generate`, seeded per day.
+fn generate<I>(day: u64, mut generate: impl FnMut(&mut Lcg) -> I) -> Vec<I> {
+    let mut rng = Lcg::new(day);
+    (0..CASES).map(|_| generate(&mut rng)).collect()
+}
+
+fn day01() -> Vec<String> {
+    let inputs = generate(1, |rng| {
+        let count = 1 + rng.below(40) as usize;
+        generators::random_rotations(rng, count)
+    });
+    let mut failures = check(
+        "01",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day01::part1(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day01::part1(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day01::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day01::part1(i).into_answer()),
+        ],
+    );
+    failures.extend(check(
+        "01",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day01::part2(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day01::part2(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day01::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day01::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day02() -> Vec<String> {
+    let inputs = generate(2, |rng| {
+        let count = 1 + rng.below(10) as usize;
+        generators::random_id_ranges(rng, count)
+    });
+    let mut failures = check(
+        "02",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &String| claude_day02::part1(i).into_answer()),
+            ("antigravity", &|i| {
+                antigravity_day02::part1(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day02::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day02::part1(i).into_answer()),
+        ],
+    );
+    failures.extend(check(
+        "02",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &String| claude_day02::part2(i).into_answer()),
+            ("antigravity", &|i| {
+                antigravity_day02::part2(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day02::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day02::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day03() -> Vec<String> {
+    let inputs = generate(3, |rng| {
+        let rows = 1 + rng.below(10) as usize;
+        let width = 12 + rng.below(30) as usize;
+        generators::random_digit_grid(rng, rows, width)
+    });
+    let mut failures = check(
+        "03",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<Vec<u8>>| {
+                claude_day03::part1(i).into_answer()
+            }),
+            ("claude parallel", &|i| {
+                claude_day03::part1_parallel(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day03::part1(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day03::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day03::part1(i).into_answer()),
+        ],
+    );
+    failures.extend(check(
+        "03",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<Vec<u8>>| {
+                claude_day03::part2(i).into_answer()
+            }),
+            ("claude parallel", &|i| {
+                claude_day03::part2_parallel(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day03::part2(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day03::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day03::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day04() -> Vec<String> {
+    let inputs = generate(4, |rng| {
+        let rows = 1 + rng.below(20) as usize;
+        let width = 1 + rng.below(20) as usize;
+        let percent = 30 + rng.below(50);
+        generators::random_roll_grid(rng, rows, width, percent)
+    });
+    let mut failures = check(
+        "04",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day04::part1(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day04::part1(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day04::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day04::part1(i).into_answer()),
+        ],
+    );
+    failures.extend(check(
+        "04",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day04::part2(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day04::part2(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day04::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day04::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day05() -> Vec<String> {
+    let inputs = generate(5, |rng| {
+        let ranges = 1 + rng.below(20) as usize;
+        let values = 1 + rng.below(20) as usize;
+        generators::random_range_data(rng, ranges, values)
+    });
+    let mut failures = check(
+        "05",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &rust_advent::RangeData| {
+                claude_day05::part1(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day05::part1(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day05::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day05::part1(i).into_answer()),
+            ("gemini_cli_3", &|i| {
+                gemini_cli_3_day05::part1(i).into_answer()
+            }),
+        ],
+    );
+    failures.extend(check(
+        "05",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &rust_advent::RangeData| {
+                claude_day05::part2(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day05::part2(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day05::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day05::part2(i).into_answer()),
+            ("gemini_cli_3", &|i| {
+                gemini_cli_3_day05::part2(i).into_answer()
+            }),
+        ],
+    ));
+    failures
+}
+
+fn day06() -> Vec<String> {
+    let inputs = generate(6, |rng| {
+        let problems = 1 + rng.below(8) as usize;
+        let rows = 3 + rng.below(2) as usize;
+        generators::random_worksheet(rng, problems, rows)
+    });
+    let mut failures = check(
+        "06",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day06::part1(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day06::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day06::part1(i).into_answer()),
+        ],
+    );
+    failures.extend(check(
+        "06",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day06::part2(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day06::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day06::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day07() -> Vec<String> {
+    let inputs = generate(7, |rng| {
+        let width = 3 + rng.below(40) as usize;
+        // A lone `S` row has no manifold below it; some variants count that
+        // as no timelines, others as one.
+        let rows = 2 + rng.below(30) as usize;
+        let spacing = 2 + rng.below(8);
+        generators::random_splitter_grid(rng, width, rows, spacing)
+    });
+    let mut failures = check(
+        "07",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day07::part1(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day07::part1(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day07::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day07::part1(i).into_answer()),
+        ],
+    );
+    failures.extend(check(
+        "07",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<String>| {
+                claude_day07::part2::<u64>(i).into_answer()
+            }),
+            ("antigravity", &|i| {
+                antigravity_day07::part2(i).into_answer()
+            }),
+            ("cursor", &|i| cursor_day07::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day07::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day08() -> Vec<String> {
+    let inputs = generate(8, |rng| {
+        let count = 2 + rng.below(60) as usize;
+        generators::random_points(rng, count, 100_000)
+    });
+    let mut failures = check(
+        "08",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<rust_advent::Point>| {
+                claude_day08::part1(10, 3, i).into_answer()
+            }),
+            ("codex", &|i| codex_day08::part1(10, 3, i).into_answer()),
+            ("gemini_cli", &|i| {
+                gemini_cli_day08::part1(10, 3, i).into_answer()
+            }),
+        ],
+    );
+    failures.extend(check(
+        "08",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<rust_advent::Point>| {
+                claude_day08::part2(i).into_answer()
+            }),
+            ("claude kruskal", &|i| {
+                claude_day08::part2_kruskal(i).into_answer()
+            }),
+            ("codex", &|i| codex_day08::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day08::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day09() -> Vec<String> {
+    let inputs = generate(9, |rng| {
+        let columns = 1 + rng.below(8) as usize;
+        generators::random_histogram_polygon(rng, columns)
+    });
+    let mut failures = check(
+        "09",
+        1,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<rust_advent::Point2d>| {
+                claude_day09::part1(i).into_answer()
+            }),
+            ("codex", &|i| codex_day09::part1(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day09::part1(i).into_answer()),
+        ],
+    );
+    failures.extend(check(
+        "09",
+        2,
+        &inputs,
+        &[
+            ("claude", &|i: &Vec<rust_advent::Point2d>| {
+                claude_day09::part2(i).into_answer()
+            }),
+            ("codex", &|i| codex_day09::part2(i).into_answer()),
+            ("gemini_cli", &|i| gemini_cli_day09::part2(i).into_answer()),
+        ],
+    ));
+    failures
+}
+
+fn day10() -> Vec<String> {
+    // Each input is the machine lines and the fewest presses over all of them.
+    let inputs = generate(10, |rng| {
+        let machines: Vec<_> = (0..1 + rng.below(4))
+            .map(|_| {
+                let counters = 1 + rng.below(8) as usize;
+                let buttons = 1 + rng.below(counters as u64) as usize;
+                generators::known_machine(rng, counters, buttons, 6)
+            })
+            .collect();
+        (
+            machines.iter().map(|m| m.line.clone()).collect::<Vec<_>>(),
+            machines.iter().map(|m| m.light_presses).sum::<u64>(),
+            machines.iter().map(|m| m.joltage_presses).sum::<u64>(),
+        )
+    });
+    let ctx = Context::new();
+    let mut failures = check(
+        "10",
+        1,
+        &inputs,
+        &[
+            ("expected", &|(_, lights, _): &(Vec<String>, u64, u64)| {
+                lights.into_answer()
+            }),
+            ("claude", &|(i, _, _)| {
+                claude_day10::part1(i, &ctx).into_answer()
+            }),
+            ("claude_sat", &|(i, _, _)| {
+                claude_day10::part1_sat(i, &ctx).into_answer()
+            }),
+            ("codex", &|(i, _, _)| codex_day10::part1(i).into_answer()),
+            ("gemini_cli", &|(i, _, _)| {
+                gemini_cli_day10::part1(i, &ctx).into_answer()
+            }),
+        ],
+    );
+    failures.extend(check(
+        "10",
+        2,
+        &inputs,
+        &[
+            ("expected", &|(_, _, joltages): &(Vec<String>, u64, u64)| {
+                joltages.into_answer()
+            }),
+            ("claude", &|(i, _, _)| {
+                claude_day10::part2(i, &ctx).into_answer()
+            }),
+            ("codex", &|(i, _, _)| {
+                codex_day10::part2(i, &ctx).into_answer()
+            }),
+            ("gemini_cli", &|(i, _, _)| {
+                gemini_cli_day10::part2(i, &ctx).into_answer()
+            }),
+        ],
+    ));
+    failures
+}
+
+fn day11() -> Vec<String> {
+    let inputs = generate(11, |rng| {
+        let devices = rng.below(15) as usize;
+        generators::random_device_graph(rng, devices)
+    });
+    let mut failures = check(
+        "11",
+        1,
+        &inputs,
+        &[
+            ("expected", &|i: &generators::DeviceGraph| {
+                i.paths_from_you.into_answer()
+            }),
+            ("claude", &|i| {
+                claude_day11::part1::<u64, _>("you", "out", &i.lines).into_answer()
+            }),
+            ("codex", &|i| {
+                codex_day11::part1("you", "out", &i.lines).into_answer()
+            }),
+            ("gemini_cli", &|i| {
+                gemini_cli_day11::part1("you", "out", &i.lines).into_answer()
+            }),
+        ],
+    );
+    let required = ["dac", "fft"];
+    failures.extend(check(
+        "11",
+        2,
+        &inputs,
+        &[
+            ("expected", &|i: &generators::DeviceGraph| {
+                i.paths_via_dac_fft.into_answer()
+            }),
+            ("claude", &|i| {
+                claude_day11::part2::<u64, _, _>("svr", "out", &required, &i.lines).into_answer()
+            }),
+            ("codex", &|i| {
+                codex_day11::part2("svr", "out", &required, &i.lines).into_answer()
+            }),
+            ("gemini_cli", &|i| {
+                gemini_cli_day11::part2("svr", "out", &required, &i.lines).into_answer()
+            }),
+        ],
+    ));
+    failures
+}
+
+fn day12() -> Vec<String> {
+    const SHAPES: &str = "0:\n###\n##.\n##.\n\n1:\n###\n##.\n.##\n\n2:\n.##\n###\n##.\n\n\
+                          3:\n##.\n###\n##.\n\n4:\n###\n#..\n###\n\n5:\n###\n.#.\n###\n";
+    // Each input is the lines and, where known, how many regions fit.
+    let with_shapes = |regions: Vec<String>, fitting: Option<u64>| {
+        let mut lines: Vec<String> = SHAPES.lines().map(str::to_string).collect();
+        lines.push(String::new());
+        lines.extend(regions);
+        (lines, fitting)
+    };
+    let variants: [Solver<(Vec<String>, Option<u64>)>; 3] = [
+        ("claude", &|(i, _)| claude_day12::part1(i).into_answer()),
+        ("codex", &|(i, _)| codex_day12::part1(i).into_answer()),
+        ("gemini_cli", &|(i, _)| {
+            gemini_cli_day12::part1(i).into_answer()
+        }),
+    ];
+
+    // Small regions with few presents, where whether they fit takes a search
+    let searched = generate(12, |rng| {
+        let regions = (0..1 + rng.below(4))
+            .map(|_| {
+                let width = 3 + rng.below(5) as usize;
+                let height = 3 + rng.below(5) as usize;
+                let presents = rng.below(4) as usize;
+                generators::random_region(rng, width, height, 6, presents)
+            })
+            .collect();
+        with_shapes(regions, None)
+    });
+    let mut failures = check("12", 1, &searched, &variants);
+
+    // Larger regions like the puzzle's, whose answer is known
+    let known = generate(12, |rng| {
+        let regions: Vec<_> = (0..1 + rng.below(6))
+            .map(|_| generators::known_region(rng, &[7; 6], 3))
+            .collect();
+        let fitting = regions.iter().filter(|region| region.fits).count() as u64;
+        with_shapes(regions.into_iter().map(|r| r.line).collect(), Some(fitting))
+    });
+    let [claude, codex, gemini_cli] = variants;
+    failures.extend(check(
+        "12",
+        1,
+        &known,
+        &[
+            ("expected", &|(_, fitting)| fitting.into_answer()),
+            claude,
+            codex,
+            gemini_cli,
+        ],
+    ));
+    failures
+}
+
+fn main() {
+    // Panicking variants are reported as answers; keep their messages quiet.
+    panic::set_hook(Box::new(|_| {}));
+    let days: [(&str, Day); 12] = [
+        ("01", day01),
+        ("02", day02),
+        ("03", day03),
+        ("04", day04),
+        ("05", day05),
+        ("06", day06),
+        ("07", day07),
+        ("08", day08),
+        ("09", day09),
+        ("10", day10),
+        ("11", day11),
+        ("12", day12),
+    ];
+    let mut failed = 0;
+    for (day, run) in days {
+        let failures = run();
+        if failures.is_empty() {
+            println!("day {} variants agree", day);
+        } else {
+            println!(
+                "day {} variants disagree:\n\n{}\n",
+                day,
+                failures.join("\n\n")
+            );
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        println!("{} of {} days disagreed", failed, days.len());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Heavy checks: every variant on the inputs its own unit tests once had to
+//! `#[ignore]` because some variants take far too long on them.  A variant
+//! that gives up or runs out of time is a capability gap; gaps listed in
+//! [`KNOWN_GAPS`] are reported but do not fail the run, so the list records
+//! what each variant still cannot solve.
+//!
+//! The checks only build with the `heavy-tests` feature and are meant for
+//! optimised builds:
+//!
+//! ```text
+//! cargo run --bin advent -- test --heavy
+//! cargo test --release --features heavy-tests --test heavy
+//! ```
+//!
+//! Each variant gets [`DEFAULT_TIMEOUT`] per input, or the duration in
+//! `ADVENT_HEAVY_TIMEOUT` (such as `30s`).  Solvers are asked to stop through
+//! their [`Context`]; one that ignores it is abandoned and left to finish in
+//! the background.  This target has no test harness (see `Cargo.toml`).
+
+use rust_advent::Outcome;
+use rust_advent::day10::{self, Machine};
+use rust_advent::solution::{CancellationToken, Context, parse_duration};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+#[path = "../benches/support/mod.rs"]
+mod support;
+
+// Their tests are compiled out here, leaving their imports unused
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/claude_day10.rs"]
+mod claude_day10;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/codex_day10.rs"]
+mod codex_day10;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/gemini_cli_day10.rs"]
+mod gemini_cli_day10;
+
+/// Overrides [`DEFAULT_TIMEOUT`]; `advent test --heavy --timeout` sets it.
+const TIMEOUT_VAR: &str = "ADVENT_HEAVY_TIMEOUT";
+
+/// How long a variant may spend on one input.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Extra time a solver gets to notice its deadline before it is abandoned.
+const GRACE: Duration = Duration::from_secs(1);
+
+/// `(case, variant, reason)` for variants known not to solve a case.
+const KNOWN_GAPS: &[(&str, &str, &str)] = &[
+    (
+        "day 10 part 2, hard case",
+        "claude",
+        "enumerates press counts by their total; far too many for targets in the hundreds",
+    ),
+    (
+        "day 10 part 2, hard case",
+        "codex",
+        "A* over counter values; far too many states for targets in the hundreds",
+    ),
+    (
+        "day 10 part 2, worst case",
+        "claude",
+        "enumerates press counts by their total; thirty buttons give too many to try",
+    ),
+    (
+        "day 10 part 2, worst case",
+        "gemini_cli",
+        "tries all 2^20 free parity choices at every level of the decomposition",
+    ),
+];
+
+/// A day 10 machine that some variants cannot solve in time.
+struct Case {
+    name: &'static str,
+    line: &'static str,
+}
+
+const CASES: &[Case] = &[
+    Case {
+        name: "day 10 part 2, hard case",
+        line: support::HARD_CASE,
+    },
+    Case {
+        name: "day 10 part 2, worst case",
+        line: support::WORST_CASE,
+    },
+];
+
+type Solver = fn(&[String], &Context) -> Result<Outcome, String>;
+
+const VARIANTS: &[(&str, Solver)] = &[
+    ("claude", |input, ctx| {
+        claude_day10::part2(input, ctx).map_err(|err| err.to_string())
+    }),
+    ("codex", codex_day10::part2),
+    ("gemini_cli", |input, ctx| {
+        gemini_cli_day10::part2(input, ctx).map(Outcome::Answer)
+    }),
+];
+
+/// What one variant made of one case.
+enum Attempt {
+    Solved(Outcome),
+    Failed(String),
+    TimedOut,
+}
+
+/// Runs `solver` on `line` on its own thread, giving up after `timeout`.
+fn attempt(solver: Solver, line: &str, timeout: Duration) -> (Attempt, Duration) {
+    let (sender, receiver) = mpsc::channel();
+    let input = vec![line.to_string()];
+    let start = Instant::now();
+    std::thread::spawn(move || {
+        let ctx = Context::new().with_cancellation(CancellationToken::with_timeout(timeout));
+        let _ = sender.send(solver(&input, &ctx));
+    });
+    let attempt = match receiver.recv_timeout(timeout + GRACE) {
+        Ok(Ok(outcome)) => Attempt::Solved(outcome),
+        // A solver that honors its deadline stops with an error
+        Ok(Err(_)) if start.elapsed() >= timeout => Attempt::TimedOut,
+        Ok(Err(err)) => Attempt::Failed(err),
+        Err(RecvTimeoutError::Timeout) => Attempt::TimedOut,
+        Err(RecvTimeoutError::Disconnected) => Attempt::Failed("panicked".to_string()),
+    };
+    (attempt, start.elapsed())
+}
+
+fn main() {
+    let timeout = match std::env::var(TIMEOUT_VAR) {
+        Ok(value) => parse_duration(&value).unwrap_or_else(|err| {
+            eprintln!("{}: {}", TIMEOUT_VAR, err);
+            std::process::exit(2)
+        }),
+        Err(_) => DEFAULT_TIMEOUT,
+    };
+
+    let mut failures = Vec::new();
+    for case in CASES {
+        let machine = Machine::parse(case.line).unwrap();
+        let expected = day10::min_presses(&machine, &Context::new()).unwrap();
+        println!("{} (expected {})", case.name, expected);
+        for &(variant, solver) in VARIANTS {
+            let gap = KNOWN_GAPS
+                .iter()
+                .find(|&&(c, v, _)| c == case.name && v == variant)
+                .map(|&(_, _, reason)| reason);
+            let (attempt, elapsed) = attempt(solver, case.line, timeout);
+            let verdict = match (attempt, gap) {
+                (Attempt::Solved(outcome), _) if outcome != expected => {
+                    failures.push(format!("{}: {} answered {}", case.name, variant, outcome));
+                    format!("WRONG ANSWER {}", outcome)
+                }
+                (Attempt::Solved(_), None) => "ok".to_string(),
+                (Attempt::Solved(_), Some(_)) => {
+                    failures.push(format!(
+                        "{}: {} now solves it; remove it from KNOWN_GAPS",
+                        case.name, variant
+                    ));
+                    "ok, but listed as a known gap".to_string()
+                }
+                (Attempt::Failed(err), Some(reason)) => {
+                    format!("failed: {} (known gap: {})", err, reason)
+                }
+                (Attempt::TimedOut, Some(reason)) => {
+                    format!("timed out (known gap: {})", reason)
+                }
+                (Attempt::Failed(err), None) => {
+                    failures.push(format!("{}: {} failed: {}", case.name, variant, err));
+                    format!("FAILED: {}", err)
+                }
+                (Attempt::TimedOut, None) => {
+                    failures.push(format!("{}: {} timed out", case.name, variant));
+                    "TIMED OUT".to_string()
+                }
+            };
+            println!("  {:<12} {:>10.2?}  {}", variant, elapsed, verdict);
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("{} heavy checks failed:", failures.len());
+        for failure in &failures {
+            println!("  {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
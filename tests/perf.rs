@@ -0,0 +1,149 @@
+//! Opt-in timing checks: selected solvers must finish the benchmark inputs
+//! within a budget, so a change to a shared module that slows them down
+//! fails here rather than going unnoticed until the next benchmark run.
+//!
+//! The checks only run with `ADVENT_PERF_TESTS=1`, and the budgets are for
+//! optimised builds:
+//!
+//! ```text
+//! ADVENT_PERF_TESTS=1 cargo test --release --test perf
+//! ```
+//!
+//! Unoptimised builds get [`DEBUG_SLOWDOWN`] times the budget.  This target
+//! has no test harness (see `Cargo.toml`), so the binaries it includes leave
+//! their unit tests out and each check runs alone rather than beside other
+//! tests.
+
+use rust_advent::day10::{self, Machine};
+use rust_advent::solution::Context;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[path = "../benches/support/mod.rs"]
+mod support;
+
+// Their tests are compiled out here, leaving their imports unused
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/claude_day08.rs"]
+mod claude_day08;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/claude_day09.rs"]
+mod claude_day09;
+#[allow(dead_code, unused_imports)]
+#[path = "../src/bin/claude_day12.rs"]
+mod claude_day12;
+
+/// Set to `1` to run the checks.
+const ENABLE_VAR: &str = "ADVENT_PERF_TESTS";
+
+/// How much longer a check may take without optimisations.
+const DEBUG_SLOWDOWN: u32 = 20;
+
+/// Runs per check; the fastest counts, so one slow run on a busy machine
+/// does not fail the check.
+const ATTEMPTS: usize = 3;
+
+/// A solver run on a benchmark input, and the time it must finish within.
+/// Budgets leave twenty to forty times the time an optimised build takes on
+/// a current laptop, enough for slower machines but not for a regression
+/// that changes the complexity.
+struct Check {
+    name: &'static str,
+    budget: Duration,
+    run: fn(),
+}
+
+const CHECKS: &[Check] = &[
+    Check {
+        name: "day 10 part 2, hard case",
+        budget: Duration::from_millis(20),
+        run: || {
+            let machine = Machine::parse(support::HARD_CASE).unwrap();
+            black_box(day10::min_presses(&machine, &Context::new()).unwrap());
+        },
+    },
+    Check {
+        name: "day 10 part 2, worst case",
+        budget: Duration::from_secs(1),
+        run: || {
+            let machine = Machine::parse(support::WORST_CASE).unwrap();
+            black_box(day10::min_presses(&machine, &Context::new()).unwrap());
+        },
+    },
+    Check {
+        name: "day 12 part 1, example",
+        budget: Duration::from_millis(200),
+        run: || {
+            for region in ["4x4: 0 0 0 0 2 0", "12x5: 1 0 1 0 2 2", "12x5: 1 0 1 0 3 2"] {
+                let input = support::example_with_region(region);
+                black_box(claude_day12::part1(&input).unwrap());
+            }
+        },
+    },
+    Check {
+        name: "day 8 part 1, 1000 points",
+        budget: Duration::from_millis(200),
+        run: || {
+            let points = support::scattered_points(1000);
+            black_box(claude_day08::part1(1000, 3, &points));
+        },
+    },
+    Check {
+        name: "day 9 convex hull, 50000 points",
+        budget: Duration::from_millis(100),
+        run: || {
+            let points = support::scattered_points2d(50_000);
+            black_box(rust_advent::convex_hull(&points));
+        },
+    },
+];
+
+/// The fastest of up to [`ATTEMPTS`] runs, stopping at the first within
+/// `budget`.
+fn fastest(run: fn(), budget: Duration) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..ATTEMPTS {
+        let start = Instant::now();
+        run();
+        best = best.min(start.elapsed());
+        if best <= budget {
+            break;
+        }
+    }
+    best
+}
+
+fn main() {
+    if std::env::var(ENABLE_VAR).is_ok_and(|value| value == "1") {
+        run_checks();
+    } else {
+        println!("skipping timing checks; set {}=1 to run them", ENABLE_VAR);
+    }
+}
+
+fn run_checks() {
+    let slowdown = if cfg!(debug_assertions) {
+        DEBUG_SLOWDOWN
+    } else {
+        1
+    };
+    let mut failed = 0;
+    for check in CHECKS {
+        let budget = check.budget * slowdown;
+        let elapsed = fastest(check.run, budget);
+        let verdict = if elapsed <= budget {
+            "ok"
+        } else {
+            failed += 1;
+            "TOO SLOW"
+        };
+        println!(
+            "{}: {:.1?} (budget {:?}) {}",
+            check.name, elapsed, budget, verdict
+        );
+    }
+    if failed > 0 {
+        println!("{} of {} checks over budget", failed, CHECKS.len());
+        std::process::exit(1);
+    }
+}